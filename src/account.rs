@@ -1,9 +1,19 @@
+use bc_components::ARID;
 use bc_envelope::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    Address, BlockHash, BlockHeight, Indexed, NoQuotesDebugOption, TxId,
-    envelope_indexed_objects_for_predicate, orchard::OrchardSentOutput, sapling::SaplingSentOutput,
+    AccountDerivation, Address, Amount, Balance, BlockHash, BlockHeight, Error, Indexed, KeyEntry,
+    KeyMaterial, KeyOrigin, KeyType, NoQuotesDebugOption, ProtocolAddress, Result, SeedFingerprint,
+    SetIndexes, TxId, UnifiedAccountMetadata,
+    envelope_decoder::{EnvelopeDecoder, segment},
+    orchard::{
+        OrchardAnchorRegistry, OrchardDerivation, OrchardNullifier, OrchardReceivedNote,
+        OrchardSentOutput, OrchardSpendingKey,
+    },
+    sapling::{SaplingAnchorRegistry, SaplingNullifier, SaplingReceivedNote, SaplingSentOutput},
+    sprout::{SproutMigrationStatus, SproutNullifier, SproutReceivedNote},
+    transparent::Utxo,
 };
 
 /// A logical grouping of addresses and transaction history within a wallet.
@@ -56,9 +66,16 @@ use crate::{
 /// account.add_relevant_transaction(txid);
 /// ```
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Account {
+    // The display position of the account within its wallet.
     index: usize,
 
+    // The account's permanent identifier, which survives renaming and
+    // reordering.
+    #[cfg_attr(feature = "json", serde(with = "crate::json::arid"))]
+    id: ARID,
+
     // User-defined, may not be unique.
     name: String,
 
@@ -80,16 +97,62 @@ pub struct Account {
     // The ZIP 32 account ID used in derivation from an HD seed.
     zip32_account_id: Option<u32>,
 
+    // The coin type used in derivation from an HD seed, if known.
+    coin_type: Option<u32>,
+
+    // Whether this is the legacy default account of a zcashd wallet, whose
+    // keys were generated before the wallet held a mnemonic seed.
+    is_legacy_default: bool,
+
+    // The ZIP 32 fingerprint of the seed from which the account is derived, if
+    // known. This identifies the seed unambiguously when a wallet holds more
+    // than one.
+    seed_fingerprint: Option<SeedFingerprint>,
+
+    // What the account's keys are derived from.
+    derivation: AccountDerivation,
+
     // The set of addresses that are associated with this account.
     addresses: Vec<Address>,
 
     // Subset of the global transaction history that involves this account.
+    #[cfg_attr(feature = "json", serde(with = "crate::json::txid_set"))]
     relevant_transactions: HashSet<TxId>,
 
+    // The account's Orchard spending key, and how it was derived from the
+    // wallet's seed, if known.
+    orchard_spending_key: Option<OrchardSpendingKey>,
+    orchard_derivation: Option<OrchardDerivation>,
+
+    // Whether the Orchard spending key was derived from a seed or imported,
+    // if known.
+    orchard_key_origin: Option<KeyOrigin>,
+
+    // The state of zcashd's Sprout-to-Sapling migration, if it was used.
+    sprout_migration_status: Option<SproutMigrationStatus>,
+
+    // The account's unified full viewing key and generated unified
+    // addresses, if it is a ZIP 316 unified account.
+    unified_metadata: Option<UnifiedAccountMetadata>,
+
+    // Keys held by the account that have no field of their own, including
+    // keys of types unknown to this version of the format.
+    key_material: KeyMaterial,
+
     // The following are intended for storage of information that may not be
     // recoverable from the chain.
     sapling_sent_outputs: Vec<SaplingSentOutput>,
     orchard_sent_outputs: Vec<OrchardSentOutput>,
+
+    // Notes received by this account, along with the witnesses needed to
+    // spend them.
+    sprout_received_notes: Vec<SproutReceivedNote>,
+    sapling_received_notes: Vec<SaplingReceivedNote>,
+    orchard_received_notes: Vec<OrchardReceivedNote>,
+
+    // The account's unspent transparent outputs, as of the export.
+    utxos: Vec<Utxo>,
+    #[cfg_attr(feature = "json", serde(with = "crate::json::attachments"))]
     attachments: Attachments,
 }
 
@@ -98,14 +161,29 @@ impl std::fmt::Debug for Account {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Account")
             .field("index", &self.index)
+            .field("id", &self.id)
             .field("name", &self.name)
             .field("birthday_height", &self.birthday_height)
             .field("birthday_block", &self.birthday_block)
             .field("zip32_account_id", &NoQuotesDebugOption(&self.zip32_account_id))
+            .field("coin_type", &NoQuotesDebugOption(&self.coin_type))
+            .field("is_legacy_default", &self.is_legacy_default)
+            .field("seed_fingerprint", &self.seed_fingerprint)
+            .field("derivation", &self.derivation)
             .field("addresses", &self.addresses)
             .field("relevant_transactions", &self.relevant_transactions)
+            .field("orchard_spending_key", &self.orchard_spending_key)
+            .field("orchard_derivation", &self.orchard_derivation)
+            .field("orchard_key_origin", &self.orchard_key_origin)
+            .field("sprout_migration_status", &self.sprout_migration_status)
+            .field("unified_metadata", &self.unified_metadata)
+            .field("key_material", &self.key_material)
             .field("sapling_sent_outputs", &self.sapling_sent_outputs)
             .field("orchard_sent_outputs", &self.orchard_sent_outputs)
+            .field("sprout_received_notes", &self.sprout_received_notes)
+            .field("sapling_received_notes", &self.sapling_received_notes)
+            .field("orchard_received_notes", &self.orchard_received_notes)
+            .field("utxos", &self.utxos)
             .field("attachments", &self.attachments)
             .finish()
     }
@@ -127,18 +205,40 @@ impl Account {
     pub fn new() -> Self {
         Self {
             index: 0,
+            id: ARID::new(),
             name: String::default(),
             birthday_height: None,
             birthday_block: None,
             zip32_account_id: None,
+            coin_type: None,
+            is_legacy_default: false,
+            seed_fingerprint: None,
+            derivation: AccountDerivation::Unknown,
             addresses: Vec::new(),
             relevant_transactions: HashSet::new(),
+            orchard_spending_key: None,
+            orchard_derivation: None,
+            orchard_key_origin: None,
+            sprout_migration_status: None,
+            unified_metadata: None,
+            key_material: KeyMaterial::new(),
             sapling_sent_outputs: Vec::new(),
             orchard_sent_outputs: Vec::new(),
+            sprout_received_notes: Vec::new(),
+            sapling_received_notes: Vec::new(),
+            orchard_received_notes: Vec::new(),
+            utxos: Vec::new(),
             attachments: Attachments::new(),
         }
     }
 
+    /// Returns the account's identifier, assigned when it was created. Unlike
+    /// its index, the identifier does not change when the accounts of its
+    /// wallet are reordered.
+    pub fn id(&self) -> ARID {
+        self.id
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -171,29 +271,162 @@ impl Account {
         self.zip32_account_id = Some(id);
     }
 
+    /// Returns the coin type used in derivation from an HD seed, if known.
+    pub fn coin_type(&self) -> Option<u32> {
+        self.coin_type
+    }
+
+    pub fn set_coin_type(&mut self, coin_type: Option<u32>) {
+        self.coin_type = coin_type;
+    }
+
+    /// Returns `true` if this is the legacy default account of a zcashd
+    /// wallet, which predates the wallet's mnemonic seed.
+    pub fn is_legacy_default(&self) -> bool {
+        self.is_legacy_default
+    }
+
+    pub fn set_legacy_default(&mut self, is_legacy_default: bool) {
+        self.is_legacy_default = is_legacy_default;
+    }
+
+    pub fn seed_fingerprint(&self) -> Option<&SeedFingerprint> {
+        self.seed_fingerprint.as_ref()
+    }
+
+    pub fn set_seed_fingerprint(&mut self, seed_fingerprint: Option<SeedFingerprint>) {
+        self.seed_fingerprint = seed_fingerprint;
+    }
+
+    /// Returns what the account's keys are derived from.
+    pub fn derivation(&self) -> &AccountDerivation {
+        &self.derivation
+    }
+
+    pub fn set_derivation(&mut self, derivation: AccountDerivation) {
+        self.derivation = derivation;
+    }
+
+    /// Returns the fingerprints of the seeds the account refers to: those of
+    /// its own seed fingerprint, its derivation, and its keys' origins.
+    pub fn referenced_seed_fingerprints(&self) -> impl Iterator<Item = &SeedFingerprint> {
+        self.seed_fingerprint
+            .iter()
+            .chain(self.derivation.seed_fingerprint())
+            .chain(
+                self.key_origins()
+                    .filter_map(|origin| origin.seed_fingerprint()),
+            )
+    }
+
     pub fn addresses(&self) -> &Vec<Address> {
         &self.addresses
     }
 
+    pub fn addresses_mut(&mut self) -> &mut Vec<Address> {
+        &mut self.addresses
+    }
+
     pub fn addresses_len(&self) -> usize {
         self.addresses.len()
     }
 
+    /// Returns the addresses in ascending order of their indexes, which is the
+    /// order in which they are decoded from an envelope.
+    pub fn addresses_ordered(&self) -> Vec<&Address> {
+        let mut addresses: Vec<_> = self.addresses.iter().collect();
+        addresses.sort_by_key(|address| address.index());
+        addresses
+    }
+
+    /// Adds an address, keeping its index unless another address of the
+    /// account already has it, in which case it is given the index after the
+    /// highest in use. Addresses added with the default index of 0 are thus
+    /// numbered in the order they are added.
     pub fn add_address(&mut self, mut address: Address) {
-        address.set_index(self.addresses.len());
+        if self
+            .addresses
+            .iter()
+            .any(|existing| existing.index() == address.index())
+        {
+            let next = self
+                .addresses
+                .iter()
+                .map(|existing| existing.index() + 1)
+                .max()
+                .unwrap_or(0);
+            address.set_index(next);
+        }
         self.addresses.push(address);
     }
 
-    pub fn relevant_transactions(&self) -> &HashSet<TxId> {
-        &self.relevant_transactions
+    /// Removes the address with the given string encoding, returning it if
+    /// the account held it. The remaining addresses are re-indexed as by
+    /// [`Account::reindex`].
+    pub fn remove_address(&mut self, address: &str) -> Option<Address> {
+        let position = self
+            .addresses
+            .iter()
+            .position(|a| a.as_string() == address)?;
+        let removed = self.addresses.remove(position);
+        self.reindex();
+        Some(removed)
+    }
+
+    /// Sorts the addresses by index and renumbers them from 0, closing any
+    /// gaps left by removals.
+    pub fn reindex(&mut self) {
+        self.addresses.sort_by_key(|address| address.index());
+        for (index, address) in self.addresses.iter_mut().enumerate() {
+            address.set_index(index);
+        }
+    }
+
+    /// Returns the IDs of the transactions relevant to the account, in
+    /// ascending order.
+    pub fn relevant_transactions(&self) -> impl Iterator<Item = &TxId> {
+        let mut txids: Vec<_> = self.relevant_transactions.iter().collect();
+        txids.sort();
+        txids.into_iter()
     }
 
     pub fn relevant_transactions_len(&self) -> usize {
         self.relevant_transactions.len()
     }
 
-    pub fn add_relevant_transaction(&mut self, txid: TxId) {
-        self.relevant_transactions.insert(txid);
+    /// Returns whether the transaction is recorded as relevant to the account.
+    pub fn contains_transaction(&self, txid: TxId) -> bool {
+        self.relevant_transactions.contains(&txid)
+    }
+
+    /// Records the transaction as relevant to the account, returning `false`
+    /// if it already was.
+    pub fn add_relevant_transaction(&mut self, txid: TxId) -> bool {
+        self.relevant_transactions.insert(txid)
+    }
+
+    /// Removes the transaction from those relevant to the account, returning
+    /// `false` if it was not among them.
+    pub fn remove_relevant_transaction(&mut self, txid: TxId) -> bool {
+        self.relevant_transactions.remove(&txid)
+    }
+
+    /// Returns the IDs of the transactions that created or spent the
+    /// account's received notes, as far as they are recorded.
+    pub fn note_transactions(&self) -> impl Iterator<Item = TxId> {
+        let sprout = self
+            .sprout_received_notes
+            .iter()
+            .flat_map(|note| [Some(note.txid()), note.spent_in()]);
+        let sapling = self
+            .sapling_received_notes
+            .iter()
+            .flat_map(|note| [Some(note.txid()), note.spent_in()]);
+        let orchard = self
+            .orchard_received_notes
+            .iter()
+            .flat_map(|note| [Some(note.txid()), note.spent_in()]);
+        sprout.chain(sapling).chain(orchard).flatten()
     }
 
     pub fn sapling_sent_outputs(&self) -> &Vec<SaplingSentOutput> {
@@ -221,6 +454,449 @@ impl Account {
         output.set_index(self.orchard_sent_outputs.len());
         self.orchard_sent_outputs.push(output);
     }
+
+    pub fn orchard_spending_key(&self) -> Option<&OrchardSpendingKey> {
+        self.orchard_spending_key.as_ref()
+    }
+
+    pub fn set_orchard_spending_key(&mut self, key: Option<OrchardSpendingKey>) {
+        self.orchard_spending_key = key;
+    }
+
+    pub fn orchard_derivation(&self) -> Option<&OrchardDerivation> {
+        self.orchard_derivation.as_ref()
+    }
+
+    pub fn set_orchard_derivation(&mut self, derivation: Option<OrchardDerivation>) {
+        self.orchard_derivation = derivation;
+    }
+
+    pub fn orchard_key_origin(&self) -> Option<&KeyOrigin> {
+        self.orchard_key_origin.as_ref()
+    }
+
+    pub fn set_orchard_key_origin(&mut self, key_origin: Option<KeyOrigin>) {
+        self.orchard_key_origin = key_origin;
+    }
+
+    /// Returns the recorded origins of the account's keys: those of its
+    /// transparent and Sapling addresses, and of its Orchard spending key.
+    pub fn key_origins(&self) -> impl Iterator<Item = &KeyOrigin> {
+        self.addresses
+            .iter()
+            .filter_map(|address| match address.address() {
+                ProtocolAddress::Transparent(address) => address.key_origin(),
+                ProtocolAddress::Sapling(address) => address.key_origin(),
+                _ => None,
+            })
+            .chain(self.orchard_key_origin.as_ref())
+    }
+
+    /// Returns the derivation of the account's Orchard spending key: its
+    /// recorded derivation metadata if present, and otherwise one built from
+    /// the account's coin type and ZIP 32 account ID when both are known.
+    pub fn hd_orchard_derivation(&self) -> Option<OrchardDerivation> {
+        self.orchard_derivation.or_else(|| {
+            let seed_fingerprint = self
+                .seed_fingerprint
+                .or(self.derivation.seed_fingerprint().copied());
+            Some(OrchardDerivation::new(
+                self.coin_type?,
+                self.zip32_account_id?,
+                seed_fingerprint,
+            ))
+        })
+    }
+
+    /// Checks that the account's Orchard spending key matches its derivation
+    /// when re-derived from `seed`, using [`Self::hd_orchard_derivation`].
+    ///
+    /// Succeeds without checking anything unless the account is derived
+    /// [`FromSeed`](AccountDerivation::FromSeed) and both the spending key and
+    /// a derivation are present.
+    ///
+    /// # Errors
+    /// Returns [`Error::SeedFingerprintMismatch`] if `seed` is not the seed
+    /// the account derives from, and [`Error::DerivationMismatch`] if the
+    /// recorded derivation metadata disagrees with the account's coin type or
+    /// ZIP 32 account ID, or if the re-derived key does not match.
+    #[cfg(feature = "zip32")]
+    pub fn verify_orchard_derivation(&self, seed: &[u8]) -> Result<()> {
+        let AccountDerivation::FromSeed { fingerprint, .. } = &self.derivation else {
+            return Ok(());
+        };
+        let actual = SeedFingerprint::from_seed(seed)?;
+        if actual != *fingerprint {
+            return Err(Error::SeedFingerprintMismatch {
+                expected: *fingerprint,
+                actual,
+            });
+        }
+        if let Some(derivation) = &self.orchard_derivation {
+            if self
+                .coin_type
+                .is_some_and(|coin_type| coin_type != derivation.coin_type())
+            {
+                return Err(Error::DerivationMismatch {
+                    derivation: derivation.path().to_string(),
+                    reason: format!(
+                        "it does not use the account's coin type {}",
+                        self.coin_type.unwrap()
+                    ),
+                });
+            }
+            if self
+                .zip32_account_id
+                .is_some_and(|id| id != derivation.account_index())
+            {
+                return Err(Error::DerivationMismatch {
+                    derivation: derivation.path().to_string(),
+                    reason: format!(
+                        "it does not use the account's ZIP 32 account ID {}",
+                        self.zip32_account_id.unwrap()
+                    ),
+                });
+            }
+        }
+        match (self.hd_orchard_derivation(), &self.orchard_spending_key) {
+            (Some(derivation), Some(key)) => derivation.verify(seed, key),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn sprout_migration_status(&self) -> Option<&SproutMigrationStatus> {
+        self.sprout_migration_status.as_ref()
+    }
+
+    pub fn set_sprout_migration_status(&mut self, status: Option<SproutMigrationStatus>) {
+        self.sprout_migration_status = status;
+    }
+
+    pub fn unified_metadata(&self) -> Option<&UnifiedAccountMetadata> {
+        self.unified_metadata.as_ref()
+    }
+
+    pub fn set_unified_metadata(&mut self, metadata: Option<UnifiedAccountMetadata>) {
+        self.unified_metadata = metadata;
+    }
+
+    pub fn key_material(&self) -> &KeyMaterial {
+        &self.key_material
+    }
+
+    /// Returns whether the account holds any spending key: an Orchard
+    /// spending key, a spending key in its [`KeyMaterial`], or a spending key
+    /// or spend authority on one of its addresses.
+    pub fn holds_spending_keys(&self) -> bool {
+        let address_spends = self
+            .addresses
+            .iter()
+            .any(|address| match address.address() {
+                ProtocolAddress::Transparent(address) => address.spend_authority().is_some(),
+                ProtocolAddress::Sprout(address) => address.spending_key().is_some(),
+                ProtocolAddress::Sapling(address) => address.spending_key().is_some(),
+                ProtocolAddress::Unified(_) => false,
+            });
+        self.orchard_spending_key.is_some()
+            || self
+                .key_material
+                .entries()
+                .iter()
+                .any(KeyEntry::is_spending_key)
+            || address_spends
+    }
+
+    /// Adds a key to the account's [`KeyMaterial`].
+    pub fn add_key(&mut self, key: impl Into<KeyEntry>) {
+        self.key_material.add_key(key);
+    }
+
+    /// Returns the keys of type `T` in the account's [`KeyMaterial`].
+    pub fn keys_of_type<T: KeyType>(&self) -> impl Iterator<Item = &T> {
+        self.key_material.keys_of_type()
+    }
+
+    /// Returns the total value of the account's Sprout notes that have not
+    /// been spent, whether by the Sprout-to-Sapling migration or otherwise.
+    ///
+    /// A note is considered spent once its spending transaction is recorded,
+    /// so spends should first be linked with
+    /// [`Zewif::link_spent_notes`](crate::Zewif::link_spent_notes).
+    ///
+    /// Returns `None` if the total overflows the valid range of amounts.
+    pub fn unmigrated_sprout_balance(&self) -> Option<Amount> {
+        Amount::sum(
+            self.sprout_received_notes
+                .iter()
+                .filter(|note| note.spent_in().is_none())
+                .map(|note| note.note().value()),
+        )
+    }
+
+    /// Returns the account's balance in each pool, computed from its unspent
+    /// received notes.
+    ///
+    /// A note is considered spent once its spending transaction is recorded.
+    /// The transparent component is the total value of the account's
+    /// [UTXOs](Account::utxos), mature or not. The status of the transactions is not consulted;
+    /// see [`Zewif::account_balance`](crate::Zewif::account_balance) to
+    /// exclude those that were never mined.
+    ///
+    /// Returns `None` if any total overflows the valid range of amounts.
+    pub fn balance(&self) -> Option<Balance> {
+        let sapling = Amount::sum(
+            self.sapling_received_notes
+                .iter()
+                .filter(|note| note.spent_in().is_none())
+                .map(|note| note.note().value()),
+        )?;
+        let orchard = Amount::sum(
+            self.orchard_received_notes
+                .iter()
+                .filter(|note| note.spent_in().is_none())
+                .map(|note| note.note().value()),
+        )?;
+        let transparent = Amount::sum(self.utxos.iter().map(Utxo::value))?;
+        Some(Balance::new(
+            transparent,
+            self.unmigrated_sprout_balance()?,
+            sapling,
+            orchard,
+        ))
+    }
+
+    pub fn sprout_received_notes(&self) -> &Vec<SproutReceivedNote> {
+        &self.sprout_received_notes
+    }
+
+    pub fn sprout_received_notes_len(&self) -> usize {
+        self.sprout_received_notes.len()
+    }
+
+    pub fn add_sprout_received_note(&mut self, mut note: SproutReceivedNote) {
+        note.set_index(self.sprout_received_notes.len());
+        self.sprout_received_notes.push(note);
+    }
+
+    pub fn sapling_received_notes(&self) -> &Vec<SaplingReceivedNote> {
+        &self.sapling_received_notes
+    }
+
+    pub fn sapling_received_notes_len(&self) -> usize {
+        self.sapling_received_notes.len()
+    }
+
+    pub fn add_sapling_received_note(&mut self, mut note: SaplingReceivedNote) {
+        note.set_index(self.sapling_received_notes.len());
+        self.sapling_received_notes.push(note);
+    }
+
+    pub fn orchard_received_notes(&self) -> &Vec<OrchardReceivedNote> {
+        &self.orchard_received_notes
+    }
+
+    pub fn orchard_received_notes_len(&self) -> usize {
+        self.orchard_received_notes.len()
+    }
+
+    pub fn add_orchard_received_note(&mut self, mut note: OrchardReceivedNote) {
+        note.set_index(self.orchard_received_notes.len());
+        self.orchard_received_notes.push(note);
+    }
+
+    /// Returns the account's unspent transparent outputs, as last computed by
+    /// [`Zewif::rebuild_utxo_set`](crate::Zewif::rebuild_utxo_set).
+    pub fn utxos(&self) -> &Vec<Utxo> {
+        &self.utxos
+    }
+
+    pub fn add_utxo(&mut self, mut utxo: Utxo) {
+        utxo.set_index(self.utxos.len());
+        self.utxos.push(utxo);
+    }
+
+    /// Replaces the account's unspent transparent outputs, indexing them in
+    /// the order given.
+    pub fn set_utxos(&mut self, utxos: Vec<Utxo>) {
+        self.utxos = utxos.set_indexes();
+    }
+
+    /// Removes the account's spent received notes, returning the number
+    /// removed; see [`Zewif::compact`](crate::Zewif::compact).
+    pub(crate) fn remove_spent_notes(&mut self) -> usize {
+        let before = self.sprout_received_notes.len()
+            + self.sapling_received_notes.len()
+            + self.orchard_received_notes.len();
+        self.sprout_received_notes
+            .retain(|note| note.spent_in().is_none());
+        self.sapling_received_notes
+            .retain(|note| note.spent_in().is_none());
+        self.orchard_received_notes
+            .retain(|note| note.spent_in().is_none());
+        self.sprout_received_notes = std::mem::take(&mut self.sprout_received_notes).set_indexes();
+        self.sapling_received_notes =
+            std::mem::take(&mut self.sapling_received_notes).set_indexes();
+        self.orchard_received_notes =
+            std::mem::take(&mut self.orchard_received_notes).set_indexes();
+        before
+            - (self.sprout_received_notes.len()
+                + self.sapling_received_notes.len()
+                + self.orchard_received_notes.len())
+    }
+
+    /// Removes the witnesses of the account's spent received notes, returning
+    /// the number removed; see [`Zewif::compact`](crate::Zewif::compact).
+    pub(crate) fn remove_spent_witnesses(&mut self) -> usize {
+        let mut removed = 0;
+        for note in self
+            .sprout_received_notes
+            .iter_mut()
+            .filter(|note| note.spent_in().is_some() && note.witness().is_some())
+        {
+            note.set_witness(None);
+            removed += 1;
+        }
+        for note in self
+            .sapling_received_notes
+            .iter_mut()
+            .filter(|note| note.spent_in().is_some() && note.witness().is_some())
+        {
+            note.set_witness(None);
+            removed += 1;
+        }
+        for note in self
+            .orchard_received_notes
+            .iter_mut()
+            .filter(|note| note.spent_in().is_some() && note.witness().is_some())
+        {
+            note.set_witness(None);
+            removed += 1;
+        }
+        removed
+    }
+
+    /// Returns the IDs of the transactions that created the account's
+    /// unspent received notes.
+    pub(crate) fn unspent_note_transactions(&self) -> impl Iterator<Item = TxId> {
+        let sprout = self
+            .sprout_received_notes
+            .iter()
+            .filter(|note| note.spent_in().is_none())
+            .map(|note| note.txid());
+        let sapling = self
+            .sapling_received_notes
+            .iter()
+            .filter(|note| note.spent_in().is_none())
+            .map(|note| note.txid());
+        let orchard = self
+            .orchard_received_notes
+            .iter()
+            .filter(|note| note.spent_in().is_none())
+            .map(|note| note.txid());
+        sprout.chain(sapling).chain(orchard)
+    }
+
+    /// Checks that every witness held by this account is consistent with the
+    /// commitment tree of its pool.
+    ///
+    /// When an anchor registry is supplied for a pool, each witness in that
+    /// pool must also refer to an anchor known to the registry. Sprout
+    /// witnesses are checked for structure only.
+    pub fn validate_witnesses(
+        &self,
+        sapling_anchors: Option<&SaplingAnchorRegistry>,
+        orchard_anchors: Option<&OrchardAnchorRegistry>,
+    ) -> Result<()> {
+        for note in &self.sprout_received_notes {
+            if let Some(witness) = note.witness() {
+                witness.validate().map_err(|e| Error::Context {
+                    message: format!("Sprout note {}", note.index()).into(),
+                    source: Box::new(e),
+                })?;
+            }
+        }
+        for note in &self.sapling_received_notes {
+            if let Some(witness) = note.witness() {
+                witness
+                    .validate()
+                    .and_then(|_| match sapling_anchors {
+                        Some(anchors)
+                            if !anchors.registry().contains(witness.witness().anchor()) =>
+                        {
+                            Err(Error::UnknownAnchor { pool: "Sapling" })
+                        }
+                        _ => Ok(()),
+                    })
+                    .map_err(|e| Error::Context {
+                        message: format!("Sapling note {}", note.index()).into(),
+                        source: Box::new(e),
+                    })?;
+            }
+        }
+        for note in &self.orchard_received_notes {
+            if let Some(witness) = note.witness() {
+                witness
+                    .validate()
+                    .and_then(|_| match orchard_anchors {
+                        Some(anchors)
+                            if !anchors.registry().contains(witness.witness().anchor()) =>
+                        {
+                            Err(Error::UnknownAnchor { pool: "Orchard" })
+                        }
+                        _ => Ok(()),
+                    })
+                    .map_err(|e| Error::Context {
+                        message: format!("Orchard note {}", note.index()).into(),
+                        source: Box::new(e),
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Records the spending transaction of each received note whose nullifier
+    /// appears in the given maps from nullifier to spending transaction.
+    ///
+    /// Returns the number of notes newly marked as spent.
+    pub fn link_spent_notes(
+        &mut self,
+        sprout_spends: &HashMap<SproutNullifier, TxId>,
+        sapling_spends: &HashMap<SaplingNullifier, TxId>,
+        orchard_spends: &HashMap<OrchardNullifier, TxId>,
+    ) -> usize {
+        let mut linked = 0;
+        for note in &mut self.sprout_received_notes {
+            let spent_in = note
+                .nullifier()
+                .and_then(|nf| sprout_spends.get(nf))
+                .copied();
+            if spent_in.is_some() && note.spent_in() != spent_in {
+                note.set_spent_in(spent_in);
+                linked += 1;
+            }
+        }
+        for note in &mut self.sapling_received_notes {
+            let spent_in = note
+                .nullifier()
+                .and_then(|nf| sapling_spends.get(nf))
+                .copied();
+            if spent_in.is_some() && note.spent_in() != spent_in {
+                note.set_spent_in(spent_in);
+                linked += 1;
+            }
+        }
+        for note in &mut self.orchard_received_notes {
+            let spent_in = note
+                .nullifier()
+                .and_then(|nf| orchard_spends.get(nf))
+                .copied();
+            if spent_in.is_some() && note.spent_in() != spent_in {
+                note.set_spent_in(spent_in);
+                linked += 1;
+            }
+        }
+        linked
+    }
 }
 
 impl Default for Account {
@@ -234,15 +910,33 @@ impl From<Account> for Envelope {
     fn from(value: Account) -> Self {
         let mut e = Envelope::new(value.index)
             .add_type("Account")
+            .add_assertion("id", value.id)
             .add_assertion("name", value.name)
             .add_optional_assertion("birthday_height", value.birthday_height)
             .add_optional_assertion("birthday_block", value.birthday_block)
             .add_optional_assertion("zip32_account_id", value.zip32_account_id)
-            .add_assertion("relevant_transactions", value.relevant_transactions.sort_by_cbor_encoding()); // Deterministic ordering
+            .add_optional_assertion("coin_type", value.coin_type)
+            .add_optional_assertion("is_legacy_default", value.is_legacy_default.then_some(true))
+            .add_optional_assertion("seed_fingerprint", value.seed_fingerprint)
+            .add_assertion("derivation", value.derivation)
+            .add_assertion("relevant_transactions", value.relevant_transactions.sort_by_cbor_encoding()) // Deterministic ordering
+            .add_optional_assertion("orchard_spending_key", value.orchard_spending_key)
+            .add_optional_assertion("orchard_derivation", value.orchard_derivation)
+            .add_optional_assertion("orchard_key_origin", value.orchard_key_origin)
+            .add_optional_assertion("sprout_migration_status", value.sprout_migration_status)
+            .add_optional_assertion("unified_metadata", value.unified_metadata)
+            .add_optional_assertion(
+                "key_material",
+                (!value.key_material.is_empty()).then_some(value.key_material),
+            );
 
         e = value.addresses.iter().fold(e, |e, address| e.add_assertion("address", address.clone()));
         e = value.sapling_sent_outputs.iter().fold(e, |e, output| e.add_assertion("sapling_sent_output", output.clone()));
         e = value.orchard_sent_outputs.iter().fold(e, |e, output| e.add_assertion("orchard_sent_output", output.clone()));
+        e = value.sprout_received_notes.iter().fold(e, |e, note| e.add_assertion("sprout_received_note", note.clone()));
+        e = value.sapling_received_notes.iter().fold(e, |e, note| e.add_assertion("sapling_received_note", note.clone()));
+        e = value.orchard_received_notes.iter().fold(e, |e, note| e.add_assertion("orchard_received_note", note.clone()));
+        e = value.utxos.iter().fold(e, |e, utxo| e.add_assertion("utxo", utxo.clone()));
 
         value.attachments.add_to_envelope(e)
     }
@@ -252,34 +946,92 @@ impl TryFrom<Envelope> for Account {
     type Error = bc_envelope::Error;
 
     fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
-        envelope.check_type("Account")?;
-        let index = envelope.extract_subject()?;
-        let name = envelope.extract_object_for_predicate("name")?;
-        let birthday_height = envelope.extract_optional_object_for_predicate("birthday_height")?;
-        let birthday_block = envelope.extract_optional_object_for_predicate("birthday_block")?;
-        let zip32_account_id = envelope.extract_optional_object_for_predicate("zip32_account_id")?;
-        let relevant_transactions = envelope.extract_object_for_predicate("relevant_transactions")?;
-
-        let addresses = envelope_indexed_objects_for_predicate(&envelope, "address")
-            .map_err(|e| bc_envelope::Error::General(format!("addresses: {}", e)))?;
-        let sapling_sent_outputs = envelope_indexed_objects_for_predicate(&envelope, "sapling_sent_output")
-            .map_err(|e| bc_envelope::Error::General(format!("sapling_sent_outputs: {}", e)))?;
-        let orchard_sent_outputs = envelope_indexed_objects_for_predicate(&envelope, "orchard_sent_output")
-            .map_err(|e| bc_envelope::Error::General(format!("orchard_sent_outputs: {}", e)))?;
-
-        let attachments = Attachments::try_from_envelope(&envelope)
-            .map_err(|e| bc_envelope::Error::General(format!("attachments: {}", e)))?;
+        let segment = Self::envelope_segment(&envelope);
+        Ok(Self::decode(&EnvelopeDecoder::new(envelope, segment))?)
+    }
+}
+
+impl Account {
+    /// Returns the path segment identifying an encoded account in decoding
+    /// errors: its name if it can be read, such as `Account["Savings"]`, and
+    /// otherwise its index.
+    pub(crate) fn envelope_segment(envelope: &Envelope) -> String {
+        match envelope.extract_object_for_predicate::<String>("name") {
+            Ok(name) => segment("Account", format!("{:?}", name)),
+            Err(_) => segment(
+                "Account",
+                envelope
+                    .extract_subject::<usize>()
+                    .map_or("?".to_string(), |index| index.to_string()),
+            ),
+        }
+    }
+
+    pub(crate) fn decode(decoder: &EnvelopeDecoder) -> crate::Result<Self> {
+        decoder.check_type("Account")?;
+        let index = decoder.subject()?;
+        // Accounts written before they had identifiers are given one derived
+        // from their digest, so that every decoding agrees on it.
+        let id = decoder
+            .extract_optional("id")?
+            .unwrap_or_else(|| ARID::from_data(*decoder.envelope().digest().data()));
+        let name = decoder.extract("name")?;
+        let birthday_height = decoder.extract_optional("birthday_height")?;
+        let birthday_block = decoder.extract_optional("birthday_block")?;
+        let zip32_account_id = decoder.extract_optional("zip32_account_id")?;
+        let coin_type = decoder.extract_optional("coin_type")?;
+        // Only asserted for the legacy default account.
+        let is_legacy_default = decoder
+            .extract_optional("is_legacy_default")?
+            .unwrap_or(false);
+        let seed_fingerprint = decoder.optional_object("seed_fingerprint")?;
+        // Absent from envelopes written before the field was introduced.
+        let derivation = decoder.optional_object("derivation")?.unwrap_or_default();
+        let relevant_transactions = decoder.extract("relevant_transactions")?;
+        let orchard_spending_key = decoder.optional_spending_object("orchard_spending_key")?;
+        let orchard_derivation = decoder.optional_object("orchard_derivation")?;
+        let orchard_key_origin = decoder.optional_object("orchard_key_origin")?;
+        let sprout_migration_status = decoder.optional_object("sprout_migration_status")?;
+        let unified_metadata = decoder.optional_object("unified_metadata")?;
+        // Only asserted when the account holds keys.
+        let key_material = decoder.optional_object("key_material")?.unwrap_or_default();
+
+        let addresses =
+            decoder.indexed_objects_with("address", Address::envelope_segment, Address::decode)?;
+        let sapling_sent_outputs = decoder.indexed_objects("sapling_sent_output")?;
+        let orchard_sent_outputs = decoder.indexed_objects("orchard_sent_output")?;
+        let sprout_received_notes = decoder.indexed_objects("sprout_received_note")?;
+        let sapling_received_notes = decoder.indexed_objects("sapling_received_note")?;
+        let orchard_received_notes = decoder.indexed_objects("orchard_received_note")?;
+        let utxos = decoder.indexed_objects("utxo")?;
+
+        let attachments = decoder.attachments()?;
 
         Ok(Self {
             index,
+            id,
             name,
             birthday_height,
             birthday_block,
             zip32_account_id,
+            coin_type,
+            is_legacy_default,
+            seed_fingerprint,
+            derivation,
             addresses,
             relevant_transactions,
+            orchard_spending_key,
+            orchard_derivation,
+            orchard_key_origin,
+            sprout_migration_status,
+            unified_metadata,
+            key_material,
             sapling_sent_outputs,
             orchard_sent_outputs,
+            sprout_received_notes,
+            sapling_received_notes,
+            orchard_received_notes,
+            utxos,
             attachments,
         })
     }
@@ -289,9 +1041,16 @@ impl TryFrom<Envelope> for Account {
 mod tests {
     use std::collections::HashSet;
 
-    use bc_envelope::Attachments;
+    use bc_components::ARID;
+    use bc_envelope::{Attachments, Envelope};
 
-    use crate::{BlockHash, BlockHeight, test_envelope_roundtrip};
+    use crate::{
+        AccountDerivation, BlockHash, BlockHeight, KeyMaterial, KeyOrigin, SeedFingerprint,
+        UnifiedAccountMetadata,
+        orchard::{OrchardDerivation, OrchardSpendingKey},
+        sprout::SproutMigrationStatus,
+        test_envelope_roundtrip,
+    };
 
     use super::Account;
 
@@ -301,18 +1060,284 @@ mod tests {
 
             Self {
                 index: 0,
+                id: ARID::new(),
                 name: String::random(),
                 birthday_height: BlockHeight::opt_random(),
                 birthday_block: BlockHash::opt_random(),
                 zip32_account_id: u32::opt_random(),
+                coin_type: u32::opt_random(),
+                is_legacy_default: rand::random(),
+                seed_fingerprint: SeedFingerprint::opt_random(),
+                derivation: AccountDerivation::random(),
                 addresses: Vec::random().set_indexes(),
                 relevant_transactions: HashSet::random(),
+                orchard_spending_key: OrchardSpendingKey::opt_random(),
+                orchard_derivation: OrchardDerivation::opt_random(),
+                orchard_key_origin: KeyOrigin::opt_random(),
+                sprout_migration_status: SproutMigrationStatus::opt_random(),
+                unified_metadata: UnifiedAccountMetadata::opt_random(),
+                key_material: KeyMaterial::random(),
                 sapling_sent_outputs: Vec::random().set_indexes(),
                 orchard_sent_outputs: Vec::random().set_indexes(),
+                sprout_received_notes: Vec::random().set_indexes(),
+                sapling_received_notes: Vec::random().set_indexes(),
+                orchard_received_notes: Vec::random().set_indexes(),
+                utxos: Vec::random().set_indexes(),
                 attachments: Attachments::random(),
             }
         }
     }
 
     test_envelope_roundtrip!(Account);
+
+    #[test]
+    fn test_legacy_account_id_is_stable() {
+        let mut account = Account::new();
+        account.set_name("Savings");
+        let envelope = Envelope::from(account.clone())
+            .remove_assertion(Envelope::new_assertion("id", account.id()));
+        let first = Account::try_from(envelope.clone()).unwrap();
+        assert_ne!(first.id(), account.id());
+        assert_eq!(Account::try_from(envelope).unwrap().id(), first.id());
+    }
+
+    #[test]
+    fn test_relevant_transactions_are_a_set() {
+        use crate::TxId;
+
+        let [tx1, tx2] = [1u8, 2].map(|byte| TxId::from_bytes([byte; 32]));
+        let mut account = Account::new();
+        assert!(account.add_relevant_transaction(tx2));
+        assert!(account.add_relevant_transaction(tx1));
+        assert!(!account.add_relevant_transaction(tx2));
+        assert_eq!(account.relevant_transactions_len(), 2);
+        assert_eq!(
+            account.relevant_transactions().copied().collect::<Vec<_>>(),
+            [tx1, tx2]
+        );
+        assert!(account.contains_transaction(tx1));
+
+        assert!(account.remove_relevant_transaction(tx1));
+        assert!(!account.remove_relevant_transaction(tx1));
+        assert!(!account.contains_transaction(tx1));
+        assert_eq!(
+            account.relevant_transactions().copied().collect::<Vec<_>>(),
+            [tx2]
+        );
+    }
+
+    #[test]
+    fn test_address_indexes() {
+        use crate::{Address, Indexed, ProtocolAddress, transparent};
+
+        let address = |string: &str| {
+            Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+                string,
+            )))
+        };
+        let strings =
+            |addresses: Vec<&Address>| addresses.iter().map(|a| a.as_string()).collect::<Vec<_>>();
+
+        let mut account = Account::new();
+        for string in ["t1d", "t1b", "t1c", "t1a"] {
+            account.add_address(address(string));
+        }
+        assert_eq!(
+            account
+                .addresses()
+                .iter()
+                .map(Indexed::index)
+                .collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+
+        // An explicit index is kept unless it is taken.
+        let mut explicit = address("t1e");
+        explicit.set_index(9);
+        account.add_address(explicit);
+        let mut taken = address("t1f");
+        taken.set_index(2);
+        account.add_address(taken);
+        let ordered = account.addresses_ordered();
+        assert_eq!(
+            ordered.iter().map(|a| a.index()).collect::<Vec<_>>(),
+            [0, 1, 2, 3, 9, 10]
+        );
+
+        // Insertion order survives an envelope round trip.
+        let decoded = Account::try_from(Envelope::from(account.clone())).unwrap();
+        assert_eq!(decoded, account);
+        assert_eq!(
+            strings(decoded.addresses_ordered()),
+            ["t1d", "t1b", "t1c", "t1a", "t1e", "t1f"]
+        );
+
+        account.addresses_mut().retain(|a| a.as_string() != "t1b");
+        account.reindex();
+        assert_eq!(
+            account
+                .addresses()
+                .iter()
+                .map(Indexed::index)
+                .collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4]
+        );
+        assert_eq!(
+            strings(account.addresses_ordered()),
+            ["t1d", "t1c", "t1a", "t1e", "t1f"]
+        );
+    }
+
+    #[test]
+    fn test_hd_metadata_roundtrip() {
+        let mut account = Account::new();
+        account.set_zip32_account_id(2);
+        account.set_coin_type(Some(133));
+        account.set_legacy_default(true);
+        let decoded = Account::try_from(Envelope::from(account.clone())).unwrap();
+        assert_eq!(decoded, account);
+        assert_eq!(decoded.coin_type(), Some(133));
+        assert!(decoded.is_legacy_default());
+
+        // The flag is only asserted for the legacy default account.
+        let envelope = Envelope::from(Account::new());
+        assert!(
+            envelope
+                .optional_assertion_with_predicate("is_legacy_default")
+                .unwrap()
+                .is_none()
+        );
+        assert!(!Account::try_from(envelope).unwrap().is_legacy_default());
+    }
+
+    #[cfg(feature = "zip32")]
+    #[test]
+    fn test_verify_orchard_derivation_from_hd_metadata() {
+        use crate::DerivationPath;
+
+        let seed: Vec<u8> = (0u8..32).collect();
+        let key = OrchardDerivation::new(133, 1, None)
+            .derive_spending_key(&seed)
+            .unwrap();
+        let mut account = Account::new();
+        account.set_orchard_spending_key(Some(key));
+        account.set_coin_type(Some(133));
+        account.set_derivation(AccountDerivation::FromSeed {
+            fingerprint: SeedFingerprint::from_seed(&seed).unwrap(),
+            path: DerivationPath::hardened(&[32, 133, 1]).unwrap(),
+        });
+        assert_eq!(account.hd_orchard_derivation(), None);
+
+        account.set_zip32_account_id(1);
+        assert_eq!(
+            account.hd_orchard_derivation().unwrap().path(),
+            "m/32'/133'/1'"
+        );
+        account.verify_orchard_derivation(&seed).unwrap();
+        account.set_zip32_account_id(0);
+        assert!(account.verify_orchard_derivation(&seed).is_err());
+
+        // Recorded derivation metadata must agree with the account's own.
+        account.set_orchard_derivation(Some(OrchardDerivation::new(133, 1, None)));
+        let error = account.verify_orchard_derivation(&seed).unwrap_err();
+        assert!(
+            error.to_string().contains("ZIP 32 account ID 0"),
+            "{}",
+            error
+        );
+        account.set_zip32_account_id(1);
+        account.verify_orchard_derivation(&seed).unwrap();
+
+        // Only accounts derived from a seed are re-derived.
+        assert!(account.verify_orchard_derivation(&[7u8; 32]).is_err());
+        account.set_derivation(AccountDerivation::ImportedKeysOnly);
+        account.verify_orchard_derivation(&[7u8; 32]).unwrap();
+    }
+
+    #[test]
+    fn test_random_account_witnesses_are_valid() {
+        use crate::RandomInstance;
+        assert!(Account::random().validate_witnesses(None, None).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_sprout_witness_is_rejected() {
+        use crate::{
+            Amount, Blob, IncrementalWitness, TxId,
+            sprout::{self, MerkleHashSprout, SproutReceivedNote, SproutWitness},
+        };
+
+        let mut note = SproutReceivedNote::new(
+            TxId::from_bytes([0u8; 32]),
+            0,
+            0,
+            sprout::Note::from_parts(
+                Amount::from_u64(1000).unwrap(),
+                Blob::default(),
+                Blob::default(),
+                Blob::default(),
+            )
+            .unwrap(),
+        );
+        // A Sapling-depth path is too long for the Sprout tree.
+        note.set_witness(Some(SproutWitness::new(IncrementalWitness::from_parts(
+            MerkleHashSprout::new([1u8; 32]),
+            0,
+            vec![MerkleHashSprout::new([2u8; 32]); 32],
+            MerkleHashSprout::new([3u8; 32]),
+            1,
+            vec![],
+        ))));
+        let mut account = Account::new();
+        account.add_sprout_received_note(note);
+        assert!(account.validate_witnesses(None, None).is_err());
+    }
+
+    #[test]
+    fn test_unmigrated_sprout_balance() {
+        use std::collections::HashMap;
+
+        use crate::{
+            Amount, Blob, TxId,
+            sprout::{self, SproutNullifier, SproutReceivedNote},
+        };
+
+        let migrated_nf = SproutNullifier::new([1u8; 32]);
+        let migration_tx = TxId::from_bytes([9u8; 32]);
+        let mut account = Account::new();
+        for (value, nullifier) in [(3000, migrated_nf), (500, SproutNullifier::new([2u8; 32]))] {
+            let mut note = SproutReceivedNote::new(
+                TxId::from_bytes([0u8; 32]),
+                0,
+                0,
+                sprout::Note::from_parts(
+                    Amount::from_u64(value).unwrap(),
+                    Blob::default(),
+                    Blob::default(),
+                    Blob::default(),
+                )
+                .unwrap(),
+            );
+            note.set_nullifier(Some(nullifier));
+            account.add_sprout_received_note(note);
+        }
+        assert_eq!(
+            account.unmigrated_sprout_balance(),
+            Some(Amount::from_u64(3500).unwrap())
+        );
+
+        let sprout_spends = HashMap::from([(migrated_nf, migration_tx)]);
+        assert_eq!(
+            account.link_spent_notes(&sprout_spends, &HashMap::new(), &HashMap::new()),
+            1
+        );
+        assert_eq!(
+            account.sprout_received_notes()[0].spent_in(),
+            Some(migration_tx)
+        );
+        assert_eq!(
+            account.unmigrated_sprout_balance(),
+            Some(Amount::from_u64(500).unwrap())
+        );
+    }
 }
@@ -0,0 +1,118 @@
+use bc_envelope::prelude::*;
+
+use crate::{DerivationPath, SeedFingerprint};
+
+/// What an account's keys are derived from.
+///
+/// A wallet may hold several seeds: zcashd wallets created before it adopted
+/// BIP-39 hold both their legacy HD seed and the mnemonic seed generated on
+/// upgrade, with accounts derived from either. Rather than leaving a receiving
+/// wallet to guess that an account belongs to "the wallet's seed", each
+/// account states its source explicitly.
+///
+/// # Examples
+/// ```
+/// # use zewif::{AccountDerivation, DerivationPath, SeedFingerprint};
+/// let derivation = AccountDerivation::FromSeed {
+///     fingerprint: SeedFingerprint::new([7u8; 32]),
+///     path: DerivationPath::hardened(&[32, 133, 0]).unwrap(),
+/// };
+/// assert!(derivation.is_from_seed());
+/// assert_eq!(derivation.path().unwrap().to_string(), "m/32'/133'/0'");
+/// assert_eq!(AccountDerivation::ImportedKeysOnly.seed_fingerprint(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum AccountDerivation {
+    /// Derived from the seed with the given fingerprint, at the given account
+    /// path.
+    FromSeed {
+        fingerprint: SeedFingerprint,
+        path: DerivationPath,
+    },
+
+    /// The account holds only imported keys, and cannot be recovered from any
+    /// seed.
+    ImportedKeysOnly,
+
+    /// The source wallet did not record what the account derives from.
+    #[default]
+    Unknown,
+}
+
+impl AccountDerivation {
+    pub fn is_from_seed(&self) -> bool {
+        matches!(self, Self::FromSeed { .. })
+    }
+
+    /// Returns the fingerprint of the seed the account derives from, if any.
+    pub fn seed_fingerprint(&self) -> Option<&SeedFingerprint> {
+        match self {
+            Self::FromSeed { fingerprint, .. } => Some(fingerprint),
+            _ => None,
+        }
+    }
+
+    /// Returns the account's derivation path, if it derives from a seed.
+    pub fn path(&self) -> Option<&DerivationPath> {
+        match self {
+            Self::FromSeed { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl From<AccountDerivation> for Envelope {
+    fn from(value: AccountDerivation) -> Self {
+        match value {
+            AccountDerivation::FromSeed { fingerprint, path } => Envelope::new("FromSeed")
+                .add_assertion("fingerprint", fingerprint)
+                .add_assertion("path", path),
+            AccountDerivation::ImportedKeysOnly => Envelope::new("ImportedKeysOnly"),
+            AccountDerivation::Unknown => Envelope::new("Unknown"),
+        }
+        .add_type("AccountDerivation")
+    }
+}
+
+impl TryFrom<Envelope> for AccountDerivation {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("AccountDerivation")?;
+        match envelope.extract_subject::<String>()?.as_str() {
+            "FromSeed" => Ok(AccountDerivation::FromSeed {
+                fingerprint: envelope.try_object_for_predicate("fingerprint")?,
+                path: envelope.extract_object_for_predicate("path")?,
+            }),
+            "ImportedKeysOnly" => Ok(AccountDerivation::ImportedKeysOnly),
+            "Unknown" => Ok(AccountDerivation::Unknown),
+            other => Err(bc_envelope::Error::General(format!(
+                "invalid account derivation: {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DerivationPath, SeedFingerprint, test_envelope_roundtrip};
+
+    use super::AccountDerivation;
+
+    impl crate::RandomInstance for AccountDerivation {
+        fn random() -> Self {
+            match rand::random_range(0..3) {
+                0 => AccountDerivation::FromSeed {
+                    fingerprint: SeedFingerprint::random(),
+                    path: DerivationPath::random(),
+                },
+                1 => AccountDerivation::ImportedKeysOnly,
+                _ => AccountDerivation::Unknown,
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(AccountDerivation);
+}
@@ -1,4 +1,7 @@
-use crate::{DebugOption, Indexed};
+use crate::{
+    DebugOption, Indexed,
+    envelope_decoder::{EnvelopeDecoder, segment},
+};
 use bc_envelope::prelude::*;
 
 use super::ProtocolAddress;
@@ -47,6 +50,7 @@ use super::ProtocolAddress;
 /// assert_eq!(address.name(), "Donation Address");
 /// ```
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Address {
     /// The index of this address in the wallet
     /// TODO: I'm not sure that this is useful; if it's intended to be used as a primary key then
@@ -63,6 +67,7 @@ pub struct Address {
     purpose: Option<String>,
 
     /// Additional metadata attached to this address
+    #[cfg_attr(feature = "json", serde(with = "crate::json::attachments"))]
     attachments: Attachments,
 }
 
@@ -304,15 +309,37 @@ impl TryFrom<Envelope> for Address {
     type Error = bc_envelope::Error;
 
     fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
-        envelope.check_type("Address")?;
-        let index = envelope.extract_subject()?;
-        let address = envelope.try_object_for_predicate("address")?;
-        let name = envelope.try_object_for_predicate("name")?;
-        let purpose = envelope.try_optional_object_for_predicate("purpose")?;
-        let attachments =
-            Attachments::try_from_envelope(&envelope).map_err(|e| {
-                bc_envelope::Error::General(format!("attachments: {}", e))
-            })?;
+        let segment = Self::envelope_segment(&envelope);
+        Ok(Self::decode(&EnvelopeDecoder::new(envelope, segment))?)
+    }
+}
+
+impl Address {
+    /// Returns the path segment identifying an encoded address in decoding
+    /// errors: the address string if it can be read, such as
+    /// `Address[t1abc...]`, and otherwise its index.
+    pub(crate) fn envelope_segment(envelope: &Envelope) -> String {
+        let address = envelope
+            .object_for_predicate("address")
+            .and_then(|address| address.extract_subject::<String>());
+        match address {
+            Ok(address) => segment("Address", address),
+            Err(_) => segment(
+                "Address",
+                envelope
+                    .extract_subject::<usize>()
+                    .map_or("?".to_string(), |index| index.to_string()),
+            ),
+        }
+    }
+
+    pub(crate) fn decode(decoder: &EnvelopeDecoder) -> crate::Result<Self> {
+        decoder.check_type("Address")?;
+        let index = decoder.subject()?;
+        let address = decoder.object("address")?;
+        let name = decoder.object("name")?;
+        let purpose = decoder.optional_object("purpose")?;
+        let attachments = decoder.attachments()?;
         Ok(Address { index, address, name, purpose, attachments })
     }
 }
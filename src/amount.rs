@@ -56,9 +56,21 @@ pub const MAX_BALANCE: i64 = MAX_MONEY as i64;
 /// # Ok(())
 /// # }
 /// ```
-/// FIXME: Amounts in the zewif format should never be negative; negative values are only used
-/// transiently in the protocol.
+///
+/// # Signedness
+/// Negative amounts are representable so that net changes in value, such as
+/// those of [`AccountValueChange`](crate::AccountValueChange), can be
+/// computed. The values recorded in the format, such as those of notes and
+/// transparent outputs, are never negative: construct them with
+/// [`Amount::from_u64`], [`Amount::from_nonnegative_i64`], or
+/// [`Amount::from_zec_str`] on an unsigned string, which reject negative
+/// values.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "i64", try_from = "i64")
+)]
 pub struct Amount(i64);
 
 impl std::fmt::Debug for Amount {
@@ -68,6 +80,15 @@ impl std::fmt::Debug for Amount {
 }
 
 impl Amount {
+    /// One ZEC.
+    pub const COIN: Amount = Amount(COIN as i64);
+
+    /// The largest valid amount, the total supply of ZEC.
+    pub const MAX: Amount = Amount(MAX_BALANCE);
+
+    /// The smallest valid amount, the negation of [`Amount::MAX`].
+    pub const MIN: Amount = Amount(-MAX_BALANCE);
+
     /// Returns a zero-valued Amount.
     pub const fn zero() -> Self {
         Amount(0)
@@ -167,6 +188,97 @@ impl Amount {
         self.0.is_negative()
     }
 
+    /// Adds two Amounts.
+    ///
+    /// Returns [`Error::AmountOverflow`] or [`Error::AmountUnderflow`] if the
+    /// sum is outside the range `{-MAX_BALANCE..MAX_BALANCE}`.
+    pub fn checked_add(self, rhs: Amount) -> Result<Amount> {
+        // Both operands are within the valid range, so the sum cannot overflow an i64.
+        Amount::from_i64(self.0 + rhs.0)
+    }
+
+    /// Subtracts `rhs` from `self`.
+    ///
+    /// Returns [`Error::AmountOverflow`] or [`Error::AmountUnderflow`] if the
+    /// difference is outside the range `{-MAX_BALANCE..MAX_BALANCE}`.
+    pub fn checked_sub(self, rhs: Amount) -> Result<Amount> {
+        Amount::from_i64(self.0 - rhs.0)
+    }
+
+    /// Parses an amount of ZEC written in decimal, such as `"1.2345"` or
+    /// `"-0.00000001"`, as zcashd's RPC methods accept them.
+    ///
+    /// The integer part is required, and the fractional part, if present,
+    /// must have between one and eight digits: a ninth digit would be a
+    /// fraction of a zatoshi, which cannot be represented without loss.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidAmount`] if the string is not such a number,
+    /// and [`Error::AmountOverflow`] or [`Error::AmountUnderflow`] if it is
+    /// outside the valid range.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Amount;
+    /// let amount = Amount::from_zec_str("1.2345").unwrap();
+    /// assert_eq!(i64::from(amount), 123_450_000);
+    /// assert_eq!(amount.to_zec_string(), "1.23450000");
+    /// assert!(Amount::from_zec_str("0.000000001").is_err());
+    /// assert!(Amount::from_zec_str("21000000.00000001").is_err());
+    /// ```
+    pub fn from_zec_str(s: &str) -> Result<Amount> {
+        let invalid = || Error::InvalidAmount {
+            amount: s.to_string(),
+            reason: "not a decimal number".to_string(),
+        };
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+        if integer.is_empty() || !is_digits(integer) || !is_digits(fraction) {
+            return Err(invalid());
+        }
+        if unsigned.contains('.') && fraction.is_empty() {
+            return Err(invalid());
+        }
+        if fraction.len() > 8 {
+            return Err(Error::InvalidAmount {
+                amount: s.to_string(),
+                reason: "more than eight decimal places".to_string(),
+            });
+        }
+        let out_of_range = || {
+            if negative {
+                Error::AmountUnderflow(u64::MAX)
+            } else {
+                Error::AmountOverflow(u64::MAX)
+            }
+        };
+        // Larger integer parts are out of range, and could overflow below.
+        let integer: u64 = integer
+            .parse()
+            .ok()
+            .filter(|integer| *integer <= MAX_MONEY / COIN)
+            .ok_or_else(out_of_range)?;
+        let fraction: u64 = format!("{:0<8}", fraction).parse().map_err(|_| invalid())?;
+        let zats = integer
+            .checked_mul(COIN)
+            .and_then(|zats| zats.checked_add(fraction))
+            .ok_or_else(out_of_range)? as i64;
+        Amount::from_i64(if negative { -zats } else { zats })
+    }
+
+    /// Formats the amount in ZEC with exactly eight decimal places, such as
+    /// `"1.23450000"`, prefixed with `-` if it is negative. The result is
+    /// parsed back by [`Amount::from_zec_str`].
+    pub fn to_zec_string(self) -> String {
+        let sign = if self.is_negative() { "-" } else { "" };
+        let zats = self.0.unsigned_abs();
+        format!("{}{}.{:08}", sign, zats / COIN, zats % COIN)
+    }
+
     /// Sums a collection of Amount values with overflow checking.
     ///
     /// This helper method safely adds a collection of Amounts, returning None if
@@ -247,7 +359,7 @@ impl Add<Amount> for Amount {
     type Output = Option<Amount>;
 
     fn add(self, rhs: Amount) -> Option<Amount> {
-        Amount::from_i64(self.0 + rhs.0).ok()
+        self.checked_add(rhs).ok()
     }
 }
 
@@ -265,7 +377,7 @@ impl Sub<Amount> for Amount {
     type Output = Option<Amount>;
 
     fn sub(self, rhs: Amount) -> Option<Amount> {
-        Amount::from_i64(self.0 - rhs.0).ok()
+        self.checked_sub(rhs).ok()
     }
 }
 
@@ -350,7 +462,7 @@ impl TryFrom<Envelope> for Amount {
 
 #[cfg(test)]
 mod tests {
-    use crate::{test_cbor_roundtrip, test_envelope_roundtrip};
+    use crate::{Error, test_cbor_roundtrip, test_envelope_roundtrip};
 
     use super::{Amount, MAX_BALANCE};
 
@@ -358,11 +470,140 @@ mod tests {
         fn random() -> Self {
             let mut rng = bc_rand::thread_rng();
             let value =
-                rand::Rng::gen_range(&mut rng, -MAX_BALANCE..=MAX_BALANCE);
+                rand::Rng::random_range(&mut rng, -MAX_BALANCE..=MAX_BALANCE);
             Self(value)
         }
     }
 
+    impl Amount {
+        /// Returns a random valid amount that is not negative, as the values
+        /// of notes are.
+        pub(crate) fn random_nonnegative() -> Self {
+            Self(rand::random_range(0..=MAX_BALANCE))
+        }
+    }
+
     test_cbor_roundtrip!(Amount);
     test_envelope_roundtrip!(Amount);
+
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(
+            Amount::COIN.checked_add(Amount::COIN).unwrap(),
+            Amount::from_u64(200_000_000).unwrap()
+        );
+        assert_eq!(
+            Amount::zero().checked_sub(Amount::COIN).unwrap(),
+            -Amount::COIN
+        );
+        assert!(matches!(
+            Amount::MAX.checked_add(Amount::from_u64(1).unwrap()),
+            Err(Error::AmountOverflow(_))
+        ));
+        assert!(matches!(
+            Amount::MIN.checked_sub(Amount::from_u64(1).unwrap()),
+            Err(Error::AmountUnderflow(_))
+        ));
+        assert_eq!(
+            Amount::MAX.checked_add(Amount::MIN).unwrap(),
+            Amount::zero()
+        );
+        assert!(Amount::MIN < -Amount::COIN && -Amount::COIN < Amount::zero());
+    }
+
+    #[test]
+    fn test_zec_str_parsing() {
+        let zats = |s: &str| Amount::from_zec_str(s).map(i64::from);
+        assert_eq!(zats("0").unwrap(), 0);
+        assert_eq!(zats("1").unwrap(), 100_000_000);
+        assert_eq!(zats("0.00000001").unwrap(), 1);
+        assert_eq!(zats("-12.5").unwrap(), -1_250_000_000);
+        assert_eq!(zats("21000000").unwrap(), MAX_BALANCE);
+        assert_eq!(zats("-21000000.00000000").unwrap(), -MAX_BALANCE);
+        for invalid in [
+            "",
+            "-",
+            ".5",
+            "1.",
+            "1.2.3",
+            "+1",
+            "1e8",
+            " 1",
+            "1,5",
+            "--1",
+            "0x10",
+            "1.123456789",
+        ] {
+            assert!(
+                matches!(zats(invalid), Err(Error::InvalidAmount { .. })),
+                "{:?}",
+                invalid
+            );
+        }
+        assert!(matches!(
+            zats("21000000.00000001"),
+            Err(Error::AmountOverflow(_))
+        ));
+        assert!(matches!(
+            zats("99999999999999999999"),
+            Err(Error::AmountOverflow(_))
+        ));
+        // Integer parts that fit in a u64 but overflow it once in zatoshis.
+        assert!(matches!(
+            zats("1000000000000"),
+            Err(Error::AmountOverflow(_))
+        ));
+        assert!(matches!(
+            zats("184467440738"),
+            Err(Error::AmountOverflow(_))
+        ));
+        assert!(matches!(
+            zats("-184467440738"),
+            Err(Error::AmountUnderflow(_))
+        ));
+        assert!(matches!(zats("-21000001"), Err(Error::AmountUnderflow(_))));
+    }
+
+    /// Every valid amount formats to a string that parses back to it, and
+    /// every string with at most eight decimals parses to an amount that
+    /// formats back to it, padded to eight decimals.
+    #[test]
+    fn test_zec_string_roundtrip() {
+        use crate::RandomInstance;
+
+        for amount in [
+            Amount::zero(),
+            Amount::MAX,
+            Amount::MIN,
+            Amount::COIN,
+            -Amount::COIN,
+        ] {
+            assert_eq!(
+                Amount::from_zec_str(&amount.to_zec_string()).unwrap(),
+                amount
+            );
+        }
+        for _ in 0..1_000 {
+            let amount = Amount::random();
+            let formatted = amount.to_zec_string();
+            assert_eq!(formatted.split_once('.').unwrap().1.len(), 8);
+            assert_eq!(Amount::from_zec_str(&formatted).unwrap(), amount);
+
+            let integer = rand::random_range(0..=20_999_999u64);
+            let decimals = rand::random_range(0..=8usize);
+            let fraction: String = (0..decimals)
+                .map(|_| char::from(b'0' + rand::random_range(0..10u8)))
+                .collect();
+            let s = if decimals == 0 {
+                integer.to_string()
+            } else {
+                format!("{}.{}", integer, fraction)
+            };
+            let parsed = Amount::from_zec_str(&s).unwrap();
+            assert_eq!(
+                parsed.to_zec_string(),
+                format!("{}.{:0<8}", integer, fraction)
+            );
+        }
+    }
 }
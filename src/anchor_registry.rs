@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use crate::BlockHeight;
+
+/// A record of the note commitment tree roots known to a wallet, and the block
+/// heights at which they were the tree's root.
+///
+/// Every witness held by a wallet is valid for a particular anchor. Recording
+/// the anchors the wallet has observed lets the receiving wallet check that
+/// each migrated witness refers to a root of the chain it knows about, and
+/// identify the height from which the witness must be brought up to date.
+///
+/// # Zcash Concept Relation
+/// The Sapling and Orchard pools each have their own note commitment tree and
+/// therefore their own anchors. The pool-specific types
+/// [`SaplingAnchorRegistry`](crate::sapling::SaplingAnchorRegistry) and
+/// [`OrchardAnchorRegistry`](crate::orchard::OrchardAnchorRegistry) fix the
+/// node type for each pool.
+///
+/// # Examples
+/// ```
+/// # use zewif::{AnchorRegistry, BlockHeight};
+/// let mut registry = AnchorRegistry::<[u8; 32]>::new();
+/// registry.insert([1u8; 32], BlockHeight::from(2_000_000));
+///
+/// assert_eq!(registry.height_of(&[1u8; 32]), Some(BlockHeight::from(2_000_000)));
+/// assert!(!registry.contains(&[2u8; 32]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "Node: serde::Serialize + AsRef<[u8]> + Clone",
+        deserialize = "Node: serde::Deserialize<'de> + std::hash::Hash + Eq"
+    ))
+)]
+pub struct AnchorRegistry<Node: std::hash::Hash + Eq> {
+    #[cfg_attr(feature = "json", serde(with = "crate::json::anchors"))]
+    anchors: HashMap<Node, BlockHeight>,
+}
+
+impl<Node: std::hash::Hash + Eq> AnchorRegistry<Node> {
+    /// Creates an empty anchor registry.
+    pub fn new() -> Self {
+        Self {
+            anchors: HashMap::new(),
+        }
+    }
+
+    /// Records that `anchor` was the root of the tree as of `height`.
+    pub fn insert(&mut self, anchor: Node, height: BlockHeight) {
+        self.anchors.insert(anchor, height);
+    }
+
+    /// Returns the height at which `anchor` was the root of the tree, if known.
+    pub fn height_of(&self, anchor: &Node) -> Option<BlockHeight> {
+        self.anchors.get(anchor).copied()
+    }
+
+    /// Returns `true` if `anchor` is known to the registry.
+    pub fn contains(&self, anchor: &Node) -> bool {
+        self.anchors.contains_key(anchor)
+    }
+
+    /// Returns the number of anchors in the registry.
+    pub fn len(&self) -> usize {
+        self.anchors.len()
+    }
+
+    /// Returns `true` if the registry contains no anchors.
+    pub fn is_empty(&self) -> bool {
+        self.anchors.is_empty()
+    }
+
+    /// Iterates over the anchors in the registry and their heights.
+    pub fn iter(&self) -> impl Iterator<Item = (&Node, &BlockHeight)> {
+        self.anchors.iter()
+    }
+}
+
+impl<Node: std::hash::Hash + Eq> Default for AnchorRegistry<Node> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Node: std::hash::Hash + Eq> FromIterator<(Node, BlockHeight)> for AnchorRegistry<Node> {
+    fn from_iter<I: IntoIterator<Item = (Node, BlockHeight)>>(iter: I) -> Self {
+        Self {
+            anchors: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AnchorRegistry;
+    use crate::{BlockHeight, RandomInstance};
+
+    impl<Node> RandomInstance for AnchorRegistry<Node>
+    where
+        Node: RandomInstance + std::hash::Hash + Eq + Clone,
+    {
+        fn random() -> Self {
+            Vec::<Node>::random()
+                .into_iter()
+                .map(|anchor| (anchor, BlockHeight::random()))
+                .collect()
+        }
+    }
+}
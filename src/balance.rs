@@ -0,0 +1,94 @@
+use std::ops::Add;
+
+use crate::Amount;
+
+/// The value held by an account or wallet, broken down by pool.
+///
+/// `Balance` is computed from the unspent funds preserved in the interchange
+/// format rather than stored in it. Each shielded component is the total value
+/// of the received notes in that pool that have no recorded spending
+/// transaction, so spends should first be linked with
+/// [`Zewif::link_spent_notes`](crate::Zewif::link_spent_notes).
+///
+/// # Zcash Concept Relation
+/// Zcash funds live in separate value pools, and moving value between them is
+/// visible on chain. Reporting each pool separately lets a receiving wallet
+/// show, for example, that some funds remain in the deprecated Sprout pool and
+/// need special handling.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Account, Amount};
+/// let account = Account::new();
+/// let balance = account.balance().unwrap();
+/// assert_eq!(balance.total(), Some(Amount::zero()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Balance {
+    transparent: Amount,
+    sprout: Amount,
+    sapling: Amount,
+    orchard: Amount,
+}
+
+impl Balance {
+    /// Creates a balance from its per-pool components.
+    pub fn new(transparent: Amount, sprout: Amount, sapling: Amount, orchard: Amount) -> Self {
+        Self {
+            transparent,
+            sprout,
+            sapling,
+            orchard,
+        }
+    }
+
+    /// Returns the value held in the transparent pool.
+    pub fn transparent(&self) -> Amount {
+        self.transparent
+    }
+
+    /// Returns the value held in the Sprout pool.
+    pub fn sprout(&self) -> Amount {
+        self.sprout
+    }
+
+    /// Returns the value held in the Sapling pool.
+    pub fn sapling(&self) -> Amount {
+        self.sapling
+    }
+
+    /// Returns the value held in the Orchard pool.
+    pub fn orchard(&self) -> Amount {
+        self.orchard
+    }
+
+    /// Returns the total value across all pools, or `None` on overflow.
+    pub fn total(&self) -> Option<Amount> {
+        Amount::sum([self.transparent, self.sprout, self.sapling, self.orchard])
+    }
+}
+
+impl Default for Balance {
+    fn default() -> Self {
+        Self::new(
+            Amount::zero(),
+            Amount::zero(),
+            Amount::zero(),
+            Amount::zero(),
+        )
+    }
+}
+
+/// Adds two balances pool by pool, checking for overflow
+impl Add<Balance> for Balance {
+    type Output = Option<Balance>;
+
+    fn add(self, rhs: Balance) -> Option<Balance> {
+        Some(Balance {
+            transparent: (self.transparent + rhs.transparent)?,
+            sprout: (self.sprout + rhs.sprout)?,
+            sapling: (self.sapling + rhs.sapling)?,
+            orchard: (self.orchard + rhs.orchard)?,
+        })
+    }
+}
@@ -0,0 +1,60 @@
+//! Base58Check encoding, as used for transparent and Sprout addresses and
+//! keys.
+
+use crate::{Error, Result};
+
+/// The length of the checksum appended to a Base58Check payload.
+const CHECKSUM_LEN: usize = 4;
+
+/// Encodes `prefix || payload` followed by its checksum.
+pub(crate) fn encode(prefix: &[u8], payload: &[u8]) -> String {
+    let mut data = Vec::with_capacity(prefix.len() + payload.len() + CHECKSUM_LEN);
+    data.extend_from_slice(prefix);
+    data.extend_from_slice(payload);
+    let checksum = bc_crypto::hash::double_sha256(&data);
+    data.extend_from_slice(&checksum[..CHECKSUM_LEN]);
+    bs58::encode(data).into_string()
+}
+
+/// Decodes a Base58Check string and verifies its checksum, returning the
+/// version prefix and payload without the checksum.
+pub(crate) fn decode(s: &str) -> Result<Vec<u8>> {
+    let mut data = bs58::decode(s)
+        .into_vec()
+        .map_err(|e| Error::InvalidBase58Check {
+            reason: e.to_string(),
+        })?;
+    if data.len() < CHECKSUM_LEN {
+        return Err(Error::InvalidBase58Check {
+            reason: "too short".to_string(),
+        });
+    }
+    let checksum = data.split_off(data.len() - CHECKSUM_LEN);
+    if bc_crypto::hash::double_sha256(&data)[..CHECKSUM_LEN] != checksum[..] {
+        return Err(Error::InvalidBase58Check {
+            reason: "checksum mismatch".to_string(),
+        });
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+
+    #[test]
+    fn test_roundtrip() {
+        let encoded = encode(&[0x1c, 0xb8], &[7u8; 20]);
+        let decoded = decode(&encoded).unwrap();
+        assert_eq!(&decoded[..2], &[0x1c, 0xb8]);
+        assert_eq!(&decoded[2..], &[7u8; 20]);
+    }
+
+    #[test]
+    fn test_bad_checksum() {
+        let mut encoded = encode(&[0x1c, 0xb8], &[7u8; 20]);
+        let last = if encoded.ends_with('1') { "2" } else { "1" };
+        encoded.replace_range(encoded.len() - 1.., last);
+        assert!(decode(&encoded).is_err());
+    }
+}
@@ -1,24 +1,48 @@
 use bc_envelope::prelude::*;
+use zeroize::Zeroize;
 
-use crate::{MnemonicLanguage, NoQuotesDebugOption, SeedFingerprint};
+use std::borrow::Cow;
 
+use crate::{Error, MnemonicLanguage, Result, SeedFingerprint, detect_language};
+
+/// A BIP-39 mnemonic phrase, with the optional passphrase used alongside it.
+///
+/// The passphrase (sometimes called the "25th word") is as sensitive as the
+/// phrase itself: a wallet restored without it derives entirely different
+/// keys. An absent passphrase means the empty passphrase, as specified by
+/// BIP-39. Both are redacted from `Debug` output and zeroized on drop.
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bip39Mnemonic {
     mnemonic: String,
+    passphrase: Option<String>,
     language: Option<MnemonicLanguage>,
     fingerprint: Option<SeedFingerprint>,
 }
 
+impl Drop for Bip39Mnemonic {
+    fn drop(&mut self) {
+        self.mnemonic.zeroize();
+        self.passphrase.zeroize();
+    }
+}
+
+/// Prints the number of words, whether there is a passphrase, and the seed
+/// fingerprint, but never the words or the passphrase themselves.
 impl std::fmt::Debug for Bip39Mnemonic {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        f.debug_struct("MnemonicSeed")
-            .field("language", &NoQuotesDebugOption(&self.language))
-            .field("mnemonic", &"<elided>".to_string())
-            .field(
-                "fingerprint",
-                &NoQuotesDebugOption(&self.fingerprint.map(|f| f.to_hex())),
-            )
-            .finish()
+        write!(
+            f,
+            "Bip39Mnemonic(<{} words",
+            self.mnemonic.split_whitespace().count()
+        )?;
+        if self.passphrase.is_some() {
+            write!(f, ", with passphrase")?;
+        }
+        if let Some(fingerprint) = &self.fingerprint {
+            write!(f, ", fingerprint {}", fingerprint.short_hex())?;
+        }
+        write!(f, ">)")
     }
 }
 
@@ -29,6 +53,7 @@ impl Bip39Mnemonic {
     ) -> Self {
         Self {
             mnemonic: mnemonic.as_ref().to_string(),
+            passphrase: None,
             language,
             fingerprint: None,
         }
@@ -46,6 +71,42 @@ impl Bip39Mnemonic {
         self.mnemonic = mnemonic;
     }
 
+    /// Returns the BIP-39 passphrase, if one is used. `None` is equivalent to
+    /// the empty passphrase.
+    pub fn passphrase(&self) -> Option<&str> {
+        self.passphrase.as_deref()
+    }
+
+    pub fn set_passphrase(&mut self, passphrase: Option<String>) {
+        self.passphrase = passphrase;
+    }
+
+    /// Returns the 64-byte BIP-39 seed computed from the mnemonic and its
+    /// passphrase.
+    ///
+    /// Both are NFKD normalized first, as BIP-39 requires; this also turns the
+    /// ideographic spaces separating Japanese words into ASCII spaces.
+    pub fn to_seed(&self) -> Vec<u8> {
+        self.to_seed_with_passphrase(self.passphrase.as_deref().unwrap_or_default())
+    }
+
+    /// Returns the 64-byte BIP-39 seed computed from the mnemonic and the
+    /// given passphrase, ignoring the recorded one; see
+    /// [`Bip39Mnemonic::to_seed`].
+    pub fn to_seed_with_passphrase(&self, passphrase: &str) -> Vec<u8> {
+        let mut mnemonic = Cow::Borrowed(self.mnemonic.as_str());
+        bip39::Mnemonic::normalize_utf8_cow(&mut mnemonic);
+        let mut salt = Cow::Owned(format!("mnemonic{}", passphrase));
+        bip39::Mnemonic::normalize_utf8_cow(&mut salt);
+        let seed =
+            bc_crypto::hash::pbkdf2_hmac_sha512(mnemonic.as_bytes(), salt.as_bytes(), 2048, 64);
+        if let Cow::Owned(mut mnemonic) = mnemonic {
+            mnemonic.zeroize();
+        }
+        salt.to_mut().zeroize();
+        seed
+    }
+
     pub fn language(&self) -> Option<&MnemonicLanguage> {
         self.language.as_ref()
     }
@@ -57,12 +118,34 @@ impl Bip39Mnemonic {
     pub fn set_language(&mut self, language: MnemonicLanguage) {
         self.language = Some(language);
     }
+
+    /// Checks that the phrase is a valid BIP-39 mnemonic.
+    ///
+    /// The phrase must have 12, 15, 18, 21, or 24 words, every word must be in
+    /// the wordlist of the mnemonic's language, and the checksum must match.
+    /// If no language is recorded, it is detected from the words with
+    /// [`detect_language`].
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidMnemonic`] describing the first problem found,
+    /// or [`Error::UndetectedMnemonicLanguage`] if no language is recorded
+    /// and none can be detected.
+    pub fn validate(&self) -> Result<()> {
+        let language = match self.language {
+            Some(language) => language,
+            None => detect_language(&self.mnemonic)?,
+        };
+        bip39::Mnemonic::parse_in(language.into(), self.mnemonic.as_str())
+            .map(|_| ())
+            .map_err(Error::InvalidMnemonic)
+    }
 }
 
 impl From<Bip39Mnemonic> for Envelope {
-    fn from(value: Bip39Mnemonic) -> Self {
-        Envelope::new(value.mnemonic)
+    fn from(mut value: Bip39Mnemonic) -> Self {
+        Envelope::new(std::mem::take(&mut value.mnemonic))
             .add_type("Bip39Mnemonic")
+            .add_optional_assertion("passphrase", value.passphrase.take())
             .add_optional_assertion("language", value.language)
             .add_optional_assertion("fingerprint", value.fingerprint)
     }
@@ -74,11 +157,13 @@ impl TryFrom<Envelope> for Bip39Mnemonic {
     fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
         envelope.check_type("Bip39Mnemonic")?;
         let mnemonic = envelope.extract_subject()?;
+        let passphrase =
+            envelope.extract_optional_object_for_predicate("passphrase")?;
         let language =
             envelope.try_optional_object_for_predicate("language")?;
         let fingerprint =
             envelope.try_optional_object_for_predicate("fingerprint")?;
-        Ok(Self { mnemonic, language, fingerprint })
+        Ok(Self { mnemonic, passphrase, language, fingerprint })
     }
 }
 
@@ -92,6 +177,7 @@ mod tests {
         fn random() -> Self {
             Self {
                 mnemonic: String::random(),
+                passphrase: String::opt_random(),
                 language: MnemonicLanguage::opt_random(),
                 fingerprint: SeedFingerprint::opt_random(),
             }
@@ -99,4 +185,56 @@ mod tests {
     }
 
     test_envelope_roundtrip!(Bip39Mnemonic);
+
+    #[test]
+    fn test_japanese_seed() {
+        // The first Japanese test vector from
+        // https://github.com/bip32JP/bip32JP.github.io/blob/master/test_JP_BIP39.json,
+        // whose mnemonic is separated by ideographic spaces and whose
+        // passphrase needs NFKD normalization.
+        let mnemonic = "あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あおぞら";
+        let mut mnemonic = Bip39Mnemonic::new(mnemonic, Some(MnemonicLanguage::Japanese));
+        mnemonic.set_passphrase(Some("㍍ガバヴァぱばぐゞちぢ十人十色".to_string()));
+        assert_eq!(
+            hex::encode(mnemonic.to_seed()),
+            "a262d6fb6122ecf45be09c50492b31f92e9beb7d9a845987a02cefda57a15f9c467a17872029a9e92299b5cbdf306e3a0ee620245cbd508959b6cb7ca637bd55"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        assert!(Bip39Mnemonic::new(valid, None).validate().is_ok());
+        assert!(
+            Bip39Mnemonic::new(valid, Some(MnemonicLanguage::English))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            Bip39Mnemonic::new(valid, Some(MnemonicLanguage::Spanish))
+                .validate()
+                .is_err()
+        );
+
+        // Bad checksum
+        let invalid = "legal winner thank year wave sausage worth useful legal winner thank thank";
+        assert!(Bip39Mnemonic::new(invalid, None).validate().is_err());
+
+        // Wrong word count
+        let invalid = "legal winner thank year wave sausage worth useful legal winner thank";
+        assert!(Bip39Mnemonic::new(invalid, None).validate().is_err());
+
+        // Japanese, whose words are separated by ideographic spaces
+        let japanese = "あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あおぞら";
+        assert!(Bip39Mnemonic::new(japanese, None).validate().is_ok());
+        assert!(
+            Bip39Mnemonic::new(japanese, Some(MnemonicLanguage::Japanese))
+                .validate()
+                .is_ok()
+        );
+
+        // Word not in the wordlist
+        let invalid = "legal winner thank year wave sausage worth useful legal winner thank zcash";
+        assert!(Bip39Mnemonic::new(invalid, None).validate().is_err());
+    }
 }
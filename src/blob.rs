@@ -370,4 +370,22 @@ mod tests {
 
     test_cbor_roundtrip!(Blob32);
     test_envelope_roundtrip!(Blob32);
+
+    #[test]
+    fn test_length_must_match() {
+        use bc_envelope::prelude::*;
+
+        use crate::sapling::SaplingExtendedSpendingKey;
+
+        assert!(Blob::<96>::try_from(CBOR::to_byte_string([0u8; 100])).is_err());
+        assert!(Blob::<96>::try_from(CBOR::to_byte_string([0u8; 95])).is_err());
+        assert!(Blob::<96>::try_from(CBOR::to_byte_string([0u8; 96])).is_ok());
+
+        let key = SaplingExtendedSpendingKey::new([7u8; 169]);
+        let mut bytes = key.to_vec();
+        bytes.push(0);
+        let envelope =
+            Envelope::new(CBOR::to_byte_string(bytes)).add_type("SaplingExtendedSpendingKey");
+        assert!(SaplingExtendedSpendingKey::try_from(envelope).is_err());
+    }
 }
@@ -21,12 +21,42 @@
 /// `Debug`, `Clone`, and various conversion traits to and from byte
 /// collections.
 ///
+/// Types holding secret key material should pass `secret` as a fourth
+/// argument, so that their `Debug` output does not reveal the bytes:
+///
+/// ```
+/// # use zewif::blob;
+/// #
+/// blob!(SpendingKey, 32, "A spending key", secret);
+/// assert_eq!(format!("{:?}", SpendingKey::new([7u8; 32])), "SpendingKey(<redacted>)");
+/// ```
+///
 /// The macro adds type safety and domain-specific semantics to otherwise
 /// generic byte array data, particularly for cryptographic values used in the
 /// Zcash protocol.
 #[macro_export]
 macro_rules! blob {
     ($name:ident, $size:expr, $doc:expr) => {
+        $crate::blob!(@impl $name, $size, $doc);
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{}({})", stringify!($name), hex::encode(self.0))
+            }
+        }
+    };
+
+    ($name:ident, $size:expr, $doc:expr, secret) => {
+        $crate::blob!(@impl $name, $size, $doc);
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{}(<redacted>)", stringify!($name))
+            }
+        }
+    };
+
+    (@impl $name:ident, $size:expr, $doc:expr) => {
         #[doc = $doc]
         pub struct $name([u8; $size]);
 
@@ -122,17 +152,11 @@ macro_rules! blob {
             }
         }
 
+        #[allow(clippy::non_canonical_clone_impl)]
         impl Clone for $name {
-            #[allow(clippy::non_canonical_clone_impl)]
             fn clone(&self) -> Self { Self(self.0.clone()) }
         }
 
-        impl std::fmt::Debug for $name {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-                write!(f, "{}({})", stringify!($name), hex::encode(self.0))
-            }
-        }
-
         impl AsRef<[u8]> for $name {
             fn as_ref(&self) -> &[u8] { &self.0[..] }
         }
@@ -5,7 +5,7 @@ use std::{
     io::{self, Read, Write},
 };
 
-/// A transaction identifier (BlockHash) represented as a 32-byte hash.
+/// A block identifier (BlockHash) represented as a 32-byte hash.
 ///
 /// `BlockHash` is a specialized wrapper around a 32-byte array representing a block's
 /// unique identifier in the Zcash blockchain.
@@ -45,12 +45,23 @@ use std::ops::{Add, Sub};
 /// ```
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct BlockHeight(u32);
 
 /// The height of the genesis block on a network.
-pub const H0: BlockHeight = BlockHeight(0);
+pub const H0: BlockHeight = BlockHeight::GENESIS;
 
 impl BlockHeight {
+    /// The height of the genesis block.
+    pub const GENESIS: BlockHeight = BlockHeight(0);
+
+    /// The greatest representable height.
+    pub const MAX: BlockHeight = BlockHeight(u32::MAX);
+
     /// Creates a new `BlockHeight` from a u32 value.
     ///
     /// This constructor is a `const fn`, which allows it to be used in constant expressions.
@@ -87,6 +98,35 @@ impl BlockHeight {
     pub fn saturating_sub(self, v: u32) -> BlockHeight {
         BlockHeight(self.0.saturating_sub(v))
     }
+
+    /// Adds the provided number of blocks to this height, returning `None` if
+    /// the result would exceed [`BlockHeight::MAX`].
+    ///
+    /// Unlike the `+` operator, which saturates, this lets callers detect
+    /// heights that have run off the end of the representable range.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::BlockHeight;
+    /// assert_eq!(BlockHeight::from(100u32).checked_add(50), Some(BlockHeight::from(150u32)));
+    /// assert_eq!(BlockHeight::MAX.checked_add(1), None);
+    /// ```
+    pub fn checked_add(self, v: u32) -> Option<BlockHeight> {
+        self.0.checked_add(v).map(BlockHeight)
+    }
+
+    /// Subtracts the provided number of blocks from this height, returning
+    /// `None` if the result would precede the genesis block.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::BlockHeight;
+    /// assert_eq!(BlockHeight::from(100u32).checked_sub(50), Some(BlockHeight::from(50u32)));
+    /// assert_eq!(BlockHeight::GENESIS.checked_sub(1), None);
+    /// ```
+    pub fn checked_sub(self, v: u32) -> Option<BlockHeight> {
+        self.0.checked_sub(v).map(BlockHeight)
+    }
 }
 
 /// Displays the block height as a plain number
@@ -207,8 +247,16 @@ impl From<&BlockHeight> for CBOR {
 impl TryFrom<CBOR> for BlockHeight {
     type Error = dcbor::Error;
 
+    /// Rejects negative integers, which dcbor would otherwise wrap into the
+    /// `u32` range.
     fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
-        Ok(BlockHeight::from(u32::try_from(cbor)?))
+        match cbor.into_case() {
+            CBORCase::Unsigned(n) => u32::try_from(n)
+                .map(BlockHeight)
+                .map_err(|_| dcbor::Error::OutOfRange),
+            CBORCase::Negative(_) => Err(dcbor::Error::OutOfRange),
+            _ => Err(dcbor::Error::WrongType),
+        }
     }
 }
 
@@ -235,11 +283,38 @@ mod tests {
     impl crate::RandomInstance for BlockHeight {
         fn random() -> Self {
             let mut rng = bc_rand::thread_rng();
-            let value = rand::Rng::gen_range(&mut rng, 0..u32::MAX);
+            let value = rand::Rng::random_range(&mut rng, 0..u32::MAX);
             Self(value)
         }
     }
 
     test_cbor_roundtrip!(BlockHeight);
     test_envelope_roundtrip!(BlockHeight);
+
+    #[test]
+    fn test_out_of_range() {
+        use bc_envelope::prelude::*;
+
+        let too_high = u64::from(u32::MAX) + 1;
+        assert!(BlockHeight::try_from(CBOR::from(too_high)).is_err());
+        assert!(BlockHeight::try_from(Envelope::new(too_high)).is_err());
+        assert!(BlockHeight::try_from(CBOR::from(-1)).is_err());
+        assert!(BlockHeight::try_from(too_high).is_err());
+        assert_eq!(
+            BlockHeight::try_from(CBOR::from(u32::MAX)).unwrap(),
+            BlockHeight::MAX
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let height = BlockHeight::from(1_000u32);
+        assert_eq!(height + 10, BlockHeight::from(1_010u32));
+        assert_eq!(BlockHeight::MAX + 1, BlockHeight::MAX);
+        assert_eq!(height - BlockHeight::from(400u32), 600);
+        assert_eq!(height.saturating_sub(2_000), BlockHeight::GENESIS);
+        assert_eq!(height.checked_sub(2_000), None);
+        assert!(BlockHeight::GENESIS < height && height < BlockHeight::MAX);
+        assert_eq!(height.to_string(), "1000");
+    }
 }
@@ -0,0 +1,154 @@
+use std::fmt;
+
+use bc_envelope::prelude::*;
+
+use crate::NetworkUpgrade;
+
+/// The consensus branch ID of a network upgrade, identifying the consensus
+/// rules a transaction was signed under.
+///
+/// # Zcash Concept Relation
+/// Since Overwinter, signatures commit to the consensus branch ID of the
+/// upgrade in force where the transaction is to be mined (ZIP 200), so a
+/// transaction signed for one branch is invalid on another. Version 5
+/// transactions also carry the ID in their encoding, and commit to it in
+/// their transaction ID (ZIP 244). IDs of upgrades unknown to this version
+/// of the format are preserved as [`BranchId::Unknown`].
+///
+/// # Examples
+/// ```
+/// # use zewif::{BranchId, NetworkUpgrade};
+/// let branch = BranchId::from(0xC2D6_D0B4);
+/// assert_eq!(branch, BranchId::Nu5);
+/// assert_eq!(branch.upgrade(), Some(NetworkUpgrade::Nu5));
+/// assert_eq!(u32::from(BranchId::from(0x1234_5678)), 0x1234_5678);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum BranchId {
+    Sprout,
+    Overwinter,
+    Sapling,
+    Blossom,
+    Heartwood,
+    Canopy,
+    Nu5,
+    Nu6,
+    Unknown(u32),
+}
+
+impl BranchId {
+    /// The branches named by this version of the format, in order of
+    /// activation.
+    pub const NAMED: [BranchId; 8] = [
+        BranchId::Sprout,
+        BranchId::Overwinter,
+        BranchId::Sapling,
+        BranchId::Blossom,
+        BranchId::Heartwood,
+        BranchId::Canopy,
+        BranchId::Nu5,
+        BranchId::Nu6,
+    ];
+
+    /// Returns the network upgrade that activated the branch, if it is one
+    /// described by [`NetworkUpgrade`].
+    pub fn upgrade(&self) -> Option<NetworkUpgrade> {
+        match self {
+            BranchId::Overwinter => Some(NetworkUpgrade::Overwinter),
+            BranchId::Sapling => Some(NetworkUpgrade::Sapling),
+            BranchId::Blossom => Some(NetworkUpgrade::Blossom),
+            BranchId::Heartwood => Some(NetworkUpgrade::Heartwood),
+            BranchId::Canopy => Some(NetworkUpgrade::Canopy),
+            BranchId::Nu5 => Some(NetworkUpgrade::Nu5),
+            BranchId::Sprout | BranchId::Nu6 | BranchId::Unknown(_) => None,
+        }
+    }
+}
+
+impl From<u32> for BranchId {
+    fn from(value: u32) -> Self {
+        BranchId::NAMED
+            .into_iter()
+            .find(|branch| u32::from(*branch) == value)
+            .unwrap_or(BranchId::Unknown(value))
+    }
+}
+
+impl From<BranchId> for u32 {
+    fn from(value: BranchId) -> Self {
+        match value {
+            BranchId::Sprout => 0,
+            BranchId::Overwinter => 0x5BA8_1B19,
+            BranchId::Sapling => 0x76B8_09BB,
+            BranchId::Blossom => 0x2BB4_0E60,
+            BranchId::Heartwood => 0xF5B9_230B,
+            BranchId::Canopy => 0xE9FF_75A6,
+            BranchId::Nu5 => 0xC2D6_D0B4,
+            BranchId::Nu6 => 0xC8E7_1055,
+            BranchId::Unknown(id) => id,
+        }
+    }
+}
+
+impl fmt::Display for BranchId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BranchId::Unknown(id) => write!(f, "{:#010x}", id),
+            named => write!(f, "{:?} ({:#010x})", named, u32::from(*named)),
+        }
+    }
+}
+
+/// Encodes the branch as its numeric ID, so that branches unknown to a
+/// reader decode as [`BranchId::Unknown`].
+impl From<BranchId> for CBOR {
+    fn from(value: BranchId) -> Self {
+        u32::from(value).into()
+    }
+}
+
+impl TryFrom<CBOR> for BranchId {
+    type Error = dcbor::Error;
+
+    fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
+        Ok(BranchId::from(u32::try_from(cbor)?))
+    }
+}
+
+impl From<BranchId> for Envelope {
+    fn from(value: BranchId) -> Self {
+        Envelope::new(CBOR::from(value))
+    }
+}
+
+impl TryFrom<Envelope> for BranchId {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.extract_subject()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cbor_roundtrip, test_envelope_roundtrip};
+
+    use super::BranchId;
+
+    impl crate::RandomInstance for BranchId {
+        fn random() -> Self {
+            BranchId::from(u32::random())
+        }
+    }
+
+    test_cbor_roundtrip!(BranchId);
+    test_envelope_roundtrip!(BranchId);
+
+    #[test]
+    fn test_named_ids_roundtrip() {
+        for branch in BranchId::NAMED {
+            assert_eq!(BranchId::from(u32::from(branch)), branch);
+        }
+    }
+}
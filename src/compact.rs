@@ -0,0 +1,349 @@
+use std::{collections::HashSet, fmt};
+
+use bc_envelope::prelude::*;
+
+use crate::Zewif;
+
+/// Selects what [`Zewif::compact`] removes.
+///
+/// By default, spent notes, the witnesses of spent notes, and the serialized
+/// form of transactions that created no unspent note are removed, and the
+/// transactions themselves are kept for history display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactOptions {
+    spent_notes: bool,
+    spent_witnesses: bool,
+    transaction_data: bool,
+    keep_history: bool,
+}
+
+impl Default for CompactOptions {
+    fn default() -> Self {
+        Self {
+            spent_notes: true,
+            spent_witnesses: true,
+            transaction_data: true,
+            keep_history: true,
+        }
+    }
+}
+
+impl CompactOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spent_notes(&self) -> bool {
+        self.spent_notes
+    }
+
+    /// Selects whether received notes that have been spent are removed.
+    pub fn set_spent_notes(&mut self, spent_notes: bool) {
+        self.spent_notes = spent_notes;
+    }
+
+    pub fn spent_witnesses(&self) -> bool {
+        self.spent_witnesses
+    }
+
+    /// Selects whether the witnesses of spent notes are removed, when the
+    /// notes themselves are kept.
+    pub fn set_spent_witnesses(&mut self, spent_witnesses: bool) {
+        self.spent_witnesses = spent_witnesses;
+    }
+
+    pub fn transaction_data(&self) -> bool {
+        self.transaction_data
+    }
+
+    /// Selects whether the serialized form of each transaction, with its
+    /// proofs, is removed unless the transaction created an unspent note.
+    pub fn set_transaction_data(&mut self, transaction_data: bool) {
+        self.transaction_data = transaction_data;
+    }
+
+    pub fn keep_history(&self) -> bool {
+        self.keep_history
+    }
+
+    /// Selects whether transactions are kept for history display. If not,
+    /// every transaction not involving a remaining note is removed, along
+    /// with the accounts' references to it.
+    pub fn set_keep_history(&mut self, keep_history: bool) {
+        self.keep_history = keep_history;
+    }
+}
+
+/// What [`Zewif::compact`] removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactReport {
+    spent_notes_removed: usize,
+    witnesses_removed: usize,
+    transaction_data_removed: usize,
+    transactions_removed: usize,
+    size_before: usize,
+    size_after: usize,
+}
+
+impl CompactReport {
+    pub fn spent_notes_removed(&self) -> usize {
+        self.spent_notes_removed
+    }
+
+    /// The number of witnesses removed from spent notes that were kept.
+    pub fn witnesses_removed(&self) -> usize {
+        self.witnesses_removed
+    }
+
+    /// The number of transactions whose serialized form was removed.
+    pub fn transaction_data_removed(&self) -> usize {
+        self.transaction_data_removed
+    }
+
+    pub fn transactions_removed(&self) -> usize {
+        self.transactions_removed
+    }
+
+    /// The size in bytes of the uncompressed envelope encoding before
+    /// compaction.
+    pub fn size_before(&self) -> usize {
+        self.size_before
+    }
+
+    /// The size in bytes of the uncompressed envelope encoding after
+    /// compaction. A compressed export shrinks by a similar proportion.
+    pub fn size_after(&self) -> usize {
+        self.size_after
+    }
+
+    pub fn size_reduction(&self) -> usize {
+        self.size_before.saturating_sub(self.size_after)
+    }
+}
+
+impl fmt::Display for CompactReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "removed {} spent notes, {} witnesses, the data of {} transactions, and {} transactions; \
+             {} bytes reduced to {}",
+            self.spent_notes_removed,
+            self.witnesses_removed,
+            self.transaction_data_removed,
+            self.transactions_removed,
+            self.size_before,
+            self.size_after
+        )
+    }
+}
+
+impl Zewif {
+    /// Returns a copy of the Zewif without the data not needed to keep
+    /// spending its funds, as selected by `options`, and a report of what was
+    /// removed.
+    ///
+    /// Unspent notes, keys, seeds, addresses, and sent outputs are never
+    /// removed, so the balances of the copy are those of the original. A note
+    /// is considered spent once its spending transaction is recorded, so
+    /// spends should first be linked with [`Zewif::link_spent_notes`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{BlockHeight, CompactOptions, Zewif};
+    /// let zewif = Zewif::new(BlockHeight::from(2_000_000u32));
+    /// let (compacted, report) = zewif.compact(CompactOptions::new());
+    /// assert_eq!(compacted.total_balance()?, zewif.total_balance()?);
+    /// assert_eq!(report.spent_notes_removed(), 0);
+    /// # Ok::<(), zewif::Error>(())
+    /// ```
+    pub fn compact(&self, options: CompactOptions) -> (Zewif, CompactReport) {
+        let mut compacted = self.clone();
+        let mut report = CompactReport {
+            size_before: encoded_size(self),
+            ..CompactReport::default()
+        };
+
+        for account in compacted
+            .wallets_mut()
+            .iter_mut()
+            .flat_map(|wallet| wallet.accounts_mut())
+        {
+            if options.spent_notes {
+                report.spent_notes_removed += account.remove_spent_notes();
+            } else if options.spent_witnesses {
+                report.witnesses_removed += account.remove_spent_witnesses();
+            }
+        }
+
+        let accounts = || {
+            compacted
+                .wallets()
+                .iter()
+                .flat_map(|wallet| wallet.accounts())
+        };
+        let unspent: HashSet<_> = accounts()
+            .flat_map(|account| account.unspent_note_transactions())
+            .collect();
+        let noted: HashSet<_> = accounts()
+            .flat_map(|account| account.note_transactions())
+            .collect();
+
+        if !options.keep_history {
+            let removed: Vec<_> = compacted
+                .transactions()
+                .keys()
+                .copied()
+                .filter(|txid| !noted.contains(txid))
+                .collect();
+            for account in compacted
+                .wallets_mut()
+                .iter_mut()
+                .flat_map(|wallet| wallet.accounts_mut())
+            {
+                for txid in &removed {
+                    account.remove_relevant_transaction(*txid);
+                }
+            }
+            for txid in &removed {
+                compacted.remove_transaction(txid);
+            }
+            report.transactions_removed = removed.len();
+        }
+
+        if options.transaction_data {
+            let stripped: Vec<_> = compacted
+                .transactions()
+                .values()
+                .filter(|transaction| {
+                    transaction.raw().is_some() && !unspent.contains(&transaction.txid())
+                })
+                .map(|transaction| transaction.txid())
+                .collect();
+            for txid in stripped {
+                let mut transaction = compacted
+                    .remove_transaction(&txid)
+                    .expect("transaction is present");
+                transaction.remove_raw();
+                compacted.replace_transaction(txid, transaction);
+                report.transaction_data_removed += 1;
+            }
+        }
+
+        report.size_after = encoded_size(&compacted);
+        (compacted, report)
+    }
+}
+
+fn encoded_size(zewif: &Zewif) -> usize {
+    Envelope::from(zewif.clone()).to_cbor_data().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Account, Amount, Blob, BlockHeight, Data, Network, RandomInstance, Transaction, TxId,
+        Zewif, ZewifWallet,
+        sapling::{self, SaplingReceivedNote, SaplingWitness},
+    };
+
+    use super::CompactOptions;
+
+    fn txid(byte: u8) -> TxId {
+        TxId::from_bytes([byte; 32])
+    }
+
+    fn note(received_in: u8, value: u64, spent_in: Option<u8>) -> SaplingReceivedNote {
+        let note = sapling::Note::from_parts(
+            Blob::default(),
+            Amount::from_u64(value).unwrap(),
+            Blob::default(),
+        )
+        .unwrap();
+        let mut note = SaplingReceivedNote::new(txid(received_in), 0, note);
+        note.set_witness(Some(SaplingWitness::random()));
+        note.set_spent_in(spent_in.map(txid));
+        note
+    }
+
+    fn zewif() -> Zewif {
+        let mut account = Account::new();
+        account.add_sapling_received_note(note(1, 50_000, Some(2)));
+        account.add_sapling_received_note(note(2, 30_000, None));
+        account.add_sapling_received_note(note(3, 20_000, None));
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        for byte in 1..=4 {
+            account.add_relevant_transaction(txid(byte));
+            let mut transaction = Transaction::new(txid(byte));
+            transaction.set_raw(Data::from_slice(&[byte; 2_000]));
+            zewif.add_transaction(txid(byte), transaction).unwrap();
+        }
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+        zewif.add_wallet(wallet);
+        zewif
+    }
+
+    #[test]
+    fn test_compact_keeps_balances() {
+        let zewif = zewif();
+        let (compacted, report) = zewif.compact(CompactOptions::new());
+        assert_eq!(
+            compacted.total_balance().unwrap(),
+            zewif.total_balance().unwrap()
+        );
+
+        let account = &compacted.wallets()[0].accounts()[0];
+        assert_eq!(account.sapling_received_notes_len(), 2);
+        assert!(
+            account
+                .sapling_received_notes()
+                .iter()
+                .all(|note| note.witness().is_some())
+        );
+        assert_eq!(report.spent_notes_removed(), 1);
+        // Transactions 1 and 4 created no unspent note.
+        assert_eq!(report.transaction_data_removed(), 2);
+        assert!(compacted.transaction(&txid(1)).unwrap().raw().is_none());
+        assert!(compacted.transaction(&txid(2)).unwrap().raw().is_some());
+        assert_eq!(report.transactions_removed(), 0);
+        assert!(report.size_after() < report.size_before());
+
+        let mut options = CompactOptions::new();
+        options.set_keep_history(false);
+        let (compacted, report) = zewif.compact(options);
+        assert_eq!(
+            compacted.total_balance().unwrap(),
+            zewif.total_balance().unwrap()
+        );
+        // Transaction 1 created only the removed note; transaction 2 spent it
+        // but also created an unspent note.
+        assert_eq!(report.transactions_removed(), 2);
+        assert!(compacted.transaction(&txid(1)).is_none());
+        assert!(compacted.transaction(&txid(4)).is_none());
+        let account = &compacted.wallets()[0].accounts()[0];
+        assert_eq!(
+            account.relevant_transactions().copied().collect::<Vec<_>>(),
+            [txid(2), txid(3)]
+        );
+    }
+
+    #[test]
+    fn test_compact_witnesses_only() {
+        let zewif = zewif();
+        let mut options = CompactOptions::new();
+        options.set_spent_notes(false);
+        options.set_transaction_data(false);
+        let (compacted, report) = zewif.compact(options);
+        assert_eq!(
+            compacted.total_balance().unwrap(),
+            zewif.total_balance().unwrap()
+        );
+
+        let notes = compacted.wallets()[0].accounts()[0].sapling_received_notes();
+        assert_eq!(notes.len(), 3);
+        assert!(notes[0].witness().is_none());
+        assert!(notes[1].witness().is_some() && notes[2].witness().is_some());
+        assert_eq!(report.witnesses_removed(), 1);
+        assert_eq!(report.transaction_data_removed(), 0);
+    }
+}
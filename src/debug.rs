@@ -0,0 +1,327 @@
+//! Tools for inspecting envelopes that fail to decode.
+
+use std::fmt::Write;
+
+use bc_components::tags::TAG_LEAF;
+use bc_envelope::{KNOWN_VALUES, prelude::*};
+
+/// Renders `envelope` in CBOR diagnostic notation, annotated with the names of
+/// the CBOR tags it uses and of its known-value predicates, such as `isA`, and
+/// marking the digests of elided elements.
+///
+/// Arrays, maps, and tagged values nested more than `max_depth` levels deep
+/// are abbreviated to `[...]`, `{...}`, or `tag(...)`, and byte strings
+/// longer than `redact_byte_strings_over` bytes are written as `h'...'`, with
+/// a comment giving their length.
+///
+/// # Examples
+/// ```
+/// # use bc_envelope::prelude::*;
+/// let envelope = Envelope::new("Alice").add_type("Person");
+/// assert_eq!(
+///     zewif::debug::diagnostic(&envelope, None, None),
+///     r#"200(   / envelope /
+///     [
+///         201("Alice"),   / leaf /
+///         {
+///             1:   / isA /
+///             201("Person")   / leaf /
+///         }
+///     ]
+/// )"#
+/// );
+/// ```
+pub fn diagnostic(
+    envelope: &Envelope,
+    max_depth: Option<usize>,
+    redact_byte_strings_over: Option<usize>,
+) -> String {
+    let mut tags = TagsStore::default();
+    bc_components::register_tags_in(&mut tags);
+    let renderer = Renderer {
+        tags,
+        max_depth,
+        redact_byte_strings_over,
+    };
+    let mut out = String::new();
+    renderer.item(&envelope.to_cbor(), 0, false, "", &mut out);
+    out.truncate(out.trim_end().len());
+    out
+}
+
+struct Renderer {
+    tags: TagsStore,
+    max_depth: Option<usize>,
+    redact_byte_strings_over: Option<usize>,
+}
+
+impl Renderer {
+    /// Writes `cbor` as lines indented to `level`, followed by `separator`.
+    /// Outside a leaf, unsigned integers are known values and byte strings
+    /// are the digests of elided elements.
+    fn item(&self, cbor: &CBOR, level: usize, in_leaf: bool, separator: &str, out: &mut String) {
+        let indent = "    ".repeat(level);
+        let elided = self.max_depth.is_some_and(|max_depth| level >= max_depth);
+        match cbor.as_case() {
+            CBORCase::Array(items) if items.is_empty() => line(out, &indent, "[]", separator, None),
+            CBORCase::Array(items) if elided => {
+                line(
+                    out,
+                    &indent,
+                    "[...]",
+                    separator,
+                    Some(count(items.len(), "item", "items")),
+                );
+            }
+            CBORCase::Array(items) => {
+                line(out, &indent, "[", "", None);
+                for (i, item) in items.iter().enumerate() {
+                    self.item(
+                        item,
+                        level + 1,
+                        in_leaf,
+                        if i + 1 < items.len() { "," } else { "" },
+                        out,
+                    );
+                }
+                line(out, &indent, "]", separator, None);
+            }
+            CBORCase::Map(map) if map.is_empty() => line(out, &indent, "{}", separator, None),
+            CBORCase::Map(map) if elided => {
+                line(
+                    out,
+                    &indent,
+                    "{...}",
+                    separator,
+                    Some(count(map.len(), "entry", "entries")),
+                );
+            }
+            CBORCase::Map(map) => {
+                line(out, &indent, "{", "", None);
+                let len = map.len();
+                for (i, (key, value)) in map.iter().enumerate() {
+                    self.item(key, level + 1, in_leaf, ":", out);
+                    self.item(
+                        value,
+                        level + 1,
+                        in_leaf,
+                        if i + 1 < len { "," } else { "" },
+                        out,
+                    );
+                }
+                line(out, &indent, "}", separator, None);
+            }
+            CBORCase::Tagged(tag, content) => {
+                let name = self.tags.assigned_name_for_tag(tag);
+                let in_leaf = in_leaf || tag.value() == TAG_LEAF;
+                if let Some((text, comment)) = self.scalar(content, in_leaf) {
+                    let comment = [name, comment]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let text = format!("{}({})", tag.value(), text);
+                    line(
+                        out,
+                        &indent,
+                        &text,
+                        separator,
+                        Some(comment).filter(|c| !c.is_empty()),
+                    );
+                } else if elided {
+                    line(
+                        out,
+                        &indent,
+                        &format!("{}(...)", tag.value()),
+                        separator,
+                        name,
+                    );
+                } else {
+                    line(out, &indent, &format!("{}(", tag.value()), "", name);
+                    self.item(content, level + 1, in_leaf, "", out);
+                    line(out, &indent, ")", separator, None);
+                }
+            }
+            _ => {
+                let (text, comment) = self.scalar(cbor, in_leaf).expect("not a container");
+                line(out, &indent, &text, separator, comment);
+            }
+        }
+    }
+
+    /// Returns the text of a value other than an array, map, or tagged value,
+    /// and its comment, if any.
+    fn scalar(&self, cbor: &CBOR, in_leaf: bool) -> Option<(String, Option<String>)> {
+        match cbor.as_case() {
+            CBORCase::Array(_) | CBORCase::Map(_) | CBORCase::Tagged(..) => None,
+            CBORCase::ByteString(bytes)
+                if self
+                    .redact_byte_strings_over
+                    .is_some_and(|max| bytes.len() > max) =>
+            {
+                Some((
+                    "h'...'".to_string(),
+                    Some(count(bytes.len(), "byte", "bytes")),
+                ))
+            }
+            CBORCase::Unsigned(value) if !in_leaf => {
+                let known_value = KnownValue::new(*value);
+                let name = KNOWN_VALUES
+                    .get()
+                    .as_ref()
+                    .and_then(|store| store.assigned_name(&known_value).map(str::to_string));
+                Some((cbor.to_string(), name))
+            }
+            CBORCase::ByteString(_) if !in_leaf => {
+                Some((cbor.to_string(), Some("elided".to_string())))
+            }
+            _ => Some((cbor.to_string(), None)),
+        }
+    }
+}
+
+fn count(n: usize, singular: &str, plural: &str) -> String {
+    format!("{} {}", n, if n == 1 { singular } else { plural })
+}
+
+fn line(out: &mut String, indent: &str, text: &str, separator: &str, comment: Option<String>) {
+    write!(out, "{}{}{}", indent, text, separator).unwrap();
+    if let Some(comment) = comment {
+        write!(out, "   / {} /", comment).unwrap();
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, Address, BlockHeight, Error, Network, ProtocolAddress, Zewif, ZewifWallet,
+        transparent::{self, TransparentSpendAuthority, TransparentSpendingKey},
+    };
+
+    use super::diagnostic;
+
+    fn wallet() -> Envelope {
+        let mut address = transparent::Address::new("t1debug");
+        address.set_spend_authority(TransparentSpendAuthority::SpendingKey(
+            TransparentSpendingKey::new([0xa4; 32]),
+        ));
+        let mut address = Address::new(ProtocolAddress::Transparent(address));
+        address.set_name("change".to_string());
+        let mut account = Account::new();
+        account.set_name("Savings");
+        account.add_address(address);
+        // Decoding without the random identifier derives a fixed one, which
+        // keeps the order of the account's assertions stable.
+        let envelope = Envelope::from(account.clone())
+            .remove_assertion(Envelope::new_assertion("id", account.id()));
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(Account::try_from(envelope).unwrap());
+        Envelope::from(wallet)
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let expected = r#"200(   / envelope /
+    [
+        201(0),   / leaf /
+        {
+            201("network"):   / leaf /
+            201(0)   / leaf /
+        },
+        {
+            1:   / isA /
+            201("ZewifWallet")   / leaf /
+        },
+        {
+            201("account"):   / leaf /
+            [
+                201(0),   / leaf /
+                {...},   / 1 entry /
+                {...},   / 1 entry /
+                {...},   / 1 entry /
+                {...},   / 1 entry /
+                {...},   / 1 entry /
+                {...}   / 1 entry /
+            ]
+        }
+    ]
+)"#;
+        assert_eq!(diagnostic(&wallet(), Some(4), None), expected);
+    }
+
+    #[test]
+    fn test_diagnostic_redacts_byte_strings() {
+        let key = Envelope::from(TransparentSpendingKey::new([0xa4; 32]));
+        let expected = r#"200(   / envelope /
+    [
+        201(h'...'),   / leaf, 32 bytes /
+        {
+            1:   / isA /
+            201("TransparentSpendingKey")   / leaf /
+        }
+    ]
+)"#;
+        assert_eq!(diagnostic(&key, None, Some(16)), expected);
+        assert!(diagnostic(&key, None, Some(32)).contains(&"a4".repeat(32)));
+    }
+
+    #[test]
+    fn test_decode_error_diagnostic() {
+        let envelope = Envelope::from(Zewif::new(BlockHeight::from(100))).add_assertion(
+            "wallet",
+            wallet().add_assertion("account", Envelope::new("not an account")),
+        );
+
+        let error = Zewif::try_from(envelope.clone()).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::EnvelopeDecode {
+                diagnostic: None,
+                ..
+            }
+        ));
+
+        let error = Zewif::from_envelope_with_diagnostics(envelope).unwrap_err();
+        let Error::EnvelopeDecode {
+            diagnostic: Some(diagnostic),
+            ..
+        } = &error
+        else {
+            panic!("unexpected error: {}", error);
+        };
+        assert!(diagnostic.contains(r#"201("not an account")"#));
+        assert!(error.to_string().ends_with(diagnostic.as_str()));
+    }
+
+    #[test]
+    fn test_decode_error_diagnostic_elides_secrets() {
+        let mut address = transparent::Address::new("t1debug");
+        address.set_spend_authority(TransparentSpendAuthority::SpendingKey(
+            TransparentSpendingKey::new([0xa4; 32]),
+        ));
+        let address = Envelope::from(Address::new(ProtocolAddress::Transparent(address)));
+        let name = address.assertion_with_predicate("name").unwrap();
+        let address = address.remove_assertion(name).add_assertion("name", 42);
+        let account = Envelope::from(Account::new()).add_assertion("address", address);
+        let wallet =
+            Envelope::from(ZewifWallet::new(Network::Main)).add_assertion("account", account);
+        let envelope =
+            Envelope::from(Zewif::new(BlockHeight::from(100))).add_assertion("wallet", wallet);
+
+        let error = Zewif::from_envelope_with_diagnostics(envelope).unwrap_err();
+        let Error::EnvelopeDecode {
+            diagnostic: Some(diagnostic),
+            ..
+        } = &error
+        else {
+            panic!("unexpected error: {}", error);
+        };
+        assert!(diagnostic.contains(r#"201("t1debug")"#));
+        assert!(diagnostic.contains("201(42)"));
+        assert!(diagnostic.contains("/ elided /"));
+        assert!(!diagnostic.contains("a4a4"));
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+
+use crate::Error;
+
+/// An element that was skipped because it could not be decoded; see
+/// [`Zewif::from_envelope_lenient`](crate::Zewif::from_envelope_lenient).
+#[derive(Debug)]
+pub struct DecodeIssue {
+    path: Vec<String>,
+    predicate: String,
+    position: Option<usize>,
+    type_tag: Option<String>,
+    error: Error,
+}
+
+impl DecodeIssue {
+    pub fn new(
+        path: Vec<String>,
+        predicate: impl Into<String>,
+        position: Option<usize>,
+        type_tag: Option<String>,
+        error: Error,
+    ) -> Self {
+        Self {
+            path,
+            predicate: predicate.into(),
+            position,
+            type_tag,
+            error,
+        }
+    }
+
+    /// Returns the path of the object that contained the element, such as
+    /// `["Zewif", "Wallet[0]", "Account[\"Savings\"]"]`.
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+
+    /// Returns the predicate under which the element was encoded, such as
+    /// `address`.
+    pub fn predicate(&self) -> &str {
+        &self.predicate
+    }
+
+    /// Returns the element's index within its container, if it could be read.
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+
+    /// Returns the element's declared type, such as `Address`, if it could be
+    /// read.
+    pub fn type_tag(&self) -> Option<&str> {
+        self.type_tag.as_deref()
+    }
+
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+}
+
+impl fmt::Display for DecodeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "skipped {}",
+            self.type_tag.as_deref().unwrap_or("element")
+        )?;
+        if let Some(position) = self.position {
+            write!(f, " {}", position)?;
+        }
+        write!(
+            f,
+            " of {} ({}): {}",
+            self.path.join(" > "),
+            self.predicate,
+            self.error
+        )
+    }
+}
@@ -48,6 +48,7 @@ use crate::NonHardenedChildIndex;
 /// assert_eq!(u32::from(derivation_info.address_index()), 5);
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct DerivationInfo {
     /// The change level (0 = external addresses, 1 = internal/change addresses)
     change: NonHardenedChildIndex,
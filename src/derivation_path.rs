@@ -0,0 +1,224 @@
+use bc_envelope::prelude::*;
+use std::{fmt, str::FromStr};
+
+use crate::{Error, Result};
+
+/// The bit marking a hardened child index.
+const HARDENED: u32 = 1 << 31;
+
+/// A BIP-32/ZIP 32 derivation path, such as `m/32'/133'/0'`.
+///
+/// Each component is a child index, with hardened components distinguished by
+/// the high bit, as in BIP-32. Paths are written with an apostrophe (or `h`)
+/// marking hardened components and are encoded in this written form.
+///
+/// # Examples
+/// ```
+/// # use zewif::DerivationPath;
+/// let path: DerivationPath = "m/44'/133'/0'/0/5".parse().unwrap();
+/// assert_eq!(path.len(), 5);
+/// assert!(path.is_hardened(2));
+/// assert!(!path.is_hardened(3));
+/// assert_eq!(path.to_string(), "m/44'/133'/0'/0/5");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// Creates a path from child indices, with hardened components having the
+    /// high bit set.
+    pub fn new(components: Vec<u32>) -> Self {
+        Self(components)
+    }
+
+    /// Creates a path whose components are all hardened. Components are given
+    /// without the hardened bit.
+    ///
+    /// # Errors
+    /// Returns an error if a component is not below 2<sup>31</sup>.
+    pub fn hardened(components: &[u32]) -> Result<Self> {
+        components
+            .iter()
+            .map(|&index| {
+                if index < HARDENED {
+                    Ok(index | HARDENED)
+                } else {
+                    Err(Error::ChildIndexTooLarge { index })
+                }
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Self)
+    }
+
+    /// Returns the child indices, with hardened components having the high
+    /// bit set.
+    pub fn components(&self) -> &[u32] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns whether the component at `position` is hardened.
+    pub fn is_hardened(&self, position: usize) -> bool {
+        self.0
+            .get(position)
+            .is_some_and(|index| index & HARDENED != 0)
+    }
+
+    /// Returns the coin type of a BIP-44 or ZIP 32 path, which begins with a
+    /// hardened purpose of 44 or 32 followed by the hardened coin type.
+    pub fn coin_type(&self) -> Option<u32> {
+        match self.0.as_slice() {
+            [purpose, coin_type, ..]
+                if [44 | HARDENED, 32 | HARDENED].contains(purpose)
+                    && coin_type & HARDENED != 0 =>
+            {
+                Some(coin_type & !HARDENED)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the components without the hardened bit if every component is
+    /// hardened, or `None` otherwise.
+    pub fn hardened_indices(&self) -> Option<Vec<u32>> {
+        self.0
+            .iter()
+            .map(|&index| (index & HARDENED != 0).then_some(index & !HARDENED))
+            .collect()
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            if index & HARDENED != 0 {
+                write!(f, "/{}'", index & !HARDENED)?;
+            } else {
+                write!(f, "/{}", index)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidDerivationPath {
+            path: s.to_string(),
+        };
+        let mut components = s.split('/');
+        if components.next() != Some("m") {
+            return Err(invalid());
+        }
+        components
+            .map(|component| {
+                let (digits, hardened) = match component.strip_suffix(['\'', 'h']) {
+                    Some(digits) => (digits, true),
+                    None => (component, false),
+                };
+                let index: u32 = digits.parse().map_err(|_| invalid())?;
+                if index >= HARDENED {
+                    return Err(invalid());
+                }
+                Ok(if hardened { index | HARDENED } else { index })
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Self)
+    }
+}
+
+impl From<DerivationPath> for CBOR {
+    fn from(value: DerivationPath) -> Self {
+        CBOR::from(value.to_string())
+    }
+}
+
+impl From<&DerivationPath> for CBOR {
+    fn from(value: &DerivationPath) -> Self {
+        CBOR::from(value.to_string())
+    }
+}
+
+impl TryFrom<CBOR> for DerivationPath {
+    type Error = dcbor::Error;
+
+    fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
+        let path: String = cbor.try_into()?;
+        path.parse()
+            .map_err(|e: Error| dcbor::Error::msg(e.to_string()))
+    }
+}
+
+impl From<DerivationPath> for Envelope {
+    fn from(value: DerivationPath) -> Self {
+        Envelope::new(CBOR::from(value))
+    }
+}
+
+impl TryFrom<Envelope> for DerivationPath {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.extract_subject()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cbor_roundtrip, test_envelope_roundtrip};
+
+    use super::DerivationPath;
+
+    impl crate::RandomInstance for DerivationPath {
+        fn random() -> Self {
+            Self(
+                (0..rand::random_range(0..6))
+                    .map(|_| rand::random())
+                    .collect(),
+            )
+        }
+    }
+
+    test_cbor_roundtrip!(DerivationPath);
+    test_envelope_roundtrip!(DerivationPath);
+
+    #[test]
+    fn test_parse() {
+        let path: DerivationPath = "m/32h/133h/0h".parse().unwrap();
+        assert_eq!(path, DerivationPath::hardened(&[32, 133, 0]).unwrap());
+        assert_eq!(path.to_string(), "m/32'/133'/0'");
+        assert_eq!(path.hardened_indices(), Some(vec![32, 133, 0]));
+
+        let path: DerivationPath = "m".parse().unwrap();
+        assert!(path.is_empty());
+
+        assert!(
+            "m/32'/133'/0'/0/5"
+                .parse::<DerivationPath>()
+                .unwrap()
+                .hardened_indices()
+                .is_none()
+        );
+        assert!("32'/133'/0'".parse::<DerivationPath>().is_err());
+        assert!("m/32'/x".parse::<DerivationPath>().is_err());
+        assert!("m/2147483648".parse::<DerivationPath>().is_err());
+        assert!(DerivationPath::hardened(&[1 << 31]).is_err());
+
+        let coin_type = |path: &str| path.parse::<DerivationPath>().unwrap().coin_type();
+        assert_eq!(coin_type("m/32'/133'/0'"), Some(133));
+        assert_eq!(coin_type("m/44'/1'/0'/0/5"), Some(1));
+        assert_eq!(coin_type("m/32'/133/0'"), None);
+        assert_eq!(coin_type("m/0'/133'/0'"), None);
+        assert_eq!(coin_type("m/32'"), None);
+    }
+}
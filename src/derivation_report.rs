@@ -0,0 +1,248 @@
+#[cfg(feature = "zip32")]
+use crate::{
+    Account, DerivationPath, ProtocolAddress, Result, SeedFingerprint, orchard::ZIP32_PURPOSE,
+    sapling::SaplingExtendedSpendingKey,
+};
+
+/// A key of an account whose derivation from a seed can be checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerivedKey {
+    /// The Sapling extended spending key of the given address.
+    Sapling(String),
+    /// The account's Orchard spending key.
+    Orchard,
+}
+
+/// Why a stored key could not be confirmed to derive from the seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MismatchReason {
+    /// The key re-derived at the recorded path differs from the stored key.
+    KeyDiffers,
+    /// The seed does not match the fingerprint recorded with the derivation.
+    SeedFingerprintDiffers,
+    /// The recorded path is malformed or has non-hardened components, so the
+    /// key cannot be re-derived.
+    UnsupportedPath,
+}
+
+/// A stored key that does not derive from the seed as recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMismatch {
+    key: DerivedKey,
+    path: String,
+    reason: MismatchReason,
+}
+
+impl KeyMismatch {
+    pub fn new(key: DerivedKey, path: impl Into<String>, reason: MismatchReason) -> Self {
+        Self {
+            key,
+            path: path.into(),
+            reason,
+        }
+    }
+
+    pub fn key(&self) -> &DerivedKey {
+        &self.key
+    }
+
+    /// Returns the recorded derivation path of the key.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn reason(&self) -> MismatchReason {
+        self.reason
+    }
+}
+
+/// The outcome of checking an account's stored keys against a seed, as
+/// returned by `verify_account_derivation` with the `zip32` feature.
+///
+/// Only keys stored together with their derivation path are checked.
+#[cfg_attr(feature = "zip32", doc = "")]
+#[cfg_attr(feature = "zip32", doc = "See [`verify_account_derivation`].")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DerivationReport {
+    checked: usize,
+    mismatches: Vec<KeyMismatch>,
+}
+
+impl DerivationReport {
+    /// Returns the number of keys checked, including those that mismatched.
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+
+    pub fn mismatches(&self) -> &[KeyMismatch] {
+        &self.mismatches
+    }
+
+    /// Returns whether every key checked derives from the seed as recorded.
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    #[cfg(feature = "zip32")]
+    fn record(&mut self, key: DerivedKey, path: String, reason: Option<MismatchReason>) {
+        self.checked += 1;
+        if let Some(reason) = reason {
+            self.mismatches.push(KeyMismatch::new(key, path, reason));
+        }
+    }
+}
+
+/// Checks that the account's stored keys derive from `seed` as recorded.
+///
+/// Each Sapling address holding both an extended spending key and an HD
+/// derivation path has its key re-derived at that path (ZIP 32 Sapling
+/// derivation supports only hardened paths here). The account's Orchard
+/// spending key is checked against its [`OrchardDerivation`], including the
+/// recorded seed fingerprint, or, if none is recorded, against the path given
+/// by the account's coin type and ZIP 32 account ID.
+///
+/// The seed is usually obtained with
+/// [`SeedMaterial::to_seed_bytes`](crate::SeedMaterial::to_seed_bytes).
+///
+/// # Errors
+/// Returns an error if the seed is shorter than 32 bytes or longer than 252
+/// bytes. Keys that do not match are reported in the returned
+/// [`DerivationReport`] rather than as errors.
+///
+/// [`OrchardDerivation`]: crate::orchard::OrchardDerivation
+#[cfg(feature = "zip32")]
+pub fn verify_account_derivation(account: &Account, seed: &[u8]) -> Result<DerivationReport> {
+    let fingerprint = SeedFingerprint::from_seed(seed)?;
+    let mut report = DerivationReport::default();
+
+    for address in account.addresses() {
+        let ProtocolAddress::Sapling(address) = address.address() else {
+            continue;
+        };
+        let (Some(key), Some(path)) = (address.spending_key(), address.hd_derivation_path()) else {
+            continue;
+        };
+        let reason = match parse_hardened_path(path) {
+            Some(components) => (SaplingExtendedSpendingKey::derive(seed, &components)? != *key)
+                .then_some(MismatchReason::KeyDiffers),
+            None => Some(MismatchReason::UnsupportedPath),
+        };
+        report.record(
+            DerivedKey::Sapling(address.address().to_string()),
+            path.to_string(),
+            reason,
+        );
+    }
+
+    if let (Some(derivation), Some(key)) = (
+        account.hd_orchard_derivation(),
+        account.orchard_spending_key(),
+    ) {
+        let reason = if derivation
+            .seed_fingerprint()
+            .is_some_and(|recorded| *recorded != fingerprint)
+        {
+            Some(MismatchReason::SeedFingerprintDiffers)
+        } else {
+            (derivation.derive_spending_key(seed)? != *key).then_some(MismatchReason::KeyDiffers)
+        };
+        report.record(DerivedKey::Orchard, derivation.path(), reason);
+    }
+
+    Ok(report)
+}
+
+/// Parses a derivation path such as `m/32'/133'/0'` whose components are all
+/// hardened, returning the components without the hardened bit.
+#[cfg(feature = "zip32")]
+fn parse_hardened_path(path: &str) -> Option<Vec<u32>> {
+    path.parse::<DerivationPath>()
+        .ok()?
+        .hardened_indices()
+        .filter(|components| components.first() == Some(&ZIP32_PURPOSE))
+}
+
+#[cfg(all(test, feature = "zip32"))]
+mod tests {
+    use crate::{
+        Account, Address, ProtocolAddress, SeedFingerprint,
+        orchard::{OrchardDerivation, OrchardSpendingKey},
+        sapling::{self, SaplingExtendedSpendingKey},
+    };
+
+    use super::{DerivedKey, MismatchReason, parse_hardened_path, verify_account_derivation};
+
+    fn sapling_address(name: &str, key: SaplingExtendedSpendingKey, path: &str) -> Address {
+        let mut address = sapling::Address::new(name.to_string());
+        address.set_spending_key(key);
+        address.set_hd_derivation_path(path.to_string());
+        Address::new(ProtocolAddress::Sapling(Box::new(address)))
+    }
+
+    #[test]
+    fn test_parse_hardened_path() {
+        assert_eq!(parse_hardened_path("m/32'/133'/0'"), Some(vec![32, 133, 0]));
+        assert_eq!(
+            parse_hardened_path("m/32h/1h/2147483647h"),
+            Some(vec![32, 1, 0x7fff_ffff])
+        );
+        assert_eq!(parse_hardened_path("m/32'/133'/0'/0/5"), None);
+        assert_eq!(parse_hardened_path("m/44'/133'/0'"), None);
+        assert_eq!(parse_hardened_path("m/32'/133'/2147483648'"), None);
+        assert_eq!(parse_hardened_path("32'/133'/0'"), None);
+    }
+
+    #[test]
+    fn test_verify_account_derivation() {
+        let seed: Vec<u8> = (0u8..32).collect();
+        let mut account = Account::new();
+
+        let key = SaplingExtendedSpendingKey::derive(&seed, &[32, 133, 0]).unwrap();
+        account.add_address(sapling_address("zs1good", key, "m/32'/133'/0'"));
+        let key = SaplingExtendedSpendingKey::derive(&seed, &[32, 133, 1]).unwrap();
+        account.add_address(sapling_address("zs1wrong", key, "m/32'/133'/2'"));
+        account.add_address(sapling_address(
+            "zs1path",
+            SaplingExtendedSpendingKey::new([0u8; 169]),
+            "m/32'/133'/0'/0/5",
+        ));
+
+        let derivation =
+            OrchardDerivation::new(133, 0, Some(SeedFingerprint::from_seed(&seed).unwrap()));
+        account.set_orchard_spending_key(Some(derivation.derive_spending_key(&seed).unwrap()));
+        account.set_orchard_derivation(Some(derivation));
+
+        let report = verify_account_derivation(&account, &seed).unwrap();
+        assert_eq!(report.checked(), 4);
+        assert!(!report.is_consistent());
+        let mismatches: Vec<_> = report
+            .mismatches()
+            .iter()
+            .map(|m| (m.key().clone(), m.reason()))
+            .collect();
+        assert_eq!(
+            mismatches,
+            vec![
+                (
+                    DerivedKey::Sapling("zs1wrong".to_string()),
+                    MismatchReason::KeyDiffers
+                ),
+                (
+                    DerivedKey::Sapling("zs1path".to_string()),
+                    MismatchReason::UnsupportedPath
+                ),
+            ]
+        );
+
+        let other_seed = [7u8; 32];
+        account.set_orchard_spending_key(Some(OrchardSpendingKey::new([0u8; 32])));
+        let report = verify_account_derivation(&account, &other_seed).unwrap();
+        assert!(report.mismatches().iter().any(|m| {
+            *m.key() == DerivedKey::Orchard
+                && m.reason() == MismatchReason::SeedFingerprintDiffers
+                && m.path() == "m/32'/133'/0'"
+        }));
+
+        assert!(verify_account_derivation(&account, &seed[..16]).is_err());
+    }
+}
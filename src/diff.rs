@@ -0,0 +1,480 @@
+use std::fmt;
+
+use bc_envelope::prelude::*;
+
+use crate::{
+    SeedFingerprint, TxId, Zewif,
+    envelope_decoder::segment,
+    loss_report::{is_structured, object_segment, objects, predicate_name},
+};
+
+/// How an element differs between two Zewifs; see [`Difference`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiffKind {
+    /// The element is in both Zewifs, but its value differs.
+    Changed,
+    /// The element is only in the first Zewif.
+    Removed,
+    /// The element is only in the second Zewif.
+    Added,
+}
+
+impl fmt::Display for DiffKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffKind::Changed => write!(f, "changed"),
+            DiffKind::Removed => write!(f, "removed"),
+            DiffKind::Added => write!(f, "added"),
+        }
+    }
+}
+
+/// One difference found by [`Zewif::diff`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Difference {
+    path: Vec<String>,
+    field: Option<String>,
+    kind: DiffKind,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+impl Difference {
+    pub fn kind(&self) -> DiffKind {
+        self.kind
+    }
+
+    /// Returns the path of the object that differs, or that contains the
+    /// field that differs, such as
+    /// `["Zewif", "Wallet[0]", "Account[\"Savings\"]", "Address[t1abc]"]`.
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+
+    /// Returns the field that differs, such as `name` or `witness`, or `None`
+    /// if a whole wallet, account, address, seed, note, or transaction was
+    /// added or removed.
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    /// Returns the value in the first Zewif, if it has one. Byte strings,
+    /// which include all key material, are shown only by their length and
+    /// the prefix of their digest.
+    pub fn before(&self) -> Option<&str> {
+        self.before.as_deref()
+    }
+
+    /// Returns the value in the second Zewif, if it has one, shown as by
+    /// [`Difference::before`].
+    pub fn after(&self) -> Option<&str> {
+        self.after.as_deref()
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.path.join(" > "))?;
+        if let Some(field) = &self.field {
+            write!(f, ": {}", field)?;
+        }
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => write!(f, " {} -> {}", before, after),
+            (Some(value), None) | (None, Some(value)) => write!(f, " {}", value),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// The result of [`Zewif::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffReport {
+    differences: Vec<Difference>,
+}
+
+impl DiffReport {
+    /// Returns whether the two Zewifs hold the same content.
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Returns the differences found, ordered by path.
+    pub fn differences(&self) -> &[Difference] {
+        &self.differences
+    }
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.differences.is_empty() {
+            return write!(f, "no differences");
+        }
+        let differences: Vec<_> = self.differences.iter().map(Difference::to_string).collect();
+        write!(f, "{}", differences.join("\n"))
+    }
+}
+
+impl Zewif {
+    /// Compares this Zewif with `other`, such as the Zewifs produced from the
+    /// same wallet file by two versions of an importer.
+    ///
+    /// Elements are matched by their stable identifiers rather than their
+    /// position: seeds by fingerprint, accounts by seed fingerprint and ZIP 32
+    /// account index where both are known, addresses by their string
+    /// encoding, notes by the transaction output they were received in, and
+    /// transactions by txid. Each matched pair is compared field by field,
+    /// down to the fields of witnesses and keys. The identifiers of the Zewifs
+    /// themselves and of their accounts, which are random, and the indices of
+    /// matched elements are not compared.
+    pub fn diff(&self, other: &Zewif) -> DiffReport {
+        let mut differences = Vec::new();
+        let mut path = vec!["Zewif".to_string()];
+        compare(
+            &Envelope::from(self.clone()),
+            &Envelope::from(other.clone()),
+            false,
+            &mut path,
+            &mut differences,
+        );
+        differences.sort();
+        DiffReport { differences }
+    }
+}
+
+/// Records the differences between two envelopes of the object at `path`,
+/// comparing their subjects only if `compare_subjects`.
+fn compare(
+    before: &Envelope,
+    after: &Envelope,
+    compare_subjects: bool,
+    path: &mut Vec<String>,
+    differences: &mut Vec<Difference>,
+) {
+    if before.digest() == after.digest() {
+        return;
+    }
+
+    let (before_subject, after_subject) = (before.subject(), after.subject());
+    if before_subject.digest() != after_subject.digest() {
+        match (before_subject.try_unwrap(), after_subject.try_unwrap()) {
+            (Ok(before), Ok(after)) => compare(&before, &after, true, path, differences),
+            _ if compare_subjects => differences.push(Difference {
+                path: path.clone(),
+                field: None,
+                kind: DiffKind::Changed,
+                before: Some(render(&before_subject)),
+                after: Some(render(&after_subject)),
+            }),
+            _ => {}
+        }
+    }
+
+    // Only accounts have an `id` assertion.
+    let mut predicates: Vec<_> = before
+        .assertions()
+        .iter()
+        .chain(after.assertions().iter())
+        .filter_map(predicate_name)
+        .filter(|predicate| predicate != "id")
+        .collect();
+    predicates.sort();
+    predicates.dedup();
+    for predicate in predicates {
+        compare_objects(
+            &predicate,
+            objects(before, &predicate),
+            objects(after, &predicate),
+            path,
+            differences,
+        );
+    }
+}
+
+/// Pairs the objects of `predicate` in two envelopes, first by digest, then
+/// by stable identifier, and records those that differ or have no
+/// counterpart.
+fn compare_objects(
+    predicate: &str,
+    mut before: Vec<Envelope>,
+    mut after: Vec<Envelope>,
+    path: &mut Vec<String>,
+    differences: &mut Vec<Difference>,
+) {
+    before.retain(
+        |b| match after.iter().position(|a| a.digest() == b.digest()) {
+            Some(position) => {
+                after.remove(position);
+                false
+            }
+            None => true,
+        },
+    );
+
+    let mut pairs = Vec::new();
+    before.retain(|b| {
+        match after
+            .iter()
+            .position(|a| stable_key(predicate, a) == stable_key(predicate, b))
+        {
+            Some(position) => {
+                pairs.push((b.clone(), after.remove(position)));
+                false
+            }
+            None => true,
+        }
+    });
+    if before.len() == 1 && after.len() == 1 && !is_keyed(predicate) {
+        pairs.push((before.remove(0), after.remove(0)));
+    }
+
+    for (b, a) in pairs {
+        if is_structured(&b) || is_structured(&a) {
+            path.push(diff_segment(predicate, &b));
+            compare(&b, &a, !is_keyed(predicate), path, differences);
+            path.pop();
+        } else {
+            differences.push(Difference {
+                path: path.clone(),
+                field: Some(predicate.to_string()),
+                kind: DiffKind::Changed,
+                before: Some(render(&b)),
+                after: Some(render(&a)),
+            });
+        }
+    }
+
+    let unpaired = |object: &Envelope, kind, path: &Vec<String>| {
+        let value = Some(render(object));
+        let (before, after) = if kind == DiffKind::Removed {
+            (value, None)
+        } else {
+            (None, value)
+        };
+        if is_keyed(predicate) {
+            let mut path = path.clone();
+            path.push(diff_segment(predicate, object));
+            Difference {
+                path,
+                field: None,
+                kind,
+                before: None,
+                after: None,
+            }
+        } else {
+            Difference {
+                path: path.clone(),
+                field: Some(predicate.to_string()),
+                kind,
+                before,
+                after,
+            }
+        }
+    };
+    differences.extend(before.iter().map(|b| unpaired(b, DiffKind::Removed, path)));
+    differences.extend(after.iter().map(|a| unpaired(a, DiffKind::Added, path)));
+}
+
+/// The fields identifying the objects of `predicate`, if they have a stable
+/// identifier other than their subject.
+fn key_fields(predicate: &str) -> &'static [&'static str] {
+    match predicate {
+        "seed" => &["fingerprint"],
+        "account" => &["seed_fingerprint", "zip32_account_id"],
+        "address" => &["address"],
+        "sprout_received_note" | "sapling_received_note" | "orchard_received_note" => {
+            &["txid", "joinsplit_index", "output_index", "action_index"]
+        }
+        _ => &[],
+    }
+}
+
+/// Returns whether the objects of `predicate` are identified by a stable
+/// identifier, so that two such objects with different identifiers are never
+/// compared with each other.
+fn is_keyed(predicate: &str) -> bool {
+    matches!(predicate, "wallet" | "transaction") || !key_fields(predicate).is_empty()
+}
+
+/// Returns the digest of the stable identifier of an object: the digest of
+/// its key fields if it has them all, and otherwise that of its subject.
+fn stable_key(predicate: &str, object: &Envelope) -> Digest {
+    let fields = key_fields(predicate);
+    let digests: Vec<_> = fields
+        .iter()
+        .filter_map(|field| object.object_for_predicate(*field).ok())
+        .map(|object| object.digest())
+        .collect();
+    let has_all = match predicate {
+        "sprout_received_note" | "sapling_received_note" | "orchard_received_note" => {
+            digests.len() > 1
+        }
+        _ => !fields.is_empty() && digests.len() == fields.len(),
+    };
+    if has_all {
+        Digest::from_digests(&digests)
+    } else {
+        object.subject().digest()
+    }
+}
+
+/// Returns the path segment of an object, naming notes by the transaction
+/// output they were received in, such as `SaplingReceivedNote[<txid>:1]`.
+fn diff_segment(predicate: &str, object: &Envelope) -> String {
+    let type_name = || {
+        object
+            .get_type()
+            .ok()
+            .and_then(|t| t.extract_subject::<String>().ok())
+    };
+    match predicate {
+        "seed" => match object.extract_object_for_predicate::<SeedFingerprint>("fingerprint") {
+            Ok(fingerprint) => segment("WalletSeed", fingerprint.short_hex()),
+            Err(_) => object_segment(predicate, object),
+        },
+        "sprout_received_note" | "sapling_received_note" | "orchard_received_note" => {
+            let txid = object.extract_object_for_predicate::<TxId>("txid");
+            let indices: Vec<String> = ["joinsplit_index", "output_index", "action_index"]
+                .iter()
+                .filter_map(|field| object.extract_object_for_predicate::<u32>(*field).ok())
+                .map(|index| index.to_string())
+                .collect();
+            match (type_name(), txid) {
+                (Some(type_name), Ok(txid)) => {
+                    segment(&type_name, format!("{}:{}", txid, indices.join(":")))
+                }
+                _ => object_segment(predicate, object),
+            }
+        }
+        _ => object_segment(predicate, object),
+    }
+}
+
+/// Renders a value for a [`Difference`], showing byte strings only by their
+/// length and the prefix of their digest, so that key material is never
+/// revealed.
+fn render(envelope: &Envelope) -> String {
+    match envelope.subject().as_leaf() {
+        Some(cbor) if !envelope.has_assertions() => match cbor.as_byte_string() {
+            Some(bytes) => format!(
+                "<{} bytes, digest {}>",
+                bytes.len(),
+                envelope.digest().short_description()
+            ),
+            None => cbor.diagnostic_flat(),
+        },
+        _ => format!("<{}>", envelope.digest().short_description()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Account, Address, BlockHeight, DiffKind, Network, ProtocolAddress, Transaction, TxId,
+        Zewif, ZewifWallet, transparent,
+    };
+
+    fn zewif() -> Zewif {
+        let mut account = Account::new();
+        account.set_name("Savings");
+        for (string, name) in [("t1diff0", "Spending"), ("t1diff1", "Change")] {
+            let mut address = Address::new(ProtocolAddress::Transparent(
+                transparent::Address::new(string),
+            ));
+            address.set_name(name.to_string());
+            account.add_address(address);
+        }
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        for byte in [1, 2] {
+            let mut transaction = Transaction::new(TxId::from_bytes([byte; 32]));
+            transaction.set_mined_height(BlockHeight::from(1_000_000 + byte as u32));
+            zewif
+                .add_transaction(transaction.txid(), transaction)
+                .unwrap();
+        }
+        zewif
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let zewif = zewif();
+        let mut other = Zewif::new(zewif.export_height());
+        for wallet in zewif.wallets() {
+            other.add_wallet(wallet.clone());
+        }
+        other.set_transactions(zewif.transactions().clone());
+        assert_ne!(zewif.id(), other.id());
+
+        let report = zewif.diff(&other);
+        assert!(report.is_empty());
+        assert_eq!(report.to_string(), "no differences");
+    }
+
+    #[test]
+    fn test_diff_address_name() {
+        let zewif = zewif();
+        let mut other = zewif.clone();
+        other.wallets_mut()[0].accounts_mut()[0].addresses_mut()[1]
+            .set_name("Savings change".to_string());
+
+        let report = zewif.diff(&other);
+        assert_eq!(report.differences().len(), 1);
+        let difference = &report.differences()[0];
+        assert_eq!(difference.kind(), DiffKind::Changed);
+        assert_eq!(
+            difference.path(),
+            [
+                "Zewif",
+                "Wallet[0]",
+                "Account[\"Savings\"]",
+                "Address[t1diff1]"
+            ]
+        );
+        assert_eq!(difference.field(), Some("name"));
+        assert_eq!(difference.before(), Some("\"Change\""));
+        assert_eq!(difference.after(), Some("\"Savings change\""));
+        assert_eq!(
+            report.to_string(),
+            "changed Zewif > Wallet[0] > Account[\"Savings\"] > Address[t1diff1]: name \"Change\" -> \"Savings change\""
+        );
+    }
+
+    #[test]
+    fn test_diff_reordered_addresses() {
+        let zewif = zewif();
+        let mut other = zewif.clone();
+        other.wallets_mut()[0].accounts_mut()[0]
+            .addresses_mut()
+            .reverse();
+        assert!(zewif.diff(&other).is_empty());
+    }
+
+    #[test]
+    fn test_diff_missing_transaction() {
+        let zewif = zewif();
+        let mut other = zewif.clone();
+        let mut transactions = other.transactions().clone();
+        let txid = TxId::from_bytes([2; 32]);
+        transactions.remove(&txid);
+        other.set_transactions(transactions);
+
+        let report = zewif.diff(&other);
+        assert_eq!(report.differences().len(), 1);
+        let difference = &report.differences()[0];
+        assert_eq!(difference.kind(), DiffKind::Removed);
+        assert_eq!(
+            difference.path(),
+            ["Zewif".to_string(), format!("Transaction[{}]", txid)]
+        );
+        assert_eq!(difference.field(), None);
+
+        let reversed = other.diff(&zewif);
+        assert_eq!(reversed.differences()[0].kind(), DiffKind::Added);
+        assert_eq!(
+            reversed.to_string(),
+            format!("added Zewif > Transaction[{}]", txid)
+        );
+    }
+}
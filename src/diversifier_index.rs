@@ -0,0 +1,70 @@
+use std::cmp::Ordering;
+
+use crate::{Blob, blob, blob_envelope};
+
+blob!(
+    DiversifierIndex,
+    11,
+    r#"The index from which a diversified address is derived, as defined in ZIP 32.
+
+A diversifier index is an 88-bit unsigned integer stored in little-endian
+order. Indexes are ordered by their integer value, so the addresses of a
+unified account keyed by index are listed in the order they were generated.
+
+# Examples
+```
+# use zewif::DiversifierIndex;
+let first = DiversifierIndex::from(0u64);
+let second = DiversifierIndex::from(1u64);
+assert!(first < second);
+assert_eq!(second.as_bytes()[0], 1);
+```"#
+);
+impl Copy for DiversifierIndex {}
+
+impl Ord for DiversifierIndex {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl PartialOrd for DiversifierIndex {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<u64> for DiversifierIndex {
+    fn from(value: u64) -> Self {
+        let mut bytes = [0u8; 11];
+        bytes[..8].copy_from_slice(&value.to_le_bytes());
+        Self(bytes)
+    }
+}
+
+impl From<Blob<11>> for DiversifierIndex {
+    fn from(value: Blob<11>) -> Self {
+        Self(*value.as_bytes())
+    }
+}
+
+blob_envelope!(DiversifierIndex);
+
+#[cfg(test)]
+mod tests {
+    use super::DiversifierIndex;
+
+    #[test]
+    fn test_numeric_order() {
+        let mut high = [0u8; 11];
+        high[10] = 1;
+        let indexes = [
+            DiversifierIndex::from(0x0100u64),
+            DiversifierIndex::new(high),
+            DiversifierIndex::from(0xffu64),
+        ];
+        let mut sorted = indexes;
+        sorted.sort();
+        assert_eq!(sorted, [indexes[2], indexes[0], indexes[1]]);
+    }
+}
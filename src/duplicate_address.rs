@@ -0,0 +1,247 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{Address, Error, Indexed, Result, Zewif, ZewifWallet};
+
+/// An address held by more than one account; see
+/// [`Zewif::find_duplicate_addresses`].
+///
+/// An address held by two accounts has ambiguous ownership, and its funds are
+/// counted in the balance of each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateAddress {
+    address: String,
+    accounts: Vec<(usize, usize)>,
+}
+
+impl DuplicateAddress {
+    /// The string encoding of the address.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// The accounts holding the address, as pairs of wallet index and account
+    /// index, in order.
+    pub fn accounts(&self) -> &[(usize, usize)] {
+        &self.accounts
+    }
+}
+
+impl fmt::Display for DuplicateAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let accounts: Vec<_> = self
+            .accounts
+            .iter()
+            .map(|(wallet, account)| format!("account {} of wallet {}", account, wallet))
+            .collect();
+        write!(
+            f,
+            "address {} is held by {}",
+            self.address,
+            accounts.join(", ")
+        )
+    }
+}
+
+/// What to do when an address being added is already held by another account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AddressConflictPolicy {
+    /// Refuses the address with [`Error::DuplicateAddress`].
+    Reject,
+    /// Adds the address and reports the duplicate.
+    #[default]
+    Warn,
+    /// Adds the address without reporting the duplicate.
+    Allow,
+}
+
+impl Zewif {
+    /// Returns each address held by more than one account, across all of the
+    /// Zewif's wallets, ordered by address.
+    ///
+    /// Addresses are compared by their string encoding. An address appearing
+    /// twice in the same account is not reported.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{
+    /// #     Account, Address, BlockHeight, Network, ProtocolAddress, Zewif, ZewifWallet,
+    /// #     transparent,
+    /// # };
+    /// let transparent = transparent::Address::new("t1shared");
+    /// let address = Address::new(ProtocolAddress::Transparent(transparent));
+    /// let mut wallet = ZewifWallet::new(Network::Main);
+    /// for _ in 0..2 {
+    ///     let mut account = Account::new();
+    ///     account.add_address(address.clone());
+    ///     wallet.add_account(account);
+    /// }
+    /// let mut zewif = Zewif::new(BlockHeight::from(2_000_000u32));
+    /// zewif.add_wallet(wallet);
+    ///
+    /// let duplicates = zewif.find_duplicate_addresses();
+    /// assert_eq!(duplicates[0].address(), "t1shared");
+    /// assert_eq!(duplicates[0].accounts(), [(0, 0), (0, 1)]);
+    /// ```
+    pub fn find_duplicate_addresses(&self) -> Vec<DuplicateAddress> {
+        let mut holders: BTreeMap<String, Vec<(usize, usize)>> = BTreeMap::new();
+        for wallet in self.wallets() {
+            for account in wallet.accounts() {
+                for address in account.addresses() {
+                    let accounts = holders.entry(address.as_string()).or_default();
+                    if !accounts.contains(&(wallet.index(), account.index())) {
+                        accounts.push((wallet.index(), account.index()));
+                    }
+                }
+            }
+        }
+        holders
+            .into_iter()
+            .filter(|(_, accounts)| accounts.len() > 1)
+            .map(|(address, accounts)| DuplicateAddress { address, accounts })
+            .collect()
+    }
+}
+
+impl ZewifWallet {
+    /// Adds `address` to the account at `account`, first checking whether
+    /// another account of the wallet already holds it.
+    ///
+    /// Returns the duplicate if the address was added although another
+    /// account holds it, under [`AddressConflictPolicy::Warn`].
+    ///
+    /// # Errors
+    /// Returns [`Error::NotInZewif`] if the wallet has no such account, and
+    /// [`Error::DuplicateAddress`] if another account holds the address under
+    /// [`AddressConflictPolicy::Reject`].
+    pub fn add_account_address(
+        &mut self,
+        account: usize,
+        address: Address,
+        on_conflict: AddressConflictPolicy,
+    ) -> Result<Option<DuplicateAddress>> {
+        if account >= self.accounts().len() {
+            return Err(Error::NotInZewif {
+                kind: "Account",
+                id: format!("{} of wallet {}", account, self.index()),
+            });
+        }
+        let string = address.as_string();
+        let mut accounts: Vec<_> = self
+            .accounts()
+            .iter()
+            .filter(|other| {
+                other.index() != account
+                    && other.addresses().iter().any(|a| a.as_string() == string)
+            })
+            .map(|other| (self.index(), other.index()))
+            .collect();
+        let duplicate = (!accounts.is_empty()).then(|| {
+            accounts.push((self.index(), account));
+            accounts.sort();
+            DuplicateAddress {
+                address: string,
+                accounts,
+            }
+        });
+        if let (Some(duplicate), AddressConflictPolicy::Reject) = (&duplicate, on_conflict) {
+            return Err(Error::DuplicateAddress(duplicate.clone()));
+        }
+        self.accounts_mut()[account].add_address(address);
+        Ok(duplicate.filter(|_| on_conflict == AddressConflictPolicy::Warn))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Account, Address, AddressConflictPolicy, BlockHeight, Error, Network, ProtocolAddress,
+        Zewif, ZewifWallet, transparent,
+    };
+
+    fn address(string: &str) -> Address {
+        Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+            string,
+        )))
+    }
+
+    fn wallet() -> ZewifWallet {
+        let mut wallet = ZewifWallet::new(Network::Main);
+        let mut account = Account::new();
+        account.add_address(address("t1shared"));
+        account.add_address(address("t1first"));
+        wallet.add_account(account);
+        wallet.add_account(Account::new());
+        wallet
+    }
+
+    #[test]
+    fn test_find_duplicate_addresses() {
+        let mut wallet = wallet();
+        let duplicate = wallet
+            .add_account_address(1, address("t1shared"), AddressConflictPolicy::Warn)
+            .unwrap();
+        assert_eq!(duplicate.unwrap().accounts(), [(0, 0), (0, 1)]);
+        assert_eq!(
+            wallet
+                .add_account_address(1, address("t1second"), AddressConflictPolicy::Warn)
+                .unwrap(),
+            None
+        );
+
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet.clone());
+        zewif.add_wallet(wallet);
+        let duplicates = zewif.find_duplicate_addresses();
+        assert_eq!(
+            duplicates
+                .iter()
+                .map(|d| (d.address(), d.accounts().to_vec()))
+                .collect::<Vec<_>>(),
+            [
+                ("t1first", vec![(0, 0), (1, 0)]),
+                ("t1second", vec![(0, 1), (1, 1)]),
+                ("t1shared", vec![(0, 0), (0, 1), (1, 0), (1, 1)]),
+            ]
+        );
+        assert_eq!(
+            duplicates[0].to_string(),
+            "address t1first is held by account 0 of wallet 0, account 0 of wallet 1"
+        );
+    }
+
+    #[test]
+    fn test_address_conflict_policies() {
+        let mut wallet = wallet();
+        let original = wallet.clone();
+        let error = wallet
+            .add_account_address(1, address("t1shared"), AddressConflictPolicy::Reject)
+            .unwrap_err();
+        assert!(matches!(error, Error::DuplicateAddress(_)));
+        assert_eq!(wallet, original);
+
+        // Re-adding an address to the account already holding it is not a
+        // conflict between accounts.
+        assert!(
+            wallet
+                .add_account_address(0, address("t1first"), AddressConflictPolicy::Reject)
+                .is_ok()
+        );
+        assert!(
+            wallet
+                .add_account_address(1, address("t1new"), AddressConflictPolicy::Reject)
+                .is_ok()
+        );
+
+        assert_eq!(
+            wallet
+                .add_account_address(1, address("t1shared"), AddressConflictPolicy::Allow)
+                .unwrap(),
+            None
+        );
+        assert_eq!(wallet.accounts()[1].addresses_len(), 2);
+        assert!(matches!(
+            wallet.add_account_address(2, address("t1other"), AddressConflictPolicy::Allow),
+            Err(Error::NotInZewif { .. })
+        ));
+    }
+}
@@ -0,0 +1,55 @@
+/// How a Zewif envelope is compressed when it is encoded.
+///
+/// [`Zewif::try_from`](crate::Zewif) decodes envelopes compressed in any of
+/// these ways.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Leave the envelope uncompressed.
+    #[default]
+    None,
+
+    /// Compress the whole envelope into a single `content` assertion. This
+    /// gives the smallest encoding, but nothing but the Zewif's ID can be read
+    /// without decompressing it.
+    Whole,
+
+    /// Compress each leaf whose CBOR encoding is longer than the given number
+    /// of bytes, such as witnesses and note ciphertexts. The structure of the
+    /// envelope and its smaller values remain readable without decompression,
+    /// and its digest is unchanged.
+    LargeLeavesOver(usize),
+}
+
+/// Options for encoding a Zewif as an envelope; see
+/// [`Zewif::to_envelope_with`](crate::Zewif::to_envelope_with).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodeOptions {
+    compression: Compression,
+    content_digest: bool,
+}
+
+impl EncodeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.compression = compression;
+    }
+
+    pub fn content_digest(&self) -> bool {
+        self.content_digest
+    }
+
+    /// Selects whether the envelope carries a `contentDigest` assertion
+    /// holding [`Zewif::digest`](crate::Zewif::digest), so that
+    /// [`Zewif::verify_integrity`](crate::Zewif::verify_integrity) can detect
+    /// a damaged file.
+    pub fn set_content_digest(&mut self, content_digest: bool) {
+        self.content_digest = content_digest;
+    }
+}
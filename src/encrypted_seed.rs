@@ -0,0 +1,124 @@
+use bc_envelope::prelude::*;
+
+use crate::{Data, NoQuotesDebugOption, SeedFingerprint, WalletCryptoParams};
+
+/// A seed that remains encrypted under the source wallet's passphrase.
+///
+/// When a passphrase-protected zcashd wallet is exported without its
+/// passphrase, its seed (the `cmnemonicphrase` or crypted HD seed record)
+/// cannot be decrypted. Rather than dropping it, the ciphertext is carried
+/// along with the parameters needed to decrypt it later, once the user
+/// supplies the passphrase.
+///
+/// zcashd stores the fingerprint of an encrypted seed in the clear, so it is
+/// usually known even though the seed is not.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Data, EncryptedSeed, WalletCryptoParams};
+/// let params = WalletCryptoParams::new(
+///     Data::from_vec(vec![0u8; 48]),
+///     Data::from_vec(vec![1u8; 8]),
+///     0,
+///     25_000,
+/// );
+/// let seed = EncryptedSeed::new(Data::from_vec(vec![2u8; 48]), params, None);
+/// assert_eq!(seed.ciphertext().len(), 48);
+/// ```
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncryptedSeed {
+    ciphertext: Data,
+    kdf_params: WalletCryptoParams,
+    fingerprint: Option<SeedFingerprint>,
+}
+
+/// Prints the length of the ciphertext and the seed fingerprint.
+impl std::fmt::Debug for EncryptedSeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("EncryptedSeed")
+            .field(
+                "ciphertext",
+                &format_args!("<{} bytes>", self.ciphertext.len()),
+            )
+            .field("kdf_params", &self.kdf_params)
+            .field(
+                "fingerprint",
+                &NoQuotesDebugOption(&self.fingerprint.map(|f| f.short_hex())),
+            )
+            .finish()
+    }
+}
+
+impl EncryptedSeed {
+    pub fn new(
+        ciphertext: Data,
+        kdf_params: WalletCryptoParams,
+        fingerprint: Option<SeedFingerprint>,
+    ) -> Self {
+        Self {
+            ciphertext,
+            kdf_params,
+            fingerprint,
+        }
+    }
+
+    /// Returns the encrypted seed record.
+    pub fn ciphertext(&self) -> &Data {
+        &self.ciphertext
+    }
+
+    /// Returns the parameters needed to decrypt the seed.
+    pub fn kdf_params(&self) -> &WalletCryptoParams {
+        &self.kdf_params
+    }
+
+    /// Returns the fingerprint of the decrypted seed, if known.
+    pub fn fingerprint(&self) -> Option<&SeedFingerprint> {
+        self.fingerprint.as_ref()
+    }
+}
+
+impl From<EncryptedSeed> for Envelope {
+    fn from(value: EncryptedSeed) -> Self {
+        Envelope::new(value.ciphertext)
+            .add_type("EncryptedSeed")
+            .add_assertion("kdf_params", value.kdf_params)
+            .add_optional_assertion("fingerprint", value.fingerprint)
+    }
+}
+
+impl TryFrom<Envelope> for EncryptedSeed {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("EncryptedSeed")?;
+        let ciphertext = envelope.extract_subject()?;
+        let kdf_params = envelope.try_object_for_predicate("kdf_params")?;
+        let fingerprint = envelope.try_optional_object_for_predicate("fingerprint")?;
+        Ok(Self {
+            ciphertext,
+            kdf_params,
+            fingerprint,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Data, SeedFingerprint, WalletCryptoParams, test_envelope_roundtrip};
+
+    use super::EncryptedSeed;
+
+    impl crate::RandomInstance for EncryptedSeed {
+        fn random() -> Self {
+            Self {
+                ciphertext: Data::random(),
+                kdf_params: WalletCryptoParams::random(),
+                fingerprint: SeedFingerprint::opt_random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(EncryptedSeed);
+}
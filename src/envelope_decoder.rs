@@ -0,0 +1,272 @@
+//! Decoding of nested envelopes that records where in the hierarchy a failure
+//! occurred.
+
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+use bc_envelope::prelude::*;
+
+use crate::{
+    DecodeIssue, Error, Indexed, Result,
+    debug::diagnostic,
+    watch_only::{SECRET_PREDICATES, elide_objects_of, optional_spending_object},
+};
+
+/// How many levels of CBOR nesting, and how many bytes of each byte string,
+/// the diagnostic attached to a decoding error shows.
+const DIAGNOSTIC_DEPTH: usize = 8;
+const DIAGNOSTIC_BYTES: usize = 32;
+
+/// An envelope being decoded, together with the path of objects that contain
+/// it, such as `Zewif > Wallet[0] > Account["Savings"]`.
+///
+/// Container types decode their fields through an `EnvelopeDecoder`, so that
+/// a failure anywhere below them is reported as an [`Error::EnvelopeDecode`]
+/// naming the object and predicate that could not be decoded.
+///
+/// A lenient decoder instead skips elements decoded with
+/// [`EnvelopeDecoder::objects_with`] that fail, recording a [`DecodeIssue`]
+/// for each, unless the element holds secret key material.
+///
+/// A decoder with diagnostics attaches the failing object, with its secrets
+/// elided, to each error in CBOR diagnostic notation.
+#[derive(Debug, Clone)]
+pub(crate) struct EnvelopeDecoder {
+    envelope: Envelope,
+    path: Vec<String>,
+    issues: Option<Rc<RefCell<Vec<DecodeIssue>>>>,
+    diagnostics: bool,
+}
+
+/// Formats a path segment identifying an object by its type and an
+/// identifying value, such as its index or name.
+pub(crate) fn segment(type_name: &str, id: impl Display) -> String {
+    format!("{}[{}]", type_name, id)
+}
+
+impl EnvelopeDecoder {
+    pub(crate) fn new(envelope: Envelope, segment: impl Into<String>) -> Self {
+        Self {
+            envelope,
+            path: vec![segment.into()],
+            issues: None,
+            diagnostics: false,
+        }
+    }
+
+    pub(crate) fn with_diagnostics(self) -> Self {
+        Self {
+            diagnostics: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn lenient(envelope: Envelope, segment: impl Into<String>) -> Self {
+        Self {
+            issues: Some(Rc::default()),
+            ..Self::new(envelope, segment)
+        }
+    }
+
+    /// Returns the issues recorded by this decoder and its children.
+    pub(crate) fn take_issues(&self) -> Vec<DecodeIssue> {
+        self.issues
+            .as_ref()
+            .map(|issues| issues.take())
+            .unwrap_or_default()
+    }
+
+    /// Returns a decoder for an object contained in this one.
+    pub(crate) fn child(&self, envelope: Envelope, segment: impl Into<String>) -> Self {
+        let mut path = self.path.clone();
+        path.push(segment.into());
+        Self {
+            envelope,
+            path,
+            issues: self.issues.clone(),
+            diagnostics: self.diagnostics,
+        }
+    }
+
+    /// Attributes an error to this object and, if given, one of its
+    /// predicates. Errors already attributed to a nested object are returned
+    /// unchanged.
+    pub(crate) fn error(&self, predicate: Option<&str>, source: impl Into<Error>) -> Error {
+        match source.into() {
+            error @ Error::EnvelopeDecode { .. } => error,
+            source => Error::EnvelopeDecode {
+                path: self.path.clone(),
+                predicate: predicate.map(str::to_string),
+                source: Box::new(source),
+                diagnostic: self.diagnostics.then(|| {
+                    let redacted = elide_objects_of(&self.envelope, SECRET_PREDICATES);
+                    diagnostic(&redacted, Some(DIAGNOSTIC_DEPTH), Some(DIAGNOSTIC_BYTES))
+                }),
+            },
+        }
+    }
+
+    pub(crate) fn envelope(&self) -> &Envelope {
+        &self.envelope
+    }
+
+    pub(crate) fn check_type(&self, type_name: &str) -> Result<()> {
+        self.envelope
+            .check_type(type_name)
+            .map_err(|e| self.error(None, e))
+    }
+
+    pub(crate) fn subject<T>(&self) -> Result<T>
+    where
+        T: TryFrom<CBOR, Error = dcbor::Error> + 'static,
+    {
+        self.envelope
+            .extract_subject()
+            .map_err(|e| self.error(None, e))
+    }
+
+    /// Decodes the envelope as a whole with its `TryFrom` conversion.
+    pub(crate) fn decode<T>(&self) -> Result<T>
+    where
+        T: TryFrom<Envelope, Error = bc_envelope::Error>,
+    {
+        T::try_from(self.envelope.clone()).map_err(|e| self.error(None, e))
+    }
+
+    pub(crate) fn extract<T>(&self, predicate: &str) -> Result<T>
+    where
+        T: TryFrom<CBOR, Error = dcbor::Error> + 'static,
+    {
+        self.envelope
+            .extract_object_for_predicate(predicate)
+            .map_err(|e| self.error(Some(predicate), e))
+    }
+
+    pub(crate) fn extract_optional<T>(&self, predicate: &str) -> Result<Option<T>>
+    where
+        T: TryFrom<CBOR, Error = dcbor::Error> + 'static,
+    {
+        self.envelope
+            .extract_optional_object_for_predicate(predicate)
+            .map_err(|e| self.error(Some(predicate), e))
+    }
+
+    pub(crate) fn object<T>(&self, predicate: &str) -> Result<T>
+    where
+        T: TryFrom<Envelope, Error = bc_envelope::Error> + 'static,
+    {
+        self.envelope
+            .try_object_for_predicate(predicate)
+            .map_err(|e| self.error(Some(predicate), e))
+    }
+
+    pub(crate) fn optional_object<T>(&self, predicate: &str) -> Result<Option<T>>
+    where
+        T: TryFrom<Envelope, Error = bc_envelope::Error> + 'static,
+    {
+        self.envelope
+            .try_optional_object_for_predicate(predicate)
+            .map_err(|e| self.error(Some(predicate), e))
+    }
+
+    /// Decodes the optional seed or spending key of `predicate`, which is
+    /// `None` if it was elided from a watch-only export.
+    pub(crate) fn optional_spending_object<T>(&self, predicate: &str) -> Result<Option<T>>
+    where
+        T: TryFrom<Envelope, Error = bc_envelope::Error>,
+    {
+        optional_spending_object(&self.envelope, predicate)
+            .map_err(|e| self.error(Some(predicate), e))
+    }
+
+    /// Decodes every object of `predicate` with `decode`, giving each object
+    /// the path segment returned by `segment`.
+    pub(crate) fn objects_with<T>(
+        &self,
+        predicate: &str,
+        segment: impl Fn(&Envelope) -> String,
+        decode: impl Fn(&EnvelopeDecoder) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let mut objects = Vec::new();
+        for object in self.envelope.objects_for_predicate(predicate) {
+            match decode(&self.child(object.clone(), segment(&object))) {
+                Ok(decoded) => objects.push(decoded),
+                Err(error) => self.skip(predicate, &object, error)?,
+            }
+        }
+        Ok(objects)
+    }
+
+    /// Records an element that failed to decode if this decoder is lenient
+    /// and the element holds no secrets; otherwise returns the error.
+    fn skip(&self, predicate: &str, object: &Envelope, error: Error) -> Result<()> {
+        match &self.issues {
+            Some(issues) if !holds_secrets(object) => {
+                let position = object.extract_subject::<usize>().ok();
+                let type_tag = object
+                    .get_type()
+                    .ok()
+                    .and_then(|t| t.extract_subject::<String>().ok());
+                issues.borrow_mut().push(DecodeIssue::new(
+                    self.path.clone(),
+                    predicate,
+                    position,
+                    type_tag,
+                    error,
+                ));
+                Ok(())
+            }
+            _ => Err(error),
+        }
+    }
+
+    /// Like [`EnvelopeDecoder::objects_with`], sorting the objects by index.
+    pub(crate) fn indexed_objects_with<T: Indexed>(
+        &self,
+        predicate: &str,
+        segment: impl Fn(&Envelope) -> String,
+        decode: impl Fn(&EnvelopeDecoder) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let mut objects = self.objects_with(predicate, segment, decode)?;
+        objects.sort_by_key(|object| object.index());
+        Ok(objects)
+    }
+
+    /// Decodes every object of `predicate` with its `TryFrom` conversion,
+    /// sorting the objects by index. Failures are attributed to `predicate`
+    /// of this object.
+    pub(crate) fn indexed_objects<T>(&self, predicate: &str) -> Result<Vec<T>>
+    where
+        T: Indexed + TryFrom<Envelope, Error = bc_envelope::Error> + 'static,
+    {
+        let mut objects: Vec<T> = self
+            .envelope
+            .try_objects_for_predicate(predicate)
+            .map_err(|e| self.error(Some(predicate), e))?;
+        objects.sort_by_key(|object| object.index());
+        Ok(objects)
+    }
+
+    pub(crate) fn attachments(&self) -> Result<Attachments> {
+        Attachments::try_from_envelope(&self.envelope)
+            .map_err(|e| self.error(Some("attachments"), e))
+    }
+}
+
+/// Returns whether the envelope holds secret key material at any depth. An
+/// element holding any is never skipped by lenient decoding, so that a wallet
+/// is not recovered without keys that could not be decoded.
+fn holds_secrets(envelope: &Envelope) -> bool {
+    envelope
+        .try_unwrap()
+        .is_ok_and(|inner| holds_secrets(&inner))
+        || envelope.assertions().iter().any(|assertion| {
+            let secret = assertion
+                .as_predicate()
+                .and_then(|predicate| predicate.extract_subject::<String>().ok())
+                .is_some_and(|predicate| SECRET_PREDICATES.contains(&predicate.as_str()));
+            secret
+                || assertion
+                    .as_object()
+                    .is_some_and(|object| holds_secrets(&object))
+        })
+}
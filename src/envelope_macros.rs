@@ -1,3 +1,10 @@
+/// Implements envelope conversions for a type created with
+/// [`blob!`](crate::blob) or [`data!`](crate::data), encoding it as a byte
+/// string subject with an `isA` assertion naming the type.
+///
+/// The type's own CBOR conversions, which encode it as a bare byte string,
+/// are the compact form to use when it is an element of a larger CBOR value,
+/// such as a Merkle path or a map of anchors.
 #[macro_export]
 macro_rules! blob_envelope {
     ($name:ident) => {
@@ -17,10 +24,12 @@ macro_rules! blob_envelope {
             ) -> bc_envelope::Result<Self> {
                 envelope.check_type(stringify!($name))?;
                 let bytes = envelope.subject().try_byte_string()?;
-                Self::try_from(bytes).map_err(|_| {
+                let cbor = bc_envelope::prelude::CBOR::to_byte_string(bytes);
+                Self::try_from(cbor).map_err(|e| {
                     bc_envelope::Error::General(format!(
-                        "Invalid {} blob",
-                        stringify!($name)
+                        "Invalid {} blob: {}",
+                        stringify!($name),
+                        e
                     ))
                 })
             }
@@ -28,10 +37,11 @@ macro_rules! blob_envelope {
 
         #[cfg(test)]
         mod test_envelope {
-            use $crate::test_envelope_roundtrip;
+            use $crate::{test_cbor_roundtrip, test_envelope_roundtrip};
 
             use super::$name;
 
+            test_cbor_roundtrip!($name);
             test_envelope_roundtrip!($name);
         }
     };
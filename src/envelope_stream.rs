@@ -0,0 +1,323 @@
+//! Writing and reading the tagged CBOR of a Zewif's envelope incrementally,
+//! without holding the whole envelope tree, or the whole file, in memory.
+
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    io::{self, BufRead, BufReader, Read, Write},
+};
+
+use bc_components::tags::TAG_ENVELOPE;
+use bc_envelope::prelude::*;
+
+use crate::{Compression, EncodeOptions, Error, Result, Zewif};
+
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_TAG: u8 = 6;
+
+/// A top-level assertion of a Zewif's envelope, built only when it is
+/// needed.
+enum TopLevel<'a> {
+    Type,
+    Version,
+    Wallet(usize),
+    Transaction(&'a crate::Transaction),
+    ExportHeight,
+    Attachment(Envelope),
+    ContentDigest(Digest),
+}
+
+impl Zewif {
+    /// Writes the tagged CBOR of the Zewif's envelope, compressed as selected
+    /// by `options`, building one top-level assertion at a time.
+    ///
+    /// The output is byte-identical to the CBOR of
+    /// [`Zewif::to_envelope_with`], but only a single wallet or transaction is
+    /// encoded as an envelope at any time, so a wallet with many transactions
+    /// can be written without materializing its whole envelope. Each
+    /// assertion is encoded twice: once to order the assertions by digest,
+    /// and again to write it. The `contentDigest` assertion, if requested, is
+    /// computed from the digests of the others.
+    ///
+    /// [`Compression::Whole`] compresses the complete encoding at once, and
+    /// so is written from an envelope built in memory.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if `w` fails.
+    pub fn write_envelope(&self, mut w: impl Write, options: &EncodeOptions) -> Result<()> {
+        let threshold = match options.compression() {
+            Compression::None => None,
+            Compression::LargeLeavesOver(threshold) => Some(threshold),
+            Compression::Whole => {
+                w.write_all(&self.to_envelope_with(options)?.to_cbor_data())?;
+                return Ok(());
+            }
+        };
+
+        let mut assertions: Vec<_> = self
+            .top_level_assertions()
+            .into_iter()
+            .map(|assertion| (self.assertion_envelope(&assertion).digest(), assertion))
+            .collect();
+        assertions.sort_by_key(|(digest, _)| *digest);
+        assertions.dedup_by(|(a, _), (b, _)| a == b);
+        if options.content_digest() {
+            let subject = Envelope::new(self.id()).digest();
+            let digests: Vec<_> = std::iter::once(subject)
+                .chain(assertions.iter().map(|(digest, _)| *digest))
+                .collect();
+            let assertion = TopLevel::ContentDigest(Digest::from_digests(&digests));
+            assertions.push((self.assertion_envelope(&assertion).digest(), assertion));
+            assertions.sort_by_key(|(digest, _)| *digest);
+        }
+
+        write_header(&mut w, MAJOR_TAG, TAG_ENVELOPE)?;
+        write_header(&mut w, MAJOR_ARRAY, assertions.len() as u64 + 1)?;
+        w.write_all(&Envelope::new(self.id()).untagged_cbor().to_cbor_data())?;
+        for (_, assertion) in &assertions {
+            let mut envelope = self.assertion_envelope(assertion);
+            if let Some(threshold) = threshold {
+                envelope = compress_large_leaves(envelope, threshold);
+            }
+            w.write_all(&envelope.untagged_cbor().to_cbor_data())?;
+        }
+        Ok(())
+    }
+
+    /// Reads a Zewif from the tagged CBOR of its envelope, as written by
+    /// [`Zewif::write_envelope`] or [`Zewif::save`].
+    ///
+    /// The top-level elements of the envelope are read one at a time, so the
+    /// encoding is never copied into memory as a whole. Compressed envelopes
+    /// and envelopes of earlier versions of the format are accepted, as by
+    /// [`Zewif::try_from`](Envelope).
+    ///
+    /// # Errors
+    /// - [`Error::Io`] if `r` fails or ends before the envelope does.
+    /// - [`Error::NotZewifEnvelope`] if `r` does not begin with an envelope.
+    /// - A CBOR, envelope, or decoding error if the envelope is malformed.
+    pub fn read_envelope(r: impl Read) -> Result<Zewif> {
+        let mut r = BufReader::new(r);
+        let mut buf = Vec::new();
+        if read_header(&mut r, &mut buf)? != (MAJOR_TAG, TAG_ENVELOPE) {
+            return Err(Error::NotZewifEnvelope);
+        }
+
+        buf.clear();
+        let (major, value) = read_header(&mut r, &mut buf)?;
+        let cbor = if major == MAJOR_ARRAY {
+            let mut elements = Vec::new();
+            for _ in 0..value {
+                buf.clear();
+                read_item(&mut r, &mut buf)?;
+                elements.push(CBOR::try_from_data(&buf)?);
+            }
+            CBOR::from(elements)
+        } else {
+            read_item_body(&mut r, &mut buf, major, value)?;
+            CBOR::try_from_data(&buf)?
+        };
+        Zewif::try_from(Envelope::from_untagged_cbor(cbor)?)
+    }
+
+    /// Lists the top-level assertions of the Zewif's envelope, as added by
+    /// `From<Zewif> for Envelope`.
+    fn top_level_assertions(&self) -> Vec<TopLevel<'_>> {
+        let mut assertions = vec![TopLevel::Type, TopLevel::Version, TopLevel::ExportHeight];
+        assertions.extend((0..self.wallets().len()).map(TopLevel::Wallet));
+        assertions.extend(self.transactions().values().map(TopLevel::Transaction));
+        assertions.extend(
+            self.attachments()
+                .add_to_envelope(Envelope::unit())
+                .assertions()
+                .into_iter()
+                .map(TopLevel::Attachment),
+        );
+        assertions
+    }
+
+    fn assertion_envelope(&self, assertion: &TopLevel<'_>) -> Envelope {
+        match assertion {
+            TopLevel::Type => Envelope::new_assertion(known_values::IS_A, "Zewif"),
+            TopLevel::Version => Envelope::new_assertion("zewifVersion", Zewif::VERSION),
+            TopLevel::Wallet(index) => {
+                Envelope::new_assertion("wallet", self.wallets()[*index].clone())
+            }
+            TopLevel::Transaction(transaction) => {
+                Envelope::new_assertion("transaction", (*transaction).clone())
+            }
+            TopLevel::ExportHeight => {
+                Envelope::new_assertion("export_height", self.export_height())
+            }
+            TopLevel::Attachment(attachment) => attachment.clone(),
+            TopLevel::ContentDigest(digest) => Envelope::new_assertion("contentDigest", *digest),
+        }
+    }
+}
+
+/// Compresses the large leaves of one top-level assertion, as
+/// [`ZewifEnvelope::compress_large_leaves`](crate::ZewifEnvelope::compress_large_leaves)
+/// does for the whole envelope.
+fn compress_large_leaves(envelope: Envelope, threshold: usize) -> Envelope {
+    let targets = RefCell::new(HashSet::new());
+    envelope.walk(false, (), &|envelope, _, _, _| {
+        if envelope.is_leaf() && envelope.to_cbor_data().len() > threshold {
+            targets.borrow_mut().insert(envelope.digest());
+        }
+        ((), false)
+    });
+    envelope.elide_removing_set_with_action(&targets.into_inner(), &ObscureAction::Compress)
+}
+
+/// Writes the shortest CBOR header of the given major type and value.
+fn write_header(w: &mut impl Write, major: u8, value: u64) -> io::Result<()> {
+    let major = major << 5;
+    match value {
+        0..=23 => w.write_all(&[major | value as u8]),
+        24..=0xff => w.write_all(&[major | 24, value as u8]),
+        0x100..=0xffff => {
+            w.write_all(&[major | 25])?;
+            w.write_all(&(value as u16).to_be_bytes())
+        }
+        0x10000..=0xffff_ffff => {
+            w.write_all(&[major | 26])?;
+            w.write_all(&(value as u32).to_be_bytes())
+        }
+        _ => {
+            w.write_all(&[major | 27])?;
+            w.write_all(&value.to_be_bytes())
+        }
+    }
+}
+
+/// Reads a CBOR header, appending its bytes to `buf`, and returns its major
+/// type and value.
+fn read_header(r: &mut impl BufRead, buf: &mut Vec<u8>) -> Result<(u8, u64)> {
+    let mut initial = [0u8];
+    r.read_exact(&mut initial)?;
+    buf.push(initial[0]);
+    let (major, info) = (initial[0] >> 5, initial[0] & 0x1f);
+    let len = match info {
+        0..=23 => return Ok((major, info as u64)),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        _ => return Err(dcbor::Error::UnsupportedHeaderValue(info).into()),
+    };
+    let mut bytes = [0u8; 8];
+    r.read_exact(&mut bytes[8 - len..])?;
+    buf.extend_from_slice(&bytes[8 - len..]);
+    Ok((major, u64::from_be_bytes(bytes)))
+}
+
+/// Reads one complete CBOR data item, appending its bytes to `buf`.
+fn read_item(r: &mut impl BufRead, buf: &mut Vec<u8>) -> Result<()> {
+    let (major, value) = read_header(r, buf)?;
+    read_item_body(r, buf, major, value)
+}
+
+/// Reads the rest of a CBOR data item whose header has been read.
+fn read_item_body(r: &mut impl BufRead, buf: &mut Vec<u8>, major: u8, value: u64) -> Result<()> {
+    match major {
+        // Byte and text strings.
+        2 | 3 => {
+            let start = buf.len();
+            r.take(value).read_to_end(buf)?;
+            if ((buf.len() - start) as u64) < value {
+                return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()));
+            }
+        }
+        // Arrays and maps.
+        4 => (0..value).try_for_each(|_| read_item(r, buf))?,
+        5 => (0..value.saturating_mul(2)).try_for_each(|_| read_item(r, buf))?,
+        // Tagged values.
+        6 => read_item(r, buf)?,
+        // Integers and simple values are complete.
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, BlockHeight, Compression, Data, EncodeOptions, Error, Network, RandomInstance,
+        Transaction, TxId, Zewif, ZewifWallet,
+    };
+
+    fn large_zewif() -> Zewif {
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        for index in 0..3 {
+            let mut account = Account::new();
+            account.set_name(format!("Account {}", index));
+            let mut wallet = ZewifWallet::new(Network::Main);
+            wallet.add_account(account);
+            zewif.add_wallet(wallet);
+        }
+        for n in 0..2_000u32 {
+            let mut bytes = [0u8; 32];
+            bytes[..4].copy_from_slice(&n.to_le_bytes());
+            let txid = TxId::from_bytes(bytes);
+            let mut transaction = Transaction::new(txid);
+            transaction.set_raw(Data::from_vec(bytes.repeat(n as usize % 8 + 1)));
+            transaction.set_mined_height(BlockHeight::from(1_000_000 + n));
+            zewif.add_transaction(txid, transaction).unwrap();
+        }
+        zewif
+    }
+
+    fn options(compression: Compression) -> EncodeOptions {
+        let mut options = EncodeOptions::new();
+        options.set_compression(compression);
+        options
+    }
+
+    #[test]
+    fn test_streamed_matches_in_memory() {
+        let zewif = large_zewif();
+        for compression in [
+            Compression::None,
+            Compression::LargeLeavesOver(100),
+            Compression::Whole,
+        ] {
+            for content_digest in [false, true] {
+                let mut options = options(compression);
+                options.set_content_digest(content_digest);
+                let mut streamed = Vec::new();
+                zewif.write_envelope(&mut streamed, &options).unwrap();
+                let expected = zewif.to_envelope_with(&options).unwrap().to_cbor_data();
+                assert_eq!(streamed, expected, "{:?}, {}", compression, content_digest);
+                assert_eq!(Zewif::read_envelope(streamed.as_slice()).unwrap(), zewif);
+            }
+        }
+
+        for _ in 0..10 {
+            let zewif = Zewif::random();
+            let mut streamed = Vec::new();
+            zewif
+                .write_envelope(&mut streamed, &EncodeOptions::new())
+                .unwrap();
+            assert_eq!(streamed, Envelope::from(zewif.clone()).to_cbor_data());
+            assert_eq!(Zewif::read_envelope(streamed.as_slice()).unwrap(), zewif);
+        }
+    }
+
+    #[test]
+    fn test_read_malformed_envelope() {
+        let mut streamed = Vec::new();
+        large_zewif()
+            .write_envelope(&mut streamed, &EncodeOptions::new())
+            .unwrap();
+        let truncated = &streamed[..streamed.len() / 2];
+        assert!(matches!(Zewif::read_envelope(truncated), Err(Error::Io(_))));
+
+        let not_envelope = CBOR::from("not an envelope").to_cbor_data();
+        assert!(matches!(
+            Zewif::read_envelope(not_envelope.as_slice()),
+            Err(Error::NotZewifEnvelope)
+        ));
+    }
+}
@@ -0,0 +1,395 @@
+//! Migration of envelopes written by earlier versions of the format.
+//!
+//! The top-level envelope records its layout in a `zewifVersion` assertion;
+//! envelopes without one are version 1. Decoding accepts the current version
+//! and any earlier version that can be migrated to it, one version at a time,
+//! and rejects later versions, whose layout this crate cannot know.
+//!
+//! Each layout shipped by a release is pinned by a fixture in `test_data`,
+//! written by that release, which the tests below decode.
+
+use std::collections::HashSet;
+
+use bc_envelope::prelude::*;
+
+use crate::{
+    Error, Result, SeedMaterial, Transaction, TxId, WalletSeed, Zewif,
+    envelope_decoder::EnvelopeDecoder,
+};
+
+/// The version assumed for envelopes that carry no `zewifVersion` assertion.
+const UNVERSIONED: u32 = 1;
+
+/// Returns the version of the format of an uncompressed top-level envelope.
+pub(crate) fn envelope_version(envelope: &Envelope) -> Result<u32> {
+    Ok(EnvelopeDecoder::new(envelope.clone(), "Zewif")
+        .extract_optional::<u32>("zewifVersion")?
+        .unwrap_or(UNVERSIONED))
+}
+
+/// Brings a top-level envelope up to [`Zewif::VERSION`].
+///
+/// Fails with [`Error::UnsupportedVersion`] if the version is unknown or the
+/// envelope does not have the layout its version promises.
+pub(crate) fn upgraded(envelope: Envelope) -> Result<Envelope> {
+    let version = envelope_version(&envelope)?;
+    let unsupported = |guidance: String| Error::UnsupportedVersion {
+        found: version,
+        supported: Zewif::VERSION,
+        guidance,
+    };
+    if version < UNVERSIONED {
+        return Err(unsupported(format!(
+            "no release writes version {}, so the envelope was not exported by zewif or is corrupt",
+            version
+        )));
+    }
+    if version > Zewif::VERSION {
+        return Err(unsupported(
+            "upgrade zewif to a release that reads it".to_string(),
+        ));
+    }
+    (version..Zewif::VERSION)
+        .try_fold(envelope, upgrade_envelope)
+        .and_then(hoist_account_transactions)
+        .map_err(|error| {
+            unsupported(format!(
+                "the envelope does not have the version {} layout ({}); re-export it from the source wallet",
+                version, error
+            ))
+        })
+}
+
+/// Migrates a top-level envelope from `version` to the next version.
+fn upgrade_envelope(envelope: Envelope, version: u32) -> Result<Envelope> {
+    match version {
+        1 => upgrade_single_seed_wallets(envelope),
+        _ => unreachable!("no migration from version {}", version),
+    }
+}
+
+/// Version 1 wallets held at most one seed, as a bare `seed_material`
+/// assertion without a fingerprint. Version 2 wallets hold any number of
+/// indexed `seed` objects.
+fn upgrade_single_seed_wallets(envelope: Envelope) -> Result<Envelope> {
+    let mut envelope = envelope;
+    for assertion in envelope.assertions_with_predicate("wallet") {
+        let wallet = assertion.try_object()?;
+        let Ok(seed_material_assertion) = wallet.assertion_with_predicate("seed_material") else {
+            continue;
+        };
+        let seed_material = SeedMaterial::try_from(seed_material_assertion.try_object()?)?;
+        let mut seed = WalletSeed::new(seed_material)?;
+        crate::Indexed::set_index(&mut seed, wallet.objects_for_predicate("seed").len());
+        let wallet = wallet
+            .remove_assertion(seed_material_assertion)
+            .add_assertion("seed", seed);
+        envelope =
+            envelope.replace_assertion(assertion, Envelope::new_assertion("wallet", wallet))?;
+    }
+    Ok(envelope)
+}
+
+/// Each transaction is stored once, at the top level, and accounts refer to
+/// the transactions relevant to them by ID. Envelopes of any version that
+/// instead embed `transaction` objects in accounts are accepted: each
+/// embedded transaction is hoisted to the top level, where identical copies
+/// embedded in several accounts collapse into one, and its ID is added to the
+/// account's relevant transactions.
+fn hoist_account_transactions(envelope: Envelope) -> Result<Envelope> {
+    let mut envelope = envelope;
+    for wallet_assertion in envelope.assertions_with_predicate("wallet") {
+        let mut wallet = wallet_assertion.try_object()?;
+        let mut hoisted = false;
+        for account_assertion in wallet.assertions_with_predicate("account") {
+            let mut account = account_assertion.try_object()?;
+            let embedded = account.assertions_with_predicate("transaction");
+            if embedded.is_empty() {
+                continue;
+            }
+            let mut relevant: HashSet<TxId> = account
+                .extract_optional_object_for_predicate("relevant_transactions")?
+                .unwrap_or_default();
+            for assertion in embedded {
+                let transaction = assertion.try_object()?;
+                relevant.insert(Transaction::try_from(transaction.clone())?.txid());
+                account = account.remove_assertion(assertion);
+                envelope = envelope.add_assertion("transaction", transaction);
+            }
+            if let Ok(assertion) = account.assertion_with_predicate("relevant_transactions") {
+                account = account.remove_assertion(assertion);
+            }
+            account =
+                account.add_assertion("relevant_transactions", relevant.sort_by_cbor_encoding());
+            wallet = wallet.replace_assertion(
+                account_assertion,
+                Envelope::new_assertion("account", account),
+            )?;
+            hoisted = true;
+        }
+        if hoisted {
+            envelope = envelope
+                .replace_assertion(wallet_assertion, Envelope::new_assertion("wallet", wallet))?;
+        }
+    }
+    Ok(envelope)
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Amount, BlockHeight, Error, Network, ProtocolAddress, SeedMaterial, TxId, Zewif,
+        sapling::SaplingExtendedSpendingKey,
+        transparent::{TransparentSpendAuthority, TransparentSpendingKey},
+    };
+
+    fn fixture(hex_lines: &str) -> Envelope {
+        let data = hex::decode(hex_lines.split_whitespace().collect::<String>()).unwrap();
+        Envelope::try_from_cbor_data(data).unwrap()
+    }
+
+    /// A version 1 Zewif, written before the `zewifVersion` assertion, whose
+    /// one wallet holds a single seed in the legacy layout.
+    #[test]
+    fn test_upgrade_single_seed_fixture() {
+        let envelope = fixture(include_str!("test_data/zewif_v1_single_seed.hex"));
+        assert!(
+            envelope
+                .assertions_with_predicate("zewifVersion")
+                .is_empty()
+        );
+
+        let zewif = Zewif::try_from(envelope).unwrap();
+        assert_eq!(zewif.wallets()[0].network(), Network::Main);
+        let seeds = zewif.wallets()[0].seeds();
+        assert_eq!(seeds.len(), 1);
+        let SeedMaterial::Bip39Mnemonic(mnemonic) = seeds[0].seed_material() else {
+            panic!("unexpected seed material");
+        };
+        assert!(mnemonic.mnemonic().starts_with("abandon abandon"));
+        assert_eq!(
+            seeds[0].fingerprint(),
+            Some(&seeds[0].seed_material().fingerprint().unwrap())
+        );
+
+        // Re-encoding writes the current version.
+        let envelope = Envelope::from(zewif.clone());
+        assert_eq!(
+            envelope
+                .extract_object_for_predicate::<u32>("zewifVersion")
+                .unwrap(),
+            Zewif::VERSION
+        );
+        assert_eq!(Zewif::try_from(envelope).unwrap(), zewif);
+    }
+
+    #[test]
+    fn test_hoist_account_transactions() {
+        use crate::{Account, BlockHeight, Network, Transaction, TxId, ZewifWallet};
+
+        let shared = TxId::from_bytes([1; 32]);
+        let mut transaction = Transaction::new(shared);
+        transaction.set_mined_height(BlockHeight::from(1_000_000));
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(Account::new());
+        wallet.add_account(Account::new());
+        let zewif = {
+            let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+            zewif.add_wallet(wallet);
+            zewif
+        };
+
+        // Embed a copy of the transaction in each account.
+        let envelope = Envelope::from(zewif.clone());
+        let wallet_assertion = envelope.assertion_with_predicate("wallet").unwrap();
+        let mut wallet = wallet_assertion.try_object().unwrap();
+        for account_assertion in wallet.assertions_with_predicate("account") {
+            let account = account_assertion
+                .try_object()
+                .unwrap()
+                .add_assertion("transaction", transaction.clone());
+            wallet = wallet
+                .replace_assertion(
+                    account_assertion,
+                    Envelope::new_assertion("account", account),
+                )
+                .unwrap();
+        }
+        let envelope = envelope
+            .replace_assertion(wallet_assertion, Envelope::new_assertion("wallet", wallet))
+            .unwrap();
+
+        let decoded = Zewif::try_from(envelope).unwrap();
+        assert_eq!(decoded.transactions().len(), 1);
+        assert_eq!(decoded.transaction(&shared), Some(&transaction));
+        for account in decoded.wallets()[0].accounts() {
+            assert!(account.contains_transaction(shared));
+        }
+        let envelope = Envelope::from(decoded);
+        assert_eq!(envelope.objects_for_predicate("transaction").len(), 1);
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let zewif = Zewif::new(crate::BlockHeight::from(100));
+        let with_version = |version: u32| {
+            let envelope = Envelope::from(zewif.clone());
+            let assertion = envelope.assertion_with_predicate("zewifVersion").unwrap();
+            envelope
+                .remove_assertion(assertion)
+                .add_assertion("zewifVersion", version)
+        };
+        for found in [0, Zewif::VERSION + 1] {
+            let error = Zewif::try_from(with_version(found)).unwrap_err();
+            assert!(matches!(
+                error,
+                Error::UnsupportedVersion { found: f, supported, .. }
+                    if f == found && supported == Zewif::VERSION
+            ));
+        }
+        assert!(
+            Zewif::try_from(with_version(0))
+                .unwrap_err()
+                .to_string()
+                .contains("not exported by zewif")
+        );
+        assert!(
+            Zewif::try_from(with_version(Zewif::VERSION + 1))
+                .unwrap_err()
+                .to_string()
+                .contains("upgrade zewif")
+        );
+        assert_eq!(
+            Zewif::try_from(with_version(Zewif::VERSION)).unwrap(),
+            zewif
+        );
+    }
+
+    /// A version 1 envelope whose seed material cannot be migrated.
+    #[test]
+    fn test_failed_migration() {
+        let envelope = fixture(include_str!("test_data/zewif_v1_single_seed.hex"));
+        let wallet_assertion = envelope.assertion_with_predicate("wallet").unwrap();
+        let wallet = wallet_assertion.try_object().unwrap();
+        let seed_material = wallet.assertion_with_predicate("seed_material").unwrap();
+        let wallet = wallet
+            .remove_assertion(seed_material)
+            .add_assertion("seed_material", "not a seed");
+        let envelope = envelope
+            .replace_assertion(wallet_assertion, Envelope::new_assertion("wallet", wallet))
+            .unwrap();
+
+        let error = Zewif::try_from(envelope).unwrap_err();
+        let Error::UnsupportedVersion {
+            found: 1,
+            ref guidance,
+            ..
+        } = error
+        else {
+            panic!("unexpected error: {}", error);
+        };
+        assert!(guidance.contains("version 1 layout"));
+    }
+
+    /// Checks the contents shared by the fixtures written by each release.
+    fn check_release_fixture(zewif: &Zewif) {
+        let txid = TxId::from_bytes([0x11; 32]);
+        assert_eq!(zewif.export_height(), BlockHeight::from(2_100_000));
+        let transaction = zewif.transaction(&txid).unwrap();
+        assert_eq!(
+            transaction.mined_height(),
+            Some(&BlockHeight::from(2_000_000))
+        );
+        assert_eq!(
+            transaction.raw().unwrap().as_ref(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+
+        let wallet = &zewif.wallets()[0];
+        assert_eq!(wallet.network(), Network::Main);
+        assert_eq!(wallet.seeds().len(), 1);
+        assert!(matches!(
+            wallet.seeds()[0].seed_material(),
+            SeedMaterial::Bip39Mnemonic(_)
+        ));
+
+        let account = &wallet.accounts()[0];
+        assert_eq!(account.name(), "Legacy account");
+        assert_eq!(
+            account.birthday_height(),
+            Some(BlockHeight::from(1_900_000))
+        );
+        assert!(account.contains_transaction(txid));
+        let addresses = account.addresses();
+        assert_eq!(addresses.len(), 3);
+        let ProtocolAddress::Transparent(transparent) = addresses[0].address() else {
+            panic!("unexpected address: {:?}", addresses[0]);
+        };
+        assert_eq!(
+            transparent.spend_authority(),
+            Some(&TransparentSpendAuthority::SpendingKey(
+                TransparentSpendingKey::new([0x22; 32])
+            ))
+        );
+        assert_eq!(addresses[0].name(), "change");
+        let ProtocolAddress::Sapling(sapling) = addresses[1].address() else {
+            panic!("unexpected address: {:?}", addresses[1]);
+        };
+        assert_eq!(
+            sapling.spending_key(),
+            Some(&SaplingExtendedSpendingKey::new([0x33; 169]))
+        );
+        assert_eq!(sapling.hd_derivation_path(), Some("m/32'/133'/0'"));
+        assert!(matches!(
+            addresses[2].address(),
+            ProtocolAddress::Unified(_)
+        ));
+        assert_eq!(
+            account.sapling_sent_outputs()[0].value(),
+            Amount::from_u64(50_000).unwrap()
+        );
+        assert_eq!(
+            account.orchard_sent_outputs()[0].recipient_address(),
+            "u1recipient"
+        );
+
+        // Re-encoding writes the current version.
+        let envelope = Envelope::from(zewif.clone());
+        assert_eq!(
+            envelope
+                .extract_object_for_predicate::<u32>("zewifVersion")
+                .unwrap(),
+            Zewif::VERSION
+        );
+        assert_eq!(&Zewif::try_from(envelope).unwrap(), zewif);
+    }
+
+    /// The layout written by the 0.1.0 release, before the `zewifVersion`
+    /// assertion.
+    #[test]
+    fn test_baseline_release_fixture() {
+        let envelope = fixture(include_str!("test_data/zewif_v1_baseline.hex"));
+        assert!(
+            envelope
+                .assertions_with_predicate("zewifVersion")
+                .is_empty()
+        );
+        check_release_fixture(&Zewif::try_from(envelope).unwrap());
+    }
+
+    /// The version 2 layout as first written, before key material, account
+    /// identifiers, and the other additions made since.
+    #[test]
+    fn test_initial_v2_fixture() {
+        let envelope = fixture(include_str!("test_data/zewif_v2_initial.hex"));
+        assert_eq!(
+            envelope
+                .extract_object_for_predicate::<u32>("zewifVersion")
+                .unwrap(),
+            2
+        );
+        check_release_fixture(&Zewif::try_from(envelope).unwrap());
+    }
+}
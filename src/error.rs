@@ -5,7 +5,16 @@ use std::{
 };
 use thiserror::Error;
 
+use crate::{
+    Amount, DuplicateAddress, MnemonicLanguage, OutPoint, SeedFingerprint, SeedStatus, TxId,
+};
+
+/// The errors returned by this crate.
+///
+/// New variants may be added in minor releases; bindings that need a stable
+/// classification should use [`Error::code`].
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     // Catch-all string context that wraps any source error
     #[error("{message}")]
@@ -16,8 +25,14 @@ pub enum Error {
     },
 
     // Validation errors
-    #[error("Invalid network identifier: {0}")]
-    InvalidNetwork(String),
+    #[error(
+        "Invalid network{}: {reason}",
+        network.as_ref().map(|n| format!(" {}", n)).unwrap_or_default()
+    )]
+    InvalidNetwork {
+        network: Option<String>,
+        reason: String,
+    },
 
     #[error("Amount underflow: {0}")]
     AmountUnderflow(u64),
@@ -25,12 +40,27 @@ pub enum Error {
     #[error("Amount overflow: {0}")]
     AmountOverflow(u64),
 
+    #[error("{pool} note has negative value {} ZEC", value.to_zec_string())]
+    NegativeNoteValue { pool: &'static str, value: Amount },
+
+    #[error("Invalid ZEC amount {amount:?}: {reason}")]
+    InvalidAmount { amount: String, reason: String },
+
     #[error("Invalid SeedMaterial envelope")]
     InvalidSeedMaterial,
 
     #[error("Envelope is not a Zewif envelope")]
     NotZewifEnvelope,
 
+    #[error(
+        "Unsupported Zewif version {found}; this version of zewif reads versions up to {supported}: {guidance}"
+    )]
+    UnsupportedVersion {
+        found: u32,
+        supported: u32,
+        guidance: String,
+    },
+
     #[error(
         "Cannot compress a Zewif that has already been compressed or encrypted"
     )]
@@ -45,11 +75,71 @@ pub enum Error {
     #[error("Cannot decrypt a Zewif that has not been encrypted")]
     NotEncrypted,
 
-    #[error("Invalid language value: {0}")]
-    InvalidLanguage(String),
+    #[error("Zewif is encrypted; decrypt it before decoding")]
+    EncryptedZewif,
+
+    #[error("Zewif is not encrypted with a password")]
+    NotPasswordEncrypted,
+
+    #[error("Zewif has no contentDigest assertion to verify")]
+    ContentDigestMissing,
+
+    #[error("Zewif content digest mismatch: expected {expected}, computed {actual}")]
+    ContentDigestMismatch { expected: String, actual: String },
+
+    #[error("Wrong password, or the encrypted Zewif is corrupted")]
+    WrongPassword,
+
+    #[error("The private keys are not those of a recipient of the encrypted Zewif")]
+    NotARecipient,
+
+    #[error("Cannot encrypt a Zewif to an empty list of recipients")]
+    NoRecipients,
+
+    #[error("The Zewif is not signed by the given key, or it has been altered since it was signed")]
+    InvalidSignature,
+
+    #[error("Invalid key derivation parameter {parameter}: {reason}")]
+    InvalidKdfParams {
+        parameter: &'static str,
+        reason: String,
+    },
+
+    #[error("Not enough SSKR shares to recover the Zewif")]
+    InsufficientShares,
+
+    #[error("SSKR shares come from different sharding operations")]
+    MixedShares,
+
+    #[error("SSKR share {index} is invalid: {reason}")]
+    InvalidShare { index: usize, reason: String },
+
+    #[error("{kind} {id} is not in the Zewif")]
+    NotInZewif { kind: &'static str, id: String },
+
+    #[error("Merge conflict: {element} {reason}")]
+    MergeConflict { element: String, reason: String },
 
-    #[error("Invalid MnemonicLanguage string: {0}")]
-    InvalidMnemonicLanguage(String),
+    #[error("Transaction {txid} conflicts with the copy already in the Zewif")]
+    ConflictingTransaction { txid: TxId },
+
+    #[error("Duplicate address: {0}")]
+    DuplicateAddress(DuplicateAddress),
+
+    #[error(
+        "Invalid account order: expected each of the {accounts} identifiers of the wallet's \
+         accounts once, but got {identifiers} identifiers"
+    )]
+    InvalidAccountOrder { accounts: usize, identifiers: usize },
+
+    #[error("Invalid Zewif: {}", problems.join("; "))]
+    InvalidZewif { problems: Vec<String> },
+
+    #[error("Invalid language value: {value}")]
+    InvalidLanguage { value: u32 },
+
+    #[error("Invalid MnemonicLanguage string: {name}")]
+    InvalidMnemonicLanguage { name: String },
 
     #[error("Invalid TransparentSpendAuthority envelope")]
     InvalidTransparentSpendAuthority,
@@ -57,6 +147,100 @@ pub enum Error {
     #[error("Invalid ProtocolAddress type")]
     InvalidProtocolAddress,
 
+    #[error("Invalid witness: {reason}")]
+    InvalidWitness { reason: String },
+
+    #[error("Witness anchor is not a known {pool} anchor")]
+    UnknownAnchor { pool: &'static str },
+
+    #[error("Witness Merkle path does not lead to its anchor")]
+    WitnessRootMismatch,
+
+    #[error("Invalid tree state: {reason}")]
+    InvalidTreeState { reason: String },
+
+    #[error("Invalid BIP-39 mnemonic: {0}")]
+    InvalidMnemonic(#[source] bip39::Error),
+
+    #[error(
+        "Cannot detect the mnemonic's language: {}",
+        undetected_language(candidates)
+    )]
+    UndetectedMnemonicLanguage { candidates: Vec<MnemonicLanguage> },
+
+    #[error("Seed unavailable: {}", unavailable_seed(status))]
+    SeedUnavailable { status: SeedStatus },
+
+    #[error("No seed in the wallet has fingerprint {}", fingerprint.to_hex())]
+    UnknownSeedFingerprint { fingerprint: SeedFingerprint },
+
+    #[error("Seed fingerprint mismatch: expected {}, got {}", expected.to_hex(), actual.to_hex())]
+    SeedFingerprintMismatch {
+        expected: SeedFingerprint,
+        actual: SeedFingerprint,
+    },
+
+    #[error("Invalid seed length: {0} bytes")]
+    InvalidSeedLength(usize),
+
+    #[error("Key derivation mismatch for {derivation}: {reason}")]
+    DerivationMismatch { derivation: String, reason: String },
+
+    #[error("Invalid derivation path: {path}")]
+    InvalidDerivationPath { path: String },
+
+    #[error("Child index {index} is too large for a hardened derivation")]
+    ChildIndexTooLarge { index: u32 },
+
+    #[error("Unified key is for the wrong network: expected HRP {expected}, got {actual}")]
+    UnifiedKeyNetworkMismatch { expected: String, actual: String },
+
+    #[error(
+        "Invalid unified key{}: {reason}",
+        typecode.map(|t| format!(" item with typecode {}", t)).unwrap_or_default()
+    )]
+    InvalidUnifiedKey {
+        typecode: Option<u32>,
+        reason: String,
+    },
+
+    #[error("Invalid Base58Check encoding: {reason}")]
+    InvalidBase58Check { reason: String },
+
+    #[error("Invalid UR: {reason}")]
+    InvalidUR { reason: String },
+
+    #[error("Invalid address {address}: {reason}")]
+    InvalidAddress { address: String, reason: String },
+
+    #[error("Address {address} is not valid for the {expected} network")]
+    AddressNetworkMismatch { address: String, expected: String },
+
+    #[error("{element} is for the {detected} network, but the wallet is on the {expected} network")]
+    NetworkMismatch {
+        element: String,
+        expected: String,
+        detected: String,
+    },
+
+    #[error("Cannot resolve the value of transparent input {outpoint}")]
+    UnresolvedInput { outpoint: OutPoint },
+
+    #[error("Transaction {txid} has no raw bytes")]
+    MissingRawTransaction { txid: TxId },
+
+    #[error("Unsupported transaction version {version}")]
+    UnsupportedTransactionVersion { version: u32 },
+
+    #[error("Invalid raw transaction: {reason}")]
+    InvalidRawTransaction { reason: String },
+
+    #[error("Invalid viewing key: {reason}")]
+    InvalidViewingKey { reason: String },
+
+    #[error("Key is not valid for the {expected} network")]
+    KeyNetworkMismatch { expected: String },
+
     #[error("Hex parsing error: expected {expected} bytes, got {actual}")]
     HexLengthMismatch { expected: usize, actual: usize },
 
@@ -66,11 +250,180 @@ pub enum Error {
     #[error("Envelope error: {0}")]
     EnvelopeError(#[from] bc_envelope::Error),
 
+    /// An object nested within an envelope could not be decoded. The path
+    /// names each enclosing object, outermost first, such as
+    /// `["Zewif", "Wallet[0]", "Account[\"Savings\"]"]`, and the predicate,
+    /// if known, is the assertion of the innermost object that failed.
+    ///
+    /// The diagnostic, if requested with
+    /// [`Zewif::from_envelope_with_diagnostics`](crate::Zewif::from_envelope_with_diagnostics),
+    /// is the failing object in CBOR diagnostic notation; see
+    /// [`debug::diagnostic`](crate::debug::diagnostic).
+    #[error(
+        "{}{}: {source}{}",
+        path.join(" > "),
+        predicate.as_ref().map(|p| format!(": predicate \"{}\"", p)).unwrap_or_default(),
+        diagnostic.as_ref().map(|d| format!("\n{}", d)).unwrap_or_default()
+    )]
+    EnvelopeDecode {
+        path: Vec<String>,
+        predicate: Option<String>,
+        source: Box<Error>,
+        diagnostic: Option<String>,
+    },
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     #[error("Slice conversion error: {0}")]
     TryFromSliceError(#[from] TryFromSliceError),
 
     #[error("CBOR error: {0}")]
     CBORError(#[from] CBORError),
+
+    #[cfg(feature = "json")]
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A stable numeric classification of an [`Error`](enum@Error), for bindings that cannot
+/// match on the error enum.
+///
+/// The numeric values are part of the crate's public API and will not change;
+/// new categories may be added with new values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u32)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The error did not originate in this crate, such as the source of an
+    /// [`Error::Context`] from another library.
+    Other = 0,
+    /// Well-formed data that is inconsistent: amounts out of range, elements
+    /// for the wrong network, or references to seeds the wallet lacks.
+    Validation = 1,
+    /// A malformed string or byte encoding, such as an address, key, hex
+    /// string, mnemonic, or derivation path.
+    Parse = 2,
+    /// A malformed envelope or CBOR value, or an envelope in the wrong state
+    /// for the requested operation.
+    Envelope = 3,
+    /// A failure of key derivation, recovery, or decryption, or seed material
+    /// that cannot be used.
+    Crypto = 4,
+    /// A failure reading or writing a file.
+    Io = 5,
+}
+
+impl From<ErrorCode> for u32 {
+    fn from(value: ErrorCode) -> Self {
+        value as u32
+    }
+}
+
+impl Error {
+    /// Returns the stable classification of the error. An [`Error::Context`]
+    /// has the code of the error it wraps.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Context { source, .. } => source
+                .downcast_ref::<Error>()
+                .map_or(ErrorCode::Other, Error::code),
+
+            Error::InvalidNetwork { .. }
+            | Error::AmountUnderflow(_)
+            | Error::AmountOverflow(_)
+            | Error::NegativeNoteValue { .. }
+            | Error::InvalidWitness { .. }
+            | Error::UnknownAnchor { .. }
+            | Error::WitnessRootMismatch
+            | Error::InvalidTreeState { .. }
+            | Error::UnknownSeedFingerprint { .. }
+            | Error::UnifiedKeyNetworkMismatch { .. }
+            | Error::AddressNetworkMismatch { .. }
+            | Error::NetworkMismatch { .. }
+            | Error::ContentDigestMismatch { .. }
+            | Error::KeyNetworkMismatch { .. }
+            | Error::NotInZewif { .. }
+            | Error::MergeConflict { .. }
+            | Error::ConflictingTransaction { .. }
+            | Error::DuplicateAddress(_)
+            | Error::InvalidAccountOrder { .. }
+            | Error::UnresolvedInput { .. }
+            | Error::MissingRawTransaction { .. }
+            | Error::InvalidZewif { .. } => ErrorCode::Validation,
+
+            Error::InvalidLanguage { .. }
+            | Error::InvalidMnemonicLanguage { .. }
+            | Error::InvalidMnemonic(_)
+            | Error::UndetectedMnemonicLanguage { .. }
+            | Error::InvalidDerivationPath { .. }
+            | Error::ChildIndexTooLarge { .. }
+            | Error::InvalidUnifiedKey { .. }
+            | Error::InvalidBase58Check { .. }
+            | Error::InvalidAddress { .. }
+            | Error::InvalidUR { .. }
+            | Error::InvalidRawTransaction { .. }
+            | Error::UnsupportedTransactionVersion { .. }
+            | Error::InvalidAmount { .. }
+            | Error::InvalidViewingKey { .. }
+            | Error::HexLengthMismatch { .. }
+            | Error::InvalidHexString(_)
+            | Error::TryFromSliceError(_) => ErrorCode::Parse,
+
+            #[cfg(feature = "json")]
+            Error::Json(_) => ErrorCode::Parse,
+
+            Error::InvalidSeedMaterial
+            | Error::NotZewifEnvelope
+            | Error::UnsupportedVersion { .. }
+            | Error::AlreadyCompressedOrEncrypted
+            | Error::NotCompressed
+            | Error::AlreadyEncrypted
+            | Error::NotEncrypted
+            | Error::NotPasswordEncrypted
+            | Error::ContentDigestMissing
+            | Error::EncryptedZewif
+            | Error::InvalidTransparentSpendAuthority
+            | Error::InvalidProtocolAddress
+            | Error::EnvelopeError(_)
+            | Error::EnvelopeDecode { .. }
+            | Error::CBORError(_) => ErrorCode::Envelope,
+
+            Error::SeedUnavailable { .. }
+            | Error::InvalidSeedLength(_)
+            | Error::SeedFingerprintMismatch { .. }
+            | Error::DerivationMismatch { .. }
+            | Error::WrongPassword
+            | Error::NotARecipient
+            | Error::NoRecipients
+            | Error::InvalidSignature
+            | Error::InvalidKdfParams { .. }
+            | Error::InsufficientShares
+            | Error::MixedShares
+            | Error::InvalidShare { .. } => ErrorCode::Crypto,
+
+            Error::Io(_) => ErrorCode::Io,
+        }
+    }
+}
+
+/// Describes why [`Error::UndetectedMnemonicLanguage`] was returned.
+fn undetected_language(candidates: &[MnemonicLanguage]) -> String {
+    if candidates.is_empty() {
+        return "its words are not all from a single wordlist".to_string();
+    }
+    let names: Vec<_> = candidates.iter().map(MnemonicLanguage::name).collect();
+    format!("it is ambiguous between {}", names.join(", "))
+}
+
+/// Describes why [`Error::SeedUnavailable`] was returned.
+fn unavailable_seed(status: &SeedStatus) -> &'static str {
+    match status {
+        SeedStatus::Available => "the seed is available",
+        SeedStatus::Locked => "the seed is encrypted",
+        SeedStatus::External => "the seed is held by a hardware device",
+        SeedStatus::Redacted => "the seed was redacted",
+    }
 }
 
 impl From<Infallible> for Error {
@@ -98,3 +451,317 @@ impl From<Error> for bc_envelope::Error {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, ErrorCode};
+    use crate::{
+        Account, Address, AddressConflictPolicy, Amount, Network, OutPoint, ProtocolAddress,
+        SeedFingerprint, SeedStatus, TxId, ZewifWallet, transparent,
+    };
+
+    #[test]
+    fn test_error_codes() {
+        let s = || "x".to_string();
+        let txid = TxId::from_bytes([1; 32]);
+        let fingerprint = SeedFingerprint::from_seed(&[0; 32]).unwrap();
+        let address = Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+            "t1shared",
+        )));
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(Account::new());
+        wallet.add_account(Account::new());
+        wallet
+            .add_account_address(0, address.clone(), AddressConflictPolicy::Reject)
+            .unwrap();
+        let duplicate = wallet
+            .add_account_address(1, address, AddressConflictPolicy::Reject)
+            .unwrap_err();
+        let cases = [
+            (
+                Error::InvalidNetwork {
+                    network: None,
+                    reason: s(),
+                },
+                ErrorCode::Validation,
+            ),
+            (Error::AmountUnderflow(0), ErrorCode::Validation),
+            (Error::AmountOverflow(0), ErrorCode::Validation),
+            (
+                Error::NegativeNoteValue {
+                    pool: "Sapling",
+                    value: Amount::from_i64(-1).unwrap(),
+                },
+                ErrorCode::Validation,
+            ),
+            (Error::InvalidWitness { reason: s() }, ErrorCode::Validation),
+            (
+                Error::UnknownAnchor { pool: "Sapling" },
+                ErrorCode::Validation,
+            ),
+            (Error::WitnessRootMismatch, ErrorCode::Validation),
+            (
+                Error::InvalidTreeState { reason: s() },
+                ErrorCode::Validation,
+            ),
+            (
+                Error::UnknownSeedFingerprint { fingerprint },
+                ErrorCode::Validation,
+            ),
+            (
+                Error::UnifiedKeyNetworkMismatch {
+                    expected: s(),
+                    actual: s(),
+                },
+                ErrorCode::Validation,
+            ),
+            (
+                Error::AddressNetworkMismatch {
+                    address: s(),
+                    expected: s(),
+                },
+                ErrorCode::Validation,
+            ),
+            (
+                Error::NetworkMismatch {
+                    element: s(),
+                    expected: s(),
+                    detected: s(),
+                },
+                ErrorCode::Validation,
+            ),
+            (
+                Error::KeyNetworkMismatch { expected: s() },
+                ErrorCode::Validation,
+            ),
+            (
+                Error::NotInZewif {
+                    kind: "Account",
+                    id: s(),
+                },
+                ErrorCode::Validation,
+            ),
+            (
+                Error::MergeConflict {
+                    element: s(),
+                    reason: s(),
+                },
+                ErrorCode::Validation,
+            ),
+            (
+                Error::ConflictingTransaction { txid },
+                ErrorCode::Validation,
+            ),
+            (duplicate, ErrorCode::Validation),
+            (
+                Error::InvalidAccountOrder {
+                    accounts: 1,
+                    identifiers: 2,
+                },
+                ErrorCode::Validation,
+            ),
+            (
+                Error::UnresolvedInput {
+                    outpoint: OutPoint::new(txid, 0),
+                },
+                ErrorCode::Validation,
+            ),
+            (Error::MissingRawTransaction { txid }, ErrorCode::Validation),
+            (
+                Error::InvalidZewif {
+                    problems: vec![s()],
+                },
+                ErrorCode::Validation,
+            ),
+            (
+                Error::ContentDigestMismatch {
+                    expected: s(),
+                    actual: s(),
+                },
+                ErrorCode::Validation,
+            ),
+            (Error::InvalidLanguage { value: 9 }, ErrorCode::Parse),
+            (
+                Error::InvalidMnemonicLanguage { name: s() },
+                ErrorCode::Parse,
+            ),
+            (
+                Error::InvalidMnemonic(bip39::Error::BadWordCount(1)),
+                ErrorCode::Parse,
+            ),
+            (
+                Error::UndetectedMnemonicLanguage { candidates: vec![] },
+                ErrorCode::Parse,
+            ),
+            (Error::InvalidDerivationPath { path: s() }, ErrorCode::Parse),
+            (
+                Error::ChildIndexTooLarge { index: u32::MAX },
+                ErrorCode::Parse,
+            ),
+            (
+                Error::InvalidUnifiedKey {
+                    typecode: Some(3),
+                    reason: s(),
+                },
+                ErrorCode::Parse,
+            ),
+            (Error::InvalidBase58Check { reason: s() }, ErrorCode::Parse),
+            (
+                Error::InvalidAddress {
+                    address: s(),
+                    reason: s(),
+                },
+                ErrorCode::Parse,
+            ),
+            (Error::InvalidUR { reason: s() }, ErrorCode::Parse),
+            (
+                Error::InvalidRawTransaction { reason: s() },
+                ErrorCode::Parse,
+            ),
+            (
+                Error::UnsupportedTransactionVersion { version: 6 },
+                ErrorCode::Parse,
+            ),
+            (
+                Error::InvalidAmount {
+                    amount: s(),
+                    reason: s(),
+                },
+                ErrorCode::Parse,
+            ),
+            (Error::InvalidViewingKey { reason: s() }, ErrorCode::Parse),
+            (
+                Error::HexLengthMismatch {
+                    expected: 1,
+                    actual: 2,
+                },
+                ErrorCode::Parse,
+            ),
+            (
+                Error::InvalidHexString(hex::FromHexError::OddLength),
+                ErrorCode::Parse,
+            ),
+            (
+                Error::TryFromSliceError(<[u8; 1]>::try_from(&[][..]).unwrap_err()),
+                ErrorCode::Parse,
+            ),
+            (Error::InvalidSeedMaterial, ErrorCode::Envelope),
+            (Error::NotZewifEnvelope, ErrorCode::Envelope),
+            (
+                Error::UnsupportedVersion {
+                    found: 3,
+                    supported: 2,
+                    guidance: s(),
+                },
+                ErrorCode::Envelope,
+            ),
+            (Error::AlreadyCompressedOrEncrypted, ErrorCode::Envelope),
+            (Error::NotCompressed, ErrorCode::Envelope),
+            (Error::AlreadyEncrypted, ErrorCode::Envelope),
+            (Error::NotEncrypted, ErrorCode::Envelope),
+            (Error::NotPasswordEncrypted, ErrorCode::Envelope),
+            (Error::ContentDigestMissing, ErrorCode::Envelope),
+            (Error::EncryptedZewif, ErrorCode::Envelope),
+            (Error::InvalidTransparentSpendAuthority, ErrorCode::Envelope),
+            (Error::InvalidProtocolAddress, ErrorCode::Envelope),
+            (
+                Error::EnvelopeError(bc_envelope::Error::InvalidFormat),
+                ErrorCode::Envelope,
+            ),
+            (
+                Error::CBORError(dcbor::Error::WrongType),
+                ErrorCode::Envelope,
+            ),
+            (
+                Error::EnvelopeDecode {
+                    path: vec![s()],
+                    predicate: None,
+                    source: Box::new(Error::InvalidSeedMaterial),
+                    diagnostic: None,
+                },
+                ErrorCode::Envelope,
+            ),
+            (
+                Error::SeedUnavailable {
+                    status: SeedStatus::Locked,
+                },
+                ErrorCode::Crypto,
+            ),
+            (Error::InvalidSeedLength(0), ErrorCode::Crypto),
+            (
+                Error::SeedFingerprintMismatch {
+                    expected: fingerprint,
+                    actual: fingerprint,
+                },
+                ErrorCode::Crypto,
+            ),
+            (
+                Error::DerivationMismatch {
+                    derivation: s(),
+                    reason: s(),
+                },
+                ErrorCode::Crypto,
+            ),
+            (Error::WrongPassword, ErrorCode::Crypto),
+            (Error::NotARecipient, ErrorCode::Crypto),
+            (Error::NoRecipients, ErrorCode::Crypto),
+            (Error::InvalidSignature, ErrorCode::Crypto),
+            (Error::InsufficientShares, ErrorCode::Crypto),
+            (Error::MixedShares, ErrorCode::Crypto),
+            (
+                Error::InvalidShare {
+                    index: 0,
+                    reason: s(),
+                },
+                ErrorCode::Crypto,
+            ),
+            (
+                Error::InvalidKdfParams {
+                    parameter: "memlimit",
+                    reason: s(),
+                },
+                ErrorCode::Crypto,
+            ),
+            (
+                Error::Io(std::io::ErrorKind::NotFound.into()),
+                ErrorCode::Io,
+            ),
+        ];
+        #[cfg(feature = "json")]
+        let cases = {
+            let mut cases = Vec::from(cases);
+            cases.push((
+                Error::Json(serde_json::from_str::<u8>("").unwrap_err()),
+                ErrorCode::Parse,
+            ));
+            cases
+        };
+        for (error, code) in cases {
+            assert_eq!(error.code(), code, "{:?}", error);
+        }
+
+        let wrapped = Error::Context {
+            message: "account 0".into(),
+            source: Box::new(Error::AmountOverflow(0)),
+        };
+        assert_eq!(wrapped.code(), ErrorCode::Validation);
+        let foreign = Error::Context {
+            message: "io".into(),
+            source: Box::new(std::fmt::Error),
+        };
+        assert_eq!(foreign.code(), ErrorCode::Other);
+
+        let values: Vec<u32> = [
+            ErrorCode::Other,
+            ErrorCode::Validation,
+            ErrorCode::Parse,
+            ErrorCode::Envelope,
+            ErrorCode::Crypto,
+            ErrorCode::Io,
+        ]
+        .into_iter()
+        .map(u32::from)
+        .collect();
+        assert_eq!(values, [0, 1, 2, 3, 4, 5]);
+    }
+}
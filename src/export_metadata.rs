@@ -0,0 +1,301 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bc_envelope::prelude::*;
+
+use crate::{
+    BlockHeight, Error, Network, Result, SecondsSinceEpoch, Zewif, ZewifEnvelope,
+    envelope_upgrade::envelope_version, zewif_impl::uncompressed,
+};
+
+/// Identifying information about an exported Zewif that remains readable
+/// when its content is encrypted, so that files can be told apart without
+/// decrypting them; see [`Zewif::peek_metadata`].
+///
+/// The metadata is built only from fields of the Zewif that are not
+/// sensitive: the export height is the chain tip at the time of the export,
+/// not a height relevant to any account, and the wallet's contents are
+/// summarized only by their numbers. The only free text is the name of the
+/// exporting tool and a note, both chosen by the exporter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportMetadata {
+    format_version: u32,
+    export_height: BlockHeight,
+    exported_at: Option<SecondsSinceEpoch>,
+    source_tool: Option<String>,
+    network: Option<Network>,
+    account_count: usize,
+    address_count: usize,
+    transaction_count: usize,
+    note: Option<String>,
+}
+
+impl ExportMetadata {
+    /// The version of the Zewif envelope format written by this crate.
+    pub const FORMAT_VERSION: u32 = Zewif::VERSION;
+
+    /// Returns the metadata for exporting `zewif` now with this crate.
+    pub fn for_export(zewif: &Zewif) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let mut metadata = Self::summarizing(zewif, Self::FORMAT_VERSION);
+        metadata.exported_at = Some(SecondsSinceEpoch::from(now));
+        metadata.source_tool = Some(concat!("zewif ", env!("CARGO_PKG_VERSION")).to_string());
+        metadata
+    }
+
+    /// Returns the metadata that can be read from the content of `zewif`
+    /// alone, without the time or tool of its export.
+    fn summarizing(zewif: &Zewif, format_version: u32) -> Self {
+        let mut networks = zewif.wallets().iter().map(|wallet| wallet.network());
+        let network = networks
+            .next()
+            .filter(|first| networks.all(|network| network == *first));
+        let accounts = || zewif.wallets().iter().flat_map(|wallet| wallet.accounts());
+        Self {
+            format_version,
+            export_height: zewif.export_height(),
+            exported_at: None,
+            source_tool: None,
+            network,
+            account_count: accounts().count(),
+            address_count: accounts().map(|account| account.addresses_len()).sum(),
+            transaction_count: zewif.transactions().len(),
+            note: None,
+        }
+    }
+
+    pub fn format_version(&self) -> u32 {
+        self.format_version
+    }
+
+    pub fn export_height(&self) -> BlockHeight {
+        self.export_height
+    }
+
+    /// Returns the time at which the Zewif was encrypted for export, which
+    /// only encrypted exports record.
+    pub fn exported_at(&self) -> Option<SecondsSinceEpoch> {
+        self.exported_at
+    }
+
+    /// Returns the name and version of the software that wrote the export,
+    /// which only encrypted exports record.
+    pub fn source_tool(&self) -> Option<&str> {
+        self.source_tool.as_deref()
+    }
+
+    /// Sets the name and version of the software writing the export, such as
+    /// `zmigrate 1.0`; [`ExportMetadata::for_export`] names this crate.
+    pub fn set_source_tool(&mut self, source_tool: impl Into<String>) {
+        self.source_tool = Some(source_tool.into());
+    }
+
+    /// Returns the network of the Zewif's wallets, or `None` if it has no
+    /// wallets or they are for different networks.
+    pub fn network(&self) -> Option<Network> {
+        self.network
+    }
+
+    pub fn account_count(&self) -> usize {
+        self.account_count
+    }
+
+    pub fn address_count(&self) -> usize {
+        self.address_count
+    }
+
+    pub fn transaction_count(&self) -> usize {
+        self.transaction_count
+    }
+
+    /// Returns the note left by the user who made the export.
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    /// Sets a note identifying the export, such as a support ticket number.
+    /// The note is readable without decrypting the export, so it must not
+    /// hold anything secret.
+    pub fn set_note(&mut self, note: Option<String>) {
+        self.note = note;
+    }
+
+    /// Reads the metadata from the outer layer of an encrypted Zewif envelope.
+    pub fn from_envelope(envelope: &Envelope) -> Result<Self> {
+        Ok(Self {
+            format_version: envelope.extract_object_for_predicate("format_version")?,
+            export_height: envelope.extract_object_for_predicate("export_height")?,
+            exported_at: envelope.extract_optional_object_for_predicate("exported_at")?,
+            source_tool: envelope.extract_optional_object_for_predicate("source_tool")?,
+            network: envelope.extract_optional_object_for_predicate("network")?,
+            account_count: envelope.extract_object_for_predicate("account_count")?,
+            address_count: envelope.extract_object_for_predicate("address_count")?,
+            transaction_count: envelope.extract_object_for_predicate("transaction_count")?,
+            note: envelope.extract_optional_object_for_predicate("note")?,
+        })
+    }
+
+    pub(crate) fn add_to_envelope(&self, envelope: Envelope) -> Envelope {
+        envelope
+            .add_assertion("format_version", self.format_version)
+            .add_assertion("export_height", self.export_height)
+            .add_optional_assertion("exported_at", self.exported_at)
+            .add_optional_assertion("source_tool", self.source_tool.clone())
+            .add_optional_assertion("network", self.network)
+            .add_assertion("account_count", self.account_count)
+            .add_assertion("address_count", self.address_count)
+            .add_assertion("transaction_count", self.transaction_count)
+            .add_optional_assertion("note", self.note.clone())
+    }
+}
+
+impl Zewif {
+    /// Reads the [`ExportMetadata`] of an exported Zewif without decrypting
+    /// it.
+    ///
+    /// Encrypted and sharded exports carry their metadata in their outer
+    /// layer. For unencrypted exports, compressed or not, it is summarized
+    /// from the content, and records neither the time nor the tool of the
+    /// export.
+    ///
+    /// # Errors
+    /// Returns [`Error::EncryptedZewif`] for an encrypted envelope without
+    /// metadata in its outer layer, and a decoding error for an unencrypted
+    /// envelope that is not a valid Zewif.
+    pub fn peek_metadata(envelope: &Envelope) -> Result<ExportMetadata> {
+        if !envelope
+            .assertions_with_predicate("account_count")
+            .is_empty()
+        {
+            return ExportMetadata::from_envelope(envelope);
+        }
+        if ZewifEnvelope::new(envelope.clone())?.is_encrypted() {
+            return Err(Error::EncryptedZewif);
+        }
+        let content = uncompressed(envelope.clone())?;
+        let format_version = envelope_version(&content)?;
+        Ok(ExportMetadata::summarizing(
+            &Zewif::try_from(content)?,
+            format_version,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, Address, BlockHeight, Compression, EncodeOptions, Error, ExportMetadata,
+        KdfParams, Network, ProtocolAddress, Zewif, ZewifEnvelope, ZewifWallet, transparent,
+    };
+
+    fn zewif() -> Zewif {
+        let mut account = Account::new();
+        account.set_name("Savings");
+        for address in ["t1peek0", "t1peek1"] {
+            account.add_address(Address::new(ProtocolAddress::Transparent(
+                transparent::Address::new(address),
+            )));
+        }
+        let mut wallet = ZewifWallet::new(Network::Test);
+        wallet.add_account(account);
+        wallet.add_account(Account::new());
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        zewif
+    }
+
+    fn assert_summary(metadata: &ExportMetadata) {
+        assert_eq!(metadata.format_version(), ExportMetadata::FORMAT_VERSION);
+        assert_eq!(metadata.export_height(), BlockHeight::from(2_000_000));
+        assert_eq!(metadata.network(), Some(Network::Test));
+        assert_eq!(metadata.account_count(), 2);
+        assert_eq!(metadata.address_count(), 2);
+        assert_eq!(metadata.transaction_count(), 0);
+    }
+
+    #[test]
+    fn test_peek_encrypted_metadata() {
+        let zewif = zewif();
+        let mut metadata = ExportMetadata::for_export(&zewif);
+        metadata.set_source_tool("zmigrate 1.0");
+        metadata.set_note(Some("ticket 4521".to_string()));
+        let envelope = zewif
+            .encrypt_with_password_and_metadata("password", KdfParams::default(), &metadata)
+            .unwrap();
+        assert!(ZewifEnvelope::new(envelope.clone()).unwrap().is_encrypted());
+
+        let peeked = Zewif::peek_metadata(&envelope).unwrap();
+        assert_eq!(peeked, metadata);
+        assert_summary(&peeked);
+        assert_eq!(peeked.source_tool(), Some("zmigrate 1.0"));
+        assert_eq!(peeked.note(), Some("ticket 4521"));
+        assert_eq!(
+            Zewif::decrypt_with_password(&envelope, "password").unwrap(),
+            zewif
+        );
+
+        let envelope = zewif
+            .encrypt_with_password("password", KdfParams::default())
+            .unwrap();
+        let peeked = Zewif::peek_metadata(&envelope).unwrap();
+        assert_summary(&peeked);
+        assert!(peeked.source_tool().unwrap().starts_with("zewif "));
+        assert!(peeked.exported_at().is_some());
+        assert_eq!(peeked.note(), None);
+    }
+
+    #[test]
+    fn test_peek_unencrypted_metadata() {
+        let zewif = zewif();
+        for compression in [
+            Compression::None,
+            Compression::Whole,
+            Compression::LargeLeavesOver(16),
+        ] {
+            let mut options = EncodeOptions::new();
+            options.set_compression(compression);
+            let metadata =
+                Zewif::peek_metadata(&zewif.to_envelope_with(&options).unwrap()).unwrap();
+            assert_summary(&metadata);
+            assert_eq!(metadata.exported_at(), None);
+            assert_eq!(metadata.source_tool(), None);
+        }
+
+        let mut mixed = zewif.clone();
+        mixed.add_wallet(ZewifWallet::new(Network::Main));
+        assert_eq!(
+            Zewif::peek_metadata(&Envelope::from(mixed))
+                .unwrap()
+                .network(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_metadata_reveals_no_secrets() {
+        let zewif = zewif();
+        let envelope = zewif
+            .encrypt_with_password("password", KdfParams::default())
+            .unwrap();
+        for assertion in envelope.assertions() {
+            let object = assertion.as_object().unwrap();
+            assert!(!object.format_flat().contains("t1peek"));
+            assert!(!object.format_flat().contains("Savings"));
+        }
+
+        let content = ZewifEnvelope::new(envelope.clone())
+            .unwrap()
+            .obscured_content()
+            .unwrap();
+        let without_metadata = Envelope::new(zewif.id())
+            .add_type("Zewif")
+            .add_assertion("content", content);
+        assert!(matches!(
+            Zewif::peek_metadata(&without_metadata),
+            Err(Error::EncryptedZewif)
+        ));
+    }
+}
@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bc_envelope::prelude::*;
+
+use crate::{
+    Result, SecondsSinceEpoch, TypedAttachments, Zewif, typed_attachments::attachment_vendors,
+};
+
+/// Describes the tool that produced a Zewif, or the parts of it attached by
+/// one vendor; see [`Zewif::set_exporter_info`].
+///
+/// # Envelope Shape
+/// The subject is the name of the tool, with the type `ExporterInfo`, and the
+/// assertions:
+///
+/// - `version`: the tool's version string
+/// - `timestamp` (optional): when the tool ran, in seconds since the epoch
+/// - `source_format` (optional): the format the tool read, such as
+///   `zcashd-wallet.dat`
+/// - `entry` (any number): a free-form entry, whose subject is its key and
+///   whose `value` assertion is its value
+///
+/// The info is attached conforming to [`ExporterInfo::CONFORMS_TO`].
+///
+/// # Examples
+/// ```
+/// # use zewif::{BlockHeight, ExporterInfo, Zewif};
+/// let mut info = ExporterInfo::new("zewif-zcashd", "0.3.1");
+/// info.set_source_format(Some("zcashd-wallet.dat".to_string()));
+/// info.set_entry("zcashd_version", "5.10.0");
+///
+/// let mut zewif = Zewif::new(BlockHeight::from(2_500_000u32));
+/// zewif.set_exporter_info("org.zcashfoundation", info.clone());
+/// assert_eq!(zewif.exporter_info("org.zcashfoundation")?, Some(info));
+/// assert!(zewif.attachment_vendors().contains("org.zcashfoundation"));
+/// # Ok::<(), zewif::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExporterInfo {
+    tool: String,
+    version: String,
+    timestamp: Option<SecondsSinceEpoch>,
+    source_format: Option<String>,
+    entries: BTreeMap<String, String>,
+}
+
+impl ExporterInfo {
+    /// The format that exporter info attachments conform to.
+    pub const CONFORMS_TO: &'static str = "zewif-exporter-info-v1";
+
+    pub fn new(tool: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            tool: tool.into(),
+            version: version.into(),
+            timestamp: None,
+            source_format: None,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    pub fn tool(&self) -> &str {
+        &self.tool
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn timestamp(&self) -> Option<SecondsSinceEpoch> {
+        self.timestamp
+    }
+
+    pub fn set_timestamp(&mut self, timestamp: Option<SecondsSinceEpoch>) {
+        self.timestamp = timestamp;
+    }
+
+    pub fn source_format(&self) -> Option<&str> {
+        self.source_format.as_deref()
+    }
+
+    pub fn set_source_format(&mut self, source_format: Option<String>) {
+        self.source_format = source_format;
+    }
+
+    pub fn entries(&self) -> &BTreeMap<String, String> {
+        &self.entries
+    }
+
+    pub fn entry(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    /// Sets a free-form entry, replacing any with the same key.
+    pub fn set_entry(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.entries.insert(key.into(), value.into());
+    }
+}
+
+impl From<ExporterInfo> for Envelope {
+    fn from(value: ExporterInfo) -> Self {
+        let e = Envelope::new(value.tool)
+            .add_type("ExporterInfo")
+            .add_assertion("version", value.version)
+            .add_optional_assertion("timestamp", value.timestamp)
+            .add_optional_assertion("source_format", value.source_format);
+        value.entries.into_iter().fold(e, |e, (key, value)| {
+            e.add_assertion("entry", Envelope::new(key).add_assertion("value", value))
+        })
+    }
+}
+
+impl TryFrom<Envelope> for ExporterInfo {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("ExporterInfo")?;
+        let entries = envelope
+            .objects_for_predicate("entry")
+            .into_iter()
+            .map(|entry| {
+                Ok((
+                    entry.extract_subject()?,
+                    entry.extract_object_for_predicate("value")?,
+                ))
+            })
+            .collect::<bc_envelope::Result<_>>()?;
+        Ok(Self {
+            tool: envelope.extract_subject()?,
+            version: envelope.extract_object_for_predicate("version")?,
+            timestamp: envelope.try_optional_object_for_predicate("timestamp")?,
+            source_format: envelope.try_optional_object_for_predicate("source_format")?,
+            entries,
+        })
+    }
+}
+
+impl Zewif {
+    /// Attaches `info` to the Zewif as the exporter info of `vendor`,
+    /// replacing any attached before.
+    ///
+    /// Vendors should be identified by reverse-DNS strings; see
+    /// [`TypedAttachments`].
+    pub fn set_exporter_info(&mut self, vendor: &str, info: ExporterInfo) {
+        self.add_typed_attachment(vendor, Some(ExporterInfo::CONFORMS_TO), info);
+    }
+
+    /// Returns the exporter info attached by `vendor`, if any.
+    ///
+    /// # Errors
+    /// Returns an error if the attachment is not a valid [`ExporterInfo`].
+    pub fn exporter_info(&self, vendor: &str) -> Result<Option<ExporterInfo>> {
+        self.typed_attachment(vendor, Some(ExporterInfo::CONFORMS_TO))
+    }
+
+    /// Returns the vendors of every attachment in the Zewif: those of the
+    /// Zewif itself and of its wallets, accounts, addresses, and
+    /// transactions.
+    pub fn attachment_vendors(&self) -> BTreeSet<String> {
+        let accounts = || self.wallets().iter().flat_map(|wallet| wallet.accounts());
+        std::iter::once(self.attachments())
+            .chain(self.wallets().iter().map(|wallet| wallet.attachments()))
+            .chain(accounts().map(|account| account.attachments()))
+            .chain(
+                accounts()
+                    .flat_map(|account| account.addresses())
+                    .map(|address| address.attachments()),
+            )
+            .chain(
+                self.transactions()
+                    .values()
+                    .map(|transaction| transaction.attachments()),
+            )
+            .flat_map(attachment_vendors)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, BlockHeight, Network, RandomInstance, SecondsSinceEpoch, Transaction, TxId, Zewif,
+        ZewifWallet, test_envelope_roundtrip,
+    };
+
+    use super::ExporterInfo;
+
+    impl RandomInstance for ExporterInfo {
+        fn random() -> Self {
+            Self {
+                tool: String::random(),
+                version: String::random(),
+                timestamp: SecondsSinceEpoch::opt_random(),
+                source_format: String::opt_random(),
+                entries: Vec::<String>::random()
+                    .into_iter()
+                    .map(|key| (key, String::random()))
+                    .collect::<BTreeMap<_, _>>(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(ExporterInfo);
+
+    #[test]
+    fn test_exporter_info_and_vendors() {
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        let mut info = ExporterInfo::new("zewif-zcashd", "0.3.1");
+        info.set_timestamp(Some(SecondsSinceEpoch::from(1_700_000_000)));
+        zewif.set_exporter_info(
+            "org.zcashfoundation",
+            ExporterInfo::new("zewif-zcashd", "0.3.0"),
+        );
+        zewif.set_exporter_info("org.zcashfoundation", info.clone());
+
+        let mut account = Account::new();
+        account.add_attachment("note", "com.example.account", None);
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+        zewif.add_wallet(wallet);
+        let mut transaction = Transaction::new(TxId::from_bytes([1; 32]));
+        transaction.add_attachment("note", "com.example.transaction", None);
+        zewif
+            .add_transaction(transaction.txid(), transaction)
+            .unwrap();
+
+        let zewif = Zewif::try_from(Envelope::from(zewif)).unwrap();
+        assert_eq!(
+            zewif.exporter_info("org.zcashfoundation").unwrap(),
+            Some(info)
+        );
+        assert_eq!(zewif.exporter_info("com.example.account").unwrap(), None);
+        assert_eq!(
+            zewif.attachment_vendors().into_iter().collect::<Vec<_>>(),
+            [
+                "com.example.account",
+                "com.example.transaction",
+                "org.zcashfoundation"
+            ]
+        );
+    }
+}
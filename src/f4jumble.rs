@@ -0,0 +1,99 @@
+//! The F4Jumble unkeyed permutation used by ZIP 316 unified encodings.
+//!
+//! F4Jumble is a four-round Feistel construction over BLAKE2b that ensures
+//! every character of a unified address or key depends on every byte of the
+//! encoded items, so that a partial string cannot be mistaken for another
+//! valid encoding.
+
+use blake2b_simd::{OUTBYTES, Params};
+
+/// The range of message lengths accepted by F4Jumble.
+pub(crate) const VALID_LENGTH: std::ops::RangeInclusive<usize> = 38..=4194368;
+
+fn h_round(left: &mut [u8], right: &[u8], i: u8) {
+    let mut personal = *b"UA_F4Jumble_H\0\0\0";
+    personal[13] = i;
+    let hash = Params::new()
+        .hash_length(left.len())
+        .personal(&personal)
+        .hash(right);
+    xor(left, hash.as_bytes());
+}
+
+fn g_round(left: &[u8], right: &mut [u8], i: u8) {
+    for (j, chunk) in right.chunks_mut(OUTBYTES).enumerate() {
+        let mut personal = *b"UA_F4Jumble_G\0\0\0";
+        personal[13] = i;
+        personal[14..].copy_from_slice(&(j as u16).to_le_bytes());
+        let hash = Params::new()
+            .hash_length(OUTBYTES)
+            .personal(&personal)
+            .hash(left);
+        xor(chunk, hash.as_bytes());
+    }
+}
+
+fn xor(target: &mut [u8], source: &[u8]) {
+    for (t, s) in target.iter_mut().zip(source) {
+        *t ^= s;
+    }
+}
+
+fn split(message: &mut [u8]) -> (&mut [u8], &mut [u8]) {
+    let left_len = OUTBYTES.min(message.len() / 2);
+    message.split_at_mut(left_len)
+}
+
+/// Applies F4Jumble to `message` in place.
+///
+/// Returns `None` if the message length is outside [`VALID_LENGTH`].
+pub(crate) fn f4jumble(message: &mut [u8]) -> Option<()> {
+    if !VALID_LENGTH.contains(&message.len()) {
+        return None;
+    }
+    let (left, right) = split(message);
+    g_round(left, right, 0);
+    h_round(left, right, 0);
+    g_round(left, right, 1);
+    h_round(left, right, 1);
+    Some(())
+}
+
+/// Applies the inverse of F4Jumble to `message` in place.
+///
+/// Returns `None` if the message length is outside [`VALID_LENGTH`].
+pub(crate) fn f4jumble_inv(message: &mut [u8]) -> Option<()> {
+    if !VALID_LENGTH.contains(&message.len()) {
+        return None;
+    }
+    let (left, right) = split(message);
+    h_round(left, right, 1);
+    g_round(left, right, 1);
+    h_round(left, right, 0);
+    g_round(left, right, 0);
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{f4jumble, f4jumble_inv};
+
+    /// The first test vector from zcash-test-vectors (`f4jumble.py`).
+    #[test]
+    fn test_f4jumble_vector() {
+        let normal = hex::decode(
+            "5d7a8f739a2d9e945b0ce152a8049e294c4d6e66b164939daffa2ef6ee6921481cdd86b3cc4318d9614fc820905d042b",
+        )
+        .unwrap();
+        let jumbled = hex::decode(
+            "0304d029141b995da5387c125970673504d6c764d91ea6c082123770c7139ccd88ee27368cd0c0921a0444c8e5858d22",
+        )
+        .unwrap();
+
+        let mut message = normal.clone();
+        f4jumble(&mut message).unwrap();
+        assert_eq!(message, jumbled);
+        f4jumble_inv(&mut message).unwrap();
+        assert_eq!(message, normal);
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use bc_envelope::prelude::*;
+
+use crate::{EncodeOptions, Error, Result, Zewif, ZewifEnvelope, zewif_envelope::unsigned};
+
+/// What a file or byte blob holds, as reported by [`detect`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// An envelope that is neither compressed nor encrypted, including a
+    /// watch-only export and an envelope of a type other than Zewif.
+    PlainEnvelope,
+
+    /// A Zewif envelope compressed as a whole or leaf by leaf; see
+    /// [`Compression`](crate::Compression).
+    CompressedEnvelope,
+
+    /// A Zewif envelope whose content is encrypted with a key or password,
+    /// to recipients, or as one of a set of SSKR shares.
+    EncryptedEnvelope,
+
+    /// A single-part `ur:zewif` string; see [`Zewif::to_ur`].
+    Ur,
+
+    /// Anything else.
+    Unknown,
+}
+
+/// Identifies what `bytes` holds without decoding it as a Zewif.
+///
+/// An envelope is recognized by its tagged CBOR encoding, as written by
+/// [`Zewif::save`], and a UR by its `ur:zewif/` prefix, in either case. A
+/// signed envelope is reported as the kind of envelope it signs.
+pub fn detect(bytes: &[u8]) -> FileKind {
+    if let Ok(text) = std::str::from_utf8(bytes)
+        && text
+            .trim()
+            .to_lowercase()
+            .starts_with(&format!("ur:{}/", Zewif::UR_TYPE))
+    {
+        return FileKind::Ur;
+    }
+    let Ok(envelope) = Envelope::try_from_cbor_data(bytes.to_vec()) else {
+        return FileKind::Unknown;
+    };
+    match ZewifEnvelope::new(unsigned(&envelope)) {
+        Ok(envelope) if envelope.is_encrypted() => FileKind::EncryptedEnvelope,
+        Ok(envelope) if envelope.can_uncompress() => FileKind::CompressedEnvelope,
+        _ => FileKind::PlainEnvelope,
+    }
+}
+
+impl Zewif {
+    /// Reads a Zewif from a file holding a plain or compressed envelope, or a
+    /// `ur:zewif` string.
+    ///
+    /// The signature of an envelope signed by [`Zewif::sign`] is removed
+    /// without being verified; read the envelope with
+    /// [`Zewif::from_signed_envelope`] to verify it.
+    ///
+    /// # Errors
+    /// - [`Error::Io`] if the file cannot be read.
+    /// - [`Error::NotZewifEnvelope`] if the file holds neither a Zewif
+    ///   envelope nor a Zewif UR.
+    /// - [`Error::EncryptedZewif`] if the Zewif is encrypted; decrypt it with
+    ///   [`Zewif::decrypt_with_password`] or [`Zewif::decrypt_as_recipient`],
+    ///   or recover it with [`Zewif::from_shards`].
+    /// - An envelope or decoding error if the envelope is malformed.
+    pub fn load(path: impl AsRef<Path>) -> Result<Zewif> {
+        let bytes = std::fs::read(path)?;
+        match detect(&bytes) {
+            FileKind::PlainEnvelope | FileKind::CompressedEnvelope => {
+                let envelope = ZewifEnvelope::new(Envelope::try_from_cbor_data(bytes)?)?;
+                Zewif::try_from(envelope.envelope().clone())
+            }
+            FileKind::EncryptedEnvelope => Err(Error::EncryptedZewif),
+            FileKind::Ur => {
+                let text = String::from_utf8(bytes).expect("detected as text");
+                let ur = UR::from_ur_string(text.trim()).map_err(|e| Error::InvalidUR {
+                    reason: e.to_string(),
+                })?;
+                Zewif::from_ur(&ur)
+            }
+            FileKind::Unknown => Err(Error::NotZewifEnvelope),
+        }
+    }
+
+    /// Writes the Zewif to a file as the tagged CBOR of its envelope,
+    /// compressed as selected by `options`; see [`Zewif::write_envelope`].
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>, options: &EncodeOptions) -> Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_envelope(&mut file, options)?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::{PrivateKeyBase, PublicKeysProvider, SSKRGroupSpec, SSKRSpec};
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        BlockHeight, Compression, Data, EncodeOptions, Error, FileKind, KdfParams, Transaction,
+        TxId, Zewif, detect,
+    };
+
+    fn zewif() -> Zewif {
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        let txid = TxId::from_bytes([0x11; 32]);
+        let mut transaction = Transaction::new(txid);
+        transaction.set_raw(Data::from_vec(vec![0x11; 500]));
+        zewif.add_transaction(txid, transaction).unwrap();
+        zewif
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("zewif-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_detect() {
+        let zewif = zewif();
+        let data = |envelope: Envelope| envelope.to_cbor_data();
+        let compressed = |compression| {
+            let mut options = EncodeOptions::new();
+            options.set_compression(compression);
+            data(zewif.to_envelope_with(&options).unwrap())
+        };
+        let recipient = PrivateKeyBase::new();
+        let spec = SSKRSpec::new(1, vec![SSKRGroupSpec::new(2, 3).unwrap()]).unwrap();
+        let params = KdfParams::new(8, 1, 1);
+
+        assert_eq!(
+            detect(&data(Envelope::from(zewif.clone()))),
+            FileKind::PlainEnvelope
+        );
+        assert_eq!(
+            detect(&data(zewif.to_watch_only_envelope().unwrap())),
+            FileKind::PlainEnvelope
+        );
+        assert_eq!(
+            detect(&data(Envelope::new("not a Zewif"))),
+            FileKind::PlainEnvelope
+        );
+        assert_eq!(
+            detect(&compressed(Compression::Whole)),
+            FileKind::CompressedEnvelope
+        );
+        assert_eq!(
+            detect(&compressed(Compression::LargeLeavesOver(100))),
+            FileKind::CompressedEnvelope
+        );
+        assert_eq!(
+            detect(&data(
+                zewif.encrypt_with_password("password", params).unwrap()
+            )),
+            FileKind::EncryptedEnvelope
+        );
+        assert_eq!(
+            detect(&data(
+                zewif
+                    .encrypt_to_recipients(&[recipient.public_keys()])
+                    .unwrap()
+            )),
+            FileKind::EncryptedEnvelope
+        );
+        assert_eq!(
+            detect(&data(zewif.shard(spec).unwrap().remove(0))),
+            FileKind::EncryptedEnvelope
+        );
+        assert_eq!(detect(zewif.to_ur().string().as_bytes()), FileKind::Ur);
+        assert_eq!(
+            detect(format!("{}\n", zewif.to_ur().qr_string()).as_bytes()),
+            FileKind::Ur
+        );
+        assert_eq!(detect(b"ur:bytes/hdcxdwvyjzjl"), FileKind::Unknown);
+        assert_eq!(detect(&[0x01, 0x02, 0x03]), FileKind::Unknown);
+        assert_eq!(
+            detect(&CBOR::from("not an envelope").to_cbor_data()),
+            FileKind::Unknown
+        );
+    }
+
+    #[test]
+    fn test_load_and_save() {
+        let zewif = zewif();
+        let path = temp_path("load-and-save");
+        for compression in [
+            Compression::None,
+            Compression::Whole,
+            Compression::LargeLeavesOver(100),
+        ] {
+            let mut options = EncodeOptions::new();
+            options.set_compression(compression);
+            zewif.save(&path, &options).unwrap();
+            assert_eq!(Zewif::load(&path).unwrap(), zewif);
+        }
+
+        std::fs::write(&path, zewif.to_ur().string()).unwrap();
+        assert_eq!(Zewif::load(&path).unwrap(), zewif);
+
+        let encrypted = zewif
+            .encrypt_with_password("password", KdfParams::new(8, 1, 1))
+            .unwrap();
+        std::fs::write(&path, encrypted.to_cbor_data()).unwrap();
+        assert!(matches!(Zewif::load(&path), Err(Error::EncryptedZewif)));
+
+        std::fs::write(
+            &path,
+            Envelope::new("not a Zewif")
+                .add_type("Wallet")
+                .to_cbor_data(),
+        )
+        .unwrap();
+        assert!(matches!(Zewif::load(&path), Err(Error::NotZewifEnvelope)));
+
+        std::fs::write(&path, b"not a Zewif").unwrap();
+        assert!(matches!(Zewif::load(&path), Err(Error::NotZewifEnvelope)));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(Zewif::load(&path), Err(Error::Io(_))));
+    }
+}
@@ -0,0 +1,137 @@
+use bc_envelope::prelude::*;
+
+use crate::{Blob, SeedFingerprint};
+
+/// A reference to a seed held by a hardware wallet.
+///
+/// Some wallets never hold their seed: it lives on a hardware device (such as
+/// a Ledger, Trezor, or Keystone), and the wallet holds only derivation
+/// metadata and viewing keys. `HardwareSeedReference` records which device
+/// and seed the wallet's accounts belong to, so that the receiving wallet can
+/// ask the user to connect the right device instead of expecting a seed.
+///
+/// # Zcash Concept Relation
+/// - **Master fingerprint**: The BIP-32 fingerprint of the device's master
+///   key, as commonly reported by hardware wallets
+/// - **Seed fingerprint**: The ZIP 32 fingerprint of the seed, if the device
+///   reports it, by which accounts refer to the seed
+///
+/// # Examples
+/// ```
+/// # use zewif::{Blob, HardwareSeedReference};
+/// let fingerprint = Blob::new([0x0a, 0x1b, 0x2c, 0x3d]);
+/// let reference = HardwareSeedReference::new("Ledger Nano S", fingerprint);
+/// assert_eq!(
+///     format!("{:?}", reference),
+///     "HardwareSeedReference(<Ledger Nano S, master fingerprint 0a1b2c3d>)"
+/// );
+/// ```
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct HardwareSeedReference {
+    device: String,
+    master_fingerprint: Blob<4>,
+    seed_fingerprint: Option<SeedFingerprint>,
+    derivation_note: Option<String>,
+}
+
+/// Prints the device and its master fingerprint, matching the terse form of
+/// the other seed material.
+impl std::fmt::Debug for HardwareSeedReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "HardwareSeedReference(<{}, master fingerprint {}>)",
+            self.device,
+            hex::encode(self.master_fingerprint.as_slice())
+        )
+    }
+}
+
+impl HardwareSeedReference {
+    pub fn new(device: impl Into<String>, master_fingerprint: Blob<4>) -> Self {
+        Self {
+            device: device.into(),
+            master_fingerprint,
+            seed_fingerprint: None,
+            derivation_note: None,
+        }
+    }
+
+    /// Returns a description of the device holding the seed.
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+
+    /// Returns the BIP-32 fingerprint of the device's master key.
+    pub fn master_fingerprint(&self) -> &Blob<4> {
+        &self.master_fingerprint
+    }
+
+    /// Returns the ZIP 32 fingerprint of the seed, if the device reported it.
+    pub fn seed_fingerprint(&self) -> Option<&SeedFingerprint> {
+        self.seed_fingerprint.as_ref()
+    }
+
+    pub fn set_seed_fingerprint(&mut self, seed_fingerprint: Option<SeedFingerprint>) {
+        self.seed_fingerprint = seed_fingerprint;
+    }
+
+    /// Returns free-form notes on how the wallet's keys were derived on the
+    /// device, if any.
+    pub fn derivation_note(&self) -> Option<&str> {
+        self.derivation_note.as_deref()
+    }
+
+    pub fn set_derivation_note(&mut self, derivation_note: Option<String>) {
+        self.derivation_note = derivation_note;
+    }
+}
+
+impl From<HardwareSeedReference> for Envelope {
+    fn from(value: HardwareSeedReference) -> Self {
+        Envelope::new(value.device)
+            .add_type("HardwareSeedReference")
+            .add_assertion("master_fingerprint", value.master_fingerprint)
+            .add_optional_assertion("seed_fingerprint", value.seed_fingerprint)
+            .add_optional_assertion("derivation_note", value.derivation_note)
+    }
+}
+
+impl TryFrom<Envelope> for HardwareSeedReference {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("HardwareSeedReference")?;
+        let device = envelope.extract_subject()?;
+        let master_fingerprint = envelope.extract_object_for_predicate("master_fingerprint")?;
+        let seed_fingerprint = envelope.try_optional_object_for_predicate("seed_fingerprint")?;
+        let derivation_note = envelope.extract_optional_object_for_predicate("derivation_note")?;
+        Ok(Self {
+            device,
+            master_fingerprint,
+            seed_fingerprint,
+            derivation_note,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Blob, SeedFingerprint, test_envelope_roundtrip};
+
+    use super::HardwareSeedReference;
+
+    impl crate::RandomInstance for HardwareSeedReference {
+        fn random() -> Self {
+            Self {
+                device: String::random(),
+                master_fingerprint: Blob::random(),
+                seed_fingerprint: SeedFingerprint::opt_random(),
+                derivation_note: String::opt_random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(HardwareSeedReference);
+}
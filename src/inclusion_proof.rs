@@ -0,0 +1,172 @@
+use std::{cell::RefCell, collections::HashSet};
+
+use bc_envelope::prelude::*;
+
+use crate::{Error, Result, TxId, Zewif};
+
+impl Zewif {
+    /// Returns an envelope proving that the Zewif contains the transaction
+    /// with the given ID, without revealing anything else about it.
+    ///
+    /// The proof is the Zewif's envelope with everything elided but the path
+    /// from its root to the transaction and the transaction's ID, so it has
+    /// the Zewif's digest and can be checked with [`verify_inclusion`].
+    ///
+    /// Elided values are hidden only as well as their digests hide them; a
+    /// verifier can confirm a guess at a short value, such as an amount or a
+    /// block height, by comparing digests.
+    ///
+    /// # Errors
+    /// Returns [`Error::NotInZewif`] if the Zewif has no such transaction.
+    pub fn prove_contains_transaction(&self, txid: &TxId) -> Result<Envelope> {
+        let transaction = self
+            .transactions()
+            .get(txid)
+            .ok_or_else(|| Error::NotInZewif {
+                kind: "Transaction",
+                id: txid.to_string(),
+            })?;
+        self.prove_contains(&Envelope::from(transaction.clone()))
+    }
+
+    /// Returns an envelope proving that an account of the Zewif contains the
+    /// given address, revealing the address string but none of its keys or
+    /// metadata; see [`Zewif::prove_contains_transaction`].
+    ///
+    /// # Errors
+    /// Returns [`Error::NotInZewif`] if no account has the address.
+    pub fn prove_contains_address(&self, address: &str) -> Result<Envelope> {
+        let found = self
+            .wallets()
+            .iter()
+            .flat_map(|wallet| wallet.accounts())
+            .flat_map(|account| account.addresses())
+            .find(|candidate| candidate.as_string() == address)
+            .ok_or_else(|| Error::NotInZewif {
+                kind: "Address",
+                id: address.to_string(),
+            })?;
+        self.prove_contains(&Envelope::from(found.address().clone()))
+    }
+
+    /// Elides everything but the path to `target` and the subject of
+    /// `target`.
+    fn prove_contains(&self, target: &Envelope) -> Result<Envelope> {
+        let envelope = Envelope::from(self.clone());
+        let path = envelope
+            .proof_contains_target(target)
+            .ok_or_else(|| Error::NotInZewif {
+                kind: "Proof target",
+                id: target.digest().hex(),
+            })?;
+        let revealed = RefCell::new(HashSet::from([target.digest(), target.subject().digest()]));
+        path.walk(false, (), &|node, _, _, _| {
+            if !node.is_elided() {
+                revealed.borrow_mut().insert(node.digest());
+            }
+            ((), false)
+        });
+        Ok(envelope.elide_revealing_set(&revealed.into_inner()))
+    }
+}
+
+/// Returns whether `proof`, as returned by
+/// [`Zewif::prove_contains_transaction`] or [`Zewif::prove_contains_address`],
+/// is an elided form of the Zewif envelope whose digest is `root_digest`, so
+/// that every element it reveals is part of that Zewif.
+///
+/// The root digest is that of the Zewif's unencrypted envelope, which is
+/// unchanged by leaf compression and by
+/// [`Zewif::to_watch_only_envelope`].
+pub fn verify_inclusion(proof: &Envelope, root_digest: &Digest) -> bool {
+    &proof.digest() == root_digest
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, Address, BlockHeight, Error, Network, ProtocolAddress, Transaction, TxId, Zewif,
+        ZewifWallet,
+        sapling::{self, SaplingExtendedSpendingKey},
+        verify_inclusion,
+    };
+
+    fn zewif() -> Zewif {
+        let mut account = Account::new();
+        account.set_name("Private savings");
+        for name in ["zs1proven", "zs1unrelated"] {
+            let mut address = sapling::Address::new(name.to_string());
+            address.set_spending_key(SaplingExtendedSpendingKey::new([0xa2; 169]));
+            account.add_address(Address::new(ProtocolAddress::Sapling(Box::new(address))));
+        }
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        for byte in [0x11, 0x22] {
+            let txid = TxId::from_bytes([byte; 32]);
+            zewif.add_transaction(txid, Transaction::new(txid)).unwrap();
+        }
+        zewif
+    }
+
+    #[test]
+    fn test_prove_contains_transaction() {
+        let zewif = zewif();
+        let root = Envelope::from(zewif.clone()).digest();
+        let txid = TxId::from_bytes([0x11; 32]);
+        let proof = zewif.prove_contains_transaction(&txid).unwrap();
+        assert!(verify_inclusion(&proof, &root));
+        assert!(verify_inclusion(
+            &proof,
+            &zewif.to_watch_only_envelope().unwrap().digest()
+        ));
+
+        // The transaction ID is revealed; the other transaction and the
+        // wallet are not.
+        let transaction = proof.object_for_predicate("transaction").unwrap();
+        assert_eq!(transaction.extract_subject::<TxId>().unwrap(), txid);
+        let cbor = proof.to_cbor_data();
+        let contains = |data: &[u8]| cbor.windows(data.len()).any(|window| window == data);
+        assert!(contains(&[0x11; 32]));
+        assert!(!contains(&[0x22; 32]));
+        assert!(!contains(b"Private savings"));
+        assert!(!contains(b"zs1proven"));
+
+        // A proof does not verify against another Zewif.
+        assert!(!verify_inclusion(
+            &proof,
+            &Envelope::from(Zewif::new(BlockHeight::from(1))).digest()
+        ));
+
+        let missing = TxId::from_bytes([0x33; 32]);
+        assert!(matches!(
+            zewif.prove_contains_transaction(&missing),
+            Err(Error::NotInZewif { .. })
+        ));
+    }
+
+    #[test]
+    fn test_prove_contains_address() {
+        let zewif = zewif();
+        let root = Envelope::from(zewif.clone()).digest();
+        let proof = zewif.prove_contains_address("zs1proven").unwrap();
+        assert!(verify_inclusion(&proof, &root));
+
+        let cbor = proof.to_cbor_data();
+        let contains = |data: &[u8]| cbor.windows(data.len()).any(|window| window == data);
+        assert!(contains(b"zs1proven"));
+        assert!(!contains(b"zs1unrelated"));
+        assert!(!contains(b"Private savings"));
+        assert!(!contains(&[0xa2; 32]));
+        assert!(!contains(&[0x11; 32]));
+
+        assert!(matches!(
+            zewif.prove_contains_address("zs1missing"),
+            Err(Error::NotInZewif { .. })
+        ));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{Error, Result};
+
 /// A Merkle path to a specific note commitment in a Merkle tree, along with metadata about the
 /// state of the tree at the time the Merkle path was computed.
 ///
@@ -41,6 +43,7 @@
 /// );
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct IncrementalWitness<const DEPTH: usize, Node> {
     note_commitment: Node,
     note_position: u32,
@@ -111,6 +114,42 @@ impl<const DEPTH: usize, Node> IncrementalWitness<DEPTH, Node> {
     pub fn anchor_frontier(&self) -> &[Node] {
         &self.anchor_frontier
     }
+
+    /// Checks that the witness is structurally consistent with a tree of depth
+    /// `DEPTH`.
+    ///
+    /// The Merkle path must contain exactly one node per tree level, the note
+    /// must lie within the tree as of the anchor, and the frontier cannot be
+    /// deeper than the tree.
+    pub fn validate(&self) -> Result<()> {
+        if self.merkle_path.len() != DEPTH {
+            return Err(Error::InvalidWitness {
+                reason: format!(
+                    "expected {} Merkle path nodes, got {}",
+                    DEPTH,
+                    self.merkle_path.len()
+                ),
+            });
+        }
+        if self.note_position >= self.anchor_tree_size {
+            return Err(Error::InvalidWitness {
+                reason: format!(
+                    "note position {} is outside a tree of size {}",
+                    self.note_position, self.anchor_tree_size
+                ),
+            });
+        }
+        if self.anchor_frontier.len() > DEPTH {
+            return Err(Error::InvalidWitness {
+                reason: format!(
+                    "frontier has {} nodes, more than the tree depth {}",
+                    self.anchor_frontier.len(),
+                    DEPTH
+                ),
+            });
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -125,15 +164,27 @@ mod tests {
             let mut rng = bc_rand::thread_rng();
             let note_position = rng_next_with_upper_bound(&mut rng, u32::MAX / 4);
             let anchor_tree_size =
-                note_position + rng_next_with_upper_bound(&mut rng, u32::MAX / 16);
+                note_position + 1 + rng_next_with_upper_bound(&mut rng, u32::MAX / 16);
             Self {
                 note_commitment: Node::random(),
                 note_position,
-                merkle_path: Vec::random(), // TODO: this should have DEPTH entries
+                merkle_path: (0..DEPTH).map(|_| Node::random()).collect(),
                 anchor: Node::random(),
                 anchor_tree_size,
                 anchor_frontier: Vec::random(),
             }
         }
     }
+
+    #[test]
+    fn test_random_witness_is_valid() {
+        let witness = IncrementalWitness::<32, u32>::random();
+        assert!(witness.validate().is_ok());
+    }
+
+    #[test]
+    fn test_short_merkle_path_is_invalid() {
+        let witness = IncrementalWitness::<32, u32>::from_parts(0, 0, vec![1; 31], 2, 1, vec![]);
+        assert!(witness.validate().is_err());
+    }
 }
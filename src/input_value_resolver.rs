@@ -0,0 +1,64 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{Amount, OutPoint, TxOut, Zewif, raw_transaction};
+
+/// Supplies the values of the transparent outputs spent by a transaction's
+/// inputs, which its encoding does not record; see
+/// [`Transaction::compute_fee`](crate::Transaction::compute_fee).
+///
+/// A [`Zewif`] resolves the outputs of the transactions it holds, from their
+/// recorded transparent outputs or, failing those, their raw bytes. Values
+/// known from elsewhere, such as a block explorer, can be supplied in a map,
+/// and a pair of resolvers tries the first before the second.
+pub trait InputValueResolver {
+    /// Returns the value of the output at `outpoint`, or `None` if it is
+    /// unknown.
+    fn input_value(&self, outpoint: &OutPoint) -> Option<Amount>;
+}
+
+impl InputValueResolver for Zewif {
+    fn input_value(&self, outpoint: &OutPoint) -> Option<Amount> {
+        self.resolve_output(outpoint).map(|output| output.value())
+    }
+}
+
+impl Zewif {
+    /// Returns the transparent output at `outpoint` if the transaction that
+    /// created it is stored, from its recorded transparent outputs or,
+    /// failing those, its raw bytes.
+    pub(crate) fn resolve_output(&self, outpoint: &OutPoint) -> Option<TxOut> {
+        let transaction = self.transaction(&outpoint.txid())?;
+        let index = usize::try_from(outpoint.index()).ok()?;
+        if let Some(output) = transaction.transparent_output(index) {
+            return Some(output.clone());
+        }
+        let mut flows = raw_transaction::value_flows(transaction.raw()?.as_slice()).ok()?;
+        (index < flows.outputs.len()).then(|| flows.outputs.swap_remove(index))
+    }
+}
+
+impl InputValueResolver for BTreeMap<OutPoint, Amount> {
+    fn input_value(&self, outpoint: &OutPoint) -> Option<Amount> {
+        self.get(outpoint).copied()
+    }
+}
+
+impl InputValueResolver for HashMap<OutPoint, Amount> {
+    fn input_value(&self, outpoint: &OutPoint) -> Option<Amount> {
+        self.get(outpoint).copied()
+    }
+}
+
+impl<R: InputValueResolver + ?Sized> InputValueResolver for &R {
+    fn input_value(&self, outpoint: &OutPoint) -> Option<Amount> {
+        (**self).input_value(outpoint)
+    }
+}
+
+impl<R: InputValueResolver, S: InputValueResolver> InputValueResolver for (R, S) {
+    fn input_value(&self, outpoint: &OutPoint) -> Option<Amount> {
+        self.0
+            .input_value(outpoint)
+            .or_else(|| self.1.input_value(outpoint))
+    }
+}
@@ -0,0 +1,119 @@
+//! Detecting damage to an encoded Zewif by its content digest.
+
+use bc_envelope::prelude::*;
+
+use crate::{Error, Result, Zewif, zewif_envelope::unsigned, zewif_impl::uncompressed};
+
+impl Zewif {
+    /// Returns the digest of the Zewif's uncompressed envelope, which
+    /// identifies its content: any change to the Zewif changes its digest.
+    ///
+    /// The digest is the one recorded in the `contentDigest` assertion by
+    /// [`EncodeOptions::set_content_digest`](crate::EncodeOptions::set_content_digest),
+    /// and [`Digest::hex`] gives it as a hexadecimal string.
+    pub fn digest(&self) -> Digest {
+        Envelope::from(self.clone()).digest()
+    }
+
+    /// Checks an envelope encoded with a `contentDigest` assertion by
+    /// recomputing the digest of its content, decompressed if necessary, and
+    /// comparing it with the one recorded. The envelope may be signed by
+    /// [`Zewif::sign`], whose signature is not verified.
+    ///
+    /// # Errors
+    /// - [`Error::ContentDigestMissing`] if the envelope has no
+    ///   `contentDigest` assertion.
+    /// - [`Error::ContentDigestMismatch`] if the recomputed digest differs from
+    ///   the recorded one.
+    /// - An envelope error if compressed content is too damaged to be
+    ///   decompressed.
+    pub fn verify_integrity(envelope: &Envelope) -> Result<()> {
+        let envelope = &unsigned(envelope);
+        let assertion = envelope
+            .optional_assertion_with_predicate("contentDigest")?
+            .ok_or(Error::ContentDigestMissing)?;
+        let expected: Digest = assertion.try_object()?.extract_subject()?;
+        let actual = uncompressed(envelope.remove_assertion(assertion))?.digest();
+        if actual != expected {
+            return Err(Error::ContentDigestMismatch {
+                expected: expected.hex(),
+                actual: actual.hex(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, BlockHeight, Compression, EncodeOptions, Error, Network, RandomInstance, Zewif,
+        ZewifWallet,
+    };
+
+    fn zewif() -> Zewif {
+        let mut account = Account::new();
+        account.set_name("Savings");
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        zewif
+    }
+
+    fn options(compression: Compression) -> EncodeOptions {
+        let mut options = EncodeOptions::new();
+        options.set_compression(compression);
+        options.set_content_digest(true);
+        options
+    }
+
+    #[test]
+    fn test_verify_integrity() {
+        let zewif = Zewif::random();
+        for compression in [
+            Compression::None,
+            Compression::LargeLeavesOver(64),
+            Compression::Whole,
+        ] {
+            let envelope = zewif.to_envelope_with(&options(compression)).unwrap();
+            let digest: Digest = envelope
+                .extract_object_for_predicate("contentDigest")
+                .unwrap();
+            assert_eq!(digest, zewif.digest());
+            Zewif::verify_integrity(&envelope).unwrap();
+            assert_eq!(Zewif::try_from(envelope).unwrap(), zewif);
+        }
+
+        let envelope = zewif.to_envelope_with(&EncodeOptions::new()).unwrap();
+        assert!(matches!(
+            Zewif::verify_integrity(&envelope),
+            Err(Error::ContentDigestMissing)
+        ));
+    }
+
+    #[test]
+    fn test_flipped_byte_fails_verification() {
+        let zewif = zewif();
+        let mut data = zewif
+            .to_envelope_with(&options(Compression::None))
+            .unwrap()
+            .to_cbor_data();
+        let position = data
+            .windows(7)
+            .position(|window| window == b"Savings")
+            .unwrap();
+        data[position] ^= 0x01;
+
+        let envelope = Envelope::try_from_cbor_data(data).unwrap();
+        let Err(Error::ContentDigestMismatch { expected, actual }) =
+            Zewif::verify_integrity(&envelope)
+        else {
+            panic!("verification did not fail with a mismatch");
+        };
+        assert_eq!(expected, zewif.digest().hex());
+        assert_ne!(actual, expected);
+    }
+}
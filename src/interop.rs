@@ -0,0 +1,166 @@
+//! Conversions between zewif's network and block height types and the
+//! consensus types of `zcash_protocol`, for tools that pass wallet data to the
+//! librustzcash stack.
+//!
+//! `zcash_protocol` distinguishes a network's type ([`NetworkType`], which
+//! includes regtest) from its consensus parameters. Its [`consensus::Network`]
+//! covers only mainnet and testnet, whose parameters are fixed; a regtest
+//! network is described by a [`LocalNetwork`] holding its activation heights.
+//! Accordingly, [`Network::Regtest`] converts to [`NetworkType::Regtest`] but
+//! not to a [`consensus::Network`], and the [`NetworkParameters`] of a regtest
+//! wallet convert to a [`LocalNetwork`].
+
+use zcash_protocol::{
+    consensus::{self, NetworkType},
+    local_consensus::LocalNetwork,
+};
+
+use crate::{BlockHeight, Error, Network, NetworkParameters, NetworkUpgrade, Result};
+
+impl From<Network> for NetworkType {
+    fn from(value: Network) -> Self {
+        match value {
+            Network::Main => NetworkType::Main,
+            Network::Test => NetworkType::Test,
+            Network::Regtest => NetworkType::Regtest,
+        }
+    }
+}
+
+impl From<NetworkType> for Network {
+    fn from(value: NetworkType) -> Self {
+        match value {
+            NetworkType::Main => Network::Main,
+            NetworkType::Test => Network::Test,
+            NetworkType::Regtest => Network::Regtest,
+        }
+    }
+}
+
+/// Fails for [`Network::Regtest`], which has no fixed consensus parameters;
+/// convert the wallet's [`NetworkParameters`] to a [`LocalNetwork`] instead.
+impl TryFrom<Network> for consensus::Network {
+    type Error = Error;
+
+    fn try_from(value: Network) -> Result<Self> {
+        match value {
+            Network::Main => Ok(consensus::Network::MainNetwork),
+            Network::Test => Ok(consensus::Network::TestNetwork),
+            Network::Regtest => Err(Error::InvalidNetwork {
+                network: Some(String::from(value)),
+                reason: "it has no fixed consensus parameters".to_string(),
+            }),
+        }
+    }
+}
+
+impl From<consensus::Network> for Network {
+    fn from(value: consensus::Network) -> Self {
+        match value {
+            consensus::Network::MainNetwork => Network::Main,
+            consensus::Network::TestNetwork => Network::Test,
+        }
+    }
+}
+
+/// Fails unless the parameters are for [`Network::Regtest`]. Upgrades after
+/// NU5, which zewif does not track, are left inactive.
+impl TryFrom<&NetworkParameters> for LocalNetwork {
+    type Error = Error;
+
+    fn try_from(value: &NetworkParameters) -> Result<Self> {
+        if value.network() != Network::Regtest {
+            return Err(Error::InvalidNetwork {
+                network: Some(String::from(value.network())),
+                reason: "its parameters do not describe a local network".to_string(),
+            });
+        }
+        let height = |upgrade| {
+            value
+                .activation_height(upgrade)
+                .map(consensus::BlockHeight::from)
+        };
+        Ok(LocalNetwork {
+            overwinter: height(NetworkUpgrade::Overwinter),
+            sapling: height(NetworkUpgrade::Sapling),
+            blossom: height(NetworkUpgrade::Blossom),
+            heartwood: height(NetworkUpgrade::Heartwood),
+            canopy: height(NetworkUpgrade::Canopy),
+            nu5: height(NetworkUpgrade::Nu5),
+            nu6: None,
+            nu6_1: None,
+            nu6_2: None,
+            nu6_3: None,
+        })
+    }
+}
+
+impl From<BlockHeight> for consensus::BlockHeight {
+    fn from(value: BlockHeight) -> Self {
+        consensus::BlockHeight::from_u32(u32::from(value))
+    }
+}
+
+impl From<consensus::BlockHeight> for BlockHeight {
+    fn from(value: consensus::BlockHeight) -> Self {
+        BlockHeight::from_u32(u32::from(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zcash_protocol::{
+        consensus::{self, NetworkType, Parameters},
+        local_consensus::LocalNetwork,
+    };
+
+    use crate::{BlockHeight, Network, NetworkParameters};
+
+    #[test]
+    fn test_network_conversions() {
+        for network in [Network::Main, Network::Test, Network::Regtest] {
+            assert_eq!(Network::from(NetworkType::from(network)), network);
+        }
+        for network in [
+            consensus::Network::MainNetwork,
+            consensus::Network::TestNetwork,
+        ] {
+            assert_eq!(
+                consensus::Network::try_from(Network::from(network)).unwrap(),
+                network
+            );
+        }
+        assert!(consensus::Network::try_from(Network::Regtest).is_err());
+    }
+
+    #[test]
+    fn test_local_network() {
+        let h = |height: u32| Some(BlockHeight::from(height));
+        let params = NetworkParameters::custom_regtest([h(1), h(1), h(5), h(5), h(10), None]);
+        let local = LocalNetwork::try_from(&params).unwrap();
+        assert_eq!(local.network_type(), NetworkType::Regtest);
+        assert_eq!(
+            local.activation_height(consensus::NetworkUpgrade::Canopy),
+            Some(consensus::BlockHeight::from(10))
+        );
+        assert_eq!(
+            local.activation_height(consensus::NetworkUpgrade::Nu5),
+            None
+        );
+
+        assert!(LocalNetwork::try_from(&NetworkParameters::for_network(Network::Main)).is_err());
+    }
+
+    #[test]
+    fn test_block_height() {
+        let height = BlockHeight::from(1_687_104u32);
+        assert_eq!(
+            consensus::BlockHeight::from(height),
+            consensus::BlockHeight::from_u32(1_687_104)
+        );
+        assert_eq!(
+            BlockHeight::from(consensus::BlockHeight::from(height)),
+            height
+        );
+    }
+}
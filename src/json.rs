@@ -0,0 +1,689 @@
+//! JSON export and import of the full Zewif structure.
+//!
+//! The JSON form is produced by `serde` implementations on the types of the
+//! Zewif hierarchy, which are only compiled with the `json` feature. The
+//! helpers here serialize the values whose JSON form differs from the one
+//! `serde` would derive.
+
+use std::collections::{HashMap, HashSet};
+
+use bc_components::ARID;
+use bc_envelope::prelude::*;
+use chrono::{DateTime, SecondsFormat};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
+use serde_json::Value;
+
+use crate::{
+    Anchor, Blob, BlockHash, BlockHeight, Data, DerivationPath, DiversifierIndex, Memo, Result,
+    Script, SecondsSinceEpoch, SeedFingerprint, Transaction, TxId, Zewif,
+    orchard::{MerkleHashOrchard, OrchardFullViewingKey, OrchardNullifier, OrchardSpendingKey},
+    sapling::{
+        MerkleHashSapling, SaplingDiversifiableFullViewingKey, SaplingExtendedFullViewingKey,
+        SaplingExtendedSpendingKey, SaplingIncomingViewingKey, SaplingNullifier,
+    },
+    sprout::{MerkleHashSprout, SproutNullifier},
+    transparent::{TransparentAccountPubKey, TransparentSpendingKey},
+    watch_only::SECRET_PREDICATES,
+};
+
+/// The variants holding a spending key of [`KeyEntry`](crate::KeyEntry) and
+/// [`OrphanedRecord`](crate::OrphanedRecord), whose JSON form is an object
+/// keyed by the variant's name. In envelopes they are encoded under the
+/// `spending_key` predicate.
+const SPENDING_KEY_VARIANTS: &[&str] = &[
+    "TransparentSpendingKey",
+    "SaplingSpendingKey",
+    "OrchardSpendingKey",
+    "SproutSpendingKey",
+];
+
+/// Returns whether [`Zewif::to_json`] drops `field` when secrets are
+/// redacted: the fields named for the predicates of secrets in envelopes,
+/// and the spending key variants.
+fn is_redacted_field(field: &str) -> bool {
+    SECRET_PREDICATES.contains(&field) || SPENDING_KEY_VARIANTS.contains(&field)
+}
+
+impl Zewif {
+    /// Returns the Zewif as pretty-printed JSON, for auditing and for tools
+    /// that cannot read envelopes.
+    ///
+    /// Each struct is a JSON object whose fields are named as in this crate,
+    /// and each enum is an object with a single field named for its variant,
+    /// or, for a variant without data, the variant's name as a string. Values
+    /// are written as follows:
+    ///
+    /// - Keys, nullifiers, hashes, ciphertexts, and other byte strings are
+    ///   lowercase hex. Transaction IDs and block hashes are in the reversed
+    ///   byte order used by block explorers.
+    /// - Addresses are their canonical encoded strings.
+    /// - Amounts are integers of zatoshis, and block heights integers.
+    /// - Times are ISO 8601 timestamps in UTC, such as
+    ///   `2018-10-29T02:15:37Z`, or strings of the number of seconds since
+    ///   1970 for times too far in the future to be dates.
+    /// - Networks are `"main"`, `"test"`, or `"regtest"`, and derivation
+    ///   paths are strings such as `"m/32'/133'/0'"`.
+    /// - Transactions, and the anchors of an anchor registry, are arrays
+    ///   sorted by transaction ID and by height, so that the JSON form of a
+    ///   Zewif is stable.
+    /// - Attachments are arrays of objects giving the `vendor`, the optional
+    ///   `conforms_to` URI, and the `payload` as a `ur:envelope` string.
+    ///
+    /// With `redact_secrets`, the secrets elided from a watch-only export
+    /// (see [`Zewif::to_watch_only_envelope`]) are omitted entirely: the
+    /// seed material of each wallet seed, which keeps its fingerprint and
+    /// label, and every spending key, including orphaned ones, and
+    /// transparent spend authority. The redacted form cannot be read by
+    /// [`Zewif::from_json`].
+    pub fn to_json(&self, redact_secrets: bool) -> Result<String> {
+        let mut value = serde_json::to_value(self)?;
+        if redact_secrets {
+            redact(&mut value);
+        }
+        Ok(serde_json::to_string_pretty(&value)?)
+    }
+
+    /// Reads a Zewif from the JSON written by [`Zewif::to_json`] without
+    /// redaction.
+    ///
+    /// # Errors
+    /// Returns [`Error::Json`](crate::Error::Json) if the JSON is malformed
+    /// or does not describe a Zewif.
+    pub fn from_json(json: &str) -> Result<Zewif> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            object.retain(|field, _| !is_redacted_field(field));
+            object.values_mut().for_each(redact);
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` for a type whose JSON form is a
+/// string, given its conversions to and from a string.
+macro_rules! string_serde {
+    ($type:ty, $to_string:expr, $from_str:expr) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_str(&$to_string(self))
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                let string = String::deserialize(deserializer)?;
+                $from_str(string.as_str()).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+string_serde!(Data, |data: &Data| hex::encode(data), Data::from_hex);
+string_serde!(Memo, |memo: &Memo| hex::encode(memo), |hex| Data::from_hex(
+    hex
+)
+.map(|data| Memo::from_vec(data.to_vec())));
+string_serde!(Script, |script: &Script| hex::encode(script), |hex| {
+    Data::from_hex(hex).map(Script::from)
+});
+string_serde!(TxId, TxId::to_string, TxId::from_hex);
+string_serde!(BlockHash, BlockHash::to_string, BlockHash::from_hex);
+string_serde!(
+    DerivationPath,
+    DerivationPath::to_string,
+    str::parse::<DerivationPath>
+);
+string_serde!(SecondsSinceEpoch, iso_8601, from_iso_8601);
+
+/// Implements the hex JSON form of types defined with the [`blob!`](crate::blob)
+/// macro.
+macro_rules! blob_serde {
+    ($($type:ty),* $(,)?) => {
+        $(string_serde!($type, <$type>::to_hex, <$type>::from_hex);)*
+    };
+}
+
+blob_serde!(
+    Anchor,
+    DiversifierIndex,
+    SeedFingerprint,
+    MerkleHashOrchard,
+    OrchardFullViewingKey,
+    OrchardNullifier,
+    OrchardSpendingKey,
+    MerkleHashSapling,
+    SaplingDiversifiableFullViewingKey,
+    SaplingExtendedFullViewingKey,
+    SaplingExtendedSpendingKey,
+    SaplingIncomingViewingKey,
+    SaplingNullifier,
+    MerkleHashSprout,
+    SproutNullifier,
+    TransparentAccountPubKey,
+    TransparentSpendingKey,
+);
+
+impl<const N: usize> Serialize for Blob<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(self))
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Blob<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        Blob::from_hex(&string).map_err(D::Error::custom)
+    }
+}
+
+fn iso_8601(time: &SecondsSinceEpoch) -> String {
+    i64::try_from(u64::from(*time))
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .map_or_else(
+            || time.to_string(),
+            |time| time.to_rfc3339_opts(SecondsFormat::Secs, true),
+        )
+}
+
+fn from_iso_8601(string: &str) -> std::result::Result<SecondsSinceEpoch, String> {
+    if let Ok(seconds) = string.parse::<u64>() {
+        return Ok(SecondsSinceEpoch::from(seconds));
+    }
+    let time = DateTime::parse_from_rfc3339(string).map_err(|e| e.to_string())?;
+    u64::try_from(time.timestamp())
+        .map(SecondsSinceEpoch::from)
+        .map_err(|_| format!("time before 1970: {}", string))
+}
+
+/// Serializes byte arrays, such as address prefixes, as hex.
+pub(crate) mod hex_bytes {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        bytes: &impl AsRef<[u8]>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<Vec<u8>>,
+    {
+        let bytes = hex::decode(String::deserialize(deserializer)?).map_err(D::Error::custom)?;
+        let len = bytes.len();
+        T::try_from(bytes)
+            .map_err(|_| D::Error::invalid_length(len, &"the number of bytes of the value"))
+    }
+}
+
+/// Serializes the Zewif's ID as hex.
+pub(crate) mod arid {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        id: &ARID,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&id.hex())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<ARID, D::Error> {
+        let data = hex::decode(String::deserialize(deserializer)?).map_err(D::Error::custom)?;
+        ARID::from_data_ref(data).map_err(D::Error::custom)
+    }
+}
+
+/// Serializes a set of transaction IDs as a sorted array.
+pub(crate) mod txid_set {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        txids: &HashSet<TxId>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let mut txids: Vec<_> = txids.iter().collect();
+        txids.sort();
+        txids.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<HashSet<TxId>, D::Error> {
+        Ok(Vec::<TxId>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+/// Serializes the anchors of an anchor registry as an array of anchors and
+/// heights, sorted by height.
+pub(crate) mod anchors {
+    use std::hash::Hash;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Entry<Node> {
+        anchor: Node,
+        height: BlockHeight,
+    }
+
+    pub(crate) fn serialize<S, Node>(
+        anchors: &HashMap<Node, BlockHeight>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        Node: Serialize + AsRef<[u8]> + Clone,
+    {
+        let mut entries: Vec<_> = anchors
+            .iter()
+            .map(|(anchor, height)| Entry {
+                anchor: anchor.clone(),
+                height: *height,
+            })
+            .collect();
+        entries.sort_by(|a, b| (a.height, a.anchor.as_ref()).cmp(&(b.height, b.anchor.as_ref())));
+        entries.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D, Node>(
+        deserializer: D,
+    ) -> std::result::Result<HashMap<Node, BlockHeight>, D::Error>
+    where
+        D: Deserializer<'de>,
+        Node: Deserialize<'de> + Hash + Eq,
+    {
+        let entries = Vec::<Entry<Node>>::deserialize(deserializer)?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| (entry.anchor, entry.height))
+            .collect())
+    }
+}
+
+/// Serializes the transactions of a Zewif as an array sorted by ID.
+pub(crate) mod transactions {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        transactions: &HashMap<TxId, Transaction>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let mut transactions: Vec<_> = transactions.values().collect();
+        transactions.sort_by_key(|transaction| transaction.txid());
+        transactions.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<HashMap<TxId, Transaction>, D::Error> {
+        let transactions = Vec::<Transaction>::deserialize(deserializer)?;
+        Ok(transactions
+            .into_iter()
+            .map(|transaction| (transaction.txid(), transaction))
+            .collect())
+    }
+}
+
+/// Serializes attachments as an array of their vendors, conformance URIs, and
+/// payloads, sorted by payload.
+pub(crate) mod attachments {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Attachment {
+        vendor: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        conforms_to: Option<String>,
+        payload: String,
+    }
+
+    pub(crate) fn serialize<S: Serializer>(
+        attachments: &Attachments,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let holder = attachments.add_to_envelope(Envelope::unit());
+        let mut entries = holder
+            .assertions_with_predicate(known_values::ATTACHMENT)
+            .into_iter()
+            .map(|attachment| {
+                Ok(Attachment {
+                    vendor: attachment
+                        .attachment_vendor()
+                        .map_err(serde::ser::Error::custom)?,
+                    conforms_to: attachment
+                        .attachment_conforms_to()
+                        .map_err(serde::ser::Error::custom)?,
+                    payload: UR::new(
+                        "envelope",
+                        attachment
+                            .attachment_payload()
+                            .map_err(serde::ser::Error::custom)?
+                            .untagged_cbor(),
+                    )
+                    .map_err(serde::ser::Error::custom)?
+                    .string(),
+                })
+            })
+            .collect::<std::result::Result<Vec<_>, S::Error>>()?;
+        entries.sort_by(|a, b| a.payload.cmp(&b.payload));
+        entries.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Attachments, D::Error> {
+        let mut attachments = Attachments::new();
+        for entry in Vec::<Attachment>::deserialize(deserializer)? {
+            let ur = UR::from_ur_string(&entry.payload).map_err(D::Error::custom)?;
+            ur.check_type("envelope").map_err(D::Error::custom)?;
+            let payload = Envelope::from_untagged_cbor(ur.cbor()).map_err(D::Error::custom)?;
+            attachments.add(payload, entry.vendor, entry.conforms_to);
+        }
+        Ok(attachments)
+    }
+}
+
+/// Serializes an envelope kept verbatim, such as a key of an unknown type, as
+/// a `ur:envelope` string.
+pub(crate) mod envelope {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        envelope: &Envelope,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let ur =
+            UR::new("envelope", envelope.untagged_cbor()).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&ur.string())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Envelope, D::Error> {
+        let ur =
+            UR::from_ur_string(String::deserialize(deserializer)?).map_err(D::Error::custom)?;
+        ur.check_type("envelope").map_err(D::Error::custom)?;
+        Envelope::from_untagged_cbor(ur.cbor()).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::{
+        Account, Address, Bip39Mnemonic, BlockHash, BlockHeight, DerivationInfo, Error, KeyOrigin,
+        Network, NonHardenedChildIndex, ProtocolAddress, RandomInstance, SecondsSinceEpoch,
+        SeedMaterial, Transaction, TxBlockPosition, TxId, WalletProvenance, WalletSeed, Zewif,
+        ZewifWallet,
+        sapling::{self, SaplingIncomingViewingKey},
+        transparent::{self, TransparentSpendAuthority, TransparentSpendingKey},
+    };
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn zewif() -> Zewif {
+        let txid = TxId::from_bytes([0x11; 32]);
+        let mut transaction = Transaction::new(txid);
+        transaction.set_mined_height(BlockHeight::from(1_000_000));
+        transaction.set_block_position(Some(TxBlockPosition::new(
+            BlockHash::from_bytes([0x22; 32]),
+            3,
+        )));
+
+        let mut transparent = transparent::Address::new("t1json");
+        transparent.set_spend_authority(TransparentSpendAuthority::SpendingKey(
+            TransparentSpendingKey::new([0xa4; 32]),
+        ));
+        transparent.set_derivation_info(DerivationInfo::new(
+            NonHardenedChildIndex::from(0u32),
+            NonHardenedChildIndex::from(7u32),
+        ));
+        transparent.set_key_origin(Some(KeyOrigin::Imported {
+            time: SecondsSinceEpoch::from(1_540_779_337),
+        }));
+
+        let mut sapling = sapling::Address::new("zs1json".to_string());
+        sapling.set_incoming_viewing_key(SaplingIncomingViewingKey::new([0x33; 32]));
+
+        let mut account = Account::new();
+        account.set_name("Savings");
+        account.set_birthday_height(Some(BlockHeight::from(999_000)));
+        account.add_address(Address::new(ProtocolAddress::Transparent(transparent)));
+        account.add_address(Address::new(ProtocolAddress::Sapling(Box::new(sapling))));
+        account.add_relevant_transaction(txid);
+
+        let mut provenance = WalletProvenance::new("zcashd");
+        provenance.set_source_version(Some("5.9.1".to_string()));
+        provenance.set_exported_at(Some(SecondsSinceEpoch::from(1_730_592_000)));
+
+        let mut wallet = ZewifWallet::new(Network::Main).with_provenance(provenance);
+        wallet.add_seed(
+            WalletSeed::new(SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(
+                PHRASE, None,
+            )))
+            .unwrap(),
+        );
+        wallet.add_account(account);
+
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        zewif.add_transaction(txid, transaction).unwrap();
+        zewif
+    }
+
+    /// Returns the JSON of `zewif` with its randomly assigned ID replaced by a
+    /// fixed one.
+    fn pinned_json(zewif: &Zewif, redact_secrets: bool) -> String {
+        let mut value: Value =
+            serde_json::from_str(&zewif.to_json(redact_secrets).unwrap()).unwrap();
+        value["id"] = Value::from("00".repeat(32));
+        value["wallets"][0]["accounts"][0]["id"] = Value::from("11".repeat(32));
+        serde_json::to_string_pretty(&value).unwrap()
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        for _ in 0..10 {
+            let zewif = Zewif::random();
+            let json = zewif.to_json(false).unwrap();
+            assert_eq!(Zewif::from_json(&json).unwrap(), zewif);
+        }
+        let zewif = zewif();
+        assert_eq!(
+            Zewif::from_json(&zewif.to_json(false).unwrap()).unwrap(),
+            zewif
+        );
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let expected = r#"{
+  "attachments": [],
+  "export_height": 2000000,
+  "id": "0000000000000000000000000000000000000000000000000000000000000000",
+  "transactions": [
+    {
+      "attachments": [],
+      "block_hash": null,
+      "block_position": {
+        "block_hash": "2222222222222222222222222222222222222222222222222222222222222222",
+        "index": 3
+      },
+      "block_time": null,
+      "consensus_branch_id": null,
+      "expiry_height": null,
+      "fee": null,
+      "joinsplits": [],
+      "mined_height": 1000000,
+      "orchard_actions": [],
+      "output_annotations": [],
+      "raw": null,
+      "sapling_spends": [],
+      "status": "Unknown",
+      "target_height": null,
+      "transparent_inputs": [],
+      "transparent_outputs": [],
+      "txid": "1111111111111111111111111111111111111111111111111111111111111111",
+      "version": null
+    }
+  ],
+  "wallets": [
+    {
+      "accounts": [
+        {
+          "addresses": [
+            {
+              "address": {
+                "Transparent": {
+                  "address": "t1json",
+                  "derivation_info": {
+                    "address_index": 7,
+                    "change": 0
+                  },
+                  "key_origin": {
+                    "Imported": {
+                      "time": "2018-10-29T02:15:37Z"
+                    }
+                  },
+                  "spend_authority": {
+                    "SpendingKey": "a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4a4"
+                  }
+                }
+              },
+              "attachments": [],
+              "index": 0,
+              "name": "",
+              "purpose": null
+            },
+            {
+              "address": {
+                "Sapling": {
+                  "address": "zs1json",
+                  "diversifier_index": null,
+                  "full_viewing_key": null,
+                  "hd_derivation_path": null,
+                  "incoming_viewing_key": "3333333333333333333333333333333333333333333333333333333333333333",
+                  "key_origin": null,
+                  "spending_key": null
+                }
+              },
+              "attachments": [],
+              "index": 1,
+              "name": "",
+              "purpose": null
+            }
+          ],
+          "attachments": [],
+          "birthday_block": null,
+          "birthday_height": 999000,
+          "coin_type": null,
+          "derivation": "Unknown",
+          "id": "1111111111111111111111111111111111111111111111111111111111111111",
+          "index": 0,
+          "is_legacy_default": false,
+          "key_material": [],
+          "name": "Savings",
+          "orchard_derivation": null,
+          "orchard_key_origin": null,
+          "orchard_received_notes": [],
+          "orchard_sent_outputs": [],
+          "orchard_spending_key": null,
+          "relevant_transactions": [
+            "1111111111111111111111111111111111111111111111111111111111111111"
+          ],
+          "sapling_received_notes": [],
+          "sapling_sent_outputs": [],
+          "seed_fingerprint": null,
+          "sprout_migration_status": null,
+          "sprout_received_notes": [],
+          "unified_metadata": null,
+          "utxos": [],
+          "zip32_account_id": null
+        }
+      ],
+      "attachments": [],
+      "birthday_height": null,
+      "index": 0,
+      "key_pool": [],
+      "network": "main",
+      "network_parameters": null,
+      "orchard_anchors": null,
+      "orchard_tree_state": null,
+      "orphaned_data": [],
+      "provenance": {
+        "exported_at": "2024-11-03T00:00:00Z",
+        "notes": null,
+        "source_format": null,
+        "source_software": "zcashd",
+        "source_version": "5.9.1",
+        "tool_name": null,
+        "tool_version": null
+      },
+      "sapling_anchors": null,
+      "sapling_tree_state": null,
+      "seeds": [
+        {
+          "fingerprint": "21ed3d7882c7e37fe012b54a6408048048cb09782d4b2938617da793ccd27815",
+          "index": 0,
+          "label": null,
+          "seed_material": {
+            "Bip39Mnemonic": {
+              "fingerprint": null,
+              "language": null,
+              "mnemonic": "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+              "passphrase": null
+            }
+          }
+        }
+      ]
+    }
+  ]
+}"#;
+        assert_eq!(pinned_json(&zewif(), false), expected);
+    }
+
+    #[test]
+    fn test_redacted_json() {
+        use crate::{OrphanedRecord, orchard::OrchardSpendingKey};
+
+        let mut zewif = zewif();
+        let key = OrchardSpendingKey::new([0xb5; 32]);
+        zewif.wallets_mut()[0].add_orphaned_record(OrphanedRecord::OrchardSpendingKey(key));
+        assert!(zewif.to_json(false).unwrap().contains(&"b5".repeat(32)));
+
+        let json = zewif.to_json(true).unwrap();
+        assert!(!json.contains(&"b5".repeat(32)));
+        assert!(json.contains("zs1json"));
+        assert!(json.contains(&"33".repeat(32)));
+        assert!(!json.contains("abandon"));
+        // Seeds keep their fingerprints, as in a watch-only export.
+        assert!(json.contains("\"seeds\""));
+        assert!(!json.contains(&"a4".repeat(32)));
+        for field in super::SECRET_PREDICATES
+            .iter()
+            .chain(super::SPENDING_KEY_VARIANTS)
+        {
+            assert!(!json.contains(&format!("\"{}\"", field)));
+        }
+        assert!(matches!(Zewif::from_json(&json), Err(Error::Json(_))));
+    }
+}
@@ -0,0 +1,202 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use bc_components::{Salt, SymmetricKey};
+use bc_envelope::prelude::*;
+use zeroize::Zeroize;
+
+use crate::{Error, Result};
+
+/// The Argon2id cost parameters used to derive a content key from a password;
+/// see [`Zewif::encrypt_with_password`](crate::Zewif::encrypt_with_password).
+///
+/// The parameters are stored with the encrypted envelope, so decryption needs
+/// only the password. The defaults are those recommended by the `argon2`
+/// crate: 19 MiB of memory, two iterations, and one lane.
+///
+/// Since a file's parameters are chosen by whoever wrote it, keys are only
+/// derived with parameters up to [`KdfParams::MAX_MEMORY_KIB`],
+/// [`KdfParams::MAX_ITERATIONS`], and [`KdfParams::MAX_PARALLELISM`]. These
+/// allow far costlier parameters than the defaults, while bounding what a
+/// crafted file can demand to 1 GiB of memory and ten passes over it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl KdfParams {
+    /// The largest memory cost accepted, 1 GiB.
+    pub const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+
+    /// The largest number of iterations accepted.
+    pub const MAX_ITERATIONS: u32 = 10;
+
+    /// The largest number of lanes accepted.
+    pub const MAX_PARALLELISM: u32 = 16;
+
+    pub fn new(memory_kib: u32, iterations: u32, parallelism: u32) -> Self {
+        Self {
+            memory_kib,
+            iterations,
+            parallelism,
+        }
+    }
+
+    /// Returns the memory cost in KiB.
+    pub fn memory_kib(&self) -> u32 {
+        self.memory_kib
+    }
+
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// Returns the number of lanes.
+    pub fn parallelism(&self) -> u32 {
+        self.parallelism
+    }
+
+    /// Derives a 256-bit content key from `password` and `salt`.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidKdfParams`] if the parameters exceed the
+    /// limits above or are outside the ranges Argon2 accepts.
+    pub fn derive_key(&self, password: impl AsRef<[u8]>, salt: &Salt) -> Result<SymmetricKey> {
+        self.check_limits()?;
+        let params = Params::new(
+            self.memory_kib,
+            self.iterations,
+            self.parallelism,
+            Some(SymmetricKey::SYMMETRIC_KEY_SIZE),
+        )
+        .map_err(invalid_params)?;
+        let mut key = [0u8; SymmetricKey::SYMMETRIC_KEY_SIZE];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_ref(), salt.as_bytes(), &mut key)
+            .map_err(invalid_params)?;
+        let derived = SymmetricKey::from_data(key);
+        key.zeroize();
+        Ok(derived)
+    }
+
+    fn check_limits(&self) -> Result<()> {
+        let limits = [
+            ("memory_kib", self.memory_kib, Self::MAX_MEMORY_KIB),
+            ("iterations", self.iterations, Self::MAX_ITERATIONS),
+            ("parallelism", self.parallelism, Self::MAX_PARALLELISM),
+        ];
+        for (name, value, max) in limits {
+            if value > max {
+                let reason = format!("is {}, more than the limit of {}", value, max);
+                return Err(Error::InvalidKdfParams {
+                    parameter: name,
+                    reason,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Names the parameter an Argon2 error refers to.
+fn invalid_params(error: argon2::Error) -> Error {
+    let parameter = match error {
+        argon2::Error::MemoryTooLittle | argon2::Error::MemoryTooMuch => "memory_kib",
+        argon2::Error::TimeTooSmall => "iterations",
+        argon2::Error::ThreadsTooFew | argon2::Error::ThreadsTooMany => "parallelism",
+        argon2::Error::SaltTooShort | argon2::Error::SaltTooLong => "salt",
+        _ => "output",
+    };
+    Error::InvalidKdfParams {
+        parameter,
+        reason: error.to_string(),
+    }
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self::new(
+            Params::DEFAULT_M_COST,
+            Params::DEFAULT_T_COST,
+            Params::DEFAULT_P_COST,
+        )
+    }
+}
+
+impl From<KdfParams> for Envelope {
+    fn from(value: KdfParams) -> Self {
+        Envelope::new("Argon2id")
+            .add_type("KdfParams")
+            .add_assertion("memory_kib", value.memory_kib)
+            .add_assertion("iterations", value.iterations)
+            .add_assertion("parallelism", value.parallelism)
+    }
+}
+
+impl TryFrom<Envelope> for KdfParams {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("KdfParams")?;
+        let algorithm: String = envelope.extract_subject()?;
+        if algorithm != "Argon2id" {
+            return Err(bc_envelope::Error::General(format!(
+                "unsupported key derivation function: {}",
+                algorithm
+            )));
+        }
+        Ok(Self {
+            memory_kib: envelope.extract_object_for_predicate("memory_kib")?,
+            iterations: envelope.extract_object_for_predicate("iterations")?,
+            parallelism: envelope.extract_object_for_predicate("parallelism")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::Salt;
+
+    use crate::{Error, test_envelope_roundtrip};
+
+    use super::KdfParams;
+
+    impl crate::RandomInstance for KdfParams {
+        fn random() -> Self {
+            Self::new(
+                rand::random_range(8..=65536),
+                rand::random_range(1..=4),
+                rand::random_range(1..=4),
+            )
+        }
+    }
+
+    test_envelope_roundtrip!(KdfParams);
+
+    #[test]
+    fn test_limits() {
+        let salt = Salt::new_with_len(16).unwrap();
+        let excessive = [
+            KdfParams::new(KdfParams::MAX_MEMORY_KIB + 1, 2, 1),
+            KdfParams::new(19 * 1024, u32::MAX, 1),
+            KdfParams::new(19 * 1024, 2, KdfParams::MAX_PARALLELISM + 1),
+        ];
+        for params in excessive {
+            assert!(matches!(
+                params.derive_key("password", &salt),
+                Err(Error::InvalidKdfParams { .. })
+            ));
+        }
+        // The limits themselves are accepted.
+        assert!(
+            KdfParams::new(64, KdfParams::MAX_ITERATIONS, 1)
+                .derive_key("password", &salt)
+                .is_ok()
+        );
+        assert!(
+            KdfParams::new(128, 1, KdfParams::MAX_PARALLELISM)
+                .derive_key("password", &salt)
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,360 @@
+use bc_envelope::prelude::*;
+
+use crate::{
+    orchard::{OrchardFullViewingKey, OrchardSpendingKey},
+    sapling::{
+        SaplingDiversifiableFullViewingKey, SaplingExtendedFullViewingKey,
+        SaplingExtendedSpendingKey, SaplingIncomingViewingKey,
+    },
+    sprout,
+    transparent::{TransparentAccountPubKey, TransparentSpendingKey},
+    watch_only::optional_spending_object,
+};
+
+/// A key held by an account; see [`KeyMaterial`].
+///
+/// Spending keys are encoded under the `spending_key` predicate, so they are
+/// elided from a watch-only export and decode as [`KeyEntry::Redacted`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyEntry {
+    TransparentSpendingKey(TransparentSpendingKey),
+    TransparentAccountPubKey(TransparentAccountPubKey),
+    SaplingSpendingKey(SaplingExtendedSpendingKey),
+    SaplingFullViewingKey(SaplingExtendedFullViewingKey),
+    SaplingDiversifiableFullViewingKey(SaplingDiversifiableFullViewingKey),
+    SaplingIncomingViewingKey(SaplingIncomingViewingKey),
+    OrchardSpendingKey(OrchardSpendingKey),
+    OrchardFullViewingKey(OrchardFullViewingKey),
+    SproutSpendingKey(sprout::SpendingKey),
+    SproutViewingKey(sprout::ViewingKey),
+
+    /// A key of a type this version of the format does not know, kept as the
+    /// envelope it was encoded as, so that it survives a round trip through
+    /// this version unchanged.
+    Other {
+        type_tag: String,
+        #[cfg_attr(feature = "json", serde(with = "crate::json::envelope"))]
+        envelope: Envelope,
+    },
+
+    /// A spending key elided from a watch-only export; see
+    /// [`Zewif::to_watch_only_envelope`](crate::Zewif::to_watch_only_envelope)
+    Redacted,
+}
+
+impl KeyEntry {
+    /// Returns the tag identifying the entry's type in its encoding.
+    pub fn type_tag(&self) -> &str {
+        match self {
+            Self::TransparentSpendingKey(_) => "TransparentSpendingKey",
+            Self::TransparentAccountPubKey(_) => "TransparentAccountPubKey",
+            Self::SaplingSpendingKey(_) => "SaplingSpendingKey",
+            Self::SaplingFullViewingKey(_) => "SaplingFullViewingKey",
+            Self::SaplingDiversifiableFullViewingKey(_) => "SaplingDiversifiableFullViewingKey",
+            Self::SaplingIncomingViewingKey(_) => "SaplingIncomingViewingKey",
+            Self::OrchardSpendingKey(_) => "OrchardSpendingKey",
+            Self::OrchardFullViewingKey(_) => "OrchardFullViewingKey",
+            Self::SproutSpendingKey(_) => "SproutSpendingKey",
+            Self::SproutViewingKey(_) => "SproutViewingKey",
+            Self::Other { type_tag, .. } => type_tag,
+            Self::Redacted => "Redacted",
+        }
+    }
+
+    /// Returns whether the entry is a spending key.
+    pub fn is_spending_key(&self) -> bool {
+        matches!(
+            self,
+            Self::TransparentSpendingKey(_)
+                | Self::SaplingSpendingKey(_)
+                | Self::OrchardSpendingKey(_)
+                | Self::SproutSpendingKey(_)
+        )
+    }
+}
+
+/// A key type that can be stored in a [`KeyMaterial`], and retrieved with
+/// [`KeyMaterial::keys_of_type`].
+pub trait KeyType: Into<KeyEntry> + 'static {
+    /// Returns the key held by `entry`, if it is of this type.
+    fn from_entry(entry: &KeyEntry) -> Option<&Self>;
+}
+
+macro_rules! key_type {
+    ($($variant:ident($type:ty)),* $(,)?) => {
+        $(
+            impl From<$type> for KeyEntry {
+                fn from(value: $type) -> Self {
+                    KeyEntry::$variant(value)
+                }
+            }
+
+            impl KeyType for $type {
+                fn from_entry(entry: &KeyEntry) -> Option<&Self> {
+                    match entry {
+                        KeyEntry::$variant(key) => Some(key),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+key_type!(
+    TransparentSpendingKey(TransparentSpendingKey),
+    TransparentAccountPubKey(TransparentAccountPubKey),
+    SaplingSpendingKey(SaplingExtendedSpendingKey),
+    SaplingFullViewingKey(SaplingExtendedFullViewingKey),
+    SaplingDiversifiableFullViewingKey(SaplingDiversifiableFullViewingKey),
+    SaplingIncomingViewingKey(SaplingIncomingViewingKey),
+    OrchardSpendingKey(OrchardSpendingKey),
+    OrchardFullViewingKey(OrchardFullViewingKey),
+    SproutSpendingKey(sprout::SpendingKey),
+    SproutViewingKey(sprout::ViewingKey),
+);
+
+/// Viewing keys, and keys of unknown types, are under `key`; spending keys
+/// are under `spending_key`.
+impl From<KeyEntry> for Envelope {
+    fn from(value: KeyEntry) -> Self {
+        let e = Envelope::new(value.type_tag()).add_type("KeyEntry");
+        match value {
+            KeyEntry::TransparentSpendingKey(key) => e.add_assertion("spending_key", key),
+            KeyEntry::TransparentAccountPubKey(key) => e.add_assertion("key", key),
+            KeyEntry::SaplingSpendingKey(key) => e.add_assertion("spending_key", key),
+            KeyEntry::SaplingFullViewingKey(key) => e.add_assertion("key", key),
+            KeyEntry::SaplingDiversifiableFullViewingKey(key) => e.add_assertion("key", key),
+            KeyEntry::SaplingIncomingViewingKey(key) => e.add_assertion("key", key),
+            KeyEntry::OrchardSpendingKey(key) => e.add_assertion("spending_key", key),
+            KeyEntry::OrchardFullViewingKey(key) => e.add_assertion("key", key),
+            KeyEntry::SproutSpendingKey(key) => e.add_assertion("spending_key", key),
+            KeyEntry::SproutViewingKey(key) => e.add_assertion("key", key),
+            KeyEntry::Other { envelope, .. } => e.add_assertion("key", envelope),
+            KeyEntry::Redacted => e,
+        }
+    }
+}
+
+impl TryFrom<Envelope> for KeyEntry {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("KeyEntry")?;
+        let type_tag = envelope.extract_subject::<String>()?;
+        let entry = match type_tag.as_str() {
+            "TransparentSpendingKey" => optional_spending_object(&envelope, "spending_key")?
+                .map(KeyEntry::TransparentSpendingKey),
+            "TransparentAccountPubKey" => Some(KeyEntry::TransparentAccountPubKey(
+                envelope.try_object_for_predicate("key")?,
+            )),
+            "SaplingSpendingKey" => optional_spending_object(&envelope, "spending_key")?
+                .map(KeyEntry::SaplingSpendingKey),
+            "SaplingFullViewingKey" => Some(KeyEntry::SaplingFullViewingKey(
+                envelope.try_object_for_predicate("key")?,
+            )),
+            "SaplingDiversifiableFullViewingKey" => {
+                Some(KeyEntry::SaplingDiversifiableFullViewingKey(
+                    envelope.try_object_for_predicate("key")?,
+                ))
+            }
+            "SaplingIncomingViewingKey" => Some(KeyEntry::SaplingIncomingViewingKey(
+                envelope.try_object_for_predicate("key")?,
+            )),
+            "OrchardSpendingKey" => optional_spending_object(&envelope, "spending_key")?
+                .map(KeyEntry::OrchardSpendingKey),
+            "OrchardFullViewingKey" => Some(KeyEntry::OrchardFullViewingKey(
+                envelope.try_object_for_predicate("key")?,
+            )),
+            "SproutSpendingKey" => optional_spending_object(&envelope, "spending_key")?
+                .map(KeyEntry::SproutSpendingKey),
+            "SproutViewingKey" => Some(KeyEntry::SproutViewingKey(
+                envelope.try_object_for_predicate("key")?,
+            )),
+            "Redacted" => None,
+            _ => Some(KeyEntry::Other {
+                envelope: envelope.object_for_predicate("key")?,
+                type_tag,
+            }),
+        };
+        Ok(entry.unwrap_or(KeyEntry::Redacted))
+    }
+}
+
+/// The keys held by an account, of any type.
+///
+/// New key types can be carried without changes to [`Account`](crate::Account):
+/// a type this version does not know decodes as [`KeyEntry::Other`] and is
+/// re-encoded exactly as it was read. Keys tied to a single address, such as
+/// the keys of a zcashd Sapling address, remain with their address.
+///
+/// # Examples
+/// ```
+/// # use zewif::{KeyMaterial, orchard::OrchardFullViewingKey, sapling::SaplingIncomingViewingKey};
+/// let mut keys = KeyMaterial::new();
+/// keys.add_key(OrchardFullViewingKey::new([1; 96]));
+/// keys.add_key(SaplingIncomingViewingKey::new([2; 32]));
+/// keys.add_key(SaplingIncomingViewingKey::new([3; 32]));
+/// assert_eq!(keys.keys_of_type::<SaplingIncomingViewingKey>().count(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyMaterial(Vec<KeyEntry>);
+
+impl KeyMaterial {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> &[KeyEntry] {
+        &self.0
+    }
+
+    pub fn add_key(&mut self, key: impl Into<KeyEntry>) {
+        self.0.push(key.into());
+    }
+
+    /// Returns the keys of type `T`, in the order they were added.
+    pub fn keys_of_type<T: KeyType>(&self) -> impl Iterator<Item = &T> {
+        self.0.iter().filter_map(T::from_entry)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Each entry is an assertion carrying its position, so that the entries
+/// keep their order.
+impl From<KeyMaterial> for Envelope {
+    fn from(value: KeyMaterial) -> Self {
+        value.0.into_iter().enumerate().fold(
+            Envelope::new("KeyMaterial").add_type("KeyMaterial"),
+            |e, (index, entry)| {
+                e.add_assertion("entry", Envelope::from(entry).add_assertion("index", index))
+            },
+        )
+    }
+}
+
+impl TryFrom<Envelope> for KeyMaterial {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("KeyMaterial")?;
+        let mut entries = envelope
+            .objects_for_predicate("entry")
+            .into_iter()
+            .map(|entry| {
+                Ok((
+                    entry.extract_object_for_predicate::<usize>("index")?,
+                    KeyEntry::try_from(entry)?,
+                ))
+            })
+            .collect::<bc_envelope::Result<Vec<_>>>()?;
+        entries.sort_by_key(|(index, _)| *index);
+        Ok(Self(entries.into_iter().map(|(_, entry)| entry).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Data, RandomInstance,
+        orchard::{OrchardFullViewingKey, OrchardSpendingKey},
+        sapling::{
+            SaplingDiversifiableFullViewingKey, SaplingExtendedFullViewingKey,
+            SaplingExtendedSpendingKey, SaplingIncomingViewingKey,
+        },
+        sprout, test_envelope_roundtrip,
+        transparent::{TransparentAccountPubKey, TransparentSpendingKey},
+        watch_only::elide_objects_of,
+    };
+
+    use super::{KeyEntry, KeyMaterial};
+
+    impl RandomInstance for KeyEntry {
+        fn random() -> Self {
+            match rand::random_range(0..12) {
+                0 => Self::TransparentSpendingKey(TransparentSpendingKey::random()),
+                1 => Self::TransparentAccountPubKey(TransparentAccountPubKey::random()),
+                2 => Self::SaplingSpendingKey(SaplingExtendedSpendingKey::random()),
+                3 => Self::SaplingFullViewingKey(SaplingExtendedFullViewingKey::random()),
+                4 => Self::SaplingDiversifiableFullViewingKey(
+                    SaplingDiversifiableFullViewingKey::random(),
+                ),
+                5 => Self::SaplingIncomingViewingKey(SaplingIncomingViewingKey::random()),
+                6 => Self::OrchardSpendingKey(OrchardSpendingKey::random()),
+                7 => Self::OrchardFullViewingKey(OrchardFullViewingKey::random()),
+                8 => Self::SproutSpendingKey(sprout::SpendingKey::random()),
+                9 => Self::SproutViewingKey(sprout::ViewingKey::random()),
+                10 => Self::Other {
+                    type_tag: "FutureKey".to_string(),
+                    envelope: Envelope::new(String::random())
+                        .add_assertion("version", u32::random()),
+                },
+                _ => Self::Redacted,
+            }
+        }
+    }
+
+    impl RandomInstance for KeyMaterial {
+        fn random() -> Self {
+            Self(Vec::random())
+        }
+    }
+
+    test_envelope_roundtrip!(KeyMaterial);
+
+    #[test]
+    fn test_unknown_key_type_roundtrip() {
+        let future = Envelope::new(Data::from(vec![9u8; 40]))
+            .add_type("FuturePoolViewingKey")
+            .add_assertion("diversifier_index", 7u64);
+        let written = Envelope::new("FuturePoolViewingKey")
+            .add_type("KeyEntry")
+            .add_assertion("key", future.clone());
+
+        let entry = KeyEntry::try_from(written.clone()).unwrap();
+        assert_eq!(
+            entry,
+            KeyEntry::Other {
+                type_tag: "FuturePoolViewingKey".to_string(),
+                envelope: future
+            }
+        );
+        assert!(Envelope::from(entry).is_identical_to(&written));
+    }
+
+    #[test]
+    fn test_keys_of_type_and_elision() {
+        let mut keys = KeyMaterial::new();
+        keys.add_key(SaplingExtendedSpendingKey::new([1; 169]));
+        keys.add_key(SaplingIncomingViewingKey::new([2; 32]));
+        assert_eq!(
+            keys.keys_of_type::<SaplingIncomingViewingKey>()
+                .collect::<Vec<_>>(),
+            [&SaplingIncomingViewingKey::new([2; 32])]
+        );
+        assert!(keys.entries()[0].is_spending_key());
+
+        let envelope = elide_objects_of(&Envelope::from(keys), &["spending_key"]);
+        let decoded = KeyMaterial::try_from(envelope).unwrap();
+        assert_eq!(decoded.entries()[0], KeyEntry::Redacted);
+        assert_eq!(
+            decoded.keys_of_type::<SaplingExtendedSpendingKey>().count(),
+            0
+        );
+        assert_eq!(
+            decoded.keys_of_type::<SaplingIncomingViewingKey>().count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use bc_envelope::prelude::*;
+
+use crate::{DerivationPath, SecondsSinceEpoch, SeedFingerprint};
+
+/// How a wallet came to hold a key: derived from one of its seeds, or
+/// imported.
+///
+/// zcashd records this in its key metadata (`keymeta`, `sapzkeymeta`): keys
+/// derived from the HD seed carry their derivation path and the fingerprint of
+/// the seed, while keys added with `importprivkey` or `z_importkey` carry only
+/// their creation time. Imported keys cannot be recovered from the wallet's
+/// seed, so a receiving wallet should back them up independently, and should
+/// rescan from the time they were imported.
+///
+/// # Examples
+/// ```
+/// # use zewif::{KeyOrigin, SecondsSinceEpoch};
+/// let origin = KeyOrigin::Imported { time: SecondsSinceEpoch::from(1_540_779_337u64) };
+/// assert!(origin.needs_backup());
+/// assert!(!KeyOrigin::Unknown.is_derived());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyOrigin {
+    /// Derived from a seed along the given path. The seed is identified by
+    /// its fingerprint, if known.
+    Derived {
+        path: DerivationPath,
+        seed_fingerprint: Option<SeedFingerprint>,
+    },
+
+    /// Imported into the wallet at the given time.
+    Imported { time: SecondsSinceEpoch },
+
+    /// The source wallet did not record the key's origin.
+    Unknown,
+}
+
+impl KeyOrigin {
+    pub fn is_derived(&self) -> bool {
+        matches!(self, Self::Derived { .. })
+    }
+
+    /// Returns the fingerprint of the seed the key is derived from, if known.
+    pub fn seed_fingerprint(&self) -> Option<&SeedFingerprint> {
+        match self {
+            Self::Derived {
+                seed_fingerprint, ..
+            } => seed_fingerprint.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the key must be backed up independently of the
+    /// wallet's seeds, which is the case unless it is known to be derived
+    /// from one.
+    pub fn needs_backup(&self) -> bool {
+        !self.is_derived()
+    }
+}
+
+impl From<KeyOrigin> for Envelope {
+    fn from(value: KeyOrigin) -> Self {
+        match value {
+            KeyOrigin::Derived {
+                path,
+                seed_fingerprint,
+            } => Envelope::new("Derived")
+                .add_assertion("path", path)
+                .add_optional_assertion("seed_fingerprint", seed_fingerprint),
+            KeyOrigin::Imported { time } => Envelope::new("Imported").add_assertion("time", time),
+            KeyOrigin::Unknown => Envelope::new("Unknown"),
+        }
+        .add_type("KeyOrigin")
+    }
+}
+
+impl TryFrom<Envelope> for KeyOrigin {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("KeyOrigin")?;
+        match envelope.extract_subject::<String>()?.as_str() {
+            "Derived" => Ok(KeyOrigin::Derived {
+                path: envelope.extract_object_for_predicate("path")?,
+                seed_fingerprint: envelope.try_optional_object_for_predicate("seed_fingerprint")?,
+            }),
+            "Imported" => Ok(KeyOrigin::Imported {
+                time: envelope.extract_object_for_predicate("time")?,
+            }),
+            "Unknown" => Ok(KeyOrigin::Unknown),
+            other => Err(bc_envelope::Error::General(format!(
+                "invalid key origin: {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DerivationPath, SecondsSinceEpoch, SeedFingerprint, test_envelope_roundtrip};
+
+    use super::KeyOrigin;
+
+    impl crate::RandomInstance for KeyOrigin {
+        fn random() -> Self {
+            match rand::random_range(0..3) {
+                0 => KeyOrigin::Derived {
+                    path: DerivationPath::random(),
+                    seed_fingerprint: SeedFingerprint::opt_random(),
+                },
+                1 => KeyOrigin::Imported {
+                    time: SecondsSinceEpoch::random(),
+                },
+                _ => KeyOrigin::Unknown,
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(KeyOrigin);
+}
@@ -0,0 +1,283 @@
+use bc_envelope::prelude::*;
+
+use crate::{Data, SecondsSinceEpoch};
+
+/// A key that a wallet generated in advance but has not yet handed out; see
+/// [`KeyPool`].
+///
+/// The key is identified by its public key, its address, or both, as the
+/// source wallet recorded them. Only entries with an address can be matched
+/// against the wallet's addresses by [`Zewif::validate`](crate::Zewif::validate).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyPoolEntry {
+    pool_index: u64,
+    public_key: Option<Data>,
+    address: Option<String>,
+    creation_time: Option<SecondsSinceEpoch>,
+    is_internal: bool,
+}
+
+impl KeyPoolEntry {
+    /// Creates an entry at `pool_index` on the external chain, or on the
+    /// internal (change) chain if `is_internal`.
+    pub fn new(pool_index: u64, is_internal: bool) -> Self {
+        Self {
+            pool_index,
+            public_key: None,
+            address: None,
+            creation_time: None,
+            is_internal,
+        }
+    }
+
+    pub fn pool_index(&self) -> u64 {
+        self.pool_index
+    }
+
+    pub fn public_key(&self) -> Option<&Data> {
+        self.public_key.as_ref()
+    }
+
+    pub fn set_public_key(&mut self, public_key: Option<Data>) {
+        self.public_key = public_key;
+    }
+
+    /// The encoded address of the key, if recorded.
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    pub fn set_address(&mut self, address: Option<String>) {
+        self.address = address;
+    }
+
+    pub fn creation_time(&self) -> Option<SecondsSinceEpoch> {
+        self.creation_time
+    }
+
+    pub fn set_creation_time(&mut self, creation_time: Option<SecondsSinceEpoch>) {
+        self.creation_time = creation_time;
+    }
+
+    /// Whether the key is on the internal (change) chain.
+    pub fn is_internal(&self) -> bool {
+        self.is_internal
+    }
+}
+
+impl From<KeyPoolEntry> for Envelope {
+    fn from(value: KeyPoolEntry) -> Self {
+        Envelope::new(value.pool_index)
+            .add_type("KeyPoolEntry")
+            .add_optional_assertion("public_key", value.public_key)
+            .add_optional_assertion("address", value.address)
+            .add_optional_assertion("creation_time", value.creation_time)
+            .add_optional_assertion("is_internal", value.is_internal.then_some(true))
+    }
+}
+
+impl TryFrom<Envelope> for KeyPoolEntry {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("KeyPoolEntry")?;
+        Ok(Self {
+            pool_index: envelope.extract_subject()?,
+            public_key: envelope.try_optional_object_for_predicate("public_key")?,
+            address: envelope.try_optional_object_for_predicate("address")?,
+            creation_time: envelope.try_optional_object_for_predicate("creation_time")?,
+            // Only asserted for keys on the internal chain.
+            is_internal: envelope
+                .extract_optional_object_for_predicate("is_internal")?
+                .unwrap_or(false),
+        })
+    }
+}
+
+/// The pool of keys a wallet generated in advance but has not yet handed out.
+///
+/// # Zcash Concept Relation
+/// zcashd keeps a pool of pre-generated transparent keys (its `pool`
+/// records), each with a pool index, a creation time, and whether it is for
+/// the internal (change) chain. These keys belong to the wallet even though
+/// no address has been given out for them, so a restoring wallet must look
+/// ahead at least as far as the highest pool index when scanning for funds.
+/// Importers should populate the pool from the source wallet's records.
+///
+/// Entries are kept in order of pool index, and adding an entry replaces any
+/// with the same index.
+///
+/// # Examples
+/// ```
+/// # use zewif::{KeyPool, KeyPoolEntry};
+/// let mut pool = KeyPool::new();
+/// pool.add(KeyPoolEntry::new(7, false));
+/// pool.add(KeyPoolEntry::new(3, false));
+/// pool.add(KeyPoolEntry::new(4, true));
+///
+/// let summary = pool.summary();
+/// assert_eq!(summary.external().count(), 2);
+/// assert_eq!(summary.external().max_index(), Some(7));
+/// assert_eq!(summary.internal().max_index(), Some(4));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyPool(Vec<KeyPoolEntry>);
+
+impl KeyPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> &[KeyPoolEntry] {
+        &self.0
+    }
+
+    pub fn add(&mut self, entry: KeyPoolEntry) {
+        match self
+            .0
+            .binary_search_by_key(&entry.pool_index, |existing| existing.pool_index)
+        {
+            Ok(position) => self.0[position] = entry,
+            Err(position) => self.0.insert(position, entry),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of entries and the highest pool index on each
+    /// chain.
+    pub fn summary(&self) -> KeyPoolSummary {
+        let mut summary = KeyPoolSummary::default();
+        for entry in &self.0 {
+            let chain = if entry.is_internal {
+                &mut summary.internal
+            } else {
+                &mut summary.external
+            };
+            chain.count += 1;
+            chain.max_index = chain.max_index.max(Some(entry.pool_index));
+        }
+        summary
+    }
+}
+
+impl From<KeyPool> for Envelope {
+    fn from(value: KeyPool) -> Self {
+        value
+            .0
+            .into_iter()
+            .fold(Envelope::new("KeyPool").add_type("KeyPool"), |e, entry| {
+                e.add_assertion("entry", entry)
+            })
+    }
+}
+
+impl TryFrom<Envelope> for KeyPool {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("KeyPool")?;
+        let mut pool = Self::new();
+        for entry in envelope.objects_for_predicate("entry") {
+            pool.add(KeyPoolEntry::try_from(entry)?);
+        }
+        Ok(pool)
+    }
+}
+
+/// The entries of one chain of a [`KeyPool`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyChainSummary {
+    count: usize,
+    max_index: Option<u64>,
+}
+
+impl KeyChainSummary {
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The highest pool index on the chain, if it has any entries.
+    pub fn max_index(&self) -> Option<u64> {
+        self.max_index
+    }
+}
+
+/// The entries of a [`KeyPool`] on its external and internal chains; see
+/// [`KeyPool::summary`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyPoolSummary {
+    external: KeyChainSummary,
+    internal: KeyChainSummary,
+}
+
+impl KeyPoolSummary {
+    pub fn external(&self) -> &KeyChainSummary {
+        &self.external
+    }
+
+    /// The entries on the internal (change) chain.
+    pub fn internal(&self) -> &KeyChainSummary {
+        &self.internal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Data, RandomInstance, SecondsSinceEpoch, test_envelope_roundtrip};
+
+    use super::{KeyPool, KeyPoolEntry};
+
+    impl RandomInstance for KeyPoolEntry {
+        fn random() -> Self {
+            Self {
+                pool_index: rand::random(),
+                public_key: Data::opt_random(),
+                address: String::opt_random(),
+                creation_time: SecondsSinceEpoch::opt_random(),
+                is_internal: rand::random(),
+            }
+        }
+    }
+
+    impl RandomInstance for KeyPool {
+        fn random() -> Self {
+            let mut pool = Self::new();
+            Vec::<KeyPoolEntry>::random()
+                .into_iter()
+                .for_each(|entry| pool.add(entry));
+            pool
+        }
+    }
+
+    test_envelope_roundtrip!(KeyPool);
+
+    #[test]
+    fn test_entries_ordered_by_index() {
+        let mut pool = KeyPool::new();
+        pool.add(KeyPoolEntry::new(2, false));
+        pool.add(KeyPoolEntry::new(1, true));
+        let mut replacement = KeyPoolEntry::new(2, false);
+        replacement.set_address(Some("t1replacement".to_string()));
+        pool.add(replacement.clone());
+
+        assert_eq!(
+            pool.entries()
+                .iter()
+                .map(KeyPoolEntry::pool_index)
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(pool.entries()[1], replacement);
+        assert_eq!(pool.summary().external().max_index(), Some(2));
+        assert_eq!(pool.summary().internal().count(), 1);
+    }
+}
@@ -1,22 +1,27 @@
 use bc_envelope::prelude::*;
 
-use crate::{Data, NoQuotesDebugOption, SeedFingerprint};
+use crate::{Data, SeedFingerprint};
 
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct LegacySeed {
     seed_data: Data,
     fingerprint: Option<SeedFingerprint>,
 }
 
+/// Prints the length of the seed and its fingerprint, but never the seed bytes.
 impl std::fmt::Debug for LegacySeed {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        f.debug_struct("MnemonicSeed")
-            .field("seed_data", &"<elided>".to_string())
-            .field(
-                "fingerprint",
-                &NoQuotesDebugOption(&self.fingerprint.map(|f| f.to_hex())),
-            )
-            .finish()
+        let len = self.seed_data.len();
+        match &self.fingerprint {
+            Some(fingerprint) => write!(
+                f,
+                "LegacySeed(<{} bytes, fingerprint {}>)",
+                len,
+                fingerprint.short_hex()
+            ),
+            None => write!(f, "LegacySeed(<{} bytes>)", len),
+        }
     }
 }
 
@@ -10,8 +10,8 @@
 //!
 //! * **Complete Wallet Data Model**: Represents all aspects of a Zcash wallet
 //!   including accounts, addresses, transactions, and keys
-//! * **Multi-Protocol Support**: Handles the Transparent, Sapling, and Orchard
-//!   Zcash protocols.
+//! * **Multi-Protocol Support**: Handles the Transparent, Sprout, Sapling, and
+//!   Orchard Zcash protocols.
 //! * **Type-Safe Representation**: Uses Rust's type system to ensure correct
 //!   handling of Zcash concepts
 //! * **Extensible Metadata**: Supports custom metadata through an attachments
@@ -34,6 +34,7 @@
 //!
 //! - **Transparent**: Bitcoin-compatible public transactions
 //!   ([`TransparentAddress`], [`TxIn`], [`TxOut`])
+//! - **Sprout**: Original, deprecated shielded protocol ([`sprout`] module)
 //! - **Sapling**: Improved shielded protocol ([`sapling`] module,
 //!   [`sapling::SaplingSentOutput`], etc.)
 //! - **Orchard**: Latest shielded protocol ([`orchard`] module,
@@ -80,41 +81,111 @@ mod test_roundtrip_macros;
 #[cfg(any(test, feature = "test-dependencies"))]
 mod_use!(test_utils);
 
+// Internal modules
+mod base58check;
+mod envelope_decoder;
+mod envelope_stream;
+mod envelope_upgrade;
+mod f4jumble;
+mod integrity;
+#[cfg(feature = "interop")]
+mod interop;
+#[cfg(feature = "json")]
+mod json;
+mod raw_transaction;
+mod watch_only;
+mod zewif_lookup;
+mod zewif_shards;
+
 // Modules requiring qualified paths
+pub mod debug;
 pub mod orchard;
 pub mod sapling;
+pub mod sprout;
 pub mod transparent;
 
 // Modules that can use unqualified paths
 mod_use!(account);
+mod_use!(account_derivation);
 mod_use!(address);
 mod_use!(amount);
 mod_use!(anchor);
+mod_use!(anchor_registry);
+mod_use!(balance);
 mod_use!(bip_39_mnemonic);
 mod_use!(blob);
 mod_use!(block_hash);
 mod_use!(block_height);
+mod_use!(branch_id);
+mod_use!(compact);
 mod_use!(data);
-mod_use!(error);
+mod_use!(decode_issue);
 mod_use!(derivation_info);
+mod_use!(derivation_path);
+mod_use!(derivation_report);
+mod_use!(diff);
+mod_use!(diversifier_index);
+mod_use!(duplicate_address);
+mod_use!(encode_options);
+mod_use!(encrypted_seed);
+mod_use!(error);
+mod_use!(export_metadata);
+mod_use!(exporter_info);
+mod_use!(file_kind);
+mod_use!(hardware_seed_reference);
+mod_use!(inclusion_proof);
 mod_use!(incremental_witness);
 mod_use!(indexed);
+mod_use!(input_value_resolver);
+mod_use!(kdf_params);
+mod_use!(key_material);
+mod_use!(key_origin);
+mod_use!(key_pool);
+mod_use!(legacy_seed);
+mod_use!(loss_report);
 mod_use!(memo);
+mod_use!(merge);
 mod_use!(mnemonic_language);
+mod_use!(net_value_change);
 mod_use!(network);
+mod_use!(network_mismatch);
+mod_use!(network_parameters);
 mod_use!(non_hardened_child_index);
+mod_use!(orphaned_data);
+mod_use!(out_point);
+mod_use!(output_annotation);
+mod_use!(pool);
 mod_use!(protocol_address);
+mod_use!(provenance_info);
 mod_use!(script);
-mod_use!(legacy_seed);
-mod_use!(seed_material);
+mod_use!(seconds_since_epoch);
 mod_use!(seed_fingerprint);
+mod_use!(seed_material);
 mod_use!(string_utils);
 mod_use!(transaction);
+mod_use!(transaction_history);
+mod_use!(transaction_kind);
+mod_use!(transaction_status);
+mod_use!(tree_state);
 mod_use!(tx_block_position);
+mod_use!(tx_in);
+mod_use!(tx_out);
+mod_use!(tx_version);
 mod_use!(txid);
+mod_use!(typed_attachments);
+mod_use!(unified_account_metadata);
 mod_use!(unified_address);
+mod_use!(unified_full_viewing_key);
+mod_use!(validation);
+mod_use!(wallet_crypto_params);
+mod_use!(wallet_provenance);
+mod_use!(wallet_seed);
+mod_use!(zcashd_legacy_seed);
+mod_use!(zewif_builder);
 mod_use!(zewif_envelope);
 mod_use!(zewif_impl);
+mod_use!(zewif_summary);
+mod_use!(zewif_ur);
 mod_use!(zewif_wallet);
 
 use std::fmt::{self, Debug, Display, Formatter};
@@ -0,0 +1,397 @@
+use std::fmt;
+
+use bc_envelope::prelude::*;
+
+use crate::{
+    Account, Address, Error, Transaction, Zewif, ZewifWallet, envelope_upgrade::upgraded,
+    zewif_impl::uncompressed,
+};
+
+/// How an element of a Zewif differs after a round trip; see [`Loss`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LossKind {
+    /// The element is present, but its value differs.
+    Changed,
+    /// The element is missing.
+    Dropped,
+    /// An element is present that was not there before.
+    Added,
+}
+
+impl fmt::Display for LossKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LossKind::Changed => write!(f, "changed"),
+            LossKind::Dropped => write!(f, "dropped"),
+            LossKind::Added => write!(f, "added"),
+        }
+    }
+}
+
+/// One difference found by [`verify_lossless`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Loss {
+    path: Vec<String>,
+    predicate: Option<String>,
+    kind: LossKind,
+}
+
+impl Loss {
+    pub fn kind(&self) -> LossKind {
+        self.kind
+    }
+
+    /// Returns the path of the object that differs, or that contains the
+    /// element that differs, such as
+    /// `["Zewif", "Wallet[0]", "Account[\"Savings\"]"]`.
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+
+    /// Returns the predicate of the assertion that differs, such as `name`,
+    /// or `None` if the object's subject differs.
+    pub fn predicate(&self) -> Option<&str> {
+        self.predicate.as_deref()
+    }
+}
+
+impl fmt::Display for Loss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.path.join(" > "))?;
+        if let Some(predicate) = &self.predicate {
+            write!(f, ": predicate \"{}\"", predicate)?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of [`verify_lossless`].
+#[derive(Debug)]
+pub struct LossReport {
+    decode_error: Option<Error>,
+    losses: Vec<Loss>,
+}
+
+impl LossReport {
+    /// Returns whether the envelope decoded to a Zewif identical to the
+    /// original, with nothing in the envelope left behind.
+    pub fn is_lossless(&self) -> bool {
+        self.decode_error.is_none() && self.losses.is_empty()
+    }
+
+    /// Returns the error, if the envelope could not be decoded at all.
+    pub fn decode_error(&self) -> Option<&Error> {
+        self.decode_error.as_ref()
+    }
+
+    /// Returns the differences found, ordered by path.
+    pub fn losses(&self) -> &[Loss] {
+        &self.losses
+    }
+}
+
+impl fmt::Display for LossReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(error) = &self.decode_error {
+            return write!(f, "decoding failed: {}", error);
+        }
+        if self.losses.is_empty() {
+            return write!(f, "lossless");
+        }
+        let losses: Vec<_> = self.losses.iter().map(Loss::to_string).collect();
+        write!(f, "{}", losses.join("\n"))
+    }
+}
+
+/// Checks that `reencoded`, an envelope written from `original` by an
+/// exporter or produced by a round trip through another format, carries
+/// everything in `original` and nothing else.
+///
+/// The envelope is decompressed, upgraded, and decoded as by
+/// [`Zewif::try_from`](Envelope). Each difference between the encoding of
+/// `original` and that of the decoded Zewif, including its attachments, is
+/// reported, as is each assertion in `reencoded` that decoding did not carry
+/// through, such as one of a predicate this crate does not know.
+///
+/// Importers can call this in their own tests, on the Zewif they built and
+/// the envelope they wrote, to show that their exports lose nothing.
+///
+/// # Examples
+/// ```
+/// # use bc_envelope::prelude::*;
+/// # use zewif::{BlockHeight, Zewif, verify_lossless};
+/// let zewif = Zewif::new(BlockHeight::from(2_000_000u32));
+/// assert!(verify_lossless(&zewif, &Envelope::from(zewif.clone())).is_lossless());
+/// ```
+pub fn verify_lossless(original: &Zewif, reencoded: &Envelope) -> LossReport {
+    let decoded = uncompressed(reencoded.clone())
+        .and_then(upgraded)
+        .and_then(|envelope| Zewif::try_from(envelope.clone()).map(|decoded| (envelope, decoded)));
+    let (carried, decoded) = match decoded {
+        Ok(decoded) => decoded,
+        Err(error) => {
+            return LossReport {
+                decode_error: Some(error),
+                losses: Vec::new(),
+            };
+        }
+    };
+
+    let decoded = Envelope::from(decoded);
+    let mut losses = Vec::new();
+    let mut path = vec!["Zewif".to_string()];
+    compare(
+        &Envelope::from(original.clone()),
+        &decoded,
+        &mut path,
+        &mut losses,
+    );
+    compare(&carried, &decoded, &mut path, &mut losses);
+    losses.sort();
+    losses.dedup();
+    LossReport {
+        decode_error: None,
+        losses,
+    }
+}
+
+/// Records the differences between two envelopes of the object at `path`.
+fn compare(expected: &Envelope, actual: &Envelope, path: &mut Vec<String>, losses: &mut Vec<Loss>) {
+    if expected.digest() == actual.digest() {
+        return;
+    }
+
+    let (expected_subject, actual_subject) = (expected.subject(), actual.subject());
+    if expected_subject.digest() != actual_subject.digest() {
+        match (expected_subject.try_unwrap(), actual_subject.try_unwrap()) {
+            (Ok(expected), Ok(actual)) => compare(&expected, &actual, path, losses),
+            _ => losses.push(Loss {
+                kind: LossKind::Changed,
+                path: path.clone(),
+                predicate: None,
+            }),
+        }
+    }
+
+    let mut predicates: Vec<_> = expected
+        .assertions()
+        .iter()
+        .chain(actual.assertions().iter())
+        .filter_map(predicate_name)
+        .collect();
+    predicates.sort();
+    predicates.dedup();
+    for predicate in predicates {
+        compare_objects(
+            &predicate,
+            objects(expected, &predicate),
+            objects(actual, &predicate),
+            path,
+            losses,
+        );
+    }
+}
+
+/// Pairs the objects of `predicate` in two envelopes, first by digest, then
+/// by subject, and records those that differ or have no counterpart.
+fn compare_objects(
+    predicate: &str,
+    mut expected: Vec<Envelope>,
+    mut actual: Vec<Envelope>,
+    path: &mut Vec<String>,
+    losses: &mut Vec<Loss>,
+) {
+    expected.retain(
+        |e| match actual.iter().position(|a| a.digest() == e.digest()) {
+            Some(position) => {
+                actual.remove(position);
+                false
+            }
+            None => true,
+        },
+    );
+
+    let mut pairs = Vec::new();
+    expected.retain(|e| {
+        match actual
+            .iter()
+            .position(|a| a.subject().digest() == e.subject().digest())
+        {
+            Some(position) => {
+                pairs.push((e.clone(), actual.remove(position)));
+                false
+            }
+            None => true,
+        }
+    });
+    if expected.len() == 1 && actual.len() == 1 {
+        pairs.push((expected.remove(0), actual.remove(0)));
+    }
+
+    let loss = |kind, path: &Vec<String>| Loss {
+        kind,
+        path: path.clone(),
+        predicate: Some(predicate.to_string()),
+    };
+    for (e, a) in pairs {
+        if is_structured(&e) || is_structured(&a) {
+            path.push(object_segment(predicate, &e));
+            compare(&e, &a, path, losses);
+            path.pop();
+        } else {
+            losses.push(loss(LossKind::Changed, path));
+        }
+    }
+    losses.extend(expected.iter().map(|_| loss(LossKind::Dropped, path)));
+    losses.extend(actual.iter().map(|_| loss(LossKind::Added, path)));
+}
+
+/// Returns whether an object has assertions or a wrapped subject to compare
+/// element by element.
+pub(crate) fn is_structured(envelope: &Envelope) -> bool {
+    envelope.has_assertions() || envelope.subject().is_wrapped()
+}
+
+pub(crate) fn objects(envelope: &Envelope, predicate: &str) -> Vec<Envelope> {
+    envelope
+        .assertions()
+        .into_iter()
+        .filter(|assertion| predicate_name(assertion).as_deref() == Some(predicate))
+        .filter_map(|assertion| assertion.as_object())
+        .collect()
+}
+
+/// Returns the name of an assertion's predicate, which is a string or a
+/// known value such as `isA`.
+pub(crate) fn predicate_name(assertion: &Envelope) -> Option<String> {
+    let predicate = assertion.as_predicate()?;
+    predicate
+        .extract_subject::<String>()
+        .ok()
+        .or_else(|| predicate.subject().as_known_value().map(KnownValue::name))
+        .or_else(|| {
+            predicate
+                .subject()
+                .as_leaf()
+                .map(|cbor| cbor.diagnostic_flat())
+        })
+}
+
+/// Returns the path segment of an object, matching those of decoding errors
+/// for wallets, accounts, addresses, and transactions.
+pub(crate) fn object_segment(predicate: &str, object: &Envelope) -> String {
+    match predicate {
+        "wallet" => ZewifWallet::envelope_segment(object),
+        "account" => Account::envelope_segment(object),
+        "address" => Address::envelope_segment(object),
+        "transaction" => Transaction::envelope_segment(object),
+        _ => object
+            .get_type()
+            .ok()
+            .and_then(|type_name| type_name.extract_subject::<String>().ok())
+            .unwrap_or_else(|| predicate.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, BlockHeight, Compression, EncodeOptions, LossKind, Network, RandomInstance, Zewif,
+        ZewifWallet,
+    };
+
+    use super::verify_lossless;
+
+    fn zewif() -> Zewif {
+        let mut account = Account::new();
+        account.set_name("Savings");
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        zewif
+    }
+
+    /// Rebuilds the envelope of the Zewif with `edit` applied to the
+    /// envelope of its only account.
+    fn edit_account(zewif: &Zewif, edit: impl Fn(Envelope) -> Envelope) -> Envelope {
+        let mut envelope = Envelope::from(zewif.clone());
+        let wallet_assertion = envelope.assertion_with_predicate("wallet").unwrap();
+        let mut wallet = wallet_assertion.as_object().unwrap();
+        let account_assertion = wallet.assertion_with_predicate("account").unwrap();
+        let account = edit(account_assertion.as_object().unwrap());
+        wallet = wallet
+            .remove_assertion(account_assertion)
+            .add_assertion("account", account);
+        envelope = envelope
+            .remove_assertion(wallet_assertion)
+            .add_assertion("wallet", wallet);
+        envelope
+    }
+
+    #[test]
+    fn test_random_wallets_are_lossless() {
+        for _ in 0..10 {
+            let zewif = Zewif::random();
+            for compression in [
+                Compression::None,
+                Compression::Whole,
+                Compression::LargeLeavesOver(64),
+            ] {
+                let mut options = EncodeOptions::new();
+                options.set_compression(compression);
+                let report = verify_lossless(&zewif, &zewif.to_envelope_with(&options).unwrap());
+                assert!(report.is_lossless(), "{:?}: {}", compression, report);
+            }
+        }
+    }
+
+    #[test]
+    fn test_changed_and_unknown_elements() {
+        let zewif = zewif();
+        let changed = edit_account(&zewif, |account| {
+            let name = account.assertion_with_predicate("name").unwrap();
+            account
+                .remove_assertion(name)
+                .add_assertion("name", "Checking")
+        });
+        let report = verify_lossless(&zewif, &changed);
+        assert_eq!(report.losses().len(), 1);
+        let loss = &report.losses()[0];
+        assert_eq!(loss.kind(), LossKind::Changed);
+        assert_eq!(loss.path(), ["Zewif", "Wallet[0]", "Account[\"Savings\"]"]);
+        assert_eq!(loss.predicate(), Some("name"));
+
+        let unknown = edit_account(&zewif, |account| {
+            account.add_assertion("vendor_notes", "carry me")
+        });
+        let report = verify_lossless(&zewif, &unknown);
+        assert_eq!(
+            report.to_string(),
+            "dropped Zewif > Wallet[0] > Account[\"Savings\"]: predicate \"vendor_notes\""
+        );
+    }
+
+    #[test]
+    fn test_dropped_elements() {
+        let mut zewif = zewif();
+        zewif.add_attachment("payload", "com.example", Some("notes-v1"));
+        let mut envelope = Envelope::from(zewif.clone());
+        let attachment = envelope
+            .assertion_with_predicate(known_values::ATTACHMENT)
+            .unwrap();
+        envelope = envelope.remove_assertion(attachment);
+        let report = verify_lossless(&zewif, &envelope);
+        assert_eq!(
+            report.to_string(),
+            "dropped Zewif: predicate \"attachment\""
+        );
+
+        let envelope = Envelope::new("not a zewif");
+        let report = verify_lossless(&zewif, &envelope);
+        assert!(!report.is_lossless());
+        assert!(report.decode_error().is_some());
+    }
+}
@@ -5,3 +5,62 @@ use crate::{blob_envelope, data};
 data!(Memo, "A memo associated with a Zcash shielded output.");
 
 blob_envelope!(Memo);
+
+/// The size of a memo as carried by a shielded output.
+const MEMO_SIZE: usize = 512;
+
+impl Memo {
+    /// Returns a memo holding `text`, encoded as ZIP 302 specifies: in UTF-8,
+    /// padded with zeros to 512 bytes. Returns `None` if the text does not
+    /// fit.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Memo;
+    /// let memo = Memo::from_text("Merci ☕").unwrap();
+    /// assert_eq!(memo.len(), 512);
+    /// assert_eq!(memo.text().as_deref(), Some("Merci ☕"));
+    /// ```
+    pub fn from_text(text: &str) -> Option<Self> {
+        if text.len() > MEMO_SIZE {
+            return None;
+        }
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.resize(MEMO_SIZE, 0);
+        Some(Self::from_vec(bytes))
+    }
+
+    /// Returns the text of the memo, with its zero padding removed, if it
+    /// holds text as ZIP 302 specifies: a first byte of at most `0xF4`, and
+    /// valid UTF-8. The empty memo, whose first byte is `0xF6`, and memos of
+    /// arbitrary data do not.
+    pub fn text(&self) -> Option<String> {
+        let bytes = self.as_ref();
+        if bytes.first().is_some_and(|first| *first > 0xF4) {
+            return None;
+        }
+        let end = bytes
+            .iter()
+            .rposition(|byte| *byte != 0)
+            .map_or(0, |last| last + 1);
+        String::from_utf8(bytes[..end].to_vec()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Memo;
+
+    #[test]
+    fn test_text() {
+        let text = "Für Zoë: 猫 🐈";
+        let memo = Memo::from_text(text).unwrap();
+        assert_eq!(memo.text().as_deref(), Some(text));
+        assert!(Memo::from_text(&"x".repeat(513)).is_none());
+
+        let mut empty = vec![0xF6];
+        empty.resize(512, 0);
+        assert_eq!(Memo::from_vec(empty).text(), None);
+        assert_eq!(Memo::from_vec(vec![0xC3, 0x28]).text(), None);
+    }
+}
@@ -0,0 +1,810 @@
+use std::fmt;
+
+use bc_envelope::prelude::*;
+
+use crate::{
+    Account, AccountDerivation, Address, BlockHeight, DuplicateAddress, Error, Indexed, Result,
+    SeedFingerprint, Transaction, TxId, WalletSeed, Zewif, ZewifWallet,
+};
+
+/// How [`Zewif::merge`] resolves a value that differs between the two Zewifs
+/// being merged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keeps the value of the Zewif being merged into.
+    #[default]
+    KeepExisting,
+    /// Takes the value of the Zewif being merged in.
+    PreferIncoming,
+    /// Fails the merge with [`Error::MergeConflict`].
+    Fail,
+}
+
+/// Which of the two merged Zewifs a value was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeSide {
+    Existing,
+    Incoming,
+}
+
+/// A decision made by [`Zewif::merge`] about data present in both Zewifs.
+///
+/// Wallets, wallet indices, and account indices refer to the merged Zewif.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeDecision {
+    /// An incoming wallet shared a seed or an address with an existing wallet
+    /// and was merged into it.
+    WalletMerged { wallet: usize },
+    /// An incoming seed duplicated one of the wallet's seeds and was dropped.
+    DuplicateSeed {
+        wallet: usize,
+        fingerprint: Option<SeedFingerprint>,
+    },
+    /// An incoming account was merged into an existing account, dropping the
+    /// given number of duplicate notes and sent outputs.
+    AccountMerged {
+        wallet: usize,
+        account: usize,
+        duplicates: usize,
+    },
+    /// An incoming address duplicated an existing address and was merged into
+    /// it.
+    DuplicateAddress { address: String },
+    /// After the merge, an address is held by more than one account, which
+    /// did not hold it before; see [`Zewif::find_duplicate_addresses`].
+    AmbiguousAddress { duplicate: DuplicateAddress },
+    /// The same address had different names; `kept` is the name it now has.
+    AddressNameConflict {
+        address: String,
+        kept: String,
+        discarded: String,
+    },
+    /// The same transaction was present in both Zewifs; `kept` is the side
+    /// whose copy was kept.
+    DuplicateTransaction { txid: TxId, kept: MergeSide },
+    /// The same transaction was mined at different heights; `kept` is the
+    /// height of the copy that was kept.
+    TransactionHeightConflict {
+        txid: TxId,
+        kept: BlockHeight,
+        discarded: BlockHeight,
+    },
+}
+
+impl fmt::Display for MergeDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WalletMerged { wallet } => {
+                write!(f, "merged an incoming wallet into wallet {}", wallet)
+            }
+            Self::DuplicateSeed {
+                wallet,
+                fingerprint,
+            } => match fingerprint {
+                Some(fingerprint) => {
+                    write!(
+                        f,
+                        "dropped duplicate seed {} of wallet {}",
+                        fingerprint.short_hex(),
+                        wallet
+                    )
+                }
+                None => write!(f, "dropped duplicate seed of wallet {}", wallet),
+            },
+            Self::AccountMerged {
+                wallet,
+                account,
+                duplicates,
+            } => write!(
+                f,
+                "merged an incoming account into account {} of wallet {}, dropping {} duplicate notes and outputs",
+                account, wallet, duplicates
+            ),
+            Self::DuplicateAddress { address } => write!(f, "merged duplicate address {}", address),
+            Self::AmbiguousAddress { duplicate } => write!(f, "{}", duplicate),
+            Self::AddressNameConflict {
+                address,
+                kept,
+                discarded,
+            } => {
+                write!(
+                    f,
+                    "address {} kept name {:?} over {:?}",
+                    address, kept, discarded
+                )
+            }
+            Self::DuplicateTransaction { txid, kept } => {
+                write!(
+                    f,
+                    "kept the {:?} copy of duplicate transaction {}",
+                    kept, txid
+                )
+            }
+            Self::TransactionHeightConflict {
+                txid,
+                kept,
+                discarded,
+            } => {
+                write!(
+                    f,
+                    "transaction {} kept mined height {} over {}",
+                    txid, kept, discarded
+                )
+            }
+        }
+    }
+}
+
+/// The decisions made by [`Zewif::merge`], in the order they were made.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    decisions: Vec<MergeDecision>,
+}
+
+impl MergeReport {
+    pub fn decisions(&self) -> &[MergeDecision] {
+        &self.decisions
+    }
+
+    /// Returns `true` if the two Zewifs had nothing in common.
+    pub fn is_disjoint(&self) -> bool {
+        self.decisions.is_empty()
+    }
+
+    fn push(&mut self, decision: MergeDecision) {
+        self.decisions.push(decision);
+    }
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for decision in &self.decisions {
+            writeln!(f, "{}", decision)?;
+        }
+        Ok(())
+    }
+}
+
+impl Zewif {
+    /// Merges `other` into this Zewif, for example to combine exports of the
+    /// same wallet taken from different devices.
+    ///
+    /// An incoming wallet that shares a seed fingerprint or an address with an
+    /// existing wallet is merged into it; any other is added. Within a merged
+    /// wallet, seeds are deduplicated by fingerprint, accounts are matched by
+    /// seed fingerprint and ZIP 32 account index or by a shared address, and
+    /// addresses are deduplicated by their string encoding. Transactions are
+    /// deduplicated by txid, keeping the copy with more metadata. `policy`
+    /// decides between the two names of an address, and between the two
+    /// heights of a transaction mined at different heights. An address left
+    /// held by more than one account is reported, and fails the merge under
+    /// [`MergePolicy::Fail`].
+    ///
+    /// This Zewif is left unchanged if the merge fails.
+    ///
+    /// # Errors
+    /// Returns [`Error::NetworkMismatch`] if the Zewifs have wallets on
+    /// different networks, and [`Error::MergeConflict`] for the first conflict
+    /// under [`MergePolicy::Fail`].
+    pub fn merge(&mut self, other: Zewif, policy: MergePolicy) -> Result<MergeReport> {
+        for incoming in other.wallets() {
+            if let Some(existing) = self
+                .wallets()
+                .iter()
+                .find(|existing| existing.network() != incoming.network())
+            {
+                return Err(Error::NetworkMismatch {
+                    element: format!("Incoming wallet {}", incoming.index()),
+                    expected: String::from(existing.network()),
+                    detected: String::from(incoming.network()),
+                });
+            }
+        }
+
+        let mut merged = self.clone();
+        let mut report = MergeReport::default();
+        for incoming in other.wallets() {
+            match merged
+                .wallets()
+                .iter()
+                .position(|existing| same_wallet(existing, incoming))
+            {
+                Some(wallet) => {
+                    report.push(MergeDecision::WalletMerged { wallet });
+                    merge_wallet(
+                        &mut merged.wallets_mut()[wallet],
+                        incoming,
+                        policy,
+                        &mut report,
+                    )?;
+                }
+                None => merged.add_wallet(incoming.clone()),
+            }
+        }
+
+        let before = self.find_duplicate_addresses();
+        for duplicate in merged.find_duplicate_addresses() {
+            if before.contains(&duplicate) {
+                continue;
+            }
+            if policy == MergePolicy::Fail {
+                let element = format!("address {}", duplicate.address());
+                let reason = format!("would be held by {} accounts", duplicate.accounts().len());
+                return Err(Error::MergeConflict { element, reason });
+            }
+            report.push(MergeDecision::AmbiguousAddress { duplicate });
+        }
+
+        let mut txids: Vec<_> = other.transactions().keys().copied().collect();
+        txids.sort();
+        for txid in txids {
+            let incoming = &other.transactions()[&txid];
+            let kept = match merged.get_transaction(txid) {
+                Some(existing) => choose_transaction(existing, incoming, policy, &mut report)?,
+                None => MergeSide::Incoming,
+            };
+            if kept == MergeSide::Incoming {
+                merged.replace_transaction(txid, incoming.clone());
+            }
+        }
+
+        merged.set_export_height(merged.export_height().max(other.export_height()));
+        union_attachments(merged.attachments_mut(), other.attachments());
+        *self = merged;
+        Ok(report)
+    }
+}
+
+fn same_seed(a: &WalletSeed, b: &WalletSeed) -> bool {
+    match (a.fingerprint(), b.fingerprint()) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.seed_material() == b.seed_material(),
+    }
+}
+
+fn shares_address(a: &Account, b: &Account) -> bool {
+    a.addresses()
+        .iter()
+        .any(|x| b.addresses().iter().any(|y| x.as_string() == y.as_string()))
+}
+
+fn same_wallet(a: &ZewifWallet, b: &ZewifWallet) -> bool {
+    a.seeds()
+        .iter()
+        .any(|x| b.seeds().iter().any(|y| same_seed(x, y)))
+        || a.accounts()
+            .iter()
+            .any(|x| b.accounts().iter().any(|y| shares_address(x, y)))
+}
+
+fn same_account(a: &Account, b: &Account) -> bool {
+    let same_derivation = a.seed_fingerprint().is_some()
+        && a.zip32_account_id().is_some()
+        && a.seed_fingerprint() == b.seed_fingerprint()
+        && a.zip32_account_id() == b.zip32_account_id();
+    same_derivation || shares_address(a, b)
+}
+
+/// Returns the side chosen by `policy` for a conflict described by
+/// `conflict`, which names the conflicting element and how its copies differ.
+fn resolve(policy: MergePolicy, conflict: impl FnOnce() -> (String, String)) -> Result<MergeSide> {
+    match policy {
+        MergePolicy::KeepExisting => Ok(MergeSide::Existing),
+        MergePolicy::PreferIncoming => Ok(MergeSide::Incoming),
+        MergePolicy::Fail => {
+            let (element, reason) = conflict();
+            Err(Error::MergeConflict { element, reason })
+        }
+    }
+}
+
+fn merge_wallet(
+    existing: &mut ZewifWallet,
+    incoming: &ZewifWallet,
+    policy: MergePolicy,
+    report: &mut MergeReport,
+) -> Result<()> {
+    let wallet = existing.index();
+    for seed in incoming.seeds() {
+        if existing
+            .seeds()
+            .iter()
+            .any(|existing| same_seed(existing, seed))
+        {
+            report.push(MergeDecision::DuplicateSeed {
+                wallet,
+                fingerprint: seed.fingerprint().copied(),
+            });
+        } else {
+            existing.add_seed(seed.clone());
+        }
+    }
+    if existing.network_parameters().is_none() {
+        existing.set_network_parameters(incoming.network_parameters().cloned());
+    }
+    // The merged wallet may hold funds received by either side.
+    let birthday_height = match (existing.birthday_height(), incoming.birthday_height()) {
+        (Some(existing), Some(incoming)) => Some(existing.min(incoming)),
+        (existing, incoming) => existing.or(incoming),
+    };
+    existing.set_birthday_height(birthday_height);
+    if existing.provenance().is_none() {
+        existing.set_provenance(incoming.provenance().cloned());
+    }
+    if existing.sapling_tree_state().is_none() {
+        existing.set_sapling_tree_state(incoming.sapling_tree_state().cloned());
+    }
+    if existing.orchard_tree_state().is_none() {
+        existing.set_orchard_tree_state(incoming.orchard_tree_state().cloned());
+    }
+    if existing.sapling_anchors().is_none() {
+        existing.set_sapling_anchors(incoming.sapling_anchors().cloned());
+    }
+    if existing.orchard_anchors().is_none() {
+        existing.set_orchard_anchors(incoming.orchard_anchors().cloned());
+    }
+    for record in incoming.orphaned_data().records() {
+        if !existing.orphaned_data().records().contains(record) {
+            existing.add_orphaned_record(record.clone());
+        }
+    }
+    for entry in incoming.key_pool().entries() {
+        if !existing
+            .key_pool()
+            .entries()
+            .iter()
+            .any(|existing| existing.pool_index() == entry.pool_index())
+        {
+            existing.key_pool_mut().add(entry.clone());
+        }
+    }
+    for account in incoming.accounts() {
+        match existing
+            .accounts()
+            .iter()
+            .position(|existing| same_account(existing, account))
+        {
+            Some(position) => {
+                let target = &mut existing.accounts_mut()[position];
+                let duplicates = merge_account(target, account, policy, report)?;
+                report.push(MergeDecision::AccountMerged {
+                    wallet,
+                    account: target.index(),
+                    duplicates,
+                });
+            }
+            None => existing.add_account(account.clone()),
+        }
+    }
+    union_attachments(existing.attachments_mut(), incoming.attachments());
+    Ok(())
+}
+
+/// Merges `incoming` into `existing`, returning the number of duplicate notes
+/// and sent outputs dropped.
+fn merge_account(
+    existing: &mut Account,
+    incoming: &Account,
+    policy: MergePolicy,
+    report: &mut MergeReport,
+) -> Result<usize> {
+    for address in incoming.addresses() {
+        let string = address.as_string();
+        match existing
+            .addresses_mut()
+            .iter_mut()
+            .find(|existing| existing.as_string() == string)
+        {
+            Some(target) => {
+                report.push(MergeDecision::DuplicateAddress {
+                    address: string.clone(),
+                });
+                merge_address(target, address, policy, report)?;
+            }
+            None => existing.add_address(address.clone()),
+        }
+    }
+    for txid in incoming.relevant_transactions() {
+        existing.add_relevant_transaction(*txid);
+    }
+
+    if existing.birthday_height().is_none() {
+        existing.set_birthday_height(incoming.birthday_height());
+    }
+    if existing.birthday_block().is_none() {
+        existing.set_birthday_block(incoming.birthday_block());
+    }
+    if let (None, Some(id)) = (existing.zip32_account_id(), incoming.zip32_account_id()) {
+        existing.set_zip32_account_id(id);
+    }
+    if existing.seed_fingerprint().is_none() {
+        existing.set_seed_fingerprint(incoming.seed_fingerprint().copied());
+    }
+    if *existing.derivation() == AccountDerivation::Unknown {
+        existing.set_derivation(incoming.derivation().clone());
+    }
+    if existing.orchard_spending_key().is_none() {
+        existing.set_orchard_spending_key(incoming.orchard_spending_key().cloned());
+    }
+    if existing.orchard_derivation().is_none() {
+        existing.set_orchard_derivation(incoming.orchard_derivation().cloned());
+    }
+    if existing.orchard_key_origin().is_none() {
+        existing.set_orchard_key_origin(incoming.orchard_key_origin().cloned());
+    }
+    if existing.sprout_migration_status().is_none() {
+        existing.set_sprout_migration_status(incoming.sprout_migration_status().cloned());
+    }
+    if existing.unified_metadata().is_none() {
+        existing.set_unified_metadata(incoming.unified_metadata().cloned());
+    }
+    for key in incoming.key_material().entries() {
+        if !existing.key_material().entries().contains(key) {
+            existing.add_key(key.clone());
+        }
+    }
+
+    let mut duplicates = 0;
+    macro_rules! union {
+        ($items:ident, $add:ident) => {
+            for item in incoming.$items() {
+                if contains_ignoring_index(existing.$items(), item) {
+                    duplicates += 1;
+                } else {
+                    existing.$add(item.clone());
+                }
+            }
+        };
+    }
+    union!(sprout_received_notes, add_sprout_received_note);
+    union!(sapling_received_notes, add_sapling_received_note);
+    union!(orchard_received_notes, add_orchard_received_note);
+    union!(sapling_sent_outputs, add_sapling_sent_output);
+    union!(orchard_sent_outputs, add_orchard_sent_output);
+    union!(utxos, add_utxo);
+
+    union_attachments(existing.attachments_mut(), incoming.attachments());
+    Ok(duplicates)
+}
+
+fn merge_address(
+    existing: &mut Address,
+    incoming: &Address,
+    policy: MergePolicy,
+    report: &mut MergeReport,
+) -> Result<()> {
+    if existing.name().is_empty() {
+        existing.set_name(incoming.name().to_string());
+    } else if !incoming.name().is_empty() && existing.name() != incoming.name() {
+        let address = existing.as_string();
+        let side = resolve(policy, || {
+            (
+                format!("address {}", address),
+                format!("is named {:?} and {:?}", existing.name(), incoming.name()),
+            )
+        })?;
+        let (kept, discarded) = match side {
+            MergeSide::Existing => (existing.name().to_string(), incoming.name().to_string()),
+            MergeSide::Incoming => (incoming.name().to_string(), existing.name().to_string()),
+        };
+        existing.set_name(kept.clone());
+        report.push(MergeDecision::AddressNameConflict {
+            address,
+            kept,
+            discarded,
+        });
+    }
+    if let (None, Some(purpose)) = (existing.purpose(), incoming.purpose()) {
+        existing.set_purpose(purpose.to_string());
+    }
+    union_attachments(existing.attachments_mut(), incoming.attachments());
+    Ok(())
+}
+
+/// Decides which copy of a transaction present in both Zewifs to keep.
+fn choose_transaction(
+    existing: &Transaction,
+    incoming: &Transaction,
+    policy: MergePolicy,
+    report: &mut MergeReport,
+) -> Result<MergeSide> {
+    let txid = existing.txid();
+    let kept = match (existing.mined_height(), incoming.mined_height()) {
+        (Some(&existing_height), Some(&incoming_height)) if existing_height != incoming_height => {
+            let side = resolve(policy, || {
+                let reason = format!(
+                    "is mined at heights {} and {}",
+                    existing_height, incoming_height
+                );
+                (format!("transaction {}", txid), reason)
+            })?;
+            let (kept, discarded) = match side {
+                MergeSide::Existing => (existing_height, incoming_height),
+                MergeSide::Incoming => (incoming_height, existing_height),
+            };
+            report.push(MergeDecision::TransactionHeightConflict {
+                txid,
+                kept,
+                discarded,
+            });
+            side
+        }
+        _ if metadata_count(incoming) > metadata_count(existing) => MergeSide::Incoming,
+        _ => MergeSide::Existing,
+    };
+    report.push(MergeDecision::DuplicateTransaction { txid, kept });
+    Ok(kept)
+}
+
+/// Counts the assertions of a transaction's envelope, which grow with every
+/// optional field and description it records.
+fn metadata_count(transaction: &Transaction) -> usize {
+    Envelope::from(transaction.clone()).assertions().len()
+}
+
+fn contains_ignoring_index<T: Indexed + Clone + PartialEq>(items: &[T], item: &T) -> bool {
+    let mut item = item.clone();
+    items.iter().any(|existing| {
+        item.set_index(existing.index());
+        *existing == item
+    })
+}
+
+fn union_attachments(existing: &mut Attachments, incoming: &Attachments) {
+    let envelope = incoming.add_to_envelope(existing.add_to_envelope(Envelope::unit()));
+    *existing =
+        Attachments::try_from_envelope(&envelope).expect("union of valid attachments is valid");
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Account, Address, BlockHeight, Data, Error, Indexed, LegacySeed, MergeDecision,
+        MergePolicy, MergeSide, Network, ProtocolAddress, SeedMaterial, Transaction, TxId,
+        WalletSeed, Zewif, ZewifWallet, transparent,
+    };
+
+    fn address(string: &str, name: &str) -> Address {
+        let mut address = Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+            string,
+        )));
+        address.set_name(name.to_string());
+        address
+    }
+
+    fn seed(byte: u8) -> WalletSeed {
+        WalletSeed::new(SeedMaterial::LegacySeed(LegacySeed::new(
+            Data::from_vec(vec![byte; 32]),
+            None,
+        )))
+        .unwrap()
+    }
+
+    fn transaction(byte: u8, height: Option<u32>) -> Transaction {
+        let mut transaction = Transaction::new(TxId::from_bytes([byte; 32]));
+        if let Some(height) = height {
+            transaction.set_mined_height(BlockHeight::from(height));
+        }
+        transaction
+    }
+
+    fn zewif(
+        seed_byte: u8,
+        addresses: &[Address],
+        transactions: &[Transaction],
+        network: Network,
+    ) -> Zewif {
+        let mut account = Account::new();
+        for address in addresses {
+            account.add_address(address.clone());
+        }
+        for transaction in transactions {
+            account.add_relevant_transaction(transaction.txid());
+        }
+        let mut wallet = ZewifWallet::new(network);
+        wallet.add_seed(seed(seed_byte));
+        wallet.add_account(account);
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        for transaction in transactions {
+            zewif
+                .add_transaction(transaction.txid(), transaction.clone())
+                .unwrap();
+        }
+        zewif
+    }
+
+    fn zewif_with_height(byte: u8, export_height: u32) -> Zewif {
+        let mut zewif = zewif(
+            byte,
+            &[address("t1b", "b")],
+            &[transaction(byte, None)],
+            Network::Main,
+        );
+        zewif.set_export_height(BlockHeight::from(export_height));
+        zewif
+    }
+
+    #[test]
+    fn test_merge_disjoint() {
+        let mut zewif = zewif(
+            1,
+            &[address("t1a", "a")],
+            &[transaction(1, Some(100))],
+            Network::Main,
+        );
+        let other = zewif_with_height(2, 2_100_000);
+        let report = zewif.merge(other, MergePolicy::Fail).unwrap();
+
+        assert!(report.is_disjoint());
+        assert_eq!(zewif.wallets_len(), 2);
+        assert_eq!(zewif.wallets()[1].index(), 1);
+        assert_eq!(zewif.transactions().len(), 2);
+        assert_eq!(zewif.export_height(), BlockHeight::from(2_100_000));
+    }
+
+    fn overlapping() -> (Zewif, Zewif) {
+        let existing = zewif(
+            1,
+            &[address("t1shared", "Savings"), address("t1mine", "")],
+            &[transaction(1, Some(100)), transaction(2, None)],
+            Network::Main,
+        );
+        let mut richer = transaction(2, None);
+        richer.set_target_height(BlockHeight::from(99));
+        let incoming = zewif(
+            1,
+            &[address("t1shared", "Holiday"), address("t1theirs", "")],
+            &[transaction(1, Some(101)), richer, transaction(3, None)],
+            Network::Main,
+        );
+        (existing, incoming)
+    }
+
+    #[test]
+    fn test_merge_overlapping() {
+        let (mut zewif, incoming) = overlapping();
+        let report = zewif.merge(incoming, MergePolicy::KeepExisting).unwrap();
+
+        assert_eq!(zewif.wallets_len(), 1);
+        let wallet = &zewif.wallets()[0];
+        assert_eq!(wallet.seeds().len(), 1);
+        assert_eq!(wallet.accounts().len(), 1);
+        let account = &wallet.accounts()[0];
+        let addresses: Vec<_> = account
+            .addresses()
+            .iter()
+            .map(|a| (a.as_string(), a.name().to_string()))
+            .collect();
+        assert_eq!(
+            addresses,
+            [("t1shared", "Savings"), ("t1mine", ""), ("t1theirs", "")]
+                .map(|(a, n)| (a.to_string(), n.to_string()))
+        );
+        assert_eq!(account.relevant_transactions_len(), 3);
+
+        let txid = |byte| TxId::from_bytes([byte; 32]);
+        assert_eq!(zewif.transactions().len(), 3);
+        assert_eq!(
+            zewif.get_transaction(txid(1)).unwrap().mined_height(),
+            Some(&BlockHeight::from(100))
+        );
+        assert_eq!(
+            zewif.get_transaction(txid(2)).unwrap().target_height(),
+            Some(&BlockHeight::from(99))
+        );
+
+        let decisions = report.decisions();
+        assert!(decisions.contains(&MergeDecision::WalletMerged { wallet: 0 }));
+        assert!(decisions.iter().any(|d| matches!(
+            d,
+            MergeDecision::DuplicateSeed {
+                wallet: 0,
+                fingerprint: Some(_)
+            }
+        )));
+        assert!(decisions.contains(&MergeDecision::DuplicateAddress {
+            address: "t1shared".to_string()
+        }));
+        assert!(decisions.contains(&MergeDecision::AddressNameConflict {
+            address: "t1shared".to_string(),
+            kept: "Savings".to_string(),
+            discarded: "Holiday".to_string(),
+        }));
+        assert!(
+            decisions.contains(&MergeDecision::TransactionHeightConflict {
+                txid: txid(1),
+                kept: BlockHeight::from(100),
+                discarded: BlockHeight::from(101),
+            })
+        );
+        assert!(decisions.contains(&MergeDecision::DuplicateTransaction {
+            txid: txid(2),
+            kept: MergeSide::Incoming
+        }));
+        assert!(decisions.contains(&MergeDecision::AccountMerged {
+            wallet: 0,
+            account: 0,
+            duplicates: 0
+        }));
+        assert!(!report.to_string().is_empty());
+    }
+
+    #[test]
+    fn test_merge_prefer_incoming() {
+        let (mut zewif, incoming) = overlapping();
+        zewif.merge(incoming, MergePolicy::PreferIncoming).unwrap();
+        let account = &zewif.wallets()[0].accounts()[0];
+        assert_eq!(account.addresses()[0].name(), "Holiday");
+        let transaction = zewif.get_transaction(TxId::from_bytes([1; 32])).unwrap();
+        assert_eq!(transaction.mined_height(), Some(&BlockHeight::from(101)));
+    }
+
+    #[test]
+    fn test_merge_fail_on_conflict() {
+        let (mut zewif, incoming) = overlapping();
+        let original = zewif.clone();
+        assert!(matches!(
+            zewif.merge(incoming, MergePolicy::Fail),
+            Err(Error::MergeConflict { .. })
+        ));
+        assert_eq!(zewif, original);
+
+        // Without conflicts, the policy never comes into play.
+        let (mut zewif, _) = overlapping();
+        let same = zewif.clone();
+        let report = zewif.merge(same, MergePolicy::Fail).unwrap();
+        assert!(!report.is_disjoint());
+        assert_eq!(zewif.transactions().len(), 2);
+        assert_eq!(zewif.wallets()[0].accounts()[0].addresses_len(), 2);
+    }
+
+    #[test]
+    fn test_merge_ambiguous_address() {
+        let fingerprint = *seed(1).fingerprint().unwrap();
+        let mut existing = zewif(1, &[address("t1a", "")], &[], Network::Main);
+        let wallet = &mut existing.wallets_mut()[0];
+        wallet.accounts_mut()[0].set_seed_fingerprint(Some(fingerprint));
+        wallet.accounts_mut()[0].set_zip32_account_id(0);
+        let mut other = Account::new();
+        other.add_address(address("t1b", ""));
+        wallet.add_account(other);
+
+        // The incoming account is matched to the first account by derivation,
+        // but holds an address of the second.
+        let mut incoming = zewif(1, &[address("t1b", "")], &[], Network::Main);
+        incoming.wallets_mut()[0].accounts_mut()[0].set_seed_fingerprint(Some(fingerprint));
+        incoming.wallets_mut()[0].accounts_mut()[0].set_zip32_account_id(0);
+
+        let original = existing.clone();
+        assert!(matches!(
+            existing.merge(incoming.clone(), MergePolicy::Fail),
+            Err(Error::MergeConflict { .. })
+        ));
+        assert_eq!(existing, original);
+
+        let report = existing.merge(incoming, MergePolicy::KeepExisting).unwrap();
+        let duplicate = report
+            .decisions()
+            .iter()
+            .find_map(|decision| match decision {
+                MergeDecision::AmbiguousAddress { duplicate } => Some(duplicate),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(duplicate.address(), "t1b");
+        assert_eq!(duplicate.accounts(), [(0, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn test_merge_network_mismatch() {
+        let mut main = zewif(1, &[], &[], Network::Main);
+        let test = zewif(2, &[], &[], Network::Test);
+        let original = main.clone();
+        assert!(matches!(
+            main.merge(test, MergePolicy::KeepExisting),
+            Err(Error::NetworkMismatch { .. })
+        ));
+        assert_eq!(main, original);
+    }
+}
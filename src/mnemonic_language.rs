@@ -42,6 +42,11 @@ use bc_envelope::prelude::*;
 /// # Ok::<(), zewif::Error>(())
 /// ```
 #[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
 pub enum MnemonicLanguage {
     /// English wordlist (the most commonly used)
     English = 0,
@@ -66,6 +71,20 @@ pub enum MnemonicLanguage {
 }
 
 impl MnemonicLanguage {
+    /// All supported languages, in the order of their numeric identifiers.
+    pub const ALL: [MnemonicLanguage; 10] = [
+        MnemonicLanguage::English,
+        MnemonicLanguage::SimplifiedChinese,
+        MnemonicLanguage::TraditionalChinese,
+        MnemonicLanguage::Czech,
+        MnemonicLanguage::French,
+        MnemonicLanguage::Italian,
+        MnemonicLanguage::Japanese,
+        MnemonicLanguage::Korean,
+        MnemonicLanguage::Portuguese,
+        MnemonicLanguage::Spanish,
+    ];
+
     /// Creates a `MnemonicLanguage` from a numeric identifier.
     ///
     /// This method converts a raw numeric value into the corresponding language
@@ -106,7 +125,7 @@ impl MnemonicLanguage {
             7 => Ok(MnemonicLanguage::Korean),
             8 => Ok(MnemonicLanguage::Portuguese),
             9 => Ok(MnemonicLanguage::Spanish),
-            _ => Err(Error::InvalidLanguage(value.to_string())),
+            _ => Err(Error::InvalidLanguage { value }),
         }
     }
 
@@ -144,6 +163,63 @@ impl MnemonicLanguage {
     }
 }
 
+impl From<MnemonicLanguage> for bip39::Language {
+    fn from(value: MnemonicLanguage) -> Self {
+        match value {
+            MnemonicLanguage::English => bip39::Language::English,
+            MnemonicLanguage::SimplifiedChinese => bip39::Language::SimplifiedChinese,
+            MnemonicLanguage::TraditionalChinese => bip39::Language::TraditionalChinese,
+            MnemonicLanguage::Czech => bip39::Language::Czech,
+            MnemonicLanguage::French => bip39::Language::French,
+            MnemonicLanguage::Italian => bip39::Language::Italian,
+            MnemonicLanguage::Japanese => bip39::Language::Japanese,
+            MnemonicLanguage::Korean => bip39::Language::Korean,
+            MnemonicLanguage::Portuguese => bip39::Language::Portuguese,
+            MnemonicLanguage::Spanish => bip39::Language::Spanish,
+        }
+    }
+}
+
+/// Identifies the BIP-39 wordlist from which a mnemonic phrase is drawn.
+///
+/// The phrase is NFKD normalized first, so Japanese phrases separated by
+/// ideographic spaces are handled. Some wordlists share words (English and
+/// French have about a hundred in common), so a language is chosen only if
+/// every word of the phrase is in its wordlist, and no other's.
+///
+/// # Errors
+/// Returns [`Error::InvalidMnemonic`] if the phrase is empty, and
+/// [`Error::UndetectedMnemonicLanguage`] if no wordlist contains all of its
+/// words, or if more than one does.
+///
+/// # Examples
+/// ```
+/// # use zewif::{MnemonicLanguage, detect_language};
+/// let phrase =
+///     "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+/// assert_eq!(detect_language(phrase)?, MnemonicLanguage::English);
+/// # Ok::<(), zewif::Error>(())
+/// ```
+pub fn detect_language(words: &str) -> Result<MnemonicLanguage> {
+    let mut words = std::borrow::Cow::Borrowed(words);
+    bip39::Mnemonic::normalize_utf8_cow(&mut words);
+    let words: Vec<&str> = words.split_whitespace().collect();
+    if words.is_empty() {
+        return Err(Error::InvalidMnemonic(bip39::Error::BadWordCount(0)));
+    }
+    let candidates: Vec<MnemonicLanguage> = MnemonicLanguage::ALL
+        .into_iter()
+        .filter(|&language| {
+            let wordlist = bip39::Language::from(language);
+            words.iter().all(|word| wordlist.find_word(word).is_some())
+        })
+        .collect();
+    match candidates.as_slice() {
+        [language] => Ok(*language),
+        _ => Err(Error::UndetectedMnemonicLanguage { candidates }),
+    }
+}
+
 /// Formats the mnemonic language as a human-readable string
 impl std::fmt::Display for MnemonicLanguage {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -190,7 +266,7 @@ impl TryFrom<String> for MnemonicLanguage {
             "ko" => Ok(MnemonicLanguage::Korean),
             "pt" => Ok(MnemonicLanguage::Portuguese),
             "es" => Ok(MnemonicLanguage::Spanish),
-            _ => Err(Error::InvalidMnemonicLanguage(value)),
+            _ => Err(Error::InvalidMnemonicLanguage { name: value }),
         }
     }
 }
@@ -239,4 +315,34 @@ mod tests {
 
     test_cbor_roundtrip!(MnemonicLanguage);
     test_envelope_roundtrip!(MnemonicLanguage);
+
+    #[test]
+    fn test_detect_language() {
+        use super::detect_language;
+
+        let english = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        assert_eq!(detect_language(english).unwrap(), MnemonicLanguage::English);
+
+        // "abandon", "fragile" and "question" are in both the English and the
+        // French wordlists; "pelouse" is only French.
+        assert!(detect_language("abandon fragile question").is_err());
+        assert_eq!(
+            detect_language("abandon fragile question pelouse").unwrap(),
+            MnemonicLanguage::French
+        );
+
+        let spanish = "ábaco ábaco ábaco ábaco ábaco ábaco ábaco ábaco ábaco ábaco ábaco abierto";
+        assert_eq!(detect_language(spanish).unwrap(), MnemonicLanguage::Spanish);
+
+        // Japanese phrases are separated by ideographic spaces (U+3000).
+        let japanese = "あいこくしん\u{3000}あいこくしん\u{3000}あおぞら";
+        assert_eq!(
+            detect_language(japanese).unwrap(),
+            MnemonicLanguage::Japanese
+        );
+
+        // A word from another wordlist rules out every language.
+        assert!(detect_language("legal winner pelouse").is_err());
+        assert!(detect_language("").is_err());
+    }
 }
@@ -0,0 +1,361 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{
+    Account, Address, Amount, Balance, Error, Indexed, NetworkParameters, OutPoint, Result,
+    Transaction, TxId, TxOut, Zewif, zewif_impl::pool_total,
+};
+
+/// The change in one account's value caused by a transaction, broken down by
+/// pool; see [`Transaction::net_value_change`].
+///
+/// A transparent input whose spent output cannot be found says nothing about
+/// whether the account funded it, so it is left out of the values and listed
+/// by [`NetValueChange::unresolved_inputs`] instead. A history can then show
+/// the change it knows of, flagged as partial, rather than none at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetValueChange {
+    received: Balance,
+    spent: Balance,
+    value: Amount,
+    unresolved_inputs: Vec<OutPoint>,
+}
+
+impl NetValueChange {
+    /// Returns the value paid to the account in each pool: its notes created
+    /// by the transaction, and the transparent outputs paying its addresses.
+    pub fn received(&self) -> &Balance {
+        &self.received
+    }
+
+    /// Returns the value spent from the account in each pool: its notes
+    /// spent by the transaction, and the outputs paying its addresses that
+    /// the transaction's transparent inputs spend.
+    pub fn spent(&self) -> &Balance {
+        &self.spent
+    }
+
+    /// Returns the signed change in the account's value, the total received
+    /// less the total spent.
+    pub fn value(&self) -> Amount {
+        self.value
+    }
+
+    /// Returns the outpoints spent by transparent inputs whose outputs could
+    /// not be found, in input order.
+    pub fn unresolved_inputs(&self) -> &[OutPoint] {
+        &self.unresolved_inputs
+    }
+
+    /// Returns whether some inputs were unresolved, so that the account may
+    /// have spent more than [`NetValueChange::spent`] accounts for.
+    pub fn is_partial(&self) -> bool {
+        !self.unresolved_inputs.is_empty()
+    }
+}
+
+impl Transaction {
+    /// Computes the change in `account`'s value caused by the transaction.
+    ///
+    /// Shielded values are those of the account's received notes created by
+    /// the transaction or recorded as spent by it, so spends should first be
+    /// linked with [`Zewif::link_spent_notes`]. Transparent values come from
+    /// the transaction's recorded inputs and outputs (see
+    /// [`Transaction::populate_transparent`]): outputs paying one of the
+    /// account's addresses, encoded with the effective network parameters of
+    /// its wallet in `zewif`, are received, and inputs spending such outputs
+    /// are spent. The output an
+    /// input spends is looked up among the account's UTXOs, then the
+    /// transactions stored in `zewif`; inputs found in neither are reported
+    /// by [`NetValueChange::unresolved_inputs`]. The inputs of a payment from
+    /// another wallet are usually among them, since the Zewif seldom stores
+    /// the transactions that funded it.
+    ///
+    /// The transaction's status is not consulted; a history that should
+    /// disregard unmined transactions can check
+    /// [`Zewif::counts_toward_balance`].
+    ///
+    /// # Errors
+    /// Returns [`Error::NotInZewif`] if no wallet of `zewif` holds the
+    /// account, and [`Error::AmountOverflow`] or [`Error::AmountUnderflow`]
+    /// if the values do not yield a valid amount.
+    pub fn net_value_change(&self, account: &Account, zewif: &Zewif) -> Result<NetValueChange> {
+        let (wallet, _) =
+            zewif
+                .find_account_by_id(&account.id())
+                .ok_or_else(|| Error::NotInZewif {
+                    kind: "Account",
+                    id: account.id().to_string(),
+                })?;
+        self.value_change_of(
+            account,
+            &addresses_of(account),
+            &wallet.effective_network_parameters(),
+            zewif,
+        )
+    }
+
+    pub(crate) fn value_change_of(
+        &self,
+        account: &Account,
+        addresses: &HashSet<String>,
+        params: &NetworkParameters,
+        zewif: &Zewif,
+    ) -> Result<NetValueChange> {
+        let pays_account = |output: &TxOut| {
+            output
+                .address_with(params)
+                .is_some_and(|address| addresses.contains(&address))
+        };
+        let mut transparent_spent = Vec::new();
+        let mut unresolved_inputs = Vec::new();
+        for input in self
+            .transparent_inputs()
+            .iter()
+            .filter(|input| !input.is_coinbase())
+        {
+            let prevout = input.prevout();
+            if let Some(utxo) = account
+                .utxos()
+                .iter()
+                .find(|utxo| utxo.outpoint() == prevout)
+            {
+                transparent_spent.push(utxo.value());
+            } else if let Some(output) = zewif.resolve_output(prevout) {
+                if pays_account(&output) {
+                    transparent_spent.push(output.value());
+                }
+            } else {
+                unresolved_inputs.push(*prevout);
+            }
+        }
+        let transparent_received = self
+            .transparent_outputs()
+            .iter()
+            .filter(|output| pays_account(output))
+            .map(TxOut::value);
+
+        let txid = self.txid();
+        let sprout: Vec<_> = account
+            .sprout_received_notes()
+            .iter()
+            .map(|note| (note.txid(), note.spent_in(), note.note().value()))
+            .collect();
+        let sapling: Vec<_> = account
+            .sapling_received_notes()
+            .iter()
+            .map(|note| (note.txid(), note.spent_in(), note.note().value()))
+            .collect();
+        let orchard: Vec<_> = account
+            .orchard_received_notes()
+            .iter()
+            .map(|note| (note.txid(), note.spent_in(), note.note().value()))
+            .collect();
+        let created = |notes: &[(TxId, Option<TxId>, Amount)]| {
+            pool_total(
+                notes
+                    .iter()
+                    .filter(|(created_in, _, _)| *created_in == txid)
+                    .map(|(_, _, value)| *value),
+            )
+        };
+        let spent = |notes: &[(TxId, Option<TxId>, Amount)]| {
+            pool_total(
+                notes
+                    .iter()
+                    .filter(|(_, spent_in, _)| *spent_in == Some(txid))
+                    .map(|(_, _, value)| *value),
+            )
+        };
+
+        let received = Balance::new(
+            pool_total(transparent_received)?,
+            created(&sprout)?,
+            created(&sapling)?,
+            created(&orchard)?,
+        );
+        let spent = Balance::new(
+            pool_total(transparent_spent.into_iter())?,
+            spent(&sprout)?,
+            spent(&sapling)?,
+            spent(&orchard)?,
+        );
+        let value = total(&received)?.checked_sub(total(&spent)?)?;
+        Ok(NetValueChange {
+            received,
+            spent,
+            value,
+            unresolved_inputs,
+        })
+    }
+}
+
+impl Zewif {
+    /// Computes the change in value of every account caused by each stored
+    /// transaction involving it, as by [`Transaction::net_value_change`],
+    /// keyed by the indexes of the account's wallet and of the account within
+    /// it, and the transaction's ID.
+    ///
+    /// An account's transactions are those recorded as relevant to it and
+    /// those that created or spent its notes; those missing from the store
+    /// are skipped. Each account's addresses are collected once for all of
+    /// its transactions.
+    ///
+    /// # Errors
+    /// Returns [`Error::AmountOverflow`] or [`Error::AmountUnderflow`] as for
+    /// [`Transaction::net_value_change`].
+    pub fn net_value_changes(&self) -> Result<BTreeMap<(usize, usize, TxId), NetValueChange>> {
+        let mut changes = BTreeMap::new();
+        for wallet in self.wallets() {
+            let params = wallet.effective_network_parameters();
+            for account in wallet.accounts() {
+                let addresses = addresses_of(account);
+                let txids: HashSet<TxId> = account
+                    .relevant_transactions()
+                    .copied()
+                    .chain(account.note_transactions())
+                    .collect();
+                for transaction in txids.iter().filter_map(|txid| self.transaction(txid)) {
+                    let change = transaction.value_change_of(account, &addresses, &params, self)?;
+                    changes.insert(
+                        (wallet.index(), account.index(), transaction.txid()),
+                        change,
+                    );
+                }
+            }
+        }
+        Ok(changes)
+    }
+}
+
+fn addresses_of(account: &Account) -> HashSet<String> {
+    account.addresses().iter().map(Address::as_string).collect()
+}
+
+fn total(balance: &Balance) -> Result<Amount> {
+    pool_total(
+        [
+            balance.transparent(),
+            balance.sprout(),
+            balance.sapling(),
+            balance.orchard(),
+        ]
+        .into_iter(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{note, p2pkh, spend_of, transaction, txid, zats};
+    use crate::{
+        Account, Address, Amount, BlockHeight, Error, Network, OutPoint, ProtocolAddress, Zewif,
+        ZewifWallet, transparent,
+    };
+
+    /// The account owns the transparent address with hash 1. Transaction 1,
+    /// funded from outside the Zewif, pays it 1000. Transaction 2 shields
+    /// that output into a Sapling note of 900. Transaction 3 spends the note,
+    /// paying 500 to hash 2 and returning a change note of 350.
+    fn zewif() -> Zewif {
+        let mut account = Account::new();
+        let address = p2pkh(1, 0).address(Network::Test).unwrap();
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new(address),
+        )));
+        account.add_sapling_received_note(note(txid(2), 900, Some(txid(3))));
+        account.add_sapling_received_note(note(txid(3), 350, None));
+        account.add_relevant_transaction(txid(1));
+        let mut wallet = ZewifWallet::new(Network::Test);
+        wallet.add_account(account);
+        let mut zewif = Zewif::new(BlockHeight::from(100));
+        zewif.add_wallet(wallet);
+        let transactions = [
+            transaction(
+                1,
+                vec![spend_of(txid(9), 0)],
+                vec![p2pkh(3, 4_000), p2pkh(1, 1_000)],
+            ),
+            transaction(2, vec![spend_of(txid(1), 1)], vec![]),
+            transaction(3, vec![], vec![p2pkh(2, 500)]),
+        ];
+        for transaction in transactions {
+            zewif
+                .add_transaction(transaction.txid(), transaction)
+                .unwrap();
+        }
+        zewif
+    }
+
+    #[test]
+    fn test_receive() {
+        let zewif = zewif();
+        let account = &zewif.wallets()[0].accounts()[0];
+        let change = zewif
+            .transaction(&txid(1))
+            .unwrap()
+            .net_value_change(account, &zewif)
+            .unwrap();
+        assert_eq!(change.value(), zats(1_000));
+        assert_eq!(change.received().transparent(), zats(1_000));
+        // The sender's input spends an output the Zewif does not hold.
+        assert!(change.is_partial());
+        assert_eq!(change.unresolved_inputs(), [OutPoint::new(txid(9), 0)]);
+    }
+
+    #[test]
+    fn test_shielding() {
+        let zewif = zewif();
+        let account = &zewif.wallets()[0].accounts()[0];
+        let change = zewif
+            .transaction(&txid(2))
+            .unwrap()
+            .net_value_change(account, &zewif)
+            .unwrap();
+        assert_eq!(change.spent().transparent(), zats(1_000));
+        assert_eq!(change.received().sapling(), zats(900));
+        // Only the fee leaves the account.
+        assert_eq!(change.value(), zats(-100));
+        assert!(!change.is_partial());
+    }
+
+    #[test]
+    fn test_send() {
+        let zewif = zewif();
+        let account = &zewif.wallets()[0].accounts()[0];
+        let change = zewif
+            .transaction(&txid(3))
+            .unwrap()
+            .net_value_change(account, &zewif)
+            .unwrap();
+        assert_eq!(change.spent().sapling(), zats(900));
+        assert_eq!(change.received().sapling(), zats(350));
+        assert_eq!(change.received().transparent(), Amount::zero());
+        assert_eq!(change.value(), zats(-550));
+
+        let stranger = Account::new();
+        assert!(matches!(
+            zewif
+                .transaction(&txid(3))
+                .unwrap()
+                .net_value_change(&stranger, &zewif),
+            Err(Error::NotInZewif { .. })
+        ));
+    }
+
+    #[test]
+    fn test_net_value_changes() {
+        let zewif = zewif();
+        let changes = zewif.net_value_changes().unwrap();
+        let values: Vec<_> = changes
+            .iter()
+            .map(|(key, change)| (*key, change.value(), change.is_partial()))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ((0, 0, txid(1)), zats(1_000), true),
+                ((0, 0, txid(2)), zats(-100), false),
+                ((0, 0, txid(3)), zats(-550), false)
+            ]
+        );
+    }
+}
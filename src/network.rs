@@ -38,12 +38,146 @@ use bc_envelope::prelude::*;
 /// }
 /// ```
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
 pub enum Network {
     Main,
     Test,
     Regtest,
 }
 
+/// Network-specific encoding constants, following zcashd's chain parameters.
+///
+/// Testnet and regtest share their coin type and Base58Check prefixes, but
+/// differ in their Bech32 human-readable parts.
+impl Network {
+    /// Returns the SLIP-44 coin type used in BIP-44 and ZIP 32 derivation
+    /// paths.
+    pub fn coin_type(&self) -> u32 {
+        match self {
+            Network::Main => 133,
+            Network::Test | Network::Regtest => 1,
+        }
+    }
+
+    /// Returns the Base58Check prefix of transparent P2PKH addresses.
+    pub fn p2pkh_prefix(&self) -> [u8; 2] {
+        match self {
+            Network::Main => [0x1c, 0xb8],
+            Network::Test | Network::Regtest => [0x1d, 0x25],
+        }
+    }
+
+    /// Returns the Base58Check prefix of transparent P2SH addresses.
+    pub fn p2sh_prefix(&self) -> [u8; 2] {
+        match self {
+            Network::Main => [0x1c, 0xbd],
+            Network::Test | Network::Regtest => [0x1c, 0xba],
+        }
+    }
+
+    /// Returns the Base58Check prefix of WIF-encoded transparent secret keys.
+    pub fn secret_key_prefix(&self) -> [u8; 1] {
+        match self {
+            Network::Main => [0x80],
+            Network::Test | Network::Regtest => [0xef],
+        }
+    }
+
+    /// Returns the Base58Check prefix of Sprout payment addresses.
+    pub fn sprout_address_prefix(&self) -> [u8; 2] {
+        match self {
+            Network::Main => [0x16, 0x9a],
+            Network::Test | Network::Regtest => [0x16, 0xb6],
+        }
+    }
+
+    /// Returns the Base58Check prefix of Sprout incoming viewing keys.
+    pub fn sprout_viewing_key_prefix(&self) -> [u8; 3] {
+        match self {
+            Network::Main => [0xa8, 0xab, 0xd3],
+            Network::Test | Network::Regtest => [0xa8, 0xac, 0x0c],
+        }
+    }
+
+    /// Returns the Bech32 human-readable part of Sapling payment addresses.
+    pub fn sapling_hrp(&self) -> &'static str {
+        match self {
+            Network::Main => "zs",
+            Network::Test => "ztestsapling",
+            Network::Regtest => "zregtestsapling",
+        }
+    }
+
+    /// Returns the Bech32 human-readable part of Sapling extended spending
+    /// keys.
+    pub fn sapling_extended_spending_key_hrp(&self) -> &'static str {
+        match self {
+            Network::Main => "secret-extended-key-main",
+            Network::Test => "secret-extended-key-test",
+            Network::Regtest => "secret-extended-key-regtest",
+        }
+    }
+
+    /// Returns the Bech32 human-readable part of Sapling extended full viewing
+    /// keys.
+    pub fn sapling_extended_full_viewing_key_hrp(&self) -> &'static str {
+        match self {
+            Network::Main => "zxviews",
+            Network::Test => "zxviewtestsapling",
+            Network::Regtest => "zxviewregtestsapling",
+        }
+    }
+
+    /// Returns the Bech32m human-readable part of unified addresses.
+    pub fn unified_hrp(&self) -> &'static str {
+        match self {
+            Network::Main => "u",
+            Network::Test => "utest",
+            Network::Regtest => "uregtest",
+        }
+    }
+
+    /// Returns the Bech32m human-readable part of unified full viewing keys.
+    pub fn unified_full_viewing_key_hrp(&self) -> &'static str {
+        match self {
+            Network::Main => "uview",
+            Network::Test => "uviewtest",
+            Network::Regtest => "uviewregtest",
+        }
+    }
+
+    /// Returns the Bech32m human-readable part of unified incoming viewing
+    /// keys.
+    pub fn unified_incoming_viewing_key_hrp(&self) -> &'static str {
+        match self {
+            Network::Main => "uivk",
+            Network::Test => "uivktest",
+            Network::Regtest => "uivkregtest",
+        }
+    }
+}
+
+impl Network {
+    /// Returns the network whose SLIP-44 coin type is `coin_type`, if any.
+    /// Regtest shares testnet's coin type, which is reported as
+    /// [`Network::Test`].
+    pub fn from_coin_type(coin_type: u32) -> Option<Network> {
+        [Network::Main, Network::Test]
+            .into_iter()
+            .find(|network| network.coin_type() == coin_type)
+    }
+
+    /// Returns whether the two networks share their Base58Check prefixes and
+    /// coin type, as testnet and regtest do.
+    pub fn shares_base58_encoding(&self, other: Network) -> bool {
+        self.p2pkh_prefix() == other.p2pkh_prefix()
+    }
+}
+
 impl From<Network> for String {
     fn from(value: Network) -> String {
         match value {
@@ -65,28 +199,51 @@ impl TryFrom<String> for Network {
         } else if value == "regtest" {
             Ok(Network::Regtest)
         } else {
-            Err(Error::InvalidNetwork(value))
+            Err(Error::InvalidNetwork {
+                network: Some(value),
+                reason: "not main, test, or regtest".to_string(),
+            })
         }
     }
 }
 
+/// Encodes the network as a small integer: 0 for mainnet, 1 for testnet, and 2
+/// for regtest.
 impl From<Network> for CBOR {
     fn from(value: Network) -> Self {
-        String::from(value).into()
+        let code: u8 = match value {
+            Network::Main => 0,
+            Network::Test => 1,
+            Network::Regtest => 2,
+        };
+        code.into()
     }
 }
 
+/// Decodes either the integer encoding or the text encoding (`"main"`,
+/// `"test"`, or `"regtest"`) used by earlier versions of the format.
 impl TryFrom<CBOR> for Network {
     type Error = dcbor::Error;
 
     fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
-        Ok(cbor.try_into_text()?.try_into()?)
+        match cbor.into_case() {
+            CBORCase::Unsigned(0) => Ok(Network::Main),
+            CBORCase::Unsigned(1) => Ok(Network::Test),
+            CBORCase::Unsigned(2) => Ok(Network::Regtest),
+            CBORCase::Unsigned(n) => Err(Error::InvalidNetwork {
+                network: Some(n.to_string()),
+                reason: "not 0, 1, or 2".to_string(),
+            }
+            .into()),
+            CBORCase::Text(text) => Ok(Network::try_from(text)?),
+            _ => Err(dcbor::Error::WrongType),
+        }
     }
 }
 
 impl From<Network> for Envelope {
     fn from(value: Network) -> Self {
-        Envelope::new(String::from(value))
+        Envelope::new(CBOR::from(value))
     }
 }
 
@@ -94,8 +251,7 @@ impl TryFrom<Envelope> for Network {
     type Error = bc_envelope::Error;
 
     fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
-        let network_str: String = envelope.extract_subject()?;
-        Network::try_from(network_str).map_err(|e| e.into())
+        envelope.extract_subject()
     }
 }
 
@@ -117,4 +273,57 @@ mod tests {
 
     test_cbor_roundtrip!(Network);
     test_envelope_roundtrip!(Network);
+
+    #[test]
+    fn test_cbor_encodings() {
+        use bc_envelope::prelude::*;
+
+        for (network, code, text) in [
+            (Network::Main, 0u8, "main"),
+            (Network::Test, 1, "test"),
+            (Network::Regtest, 2, "regtest"),
+        ] {
+            assert_eq!(CBOR::from(network), CBOR::from(code));
+            assert_eq!(Network::try_from(CBOR::from(code)).unwrap(), network);
+            assert_eq!(Network::try_from(CBOR::from(text)).unwrap(), network);
+            assert_eq!(Network::try_from(Envelope::new(text)).unwrap(), network);
+        }
+        assert!(Network::try_from(CBOR::from(3)).is_err());
+        assert!(Network::try_from(CBOR::from(-1)).is_err());
+        assert!(Network::try_from(CBOR::from("mainnet")).is_err());
+        assert!(Network::try_from(CBOR::from(true)).is_err());
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_encoding_constants() {
+        // (network, coin type, P2PKH, P2SH, WIF, Sprout, Sapling, extended spending key,
+        //  extended full viewing key, unified address, UFVK, UIVK)
+        let table = [
+            (Network::Main, 133, [0x1c, 0xb8], [0x1c, 0xbd], [0x80], [0x16, 0x9a], "zs",
+             "secret-extended-key-main", "zxviews", "u", "uview", "uivk"),
+            (Network::Test, 1, [0x1d, 0x25], [0x1c, 0xba], [0xef], [0x16, 0xb6], "ztestsapling",
+             "secret-extended-key-test", "zxviewtestsapling", "utest", "uviewtest", "uivktest"),
+            (Network::Regtest, 1, [0x1d, 0x25], [0x1c, 0xba], [0xef], [0x16, 0xb6],
+             "zregtestsapling", "secret-extended-key-regtest", "zxviewregtestsapling", "uregtest",
+             "uviewregtest", "uivkregtest"),
+        ];
+        for row in table {
+            let (network, coin_type, p2pkh, p2sh, wif, sprout, sapling, xsk, xfvk, ua, ufvk, uivk) =
+                row;
+            assert_eq!(network.coin_type(), coin_type);
+            assert_eq!(network.p2pkh_prefix(), p2pkh);
+            assert_eq!(network.p2sh_prefix(), p2sh);
+            assert_eq!(network.secret_key_prefix(), wif);
+            assert_eq!(network.sprout_address_prefix(), sprout);
+            assert_eq!(network.sapling_hrp(), sapling);
+            assert_eq!(network.sapling_extended_spending_key_hrp(), xsk);
+            assert_eq!(network.sapling_extended_full_viewing_key_hrp(), xfvk);
+            assert_eq!(network.unified_hrp(), ua);
+            assert_eq!(network.unified_full_viewing_key_hrp(), ufvk);
+            assert_eq!(network.unified_incoming_viewing_key_hrp(), uivk);
+        }
+        assert_eq!(Network::Main.sprout_viewing_key_prefix(), [0xa8, 0xab, 0xd3]);
+        assert_eq!(Network::Regtest.sprout_viewing_key_prefix(), [0xa8, 0xac, 0x0c]);
+    }
 }
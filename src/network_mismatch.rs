@@ -0,0 +1,36 @@
+use crate::Network;
+
+/// An element of a wallet that is encoded for a different network than the
+/// wallet's; see [`ZewifWallet::validate_network`](crate::ZewifWallet::validate_network).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkMismatch {
+    account: usize,
+    element: String,
+    detected: Network,
+}
+
+impl NetworkMismatch {
+    pub fn new(account: usize, element: impl Into<String>, detected: Network) -> Self {
+        Self {
+            account,
+            element: element.into(),
+            detected,
+        }
+    }
+
+    /// Returns the index of the account holding the element.
+    pub fn account(&self) -> usize {
+        self.account
+    }
+
+    /// Returns a description of the element, such as `address t1...`.
+    pub fn element(&self) -> &str {
+        &self.element
+    }
+
+    /// Returns the network for which the element is encoded. Elements whose
+    /// encoding testnet and regtest share are reported as [`Network::Test`].
+    pub fn detected(&self) -> Network {
+        self.detected
+    }
+}
@@ -0,0 +1,331 @@
+use bc_envelope::prelude::*;
+
+use crate::{Blob, BlockHeight, Network};
+
+/// A Zcash network upgrade whose activation changes consensus rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NetworkUpgrade {
+    Overwinter,
+    Sapling,
+    Blossom,
+    Heartwood,
+    Canopy,
+    Nu5,
+}
+
+impl NetworkUpgrade {
+    /// All network upgrades, in order of activation.
+    pub const ALL: [NetworkUpgrade; 6] = [
+        NetworkUpgrade::Overwinter,
+        NetworkUpgrade::Sapling,
+        NetworkUpgrade::Blossom,
+        NetworkUpgrade::Heartwood,
+        NetworkUpgrade::Canopy,
+        NetworkUpgrade::Nu5,
+    ];
+
+    fn predicate(&self) -> &'static str {
+        match self {
+            NetworkUpgrade::Overwinter => "overwinter_activation_height",
+            NetworkUpgrade::Sapling => "sapling_activation_height",
+            NetworkUpgrade::Blossom => "blossom_activation_height",
+            NetworkUpgrade::Heartwood => "heartwood_activation_height",
+            NetworkUpgrade::Canopy => "canopy_activation_height",
+            NetworkUpgrade::Nu5 => "nu5_activation_height",
+        }
+    }
+}
+
+/// The consensus and encoding parameters of the network a wallet was exported
+/// from.
+///
+/// Mainnet and testnet have fixed parameters, returned by
+/// [`NetworkParameters::for_network`]. Regtest deployments choose their own
+/// activation heights, and test harnesses sometimes change address prefixes
+/// too, so a wallet exported from such a deployment may record the
+/// parameters it used; see
+/// [`ZewifWallet::network_parameters`](crate::ZewifWallet::network_parameters).
+///
+/// # Examples
+/// ```
+/// # use zewif::{BlockHeight, Network, NetworkParameters, NetworkUpgrade};
+/// let mainnet = NetworkParameters::for_network(Network::Main);
+/// assert!(BlockHeight::from(1_687_104u32).is_nu5_active(&mainnet));
+/// assert!(!BlockHeight::from(1_687_103u32).is_nu5_active(&mainnet));
+///
+/// let mut regtest = NetworkParameters::for_network(Network::Regtest);
+/// regtest.set_activation_height(NetworkUpgrade::Overwinter, Some(BlockHeight::from(1u32)));
+/// regtest.set_activation_height(NetworkUpgrade::Sapling, Some(BlockHeight::from(1u32)));
+/// assert!(BlockHeight::from(1u32).is_upgrade_active(NetworkUpgrade::Sapling, &regtest));
+/// assert!(!BlockHeight::from(1_000_000u32).is_nu5_active(&regtest));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct NetworkParameters {
+    network: Network,
+    overwinter_activation_height: Option<BlockHeight>,
+    sapling_activation_height: Option<BlockHeight>,
+    blossom_activation_height: Option<BlockHeight>,
+    heartwood_activation_height: Option<BlockHeight>,
+    canopy_activation_height: Option<BlockHeight>,
+    nu5_activation_height: Option<BlockHeight>,
+    coin_type: u32,
+    #[cfg_attr(feature = "json", serde(with = "crate::json::hex_bytes"))]
+    p2pkh_prefix: [u8; 2],
+    #[cfg_attr(feature = "json", serde(with = "crate::json::hex_bytes"))]
+    p2sh_prefix: [u8; 2],
+    sapling_hrp: String,
+    unified_hrp: String,
+}
+
+impl NetworkParameters {
+    /// Returns the built-in parameters of the given network.
+    ///
+    /// Mainnet and testnet use their published activation heights. As in
+    /// zcashd, no upgrade is active on regtest unless configured, so the
+    /// regtest parameters have no activation heights; set those of a
+    /// particular deployment with
+    /// [`NetworkParameters::set_activation_height`].
+    pub fn for_network(network: Network) -> Self {
+        let heights: [Option<u32>; 6] = match network {
+            Network::Main => [
+                Some(347_500),
+                Some(419_200),
+                Some(653_600),
+                Some(903_000),
+                Some(1_046_400),
+                Some(1_687_104),
+            ],
+            Network::Test => [
+                Some(207_500),
+                Some(280_000),
+                Some(584_000),
+                Some(903_800),
+                Some(1_028_500),
+                Some(1_842_420),
+            ],
+            Network::Regtest => [None; 6],
+        };
+        let [overwinter, sapling, blossom, heartwood, canopy, nu5] =
+            heights.map(|h| h.map(BlockHeight::from));
+        Self {
+            network,
+            overwinter_activation_height: overwinter,
+            sapling_activation_height: sapling,
+            blossom_activation_height: blossom,
+            heartwood_activation_height: heartwood,
+            canopy_activation_height: canopy,
+            nu5_activation_height: nu5,
+            coin_type: network.coin_type(),
+            p2pkh_prefix: network.p2pkh_prefix(),
+            p2sh_prefix: network.p2sh_prefix(),
+            sapling_hrp: network.sapling_hrp().to_string(),
+            unified_hrp: network.unified_hrp().to_string(),
+        }
+    }
+
+    /// Returns the parameters of a regtest deployment activating each
+    /// upgrade at the given height, or never for `None`, in the order of
+    /// [`NetworkUpgrade::ALL`]. Address constants are those of
+    /// [`Network::Regtest`].
+    pub fn custom_regtest(activation_heights: [Option<BlockHeight>; 6]) -> Self {
+        let mut params = Self::for_network(Network::Regtest);
+        for (upgrade, height) in NetworkUpgrade::ALL.into_iter().zip(activation_heights) {
+            params.set_activation_height(upgrade, height);
+        }
+        params
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Returns the height at which the upgrade activates, or `None` if it
+    /// never does.
+    pub fn activation_height(&self, upgrade: NetworkUpgrade) -> Option<BlockHeight> {
+        match upgrade {
+            NetworkUpgrade::Overwinter => self.overwinter_activation_height,
+            NetworkUpgrade::Sapling => self.sapling_activation_height,
+            NetworkUpgrade::Blossom => self.blossom_activation_height,
+            NetworkUpgrade::Heartwood => self.heartwood_activation_height,
+            NetworkUpgrade::Canopy => self.canopy_activation_height,
+            NetworkUpgrade::Nu5 => self.nu5_activation_height,
+        }
+    }
+
+    pub fn set_activation_height(&mut self, upgrade: NetworkUpgrade, height: Option<BlockHeight>) {
+        let slot = match upgrade {
+            NetworkUpgrade::Overwinter => &mut self.overwinter_activation_height,
+            NetworkUpgrade::Sapling => &mut self.sapling_activation_height,
+            NetworkUpgrade::Blossom => &mut self.blossom_activation_height,
+            NetworkUpgrade::Heartwood => &mut self.heartwood_activation_height,
+            NetworkUpgrade::Canopy => &mut self.canopy_activation_height,
+            NetworkUpgrade::Nu5 => &mut self.nu5_activation_height,
+        };
+        *slot = height;
+    }
+
+    /// Returns whether the upgrade is active at the given height.
+    pub fn is_active(&self, upgrade: NetworkUpgrade, height: BlockHeight) -> bool {
+        self.activation_height(upgrade)
+            .is_some_and(|activation| height >= activation)
+    }
+
+    /// Returns the SLIP-44 coin type; see [`Network::coin_type`].
+    pub fn coin_type(&self) -> u32 {
+        self.coin_type
+    }
+
+    pub fn set_coin_type(&mut self, coin_type: u32) {
+        self.coin_type = coin_type;
+    }
+
+    /// Returns the network whose coin type is `coin_type`, taking this
+    /// network's to be [`NetworkParameters::coin_type`]; see
+    /// [`Network::from_coin_type`].
+    pub(crate) fn network_of_coin_type(&self, coin_type: u32) -> Option<Network> {
+        if coin_type == self.coin_type {
+            Some(self.network)
+        } else {
+            Network::from_coin_type(coin_type)
+        }
+    }
+
+    /// Returns the Base58Check prefix of P2PKH addresses; see
+    /// [`Network::p2pkh_prefix`].
+    pub fn p2pkh_prefix(&self) -> [u8; 2] {
+        self.p2pkh_prefix
+    }
+
+    pub fn set_p2pkh_prefix(&mut self, prefix: [u8; 2]) {
+        self.p2pkh_prefix = prefix;
+    }
+
+    /// Returns the Base58Check prefix of P2SH addresses; see
+    /// [`Network::p2sh_prefix`].
+    pub fn p2sh_prefix(&self) -> [u8; 2] {
+        self.p2sh_prefix
+    }
+
+    pub fn set_p2sh_prefix(&mut self, prefix: [u8; 2]) {
+        self.p2sh_prefix = prefix;
+    }
+
+    /// Returns the human-readable part of Sapling addresses; see
+    /// [`Network::sapling_hrp`].
+    pub fn sapling_hrp(&self) -> &str {
+        &self.sapling_hrp
+    }
+
+    pub fn set_sapling_hrp(&mut self, hrp: impl Into<String>) {
+        self.sapling_hrp = hrp.into();
+    }
+
+    /// Returns the human-readable part of unified addresses; see
+    /// [`Network::unified_hrp`].
+    pub fn unified_hrp(&self) -> &str {
+        &self.unified_hrp
+    }
+
+    pub fn set_unified_hrp(&mut self, hrp: impl Into<String>) {
+        self.unified_hrp = hrp.into();
+    }
+}
+
+impl BlockHeight {
+    /// Returns whether the upgrade is active at this height under the given
+    /// parameters.
+    pub fn is_upgrade_active(&self, upgrade: NetworkUpgrade, params: &NetworkParameters) -> bool {
+        params.is_active(upgrade, *self)
+    }
+
+    /// Returns whether NU5, which introduced Orchard, is active at this
+    /// height under the given parameters.
+    pub fn is_nu5_active(&self, params: &NetworkParameters) -> bool {
+        self.is_upgrade_active(NetworkUpgrade::Nu5, params)
+    }
+}
+
+impl From<NetworkParameters> for Envelope {
+    fn from(value: NetworkParameters) -> Self {
+        let mut e = Envelope::new(value.network).add_type("NetworkParameters");
+        for upgrade in NetworkUpgrade::ALL {
+            e = e.add_optional_assertion(upgrade.predicate(), value.activation_height(upgrade));
+        }
+        e.add_assertion("coin_type", value.coin_type)
+            .add_assertion("p2pkh_prefix", Blob::new(value.p2pkh_prefix))
+            .add_assertion("p2sh_prefix", Blob::new(value.p2sh_prefix))
+            .add_assertion("sapling_hrp", value.sapling_hrp)
+            .add_assertion("unified_hrp", value.unified_hrp)
+    }
+}
+
+impl TryFrom<Envelope> for NetworkParameters {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("NetworkParameters")?;
+        let network = envelope.extract_subject()?;
+        let mut params = Self::for_network(network);
+        for upgrade in NetworkUpgrade::ALL {
+            params.set_activation_height(
+                upgrade,
+                envelope.extract_optional_object_for_predicate(upgrade.predicate())?,
+            );
+        }
+        params.coin_type = envelope.extract_object_for_predicate("coin_type")?;
+        params.p2pkh_prefix = *envelope
+            .extract_object_for_predicate::<Blob<2>>("p2pkh_prefix")?
+            .as_bytes();
+        params.p2sh_prefix = *envelope
+            .extract_object_for_predicate::<Blob<2>>("p2sh_prefix")?
+            .as_bytes();
+        params.sapling_hrp = envelope.extract_object_for_predicate("sapling_hrp")?;
+        params.unified_hrp = envelope.extract_object_for_predicate("unified_hrp")?;
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BlockHeight, Network, test_envelope_roundtrip};
+
+    use super::{NetworkParameters, NetworkUpgrade};
+
+    impl crate::RandomInstance for NetworkParameters {
+        fn random() -> Self {
+            let mut params = NetworkParameters::custom_regtest(
+                NetworkUpgrade::ALL.map(|_| BlockHeight::opt_random()),
+            );
+            params.network = Network::random();
+            params.coin_type = u32::random();
+            params.p2pkh_prefix = rand::random();
+            params.p2sh_prefix = rand::random();
+            params.sapling_hrp = String::random();
+            params.unified_hrp = String::random();
+            params
+        }
+    }
+
+    test_envelope_roundtrip!(NetworkParameters);
+
+    #[test]
+    fn test_activation() {
+        let testnet = NetworkParameters::for_network(Network::Test);
+        assert_eq!(
+            testnet.activation_height(NetworkUpgrade::Nu5),
+            Some(BlockHeight::from(1_842_420u32))
+        );
+        assert!(BlockHeight::from(280_000u32).is_upgrade_active(NetworkUpgrade::Sapling, &testnet));
+        assert!(
+            !BlockHeight::from(279_999u32).is_upgrade_active(NetworkUpgrade::Sapling, &testnet)
+        );
+
+        let h = |height: u32| Some(BlockHeight::from(height));
+        let regtest = NetworkParameters::custom_regtest([h(1), h(1), h(5), h(5), h(10), None]);
+        assert!(BlockHeight::from(10u32).is_upgrade_active(NetworkUpgrade::Canopy, &regtest));
+        assert!(!BlockHeight::from(9u32).is_upgrade_active(NetworkUpgrade::Canopy, &regtest));
+        assert!(!BlockHeight::from(u32::MAX).is_nu5_active(&regtest));
+        assert_eq!(regtest.unified_hrp(), "uregtest");
+    }
+}
@@ -24,6 +24,11 @@ use bc_envelope::prelude::*;
 /// assert_eq!(value, 42);
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct NonHardenedChildIndex(u32);
 
 /// Converts a u32 value to a NonHardenedChildIndex
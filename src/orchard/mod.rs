@@ -1,4 +1,13 @@
 use crate::mod_use;
 
+mod_use!(note);
+mod_use!(orchard_anchor_registry);
+mod_use!(orchard_derivation);
+mod_use!(orchard_full_viewing_key);
+mod_use!(orchard_action_description);
+mod_use!(orchard_nullifier);
+mod_use!(orchard_received_note);
 mod_use!(orchard_sent_output);
+mod_use!(orchard_spending_key);
+mod_use!(orchard_tree_state);
 mod_use!(orchard_witness);
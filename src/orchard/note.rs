@@ -0,0 +1,150 @@
+use bc_envelope::prelude::*;
+
+use crate::{Amount, Blob, Blob32, Error, Result};
+
+/// The plaintext of an Orchard note received by the wallet.
+///
+/// An Orchard note is the unit of value in the Orchard shielded pool. Unlike a
+/// transparent output, its contents are never visible on chain: only the note
+/// commitment (`cmx`) appears in the action that created it. A wallet that has
+/// decrypted the note holds the plaintext components needed to recompute the
+/// commitment, derive the note's nullifier, and spend it.
+///
+/// # Zcash Concept Relation
+/// As specified in the Zcash protocol specification (§3.2), an Orchard note is
+/// the tuple `(d, pk_d, v, ρ, ψ, rcm)`, where `ψ` and `rcm` are derived from
+/// `rseed`:
+///
+/// - **Recipient**: The raw 43-byte Orchard address (`d || pk_d`)
+/// - **Value**: The amount of ZEC held by the note
+/// - **Rho (ρ)**: The nullifier of the note spent in the same action, which
+///   makes every Orchard note's nullifier unique
+/// - **Rseed**: The random seed from which `ψ` and `rcm` are derived
+///
+/// # Data Preservation
+/// During wallet migration, the complete note plaintext must be preserved for
+/// every unspent note. Without it, the receiving wallet cannot recompute the
+/// nullifier needed to detect spends, nor construct a proof to spend the note.
+///
+/// # Examples
+/// ```
+/// # use zewif::{orchard, Amount, Blob};
+/// # use zewif::Result;
+/// # fn example() -> Result<()> {
+/// let note = orchard::Note::from_parts(
+///     Blob::new([0u8; 43]),
+///     Amount::from_u64(10_000_000)?,
+///     Blob::new([1u8; 32]),
+///     Blob::new([2u8; 32]),
+/// )?;
+///
+/// let zats: i64 = note.value().into();
+/// assert_eq!(zats, 10_000_000);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Note {
+    /// The raw encoding of the Orchard address that received the note.
+    recipient: Blob<43>,
+
+    /// The value of the note, in zatoshis.
+    value: Amount,
+
+    /// The nullifier of the note spent by the action that created this note.
+    rho: Blob32,
+
+    /// The random seed used to derive `psi` and `rcm` for this note.
+    rseed: Blob32,
+}
+
+impl Note {
+    /// Creates a new Orchard note from its constituent parts.
+    ///
+    /// # Errors
+    /// Returns [`Error::NegativeNoteValue`] if `value` is negative.
+    pub fn from_parts(
+        recipient: Blob<43>,
+        value: Amount,
+        rho: Blob32,
+        rseed: Blob32,
+    ) -> Result<Self> {
+        if value.is_negative() {
+            return Err(Error::NegativeNoteValue {
+                pool: "Orchard",
+                value,
+            });
+        }
+        Ok(Self {
+            recipient,
+            value,
+            rho,
+            rseed,
+        })
+    }
+
+    /// Returns the raw encoding of the address that received the note.
+    pub fn recipient(&self) -> &Blob<43> {
+        &self.recipient
+    }
+
+    /// Returns the value of the note.
+    pub fn value(&self) -> Amount {
+        self.value
+    }
+
+    /// Returns the `rho` component of the note.
+    pub fn rho(&self) -> &Blob32 {
+        &self.rho
+    }
+
+    /// Returns the random seed of the note.
+    pub fn rseed(&self) -> &Blob32 {
+        &self.rseed
+    }
+}
+
+impl From<Note> for Envelope {
+    fn from(value: Note) -> Self {
+        Envelope::new(value.recipient)
+            .add_type("OrchardNote")
+            .add_assertion("value", value.value)
+            .add_assertion("rho", value.rho)
+            .add_assertion("rseed", value.rseed)
+    }
+}
+
+impl TryFrom<Envelope> for Note {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("OrchardNote")?;
+        let recipient = envelope.extract_subject()?;
+        let value: Amount = envelope.extract_object_for_predicate("value")?;
+        let rho = envelope.extract_object_for_predicate("rho")?;
+        let rseed = envelope.extract_object_for_predicate("rseed")?;
+        Self::from_parts(recipient, value, rho, rseed)
+            .map_err(|e| bc_envelope::Error::General(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Amount, Blob, test_envelope_roundtrip};
+
+    use super::Note;
+
+    impl crate::RandomInstance for Note {
+        fn random() -> Self {
+            Self {
+                recipient: Blob::random(),
+                value: Amount::random_nonnegative(),
+                rho: Blob::random(),
+                rseed: Blob::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(Note);
+}
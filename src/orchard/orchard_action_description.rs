@@ -0,0 +1,261 @@
+use std::io::{self, Read, Write};
+
+use bc_envelope::prelude::*;
+
+use crate::{Blob, Blob32, Indexed};
+
+use super::OrchardNullifier;
+
+/// The size in bytes of an Orchard note ciphertext (`encCiphertext`).
+pub const ORCHARD_ENC_CIPHERTEXT_SIZE: usize = 580;
+
+/// The size in bytes of an Orchard outgoing ciphertext (`outCiphertext`).
+pub const ORCHARD_OUT_CIPHERTEXT_SIZE: usize = 80;
+
+/// The public data of a single action within a transaction's Orchard bundle.
+///
+/// Orchard combines a spend and an output into a single *action*. Each action
+/// consumes one note (revealing its nullifier) and creates one new note
+/// (publishing its commitment and ciphertexts). A wallet that holds an incoming
+/// or outgoing viewing key can trial-decrypt the ciphertexts of an action to
+/// discover notes it received or sent.
+///
+/// # Zcash Concept Relation
+/// As specified in the Zcash protocol specification (§7.5), the consensus
+/// encoding of an action description within a v5 transaction is:
+///
+/// - **cv_net**: The value commitment to the net value of the action (32 bytes)
+/// - **nullifier**: The nullifier of the note being spent (32 bytes)
+/// - **rk**: The randomized spend validating key (32 bytes)
+/// - **cmx**: The x-coordinate of the new note's commitment (32 bytes)
+/// - **ephemeral_key**: The ephemeral public key for note encryption (32 bytes)
+/// - **enc_ciphertext**: The encrypted note plaintext (580 bytes)
+/// - **out_ciphertext**: The encrypted outgoing recovery data (80 bytes)
+///
+/// The spend authorization signature and the bundle's proof are encoded
+/// separately in the transaction and are not part of this type.
+///
+/// # Data Preservation
+/// Preserving every action allows the receiving wallet to re-derive which notes
+/// belong to it without refetching the transaction from the chain. Received and
+/// sent note records refer to actions by their index within
+/// [`Transaction::orchard_actions`](crate::Transaction::orchard_actions).
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use zewif::orchard::OrchardActionDescription;
+/// # fn example() -> std::io::Result<()> {
+/// // An action description is 820 bytes in the v5 transaction encoding
+/// let data = vec![0u8; 820];
+/// let action = OrchardActionDescription::read(Cursor::new(&data))?;
+///
+/// let mut buf = Vec::new();
+/// action.write(&mut buf)?;
+/// assert_eq!(buf, data);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrchardActionDescription {
+    /// The index of the action within the transaction's Orchard bundle.
+    index: usize,
+    cv_net: Blob32,
+    nullifier: OrchardNullifier,
+    rk: Blob32,
+    cmx: Blob32,
+    ephemeral_key: Blob32,
+    enc_ciphertext: Blob<ORCHARD_ENC_CIPHERTEXT_SIZE>,
+    out_ciphertext: Blob<ORCHARD_OUT_CIPHERTEXT_SIZE>,
+}
+
+impl Indexed for OrchardActionDescription {
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
+
+impl OrchardActionDescription {
+    /// Creates a new action description from its constituent parts.
+    pub fn from_parts(
+        cv_net: Blob32,
+        nullifier: OrchardNullifier,
+        rk: Blob32,
+        cmx: Blob32,
+        ephemeral_key: Blob32,
+        enc_ciphertext: Blob<ORCHARD_ENC_CIPHERTEXT_SIZE>,
+        out_ciphertext: Blob<ORCHARD_OUT_CIPHERTEXT_SIZE>,
+    ) -> Self {
+        Self {
+            index: 0,
+            cv_net,
+            nullifier,
+            rk,
+            cmx,
+            ephemeral_key,
+            enc_ciphertext,
+            out_ciphertext,
+        }
+    }
+
+    /// Returns the value commitment to the net value of the action.
+    pub fn cv_net(&self) -> &Blob32 {
+        &self.cv_net
+    }
+
+    /// Returns the nullifier of the note spent by the action.
+    pub fn nullifier(&self) -> &OrchardNullifier {
+        &self.nullifier
+    }
+
+    /// Returns the randomized spend validating key.
+    pub fn rk(&self) -> &Blob32 {
+        &self.rk
+    }
+
+    /// Returns the x-coordinate of the commitment to the note created by the
+    /// action.
+    pub fn cmx(&self) -> &Blob32 {
+        &self.cmx
+    }
+
+    /// Returns the ephemeral public key used to encrypt the new note.
+    pub fn ephemeral_key(&self) -> &Blob32 {
+        &self.ephemeral_key
+    }
+
+    /// Returns the encrypted note plaintext.
+    pub fn enc_ciphertext(&self) -> &Blob<ORCHARD_ENC_CIPHERTEXT_SIZE> {
+        &self.enc_ciphertext
+    }
+
+    /// Returns the encrypted outgoing recovery data.
+    pub fn out_ciphertext(&self) -> &Blob<ORCHARD_OUT_CIPHERTEXT_SIZE> {
+        &self.out_ciphertext
+    }
+
+    /// Reads an action description in its consensus encoding.
+    ///
+    /// # Errors
+    /// Returns an IO error if the reader does not contain a complete action
+    /// description.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        fn read_array<R: Read, const N: usize>(reader: &mut R) -> io::Result<Blob<N>> {
+            let mut bytes = [0u8; N];
+            reader.read_exact(&mut bytes)?;
+            Ok(Blob::new(bytes))
+        }
+
+        Ok(Self::from_parts(
+            read_array(&mut reader)?,
+            OrchardNullifier::new(*read_array(&mut reader)?.as_bytes()),
+            read_array(&mut reader)?,
+            read_array(&mut reader)?,
+            read_array(&mut reader)?,
+            read_array(&mut reader)?,
+            read_array(&mut reader)?,
+        ))
+    }
+
+    /// Writes the action description in its consensus encoding.
+    ///
+    /// # Errors
+    /// Returns an IO error if writing fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.cv_net.as_slice())?;
+        writer.write_all(self.nullifier.as_slice())?;
+        writer.write_all(self.rk.as_slice())?;
+        writer.write_all(self.cmx.as_slice())?;
+        writer.write_all(self.ephemeral_key.as_slice())?;
+        writer.write_all(self.enc_ciphertext.as_slice())?;
+        writer.write_all(self.out_ciphertext.as_slice())?;
+        Ok(())
+    }
+}
+
+impl From<OrchardActionDescription> for Envelope {
+    fn from(value: OrchardActionDescription) -> Self {
+        Envelope::new(value.index)
+            .add_type("OrchardActionDescription")
+            .add_assertion("cv_net", value.cv_net)
+            .add_assertion("nullifier", value.nullifier)
+            .add_assertion("rk", value.rk)
+            .add_assertion("cmx", value.cmx)
+            .add_assertion("ephemeral_key", value.ephemeral_key)
+            .add_assertion("enc_ciphertext", value.enc_ciphertext)
+            .add_assertion("out_ciphertext", value.out_ciphertext)
+    }
+}
+
+impl TryFrom<Envelope> for OrchardActionDescription {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("OrchardActionDescription")?;
+        let index = envelope.extract_subject()?;
+        let cv_net = envelope.extract_object_for_predicate("cv_net")?;
+        let nullifier = envelope.try_object_for_predicate("nullifier")?;
+        let rk = envelope.extract_object_for_predicate("rk")?;
+        let cmx = envelope.extract_object_for_predicate("cmx")?;
+        let ephemeral_key = envelope.extract_object_for_predicate("ephemeral_key")?;
+        let enc_ciphertext = envelope.extract_object_for_predicate("enc_ciphertext")?;
+        let out_ciphertext = envelope.extract_object_for_predicate("out_ciphertext")?;
+        Ok(Self {
+            index,
+            cv_net,
+            nullifier,
+            rk,
+            cmx,
+            ephemeral_key,
+            enc_ciphertext,
+            out_ciphertext,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{Blob, RandomInstance, orchard::OrchardNullifier, test_envelope_roundtrip};
+
+    use super::OrchardActionDescription;
+
+    impl RandomInstance for OrchardActionDescription {
+        fn random() -> Self {
+            Self {
+                index: 0,
+                cv_net: Blob::random(),
+                nullifier: OrchardNullifier::random(),
+                rk: Blob::random(),
+                cmx: Blob::random(),
+                ephemeral_key: Blob::random(),
+                enc_ciphertext: Blob::random(),
+                out_ciphertext: Blob::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(OrchardActionDescription);
+
+    #[test]
+    fn test_consensus_encoding_roundtrip() {
+        let action = OrchardActionDescription::random();
+        let mut buf = Vec::new();
+        action.write(&mut buf).unwrap();
+        assert_eq!(buf.len(), 820);
+        let decoded = OrchardActionDescription::read(Cursor::new(&buf)).unwrap();
+        assert_eq!(decoded, action);
+    }
+
+    #[test]
+    fn test_truncated_encoding_fails() {
+        let buf = vec![0u8; 819];
+        assert!(OrchardActionDescription::read(Cursor::new(&buf)).is_err());
+    }
+}
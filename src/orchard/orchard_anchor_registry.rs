@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use bc_envelope::prelude::*;
+
+use crate::{AnchorRegistry, BlockHeight};
+
+use super::MerkleHashOrchard;
+
+/// The roots of the Orchard note commitment tree known to a wallet, with the
+/// heights at which they were observed.
+///
+/// `OrchardAnchorRegistry` is used to check that each Orchard witness refers to
+/// an anchor of the chain known to the wallet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct OrchardAnchorRegistry(AnchorRegistry<MerkleHashOrchard>);
+
+impl OrchardAnchorRegistry {
+    /// Creates a new Orchard anchor registry.
+    pub fn new(registry: AnchorRegistry<MerkleHashOrchard>) -> Self {
+        Self(registry)
+    }
+
+    /// Returns the underlying anchor registry.
+    pub fn registry(&self) -> &AnchorRegistry<MerkleHashOrchard> {
+        &self.0
+    }
+
+    /// Records that `anchor` was the root of the Orchard tree as of `height`.
+    pub fn insert(&mut self, anchor: MerkleHashOrchard, height: BlockHeight) {
+        self.0.insert(anchor, height);
+    }
+}
+
+impl From<OrchardAnchorRegistry> for Envelope {
+    fn from(value: OrchardAnchorRegistry) -> Self {
+        let anchors: HashMap<MerkleHashOrchard, BlockHeight> = value
+            .0
+            .iter()
+            .map(|(anchor, height)| (*anchor, *height))
+            .collect();
+        Envelope::new(CBOR::from(anchors)).add_type("OrchardAnchorRegistry")
+    }
+}
+
+impl TryFrom<Envelope> for OrchardAnchorRegistry {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("OrchardAnchorRegistry")?;
+        let anchors: HashMap<MerkleHashOrchard, BlockHeight> = envelope.extract_subject()?;
+        Ok(Self(anchors.into_iter().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AnchorRegistry, RandomInstance, test_envelope_roundtrip};
+
+    use super::OrchardAnchorRegistry;
+
+    impl RandomInstance for OrchardAnchorRegistry {
+        fn random() -> Self {
+            Self(AnchorRegistry::random())
+        }
+    }
+
+    test_envelope_roundtrip!(OrchardAnchorRegistry);
+}
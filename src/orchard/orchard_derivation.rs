@@ -0,0 +1,248 @@
+use bc_envelope::prelude::*;
+
+use crate::SeedFingerprint;
+#[cfg(feature = "zip32")]
+use crate::{Error, Result};
+
+#[cfg(feature = "zip32")]
+use super::OrchardSpendingKey;
+
+/// The ZIP 32 purpose constant for shielded key derivation.
+pub const ZIP32_PURPOSE: u32 = 32;
+
+/// The ZIP 32 derivation metadata for an account's Orchard spending key.
+///
+/// Orchard spending keys are derived from a seed along the hardened path
+/// `m/32'/coin_type'/account'`. Recording the coin type, the account index, and
+/// the fingerprint of the seed lets the receiving wallet reproduce the key from
+/// the seed, or verify that an exported key matches it.
+///
+/// # Zcash Concept Relation
+/// - **Purpose**: Always `32'` for ZIP 32 shielded derivation
+/// - **Coin type**: `133'` on mainnet and `1'` on testnet and regtest
+/// - **Account**: The ZIP 32 account index
+///
+/// # Examples
+/// ```
+/// # use zewif::orchard::OrchardDerivation;
+/// let derivation = OrchardDerivation::new(133, 0, None);
+/// assert_eq!(derivation.path(), "m/32'/133'/0'");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrchardDerivation {
+    coin_type: u32,
+    account_index: u32,
+    seed_fingerprint: Option<SeedFingerprint>,
+}
+
+impl OrchardDerivation {
+    /// Creates new derivation metadata for the given coin type and account.
+    pub fn new(
+        coin_type: u32,
+        account_index: u32,
+        seed_fingerprint: Option<SeedFingerprint>,
+    ) -> Self {
+        Self {
+            coin_type,
+            account_index,
+            seed_fingerprint,
+        }
+    }
+
+    /// Returns the coin type component of the derivation path.
+    pub fn coin_type(&self) -> u32 {
+        self.coin_type
+    }
+
+    /// Returns the account component of the derivation path.
+    pub fn account_index(&self) -> u32 {
+        self.account_index
+    }
+
+    /// Returns the fingerprint of the seed from which the key was derived, if
+    /// known.
+    pub fn seed_fingerprint(&self) -> Option<&SeedFingerprint> {
+        self.seed_fingerprint.as_ref()
+    }
+
+    /// Sets the fingerprint of the seed from which the key was derived.
+    pub fn set_seed_fingerprint(&mut self, seed_fingerprint: Option<SeedFingerprint>) {
+        self.seed_fingerprint = seed_fingerprint;
+    }
+
+    /// Returns the derivation path in its conventional string form.
+    pub fn path(&self) -> String {
+        format!(
+            "m/{}'/{}'/{}'",
+            ZIP32_PURPOSE, self.coin_type, self.account_index
+        )
+    }
+
+    /// Derives the Orchard spending key described by this metadata from `seed`.
+    ///
+    /// # Errors
+    /// Returns an error if the seed is shorter than 32 bytes or longer than
+    /// 252 bytes.
+    #[cfg(feature = "zip32")]
+    pub fn derive_spending_key(&self, seed: &[u8]) -> Result<OrchardSpendingKey> {
+        let (sk, _) = derive_path(seed, &[ZIP32_PURPOSE, self.coin_type, self.account_index])?;
+        Ok(OrchardSpendingKey::new(sk))
+    }
+
+    /// Checks that `key` was derived from `seed` as described by this metadata.
+    ///
+    /// When a seed fingerprint is recorded, the seed is first checked against
+    /// it.
+    ///
+    /// # Errors
+    /// Returns [`Error::SeedFingerprintMismatch`] if the seed does not match
+    /// the recorded fingerprint, and [`Error::DerivationMismatch`] if the
+    /// re-derived key does not match `key`.
+    #[cfg(feature = "zip32")]
+    pub fn verify(&self, seed: &[u8], key: &OrchardSpendingKey) -> Result<()> {
+        if let Some(fingerprint) = &self.seed_fingerprint {
+            let actual = SeedFingerprint::from_seed(seed)?;
+            if actual != *fingerprint {
+                return Err(Error::SeedFingerprintMismatch {
+                    expected: *fingerprint,
+                    actual,
+                });
+            }
+        }
+        if self.derive_spending_key(seed)? != *key {
+            return Err(Error::DerivationMismatch {
+                derivation: self.path().to_string(),
+                reason: "the re-derived Orchard spending key does not match".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Derives the Orchard spending key and chain code at the given hardened path,
+/// as specified in ZIP 32. Path components are given without the hardened bit.
+#[cfg(feature = "zip32")]
+fn derive_path(seed: &[u8], path: &[u32]) -> Result<([u8; 32], [u8; 32])> {
+    fn split(i: &[u8]) -> ([u8; 32], [u8; 32]) {
+        let mut sk = [0u8; 32];
+        let mut c = [0u8; 32];
+        sk.copy_from_slice(&i[..32]);
+        c.copy_from_slice(&i[32..]);
+        (sk, c)
+    }
+
+    if !(32..=252).contains(&seed.len()) {
+        return Err(Error::InvalidSeedLength(seed.len()));
+    }
+    // I := BLAKE2b-512("ZcashIP32Orchard", seed)
+    let master = blake2b_simd::Params::new()
+        .hash_length(64)
+        .personal(b"ZcashIP32Orchard")
+        .hash(seed);
+    let (mut sk, mut c) = split(master.as_bytes());
+    for index in path {
+        // I := PRF^expand(c_par, [0x81] || sk_par || I2LEOSP_32(i))
+        let child = blake2b_simd::Params::new()
+            .hash_length(64)
+            .personal(b"Zcash_ExpandSeed")
+            .to_state()
+            .update(&c)
+            .update(&[0x81])
+            .update(&sk)
+            .update(&(index | (1 << 31)).to_le_bytes())
+            .finalize();
+        (sk, c) = split(child.as_bytes());
+    }
+    Ok((sk, c))
+}
+
+impl From<OrchardDerivation> for Envelope {
+    fn from(value: OrchardDerivation) -> Self {
+        Envelope::new(value.account_index)
+            .add_type("OrchardDerivation")
+            .add_assertion("coin_type", value.coin_type)
+            .add_optional_assertion("seed_fingerprint", value.seed_fingerprint)
+    }
+}
+
+impl TryFrom<Envelope> for OrchardDerivation {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("OrchardDerivation")?;
+        let account_index = envelope.extract_subject()?;
+        let coin_type = envelope.extract_object_for_predicate("coin_type")?;
+        let seed_fingerprint = envelope.try_optional_object_for_predicate("seed_fingerprint")?;
+        Ok(Self {
+            coin_type,
+            account_index,
+            seed_fingerprint,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SeedFingerprint, test_envelope_roundtrip};
+
+    use super::OrchardDerivation;
+
+    impl crate::RandomInstance for OrchardDerivation {
+        fn random() -> Self {
+            Self {
+                coin_type: u32::random() >> 1,
+                account_index: u32::random() >> 1,
+                seed_fingerprint: SeedFingerprint::opt_random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(OrchardDerivation);
+
+    /// Test vectors from https://github.com/zcash-hackworks/zcash-test-vectors
+    /// (`orchard_zip32.py`), using the seed `[0, 1, ..., 31]`.
+    #[cfg(feature = "zip32")]
+    #[test]
+    fn test_zip32_vectors() {
+        use super::derive_path;
+
+        let seed: Vec<u8> = (0u8..32).collect();
+
+        let (sk, c) = derive_path(&seed, &[]).unwrap();
+        assert_eq!(
+            hex::encode(sk),
+            "7eee3c1017870990a3dd6891b82f80be8976c1e7dc20d60817a5e88e8b2cd4b8"
+        );
+        assert_eq!(
+            hex::encode(c),
+            "ab8b7a00509ef20e469b5292b61d474b7cffcb1657924cda720250ae40526677"
+        );
+
+        let (sk, c) = derive_path(&seed, &[1]).unwrap();
+        assert_eq!(
+            hex::encode(sk),
+            "98d703fcb40504c95b3b6ed10ecd50082cff97dfd1dd9aa0913c78f977c962af"
+        );
+        assert_eq!(
+            hex::encode(c),
+            "6a041dfb9cfebee97cb1854fdc481cc04f02c9577aa6f13b2c445b80a9669a22"
+        );
+    }
+
+    #[cfg(feature = "zip32")]
+    #[test]
+    fn test_verify_detects_mismatch() {
+        let seed: Vec<u8> = (0u8..32).collect();
+        let derivation =
+            OrchardDerivation::new(133, 0, Some(SeedFingerprint::from_seed(&seed).unwrap()));
+        let key = derivation.derive_spending_key(&seed).unwrap();
+        assert!(derivation.verify(&seed, &key).is_ok());
+
+        let other = OrchardDerivation::new(133, 1, None);
+        assert!(other.verify(&seed, &key).is_err());
+
+        let other_seed = [7u8; 32];
+        assert!(derivation.verify(&other_seed, &key).is_err());
+    }
+}
@@ -0,0 +1,20 @@
+use crate::{blob, blob_envelope};
+
+blob!(
+    OrchardFullViewingKey,
+    96,
+    r#"An Orchard full viewing key, encoded as `ak || nk || rivk`.
+
+The full viewing key allows detection of both incoming and outgoing Orchard
+notes, and derivation of the account's Orchard addresses, without conferring
+spending authority. This is the encoding used for the Orchard component of a
+ZIP 316 unified full viewing key.
+
+# Examples
+```
+# use zewif::orchard::OrchardFullViewingKey;
+let fvk = OrchardFullViewingKey::new([0u8; 96]);
+```"#
+);
+
+blob_envelope!(OrchardFullViewingKey);
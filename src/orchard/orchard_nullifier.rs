@@ -0,0 +1,27 @@
+use crate::{blob, blob_envelope};
+
+blob!(
+    OrchardNullifier,
+    32,
+    r#"The nullifier of an Orchard note.
+
+A nullifier is revealed on chain when a note is spent. Because it can only be
+computed with knowledge of the note and the owner's nullifier deriving key, a
+wallet determines whether one of its notes has been spent by looking for the
+note's nullifier among the actions of later transactions.
+
+# Zcash Concept Relation
+Orchard nullifiers are distinct from Sapling nullifiers: they are derived by a
+different function and are checked against a separate nullifier set, so the two
+are modeled as distinct types to prevent them from being compared with each
+other.
+
+# Examples
+```
+# use zewif::orchard::OrchardNullifier;
+let nullifier = OrchardNullifier::new([0u8; 32]);
+```"#
+);
+impl Copy for OrchardNullifier {}
+
+blob_envelope!(OrchardNullifier);
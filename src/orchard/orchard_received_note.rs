@@ -0,0 +1,200 @@
+use bc_envelope::prelude::*;
+
+use crate::{Indexed, TxId};
+
+use super::{Note, OrchardNullifier, OrchardWitness};
+
+/// An Orchard note received by the wallet, paired with the witness needed to
+/// spend it.
+///
+/// `OrchardReceivedNote` records where a note was created (the transaction and
+/// the index of the action within that transaction's Orchard bundle), the
+/// decrypted note plaintext, and, for notes that remain spendable, the witness
+/// proving the note's commitment is present in the Orchard note commitment
+/// tree. Once known, the note's nullifier and the transaction that spent it
+/// are recorded as well.
+///
+/// # Data Preservation
+/// For each unspent Orchard note, both the note plaintext and its witness must
+/// survive migration; a note without a witness cannot be spent until the
+/// receiving wallet has rescanned the chain to rebuild it.
+///
+/// # Examples
+/// ```
+/// # use zewif::{orchard::{self, OrchardReceivedNote}, Amount, Blob, TxId};
+/// # use zewif::Result;
+/// # fn example() -> Result<()> {
+/// let note = orchard::Note::from_parts(
+///     Blob::new([0u8; 43]),
+///     Amount::from_u64(10_000_000)?,
+///     Blob::new([1u8; 32]),
+///     Blob::new([2u8; 32]),
+/// )?;
+/// let received = OrchardReceivedNote::new(TxId::from_bytes([0u8; 32]), 1, note);
+///
+/// assert_eq!(received.action_index(), 1);
+/// assert!(received.witness().is_none());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrchardReceivedNote {
+    /// The index of this note within the account's received notes.
+    index: usize,
+
+    /// The id of the transaction that created the note.
+    txid: TxId,
+
+    /// The index of the action within the transaction's Orchard bundle that
+    /// created the note; see [`Transaction::orchard_action`](crate::Transaction::orchard_action).
+    action_index: u32,
+
+    /// The decrypted note plaintext.
+    note: Note,
+
+    /// The witness for the note's commitment, if the note is spendable and the
+    /// source wallet maintained one.
+    witness: Option<OrchardWitness>,
+
+    /// The nullifier of the note, if the source wallet computed it.
+    nullifier: Option<OrchardNullifier>,
+
+    /// The id of the transaction that spent the note, if known.
+    spent_in: Option<TxId>,
+}
+
+impl Indexed for OrchardReceivedNote {
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
+
+impl OrchardReceivedNote {
+    /// Creates a new received note without a witness.
+    pub fn new(txid: TxId, action_index: u32, note: Note) -> Self {
+        Self {
+            index: 0,
+            txid,
+            action_index,
+            note,
+            witness: None,
+            nullifier: None,
+            spent_in: None,
+        }
+    }
+
+    /// Returns the id of the transaction that created the note.
+    pub fn txid(&self) -> TxId {
+        self.txid
+    }
+
+    /// Returns the index of the action within the transaction that created the
+    /// note.
+    pub fn action_index(&self) -> u32 {
+        self.action_index
+    }
+
+    /// Returns the decrypted note plaintext.
+    pub fn note(&self) -> &Note {
+        &self.note
+    }
+
+    /// Returns the witness for the note's commitment, if known.
+    pub fn witness(&self) -> Option<&OrchardWitness> {
+        self.witness.as_ref()
+    }
+
+    /// Sets the witness for the note's commitment.
+    pub fn set_witness(&mut self, witness: Option<OrchardWitness>) {
+        self.witness = witness;
+    }
+
+    /// Returns the nullifier of the note, if known.
+    pub fn nullifier(&self) -> Option<&OrchardNullifier> {
+        self.nullifier.as_ref()
+    }
+
+    /// Sets the nullifier of the note.
+    pub fn set_nullifier(&mut self, nullifier: Option<OrchardNullifier>) {
+        self.nullifier = nullifier;
+    }
+
+    /// Returns the id of the transaction that spent the note, if known.
+    pub fn spent_in(&self) -> Option<TxId> {
+        self.spent_in
+    }
+
+    /// Sets the id of the transaction that spent the note.
+    pub fn set_spent_in(&mut self, spent_in: Option<TxId>) {
+        self.spent_in = spent_in;
+    }
+}
+
+impl From<OrchardReceivedNote> for Envelope {
+    fn from(value: OrchardReceivedNote) -> Self {
+        Envelope::new(value.index)
+            .add_type("OrchardReceivedNote")
+            .add_assertion("txid", value.txid)
+            .add_assertion("action_index", value.action_index)
+            .add_assertion("note", value.note)
+            .add_optional_assertion("witness", value.witness)
+            .add_optional_assertion("nullifier", value.nullifier)
+            .add_optional_assertion("spent_in", value.spent_in)
+    }
+}
+
+impl TryFrom<Envelope> for OrchardReceivedNote {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("OrchardReceivedNote")?;
+        let index = envelope.extract_subject()?;
+        let txid = envelope.extract_object_for_predicate("txid")?;
+        let action_index = envelope.extract_object_for_predicate("action_index")?;
+        let note = envelope.try_object_for_predicate("note")?;
+        let witness = envelope.try_optional_object_for_predicate("witness")?;
+        let nullifier = envelope.try_optional_object_for_predicate("nullifier")?;
+        let spent_in = envelope.extract_optional_object_for_predicate("spent_in")?;
+        Ok(Self {
+            index,
+            txid,
+            action_index,
+            note,
+            witness,
+            nullifier,
+            spent_in,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        TxId,
+        orchard::{Note, OrchardNullifier, OrchardWitness},
+        test_envelope_roundtrip,
+    };
+
+    use super::OrchardReceivedNote;
+
+    impl crate::RandomInstance for OrchardReceivedNote {
+        fn random() -> Self {
+            Self {
+                index: 0,
+                txid: TxId::random(),
+                action_index: u32::random(),
+                note: Note::random(),
+                witness: OrchardWitness::opt_random(),
+                nullifier: OrchardNullifier::opt_random(),
+                spent_in: TxId::opt_random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(OrchardReceivedNote);
+}
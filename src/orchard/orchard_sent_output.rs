@@ -52,6 +52,7 @@ use crate::{Amount, Indexed, Memo};
 /// # }
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct OrchardSentOutput {
     /// The index of the sent output in the transaction.
     index: usize,
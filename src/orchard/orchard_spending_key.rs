@@ -0,0 +1,25 @@
+use crate::{blob, blob_envelope};
+
+blob!(
+    OrchardSpendingKey,
+    32,
+    r#"An Orchard spending key (`sk`).
+
+The Orchard spending key is the root of all Orchard key material for an account:
+the spend authorizing key, nullifier deriving key, and full and incoming viewing
+keys are all derived from it. Unlike Sapling, Orchard has no separate expanded
+spending key encoding.
+
+# Data Preservation
+The spending key must be preserved exactly for every account holding Orchard
+funds, since without it those funds cannot be spent.
+
+# Examples
+```
+# use zewif::orchard::OrchardSpendingKey;
+let key = OrchardSpendingKey::new([0u8; 32]);
+```"#,
+    secret
+);
+
+blob_envelope!(OrchardSpendingKey);
@@ -0,0 +1,77 @@
+use bc_envelope::prelude::*;
+
+use crate::TreeState;
+
+use super::{MerkleHashOrchard, ORCHARD_COMMITMENT_TREE_DEPTH};
+
+/// The state of the Orchard note commitment tree as of a particular block height.
+///
+/// `OrchardTreeState` holds the frontier of the Orchard note commitment tree,
+/// allowing the receiving wallet to continue updating Orchard witnesses from the
+/// recorded height instead of rebuilding the tree from Orchard activation.
+///
+/// # Data Preservation
+/// The tree state recorded at export time should be preserved alongside the
+/// wallet's witnesses, as both must refer to the same chain state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct OrchardTreeState(TreeState<ORCHARD_COMMITMENT_TREE_DEPTH, MerkleHashOrchard>);
+
+impl OrchardTreeState {
+    /// Creates a new Orchard tree state from a tree state over the Orchard note
+    /// commitment tree.
+    pub fn new(state: TreeState<ORCHARD_COMMITMENT_TREE_DEPTH, MerkleHashOrchard>) -> Self {
+        Self(state)
+    }
+
+    /// Returns the underlying tree state.
+    pub fn state(&self) -> &TreeState<ORCHARD_COMMITMENT_TREE_DEPTH, MerkleHashOrchard> {
+        &self.0
+    }
+
+    /// Checks that the tree state is structurally consistent with a depth-32
+    /// Orchard note commitment tree.
+    pub fn validate(&self) -> crate::Result<()> {
+        self.0.validate()
+    }
+}
+
+impl From<OrchardTreeState> for Envelope {
+    fn from(value: OrchardTreeState) -> Self {
+        Envelope::new(value.0.height())
+            .add_type("OrchardTreeState")
+            .add_assertion("tree_size", value.0.tree_size())
+            .add_assertion("frontier", value.0.frontier().to_vec())
+    }
+}
+
+impl TryFrom<Envelope> for OrchardTreeState {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("OrchardTreeState")?;
+        let height = envelope.extract_subject()?;
+        let tree_size = envelope.extract_object_for_predicate("tree_size")?;
+        let frontier = envelope.extract_object_for_predicate("frontier")?;
+        Ok(Self(TreeState::from_parts(height, tree_size, frontier)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RandomInstance, TreeState, test_envelope_roundtrip};
+
+    use super::OrchardTreeState;
+
+    impl RandomInstance for OrchardTreeState {
+        fn random() -> Self {
+            Self(TreeState::random())
+        }
+    }
+
+    test_envelope_roundtrip!(OrchardTreeState);
+}
@@ -3,7 +3,7 @@ use bc_envelope::prelude::*;
 use crate::{IncrementalWitness, blob, blob_envelope};
 
 /// The depth of the Zcash Orchard note commitment tree.
-const ORCHARD_COMMITMENT_TREE_DEPTH: usize = 32;
+pub const ORCHARD_COMMITMENT_TREE_DEPTH: usize = 32;
 
 blob!(
     MerkleHashOrchard,
@@ -44,10 +44,32 @@ blob_envelope!(MerkleHashOrchard);
 /// Without this witness data, unspent notes cannot be spent as it would be impossible
 /// to prove their inclusion in the note commitment tree.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct OrchardWitness(
     IncrementalWitness<ORCHARD_COMMITMENT_TREE_DEPTH, MerkleHashOrchard>,
 );
 
+impl OrchardWitness {
+    /// Creates a new Orchard witness from an incremental witness over the
+    /// Orchard note commitment tree.
+    pub fn new(
+        witness: IncrementalWitness<ORCHARD_COMMITMENT_TREE_DEPTH, MerkleHashOrchard>,
+    ) -> Self {
+        Self(witness)
+    }
+
+    /// Returns the underlying incremental witness.
+    pub fn witness(&self) -> &IncrementalWitness<ORCHARD_COMMITMENT_TREE_DEPTH, MerkleHashOrchard> {
+        &self.0
+    }
+
+    /// Checks that the witness is structurally consistent with a depth-32
+    /// Orchard note commitment tree.
+    pub fn validate(&self) -> crate::Result<()> {
+        self.0.validate()
+    }
+}
+
 impl From<OrchardWitness> for Envelope {
     fn from(value: OrchardWitness) -> Self {
         Envelope::new(*value.0.note_commitment())
@@ -0,0 +1,292 @@
+use bc_envelope::prelude::*;
+
+use crate::{
+    Address, Data, orchard::OrchardSpendingKey, sapling::SaplingExtendedSpendingKey, sprout,
+    transparent::TransparentSpendingKey, watch_only::optional_spending_object,
+};
+
+/// A record read from the source wallet that the importer could not attribute
+/// to any account; see [`OrphanedData`].
+///
+/// Spending keys are encoded under the `spending_key` predicate, so they are
+/// elided from a watch-only export like those held by accounts, and decode as
+/// [`OrphanedRecord::Redacted`]. So is the value of a raw record.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum OrphanedRecord {
+    TransparentSpendingKey(TransparentSpendingKey),
+    SaplingSpendingKey(SaplingExtendedSpendingKey),
+    OrchardSpendingKey(OrchardSpendingKey),
+    SproutSpendingKey(sprout::SpendingKey),
+
+    /// An address, such as an address book entry, that belongs to no known
+    /// account.
+    Address(Address),
+
+    /// A record of no known type, kept as the key and value under which the
+    /// source wallet stored it.
+    ///
+    /// Raw records are not inspected, and the values of wallet records such
+    /// as zcashd's `key`, `ckey`, and `hdseed` are private keys and seeds,
+    /// so every raw value is treated as secret. It is encoded under the
+    /// `raw_value` predicate, and is elided from a watch-only export, from
+    /// decoding diagnostics, and from redacted JSON like a spending key.
+    Raw {
+        key: Data,
+        #[cfg_attr(feature = "json", serde(rename = "raw_value"))]
+        value: Data,
+        description: String,
+    },
+
+    /// A spending key elided from a watch-only export; see
+    /// [`Zewif::to_watch_only_envelope`](crate::Zewif::to_watch_only_envelope)
+    Redacted,
+}
+
+impl OrphanedRecord {
+    /// Returns whether the record is a spending key, which can spend funds
+    /// even though no account claims it.
+    pub fn is_spending_key(&self) -> bool {
+        matches!(
+            self,
+            Self::TransparentSpendingKey(_)
+                | Self::SaplingSpendingKey(_)
+                | Self::OrchardSpendingKey(_)
+                | Self::SproutSpendingKey(_)
+        )
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::TransparentSpendingKey(_) => "TransparentSpendingKey",
+            Self::SaplingSpendingKey(_) => "SaplingSpendingKey",
+            Self::OrchardSpendingKey(_) => "OrchardSpendingKey",
+            Self::SproutSpendingKey(_) => "SproutSpendingKey",
+            Self::Address(_) => "Address",
+            Self::Raw { .. } => "Raw",
+            Self::Redacted => "Redacted",
+        }
+    }
+}
+
+impl From<OrphanedRecord> for Envelope {
+    fn from(value: OrphanedRecord) -> Self {
+        let e = Envelope::new(value.kind()).add_type("OrphanedRecord");
+        match value {
+            OrphanedRecord::TransparentSpendingKey(key) => e.add_assertion("spending_key", key),
+            OrphanedRecord::SaplingSpendingKey(key) => e.add_assertion("spending_key", key),
+            OrphanedRecord::OrchardSpendingKey(key) => e.add_assertion("spending_key", key),
+            OrphanedRecord::SproutSpendingKey(key) => e.add_assertion("spending_key", key),
+            OrphanedRecord::Address(address) => e.add_assertion("address", address),
+            OrphanedRecord::Raw {
+                key,
+                value,
+                description,
+            } => e
+                .add_assertion("key", key)
+                .add_assertion("raw_value", value)
+                .add_assertion("description", description),
+            OrphanedRecord::Redacted => e,
+        }
+    }
+}
+
+impl TryFrom<Envelope> for OrphanedRecord {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("OrphanedRecord")?;
+        let kind = envelope.extract_subject::<String>()?;
+        let record = match kind.as_str() {
+            "TransparentSpendingKey" => optional_spending_object(&envelope, "spending_key")?
+                .map(OrphanedRecord::TransparentSpendingKey),
+            "SaplingSpendingKey" => optional_spending_object(&envelope, "spending_key")?
+                .map(OrphanedRecord::SaplingSpendingKey),
+            "OrchardSpendingKey" => optional_spending_object(&envelope, "spending_key")?
+                .map(OrphanedRecord::OrchardSpendingKey),
+            "SproutSpendingKey" => optional_spending_object(&envelope, "spending_key")?
+                .map(OrphanedRecord::SproutSpendingKey),
+            "Address" => Some(OrphanedRecord::Address(
+                envelope.try_object_for_predicate("address")?,
+            )),
+            "Raw" => {
+                let key = envelope.try_object_for_predicate("key")?;
+                let description = envelope.extract_object_for_predicate("description")?;
+                optional_spending_object(&envelope, "raw_value")?.map(|value| OrphanedRecord::Raw {
+                    key,
+                    value,
+                    description,
+                })
+            }
+            "Redacted" => None,
+            other => {
+                return Err(bc_envelope::Error::General(format!(
+                    "invalid orphaned record: {}",
+                    other
+                )));
+            }
+        };
+        Ok(record.unwrap_or(OrphanedRecord::Redacted))
+    }
+}
+
+/// The records of a wallet that the importer could parse but could not
+/// attribute to any account, such as stray keys, address book entries for
+/// unknown addresses, and fragments of corrupted records.
+///
+/// Importers should place here everything they read but could not place
+/// elsewhere, rather than dropping it, so that a receiving wallet or a user
+/// can still recover it. [`Zewif::validate`](crate::Zewif::validate) warns of
+/// orphaned spending keys.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Data, OrphanedData, OrphanedRecord, transparent::TransparentSpendingKey};
+/// let mut orphaned = OrphanedData::new();
+/// orphaned.add(OrphanedRecord::TransparentSpendingKey(TransparentSpendingKey::new([1; 32])));
+/// orphaned.add(OrphanedRecord::Raw {
+///     key: Data::from_slice(b"\x04pool"),
+///     value: Data::from_slice(&[0xff; 8]),
+///     description: "key pool entry with a truncated value".to_string(),
+/// });
+/// assert_eq!(orphaned.len(), 2);
+/// assert_eq!(orphaned.spending_keys().count(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrphanedData(Vec<OrphanedRecord>);
+
+impl OrphanedData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn records(&self) -> &[OrphanedRecord] {
+        &self.0
+    }
+
+    pub fn add(&mut self, record: OrphanedRecord) {
+        self.0.push(record);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the records that are spending keys.
+    pub fn spending_keys(&self) -> impl Iterator<Item = &OrphanedRecord> {
+        self.0.iter().filter(|record| record.is_spending_key())
+    }
+}
+
+/// Each record is an assertion carrying its position, so that the records
+/// keep their order.
+impl From<OrphanedData> for Envelope {
+    fn from(value: OrphanedData) -> Self {
+        value.0.into_iter().enumerate().fold(
+            Envelope::new("OrphanedData").add_type("OrphanedData"),
+            |e, (index, record)| {
+                e.add_assertion(
+                    "record",
+                    Envelope::from(record).add_assertion("index", index),
+                )
+            },
+        )
+    }
+}
+
+impl TryFrom<Envelope> for OrphanedData {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("OrphanedData")?;
+        let mut records = envelope
+            .objects_for_predicate("record")
+            .into_iter()
+            .map(|record| {
+                Ok((
+                    record.extract_object_for_predicate::<usize>("index")?,
+                    OrphanedRecord::try_from(record)?,
+                ))
+            })
+            .collect::<bc_envelope::Result<Vec<_>>>()?;
+        records.sort_by_key(|(index, _)| *index);
+        Ok(Self(
+            records.into_iter().map(|(_, record)| record).collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Address, Data, RandomInstance,
+        orchard::OrchardSpendingKey,
+        sapling::SaplingExtendedSpendingKey,
+        sprout, test_envelope_roundtrip,
+        transparent::TransparentSpendingKey,
+        watch_only::{SECRET_PREDICATES, elide_objects_of},
+    };
+
+    use super::{OrphanedData, OrphanedRecord};
+
+    impl RandomInstance for OrphanedRecord {
+        fn random() -> Self {
+            match rand::random_range(0..7) {
+                0 => Self::TransparentSpendingKey(TransparentSpendingKey::random()),
+                1 => Self::SaplingSpendingKey(SaplingExtendedSpendingKey::random()),
+                2 => Self::OrchardSpendingKey(OrchardSpendingKey::random()),
+                3 => Self::SproutSpendingKey(sprout::SpendingKey::random()),
+                4 => Self::Address(Address::random()),
+                5 => Self::Raw {
+                    key: Data::random(),
+                    value: Data::random(),
+                    description: String::random(),
+                },
+                _ => Self::Redacted,
+            }
+        }
+    }
+
+    impl RandomInstance for OrphanedData {
+        fn random() -> Self {
+            Self(Vec::random())
+        }
+    }
+
+    test_envelope_roundtrip!(OrphanedData);
+
+    #[test]
+    fn test_spending_keys_elided() {
+        let mut orphaned = OrphanedData::new();
+        orphaned.add(OrphanedRecord::OrchardSpendingKey(OrchardSpendingKey::new(
+            [7; 32],
+        )));
+        let raw = OrphanedRecord::Raw {
+            key: Data::from_slice(b"key"),
+            value: Data::from_slice(b"value"),
+            description: "unknown record".to_string(),
+        };
+        orphaned.add(raw.clone());
+        assert!(orphaned.records()[0].is_spending_key());
+
+        let envelope = elide_objects_of(&Envelope::from(orphaned.clone()), &["spending_key"]);
+        let decoded = OrphanedData::try_from(envelope).unwrap();
+        assert_eq!(decoded.records(), [OrphanedRecord::Redacted, raw]);
+        assert_eq!(decoded.spending_keys().count(), 0);
+
+        // Raw values may hold private keys, so they are secrets too.
+        let envelope = elide_objects_of(&Envelope::from(orphaned), SECRET_PREDICATES);
+        let decoded = OrphanedData::try_from(envelope).unwrap();
+        assert_eq!(
+            decoded.records(),
+            [OrphanedRecord::Redacted, OrphanedRecord::Redacted]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+
+use bc_envelope::prelude::*;
+
+use crate::TxId;
+
+/// A reference to a transparent output: the ID of the transaction that
+/// created it, and the output's index within that transaction.
+///
+/// Transparent inputs spend the outputs they reference; see
+/// [`InputValueResolver`](crate::InputValueResolver).
+///
+/// # Examples
+/// ```
+/// # use zewif::{OutPoint, TxId};
+/// let outpoint = OutPoint::new(TxId::from_bytes([0; 32]), 1);
+/// assert_eq!(outpoint.index(), 1);
+/// assert!(outpoint.to_string().ends_with(":1"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutPoint {
+    txid: TxId,
+    index: u32,
+}
+
+impl OutPoint {
+    pub fn new(txid: TxId, index: u32) -> Self {
+        Self { txid, index }
+    }
+
+    pub fn txid(&self) -> TxId {
+        self.txid
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns whether this is the null outpoint referenced by the input of a
+    /// coinbase transaction, which spends no output.
+    pub fn is_null(&self) -> bool {
+        self.txid == TxId::from_bytes([0; 32]) && self.index == u32::MAX
+    }
+}
+
+impl fmt::Display for OutPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.txid, self.index)
+    }
+}
+
+impl From<OutPoint> for CBOR {
+    fn from(value: OutPoint) -> Self {
+        let mut map = Map::new();
+        map.insert("txid", value.txid);
+        map.insert("index", value.index);
+        map.into()
+    }
+}
+
+impl TryFrom<CBOR> for OutPoint {
+    type Error = dcbor::Error;
+
+    fn try_from(value: CBOR) -> dcbor::Result<Self> {
+        if let CBORCase::Map(map) = value.into_case() {
+            let txid: TxId = map.extract("txid")?;
+            let index: u32 = map.extract("index")?;
+            Ok(OutPoint { txid, index })
+        } else {
+            Err("Expected a CBOR map".into())
+        }
+    }
+}
+
+impl From<OutPoint> for Envelope {
+    fn from(value: OutPoint) -> Self {
+        Envelope::new(CBOR::from(value)).add_type("OutPoint")
+    }
+}
+
+impl TryFrom<Envelope> for OutPoint {
+    type Error = bc_envelope::Error;
+
+    fn try_from(value: Envelope) -> bc_envelope::Result<Self> {
+        value.check_type("OutPoint")?;
+        value.extract_subject()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TxId, test_envelope_roundtrip};
+
+    use super::OutPoint;
+
+    impl crate::RandomInstance for OutPoint {
+        fn random() -> Self {
+            Self {
+                txid: TxId::random(),
+                index: u32::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(OutPoint);
+}
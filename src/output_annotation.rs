@@ -0,0 +1,153 @@
+use bc_envelope::prelude::*;
+
+use crate::{Memo, Pool};
+
+/// What a wallet records about an output of one of its transactions that is
+/// not on chain: the decrypted memo, and the recipient as the user entered
+/// it.
+///
+/// # Zcash Concept Relation
+/// The memo of a shielded output is encrypted on chain, so a receiving wallet
+/// can only read it again if it holds a key that decrypts the output. zcashd
+/// also records, for each payment made by `z_sendmany`, the address the user
+/// entered (`SendManyRecipient`), and for transparent payments the labels of
+/// `vOrderForm`. Neither is recoverable from the chain.
+///
+/// An output is identified by its [`Pool`] and its index within that pool's
+/// component of the transaction: the index of the transparent output, of the
+/// Sapling output, or of the Orchard action. Sprout outputs are indexed by
+/// their JoinSplit, as twice the JoinSplit's index plus the output's index
+/// within it.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Memo, OutputAnnotation, Pool};
+/// let mut annotation = OutputAnnotation::new(Pool::Sapling, 1);
+/// annotation.set_memo(Memo::from_text("Rent for May"));
+/// annotation.set_recipient(Some("zs1landlord".to_string()));
+/// assert_eq!(annotation.key(), (Pool::Sapling, 1));
+/// assert_eq!(annotation.memo().and_then(Memo::text).as_deref(), Some("Rent for May"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutputAnnotation {
+    pool: Pool,
+    index: u32,
+    /// The decrypted memo, if known.
+    memo: Option<Memo>,
+    /// The recipient's address, as the user entered it.
+    recipient: Option<String>,
+    /// The user's label for the recipient.
+    label: Option<String>,
+}
+
+impl OutputAnnotation {
+    pub fn new(pool: Pool, index: u32) -> Self {
+        Self {
+            pool,
+            index,
+            memo: None,
+            recipient: None,
+            label: None,
+        }
+    }
+
+    pub fn pool(&self) -> Pool {
+        self.pool
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the pool and index identifying the output within its
+    /// transaction.
+    pub fn key(&self) -> (Pool, u32) {
+        (self.pool, self.index)
+    }
+
+    pub fn memo(&self) -> Option<&Memo> {
+        self.memo.as_ref()
+    }
+
+    pub fn set_memo(&mut self, memo: Option<Memo>) {
+        self.memo = memo;
+    }
+
+    pub fn recipient(&self) -> Option<&str> {
+        self.recipient.as_deref()
+    }
+
+    pub fn set_recipient(&mut self, recipient: Option<String>) {
+        self.recipient = recipient;
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+}
+
+impl From<OutputAnnotation> for Envelope {
+    fn from(value: OutputAnnotation) -> Self {
+        Envelope::new(value.index)
+            .add_type("OutputAnnotation")
+            .add_assertion("pool", value.pool)
+            .add_optional_assertion("memo", value.memo)
+            .add_optional_assertion("recipient", value.recipient)
+            .add_optional_assertion("label", value.label)
+    }
+}
+
+impl TryFrom<Envelope> for OutputAnnotation {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("OutputAnnotation")?;
+        Ok(Self {
+            index: envelope.extract_subject()?,
+            pool: envelope.extract_object_for_predicate("pool")?,
+            memo: envelope.extract_optional_object_for_predicate("memo")?,
+            recipient: envelope.extract_optional_object_for_predicate("recipient")?,
+            label: envelope.extract_optional_object_for_predicate("label")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Memo, Pool, test_envelope_roundtrip};
+
+    use super::OutputAnnotation;
+
+    impl crate::RandomInstance for OutputAnnotation {
+        fn random() -> Self {
+            Self {
+                pool: Pool::random(),
+                index: u32::random(),
+                memo: Memo::opt_random(),
+                recipient: String::opt_random(),
+                label: String::opt_random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(OutputAnnotation);
+
+    #[test]
+    fn test_non_ascii_memo_roundtrip() {
+        let mut annotation = OutputAnnotation::new(Pool::Orchard, 0);
+        annotation.set_memo(Memo::from_text("Grüße aus Zürich — 🎉"));
+        annotation.set_label(Some("Åsa".to_string()));
+        let decoded =
+            OutputAnnotation::try_from(bc_envelope::Envelope::from(annotation.clone())).unwrap();
+        assert_eq!(decoded, annotation);
+        assert_eq!(
+            decoded.memo().and_then(Memo::text).as_deref(),
+            Some("Grüße aus Zürich — 🎉")
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+use bc_envelope::prelude::*;
+
+/// One of the value pools of the Zcash chain, each with its own kind of
+/// output.
+///
+/// Outputs are identified within a transaction by their pool and their index
+/// in that pool's component; see [`OutputAnnotation`](crate::OutputAnnotation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pool {
+    Transparent,
+    Sprout,
+    Sapling,
+    Orchard,
+}
+
+impl fmt::Display for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Pool::Transparent => "transparent",
+            Pool::Sprout => "sprout",
+            Pool::Sapling => "sapling",
+            Pool::Orchard => "orchard",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Encodes the pool as a small integer: 0 for transparent, 1 for Sprout, 2 for
+/// Sapling, and 3 for Orchard.
+impl From<Pool> for CBOR {
+    fn from(value: Pool) -> Self {
+        let code: u8 = match value {
+            Pool::Transparent => 0,
+            Pool::Sprout => 1,
+            Pool::Sapling => 2,
+            Pool::Orchard => 3,
+        };
+        code.into()
+    }
+}
+
+impl TryFrom<CBOR> for Pool {
+    type Error = dcbor::Error;
+
+    fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
+        match cbor.into_case() {
+            CBORCase::Unsigned(0) => Ok(Pool::Transparent),
+            CBORCase::Unsigned(1) => Ok(Pool::Sprout),
+            CBORCase::Unsigned(2) => Ok(Pool::Sapling),
+            CBORCase::Unsigned(3) => Ok(Pool::Orchard),
+            CBORCase::Unsigned(n) => Err(format!("invalid pool: {}", n).into()),
+            _ => Err(dcbor::Error::WrongType),
+        }
+    }
+}
+
+impl From<Pool> for Envelope {
+    fn from(value: Pool) -> Self {
+        Envelope::new(CBOR::from(value))
+    }
+}
+
+impl TryFrom<Envelope> for Pool {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.extract_subject()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cbor_roundtrip, test_envelope_roundtrip};
+
+    use super::Pool;
+
+    impl crate::RandomInstance for Pool {
+        fn random() -> Self {
+            match rand::random_range(0..4) {
+                0 => Pool::Transparent,
+                1 => Pool::Sprout,
+                2 => Pool::Sapling,
+                _ => Pool::Orchard,
+            }
+        }
+    }
+
+    test_cbor_roundtrip!(Pool);
+    test_envelope_roundtrip!(Pool);
+}
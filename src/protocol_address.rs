@@ -1,9 +1,12 @@
 use crate::{
-    UnifiedAddress,
+    Network, NetworkParameters, UnifiedAddress, base58check,
     error::Error,
-    sapling, transparent,
+    sapling, sprout, transparent,
 };
 use bc_envelope::prelude::*;
+use bech32::{Bech32, Bech32m, primitives::decode::CheckedHrpstring};
+
+const NETWORKS: [Network; 3] = [Network::Main, Network::Test, Network::Regtest];
 
 /// A protocol-specific Zcash address representation without additional metadata.
 ///
@@ -57,10 +60,14 @@ use bc_envelope::prelude::*;
 /// assert!(u_protocol.as_string().starts_with("u1"));
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum ProtocolAddress {
     /// An exposed transparent (T-address) similar to Bitcoin's.
     Transparent(transparent::Address),
 
+    /// A Sprout address (zc-address) from the original shielded pool.
+    Sprout(Box<sprout::Address>),
+
     /// A Sapling address (Z-address).
     Sapling(Box<sapling::Address>),
 
@@ -95,11 +102,36 @@ impl ProtocolAddress {
     pub fn as_string(&self) -> String {
         match self {
             ProtocolAddress::Transparent(addr) => addr.address().to_string(),
+            ProtocolAddress::Sprout(addr) => addr.address().to_string(),
             ProtocolAddress::Sapling(addr) => addr.address().to_string(),
             ProtocolAddress::Unified(addr) => addr.address().to_string(),
         }
     }
 
+    /// Returns true if this is a Sprout address.
+    ///
+    /// # Returns
+    /// `true` if the address is a Sprout address (zc-address), `false` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Blob, ProtocolAddress, sprout, transparent};
+    /// #
+    /// // Create a Sprout address
+    /// let z_addr =
+    ///     sprout::Address::new("zcexample".to_string(), Blob::default(), Blob::default());
+    /// let address = ProtocolAddress::Sprout(Box::new(z_addr));
+    /// assert!(address.is_sprout());
+    ///
+    /// // Create a transparent address
+    /// let t_addr = transparent::Address::new("t1example");
+    /// let address = ProtocolAddress::Transparent(t_addr);
+    /// assert!(!address.is_sprout());
+    /// ```
+    pub fn is_sprout(&self) -> bool {
+        matches!(self, ProtocolAddress::Sprout(_))
+    }
+
     /// Returns true if this is a Sapling address.
     ///
     /// # Returns
@@ -168,12 +200,76 @@ impl ProtocolAddress {
     pub fn is_unified(&self) -> bool {
         matches!(self, ProtocolAddress::Unified(_))
     }
+
+    /// Returns the network for which the address is encoded, or `None` if
+    /// the encoding is not recognized.
+    ///
+    /// Testnet and regtest share their Base58Check prefixes, so transparent
+    /// and Sprout addresses for either are reported as [`Network::Test`];
+    /// see [`Network::shares_base58_encoding`].
+    pub fn network(&self) -> Option<Network> {
+        match self {
+            ProtocolAddress::Transparent(addr) => {
+                let data = base58check::decode(addr.address()).ok()?;
+                let prefix = data.get(..2).filter(|_| data.len() == 2 + 20)?;
+                NETWORKS.into_iter().find(|network| {
+                    prefix == network.p2pkh_prefix() || prefix == network.p2sh_prefix()
+                })
+            }
+            ProtocolAddress::Sprout(addr) => {
+                let data = base58check::decode(addr.address()).ok()?;
+                let prefix = data.get(..2).filter(|_| data.len() == 2 + 64)?;
+                NETWORKS
+                    .into_iter()
+                    .find(|network| prefix == network.sprout_address_prefix())
+            }
+            ProtocolAddress::Sapling(addr) => {
+                let checked = CheckedHrpstring::new::<Bech32>(addr.address()).ok()?;
+                NETWORKS
+                    .into_iter()
+                    .find(|network| checked.hrp().as_str() == network.sapling_hrp())
+            }
+            ProtocolAddress::Unified(addr) => {
+                let checked = CheckedHrpstring::new::<Bech32m>(addr.address()).ok()?;
+                NETWORKS
+                    .into_iter()
+                    .find(|network| checked.hrp().as_str() == network.unified_hrp())
+            }
+        }
+    }
+
+    /// Like [`ProtocolAddress::network`], but reports addresses encoded with
+    /// the prefixes or human-readable parts of `params`, which may be the
+    /// custom ones of a regtest deployment, as encoded for
+    /// [`NetworkParameters::network`]. Sprout addresses have no custom
+    /// prefixes.
+    pub fn network_with(&self, params: &NetworkParameters) -> Option<Network> {
+        let custom = match self {
+            ProtocolAddress::Transparent(addr) => {
+                base58check::decode(addr.address()).is_ok_and(|data| {
+                    data.len() == 2 + 20
+                        && (data[..2] == params.p2pkh_prefix() || data[..2] == params.p2sh_prefix())
+                })
+            }
+            ProtocolAddress::Sprout(_) => false,
+            ProtocolAddress::Sapling(addr) => CheckedHrpstring::new::<Bech32>(addr.address())
+                .is_ok_and(|checked| checked.hrp().as_str() == params.sapling_hrp()),
+            ProtocolAddress::Unified(addr) => CheckedHrpstring::new::<Bech32m>(addr.address())
+                .is_ok_and(|checked| checked.hrp().as_str() == params.unified_hrp()),
+        };
+        if custom {
+            Some(params.network())
+        } else {
+            self.network()
+        }
+    }
 }
 
 impl From<ProtocolAddress> for Envelope {
     fn from(value: ProtocolAddress) -> Self {
         match value {
             ProtocolAddress::Transparent(addr) => addr.into(),
+            ProtocolAddress::Sprout(addr) => (*addr).into(),
             ProtocolAddress::Sapling(addr) => (*addr).into(),
             ProtocolAddress::Unified(addr) => (*addr).into(),
         }
@@ -186,6 +282,8 @@ impl TryFrom<Envelope> for ProtocolAddress {
     fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
         if envelope.has_type("TransparentAddress") {
             Ok(ProtocolAddress::Transparent(envelope.try_into()?))
+        } else if envelope.has_type("SproutAddress") {
+            Ok(ProtocolAddress::Sprout(Box::new(envelope.try_into()?)))
         } else if envelope.has_type("SaplingAddress") {
             Ok(ProtocolAddress::Sapling(Box::new(envelope.try_into()?)))
         } else if envelope.has_type("UnifiedAddress") {
@@ -200,13 +298,13 @@ impl TryFrom<Envelope> for ProtocolAddress {
 mod tests {
     use super::ProtocolAddress;
     use crate::{
-        UnifiedAddress, sapling, test_envelope_roundtrip, transparent,
+        UnifiedAddress, sapling, sprout, test_envelope_roundtrip, transparent,
     };
 
     impl crate::RandomInstance for ProtocolAddress {
         fn random() -> Self {
-            let mut rng = rand::thread_rng();
-            let choice = rand::Rng::gen_range(&mut rng, 0..3);
+            let mut rng = rand::rng();
+            let choice = rand::Rng::random_range(&mut rng, 0..4);
             match choice {
                 0 => {
                     ProtocolAddress::Transparent(transparent::Address::random())
@@ -214,6 +312,9 @@ mod tests {
                 1 => ProtocolAddress::Sapling(Box::new(
                     sapling::Address::random(),
                 )),
+                2 => ProtocolAddress::Sprout(Box::new(
+                    sprout::Address::random(),
+                )),
                 _ => {
                     ProtocolAddress::Unified(Box::new(UnifiedAddress::random()))
                 }
@@ -222,4 +323,70 @@ mod tests {
     }
 
     test_envelope_roundtrip!(ProtocolAddress);
+
+    #[test]
+    fn test_network() {
+        use bech32::{Bech32, Bech32m, Hrp};
+
+        use crate::{Network, base58check};
+
+        let transparent = |prefix: [u8; 2]| {
+            ProtocolAddress::Transparent(transparent::Address::new(base58check::encode(
+                &prefix, &[7u8; 20],
+            )))
+        };
+        assert_eq!(
+            transparent(Network::Main.p2pkh_prefix()).network(),
+            Some(Network::Main)
+        );
+        assert_eq!(
+            transparent(Network::Regtest.p2sh_prefix()).network(),
+            Some(Network::Test)
+        );
+        assert_eq!(transparent([0x12, 0x34]).network(), None);
+
+        let sapling = |hrp: &str| {
+            let address = bech32::encode::<Bech32>(Hrp::parse(hrp).unwrap(), &[7u8; 43]).unwrap();
+            ProtocolAddress::Sapling(Box::new(sapling::Address::new(address)))
+        };
+        assert_eq!(sapling("zs").network(), Some(Network::Main));
+        assert_eq!(sapling("zregtestsapling").network(), Some(Network::Regtest));
+
+        let unified = bech32::encode::<Bech32m>(Hrp::parse("utest").unwrap(), &[7u8; 48]).unwrap();
+        let unified = ProtocolAddress::Unified(Box::new(UnifiedAddress::new(unified)));
+        assert_eq!(unified.network(), Some(Network::Test));
+
+        let sprout = sprout::Address::new(String::new(), Default::default(), Default::default());
+        let sprout = sprout::Address::new(
+            sprout.to_string(Network::Test),
+            Default::default(),
+            Default::default(),
+        );
+        assert_eq!(
+            ProtocolAddress::Sprout(Box::new(sprout)).network(),
+            Some(Network::Test)
+        );
+
+        let mut params = crate::NetworkParameters::for_network(Network::Regtest);
+        params.set_p2pkh_prefix([0x12, 0x34]);
+        params.set_sapling_hrp("zcustom");
+        assert_eq!(
+            transparent([0x12, 0x34]).network_with(&params),
+            Some(Network::Regtest)
+        );
+        assert_eq!(
+            transparent(Network::Main.p2pkh_prefix()).network_with(&params),
+            Some(Network::Main)
+        );
+        assert_eq!(
+            sapling("zcustom").network_with(&params),
+            Some(Network::Regtest)
+        );
+        assert_eq!(sapling("zcustom").network(), None);
+
+        assert_eq!(
+            ProtocolAddress::Transparent(transparent::Address::new("t1example")).network(),
+            None
+        );
+    }
 }
@@ -0,0 +1,209 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bc_envelope::prelude::*;
+
+use crate::{Result, SecondsSinceEpoch};
+
+/// The origin of a signed Zewif: the tool that exported it and when; see
+/// [`Zewif::sign`](crate::Zewif::sign).
+///
+/// The information is carried as metadata of the signature, and so is covered
+/// by the signature along with the Zewif itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvenanceInfo {
+    tool_name: String,
+    tool_version: String,
+    exported_at: SecondsSinceEpoch,
+}
+
+impl ProvenanceInfo {
+    pub fn new(
+        tool_name: impl Into<String>,
+        tool_version: impl Into<String>,
+        exported_at: SecondsSinceEpoch,
+    ) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            tool_version: tool_version.into(),
+            exported_at,
+        }
+    }
+
+    /// Returns the provenance of an export made now by the given tool.
+    pub fn now(tool_name: impl Into<String>, tool_version: impl Into<String>) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self::new(tool_name, tool_version, SecondsSinceEpoch::from(now))
+    }
+
+    pub fn tool_name(&self) -> &str {
+        &self.tool_name
+    }
+
+    pub fn tool_version(&self) -> &str {
+        &self.tool_version
+    }
+
+    /// Returns the time at which the Zewif was exported and signed.
+    pub fn exported_at(&self) -> SecondsSinceEpoch {
+        self.exported_at
+    }
+
+    pub(crate) fn signature_metadata(&self) -> SignatureMetadata {
+        SignatureMetadata::new()
+            .with_assertion("tool_name", self.tool_name.clone())
+            .with_assertion("tool_version", self.tool_version.clone())
+            .with_assertion("exported_at", self.exported_at)
+    }
+
+    /// Reads the provenance from the metadata of a verified signature.
+    pub(crate) fn from_signature_metadata(metadata: &Envelope) -> Result<Self> {
+        Ok(Self {
+            tool_name: metadata.extract_object_for_predicate("tool_name")?,
+            tool_version: metadata.extract_object_for_predicate("tool_version")?,
+            exported_at: metadata.extract_object_for_predicate("exported_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::keypair;
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, BlockHeight, Compression, EncodeOptions, Error, FileKind, KdfParams, Network,
+        SecondsSinceEpoch, Zewif, ZewifEnvelope, ZewifWallet, detect,
+    };
+
+    use super::ProvenanceInfo;
+
+    fn zewif() -> Zewif {
+        let mut account = Account::new();
+        account.set_name("Savings");
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        zewif
+    }
+
+    fn provenance() -> ProvenanceInfo {
+        ProvenanceInfo::new(
+            "zmigrate",
+            "1.2.3",
+            SecondsSinceEpoch::from(1_700_000_000u64),
+        )
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let (private_keys, public_keys) = keypair();
+        let zewif = zewif();
+        let signed = zewif
+            .sign(&private_keys, provenance(), &EncodeOptions::new())
+            .unwrap();
+
+        assert_eq!(
+            Zewif::verify_signature(&signed, &public_keys).unwrap(),
+            provenance()
+        );
+        assert_eq!(
+            Zewif::from_signed_envelope(&signed, &public_keys).unwrap(),
+            (zewif.clone(), provenance())
+        );
+        assert!(ZewifEnvelope::new(signed.clone()).unwrap().is_signed());
+        assert_eq!(Zewif::try_from(signed).unwrap(), zewif);
+    }
+
+    #[test]
+    fn test_signed_exports() {
+        let (private_keys, public_keys) = keypair();
+        let zewif = zewif();
+        let path = std::env::temp_dir().join(format!("zewif-{}-signed", std::process::id()));
+
+        // Content compressed before signing.
+        for compression in [Compression::Whole, Compression::LargeLeavesOver(4)] {
+            let mut options = EncodeOptions::new();
+            options.set_compression(compression);
+            options.set_content_digest(true);
+            let signed = zewif.sign(&private_keys, provenance(), &options).unwrap();
+            assert_eq!(
+                Zewif::from_signed_envelope(&signed, &public_keys)
+                    .unwrap()
+                    .0,
+                zewif
+            );
+            Zewif::verify_integrity(&signed).unwrap();
+            std::fs::write(&path, signed.to_cbor_data()).unwrap();
+            assert_eq!(
+                detect(&std::fs::read(&path).unwrap()),
+                FileKind::CompressedEnvelope
+            );
+            assert_eq!(Zewif::load(&path).unwrap(), zewif);
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        // A signed envelope compressed or encrypted after signing, whose
+        // signature still verifies once it is uncompressed or decrypted.
+        let signed = zewif
+            .sign(&private_keys, provenance(), &EncodeOptions::new())
+            .unwrap();
+        let mut envelope = ZewifEnvelope::new(signed.clone()).unwrap();
+        envelope.compress().unwrap();
+        assert_eq!(Zewif::try_from(envelope.envelope().clone()).unwrap(), zewif);
+        envelope.uncompress().unwrap();
+        assert_eq!(envelope.envelope(), &signed);
+
+        let mut envelope = ZewifEnvelope::new(signed.clone()).unwrap();
+        envelope
+            .encrypt_with_password("password", KdfParams::new(8, 1, 1))
+            .unwrap();
+        assert!(!envelope.is_signed());
+        let encrypted = envelope.envelope().clone();
+        assert_eq!(
+            Zewif::decrypt_with_password(&encrypted, "password").unwrap(),
+            zewif
+        );
+        envelope.decrypt_with_password("password").unwrap();
+        assert!(envelope.is_signed());
+        assert_eq!(
+            Zewif::verify_signature(envelope.envelope(), &public_keys).unwrap(),
+            provenance()
+        );
+    }
+
+    #[test]
+    fn test_verify_signature_wrong_key() {
+        let (private_keys, _) = keypair();
+        let (_, other_public_keys) = keypair();
+        let signed = zewif()
+            .sign(&private_keys, provenance(), &EncodeOptions::new())
+            .unwrap();
+        assert!(matches!(
+            Zewif::verify_signature(&signed, &other_public_keys),
+            Err(Error::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_verify_signature_tampered_content() {
+        let (private_keys, public_keys) = keypair();
+        let mut data = zewif()
+            .sign(&private_keys, provenance(), &EncodeOptions::new())
+            .unwrap()
+            .to_cbor_data();
+        let position = data
+            .windows(7)
+            .position(|window| window == b"Savings")
+            .unwrap();
+        data[position] ^= 0x01;
+
+        let tampered = Envelope::try_from_cbor_data(data).unwrap();
+        assert!(matches!(
+            Zewif::verify_signature(&tampered, &public_keys),
+            Err(Error::InvalidSignature)
+        ));
+    }
+}
@@ -0,0 +1,567 @@
+//! Transaction IDs computed from consensus-serialized transactions.
+//!
+//! Transactions before version 5 are identified by the double SHA-256 of
+//! their encoding. Version 5 transactions are identified by the ZIP 244
+//! digest of their effecting data, which requires parsing the transaction to
+//! separate that data from proofs and signatures.
+//!
+//! The header fields, the transparent inputs and outputs of a transaction,
+//! and the value it moves out of the shielded pools, are read from the
+//! encodings of all versions, for computing fees and tracking transparent
+//! funds.
+
+use blake2b_simd::{Hash, Params, State};
+
+use crate::{
+    Amount, BlockHeight, BranchId, Data, Error, Indexed, OutPoint, Result, Script, TxId, TxIn,
+    TxOut, TxVersion,
+};
+
+/// The version group ID of version 5 transactions.
+const V5_VERSION_GROUP_ID: u32 = 0x26A7_270A;
+
+const SAPLING_SPEND_SIZE: usize = 96;
+const SAPLING_OUTPUT_SIZE: usize = 756;
+/// The size of a version 4 Sapling spend, with its proof and signature.
+const SAPLING_V4_SPEND_SIZE: usize = 384;
+/// The size of a version 4 Sapling output, with its proof.
+const SAPLING_V4_OUTPUT_SIZE: usize = 948;
+/// The size of a JoinSplit, other than its public values and proof.
+const JOINSPLIT_SIZE: usize = 1490;
+const PHGR_PROOF_SIZE: usize = 296;
+const GROTH_PROOF_SIZE: usize = 192;
+const SAPLING_PROOF_SIZE: usize = 192;
+const ORCHARD_ACTION_SIZE: usize = 820;
+const SIGNATURE_SIZE: usize = 64;
+const ENC_CIPHERTEXT_SIZE: usize = 580;
+/// The prefix of a note ciphertext holding everything but the memo.
+const COMPACT_NOTE_SIZE: usize = 52;
+/// The offset of the end of the memo within a note ciphertext.
+const MEMO_END: usize = 564;
+
+/// Returns the ID of the consensus-serialized transaction `raw`.
+pub(crate) fn txid(raw: &[u8]) -> Result<TxId> {
+    let mut reader = Reader::new(raw);
+    let header = reader.u32()?;
+    let overwintered = header >> 31 == 1;
+    let version = header & 0x7FFF_FFFF;
+    match (overwintered, version) {
+        (true, 5) => v5_txid(header, &mut reader),
+        (true, 6..) => Err(Error::UnsupportedTransactionVersion { version }),
+        _ => Ok(TxId::from_bytes(bc_crypto::hash::double_sha256(raw))),
+    }
+}
+
+/// The fields of a transaction's header that identify its encoding and the
+/// consensus rules it is valid under.
+pub(crate) struct HeaderFields {
+    pub(crate) version: TxVersion,
+    /// The consensus branch ID, which only version 5 transactions encode.
+    pub(crate) consensus_branch_id: Option<BranchId>,
+    /// The expiry height, if the transaction is overwintered and has one.
+    pub(crate) expiry_height: Option<BlockHeight>,
+}
+
+/// Returns the header fields of the consensus-serialized transaction `raw`.
+///
+/// Versions 3 and 4 place the expiry height after the transparent bundle,
+/// which is skipped to reach it; the rest of the transaction is not read.
+pub(crate) fn header_fields(raw: &[u8]) -> Result<HeaderFields> {
+    let mut reader = Reader::new(raw);
+    let header = reader.u32()?;
+    let overwintered = header >> 31 == 1;
+    let version_group_id = if overwintered {
+        Some(reader.u32()?)
+    } else {
+        None
+    };
+    let version = TxVersion::from_header(header, version_group_id)?;
+    let (consensus_branch_id, expiry_height) = match (overwintered, version.number()) {
+        (false, _) => (None, 0),
+        (true, 3 | 4) => {
+            for _ in 0..reader.compact_size()? {
+                reader.take(36)?;
+                reader.script()?;
+                reader.take(4)?;
+            }
+            for _ in 0..reader.compact_size()? {
+                reader.take(8)?;
+                reader.script()?;
+            }
+            // The lock time.
+            reader.take(4)?;
+            (None, reader.u32()?)
+        }
+        (true, 5) => {
+            let consensus_branch_id = reader.u32()?;
+            // The lock time.
+            reader.take(4)?;
+            (Some(BranchId::from(consensus_branch_id)), reader.u32()?)
+        }
+        (true, number) => return Err(Error::UnsupportedTransactionVersion { version: number }),
+    };
+    let expiry_height = (expiry_height != 0).then(|| BlockHeight::from(expiry_height));
+    Ok(HeaderFields {
+        version,
+        consensus_branch_id,
+        expiry_height,
+    })
+}
+
+/// The transparent inputs and outputs of a transaction, and the value it
+/// moves out of the shielded pools.
+pub(crate) struct ValueFlows {
+    /// The transparent inputs, indexed in order.
+    pub(crate) inputs: Vec<TxIn>,
+    /// The transparent outputs, indexed in order.
+    pub(crate) outputs: Vec<TxOut>,
+    /// The net value leaving the shielded pools, in zatoshis.
+    pub(crate) shielded_value_balance: i128,
+}
+
+/// Returns the values moved by the consensus-serialized transaction `raw`.
+pub(crate) fn value_flows(raw: &[u8]) -> Result<ValueFlows> {
+    let mut reader = Reader::new(raw);
+    let header = reader.u32()?;
+    let overwintered = header >> 31 == 1;
+    let version = header & 0x7FFF_FFFF;
+    if overwintered && !(3..=5).contains(&version) {
+        return Err(Error::UnsupportedTransactionVersion { version });
+    }
+    if overwintered {
+        // The version group ID, then for version 5 the consensus branch ID,
+        // lock time, and expiry height.
+        reader.take(if version == 5 { 16 } else { 4 })?;
+    }
+
+    let mut inputs = Vec::new();
+    for index in 0..reader.compact_size()? {
+        let txid = TxId::from_bytes(reader.take(32)?.try_into()?);
+        let prevout = OutPoint::new(txid, reader.u32()?);
+        let script_sig = reader.script()?;
+        let mut input = TxIn::new(prevout, script_sig, reader.u32()?);
+        input.set_index(index);
+        inputs.push(input);
+    }
+    let mut outputs = Vec::new();
+    for index in 0..reader.compact_size()? {
+        let value = reader.amount()?;
+        let mut output = TxOut::new(value, reader.script()?);
+        output.set_index(index);
+        outputs.push(output);
+    }
+
+    let shielded_value_balance = match (overwintered, version) {
+        (true, 5) => {
+            let spends_len = reader.compact_size()?;
+            reader.take_elements(spends_len, SAPLING_SPEND_SIZE)?;
+            let outputs_len = reader.compact_size()?;
+            reader.take_elements(outputs_len, SAPLING_OUTPUT_SIZE)?;
+            let mut balance = 0;
+            if spends_len + outputs_len > 0 {
+                balance += reader.value_balance()?;
+                reader.take(if spends_len > 0 { 32 } else { 0 })?;
+                reader.take_elements(spends_len, SAPLING_PROOF_SIZE + SIGNATURE_SIZE)?;
+                reader.take_elements(outputs_len, SAPLING_PROOF_SIZE)?;
+                reader.take(SIGNATURE_SIZE)?;
+            }
+            let actions_len = reader.compact_size()?;
+            reader.take_elements(actions_len, ORCHARD_ACTION_SIZE)?;
+            if actions_len > 0 {
+                reader.take(1)?;
+                balance += reader.value_balance()?;
+                reader.take(32)?;
+                let proofs_len = reader.compact_size()?;
+                reader.take(proofs_len)?;
+                reader.take_elements(actions_len + 1, SIGNATURE_SIZE)?;
+            }
+            balance
+        }
+        (true, 4) => {
+            // The lock time and expiry height.
+            reader.take(8)?;
+            let mut balance = reader.value_balance()?;
+            let spends_len = reader.compact_size()?;
+            reader.take_elements(spends_len, SAPLING_V4_SPEND_SIZE)?;
+            let outputs_len = reader.compact_size()?;
+            reader.take_elements(outputs_len, SAPLING_V4_OUTPUT_SIZE)?;
+            balance += joinsplits_value_balance(&mut reader, GROTH_PROOF_SIZE)?;
+            if spends_len + outputs_len > 0 {
+                reader.take(SIGNATURE_SIZE)?;
+            }
+            balance
+        }
+        (true, _) => {
+            // The lock time and expiry height.
+            reader.take(8)?;
+            joinsplits_value_balance(&mut reader, PHGR_PROOF_SIZE)?
+        }
+        (false, version) => {
+            // The lock time.
+            reader.take(4)?;
+            if version >= 2 {
+                joinsplits_value_balance(&mut reader, PHGR_PROOF_SIZE)?
+            } else {
+                0
+            }
+        }
+    };
+    if !reader.is_empty() {
+        return Err(invalid("trailing bytes after the transaction"));
+    }
+    Ok(ValueFlows {
+        inputs,
+        outputs,
+        shielded_value_balance,
+    })
+}
+
+/// Reads the JoinSplits of a transaction before version 5, returning the
+/// net value they move out of the Sprout pool.
+fn joinsplits_value_balance(reader: &mut Reader, proof_size: usize) -> Result<i128> {
+    let mut balance = 0;
+    let joinsplits_len = reader.compact_size()?;
+    for _ in 0..joinsplits_len {
+        let vpub_old = reader.amount()?;
+        let vpub_new = reader.amount()?;
+        balance += i128::from(i64::from(vpub_new)) - i128::from(i64::from(vpub_old));
+        reader.take(JOINSPLIT_SIZE + proof_size)?;
+    }
+    if joinsplits_len > 0 {
+        // The JoinSplit public key and signature.
+        reader.take(32 + SIGNATURE_SIZE)?;
+    }
+    Ok(balance)
+}
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidRawTransaction {
+        reason: reason.into(),
+    }
+}
+
+/// Implements the transaction ID digest of ZIP 244.
+fn v5_txid(header: u32, reader: &mut Reader) -> Result<TxId> {
+    let version_group_id = reader.u32()?;
+    if version_group_id != V5_VERSION_GROUP_ID {
+        return Err(invalid(format!(
+            "unexpected version group ID {:#010x} for a version 5 transaction",
+            version_group_id
+        )));
+    }
+    let consensus_branch_id = reader.u32()?;
+    let lock_time = reader.u32()?;
+    let expiry_height = reader.u32()?;
+    let mut h = hasher(b"ZTxIdHeadersHash");
+    for field in [
+        header,
+        version_group_id,
+        consensus_branch_id,
+        lock_time,
+        expiry_height,
+    ] {
+        h.update(&field.to_le_bytes());
+    }
+    let header_digest = h.finalize();
+
+    let transparent_digest = transparent_digest(reader)?;
+    let sapling_digest = sapling_digest(reader)?;
+    let orchard_digest = orchard_digest(reader)?;
+    if !reader.is_empty() {
+        return Err(invalid("trailing bytes after the transaction"));
+    }
+
+    let mut personal = *b"ZcashTxHash_\0\0\0\0";
+    personal[12..].copy_from_slice(&consensus_branch_id.to_le_bytes());
+    let mut h = hasher(&personal);
+    for digest in [
+        header_digest,
+        transparent_digest,
+        sapling_digest,
+        orchard_digest,
+    ] {
+        h.update(digest.as_bytes());
+    }
+    let mut txid = [0u8; 32];
+    txid.copy_from_slice(h.finalize().as_bytes());
+    Ok(TxId::from_bytes(txid))
+}
+
+fn transparent_digest(reader: &mut Reader) -> Result<Hash> {
+    let mut prevouts = hasher(b"ZTxIdPrevoutHash");
+    let mut sequences = hasher(b"ZTxIdSequencHash");
+    let mut outputs = hasher(b"ZTxIdOutputsHash");
+    let inputs_len = reader.compact_size()?;
+    for _ in 0..inputs_len {
+        prevouts.update(reader.take(36)?);
+        let script_len = reader.compact_size()?;
+        reader.take(script_len)?;
+        sequences.update(reader.take(4)?);
+    }
+    let outputs_len = reader.compact_size()?;
+    for _ in 0..outputs_len {
+        let start = reader.position();
+        reader.take(8)?;
+        let script_len = reader.compact_size()?;
+        reader.take(script_len)?;
+        outputs.update(reader.since(start));
+    }
+
+    let mut h = hasher(b"ZTxIdTranspaHash");
+    if inputs_len + outputs_len > 0 {
+        for digest in [
+            prevouts.finalize(),
+            sequences.finalize(),
+            outputs.finalize(),
+        ] {
+            h.update(digest.as_bytes());
+        }
+    }
+    Ok(h.finalize())
+}
+
+fn sapling_digest(reader: &mut Reader) -> Result<Hash> {
+    let spends_len = reader.compact_size()?;
+    let spends = reader.take_elements(spends_len, SAPLING_SPEND_SIZE)?;
+    let outputs_len = reader.compact_size()?;
+    let outputs = reader.take_elements(outputs_len, SAPLING_OUTPUT_SIZE)?;
+    let mut h = hasher(b"ZTxIdSaplingHash");
+    if spends_len + outputs_len == 0 {
+        return Ok(h.finalize());
+    }
+    let value_balance = reader.take(8)?;
+    let anchor = if spends_len > 0 {
+        reader.take(32)?
+    } else {
+        &[]
+    };
+    reader.take_elements(spends_len, SAPLING_PROOF_SIZE + SIGNATURE_SIZE)?;
+    reader.take_elements(outputs_len, SAPLING_PROOF_SIZE)?;
+    reader.take(SIGNATURE_SIZE)?;
+
+    // Each spend is cv, nullifier, and rk.
+    let mut spends_digest = hasher(b"ZTxIdSSpendsHash");
+    if spends_len > 0 {
+        let mut compact = hasher(b"ZTxIdSSpendCHash");
+        let mut noncompact = hasher(b"ZTxIdSSpendNHash");
+        for spend in spends.chunks(SAPLING_SPEND_SIZE) {
+            compact.update(&spend[32..64]);
+            noncompact.update(&spend[..32]);
+            noncompact.update(anchor);
+            noncompact.update(&spend[64..]);
+        }
+        spends_digest.update(compact.finalize().as_bytes());
+        spends_digest.update(noncompact.finalize().as_bytes());
+    }
+
+    // Each output is cv, cmu, ephemeral key, and the note and outgoing
+    // ciphertexts.
+    let mut outputs_digest = hasher(b"ZTxIdSOutputHash");
+    if outputs_len > 0 {
+        let mut compact = hasher(b"ZTxIdSOutC__Hash");
+        let mut memos = hasher(b"ZTxIdSOutM__Hash");
+        let mut noncompact = hasher(b"ZTxIdSOutN__Hash");
+        for output in outputs.chunks(SAPLING_OUTPUT_SIZE) {
+            let (cv, rest) = output.split_at(32);
+            let (cmu_and_epk, rest) = rest.split_at(64);
+            let (enc_ciphertext, out_ciphertext) = rest.split_at(ENC_CIPHERTEXT_SIZE);
+            compact.update(cmu_and_epk);
+            compact.update(&enc_ciphertext[..COMPACT_NOTE_SIZE]);
+            memos.update(&enc_ciphertext[COMPACT_NOTE_SIZE..MEMO_END]);
+            noncompact.update(cv);
+            noncompact.update(&enc_ciphertext[MEMO_END..]);
+            noncompact.update(out_ciphertext);
+        }
+        for digest in [compact.finalize(), memos.finalize(), noncompact.finalize()] {
+            outputs_digest.update(digest.as_bytes());
+        }
+    }
+
+    h.update(spends_digest.finalize().as_bytes());
+    h.update(outputs_digest.finalize().as_bytes());
+    h.update(value_balance);
+    Ok(h.finalize())
+}
+
+fn orchard_digest(reader: &mut Reader) -> Result<Hash> {
+    let actions_len = reader.compact_size()?;
+    let actions = reader.take_elements(actions_len, ORCHARD_ACTION_SIZE)?;
+    let mut h = hasher(b"ZTxIdOrchardHash");
+    if actions_len == 0 {
+        return Ok(h.finalize());
+    }
+    let flags_value_balance_and_anchor = reader.take(1 + 8 + 32)?;
+    let proofs_len = reader.compact_size()?;
+    reader.take(proofs_len)?;
+    reader.take_elements(actions_len, SIGNATURE_SIZE)?;
+    reader.take(SIGNATURE_SIZE)?;
+
+    // Each action is cv, nullifier, rk, cmx, ephemeral key, and the note and
+    // outgoing ciphertexts.
+    let mut compact = hasher(b"ZTxIdOrcActCHash");
+    let mut memos = hasher(b"ZTxIdOrcActMHash");
+    let mut noncompact = hasher(b"ZTxIdOrcActNHash");
+    for action in actions.chunks(ORCHARD_ACTION_SIZE) {
+        let (cv, rest) = action.split_at(32);
+        let (nullifier, rest) = rest.split_at(32);
+        let (rk, rest) = rest.split_at(32);
+        let (cmx_and_epk, rest) = rest.split_at(64);
+        let (enc_ciphertext, out_ciphertext) = rest.split_at(ENC_CIPHERTEXT_SIZE);
+        compact.update(nullifier);
+        compact.update(cmx_and_epk);
+        compact.update(&enc_ciphertext[..COMPACT_NOTE_SIZE]);
+        memos.update(&enc_ciphertext[COMPACT_NOTE_SIZE..MEMO_END]);
+        noncompact.update(cv);
+        noncompact.update(rk);
+        noncompact.update(&enc_ciphertext[MEMO_END..]);
+        noncompact.update(out_ciphertext);
+    }
+    for digest in [compact.finalize(), memos.finalize(), noncompact.finalize()] {
+        h.update(digest.as_bytes());
+    }
+    h.update(flags_value_balance_and_anchor);
+    Ok(h.finalize())
+}
+
+fn hasher(personal: &[u8; 16]) -> State {
+    Params::new().hash_length(32).personal(personal).to_state()
+}
+
+/// Reads the fields of a serialized transaction in order.
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position == self.data.len()
+    }
+
+    /// Returns the bytes read since `start`.
+    fn since(&self, start: usize) -> &'a [u8] {
+        &self.data[start..self.position]
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| invalid("truncated transaction"))?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// Reads `count` consecutive elements of `size` bytes each.
+    fn take_elements(&mut self, count: usize, size: usize) -> Result<&'a [u8]> {
+        let len = count
+            .checked_mul(size)
+            .ok_or_else(|| invalid("truncated transaction"))?;
+        self.take(len)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    /// Reads a non-negative value, such as that of a transparent output.
+    fn script(&mut self) -> Result<Script> {
+        let len = self.compact_size()?;
+        Ok(Script::from(Data::from_slice(self.take(len)?)))
+    }
+
+    fn amount(&mut self) -> Result<Amount> {
+        Amount::from_nonnegative_i64_le_bytes(self.take(8)?.try_into()?)
+    }
+
+    /// Reads the signed value balance of a shielded bundle.
+    fn value_balance(&mut self) -> Result<i128> {
+        Ok(i64::from(Amount::from_i64_le_bytes(self.take(8)?.try_into()?)?).into())
+    }
+
+    /// Reads a Bitcoin-style variable-length integer.
+    fn compact_size(&mut self) -> Result<usize> {
+        let size = match self.take(1)?[0] {
+            0xFD => u16::from_le_bytes(self.take(2)?.try_into()?) as u64,
+            0xFE => u32::from_le_bytes(self.take(4)?.try_into()?) as u64,
+            0xFF => u64::from_le_bytes(self.take(8)?.try_into()?),
+            size => size as u64,
+        };
+        usize::try_from(size).map_err(|_| invalid(format!("size {} is too large", size)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{txid, value_flows};
+    use crate::{Error, TxId};
+
+    fn fixture(hex_lines: &str) -> Vec<u8> {
+        hex::decode(hex_lines.split_whitespace().collect::<String>()).unwrap()
+    }
+
+    /// A Sapling version 4 transaction from testnet block 280003.
+    #[test]
+    fn test_v4_txid() {
+        let raw = fixture(include_str!("test_data/tx_v4_testnet.hex"));
+        let expected =
+            TxId::from_hex("64f0bd7fe30ce23753358fe3a2dc835b8fba9c0274c4e2c54a6f73114cb55639")
+                .unwrap();
+        assert_eq!(txid(&raw).unwrap(), expected);
+    }
+
+    /// A version 5 transaction with transparent, Sapling, and Orchard
+    /// components, from the ZIP 244 test vectors.
+    #[test]
+    fn test_v5_txid() {
+        let raw = fixture(include_str!("test_data/tx_v5_zip244.hex"));
+        let expected =
+            TxId::from_hex("d0854b7070bb168392e7cf3d3a558711b49c2c0ad8eca3a8a14b8333bd962c55")
+                .unwrap();
+        assert_eq!(txid(&raw).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_malformed_v5() {
+        let raw = fixture(include_str!("test_data/tx_v5_zip244.hex"));
+        for malformed in [&raw[..raw.len() - 1], &[raw.as_slice(), &[0]].concat()] {
+            assert!(matches!(
+                txid(malformed),
+                Err(Error::InvalidRawTransaction { .. })
+            ));
+        }
+        let mut v6 = raw.clone();
+        v6[0] = 6;
+        assert!(matches!(
+            txid(&v6),
+            Err(Error::UnsupportedTransactionVersion { version: 6 })
+        ));
+    }
+
+    /// Value flows are read to the end of each fixture, which checks the
+    /// sizes of every component skipped along the way.
+    #[test]
+    fn test_value_flows() {
+        for hex_lines in [
+            include_str!("test_data/tx_v4_testnet.hex"),
+            include_str!("test_data/tx_v5_zip244.hex"),
+        ] {
+            let raw = fixture(hex_lines);
+            assert!(value_flows(&raw).is_ok());
+            assert!(matches!(
+                value_flows(&raw[..raw.len() - 1]),
+                Err(Error::InvalidRawTransaction { .. })
+            ));
+        }
+    }
+}
@@ -1,8 +1,8 @@
-use super::{
-    SaplingExtendedFullViewingKey, SaplingExtendedSpendingKey,
-    SaplingIncomingViewingKey,
+use super::{SaplingExtendedFullViewingKey, SaplingExtendedSpendingKey, SaplingIncomingViewingKey};
+use crate::{
+    Blob, KeyOrigin, NoQuotesDebugOption, test_envelope_roundtrip,
+    watch_only::optional_spending_object,
 };
-use crate::{Blob, NoQuotesDebugOption, test_envelope_roundtrip};
 
 use bc_envelope::prelude::*;
 
@@ -49,6 +49,7 @@ use bc_envelope::prelude::*;
 /// address.set_hd_derivation_path("m/32'/1'/0'/0/5".to_string());
 /// ```
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Address {
     /// The string representaion of the address. This is used as a unique identifier within the
     /// wallet.
@@ -84,6 +85,9 @@ pub struct Address {
     /// The diversifier index used creating this address, if known, stored as a byte array in
     /// little-endian order.
     diversifier_index: Option<Blob<11>>,
+
+    /// Whether the spending key was derived from a seed or imported, if known.
+    key_origin: Option<KeyOrigin>,
 }
 
 impl std::fmt::Debug for Address {
@@ -97,6 +101,7 @@ impl std::fmt::Debug for Address {
             .field("spending_key", &self.spending_key)
             .field("diversifier_index", &self.diversifier_index)
             .field("hd_derivation_path", &self.hd_derivation_path)
+            .field("key_origin", &self.key_origin)
             .finish()
     }
 }
@@ -110,6 +115,7 @@ impl Address {
             spending_key: None,
             diversifier_index: None,
             hd_derivation_path: None,
+            key_origin: None,
         }
     }
 
@@ -178,6 +184,16 @@ impl Address {
     pub fn set_hd_derivation_path(&mut self, path: String) {
         self.hd_derivation_path = Some(path);
     }
+
+    /// Returns whether the address's spending key was derived from a seed or
+    /// imported, if known.
+    pub fn key_origin(&self) -> Option<&KeyOrigin> {
+        self.key_origin.as_ref()
+    }
+
+    pub fn set_key_origin(&mut self, key_origin: Option<KeyOrigin>) {
+        self.key_origin = key_origin;
+    }
 }
 
 impl From<Address> for Envelope {
@@ -198,6 +214,7 @@ impl From<Address> for Envelope {
                 "hd_derivation_path",
                 value.hd_derivation_path,
             )
+            .add_optional_assertion("key_origin", value.key_origin)
     }
 }
 
@@ -209,9 +226,10 @@ impl TryFrom<Envelope> for Address {
         let address = envelope.extract_subject()?;
         let incoming_viewing_key = envelope.try_optional_object_for_predicate("incoming_viewing_key")?;
         let full_viewing_key = envelope.try_optional_object_for_predicate("full_viewing_key")?;
-        let spending_key = envelope.try_optional_object_for_predicate("spending_key")?;
+        let spending_key = optional_spending_object(&envelope, "spending_key")?;
         let diversifier_index = envelope.try_optional_object_for_predicate("diversifier_index")?;
         let hd_derivation_path = envelope.try_optional_object_for_predicate("hd_derivation_path")?;
+        let key_origin = envelope.try_optional_object_for_predicate("key_origin")?;
         Ok(Address {
             address,
             incoming_viewing_key,
@@ -219,6 +237,7 @@ impl TryFrom<Envelope> for Address {
             spending_key,
             diversifier_index,
             hd_derivation_path,
+            key_origin,
         })
     }
 }
@@ -233,6 +252,7 @@ impl crate::RandomInstance for Address {
             spending_key: SaplingExtendedSpendingKey::opt_random(),
             diversifier_index: Blob::<11>::opt_random(),
             hd_derivation_path: String::opt_random(),
+            key_origin: KeyOrigin::opt_random(),
         }
     }
 }
@@ -24,6 +24,10 @@
 //!   in the tree
 //! - [`SaplingSentOutput`]: Sender's record of note data for outgoing
 //!   transactions
+//! - [`Note`]: The plaintext of a received Sapling note
+//! - [`SaplingReceivedNote`]: A received note with its witness and spend status
+//! - [`SaplingNullifier`]: The value revealed when a Sapling note is spent
+//! - [`SaplingSpendDescription`]: The public data of a spend in a transaction
 //!
 //! ## Protocol Characteristics
 //!
@@ -45,8 +49,15 @@
 use crate::mod_use;
 
 mod_use!(address);
+mod_use!(note);
+mod_use!(sapling_anchor_registry);
+mod_use!(sapling_diversifiable_full_viewing_key);
 mod_use!(sapling_extended_spending_key);
 mod_use!(sapling_extended_full_viewing_key);
 mod_use!(sapling_incoming_viewing_key);
+mod_use!(sapling_nullifier);
+mod_use!(sapling_received_note);
 mod_use!(sapling_sent_output);
+mod_use!(sapling_spend_description);
+mod_use!(sapling_tree_state);
 mod_use!(sapling_witness);
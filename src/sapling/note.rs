@@ -0,0 +1,141 @@
+use bc_envelope::prelude::*;
+
+use crate::{Amount, Blob, Blob32, Error, Result};
+
+/// The plaintext of a Sapling note received by the wallet.
+///
+/// A Sapling note is the unit of value in the Sapling shielded pool. Only its
+/// commitment (`cmu`) appears on chain; a wallet that has decrypted the note
+/// holds the plaintext components needed to recompute the commitment, derive
+/// the note's nullifier, and spend it.
+///
+/// # Zcash Concept Relation
+/// As specified in the Zcash protocol specification (§3.2), a Sapling note is
+/// the tuple `(d, pk_d, v, rcm)`, where after ZIP-212 `rcm` is derived from
+/// `rseed`:
+///
+/// - **Recipient**: The raw 43-byte Sapling address (`d || pk_d`)
+/// - **Value**: The amount of ZEC held by the note
+/// - **Rseed**: The random seed from which `rcm` (and `esk`) are derived
+///
+/// # Data Preservation
+/// During wallet migration, the complete note plaintext must be preserved for
+/// every unspent note. Without it, the receiving wallet cannot recompute the
+/// nullifier needed to detect spends, nor construct a proof to spend the note.
+///
+/// # Examples
+/// ```
+/// # use zewif::{sapling, Amount, Blob};
+/// # use zewif::Result;
+/// # fn example() -> Result<()> {
+/// let note = sapling::Note::from_parts(
+///     Blob::new([0u8; 43]),
+///     Amount::from_u64(10_000_000)?,
+///     Blob::new([1u8; 32]),
+/// )?;
+///
+/// let zats: i64 = note.value().into();
+/// assert_eq!(zats, 10_000_000);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Note {
+    /// The raw encoding of the Sapling address that received the note.
+    recipient: Blob<43>,
+
+    /// The value of the note, in zatoshis.
+    value: Amount,
+
+    /// The random seed used to derive `rcm` for this note.
+    rseed: Blob32,
+}
+
+impl Note {
+    /// Creates a new Sapling note from its constituent parts.
+    ///
+    /// # Errors
+    /// Returns [`Error::NegativeNoteValue`] if `value` is negative, as the
+    /// value of a note never is.
+    pub fn from_parts(recipient: Blob<43>, value: Amount, rseed: Blob32) -> Result<Self> {
+        if value.is_negative() {
+            return Err(Error::NegativeNoteValue {
+                pool: "Sapling",
+                value,
+            });
+        }
+        Ok(Self {
+            recipient,
+            value,
+            rseed,
+        })
+    }
+
+    /// Returns the raw encoding of the address that received the note.
+    pub fn recipient(&self) -> &Blob<43> {
+        &self.recipient
+    }
+
+    /// Returns the value of the note.
+    pub fn value(&self) -> Amount {
+        self.value
+    }
+
+    /// Returns the random seed of the note.
+    pub fn rseed(&self) -> &Blob32 {
+        &self.rseed
+    }
+}
+
+impl From<Note> for Envelope {
+    fn from(value: Note) -> Self {
+        Envelope::new(value.recipient)
+            .add_type("SaplingNote")
+            .add_assertion("value", value.value)
+            .add_assertion("rseed", value.rseed)
+    }
+}
+
+impl TryFrom<Envelope> for Note {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SaplingNote")?;
+        let recipient = envelope.extract_subject()?;
+        let value: Amount = envelope.extract_object_for_predicate("value")?;
+        let rseed = envelope.extract_object_for_predicate("rseed")?;
+        Self::from_parts(recipient, value, rseed)
+            .map_err(|e| bc_envelope::Error::General(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Amount, Blob, test_envelope_roundtrip};
+
+    use super::Note;
+
+    impl crate::RandomInstance for Note {
+        fn random() -> Self {
+            Self {
+                recipient: Blob::random(),
+                value: Amount::random_nonnegative(),
+                rseed: Blob::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(Note);
+
+    #[test]
+    fn test_negative_value_rejected() {
+        assert!(Note::from_parts(Blob::default(), -Amount::COIN, Blob::default()).is_err());
+        let note = Note {
+            recipient: Blob::default(),
+            value: -Amount::COIN,
+            rseed: Blob::default(),
+        };
+        assert!(Note::try_from(bc_envelope::Envelope::from(note)).is_err());
+    }
+}
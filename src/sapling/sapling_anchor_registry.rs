@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use bc_envelope::prelude::*;
+
+use crate::{AnchorRegistry, BlockHeight};
+
+use super::MerkleHashSapling;
+
+/// The roots of the Sapling note commitment tree known to a wallet, with the
+/// heights at which they were observed.
+///
+/// `SaplingAnchorRegistry` is used to check that each Sapling witness refers to
+/// an anchor of the chain known to the wallet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct SaplingAnchorRegistry(AnchorRegistry<MerkleHashSapling>);
+
+impl SaplingAnchorRegistry {
+    /// Creates a new Sapling anchor registry.
+    pub fn new(registry: AnchorRegistry<MerkleHashSapling>) -> Self {
+        Self(registry)
+    }
+
+    /// Returns the underlying anchor registry.
+    pub fn registry(&self) -> &AnchorRegistry<MerkleHashSapling> {
+        &self.0
+    }
+
+    /// Records that `anchor` was the root of the Sapling tree as of `height`.
+    pub fn insert(&mut self, anchor: MerkleHashSapling, height: BlockHeight) {
+        self.0.insert(anchor, height);
+    }
+}
+
+impl From<SaplingAnchorRegistry> for Envelope {
+    fn from(value: SaplingAnchorRegistry) -> Self {
+        let anchors: HashMap<MerkleHashSapling, BlockHeight> = value
+            .0
+            .iter()
+            .map(|(anchor, height)| (*anchor, *height))
+            .collect();
+        Envelope::new(CBOR::from(anchors)).add_type("SaplingAnchorRegistry")
+    }
+}
+
+impl TryFrom<Envelope> for SaplingAnchorRegistry {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SaplingAnchorRegistry")?;
+        let anchors: HashMap<MerkleHashSapling, BlockHeight> = envelope.extract_subject()?;
+        Ok(Self(anchors.into_iter().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AnchorRegistry, RandomInstance, test_envelope_roundtrip};
+
+    use super::SaplingAnchorRegistry;
+
+    impl RandomInstance for SaplingAnchorRegistry {
+        fn random() -> Self {
+            Self(AnchorRegistry::random())
+        }
+    }
+
+    test_envelope_roundtrip!(SaplingAnchorRegistry);
+}
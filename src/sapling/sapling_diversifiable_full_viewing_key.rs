@@ -0,0 +1,21 @@
+use crate::{blob, blob_envelope};
+
+blob!(
+    SaplingDiversifiableFullViewingKey,
+    128,
+    r#"A Sapling diversifiable full viewing key, encoded as `ak || nk || ovk || dk`.
+
+This is the encoding used for the Sapling component of a ZIP 316 unified full
+viewing key. Unlike
+[`SaplingExtendedFullViewingKey`](crate::sapling::SaplingExtendedFullViewingKey),
+it omits the ZIP 32 depth, parent fingerprint, child index and chain code, and
+so cannot be used to derive child keys.
+
+# Examples
+```
+# use zewif::sapling::SaplingDiversifiableFullViewingKey;
+let dfvk = SaplingDiversifiableFullViewingKey::new([0u8; 128]);
+```"#
+);
+
+blob_envelope!(SaplingDiversifiableFullViewingKey);
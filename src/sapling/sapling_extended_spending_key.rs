@@ -12,7 +12,79 @@ use crate::{blob, blob_envelope};
 blob!(
     SaplingExtendedSpendingKey,
     169,
-    "A Sapling Extended Spending Key, encoded as specified in ZIP 32"
+    "A Sapling Extended Spending Key, encoded as specified in ZIP 32",
+    secret
 );
 
 blob_envelope!(SaplingExtendedSpendingKey);
+
+#[cfg(feature = "zip32")]
+impl SaplingExtendedSpendingKey {
+    /// Derives the extended spending key at the given hardened path from
+    /// `seed`, as specified in ZIP 32. Path components are given without the
+    /// hardened bit.
+    ///
+    /// # Errors
+    /// Returns an error if the seed is shorter than 32 bytes or longer than
+    /// 252 bytes, or if a path component is not below 2<sup>31</sup>.
+    pub fn derive(seed: &[u8], path: &[u32]) -> crate::Result<Self> {
+        use sapling_crypto::zip32::ExtendedSpendingKey;
+        use zip32::ChildIndex;
+
+        use crate::Error;
+
+        if !(32..=252).contains(&seed.len()) {
+            return Err(Error::InvalidSeedLength(seed.len()));
+        }
+        let path = path
+            .iter()
+            .map(|&index| {
+                ChildIndex::from_index(index | (1 << 31))
+                    .filter(|_| index < (1 << 31))
+                    .ok_or(Error::ChildIndexTooLarge { index })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+        let key = ExtendedSpendingKey::from_path(&ExtendedSpendingKey::master(seed), &path);
+        Ok(Self::new(key.to_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /// Test vectors from https://github.com/zcash-hackworks/zcash-test-vectors
+    /// (`sapling_zip32.py`), using the seed `[0, 1, ..., 31]`. The encoding is
+    /// `depth || parent_fvk_tag || i || c || ask || nsk || ovk || dk`.
+    #[cfg(feature = "zip32")]
+    #[test]
+    fn test_zip32_vectors() {
+        use super::SaplingExtendedSpendingKey;
+
+        let seed: Vec<u8> = (0u8..32).collect();
+
+        let key = SaplingExtendedSpendingKey::derive(&seed, &[]).unwrap();
+        assert_eq!(key.as_slice()[0], 0);
+        assert_eq!(
+            hex::encode(&key.as_slice()[9..41]),
+            "d0947c4b03bf72a37ab44f72276d1cf3fdcd7ebf3e73348b7e550d752018668e"
+        );
+        assert_eq!(
+            hex::encode(&key.as_slice()[41..73]),
+            "b6c00c93d36032b9a268e99e86a860776560bf0e83c1a10b51f607c954742506"
+        );
+
+        let key = SaplingExtendedSpendingKey::derive(&seed, &[1]).unwrap();
+        assert_eq!(key.as_slice()[0], 1);
+        assert_eq!(key.as_slice()[5..9], (1u32 | (1 << 31)).to_le_bytes());
+        assert_eq!(
+            hex::encode(&key.as_slice()[9..41]),
+            "6fccaa45a8206b063ebb68c610e05927aa94d61be93ec25eb4f82efd68caaedb"
+        );
+        assert_eq!(
+            hex::encode(&key.as_slice()[41..73]),
+            "d5f7e92efb7abe04dc8c148b0b3b0fc23e0429f00208ff93b68d21a6e131bd04"
+        );
+
+        assert!(SaplingExtendedSpendingKey::derive(&seed, &[1 << 31]).is_err());
+        assert!(SaplingExtendedSpendingKey::derive(&seed[..16], &[]).is_err());
+    }
+}
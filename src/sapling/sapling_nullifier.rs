@@ -0,0 +1,25 @@
+use crate::{blob, blob_envelope};
+
+blob!(
+    SaplingNullifier,
+    32,
+    r#"The nullifier of a Sapling note.
+
+A nullifier is revealed on chain when a note is spent. Because it can only be
+computed with knowledge of the note and the owner's nullifier deriving key, a
+wallet determines whether one of its notes has been spent by looking for the
+note's nullifier among the spend descriptions of later transactions.
+
+# Zcash Concept Relation
+Sapling nullifiers are checked against the Sapling nullifier set only, and so
+are modeled separately from Orchard nullifiers.
+
+# Examples
+```
+# use zewif::sapling::SaplingNullifier;
+let nullifier = SaplingNullifier::new([0u8; 32]);
+```"#
+);
+impl Copy for SaplingNullifier {}
+
+blob_envelope!(SaplingNullifier);
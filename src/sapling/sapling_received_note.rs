@@ -0,0 +1,196 @@
+use bc_envelope::prelude::*;
+
+use crate::{Indexed, TxId};
+
+use super::{Note, SaplingNullifier, SaplingWitness};
+
+/// A Sapling note received by the wallet, paired with the witness needed to
+/// spend it.
+///
+/// `SaplingReceivedNote` records where a note was created (the transaction and
+/// the index of the output within that transaction's Sapling bundle), the
+/// decrypted note plaintext, the witness for unspent notes, and the note's
+/// nullifier along with the transaction that spent it, when known.
+///
+/// # Data Preservation
+/// For each unspent Sapling note, both the note plaintext and its witness must
+/// survive migration. For spent notes, preserving the spending transaction
+/// avoids the receiving wallet treating the note as spendable.
+///
+/// # Examples
+/// ```
+/// # use zewif::{sapling::{self, SaplingReceivedNote}, Amount, Blob, TxId};
+/// # use zewif::Result;
+/// # fn example() -> Result<()> {
+/// let note = sapling::Note::from_parts(
+///     Blob::new([0u8; 43]),
+///     Amount::from_u64(10_000_000)?,
+///     Blob::new([1u8; 32]),
+/// )?;
+/// let received = SaplingReceivedNote::new(TxId::from_bytes([0u8; 32]), 0, note);
+///
+/// assert!(received.spent_in().is_none());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct SaplingReceivedNote {
+    /// The index of this note within the account's received notes.
+    index: usize,
+
+    /// The id of the transaction that created the note.
+    txid: TxId,
+
+    /// The index of the output within the transaction's Sapling bundle that
+    /// created the note.
+    output_index: u32,
+
+    /// The decrypted note plaintext.
+    note: Note,
+
+    /// The witness for the note's commitment, if the note is spendable and the
+    /// source wallet maintained one.
+    witness: Option<SaplingWitness>,
+
+    /// The nullifier of the note, if the source wallet computed it.
+    nullifier: Option<SaplingNullifier>,
+
+    /// The id of the transaction that spent the note, if known.
+    spent_in: Option<TxId>,
+}
+
+impl Indexed for SaplingReceivedNote {
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
+
+impl SaplingReceivedNote {
+    /// Creates a new received note without a witness or nullifier.
+    pub fn new(txid: TxId, output_index: u32, note: Note) -> Self {
+        Self {
+            index: 0,
+            txid,
+            output_index,
+            note,
+            witness: None,
+            nullifier: None,
+            spent_in: None,
+        }
+    }
+
+    /// Returns the id of the transaction that created the note.
+    pub fn txid(&self) -> TxId {
+        self.txid
+    }
+
+    /// Returns the index of the output within the transaction that created the
+    /// note.
+    pub fn output_index(&self) -> u32 {
+        self.output_index
+    }
+
+    /// Returns the decrypted note plaintext.
+    pub fn note(&self) -> &Note {
+        &self.note
+    }
+
+    /// Returns the witness for the note's commitment, if known.
+    pub fn witness(&self) -> Option<&SaplingWitness> {
+        self.witness.as_ref()
+    }
+
+    /// Sets the witness for the note's commitment.
+    pub fn set_witness(&mut self, witness: Option<SaplingWitness>) {
+        self.witness = witness;
+    }
+
+    /// Returns the nullifier of the note, if known.
+    pub fn nullifier(&self) -> Option<&SaplingNullifier> {
+        self.nullifier.as_ref()
+    }
+
+    /// Sets the nullifier of the note.
+    pub fn set_nullifier(&mut self, nullifier: Option<SaplingNullifier>) {
+        self.nullifier = nullifier;
+    }
+
+    /// Returns the id of the transaction that spent the note, if known.
+    pub fn spent_in(&self) -> Option<TxId> {
+        self.spent_in
+    }
+
+    /// Sets the id of the transaction that spent the note.
+    pub fn set_spent_in(&mut self, spent_in: Option<TxId>) {
+        self.spent_in = spent_in;
+    }
+}
+
+impl From<SaplingReceivedNote> for Envelope {
+    fn from(value: SaplingReceivedNote) -> Self {
+        Envelope::new(value.index)
+            .add_type("SaplingReceivedNote")
+            .add_assertion("txid", value.txid)
+            .add_assertion("output_index", value.output_index)
+            .add_assertion("note", value.note)
+            .add_optional_assertion("witness", value.witness)
+            .add_optional_assertion("nullifier", value.nullifier)
+            .add_optional_assertion("spent_in", value.spent_in)
+    }
+}
+
+impl TryFrom<Envelope> for SaplingReceivedNote {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SaplingReceivedNote")?;
+        let index = envelope.extract_subject()?;
+        let txid = envelope.extract_object_for_predicate("txid")?;
+        let output_index = envelope.extract_object_for_predicate("output_index")?;
+        let note = envelope.try_object_for_predicate("note")?;
+        let witness = envelope.try_optional_object_for_predicate("witness")?;
+        let nullifier = envelope.try_optional_object_for_predicate("nullifier")?;
+        let spent_in = envelope.extract_optional_object_for_predicate("spent_in")?;
+        Ok(Self {
+            index,
+            txid,
+            output_index,
+            note,
+            witness,
+            nullifier,
+            spent_in,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        TxId,
+        sapling::{Note, SaplingNullifier, SaplingWitness},
+        test_envelope_roundtrip,
+    };
+
+    use super::SaplingReceivedNote;
+
+    impl crate::RandomInstance for SaplingReceivedNote {
+        fn random() -> Self {
+            Self {
+                index: 0,
+                txid: TxId::random(),
+                output_index: u32::random(),
+                note: Note::random(),
+                witness: SaplingWitness::opt_random(),
+                nullifier: SaplingNullifier::opt_random(),
+                spent_in: TxId::opt_random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(SaplingReceivedNote);
+}
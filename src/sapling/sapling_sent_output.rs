@@ -47,6 +47,7 @@ use crate::{Amount, Indexed, Memo};
 /// # }
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct SaplingSentOutput {
     /// The index of the output in the transaction's Sapling bundle.
     index: usize,
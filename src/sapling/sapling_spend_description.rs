@@ -0,0 +1,129 @@
+use bc_envelope::prelude::*;
+
+use crate::{Anchor, Blob32, Indexed};
+
+use super::SaplingNullifier;
+
+/// The public data of a single spend within a transaction's Sapling bundle.
+///
+/// Each Sapling spend reveals the nullifier of the note it consumes. Recording
+/// the spends of each transaction lets the receiving wallet determine which of
+/// its notes have been spent without refetching the transaction from the chain.
+///
+/// # Zcash Concept Relation
+/// As specified in the Zcash protocol specification (§4.4), a spend
+/// description contains:
+///
+/// - **cv**: The value commitment to the value of the input note (32 bytes)
+/// - **anchor**: The root of the Sapling note commitment tree at some earlier
+///   block height (32 bytes)
+/// - **nullifier**: The nullifier of the input note (32 bytes)
+/// - **rk**: The randomized spend validating key (32 bytes)
+///
+/// The zk-SNARK proof and spend authorization signature are not preserved,
+/// as they are only needed to validate the transaction, not to interpret it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct SaplingSpendDescription {
+    /// The index of the spend within the transaction's Sapling bundle.
+    index: usize,
+    cv: Blob32,
+    anchor: Anchor,
+    nullifier: SaplingNullifier,
+    rk: Blob32,
+}
+
+impl Indexed for SaplingSpendDescription {
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
+
+impl SaplingSpendDescription {
+    /// Creates a new spend description from its constituent parts.
+    pub fn from_parts(cv: Blob32, anchor: Anchor, nullifier: SaplingNullifier, rk: Blob32) -> Self {
+        Self {
+            index: 0,
+            cv,
+            anchor,
+            nullifier,
+            rk,
+        }
+    }
+
+    /// Returns the value commitment to the value of the input note.
+    pub fn cv(&self) -> &Blob32 {
+        &self.cv
+    }
+
+    /// Returns the anchor against which the spend was proven.
+    pub fn anchor(&self) -> &Anchor {
+        &self.anchor
+    }
+
+    /// Returns the nullifier of the note spent.
+    pub fn nullifier(&self) -> &SaplingNullifier {
+        &self.nullifier
+    }
+
+    /// Returns the randomized spend validating key.
+    pub fn rk(&self) -> &Blob32 {
+        &self.rk
+    }
+}
+
+impl From<SaplingSpendDescription> for Envelope {
+    fn from(value: SaplingSpendDescription) -> Self {
+        Envelope::new(value.index)
+            .add_type("SaplingSpendDescription")
+            .add_assertion("cv", value.cv)
+            .add_assertion("anchor", value.anchor)
+            .add_assertion("nullifier", value.nullifier)
+            .add_assertion("rk", value.rk)
+    }
+}
+
+impl TryFrom<Envelope> for SaplingSpendDescription {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SaplingSpendDescription")?;
+        let index = envelope.extract_subject()?;
+        let cv = envelope.extract_object_for_predicate("cv")?;
+        let anchor = envelope.try_object_for_predicate("anchor")?;
+        let nullifier = envelope.try_object_for_predicate("nullifier")?;
+        let rk = envelope.extract_object_for_predicate("rk")?;
+        Ok(Self {
+            index,
+            cv,
+            anchor,
+            nullifier,
+            rk,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Anchor, Blob, sapling::SaplingNullifier, test_envelope_roundtrip};
+
+    use super::SaplingSpendDescription;
+
+    impl crate::RandomInstance for SaplingSpendDescription {
+        fn random() -> Self {
+            Self {
+                index: 0,
+                cv: Blob::random(),
+                anchor: Anchor::random(),
+                nullifier: SaplingNullifier::random(),
+                rk: Blob::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(SaplingSpendDescription);
+}
@@ -0,0 +1,77 @@
+use bc_envelope::prelude::*;
+
+use crate::TreeState;
+
+use super::{MerkleHashSapling, SAPLING_COMMITMENT_TREE_DEPTH};
+
+/// The state of the Sapling note commitment tree as of a particular block height.
+///
+/// `SaplingTreeState` holds the frontier of the Sapling note commitment tree,
+/// allowing the receiving wallet to continue updating Sapling witnesses from the
+/// recorded height instead of rebuilding the tree from Sapling activation.
+///
+/// # Data Preservation
+/// The tree state recorded at export time should be preserved alongside the
+/// wallet's witnesses, as both must refer to the same chain state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct SaplingTreeState(TreeState<SAPLING_COMMITMENT_TREE_DEPTH, MerkleHashSapling>);
+
+impl SaplingTreeState {
+    /// Creates a new Sapling tree state from a tree state over the Sapling note
+    /// commitment tree.
+    pub fn new(state: TreeState<SAPLING_COMMITMENT_TREE_DEPTH, MerkleHashSapling>) -> Self {
+        Self(state)
+    }
+
+    /// Returns the underlying tree state.
+    pub fn state(&self) -> &TreeState<SAPLING_COMMITMENT_TREE_DEPTH, MerkleHashSapling> {
+        &self.0
+    }
+
+    /// Checks that the tree state is structurally consistent with a depth-32
+    /// Sapling note commitment tree.
+    pub fn validate(&self) -> crate::Result<()> {
+        self.0.validate()
+    }
+}
+
+impl From<SaplingTreeState> for Envelope {
+    fn from(value: SaplingTreeState) -> Self {
+        Envelope::new(value.0.height())
+            .add_type("SaplingTreeState")
+            .add_assertion("tree_size", value.0.tree_size())
+            .add_assertion("frontier", value.0.frontier().to_vec())
+    }
+}
+
+impl TryFrom<Envelope> for SaplingTreeState {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SaplingTreeState")?;
+        let height = envelope.extract_subject()?;
+        let tree_size = envelope.extract_object_for_predicate("tree_size")?;
+        let frontier = envelope.extract_object_for_predicate("frontier")?;
+        Ok(Self(TreeState::from_parts(height, tree_size, frontier)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RandomInstance, TreeState, test_envelope_roundtrip};
+
+    use super::SaplingTreeState;
+
+    impl RandomInstance for SaplingTreeState {
+        fn random() -> Self {
+            Self(TreeState::random())
+        }
+    }
+
+    test_envelope_roundtrip!(SaplingTreeState);
+}
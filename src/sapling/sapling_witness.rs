@@ -3,7 +3,7 @@ use bc_envelope::prelude::*;
 use crate::{IncrementalWitness, blob, blob_envelope};
 
 /// The depth of the Zcash Sapling note commitment tree.
-const SAPLING_COMMITMENT_TREE_DEPTH: usize = 32;
+pub const SAPLING_COMMITMENT_TREE_DEPTH: usize = 32;
 
 blob!(
     MerkleHashSapling,
@@ -44,10 +44,60 @@ blob_envelope!(MerkleHashSapling);
 /// Without this witness data, unspent notes cannot be spent as it would be impossible
 /// to prove their inclusion in the note commitment tree.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct SaplingWitness(
     IncrementalWitness<SAPLING_COMMITMENT_TREE_DEPTH, MerkleHashSapling>,
 );
 
+impl SaplingWitness {
+    /// Creates a new Sapling witness from an incremental witness over the
+    /// Sapling note commitment tree.
+    pub fn new(
+        witness: IncrementalWitness<SAPLING_COMMITMENT_TREE_DEPTH, MerkleHashSapling>,
+    ) -> Self {
+        Self(witness)
+    }
+
+    /// Returns the underlying incremental witness.
+    pub fn witness(&self) -> &IncrementalWitness<SAPLING_COMMITMENT_TREE_DEPTH, MerkleHashSapling> {
+        &self.0
+    }
+
+    /// Checks that the witness is structurally consistent with a depth-32
+    /// Sapling note commitment tree.
+    pub fn validate(&self) -> crate::Result<()> {
+        self.0.validate()
+    }
+
+    /// Checks that hashing the note commitment up the Merkle path yields the
+    /// witness's anchor.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidWitness`](crate::Error::InvalidWitness) if the
+    /// witness is malformed, and
+    /// [`Error::WitnessRootMismatch`](crate::Error::WitnessRootMismatch) if its
+    /// path leads to a different root.
+    #[cfg(feature = "zip32")]
+    pub fn verify_root(&self) -> crate::Result<()> {
+        self.validate()?;
+        let position = self.0.note_position();
+        let root = self.0.merkle_path().iter().enumerate().fold(
+            *self.0.note_commitment().as_bytes(),
+            |node, (level, sibling)| {
+                if (position >> level) & 1 == 0 {
+                    sapling_crypto::merkle_hash(level, &node, sibling.as_bytes())
+                } else {
+                    sapling_crypto::merkle_hash(level, sibling.as_bytes(), &node)
+                }
+            },
+        );
+        if &root != self.0.anchor().as_bytes() {
+            return Err(crate::Error::WitnessRootMismatch);
+        }
+        Ok(())
+    }
+}
+
 impl From<SaplingWitness> for Envelope {
     fn from(value: SaplingWitness) -> Self {
         Envelope::new(*value.0.note_commitment())
@@ -103,4 +153,57 @@ mod tests {
     }
 
     test_envelope_roundtrip!(SaplingWitness);
+
+    #[test]
+    fn test_merkle_path_is_compact() {
+        use bc_envelope::prelude::*;
+
+        let witness = SaplingWitness::random();
+        let envelope = Envelope::from(witness.clone());
+        let path = envelope.object_for_predicate("merkle_path").unwrap();
+        let elements = path.subject().try_leaf().unwrap().try_into_array().unwrap();
+        assert_eq!(elements.len(), witness.witness().merkle_path().len());
+        for (element, node) in elements.into_iter().zip(witness.witness().merkle_path()) {
+            assert_eq!(element.try_into_byte_string().unwrap(), node.to_vec());
+        }
+    }
+
+    #[cfg(feature = "zip32")]
+    #[test]
+    fn test_verify_root() {
+        use super::{MerkleHashSapling, SAPLING_COMMITMENT_TREE_DEPTH};
+
+        // The uncommitted leaf is the field element 1, and the root of a tree
+        // holding only uncommitted leaves is the empty tree's anchor.
+        let mut uncommitted = [0u8; 32];
+        uncommitted[0] = 1;
+        let mut node = uncommitted;
+        let mut path = Vec::new();
+        for level in 0..SAPLING_COMMITMENT_TREE_DEPTH {
+            path.push(MerkleHashSapling::new(node));
+            node = sapling_crypto::merkle_hash(level, &node, &node);
+        }
+        let anchor = MerkleHashSapling::new(sapling_crypto::Anchor::empty_tree().to_bytes());
+        let leaf = MerkleHashSapling::new(uncommitted);
+        let witness = SaplingWitness::new(IncrementalWitness::from_parts(
+            leaf,
+            1,
+            path.clone(),
+            anchor,
+            2,
+            vec![],
+        ));
+        assert!(witness.verify_root().is_ok());
+
+        let wrong_anchor = MerkleHashSapling::new([7; 32]);
+        let witness = SaplingWitness::new(IncrementalWitness::from_parts(
+            leaf,
+            1,
+            path,
+            wrong_anchor,
+            2,
+            vec![],
+        ));
+        assert!(witness.verify_root().is_err());
+    }
 }
@@ -1,5 +1,7 @@
 use super::Data;
+use crate::{Network, NetworkParameters, base58check};
 use bc_envelope::prelude::*;
+use std::io::{self, Read, Write};
 use std::ops::{
     Index, IndexMut, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
 };
@@ -43,6 +45,20 @@ use std::ops::{
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Script(Data);
 
+/// The standard forms of a `scriptPubKey` that correspond to transparent
+/// addresses; see [`Script::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptKind {
+    /// Pay to Public Key Hash, encumbering coins to a `t1` (mainnet) or `tm`
+    /// (testnet) address.
+    P2pkh,
+    /// Pay to Script Hash, encumbering coins to a `t3` (mainnet) or `t2`
+    /// (testnet) address.
+    P2sh,
+    /// Any other script, which has no address.
+    Other,
+}
+
 impl Script {
     pub fn len(&self) -> usize {
         self.0.len()
@@ -51,6 +67,109 @@ impl Script {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Classifies the script as a `scriptPubKey`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Data, Script, ScriptKind};
+    /// let mut bytes = vec![0xa9, 0x14];
+    /// bytes.extend_from_slice(&[0u8; 20]);
+    /// bytes.push(0x87);
+    /// assert_eq!(Script::from(Data::from_vec(bytes)).kind(), ScriptKind::P2sh);
+    /// assert_eq!(Script::from(Data::new()).kind(), ScriptKind::Other);
+    /// ```
+    pub fn kind(&self) -> ScriptKind {
+        self.hash().map_or(ScriptKind::Other, |(kind, _)| kind)
+    }
+
+    /// Returns the kind of a standard script and the 20-byte hash it pays to.
+    fn hash(&self) -> Option<(ScriptKind, &[u8])> {
+        match self.as_ref() {
+            [0x76, 0xa9, 0x14, hash @ .., 0x88, 0xac] if hash.len() == 20 => {
+                Some((ScriptKind::P2pkh, hash))
+            }
+            [0xa9, 0x14, hash @ .., 0x87] if hash.len() == 20 => Some((ScriptKind::P2sh, hash)),
+            _ => None,
+        }
+    }
+
+    /// Returns the transparent address the script pays to on `network`, in
+    /// its string encoding, or `None` if the script is not P2PKH or P2SH.
+    pub fn address(&self, network: Network) -> Option<String> {
+        self.address_with_prefixes(network.p2pkh_prefix(), network.p2sh_prefix())
+    }
+
+    /// Like [`Script::address`], encoding the address with the prefixes of
+    /// `params`, which may be the custom ones of a regtest deployment.
+    pub fn address_with(&self, params: &NetworkParameters) -> Option<String> {
+        self.address_with_prefixes(params.p2pkh_prefix(), params.p2sh_prefix())
+    }
+
+    fn address_with_prefixes(&self, p2pkh_prefix: [u8; 2], p2sh_prefix: [u8; 2]) -> Option<String> {
+        let (kind, hash) = self.hash()?;
+        let prefix = match kind {
+            ScriptKind::P2pkh => p2pkh_prefix,
+            ScriptKind::P2sh => p2sh_prefix,
+            ScriptKind::Other => return None,
+        };
+        Some(base58check::encode(&prefix, hash))
+    }
+
+    /// Reads a script in its consensus encoding, prefixed by its length.
+    ///
+    /// # Errors
+    /// Returns an IO error if the reader does not contain a complete script.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut first = [0u8; 1];
+        reader.read_exact(&mut first)?;
+        let len = match first[0] {
+            0xfd => {
+                let mut bytes = [0u8; 2];
+                reader.read_exact(&mut bytes)?;
+                u64::from(u16::from_le_bytes(bytes))
+            }
+            0xfe => {
+                let mut bytes = [0u8; 4];
+                reader.read_exact(&mut bytes)?;
+                u64::from(u32::from_le_bytes(bytes))
+            }
+            0xff => {
+                let mut bytes = [0u8; 8];
+                reader.read_exact(&mut bytes)?;
+                u64::from_le_bytes(bytes)
+            }
+            len => u64::from(len),
+        };
+        let mut bytes = Vec::new();
+        reader.take(len).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 != len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "truncated script",
+            ));
+        }
+        Ok(Script(Data::from_vec(bytes)))
+    }
+
+    /// Writes the script in its consensus encoding, prefixed by its length.
+    ///
+    /// # Errors
+    /// Returns an IO error if writing fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        match self.len() {
+            len @ 0..0xfd => writer.write_all(&[len as u8])?,
+            len @ 0xfd..=0xffff => {
+                writer.write_all(&[0xfd])?;
+                writer.write_all(&(len as u16).to_le_bytes())?;
+            }
+            len => {
+                writer.write_all(&[0xfe])?;
+                writer.write_all(&(len as u32).to_le_bytes())?;
+            }
+        }
+        writer.write_all(self.as_ref())
+    }
 }
 
 /// Debug formatting that includes script length and hex representation
@@ -0,0 +1,85 @@
+use bc_envelope::prelude::*;
+use std::fmt;
+
+/// A point in time, as a number of seconds since the Unix epoch.
+///
+/// Wallets record times such as when a key was created or imported in this
+/// form; zcashd's `nCreateTime` key metadata is one example.
+///
+/// # Examples
+/// ```
+/// # use zewif::SecondsSinceEpoch;
+/// let time = SecondsSinceEpoch::from(1_540_779_337u64);
+/// assert_eq!(u64::from(time), 1_540_779_337);
+/// assert_eq!(time.to_string(), "1540779337");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SecondsSinceEpoch(u64);
+
+impl fmt::Display for SecondsSinceEpoch {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl From<u64> for SecondsSinceEpoch {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<SecondsSinceEpoch> for u64 {
+    fn from(value: SecondsSinceEpoch) -> u64 {
+        value.0
+    }
+}
+
+impl From<SecondsSinceEpoch> for CBOR {
+    fn from(value: SecondsSinceEpoch) -> Self {
+        CBOR::from(value.0)
+    }
+}
+
+impl From<&SecondsSinceEpoch> for CBOR {
+    fn from(value: &SecondsSinceEpoch) -> Self {
+        CBOR::from(value.0)
+    }
+}
+
+impl TryFrom<CBOR> for SecondsSinceEpoch {
+    type Error = dcbor::Error;
+
+    fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
+        Ok(Self(u64::try_from(cbor)?))
+    }
+}
+
+impl From<SecondsSinceEpoch> for Envelope {
+    fn from(value: SecondsSinceEpoch) -> Self {
+        Envelope::new(CBOR::from(value))
+    }
+}
+
+impl TryFrom<Envelope> for SecondsSinceEpoch {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.extract_subject()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cbor_roundtrip, test_envelope_roundtrip};
+
+    use super::SecondsSinceEpoch;
+
+    impl crate::RandomInstance for SecondsSinceEpoch {
+        fn random() -> Self {
+            Self(rand::random())
+        }
+    }
+
+    test_cbor_roundtrip!(SecondsSinceEpoch);
+    test_envelope_roundtrip!(SecondsSinceEpoch);
+}
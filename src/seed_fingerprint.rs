@@ -7,5 +7,50 @@ blob!(
 );
 impl Copy for SeedFingerprint {}
 
+impl SeedFingerprint {
+    /// Returns the first four bytes of the fingerprint in hex, which is enough
+    /// to tell seeds apart in diagnostic output.
+    pub fn short_hex(&self) -> String {
+        hex::encode(&self.as_bytes()[..4])
+    }
+}
+
+impl SeedFingerprint {
+    /// Computes the ZIP 32 fingerprint of the given seed bytes.
+    ///
+    /// # Errors
+    /// Returns an error if the seed is shorter than 32 bytes or longer than
+    /// 252 bytes.
+    pub fn from_seed(seed: &[u8]) -> crate::Result<Self> {
+        if !(32..=252).contains(&seed.len()) {
+            return Err(crate::Error::InvalidSeedLength(seed.len()));
+        }
+        let hash = blake2b_simd::Params::new()
+            .hash_length(32)
+            .personal(b"Zcash_HD_Seed_FP")
+            .to_state()
+            .update(&[seed.len() as u8])
+            .update(seed)
+            .finalize();
+        Ok(Self::from_slice(hash.as_bytes())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SeedFingerprint;
+
+    #[test]
+    fn test_zip32_seed_fingerprint() {
+        let seed: Vec<u8> = (0u8..32).collect();
+        let fingerprint = SeedFingerprint::from_seed(&seed).unwrap();
+        assert_eq!(
+            hex::encode(fingerprint.as_bytes()),
+            "deff604c246710f7176dead02aa746f2fd8d5389f7072556dcb555fdbe5e3ae3"
+        );
+        assert!(SeedFingerprint::from_seed(&seed[..16]).is_err());
+    }
+}
+
 blob_envelope!(SeedFingerprint);
 
@@ -1,6 +1,9 @@
 use bc_envelope::prelude::*;
 
-use crate::{Bip39Mnemonic, LegacySeed, error::Error};
+use crate::{
+    Bip39Mnemonic, EncryptedSeed, HardwareSeedReference, LegacySeed, Result, SeedFingerprint,
+    ZcashdLegacySeed, error::Error,
+};
 
 /// Source material used to generate cryptographic keys in a Zcash wallet.
 ///
@@ -20,6 +23,10 @@ use crate::{Bip39Mnemonic, LegacySeed, error::Error};
 ///   that predate the BIP-39 standard. These are typically stored as binary
 ///   data and lack the mnemonic recovery mechanism.
 ///
+/// - **Upgraded zcashd Wallets**: zcashd wallets created before version 4.7.0
+///   hold both their original raw seed and the mnemonic added on upgrade; see
+///   [`ZcashdLegacySeed`].
+///
 /// Wallet implementations use this seed material as the root of their key
 /// derivation, generating both transparent and shielded keys from this source.
 ///
@@ -57,45 +64,200 @@ use crate::{Bip39Mnemonic, LegacySeed, error::Error};
 /// let binary_seed = SeedMaterial::LegacySeed(seed);
 /// ```
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum SeedMaterial {
     /// A BIP-39 mnemonic phrase (typically 12 or 24 words) used as a
     /// human-readable seed
     Bip39Mnemonic(Bip39Mnemonic),
     /// A raw 32-byte seed predating the BIP-39 standard
     LegacySeed(LegacySeed),
+    /// Both seeds of a zcashd wallet upgraded from a version before 4.7.0
+    LegacyZcashd(ZcashdLegacySeed),
+    /// A seed still encrypted under the source wallet's passphrase
+    Encrypted(EncryptedSeed),
+    /// A seed held by a hardware wallet, which the wallet itself never had
+    HardwareReference(HardwareSeedReference),
+    /// A seed elided from a watch-only export; see
+    /// [`Zewif::to_watch_only_envelope`](crate::Zewif::to_watch_only_envelope)
+    Redacted,
 }
 
-impl std::fmt::Debug for SeedMaterial {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// Whether the seed of a [`SeedMaterial`] can be used, as reported by
+/// [`SeedMaterial::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedStatus {
+    /// The seed is present in the clear.
+    Available,
+    /// The seed is present but encrypted, and cannot be used until it is
+    /// decrypted with the source wallet's passphrase.
+    Locked,
+    /// The seed is held by a hardware device, and only the device can use it.
+    External,
+    /// The seed was elided from a watch-only export.
+    Redacted,
+}
+
+impl SeedMaterial {
+    /// Returns the seed bytes from which keys are derived.
+    ///
+    /// For a BIP-39 mnemonic this is the 64-byte BIP-39 seed computed with the
+    /// mnemonic's passphrase, if any; see [`Bip39Mnemonic::to_seed`]. For a
+    /// legacy seed, the raw seed is returned. For the seeds of an upgraded
+    /// zcashd wallet, this is the BIP-39 seed of the mnemonic; the original
+    /// seed is available from [`ZcashdLegacySeed::original_seed`].
+    ///
+    /// # Errors
+    /// Returns [`Error::SeedUnavailable`] if the seed is encrypted, held by a
+    /// hardware device, or redacted.
+    pub fn to_seed(&self) -> Result<Vec<u8>> {
         match self {
-            Self::Bip39Mnemonic(phrase) => {
-                write!(f, "SeedMaterial::Bip39Mnemonic(\"{:?}\")", phrase)
+            Self::Bip39Mnemonic(mnemonic) => Ok(mnemonic.to_seed()),
+            Self::LegacySeed(seed) => Ok(seed.seed_data().to_vec()),
+            Self::LegacyZcashd(seed) => Ok(seed.mnemonic().to_seed()),
+            Self::Encrypted(_) => Err(Error::SeedUnavailable {
+                status: SeedStatus::Locked,
+            }),
+            Self::HardwareReference(_) => Err(Error::SeedUnavailable {
+                status: SeedStatus::External,
+            }),
+            Self::Redacted => Err(Error::SeedUnavailable {
+                status: SeedStatus::Redacted,
+            }),
+        }
+    }
+
+    /// Returns the seed bytes from which keys are derived, as
+    /// [`SeedMaterial::to_seed`] does, but computing the BIP-39 seed of a
+    /// mnemonic with the given passphrase in place of the recorded one.
+    ///
+    /// With `None`, the recorded passphrase is used. The passphrase is ignored
+    /// for a legacy seed, whose raw bytes are returned.
+    ///
+    /// # Errors
+    /// Returns [`Error::SeedUnavailable`] if the seed is encrypted, held by a
+    /// hardware device, or redacted.
+    pub fn to_seed_bytes(&self, passphrase: Option<&str>) -> Result<Vec<u8>> {
+        let mnemonic = match self {
+            Self::Bip39Mnemonic(mnemonic) => mnemonic,
+            Self::LegacyZcashd(seed) => seed.mnemonic(),
+            _ => return self.to_seed(),
+        };
+        Ok(match passphrase {
+            Some(passphrase) => mnemonic.to_seed_with_passphrase(passphrase),
+            None => mnemonic.to_seed(),
+        })
+    }
+
+    /// Computes the ZIP 32 fingerprint of the seed; see
+    /// [`SeedMaterial::to_seed`]. For an encrypted seed or one held by a
+    /// hardware device, the fingerprint recorded alongside it is returned.
+    ///
+    /// # Errors
+    /// Returns an error if a legacy seed is shorter than 32 bytes or longer
+    /// than 252 bytes, or if the seed is unavailable and its fingerprint was
+    /// not recorded. The fingerprint of a redacted seed is recorded only by
+    /// its [`WalletSeed`](crate::WalletSeed).
+    pub fn fingerprint(&self) -> Result<SeedFingerprint> {
+        match self {
+            Self::Encrypted(seed) => seed.fingerprint().copied().ok_or(Error::SeedUnavailable {
+                status: SeedStatus::Locked,
+            }),
+            Self::HardwareReference(reference) => {
+                reference
+                    .seed_fingerprint()
+                    .copied()
+                    .ok_or(Error::SeedUnavailable {
+                        status: SeedStatus::External,
+                    })
             }
+            _ => SeedFingerprint::from_seed(&self.to_seed()?),
+        }
+    }
+
+    /// Checks that the seed material is well formed.
+    ///
+    /// A BIP-39 mnemonic must be valid as described in
+    /// [`Bip39Mnemonic::validate`]; a legacy seed must be between 32 and 252
+    /// bytes long. The mnemonic of an upgraded zcashd wallet is validated like
+    /// any other. An encrypted seed cannot be checked, and is reported as
+    /// [`SeedStatus::Locked`] rather than as an error, a seed held by a
+    /// hardware device as [`SeedStatus::External`], and a redacted seed as
+    /// [`SeedStatus::Redacted`].
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidMnemonic`] or [`Error::InvalidSeedLength`].
+    pub fn validate(&self) -> Result<SeedStatus> {
+        match self {
+            Self::Bip39Mnemonic(mnemonic) => mnemonic.validate()?,
+            Self::LegacyZcashd(seed) => seed.mnemonic().validate()?,
             Self::LegacySeed(seed) => {
-                write!(f, "SeedMaterial::LegacySeed({:?})", seed)
+                let len = seed.seed_data().len();
+                if !(32..=252).contains(&len) {
+                    return Err(Error::InvalidSeedLength(len));
+                }
             }
+            Self::Encrypted(_) => return Ok(SeedStatus::Locked),
+            Self::HardwareReference(_) => return Ok(SeedStatus::External),
+            Self::Redacted => return Ok(SeedStatus::Redacted),
+        }
+        Ok(SeedStatus::Available)
+    }
+
+    /// Returns the secret itself in displayable form: the mnemonic phrase for a
+    /// BIP-39 mnemonic, or the hex-encoded seed for a legacy seed. For an
+    /// upgraded zcashd wallet, both secrets are returned on separate lines,
+    /// the mnemonic phrase followed by the hex-encoded original seed. Returns
+    /// `None` if the seed is encrypted, held by a hardware device, or
+    /// redacted.
+    ///
+    /// `Debug` and `Display` deliberately redact the seed, so that logging a
+    /// wallet structure cannot leak it. Only call this where the user has
+    /// explicitly asked to see the secret.
+    pub fn expose_secret(&self) -> Option<String> {
+        match self {
+            Self::Bip39Mnemonic(mnemonic) => Some(mnemonic.mnemonic().clone()),
+            Self::LegacySeed(seed) => Some(hex::encode(seed.seed_data().as_slice())),
+            Self::LegacyZcashd(seed) => Some(format!(
+                "{}\n{}",
+                seed.mnemonic().mnemonic(),
+                hex::encode(seed.original_seed().as_slice())
+            )),
+            Self::Encrypted(_) | Self::HardwareReference(_) | Self::Redacted => None,
         }
     }
 }
 
-impl std::fmt::Display for SeedMaterial {
+/// Prints a redacted form of the seed material, such as
+/// `SeedMaterial::Bip39Mnemonic(<24 words, fingerprint abcd1234>)`; see
+/// [`SeedMaterial::expose_secret`] for the secret itself.
+impl std::fmt::Debug for SeedMaterial {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Bip39Mnemonic(phrase) => {
-                write!(f, "SeedMaterial::Bip39Mnemonic(\"{:?}\")", phrase)
-            }
-            Self::LegacySeed(seed) => {
-                write!(f, "SeedMaterial::LegacySeed({:?})", seed)
-            }
+            Self::Bip39Mnemonic(mnemonic) => write!(f, "SeedMaterial::{:?}", mnemonic),
+            Self::LegacySeed(seed) => write!(f, "SeedMaterial::{:?}", seed),
+            Self::LegacyZcashd(seed) => write!(f, "SeedMaterial::{:?}", seed),
+            Self::Encrypted(seed) => write!(f, "SeedMaterial::{:?}", seed),
+            Self::HardwareReference(reference) => write!(f, "SeedMaterial::{:?}", reference),
+            Self::Redacted => write!(f, "SeedMaterial::Redacted"),
         }
     }
 }
 
+impl std::fmt::Display for SeedMaterial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
 impl From<SeedMaterial> for Envelope {
     fn from(value: SeedMaterial) -> Self {
         match value {
             SeedMaterial::Bip39Mnemonic(mnemonic) => Envelope::new(mnemonic),
             SeedMaterial::LegacySeed(seed) => Envelope::new(seed),
+            SeedMaterial::LegacyZcashd(seed) => Envelope::new(seed),
+            SeedMaterial::Encrypted(seed) => Envelope::new(seed),
+            SeedMaterial::HardwareReference(reference) => Envelope::new(reference),
+            SeedMaterial::Redacted => Envelope::unit(),
         }
         .add_type("SeedMaterial")
     }
@@ -105,11 +267,25 @@ impl TryFrom<Envelope> for SeedMaterial {
     type Error = bc_envelope::Error;
 
     fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        // An elided seed cannot be type-checked; see
+        // `Zewif::to_watch_only_envelope`.
+        if envelope.is_elided() {
+            return Ok(SeedMaterial::Redacted);
+        }
         envelope.check_type("SeedMaterial")?;
+        if envelope.is_subject_unit() {
+            return Ok(SeedMaterial::Redacted);
+        }
         if let Ok(mnemonic) = Bip39Mnemonic::try_from(envelope.clone()) {
             Ok(SeedMaterial::Bip39Mnemonic(mnemonic))
         } else if let Ok(seed) = LegacySeed::try_from(envelope.clone()) {
             Ok(SeedMaterial::LegacySeed(seed))
+        } else if let Ok(seed) = ZcashdLegacySeed::try_from(envelope.clone()) {
+            Ok(SeedMaterial::LegacyZcashd(seed))
+        } else if let Ok(seed) = EncryptedSeed::try_from(envelope.clone()) {
+            Ok(SeedMaterial::Encrypted(seed))
+        } else if let Ok(reference) = HardwareSeedReference::try_from(envelope.clone()) {
+            Ok(SeedMaterial::HardwareReference(reference))
         } else {
             Err(Error::InvalidSeedMaterial.into())
         }
@@ -119,17 +295,225 @@ impl TryFrom<Envelope> for SeedMaterial {
 #[cfg(test)]
 mod tests {
     use super::SeedMaterial;
-    use crate::{Bip39Mnemonic, LegacySeed, test_envelope_roundtrip};
+    use crate::{
+        Bip39Mnemonic, Blob, EncryptedSeed, HardwareSeedReference, LegacySeed, SeedFingerprint,
+        ZcashdLegacySeed, test_envelope_roundtrip,
+    };
 
     impl crate::RandomInstance for SeedMaterial {
         fn random() -> Self {
-            if rand::random::<bool>() {
-                SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::random())
-            } else {
-                SeedMaterial::LegacySeed(LegacySeed::random())
+            match rand::random_range(0..5) {
+                0 => SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::random()),
+                1 => SeedMaterial::LegacySeed(LegacySeed::random()),
+                2 => SeedMaterial::LegacyZcashd(ZcashdLegacySeed::random()),
+                3 => SeedMaterial::Encrypted(EncryptedSeed::random()),
+                _ => SeedMaterial::HardwareReference(HardwareSeedReference::random()),
             }
         }
     }
 
     test_envelope_roundtrip!(SeedMaterial);
+
+    #[test]
+    fn test_formatting_redacts_secret() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let mut mnemonic = Bip39Mnemonic::new(phrase, None);
+        mnemonic.set_fingerprint(SeedFingerprint::new([0xab; 32]));
+        let seed = SeedMaterial::Bip39Mnemonic(mnemonic);
+        assert_eq!(
+            format!("{:?}", seed),
+            "SeedMaterial::Bip39Mnemonic(<12 words, fingerprint abababab>)"
+        );
+        assert_eq!(format!("{}", seed), format!("{:?}", seed));
+        assert_eq!(seed.expose_secret().as_deref(), Some(phrase));
+
+        let mut mnemonic = Bip39Mnemonic::new(phrase, None);
+        mnemonic.set_passphrase(Some("TREZOR".to_string()));
+        let formatted = format!("{:?}", mnemonic);
+        assert_eq!(formatted, "Bip39Mnemonic(<12 words, with passphrase>)");
+
+        let seed = SeedMaterial::LegacySeed(LegacySeed::new(vec![0x5a; 32].into(), None));
+        assert_eq!(
+            format!("{:?}", seed),
+            "SeedMaterial::LegacySeed(<32 bytes>)"
+        );
+        assert_eq!(seed.expose_secret(), Some("5a".repeat(32)));
+
+        let seed = SeedMaterial::LegacyZcashd(ZcashdLegacySeed::new(
+            Blob::new([0x5a; 32]),
+            Bip39Mnemonic::new(phrase, None),
+            false,
+        ));
+        assert_eq!(
+            seed.expose_secret(),
+            Some(format!("{}\n{}", phrase, "5a".repeat(32)))
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert!(
+            SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(mnemonic, None))
+                .validate()
+                .is_ok()
+        );
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+        assert!(
+            SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(mnemonic, None))
+                .validate()
+                .is_err()
+        );
+
+        assert!(
+            SeedMaterial::LegacySeed(LegacySeed::new(vec![0u8; 32].into(), None))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            SeedMaterial::LegacySeed(LegacySeed::new(vec![0u8; 16].into(), None))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_encrypted_seed_is_locked() {
+        use crate::{Data, SeedStatus, WalletCryptoParams};
+
+        let params = WalletCryptoParams::new(
+            Data::from_vec(vec![0u8; 48]),
+            Data::from_vec(vec![1u8; 8]),
+            0,
+            25_000,
+        );
+        let fingerprint = SeedFingerprint::new([3u8; 32]);
+        let seed = SeedMaterial::Encrypted(EncryptedSeed::new(
+            Data::from_vec(vec![2u8; 48]),
+            params.clone(),
+            Some(fingerprint),
+        ));
+        assert_eq!(seed.validate().unwrap(), SeedStatus::Locked);
+        assert_eq!(seed.fingerprint().unwrap(), fingerprint);
+        assert!(seed.to_seed().is_err());
+        assert!(seed.expose_secret().is_none());
+
+        let seed = SeedMaterial::Encrypted(EncryptedSeed::new(
+            Data::from_vec(vec![2u8; 48]),
+            params,
+            None,
+        ));
+        assert!(seed.fingerprint().is_err());
+    }
+
+    /// Test vectors from https://github.com/trezor/python-mnemonic
+    /// (`vectors.json`), all using the passphrase "TREZOR".
+    #[test]
+    fn test_bip39_seed_vectors() {
+        let vectors = [
+            (
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+            ),
+            (
+                "legal winner thank year wave sausage worth useful legal winner thank yellow",
+                "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+            ),
+            (
+                "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+                "d71de856f81a8acc65e6fc851a38d4d7ec216fd0796d0a6827a3ad6ed5511a30fa280f12eb2e47ed2ac03b5c462a0358d18d69fe4f985ec81778c1b370b652a8",
+            ),
+            (
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+                "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069",
+            ),
+            (
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon agent",
+                "035895f2f481b1b0f01fcf8c289c794660b289981a78f8106447707fdd9666ca06da5a9a565181599b79f53b844d8a71dd9f439c52a3d7b3e8a79c906ac845fa",
+            ),
+        ];
+        for (phrase, seed) in vectors {
+            let mut mnemonic = Bip39Mnemonic::new(phrase, None);
+            let material = SeedMaterial::Bip39Mnemonic(mnemonic.clone());
+            assert_eq!(
+                hex::encode(material.to_seed_bytes(Some("TREZOR")).unwrap()),
+                seed
+            );
+
+            // Without an explicit passphrase, the recorded one is used.
+            assert_ne!(hex::encode(material.to_seed_bytes(None).unwrap()), seed);
+            mnemonic.set_passphrase(Some("TREZOR".to_string()));
+            let material = SeedMaterial::Bip39Mnemonic(mnemonic);
+            assert_eq!(hex::encode(material.to_seed_bytes(None).unwrap()), seed);
+        }
+
+        let legacy = SeedMaterial::LegacySeed(LegacySeed::new(vec![7u8; 32].into(), None));
+        assert_eq!(legacy.to_seed_bytes(Some("TREZOR")).unwrap(), vec![7u8; 32]);
+    }
+
+    #[test]
+    fn test_hardware_reference_is_external() {
+        use crate::{Blob, SeedStatus};
+
+        let mut reference = HardwareSeedReference::new("Keystone 3 Pro", Blob::new([1, 2, 3, 4]));
+        let seed = SeedMaterial::HardwareReference(reference.clone());
+        assert_eq!(seed.validate().unwrap(), SeedStatus::External);
+        assert!(seed.to_seed().is_err());
+        assert!(seed.fingerprint().is_err());
+        assert_eq!(
+            format!("{}", seed),
+            "SeedMaterial::HardwareSeedReference(<Keystone 3 Pro, master fingerprint 01020304>)"
+        );
+
+        let fingerprint = SeedFingerprint::new([5u8; 32]);
+        reference.set_seed_fingerprint(Some(fingerprint));
+        assert_eq!(
+            SeedMaterial::HardwareReference(reference)
+                .fingerprint()
+                .unwrap(),
+            fingerprint
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        // The ZIP 32 seed fingerprint test vector.
+        let seed =
+            SeedMaterial::LegacySeed(LegacySeed::new((0u8..32).collect::<Vec<_>>().into(), None));
+        assert_eq!(
+            hex::encode(seed.fingerprint().unwrap().as_bytes()),
+            "deff604c246710f7176dead02aa746f2fd8d5389f7072556dcb555fdbe5e3ae3"
+        );
+
+        // A mnemonic is fingerprinted over its BIP-39 seed, so the passphrase
+        // matters.
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let mut mnemonic = Bip39Mnemonic::new(phrase, None);
+        let without = SeedMaterial::Bip39Mnemonic(mnemonic.clone())
+            .fingerprint()
+            .unwrap();
+        mnemonic.set_passphrase(Some("TREZOR".to_string()));
+        let seed = SeedMaterial::Bip39Mnemonic(mnemonic);
+        assert_eq!(
+            seed.fingerprint().unwrap(),
+            SeedFingerprint::from_seed(&seed.to_seed().unwrap()).unwrap()
+        );
+        assert_ne!(seed.fingerprint().unwrap(), without);
+
+        let short = SeedMaterial::LegacySeed(LegacySeed::new(vec![0u8; 16].into(), None));
+        assert!(short.fingerprint().is_err());
+    }
+
+    #[test]
+    fn test_bip39_seed() {
+        // The first BIP-39 English test vector.
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let mut mnemonic = Bip39Mnemonic::new(mnemonic, None);
+        mnemonic.set_passphrase(Some("TREZOR".to_string()));
+        let seed = SeedMaterial::Bip39Mnemonic(mnemonic);
+        assert_eq!(
+            hex::encode(seed.to_seed().unwrap()),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+    }
 }
@@ -0,0 +1,295 @@
+use std::io::{self, Read, Write};
+
+use bc_envelope::prelude::*;
+
+use super::{SpendingKey, ViewingKey};
+use crate::{Blob32, Error, Network, Result, base58check, watch_only::optional_spending_object};
+
+/// A Zcash Sprout payment address and associated key data.
+///
+/// Sprout addresses (zc-prefixed on mainnet, zt-prefixed on testnet) belong to
+/// the original shielded pool. Each address encodes two public keys:
+///
+/// - **Paying key (`a_pk`)**: Identifies the recipient of a note
+/// - **Transmission key (`pk_enc`)**: Used to encrypt notes to the recipient
+///
+/// # Data Preservation
+/// During wallet migration, the following components are preserved:
+///
+/// - **Address strings**: The canonical string representation (e.g., "zc...")
+/// - **Key components**: The paying key and transmission key, as stored by
+///   zcashd
+/// - **Spending Keys**: Preserved for spending capability when available
+/// - **Viewing Keys**: Preserved for watch-only addresses
+///
+/// # Examples
+/// ```
+/// # use zewif::{Blob, sprout::{self, SpendingKey}};
+/// let mut address = sprout::Address::new(
+///     "zcexampleaddress".to_string(),
+///     Blob::new([1u8; 32]),
+///     Blob::new([2u8; 32]),
+/// );
+/// address.set_spending_key(SpendingKey::new(Blob::new([0u8; 32])));
+/// assert!(address.spending_key().is_some());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Address {
+    /// The string representation of the address. This is used as a unique
+    /// identifier within the wallet.
+    address: String,
+
+    /// The paying key (`a_pk`).
+    paying_key: Blob32,
+
+    /// The transmission key (`pk_enc`).
+    transmission_key: Blob32,
+
+    /// Optional spending key for this address.
+    spending_key: Option<SpendingKey>,
+
+    /// Optional viewing key for this address, present for watch-only
+    /// addresses.
+    viewing_key: Option<ViewingKey>,
+}
+
+impl Address {
+    pub fn new(address: String, paying_key: Blob32, transmission_key: Blob32) -> Self {
+        Self {
+            address,
+            paying_key,
+            transmission_key,
+            spending_key: None,
+            viewing_key: None,
+        }
+    }
+
+    /// Decodes a Base58Check-encoded Sprout address for the given network.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidBase58Check`] if the string is not valid
+    /// Base58Check, [`Error::AddressNetworkMismatch`] if it is a Sprout address
+    /// for a different network, and [`Error::InvalidAddress`] if it is not a
+    /// Sprout address at all.
+    pub fn from_string(s: &str, network: Network) -> Result<Self> {
+        let data = base58check::decode(s)?;
+        if data.len() != 2 + 64 {
+            return Err(not_sprout(s));
+        }
+        let (prefix, keys) = data.split_at(2);
+        if prefix != network.sprout_address_prefix() {
+            let other_network = [Network::Main, Network::Test]
+                .iter()
+                .any(|n| prefix == n.sprout_address_prefix());
+            return if other_network {
+                Err(Error::AddressNetworkMismatch {
+                    address: s.to_string(),
+                    expected: network.into(),
+                })
+            } else {
+                Err(not_sprout(s))
+            };
+        }
+        let (paying_key, transmission_key) = keys.split_at(32);
+        Ok(Self::new(
+            s.to_string(),
+            Blob32::from_slice(paying_key)?,
+            Blob32::from_slice(transmission_key)?,
+        ))
+    }
+
+    /// Encodes the address's key components as a Base58Check Sprout address for
+    /// the given network.
+    pub fn to_string(&self, network: Network) -> String {
+        let mut keys = self.paying_key.to_vec();
+        keys.extend_from_slice(self.transmission_key.as_slice());
+        base58check::encode(&network.sprout_address_prefix(), &keys)
+    }
+
+    /// Reads the key components of a payment address in the zcashd
+    /// serialization.
+    ///
+    /// zcashd stores only the paying key and transmission key, so the encoded
+    /// address string is supplied by the caller.
+    ///
+    /// # Errors
+    /// Returns an IO error if the reader does not contain 64 bytes.
+    pub fn read<R: Read>(address: String, mut reader: R) -> io::Result<Self> {
+        let mut paying_key = [0u8; 32];
+        let mut transmission_key = [0u8; 32];
+        reader.read_exact(&mut paying_key)?;
+        reader.read_exact(&mut transmission_key)?;
+        Ok(Self::new(
+            address,
+            Blob32::new(paying_key),
+            Blob32::new(transmission_key),
+        ))
+    }
+
+    /// Writes the key components of the payment address in the zcashd
+    /// serialization.
+    ///
+    /// # Errors
+    /// Returns an IO error if writing fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.paying_key.as_slice())?;
+        writer.write_all(self.transmission_key.as_slice())
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn set_address(&mut self, address: String) {
+        self.address = address;
+    }
+
+    /// Returns the paying key (`a_pk`) of the address.
+    pub fn paying_key(&self) -> &Blob32 {
+        &self.paying_key
+    }
+
+    /// Returns the transmission key (`pk_enc`) of the address.
+    pub fn transmission_key(&self) -> &Blob32 {
+        &self.transmission_key
+    }
+
+    pub fn spending_key(&self) -> Option<&SpendingKey> {
+        self.spending_key.as_ref()
+    }
+
+    pub fn set_spending_key(&mut self, key: SpendingKey) {
+        self.spending_key = Some(key);
+    }
+
+    pub fn viewing_key(&self) -> Option<&ViewingKey> {
+        self.viewing_key.as_ref()
+    }
+
+    pub fn set_viewing_key(&mut self, key: ViewingKey) {
+        self.viewing_key = Some(key);
+    }
+}
+
+fn not_sprout(s: &str) -> Error {
+    Error::InvalidAddress {
+        address: s.to_string(),
+        reason: "not a Sprout address".to_string(),
+    }
+}
+
+impl From<Address> for Envelope {
+    fn from(value: Address) -> Self {
+        Envelope::new(value.address)
+            .add_type("SproutAddress")
+            .add_assertion("paying_key", value.paying_key)
+            .add_assertion("transmission_key", value.transmission_key)
+            .add_optional_assertion("spending_key", value.spending_key)
+            .add_optional_assertion("viewing_key", value.viewing_key)
+    }
+}
+
+impl TryFrom<Envelope> for Address {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SproutAddress")?;
+        let address = envelope.extract_subject()?;
+        let paying_key = envelope.extract_object_for_predicate("paying_key")?;
+        let transmission_key = envelope.extract_object_for_predicate("transmission_key")?;
+        let spending_key = optional_spending_object(&envelope, "spending_key")?;
+        let viewing_key = envelope.try_optional_object_for_predicate("viewing_key")?;
+        Ok(Self {
+            address,
+            paying_key,
+            transmission_key,
+            spending_key,
+            viewing_key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{
+        Blob, Error, Network, RandomInstance,
+        sprout::{SpendingKey, ViewingKey},
+        test_envelope_roundtrip,
+    };
+
+    use super::Address;
+
+    impl RandomInstance for Address {
+        fn random() -> Self {
+            Self {
+                address: String::random(),
+                paying_key: Blob::random(),
+                transmission_key: Blob::random(),
+                spending_key: SpendingKey::opt_random(),
+                viewing_key: ViewingKey::opt_random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(Address);
+
+    #[test]
+    fn test_zcashd_serialization_roundtrip() {
+        let address = Address::new("zcexample".to_string(), Blob::random(), Blob::random());
+        let mut buf = Vec::new();
+        address.write(&mut buf).unwrap();
+        assert_eq!(buf.len(), 64);
+        let decoded = Address::read("zcexample".to_string(), Cursor::new(&buf)).unwrap();
+        assert_eq!(decoded, address);
+    }
+
+    /// Encodes paying key `[0, 1, ..., 31]` and transmission key
+    /// `[32, 33, ..., 63]`, as produced by the `zcash_address` crate.
+    const MAINNET_ADDRESS: &str = "zc8E7R3StiJq1T1UaCdygazuEVBe9xddGdYBLMe8WNgnBTVRGiGwY9MEeVKqhWNtmbPmwi4S1uJtPobqCq4azuLJrKCFjcj";
+    const TESTNET_ADDRESS: &str = "ztJ1GEq3ss9HyHGpFL7xqGfgjtVQzRLgY8zxw91ngv5QkxCBmdC4JxmQKHmTbX8nvwXfXTw5EvH7xE2dBAFy4QFpRU7fB5b";
+
+    #[test]
+    fn test_string_encoding() {
+        let keys: Vec<u8> = (0u8..64).collect();
+        let address = Address::from_string(MAINNET_ADDRESS, Network::Main).unwrap();
+        assert_eq!(address.address(), MAINNET_ADDRESS);
+        assert_eq!(address.paying_key().as_slice(), &keys[..32]);
+        assert_eq!(address.transmission_key().as_slice(), &keys[32..]);
+        assert_eq!(address.to_string(Network::Main), MAINNET_ADDRESS);
+        assert_eq!(address.to_string(Network::Test), TESTNET_ADDRESS);
+
+        let address = Address::from_string(TESTNET_ADDRESS, Network::Regtest).unwrap();
+        assert_eq!(address.paying_key().as_slice(), &keys[..32]);
+
+        // The all-zero address from the `zcash_address` test suite.
+        let zero = Address::new(String::new(), Blob::default(), Blob::default());
+        assert_eq!(
+            zero.to_string(Network::Main),
+            "zc8E5gYid86n4bo2Usdq1cpr7PpfoJGzttwBHEEgGhGkLUg7SPPVFNB2AkRFXZ7usfphup5426dt1buMmY3fkYeRrQGLa8y"
+        );
+    }
+
+    #[test]
+    fn test_string_decoding_errors() {
+        assert!(matches!(
+            Address::from_string(MAINNET_ADDRESS, Network::Test),
+            Err(Error::AddressNetworkMismatch { .. })
+        ));
+
+        let mut corrupt = MAINNET_ADDRESS.to_string();
+        corrupt.replace_range(10..11, "2");
+        assert!(matches!(
+            Address::from_string(&corrupt, Network::Main),
+            Err(Error::InvalidBase58Check { .. })
+        ));
+
+        // A transparent address is valid Base58Check but not a Sprout address.
+        assert!(matches!(
+            Address::from_string("t1Hsc1LR8yKnbbe3twRp88p6vFfC5t7DLbs", Network::Main),
+            Err(Error::InvalidAddress { .. })
+        ));
+    }
+}
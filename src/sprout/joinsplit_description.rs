@@ -0,0 +1,455 @@
+use std::io::{self, Read, Write};
+
+use bc_envelope::prelude::*;
+
+use crate::{Amount, Blob, Blob32, Indexed, Result};
+
+use super::{MerkleHashSprout, SproutNullifier};
+
+/// The size in bytes of a BCTV14 zk-SNARK proof, used by JoinSplits in v2 and
+/// v3 transactions.
+pub const BCTV14_PROOF_SIZE: usize = 296;
+
+/// The size in bytes of a Groth16 zk-SNARK proof, used by JoinSplits in v4
+/// transactions.
+pub const GROTH16_PROOF_SIZE: usize = 192;
+
+/// The size in bytes of a Sprout note ciphertext.
+pub const SPROUT_CIPHERTEXT_SIZE: usize = 601;
+
+/// The zero-knowledge proof of a JoinSplit, whose proving system depends on
+/// the version of the transaction containing it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoinSplitProof {
+    /// A BCTV14 proof, used before the Sapling network upgrade.
+    Bctv14(Blob<BCTV14_PROOF_SIZE>),
+
+    /// A Groth16 proof, used from the Sapling network upgrade onward.
+    Groth16(Blob<GROTH16_PROOF_SIZE>),
+}
+
+impl JoinSplitProof {
+    /// Returns the bytes of the proof.
+    pub fn as_slice(&self) -> &[u8] {
+        match self {
+            JoinSplitProof::Bctv14(proof) => proof.as_slice(),
+            JoinSplitProof::Groth16(proof) => proof.as_slice(),
+        }
+    }
+}
+
+impl From<JoinSplitProof> for Envelope {
+    fn from(value: JoinSplitProof) -> Self {
+        match value {
+            JoinSplitProof::Bctv14(proof) => Envelope::new(proof).add_type("BCTV14Proof"),
+            JoinSplitProof::Groth16(proof) => Envelope::new(proof).add_type("Groth16Proof"),
+        }
+    }
+}
+
+impl TryFrom<Envelope> for JoinSplitProof {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        if envelope.has_type("BCTV14Proof") {
+            Ok(JoinSplitProof::Bctv14(envelope.extract_subject()?))
+        } else {
+            envelope.check_type("Groth16Proof")?;
+            Ok(JoinSplitProof::Groth16(envelope.extract_subject()?))
+        }
+    }
+}
+
+/// The public data of a single JoinSplit within a transaction.
+///
+/// A JoinSplit is the unit of transfer in the Sprout shielded pool. Each one
+/// spends up to two Sprout notes (revealing their nullifiers) and creates up
+/// to two new notes (publishing their commitments and ciphertexts), while
+/// optionally moving value to or from the transparent pool.
+///
+/// # Zcash Concept Relation
+/// As specified in the Zcash protocol specification (§7.2), the consensus
+/// encoding of a JoinSplit description is:
+///
+/// - **vpub_old**: The value entering the Sprout pool from the transparent pool
+/// - **vpub_new**: The value leaving the Sprout pool to the transparent pool
+/// - **anchor**: The root of the Sprout note commitment tree used for the spends
+/// - **nullifiers**: The nullifiers of the two notes being spent
+/// - **commitments**: The commitments to the two notes being created
+/// - **ephemeral_key**: The ephemeral public key for note encryption
+/// - **random_seed**: The seed used to derive the new notes' `rho` values
+/// - **macs**: The message authentication tags binding the spends to the
+///   transaction
+/// - **proof**: A BCTV14 proof in v2 and v3 transactions, and a Groth16 proof
+///   from v4 onward
+/// - **ciphertexts**: The encrypted plaintexts of the two new notes
+///
+/// # Data Preservation
+/// Preserving every JoinSplit allows the receiving wallet to re-derive which
+/// Sprout notes belong to it, and which have been spent, without refetching
+/// the transaction from the chain.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct JoinSplitDescription {
+    /// The index of the JoinSplit within the transaction.
+    index: usize,
+    vpub_old: Amount,
+    vpub_new: Amount,
+    anchor: MerkleHashSprout,
+    nullifiers: [SproutNullifier; 2],
+    commitments: [Blob32; 2],
+    ephemeral_key: Blob32,
+    random_seed: Blob32,
+    macs: [Blob32; 2],
+    proof: JoinSplitProof,
+    ciphertexts: [Blob<SPROUT_CIPHERTEXT_SIZE>; 2],
+}
+
+impl Indexed for JoinSplitDescription {
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
+
+impl JoinSplitDescription {
+    /// Creates a new JoinSplit description from its constituent parts.
+    ///
+    /// # Errors
+    /// Returns [`Error::AmountUnderflow`](crate::Error::AmountUnderflow) if
+    /// either public value is negative, as neither can be encoded.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_parts(
+        vpub_old: Amount,
+        vpub_new: Amount,
+        anchor: MerkleHashSprout,
+        nullifiers: [SproutNullifier; 2],
+        commitments: [Blob32; 2],
+        ephemeral_key: Blob32,
+        random_seed: Blob32,
+        macs: [Blob32; 2],
+        proof: JoinSplitProof,
+        ciphertexts: [Blob<SPROUT_CIPHERTEXT_SIZE>; 2],
+    ) -> Result<Self> {
+        Ok(Self {
+            index: 0,
+            vpub_old: Amount::from_nonnegative_i64(vpub_old.into())?,
+            vpub_new: Amount::from_nonnegative_i64(vpub_new.into())?,
+            anchor,
+            nullifiers,
+            commitments,
+            ephemeral_key,
+            random_seed,
+            macs,
+            proof,
+            ciphertexts,
+        })
+    }
+
+    /// Returns the value entering the Sprout pool from the transparent pool.
+    pub fn vpub_old(&self) -> Amount {
+        self.vpub_old
+    }
+
+    /// Returns the value leaving the Sprout pool to the transparent pool.
+    pub fn vpub_new(&self) -> Amount {
+        self.vpub_new
+    }
+
+    /// Returns the root of the Sprout note commitment tree against which the
+    /// spends were proven.
+    pub fn anchor(&self) -> &MerkleHashSprout {
+        &self.anchor
+    }
+
+    /// Returns the nullifiers of the notes spent by the JoinSplit.
+    pub fn nullifiers(&self) -> &[SproutNullifier; 2] {
+        &self.nullifiers
+    }
+
+    /// Returns the commitments to the notes created by the JoinSplit.
+    pub fn commitments(&self) -> &[Blob32; 2] {
+        &self.commitments
+    }
+
+    /// Returns the ephemeral public key used to encrypt the new notes.
+    pub fn ephemeral_key(&self) -> &Blob32 {
+        &self.ephemeral_key
+    }
+
+    /// Returns the seed used to derive the new notes' `rho` values.
+    pub fn random_seed(&self) -> &Blob32 {
+        &self.random_seed
+    }
+
+    /// Returns the message authentication tags of the spends.
+    pub fn macs(&self) -> &[Blob32; 2] {
+        &self.macs
+    }
+
+    /// Returns the zero-knowledge proof of the JoinSplit.
+    pub fn proof(&self) -> &JoinSplitProof {
+        &self.proof
+    }
+
+    /// Returns the encrypted plaintexts of the notes created by the JoinSplit.
+    pub fn ciphertexts(&self) -> &[Blob<SPROUT_CIPHERTEXT_SIZE>; 2] {
+        &self.ciphertexts
+    }
+
+    /// Reads a JoinSplit description in its consensus encoding.
+    ///
+    /// The size of the proof depends on the version of the containing
+    /// transaction: BCTV14 proofs are read for versions 2 and 3, and Groth16
+    /// proofs for version 4 and later.
+    ///
+    /// # Errors
+    /// Returns an IO error if the reader does not contain a complete
+    /// JoinSplit description, or if either public value is out of range.
+    pub fn read<R: Read>(mut reader: R, tx_version: u32) -> io::Result<Self> {
+        fn read_array<R: Read, const N: usize>(reader: &mut R) -> io::Result<Blob<N>> {
+            let mut bytes = [0u8; N];
+            reader.read_exact(&mut bytes)?;
+            Ok(Blob::new(bytes))
+        }
+
+        fn read_amount<R: Read>(reader: &mut R) -> io::Result<Amount> {
+            Amount::from_u64_le_bytes(*read_array::<R, 8>(reader)?.as_bytes())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+        }
+
+        fn read_nullifier<R: Read>(reader: &mut R) -> io::Result<SproutNullifier> {
+            Ok(SproutNullifier::new(*read_array(reader)?.as_bytes()))
+        }
+
+        let vpub_old = read_amount(&mut reader)?;
+        let vpub_new = read_amount(&mut reader)?;
+        let anchor = MerkleHashSprout::new(*read_array(&mut reader)?.as_bytes());
+        let nullifiers = [read_nullifier(&mut reader)?, read_nullifier(&mut reader)?];
+        let commitments = [read_array(&mut reader)?, read_array(&mut reader)?];
+        let ephemeral_key = read_array(&mut reader)?;
+        let random_seed = read_array(&mut reader)?;
+        let macs = [read_array(&mut reader)?, read_array(&mut reader)?];
+        let proof = if tx_version >= 4 {
+            JoinSplitProof::Groth16(read_array(&mut reader)?)
+        } else {
+            JoinSplitProof::Bctv14(read_array(&mut reader)?)
+        };
+        let ciphertexts = [read_array(&mut reader)?, read_array(&mut reader)?];
+
+        Ok(Self {
+            index: 0,
+            vpub_old,
+            vpub_new,
+            anchor,
+            nullifiers,
+            commitments,
+            ephemeral_key,
+            random_seed,
+            macs,
+            proof,
+            ciphertexts,
+        })
+    }
+
+    /// Writes the JoinSplit description in its consensus encoding.
+    ///
+    /// # Errors
+    /// Returns an IO error if writing fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.vpub_old.to_i64_le_bytes())?;
+        writer.write_all(&self.vpub_new.to_i64_le_bytes())?;
+        writer.write_all(self.anchor.as_slice())?;
+        for nullifier in &self.nullifiers {
+            writer.write_all(nullifier.as_slice())?;
+        }
+        for commitment in &self.commitments {
+            writer.write_all(commitment.as_slice())?;
+        }
+        writer.write_all(self.ephemeral_key.as_slice())?;
+        writer.write_all(self.random_seed.as_slice())?;
+        for mac in &self.macs {
+            writer.write_all(mac.as_slice())?;
+        }
+        writer.write_all(self.proof.as_slice())?;
+        for ciphertext in &self.ciphertexts {
+            writer.write_all(ciphertext.as_slice())?;
+        }
+        Ok(())
+    }
+}
+
+impl From<JoinSplitDescription> for Envelope {
+    fn from(value: JoinSplitDescription) -> Self {
+        Envelope::new(value.index)
+            .add_type("JoinSplitDescription")
+            .add_assertion("vpub_old", value.vpub_old)
+            .add_assertion("vpub_new", value.vpub_new)
+            .add_assertion("anchor", value.anchor)
+            .add_assertion("nullifiers", value.nullifiers.to_vec())
+            .add_assertion("commitments", value.commitments.to_vec())
+            .add_assertion("ephemeral_key", value.ephemeral_key)
+            .add_assertion("random_seed", value.random_seed)
+            .add_assertion("macs", value.macs.to_vec())
+            .add_assertion("proof", value.proof)
+            .add_assertion("ciphertexts", value.ciphertexts.to_vec())
+    }
+}
+
+impl TryFrom<Envelope> for JoinSplitDescription {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        fn pair<T>(envelope: &Envelope, predicate: &str) -> bc_envelope::Result<[T; 2]>
+        where
+            Vec<T>: TryFrom<CBOR, Error = dcbor::Error> + 'static,
+        {
+            envelope
+                .extract_object_for_predicate::<Vec<T>>(predicate)?
+                .try_into()
+                .map_err(|_| {
+                    bc_envelope::Error::General(format!("{} must have two elements", predicate))
+                })
+        }
+
+        envelope.check_type("JoinSplitDescription")?;
+        let index = envelope.extract_subject()?;
+        let vpub_old = envelope.extract_object_for_predicate("vpub_old")?;
+        let vpub_new = envelope.extract_object_for_predicate("vpub_new")?;
+        let anchor = envelope.extract_object_for_predicate("anchor")?;
+        let nullifiers = pair(&envelope, "nullifiers")?;
+        let commitments = pair(&envelope, "commitments")?;
+        let ephemeral_key = envelope.extract_object_for_predicate("ephemeral_key")?;
+        let random_seed = envelope.extract_object_for_predicate("random_seed")?;
+        let macs = pair(&envelope, "macs")?;
+        let proof = envelope.try_object_for_predicate("proof")?;
+        let ciphertexts = pair(&envelope, "ciphertexts")?;
+        Ok(Self {
+            index,
+            vpub_old,
+            vpub_new,
+            anchor,
+            nullifiers,
+            commitments,
+            ephemeral_key,
+            random_seed,
+            macs,
+            proof,
+            ciphertexts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{Amount, Blob, RandomInstance, test_envelope_roundtrip};
+
+    use super::{JoinSplitDescription, JoinSplitProof};
+    use crate::sprout::{MerkleHashSprout, SproutNullifier};
+
+    impl RandomInstance for JoinSplitDescription {
+        fn random() -> Self {
+            let proof = if bc_rand::rng_random_bool(&mut bc_rand::thread_rng()) {
+                JoinSplitProof::Bctv14(Blob::random())
+            } else {
+                JoinSplitProof::Groth16(Blob::random())
+            };
+            Self {
+                index: 0,
+                vpub_old: Amount::from_u64(u32::random() as u64).unwrap(),
+                vpub_new: Amount::from_u64(u32::random() as u64).unwrap(),
+                anchor: MerkleHashSprout::random(),
+                nullifiers: [SproutNullifier::random(), SproutNullifier::random()],
+                commitments: [Blob::random(), Blob::random()],
+                ephemeral_key: Blob::random(),
+                random_seed: Blob::random(),
+                macs: [Blob::random(), Blob::random()],
+                proof,
+                ciphertexts: [Blob::random(), Blob::random()],
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(JoinSplitDescription);
+
+    fn fixture(hex_lines: &str) -> Vec<u8> {
+        hex::decode(hex_lines.split_whitespace().collect::<String>()).unwrap()
+    }
+
+    /// The JoinSplit of the third transaction in the ZIP 143 test vectors.
+    #[test]
+    fn test_bctv14_fixture() {
+        let data = fixture(include_str!("test_data/joinsplit_bctv14.hex"));
+        assert_eq!(data.len(), 1802);
+        let js = JoinSplitDescription::read(Cursor::new(&data), 3).unwrap();
+        assert!(matches!(js.proof(), JoinSplitProof::Bctv14(_)));
+        assert_eq!(js.vpub_old(), Amount::zero());
+        assert_eq!(
+            js.anchor().to_hex(),
+            "e2321d146071789d2335934a680614e83562f82dfd405b54a45eb32c165448d4"
+        );
+        assert_eq!(
+            js.nullifiers()[0].to_hex(),
+            "d5d61ca2859585369f53f1a137e9e82b67b8fdaf01bda54a317311896ae10280"
+        );
+        assert_eq!(
+            hex::encode(js.commitments()[1]),
+            "34b262d5853754f1f17771cfb7503072655753fa3f54ecc587e9f83b58191609"
+        );
+
+        let mut buf = Vec::new();
+        js.write(&mut buf).unwrap();
+        assert_eq!(buf, data);
+
+        let parts = |vpub_old| {
+            JoinSplitDescription::from_parts(
+                vpub_old,
+                js.vpub_new(),
+                *js.anchor(),
+                *js.nullifiers(),
+                *js.commitments(),
+                *js.ephemeral_key(),
+                *js.random_seed(),
+                *js.macs(),
+                js.proof().clone(),
+                js.ciphertexts().clone(),
+            )
+        };
+        assert_eq!(parts(js.vpub_old()).unwrap(), js);
+        assert!(parts(-Amount::COIN).is_err());
+    }
+
+    /// The JoinSplit of the sixth transaction in the ZIP 243 test vectors.
+    #[test]
+    fn test_groth16_fixture() {
+        let data = fixture(include_str!("test_data/joinsplit_groth16.hex"));
+        assert_eq!(data.len(), 1698);
+        let js = JoinSplitDescription::read(Cursor::new(&data), 4).unwrap();
+        assert!(matches!(js.proof(), JoinSplitProof::Groth16(_)));
+        assert_eq!(
+            js.anchor().to_hex(),
+            "5d1d1165d760702ef103d2236726902359be8d797352f96d2246a2ee0af80a2a"
+        );
+        assert_eq!(
+            js.nullifiers()[0].to_hex(),
+            "2d89a58530d6e36bd33a00c1b893d6ff8f900144151bee34c7944b99ed6e7945"
+        );
+        assert_eq!(
+            hex::encode(js.commitments()[1]),
+            "bb557e88453f188cacbe922987bbe3b3d976826135c103b6ca182b63e9e67f83"
+        );
+
+        let mut buf = Vec::new();
+        js.write(&mut buf).unwrap();
+        assert_eq!(buf, data);
+
+        // The same bytes are too short to hold a BCTV14 JoinSplit.
+        assert!(JoinSplitDescription::read(Cursor::new(&data), 3).is_err());
+    }
+}
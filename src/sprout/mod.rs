@@ -0,0 +1,54 @@
+//! # Sprout Protocol Components
+//!
+//! Sprout is the original shielded protocol in Zcash, launched with the network
+//! in October 2016. It has since been superseded by Sapling and Orchard, and
+//! no new funds may enter the Sprout pool, but existing Sprout notes remain
+//! spendable and older zcashd wallets still hold them.
+//!
+//! This module contains types that represent Sprout components within the
+//! ZeWIF wallet interchange format:
+//!
+//! ## Key Components
+//!
+//! - [`Address`]: A Sprout payment address (zc-prefixed), with its paying key
+//!   and transmission key
+//! - [`SpendingKey`]: The Sprout spending key (`a_sk`)
+//! - [`ViewingKey`]: The Sprout viewing key, which can detect and decrypt
+//!   incoming notes without spending capability
+//!
+//! ## Note Components
+//!
+//! - [`Note`]: The plaintext of a received Sprout note
+//! - [`SproutWitness`]: Witness proving a note commitment exists in the
+//!   29-level Sprout note commitment tree
+//! - [`SproutReceivedNote`]: A received note with its witness
+//!
+//! ## Transaction Components
+//!
+//! - [`JoinSplitDescription`]: The public data of a JoinSplit in a transaction
+//! - [`SproutNullifier`]: The value revealed when a Sprout note is spent
+//!
+//! ## Migration
+//!
+//! - [`SproutMigrationStatus`]: The state of zcashd's Sprout-to-Sapling
+//!   migration for an account
+//!
+//! ## Protocol Characteristics
+//!
+//! * **JoinSplits**: Sprout transfers are made with JoinSplit descriptions,
+//!   each of which spends up to two notes and creates up to two notes
+//! * **No Diversification**: Each spending key has exactly one payment address
+//! * **No HD Derivation**: Sprout keys are generated independently and cannot
+//!   be recovered from a seed, so they must be preserved explicitly
+
+use crate::mod_use;
+
+mod_use!(address);
+mod_use!(joinsplit_description);
+mod_use!(note);
+mod_use!(spending_key);
+mod_use!(sprout_migration_status);
+mod_use!(sprout_nullifier);
+mod_use!(sprout_received_note);
+mod_use!(sprout_witness);
+mod_use!(viewing_key);
@@ -0,0 +1,143 @@
+use bc_envelope::prelude::*;
+
+use crate::{Amount, Blob32, Error, Result};
+
+/// The plaintext of a Sprout note received by the wallet.
+///
+/// A Sprout note is the unit of value in the Sprout shielded pool. Only its
+/// commitment appears on chain; a wallet that has decrypted the note holds the
+/// plaintext components needed to recompute the commitment, derive the note's
+/// nullifier, and spend it.
+///
+/// # Zcash Concept Relation
+/// As specified in the Zcash protocol specification (§3.2), a Sprout note is
+/// the tuple `(a_pk, v, rho, rcm)`:
+///
+/// - **Paying key (`a_pk`)**: The paying key of the recipient address
+/// - **Value**: The amount of ZEC held by the note
+/// - **Rho**: The value from which the note's nullifier is derived
+/// - **R**: The commitment trapdoor (`rcm`)
+///
+/// # Data Preservation
+/// During wallet migration, the complete note plaintext must be preserved for
+/// every unspent note. Without it, the receiving wallet cannot recompute the
+/// nullifier needed to detect spends, nor construct a JoinSplit to spend the
+/// note.
+///
+/// # Examples
+/// ```
+/// # use zewif::{sprout, Amount, Blob};
+/// # use zewif::Result;
+/// # fn example() -> Result<()> {
+/// let note = sprout::Note::from_parts(
+///     Amount::from_u64(10_000_000)?,
+///     Blob::new([1u8; 32]),
+///     Blob::new([2u8; 32]),
+///     Blob::new([3u8; 32]),
+/// )?;
+///
+/// let zats: i64 = note.value().into();
+/// assert_eq!(zats, 10_000_000);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Note {
+    /// The value of the note, in zatoshis.
+    value: Amount,
+
+    /// The value from which the note's nullifier is derived.
+    rho: Blob32,
+
+    /// The commitment trapdoor.
+    r: Blob32,
+
+    /// The paying key of the address that received the note.
+    a_pk: Blob32,
+}
+
+impl Note {
+    /// Creates a new Sprout note from its constituent parts.
+    ///
+    /// # Errors
+    /// Returns [`Error::NegativeNoteValue`] if `value` is negative.
+    pub fn from_parts(value: Amount, rho: Blob32, r: Blob32, a_pk: Blob32) -> Result<Self> {
+        if value.is_negative() {
+            return Err(Error::NegativeNoteValue {
+                pool: "Sprout",
+                value,
+            });
+        }
+        Ok(Self {
+            value,
+            rho,
+            r,
+            a_pk,
+        })
+    }
+
+    /// Returns the value of the note.
+    pub fn value(&self) -> Amount {
+        self.value
+    }
+
+    /// Returns the value from which the note's nullifier is derived.
+    pub fn rho(&self) -> &Blob32 {
+        &self.rho
+    }
+
+    /// Returns the commitment trapdoor of the note.
+    pub fn r(&self) -> &Blob32 {
+        &self.r
+    }
+
+    /// Returns the paying key of the address that received the note.
+    pub fn a_pk(&self) -> &Blob32 {
+        &self.a_pk
+    }
+}
+
+impl From<Note> for Envelope {
+    fn from(value: Note) -> Self {
+        Envelope::new(value.a_pk)
+            .add_type("SproutNote")
+            .add_assertion("value", value.value)
+            .add_assertion("rho", value.rho)
+            .add_assertion("r", value.r)
+    }
+}
+
+impl TryFrom<Envelope> for Note {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SproutNote")?;
+        let a_pk = envelope.extract_subject()?;
+        let value: Amount = envelope.extract_object_for_predicate("value")?;
+        let rho = envelope.extract_object_for_predicate("rho")?;
+        let r = envelope.extract_object_for_predicate("r")?;
+        Self::from_parts(value, rho, r, a_pk)
+            .map_err(|e| bc_envelope::Error::General(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Amount, Blob, test_envelope_roundtrip};
+
+    use super::Note;
+
+    impl crate::RandomInstance for Note {
+        fn random() -> Self {
+            Self {
+                value: Amount::random_nonnegative(),
+                rho: Blob::random(),
+                r: Blob::random(),
+                a_pk: Blob::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(Note);
+}
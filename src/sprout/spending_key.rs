@@ -0,0 +1,129 @@
+use std::io::{self, Read, Write};
+
+use bc_envelope::prelude::*;
+
+use crate::Blob32;
+
+/// A Sprout spending key (`a_sk`).
+///
+/// The Sprout spending key is a 252-bit value from which the address's paying
+/// key and transmission key are derived. zcashd serializes it as 32 bytes, the
+/// four most significant bits of the first byte being zero.
+///
+/// # Data Preservation
+/// Sprout keys are not derived from the wallet seed, so the spending key must
+/// be preserved for every Sprout address holding funds; it cannot be recovered
+/// any other way.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use zewif::sprout::SpendingKey;
+/// # fn example() -> std::io::Result<()> {
+/// let key = SpendingKey::read(Cursor::new([0x0fu8; 32]))?;
+///
+/// // Keys wider than 252 bits are rejected
+/// assert!(SpendingKey::read(Cursor::new([0xffu8; 32])).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct SpendingKey(Blob32);
+
+impl std::fmt::Debug for SpendingKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "SpendingKey(<redacted>)")
+    }
+}
+
+impl SpendingKey {
+    /// Creates a spending key from its 32-byte serialization.
+    pub fn new(a_sk: Blob32) -> Self {
+        Self(a_sk)
+    }
+
+    /// Returns the 32-byte serialization of the key.
+    pub fn as_blob(&self) -> &Blob32 {
+        &self.0
+    }
+
+    /// Reads a spending key in the zcashd serialization.
+    ///
+    /// # Errors
+    /// Returns an IO error if the reader does not contain 32 bytes, or if the
+    /// value does not fit in 252 bits.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut bytes = [0u8; 32];
+        reader.read_exact(&mut bytes)?;
+        if bytes[0] & 0xf0 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Sprout spending key has more than 252 bits",
+            ));
+        }
+        Ok(Self(Blob32::new(bytes)))
+    }
+
+    /// Writes the spending key in the zcashd serialization.
+    ///
+    /// # Errors
+    /// Returns an IO error if writing fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.0.as_slice())
+    }
+}
+
+impl From<SpendingKey> for Envelope {
+    fn from(value: SpendingKey) -> Self {
+        Envelope::new(value.0).add_type("SproutSpendingKey")
+    }
+}
+
+impl TryFrom<Envelope> for SpendingKey {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SproutSpendingKey")?;
+        Ok(Self(envelope.extract_subject()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{RandomInstance, test_envelope_roundtrip};
+
+    use super::SpendingKey;
+
+    impl RandomInstance for SpendingKey {
+        fn random() -> Self {
+            let mut bytes = [0u8; 32];
+            bytes.iter_mut().for_each(|b| *b = u8::random());
+            bytes[0] &= 0x0f;
+            Self(crate::Blob32::new(bytes))
+        }
+    }
+
+    test_envelope_roundtrip!(SpendingKey);
+
+    #[test]
+    fn test_zcashd_serialization_roundtrip() {
+        let key = SpendingKey::random();
+        let mut buf = Vec::new();
+        key.write(&mut buf).unwrap();
+        assert_eq!(buf.len(), 32);
+        assert_eq!(SpendingKey::read(Cursor::new(&buf)).unwrap(), key);
+    }
+
+    #[test]
+    fn test_debug_redacts_key() {
+        let key = SpendingKey::new(crate::Blob32::new([0x0a; 32]));
+        assert_eq!(format!("{:?}", key), "SpendingKey(<redacted>)");
+    }
+}
@@ -0,0 +1,128 @@
+use bc_envelope::prelude::*;
+
+use crate::TxId;
+
+/// The state of zcashd's Sprout-to-Sapling migration tool for an account.
+///
+/// zcashd can migrate Sprout funds to Sapling automatically, sending a series
+/// of transactions over time so that the migration cannot easily be linked to
+/// the account. An interrupted or partially completed migration leaves some
+/// Sprout notes migrated and others still live. Preserving the migration's
+/// state lets the receiving wallet tell the user which Sprout funds still need
+/// special handling.
+///
+/// # Zcash Concept Relation
+/// - **Enabled**: Whether the migration was enabled in the source wallet
+///   (`-migration` in zcashd)
+/// - **Destination address**: The Sapling address receiving migrated funds
+///   (`-migrationdestaddress`), if one was configured
+/// - **Migration transactions**: The ids of the transactions the migration has
+///   sent so far
+///
+/// The amount still to be migrated is not recorded here, since it follows from
+/// the account's unspent Sprout notes; see
+/// [`Account::unmigrated_sprout_balance`](crate::Account::unmigrated_sprout_balance).
+///
+/// # Examples
+/// ```
+/// # use zewif::{sprout::SproutMigrationStatus, TxId};
+/// let mut status = SproutMigrationStatus::new(true);
+/// status.set_destination_address(Some("zs1example".to_string()));
+/// status.add_migration_txid(TxId::from_bytes([1u8; 32]));
+/// assert_eq!(status.migration_txids().len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct SproutMigrationStatus {
+    enabled: bool,
+    destination_address: Option<String>,
+    migration_txids: Vec<TxId>,
+}
+
+impl SproutMigrationStatus {
+    /// Creates a new migration status with no destination address or
+    /// migration transactions.
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            destination_address: None,
+            migration_txids: Vec::new(),
+        }
+    }
+
+    /// Returns whether the migration was enabled in the source wallet.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets whether the migration was enabled in the source wallet.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Returns the Sapling address receiving migrated funds, if configured.
+    pub fn destination_address(&self) -> Option<&str> {
+        self.destination_address.as_deref()
+    }
+
+    /// Sets the Sapling address receiving migrated funds.
+    pub fn set_destination_address(&mut self, address: Option<String>) {
+        self.destination_address = address;
+    }
+
+    /// Returns the ids of the transactions sent by the migration, in the
+    /// order they were sent.
+    pub fn migration_txids(&self) -> &[TxId] {
+        &self.migration_txids
+    }
+
+    /// Records a transaction sent by the migration.
+    pub fn add_migration_txid(&mut self, txid: TxId) {
+        self.migration_txids.push(txid);
+    }
+}
+
+impl From<SproutMigrationStatus> for Envelope {
+    fn from(value: SproutMigrationStatus) -> Self {
+        Envelope::new(value.enabled)
+            .add_type("SproutMigrationStatus")
+            .add_optional_assertion("destination_address", value.destination_address)
+            .add_assertion("migration_txids", value.migration_txids)
+    }
+}
+
+impl TryFrom<Envelope> for SproutMigrationStatus {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SproutMigrationStatus")?;
+        let enabled = envelope.extract_subject()?;
+        let destination_address =
+            envelope.extract_optional_object_for_predicate("destination_address")?;
+        let migration_txids = envelope.extract_object_for_predicate("migration_txids")?;
+        Ok(Self {
+            enabled,
+            destination_address,
+            migration_txids,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TxId, test_envelope_roundtrip};
+
+    use super::SproutMigrationStatus;
+
+    impl crate::RandomInstance for SproutMigrationStatus {
+        fn random() -> Self {
+            Self {
+                enabled: bc_rand::rng_random_bool(&mut bc_rand::thread_rng()),
+                destination_address: String::opt_random(),
+                migration_txids: Vec::<TxId>::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(SproutMigrationStatus);
+}
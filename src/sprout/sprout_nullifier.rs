@@ -0,0 +1,26 @@
+use crate::{blob, blob_envelope};
+
+blob!(
+    SproutNullifier,
+    32,
+    r#"The nullifier of a Sprout note.
+
+A nullifier is revealed on chain when a note is spent. Each JoinSplit reveals
+the nullifiers of the two notes it consumes, so a wallet determines whether one
+of its Sprout notes has been spent by looking for the note's nullifier among
+the JoinSplits of later transactions.
+
+# Zcash Concept Relation
+Sprout nullifiers are checked against their own nullifier set, separate from
+those of Sapling and Orchard, and are modeled as a distinct type for the same
+reason.
+
+# Examples
+```
+# use zewif::sprout::SproutNullifier;
+let nullifier = SproutNullifier::new([0u8; 32]);
+```"#
+);
+impl Copy for SproutNullifier {}
+
+blob_envelope!(SproutNullifier);
@@ -0,0 +1,213 @@
+use bc_envelope::prelude::*;
+
+use crate::{Indexed, TxId};
+
+use super::{Note, SproutNullifier, SproutWitness};
+
+/// A Sprout note received by the wallet, paired with the witness needed to
+/// spend it.
+///
+/// `SproutReceivedNote` records where a note was created (the transaction, the
+/// index of the JoinSplit within that transaction, and which of the
+/// JoinSplit's two outputs it is), the decrypted note plaintext, and, for notes
+/// that remain spendable, the witness proving the note's commitment is present
+/// in the Sprout note commitment tree. Once known, the note's nullifier and the
+/// transaction that spent it are recorded as well.
+///
+/// # Data Preservation
+/// For each unspent Sprout note, both the note plaintext and its witness must
+/// survive migration. For spent notes, preserving the spending transaction
+/// avoids the receiving wallet treating the note as spendable.
+///
+/// # Examples
+/// ```
+/// # use zewif::{sprout::{self, SproutReceivedNote}, Amount, Blob, TxId};
+/// # use zewif::Result;
+/// # fn example() -> Result<()> {
+/// let note = sprout::Note::from_parts(
+///     Amount::from_u64(10_000_000)?,
+///     Blob::new([1u8; 32]),
+///     Blob::new([2u8; 32]),
+///     Blob::new([3u8; 32]),
+/// )?;
+/// let received = SproutReceivedNote::new(TxId::from_bytes([0u8; 32]), 0, 1, note);
+///
+/// assert_eq!(received.output_index(), 1);
+/// assert!(received.witness().is_none());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct SproutReceivedNote {
+    /// The index of this note within the account's received notes.
+    index: usize,
+
+    /// The id of the transaction that created the note.
+    txid: TxId,
+
+    /// The index of the JoinSplit within the transaction that created the
+    /// note.
+    joinsplit_index: u32,
+
+    /// Which of the JoinSplit's outputs (0 or 1) created the note.
+    output_index: u8,
+
+    /// The decrypted note plaintext.
+    note: Note,
+
+    /// The witness for the note's commitment, if the note is spendable and the
+    /// source wallet maintained one.
+    witness: Option<SproutWitness>,
+
+    /// The nullifier of the note, if the source wallet computed it.
+    nullifier: Option<SproutNullifier>,
+
+    /// The id of the transaction that spent the note, if known.
+    spent_in: Option<TxId>,
+}
+
+impl Indexed for SproutReceivedNote {
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
+
+impl SproutReceivedNote {
+    /// Creates a new received note without a witness or nullifier.
+    pub fn new(txid: TxId, joinsplit_index: u32, output_index: u8, note: Note) -> Self {
+        Self {
+            index: 0,
+            txid,
+            joinsplit_index,
+            output_index,
+            note,
+            witness: None,
+            nullifier: None,
+            spent_in: None,
+        }
+    }
+
+    /// Returns the id of the transaction that created the note.
+    pub fn txid(&self) -> TxId {
+        self.txid
+    }
+
+    /// Returns the index of the JoinSplit within the transaction that created
+    /// the note.
+    pub fn joinsplit_index(&self) -> u32 {
+        self.joinsplit_index
+    }
+
+    /// Returns which of the JoinSplit's outputs created the note.
+    pub fn output_index(&self) -> u8 {
+        self.output_index
+    }
+
+    /// Returns the decrypted note plaintext.
+    pub fn note(&self) -> &Note {
+        &self.note
+    }
+
+    /// Returns the witness for the note's commitment, if known.
+    pub fn witness(&self) -> Option<&SproutWitness> {
+        self.witness.as_ref()
+    }
+
+    /// Sets the witness for the note's commitment.
+    pub fn set_witness(&mut self, witness: Option<SproutWitness>) {
+        self.witness = witness;
+    }
+
+    /// Returns the nullifier of the note, if known.
+    pub fn nullifier(&self) -> Option<&SproutNullifier> {
+        self.nullifier.as_ref()
+    }
+
+    /// Sets the nullifier of the note.
+    pub fn set_nullifier(&mut self, nullifier: Option<SproutNullifier>) {
+        self.nullifier = nullifier;
+    }
+
+    /// Returns the id of the transaction that spent the note, if known.
+    pub fn spent_in(&self) -> Option<TxId> {
+        self.spent_in
+    }
+
+    /// Sets the id of the transaction that spent the note.
+    pub fn set_spent_in(&mut self, spent_in: Option<TxId>) {
+        self.spent_in = spent_in;
+    }
+}
+
+impl From<SproutReceivedNote> for Envelope {
+    fn from(value: SproutReceivedNote) -> Self {
+        Envelope::new(value.index)
+            .add_type("SproutReceivedNote")
+            .add_assertion("txid", value.txid)
+            .add_assertion("joinsplit_index", value.joinsplit_index)
+            .add_assertion("output_index", value.output_index)
+            .add_assertion("note", value.note)
+            .add_optional_assertion("witness", value.witness)
+            .add_optional_assertion("nullifier", value.nullifier)
+            .add_optional_assertion("spent_in", value.spent_in)
+    }
+}
+
+impl TryFrom<Envelope> for SproutReceivedNote {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SproutReceivedNote")?;
+        let index = envelope.extract_subject()?;
+        let txid = envelope.extract_object_for_predicate("txid")?;
+        let joinsplit_index = envelope.extract_object_for_predicate("joinsplit_index")?;
+        let output_index = envelope.extract_object_for_predicate("output_index")?;
+        let note = envelope.try_object_for_predicate("note")?;
+        let witness = envelope.try_optional_object_for_predicate("witness")?;
+        let nullifier = envelope.try_optional_object_for_predicate("nullifier")?;
+        let spent_in = envelope.extract_optional_object_for_predicate("spent_in")?;
+        Ok(Self {
+            index,
+            txid,
+            joinsplit_index,
+            output_index,
+            note,
+            witness,
+            nullifier,
+            spent_in,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        TxId,
+        sprout::{Note, SproutNullifier, SproutWitness},
+        test_envelope_roundtrip,
+    };
+
+    use super::SproutReceivedNote;
+
+    impl crate::RandomInstance for SproutReceivedNote {
+        fn random() -> Self {
+            Self {
+                index: 0,
+                txid: TxId::random(),
+                joinsplit_index: u32::random(),
+                output_index: u8::random() & 1,
+                note: Note::random(),
+                witness: SproutWitness::opt_random(),
+                nullifier: SproutNullifier::opt_random(),
+                spent_in: TxId::opt_random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(SproutReceivedNote);
+}
@@ -0,0 +1,123 @@
+use bc_envelope::prelude::*;
+
+use crate::{IncrementalWitness, blob, blob_envelope};
+
+/// The depth of the Zcash Sprout note commitment tree.
+pub const SPROUT_COMMITMENT_TREE_DEPTH: usize = 29;
+
+blob!(
+    MerkleHashSprout,
+    32,
+    "A node in the Sprout note commitment tree."
+);
+impl Copy for MerkleHashSprout {}
+
+blob_envelope!(MerkleHashSprout);
+
+/// A cryptographic witness proving that a Sprout note commitment exists in the
+/// note commitment tree.
+///
+/// `SproutWitness` is the Sprout instantiation of the incremental Merkle tree
+/// witness. It proves that a specific note commitment is included in the
+/// global Sprout note commitment tree, which is necessary when spending the
+/// note in a JoinSplit.
+///
+/// # Zcash Concept Relation
+/// The Sprout note commitment tree differs from the Sapling and Orchard trees
+/// in two respects:
+///
+/// - **Depth**: The tree is 29 levels deep rather than 32
+/// - **Hash**: Internal nodes are combined with the SHA-256 compression
+///   function rather than an algebraic hash
+///
+/// # Data Preservation
+/// During wallet migration, complete witness data must be preserved for all
+/// unspent Sprout notes. Sprout is no longer supported by most wallets, so the
+/// receiving wallet may have no practical way to rebuild a missing witness.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct SproutWitness(IncrementalWitness<SPROUT_COMMITMENT_TREE_DEPTH, MerkleHashSprout>);
+
+impl SproutWitness {
+    /// Creates a new Sprout witness from an incremental witness over the
+    /// Sprout note commitment tree.
+    pub fn new(
+        witness: IncrementalWitness<SPROUT_COMMITMENT_TREE_DEPTH, MerkleHashSprout>,
+    ) -> Self {
+        Self(witness)
+    }
+
+    /// Returns the underlying incremental witness.
+    pub fn witness(&self) -> &IncrementalWitness<SPROUT_COMMITMENT_TREE_DEPTH, MerkleHashSprout> {
+        &self.0
+    }
+
+    /// Checks that the witness is structurally consistent with a depth-29
+    /// Sprout note commitment tree.
+    pub fn validate(&self) -> crate::Result<()> {
+        self.0.validate()
+    }
+}
+
+impl From<SproutWitness> for Envelope {
+    fn from(value: SproutWitness) -> Self {
+        Envelope::new(*value.0.note_commitment())
+            .add_type("SproutWitness")
+            .add_assertion("note_position", value.0.note_position())
+            .add_assertion("merkle_path", value.0.merkle_path().to_vec())
+            .add_assertion("anchor", *value.0.anchor())
+            .add_assertion("anchor_tree_size", value.0.anchor_tree_size())
+            .add_assertion("anchor_frontier", value.0.anchor_frontier().to_vec())
+    }
+}
+
+impl TryFrom<Envelope> for SproutWitness {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SproutWitness")?;
+        let note_commitment = envelope.extract_subject::<MerkleHashSprout>()?;
+        let note_position = envelope.extract_object_for_predicate("note_position")?;
+        let merkle_path = envelope.extract_object_for_predicate("merkle_path")?;
+        let anchor = envelope.extract_object_for_predicate("anchor")?;
+        let anchor_tree_size = envelope.extract_object_for_predicate("anchor_tree_size")?;
+        let anchor_frontier = envelope.extract_object_for_predicate("anchor_frontier")?;
+        Ok(Self(IncrementalWitness::from_parts(
+            note_commitment,
+            note_position,
+            merkle_path,
+            anchor,
+            anchor_tree_size,
+            anchor_frontier,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IncrementalWitness, RandomInstance, test_envelope_roundtrip};
+
+    use super::{SPROUT_COMMITMENT_TREE_DEPTH, SproutWitness};
+
+    impl RandomInstance for SproutWitness {
+        fn random() -> Self {
+            Self(IncrementalWitness::random())
+        }
+    }
+
+    test_envelope_roundtrip!(SproutWitness);
+
+    #[test]
+    fn test_random_witness_is_valid() {
+        let witness = SproutWitness::random();
+        assert_eq!(
+            witness.witness().merkle_path().len(),
+            SPROUT_COMMITMENT_TREE_DEPTH
+        );
+        assert!(witness.validate().is_ok());
+    }
+}
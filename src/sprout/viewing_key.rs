@@ -0,0 +1,223 @@
+use std::io::{self, Read, Write};
+
+use bc_envelope::prelude::*;
+
+use crate::{Blob32, Error, Network, Result, base58check};
+
+/// A Sprout viewing key.
+///
+/// A Sprout viewing key pairs the address's paying key (`a_pk`) with the
+/// receiving key (`sk_enc`) used to decrypt incoming note ciphertexts. It
+/// allows a wallet to detect and read notes sent to the address without being
+/// able to spend them.
+///
+/// # Zcash Concept Relation
+/// zcashd stores viewing keys for watch-only Sprout addresses as the paying key
+/// followed by the receiving key, 64 bytes in total. `z_exportviewingkey`
+/// encodes the same bytes with Base58Check, giving strings beginning with
+/// `ZiVK` on mainnet and `ZiVt` on testnet and regtest.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Blob, sprout::ViewingKey};
+/// let key = ViewingKey::new(Blob::new([1u8; 32]), Blob::new([2u8; 32]));
+/// assert_eq!(key.paying_key(), &Blob::new([1u8; 32]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct ViewingKey {
+    paying_key: Blob32,
+    receiving_key: Blob32,
+}
+
+impl ViewingKey {
+    /// Creates a viewing key from its paying key and receiving key.
+    pub fn new(paying_key: Blob32, receiving_key: Blob32) -> Self {
+        Self {
+            paying_key,
+            receiving_key,
+        }
+    }
+
+    /// Returns the paying key (`a_pk`) of the address.
+    pub fn paying_key(&self) -> &Blob32 {
+        &self.paying_key
+    }
+
+    /// Returns the receiving key (`sk_enc`) used to decrypt incoming notes.
+    pub fn receiving_key(&self) -> &Blob32 {
+        &self.receiving_key
+    }
+
+    /// Decodes a viewing key in the format produced by zcashd's
+    /// `z_exportviewingkey`.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidBase58Check`] if the string is not valid
+    /// Base58Check, [`Error::KeyNetworkMismatch`] if it is a Sprout viewing key
+    /// for a different network, and [`Error::InvalidViewingKey`] if it is not a
+    /// Sprout viewing key at all.
+    pub fn from_string(s: &str, network: Network) -> Result<Self> {
+        let data = base58check::decode(s)?;
+        if data.len() != 3 + 64 {
+            return Err(Error::InvalidViewingKey {
+                reason: "not a Sprout viewing key".to_string(),
+            });
+        }
+        let (prefix, keys) = data.split_at(3);
+        if prefix != network.sprout_viewing_key_prefix() {
+            let other_network = [Network::Main, Network::Test]
+                .iter()
+                .any(|n| prefix == n.sprout_viewing_key_prefix());
+            return if other_network {
+                Err(Error::KeyNetworkMismatch {
+                    expected: network.into(),
+                })
+            } else {
+                Err(Error::InvalidViewingKey {
+                    reason: "not a Sprout viewing key".to_string(),
+                })
+            };
+        }
+        let (paying_key, receiving_key) = keys.split_at(32);
+        Ok(Self::new(
+            Blob32::from_slice(paying_key)?,
+            Blob32::from_slice(receiving_key)?,
+        ))
+    }
+
+    /// Encodes the viewing key in the format produced by zcashd's
+    /// `z_exportviewingkey` for the given network.
+    pub fn to_string(&self, network: Network) -> String {
+        let mut keys = self.paying_key.to_vec();
+        keys.extend_from_slice(self.receiving_key.as_slice());
+        base58check::encode(&network.sprout_viewing_key_prefix(), &keys)
+    }
+
+    /// Reads a viewing key in the zcashd serialization.
+    ///
+    /// # Errors
+    /// Returns an IO error if the reader does not contain 64 bytes.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut paying_key = [0u8; 32];
+        let mut receiving_key = [0u8; 32];
+        reader.read_exact(&mut paying_key)?;
+        reader.read_exact(&mut receiving_key)?;
+        Ok(Self::new(
+            Blob32::new(paying_key),
+            Blob32::new(receiving_key),
+        ))
+    }
+
+    /// Writes the viewing key in the zcashd serialization.
+    ///
+    /// # Errors
+    /// Returns an IO error if writing fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.paying_key.as_slice())?;
+        writer.write_all(self.receiving_key.as_slice())
+    }
+}
+
+impl From<ViewingKey> for Envelope {
+    fn from(value: ViewingKey) -> Self {
+        Envelope::new(value.paying_key)
+            .add_type("SproutViewingKey")
+            .add_assertion("receiving_key", value.receiving_key)
+    }
+}
+
+impl TryFrom<Envelope> for ViewingKey {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("SproutViewingKey")?;
+        let paying_key = envelope.extract_subject()?;
+        let receiving_key = envelope.extract_object_for_predicate("receiving_key")?;
+        Ok(Self {
+            paying_key,
+            receiving_key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{Blob, Error, Network, RandomInstance, test_envelope_roundtrip};
+
+    use super::ViewingKey;
+
+    impl RandomInstance for ViewingKey {
+        fn random() -> Self {
+            Self {
+                paying_key: Blob::random(),
+                receiving_key: Blob::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(ViewingKey);
+
+    #[test]
+    fn test_zcashd_serialization_roundtrip() {
+        let key = ViewingKey::random();
+        let mut buf = Vec::new();
+        key.write(&mut buf).unwrap();
+        assert_eq!(buf.len(), 64);
+        assert_eq!(ViewingKey::read(Cursor::new(&buf)).unwrap(), key);
+    }
+
+    /// Encodes paying key `[0, 1, ..., 31]` and receiving key
+    /// `[32, 33, ..., 63]` with zcashd's `ZCVIEWING_KEY` prefixes, as
+    /// `z_exportviewingkey` lays them out.
+    const MAINNET_KEY: &str = "ZiVKNJtn6NUbLMFiT9X7RP2ihMJMmaA4r8x9EjHxVXN9GCUuWmo92eMcd8dcdhkcAUPcpkxg4yUfTRrFhMxq5PweBRUuVuzYo";
+    const TESTNET_KEY: &str = "ZiVtHMjp5zUwPizwmwnZ1ZjBbNo99FeHniXnRkyh9QATbLf6JUfiBM58s7bpKRkr8hMw2gBTt8yEW3jki7qHj4duZMTyTj9LH";
+
+    #[test]
+    fn test_known_strings() {
+        let keys: Vec<u8> = (0u8..64).collect();
+        let key = ViewingKey::from_string(MAINNET_KEY, Network::Main).unwrap();
+        assert_eq!(key.paying_key().as_slice(), &keys[..32]);
+        assert_eq!(key.receiving_key().as_slice(), &keys[32..]);
+        assert_eq!(key.to_string(Network::Main), MAINNET_KEY);
+        assert_eq!(key.to_string(Network::Test), TESTNET_KEY);
+
+        let key = ViewingKey::from_string(TESTNET_KEY, Network::Test).unwrap();
+        assert_eq!(key.paying_key().as_slice(), &keys[..32]);
+        assert_eq!(key.receiving_key().as_slice(), &keys[32..]);
+    }
+
+    #[test]
+    fn test_string_encoding() {
+        let key = ViewingKey::random();
+
+        let mainnet = key.to_string(Network::Main);
+        assert!(mainnet.starts_with("ZiVK"));
+        assert_eq!(
+            ViewingKey::from_string(&mainnet, Network::Main).unwrap(),
+            key
+        );
+
+        let regtest = key.to_string(Network::Regtest);
+        assert!(regtest.starts_with("ZiVt"));
+        assert_eq!(
+            ViewingKey::from_string(&regtest, Network::Regtest).unwrap(),
+            key
+        );
+        assert_eq!(
+            ViewingKey::from_string(&regtest, Network::Test).unwrap(),
+            key
+        );
+
+        assert!(matches!(
+            ViewingKey::from_string(&mainnet, Network::Regtest),
+            Err(Error::KeyNetworkMismatch { .. })
+        ));
+        assert!(matches!(
+            ViewingKey::from_string(&mainnet[1..], Network::Main),
+            Err(Error::InvalidBase58Check { .. } | Error::InvalidViewingKey { .. })
+        ));
+    }
+}
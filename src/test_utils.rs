@@ -2,6 +2,9 @@ use bc_envelope::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
 
+#[cfg(test)]
+use crate::{Amount, Blob, Data, OutPoint, Script, Transaction, TxId, TxIn, TxOut, sapling};
+
 pub trait RandomInstance {
     fn random() -> Self;
 
@@ -62,14 +65,14 @@ impl RandomInstance for usize {
 impl RandomInstance for String {
     fn random() -> Self {
         let mut rng = bc_rand::thread_rng();
-        let len = rand::Rng::gen_range(&mut rng, 10..=100);
+        let len = rand::Rng::random_range(&mut rng, 10..=100);
         let alphabet =
             "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
         let mut s = String::new();
         for _ in 0..len {
             let c = alphabet
                 .chars()
-                .nth(rand::Rng::gen_range(&mut rng, 0..alphabet.len()))
+                .nth(rand::Rng::random_range(&mut rng, 0..alphabet.len()))
                 .unwrap();
             s.push(c);
         }
@@ -83,7 +86,7 @@ where
 {
     fn random() -> Self {
         let mut rng = bc_rand::thread_rng();
-        let len = rand::Rng::gen_range(&mut rng, 1..=5);
+        let len = rand::Rng::random_range(&mut rng, 1..=5);
         (0..len).map(|_| T::random()).collect()
     }
 }
@@ -95,7 +98,7 @@ where
 {
     fn random() -> Self {
         let mut rng = bc_rand::thread_rng();
-        let len = rand::Rng::gen_range(&mut rng, 1..=10);
+        let len = rand::Rng::random_range(&mut rng, 1..=10);
         (0..len).map(|_| (K::random(), V::random())).collect()
     }
 }
@@ -106,7 +109,7 @@ where
 {
     fn random() -> Self {
         let mut rng = bc_rand::thread_rng();
-        let len = rand::Rng::gen_range(&mut rng, 1..=10);
+        let len = rand::Rng::random_range(&mut rng, 1..=10);
         (0..len).map(|_| T::random()).collect()
     }
 }
@@ -138,10 +141,11 @@ pub fn test_envelope_roundtrip<T>(iterations: usize, print: bool)
 where
     T: RandomInstance
         + Into<Envelope>
-        + TryFrom<Envelope, Error = bc_envelope::Error>
+        + TryFrom<Envelope>
         + Clone
         + std::fmt::Debug
         + PartialEq,
+    <T as TryFrom<Envelope>>::Error: std::error::Error,
 {
     for _ in 0..iterations {
         let i1 = T::random();
@@ -180,7 +184,7 @@ impl RandomInstance for Attachments {
     fn random() -> Self {
         let mut attachments = Attachments::new();
         let mut rng = bc_rand::thread_rng();
-        let len = rand::Rng::gen_range(&mut rng, 0..=3);
+        let len = rand::Rng::random_range(&mut rng, 0..=3);
         for _ in 0..len {
             attachments.add(
                 String::random(),
@@ -191,3 +195,57 @@ impl RandomInstance for Attachments {
         attachments
     }
 }
+
+// Fixtures for tests that build transactions and notes by hand.
+
+#[cfg(test)]
+pub(crate) fn txid(byte: u8) -> TxId {
+    TxId::from_bytes([byte; 32])
+}
+
+#[cfg(test)]
+pub(crate) fn zats(value: i64) -> Amount {
+    Amount::from_i64(value).unwrap()
+}
+
+/// A P2PKH output of `value` zatoshis to the key hash filled with `hash`.
+#[cfg(test)]
+pub(crate) fn p2pkh(hash: u8, value: i64) -> TxOut {
+    let script = [&[0x76, 0xa9, 0x14][..], &[hash; 20], &[0x88, 0xac]].concat();
+    TxOut::new(zats(value), Script::from(Data::from_vec(script)))
+}
+
+#[cfg(test)]
+pub(crate) fn spend_of(txid: TxId, index: u32) -> TxIn {
+    TxIn::new(
+        OutPoint::new(txid, index),
+        Script::from(Data::new()),
+        u32::MAX,
+    )
+}
+
+#[cfg(test)]
+pub(crate) fn note(
+    created_in: TxId,
+    value: i64,
+    spent_in: Option<TxId>,
+) -> sapling::SaplingReceivedNote {
+    let note =
+        sapling::Note::from_parts(Blob::new([0; 43]), zats(value), Blob::new([0; 32])).unwrap();
+    let mut note = sapling::SaplingReceivedNote::new(created_in, 0, note);
+    note.set_spent_in(spent_in);
+    note
+}
+
+/// A transaction with the given transparent inputs and outputs.
+#[cfg(test)]
+pub(crate) fn transaction(byte: u8, inputs: Vec<TxIn>, outputs: Vec<TxOut>) -> Transaction {
+    let mut transaction = Transaction::new(txid(byte));
+    inputs
+        .into_iter()
+        .for_each(|input| transaction.add_transparent_input(input));
+    outputs
+        .into_iter()
+        .for_each(|output| transaction.add_transparent_output(output));
+    transaction
+}
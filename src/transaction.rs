@@ -1,5 +1,10 @@
 use super::{BlockHeight, Data, TxId};
-use crate::TxBlockPosition;
+use crate::{
+    Amount, BlockHash, BranchId, Error, Indexed, InputValueResolver, OutputAnnotation, Pool,
+    Result, SecondsSinceEpoch, TransactionStatus, TxBlockPosition, TxIn, TxOut, TxVersion,
+    envelope_indexed_objects_for_predicate, orchard::OrchardActionDescription, raw_transaction,
+    sapling::SaplingSpendDescription, sprout::JoinSplitDescription,
+};
 use bc_envelope::prelude::*;
 
 /// A Zcash transaction that can combine transparent and multiple shielded protocol components.
@@ -22,8 +27,8 @@ use bc_envelope::prelude::*;
 ///   - Sprout (original shielded protocol using JoinSplits)
 ///   - Sapling (improved shielded protocol with separate spends/outputs)
 ///   - Orchard (latest shielded protocol using unified actions)
-/// - **Transaction Lifecycle**: Transactions go through stages (Pending, Confirmed, Failed, Abandoned)
-///   that represent their status on the blockchain
+/// - **Transaction Lifecycle**: Transactions go through stages (in the mempool, mined, expired,
+///   abandoned) that represent their status on the blockchain; see [`TransactionStatus`]
 ///
 /// # Data Preservation
 ///
@@ -33,6 +38,7 @@ use bc_envelope::prelude::*;
 /// - **Blockchain Context**: Block height, timestamp, block hash when available
 /// - **Status Information**: Whether the transaction is pending, confirmed, failed, or abandoned
 /// - **Raw Transaction**: Optional full binary transaction data
+/// - **Output Annotations**: Decrypted memos and user-entered recipients, which are not on chain
 /// - **Protocol-Specific Components**:
 ///   - Transparent inputs and outputs
 ///   - Sapling spends and outputs
@@ -50,23 +56,53 @@ use bc_envelope::prelude::*;
 /// tx.set_mined_height(BlockHeight::from(1000000));
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transaction {
     /// The transaction id.
     txid: TxId,
     /// The raw transaction data, if known.
     raw: Option<Data>,
+    /// The version of the transaction's encoding, if known.
+    version: Option<TxVersion>,
+    /// The consensus branch the transaction was signed under, if known.
+    consensus_branch_id: Option<BranchId>,
+    /// Whether the transaction was mined, or why it was not.
+    status: TransactionStatus,
     /// The height for which the transaction was constructed, which implies
     /// the consensus branch for which the transaction was intended, if known.
     target_height: Option<BlockHeight>,
+    /// The height after which the transaction can no longer be mined, if it
+    /// has one.
+    expiry_height: Option<BlockHeight>,
     /// The height at which the transaction was mined, if known.
     /// It is possible that if a rollback occurred just after the zeWIF
     /// export, the transaction could have been unmined, and possibly
     /// remined at a different height.
     mined_height: Option<BlockHeight>,
+    /// The hash of the block containing the transaction, if known.
+    block_hash: Option<BlockHash>,
+    /// The time of the block containing the transaction, if known.
+    block_time: Option<SecondsSinceEpoch>,
     /// The hash of the block containing the transaction and the index of the transaction within
     /// the block, if known.
     block_position: Option<TxBlockPosition>,
+    /// The fee paid by the transaction, if known.
+    fee: Option<Amount>,
+    /// The transaction's transparent inputs, in transaction order.
+    transparent_inputs: Vec<TxIn>,
+    /// The transaction's transparent outputs, in transaction order.
+    transparent_outputs: Vec<TxOut>,
+    /// The transaction's Sprout JoinSplits, in transaction order.
+    joinsplits: Vec<JoinSplitDescription>,
+    /// The spends of the transaction's Sapling bundle, in bundle order.
+    sapling_spends: Vec<SaplingSpendDescription>,
+    /// The actions of the transaction's Orchard bundle, in bundle order.
+    orchard_actions: Vec<OrchardActionDescription>,
+    /// The wallet's annotations of the transaction's outputs, ordered by
+    /// pool and index.
+    output_annotations: Vec<OutputAnnotation>,
     /// Additional arbitrary metadata related to the transaction.
+    #[cfg_attr(feature = "json", serde(with = "crate::json::attachments"))]
     attachments: Attachments,
 }
 
@@ -77,9 +113,22 @@ impl Transaction {
         Self {
             txid,
             raw: None,
+            version: None,
+            consensus_branch_id: None,
+            status: TransactionStatus::Unknown,
             target_height: None,
+            expiry_height: None,
             mined_height: None,
+            block_hash: None,
+            block_time: None,
             block_position: None,
+            fee: None,
+            transparent_inputs: Vec::new(),
+            transparent_outputs: Vec::new(),
+            joinsplits: Vec::new(),
+            sapling_spends: Vec::new(),
+            orchard_actions: Vec::new(),
+            output_annotations: Vec::new(),
             attachments: Attachments::new(),
         }
     }
@@ -100,6 +149,83 @@ impl Transaction {
         self.raw = Some(raw);
     }
 
+    /// Removes the serialized transaction, returning it if it was present.
+    pub(crate) fn remove_raw(&mut self) -> Option<Data> {
+        self.raw.take()
+    }
+
+    /// Returns whether the transaction ID matches the one computed from the
+    /// raw transaction; see [`TxId::from_raw_transaction`].
+    ///
+    /// Large raw transactions are compressed when the Zewif is encoded with
+    /// [`Compression::LargeLeavesOver`](crate::Compression::LargeLeavesOver).
+    ///
+    /// # Errors
+    /// Returns [`Error::MissingRawTransaction`](crate::Error::MissingRawTransaction)
+    /// if the transaction has no raw bytes, and
+    /// [`Error::InvalidRawTransaction`](crate::Error::InvalidRawTransaction)
+    /// if they cannot be parsed.
+    pub fn verify_txid(&self) -> crate::Result<bool> {
+        let raw = self
+            .raw
+            .as_ref()
+            .ok_or_else(|| crate::Error::MissingRawTransaction { txid: self.txid })?;
+        Ok(TxId::from_raw_transaction(raw.as_slice())? == self.txid)
+    }
+
+    /// Returns the version of the transaction's encoding, if known.
+    pub fn version(&self) -> Option<TxVersion> {
+        self.version
+    }
+
+    pub fn set_version(&mut self, version: Option<TxVersion>) {
+        self.version = version;
+    }
+
+    /// Returns the consensus branch the transaction was signed under, if
+    /// known.
+    pub fn consensus_branch_id(&self) -> Option<BranchId> {
+        self.consensus_branch_id
+    }
+
+    pub fn set_consensus_branch_id(&mut self, consensus_branch_id: Option<BranchId>) {
+        self.consensus_branch_id = consensus_branch_id;
+    }
+
+    /// Sets the transaction's version and expiry height from its raw bytes,
+    /// and its consensus branch ID if they encode it, as only version 5
+    /// transactions do. The branch ID of earlier transactions is left as
+    /// recorded, since it can only be inferred from the height they were
+    /// signed for.
+    ///
+    /// # Errors
+    /// Returns [`Error::MissingRawTransaction`] if the transaction has no raw
+    /// bytes, and [`Error::InvalidRawTransaction`] if their header cannot be
+    /// parsed, leaving the transaction unchanged.
+    pub fn parse_header_fields(&mut self) -> Result<()> {
+        let raw = self
+            .raw
+            .as_ref()
+            .ok_or_else(|| Error::MissingRawTransaction { txid: self.txid })?;
+        let fields = raw_transaction::header_fields(raw.as_slice())?;
+        self.version = Some(fields.version);
+        self.expiry_height = fields.expiry_height;
+        if fields.consensus_branch_id.is_some() {
+            self.consensus_branch_id = fields.consensus_branch_id;
+        }
+        Ok(())
+    }
+
+    /// Returns whether the transaction was mined, or why it was not, as
+    /// recorded by the source wallet.
+    pub fn status(&self) -> TransactionStatus {
+        self.status
+    }
+
+    pub fn set_status(&mut self, status: TransactionStatus) {
+        self.status = status;
+    }
+
     pub fn target_height(&self) -> Option<&BlockHeight> {
         self.target_height.as_ref()
     }
@@ -108,6 +234,16 @@ impl Transaction {
         self.target_height = Some(height);
     }
 
+    /// Returns the height after which the transaction can no longer be
+    /// mined, if it has one.
+    pub fn expiry_height(&self) -> Option<BlockHeight> {
+        self.expiry_height
+    }
+
+    pub fn set_expiry_height(&mut self, expiry_height: Option<BlockHeight>) {
+        self.expiry_height = expiry_height;
+    }
+
     pub fn mined_height(&self) -> Option<&BlockHeight> {
         self.mined_height.as_ref()
     }
@@ -116,6 +252,51 @@ impl Transaction {
         self.mined_height = Some(height);
     }
 
+    /// Returns the hash of the block containing the transaction, if known,
+    /// falling back to that of its [`block_position`](Self::block_position).
+    pub fn block_hash(&self) -> Option<&BlockHash> {
+        self.block_hash.as_ref().or(self
+            .block_position
+            .as_ref()
+            .map(TxBlockPosition::block_hash))
+    }
+
+    pub fn set_block_hash(&mut self, block_hash: Option<BlockHash>) {
+        self.block_hash = block_hash;
+    }
+
+    /// Returns the time of the block containing the transaction, if known.
+    pub fn block_time(&self) -> Option<SecondsSinceEpoch> {
+        self.block_time
+    }
+
+    pub fn set_block_time(&mut self, block_time: Option<SecondsSinceEpoch>) {
+        self.block_time = block_time;
+    }
+
+    /// Records the block in which the transaction was mined, as reported by
+    /// the source wallet; passing `None` for all three marks it unmined.
+    ///
+    /// A block position whose hash differs from `block_hash` is cleared, as
+    /// it describes another block.
+    pub fn set_block_metadata(
+        &mut self,
+        mined_height: Option<BlockHeight>,
+        block_hash: Option<BlockHash>,
+        block_time: Option<SecondsSinceEpoch>,
+    ) {
+        self.mined_height = mined_height;
+        self.block_hash = block_hash;
+        self.block_time = block_time;
+        if self
+            .block_position
+            .as_ref()
+            .is_some_and(|position| Some(position.block_hash()) != block_hash.as_ref())
+        {
+            self.block_position = None;
+        }
+    }
+
     pub fn block_position(&self) -> Option<&TxBlockPosition> {
         self.block_position.as_ref()
     }
@@ -123,21 +304,252 @@ impl Transaction {
     pub fn set_block_position(&mut self, block_position: Option<TxBlockPosition>) {
         self.block_position = block_position;
     }
+
+    /// Returns the recorded fee paid by the transaction, if known; see
+    /// [`Transaction::compute_fee`] to derive it.
+    pub fn fee(&self) -> Option<Amount> {
+        self.fee
+    }
+
+    pub fn set_fee(&mut self, fee: Option<Amount>) {
+        self.fee = fee;
+    }
+
+    /// Computes the fee paid by the transaction: the values of its
+    /// transparent inputs, supplied by `resolver`, plus the net value it moves
+    /// out of the shielded pools, less its transparent outputs.
+    ///
+    /// The components are read from the raw bytes if they are recorded, and
+    /// otherwise from the recorded transparent inputs and outputs and Sprout
+    /// JoinSplits, taking the transaction to have no Sapling or Orchard
+    /// bundle. A coinbase transaction pays no fee, so its fee is zero.
+    ///
+    /// The result can be recorded with [`Transaction::set_fee`].
+    ///
+    /// # Errors
+    /// Returns [`Error::MissingRawTransaction`] if the transaction has no raw
+    /// bytes and records no transparent or Sprout components, or records
+    /// Sapling spends or Orchard actions, whose value balances are only known
+    /// from the raw bytes. Returns [`Error::InvalidRawTransaction`] if the raw
+    /// bytes cannot be parsed, [`Error::UnresolvedInput`] naming the first
+    /// input whose value `resolver` does not know, and
+    /// [`Error::AmountUnderflow`] or [`Error::AmountOverflow`] if the values
+    /// do not yield a valid fee.
+    pub fn compute_fee(&self, resolver: &dyn InputValueResolver) -> Result<Amount> {
+        let flows = match &self.raw {
+            Some(raw) => raw_transaction::value_flows(raw.as_slice())?,
+            None => self.recorded_value_flows()?,
+        };
+        if matches!(flows.inputs.as_slice(), [input] if input.is_coinbase()) {
+            return Ok(Amount::zero());
+        }
+        let mut fee = flows.shielded_value_balance;
+        for input in &flows.inputs {
+            let prevout = input.prevout();
+            let value = resolver
+                .input_value(prevout)
+                .ok_or_else(|| Error::UnresolvedInput { outpoint: *prevout })?;
+            fee += i128::from(i64::from(value));
+        }
+        for output in &flows.outputs {
+            fee -= i128::from(i64::from(output.value()));
+        }
+        let fee = i64::try_from(fee).map_err(|_| {
+            if fee < 0 {
+                Error::AmountUnderflow(fee as u64)
+            } else {
+                Error::AmountOverflow(fee as u64)
+            }
+        })?;
+        Amount::from_nonnegative_i64(fee)
+    }
+
+    /// Returns the values moved by the transaction's recorded components,
+    /// for a transaction without raw bytes.
+    fn recorded_value_flows(&self) -> Result<raw_transaction::ValueFlows> {
+        let recorded = !self.transparent_inputs.is_empty()
+            || !self.transparent_outputs.is_empty()
+            || !self.joinsplits.is_empty();
+        if !recorded || !self.sapling_spends.is_empty() || !self.orchard_actions.is_empty() {
+            return Err(Error::MissingRawTransaction { txid: self.txid });
+        }
+        let shielded_value_balance = self
+            .joinsplits
+            .iter()
+            .map(|joinsplit| {
+                i128::from(i64::from(joinsplit.vpub_new()))
+                    - i128::from(i64::from(joinsplit.vpub_old()))
+            })
+            .sum();
+        Ok(raw_transaction::ValueFlows {
+            inputs: self.transparent_inputs.clone(),
+            outputs: self.transparent_outputs.clone(),
+            shielded_value_balance,
+        })
+    }
+
+    pub fn transparent_inputs(&self) -> &Vec<TxIn> {
+        &self.transparent_inputs
+    }
+
+    pub fn add_transparent_input(&mut self, mut input: TxIn) {
+        input.set_index(self.transparent_inputs.len());
+        self.transparent_inputs.push(input);
+    }
+
+    pub fn transparent_outputs(&self) -> &Vec<TxOut> {
+        &self.transparent_outputs
+    }
+
+    /// Returns the transparent output at the given index within the
+    /// transaction, if any.
+    pub fn transparent_output(&self, index: usize) -> Option<&TxOut> {
+        self.transparent_outputs.get(index)
+    }
+
+    pub fn add_transparent_output(&mut self, mut output: TxOut) {
+        output.set_index(self.transparent_outputs.len());
+        self.transparent_outputs.push(output);
+    }
+
+    /// Returns whether the transaction is a coinbase transaction, whose single
+    /// transparent input spends no earlier output. Transactions recorded
+    /// without their transparent inputs are not.
+    pub fn is_coinbase(&self) -> bool {
+        matches!(self.transparent_inputs.as_slice(), [input] if input.is_coinbase())
+    }
+
+    /// Replaces the transaction's transparent inputs and outputs with those
+    /// parsed from its raw bytes.
+    ///
+    /// # Errors
+    /// Returns [`Error::MissingRawTransaction`] if the transaction has no raw
+    /// bytes, and [`Error::InvalidRawTransaction`] if they cannot be parsed,
+    /// leaving the transaction unchanged.
+    pub fn populate_transparent(&mut self) -> Result<()> {
+        let raw = self
+            .raw
+            .as_ref()
+            .ok_or_else(|| Error::MissingRawTransaction { txid: self.txid })?;
+        let flows = raw_transaction::value_flows(raw.as_slice())?;
+        self.transparent_inputs = flows.inputs;
+        self.transparent_outputs = flows.outputs;
+        Ok(())
+    }
+
+    pub fn joinsplits(&self) -> &Vec<JoinSplitDescription> {
+        &self.joinsplits
+    }
+
+    pub fn joinsplit(&self, index: usize) -> Option<&JoinSplitDescription> {
+        self.joinsplits.get(index)
+    }
+
+    pub fn add_joinsplit(&mut self, mut joinsplit: JoinSplitDescription) {
+        joinsplit.set_index(self.joinsplits.len());
+        self.joinsplits.push(joinsplit);
+    }
+
+    pub fn sapling_spends(&self) -> &Vec<SaplingSpendDescription> {
+        &self.sapling_spends
+    }
+
+    pub fn add_sapling_spend(&mut self, mut spend: SaplingSpendDescription) {
+        spend.set_index(self.sapling_spends.len());
+        self.sapling_spends.push(spend);
+    }
+
+    pub fn orchard_actions(&self) -> &Vec<OrchardActionDescription> {
+        &self.orchard_actions
+    }
+
+    /// Returns the Orchard action at the given index within the bundle, if any.
+    pub fn orchard_action(&self, index: usize) -> Option<&OrchardActionDescription> {
+        self.orchard_actions.get(index)
+    }
+
+    pub fn add_orchard_action(&mut self, mut action: OrchardActionDescription) {
+        action.set_index(self.orchard_actions.len());
+        self.orchard_actions.push(action);
+    }
+
+    pub fn output_annotations(&self) -> &Vec<OutputAnnotation> {
+        &self.output_annotations
+    }
+
+    /// Returns the annotation of the output at `index` within the given
+    /// pool's component of the transaction, if any.
+    pub fn output_annotation(&self, pool: Pool, index: u32) -> Option<&OutputAnnotation> {
+        self.output_annotations
+            .iter()
+            .find(|annotation| annotation.key() == (pool, index))
+    }
+
+    /// Records an annotation of one of the transaction's outputs, replacing
+    /// any earlier annotation of the same output.
+    pub fn set_output_annotation(&mut self, annotation: OutputAnnotation) {
+        match self
+            .output_annotations
+            .binary_search_by_key(&annotation.key(), OutputAnnotation::key)
+        {
+            Ok(position) => self.output_annotations[position] = annotation,
+            Err(position) => self.output_annotations.insert(position, annotation),
+        }
+    }
 }
 
 #[rustfmt::skip]
 impl From<Transaction> for Envelope {
     fn from(value: Transaction) -> Self {
-        let e = Envelope::new(value.txid)
+        let mut e = Envelope::new(value.txid)
             .add_type("Transaction")
             .add_optional_assertion("raw", value.raw)
+            .add_optional_assertion("version", value.version)
+            .add_optional_assertion("consensus_branch_id", value.consensus_branch_id)
+            .add_optional_assertion(
+                "status",
+                (value.status != TransactionStatus::Unknown).then_some(value.status),
+            )
             .add_optional_assertion("target_height", value.target_height)
+            .add_optional_assertion("expiry_height", value.expiry_height)
             .add_optional_assertion("mined_height", value.mined_height)
-            .add_optional_assertion("block_position", value.block_position);
+            .add_optional_assertion("block_hash", value.block_hash)
+            .add_optional_assertion("block_time", value.block_time)
+            .add_optional_assertion("block_position", value.block_position)
+            .add_optional_assertion("fee", value.fee);
+        e = value.transparent_inputs.iter().fold(e, |e, input| {
+            e.add_assertion("transparent_input", input.clone())
+        });
+        e = value.transparent_outputs.iter().fold(e, |e, output| {
+            e.add_assertion("transparent_output", output.clone())
+        });
+        e = value.joinsplits.iter().fold(e, |e, joinsplit| {
+            e.add_assertion("joinsplit", joinsplit.clone())
+        });
+        e = value.sapling_spends.iter().fold(e, |e, spend| {
+            e.add_assertion("sapling_spend", spend.clone())
+        });
+        e = value.orchard_actions.iter().fold(e, |e, action| {
+            e.add_assertion("orchard_action", action.clone())
+        });
+        e = value.output_annotations.iter().fold(e, |e, annotation| {
+            e.add_assertion("output_annotation", annotation.clone())
+        });
         value.attachments.add_to_envelope(e)
     }
 }
 
+impl Transaction {
+    /// Returns the path segment identifying an encoded transaction in
+    /// decoding errors, such as `Transaction[<txid>]`.
+    pub(crate) fn envelope_segment(envelope: &Envelope) -> String {
+        match envelope.extract_subject::<TxId>() {
+            Ok(txid) => crate::envelope_decoder::segment("Transaction", txid),
+            Err(_) => crate::envelope_decoder::segment("Transaction", "?"),
+        }
+    }
+}
+
 impl TryFrom<Envelope> for Transaction {
     type Error = bc_envelope::Error;
 
@@ -145,18 +557,60 @@ impl TryFrom<Envelope> for Transaction {
         envelope.check_type("Transaction")?;
         let txid = envelope.extract_subject()?;
         let raw = envelope.try_optional_object_for_predicate("raw")?;
+        let version = envelope.try_optional_object_for_predicate("version")?;
+        let consensus_branch_id =
+            envelope.try_optional_object_for_predicate("consensus_branch_id")?;
+        let status = envelope
+            .try_optional_object_for_predicate("status")?
+            .unwrap_or_default();
         let target_height = envelope.try_optional_object_for_predicate("target_height")?;
+        let expiry_height = envelope.try_optional_object_for_predicate("expiry_height")?;
         let mined_height = envelope.try_optional_object_for_predicate("mined_height")?;
+        let block_hash = envelope.try_optional_object_for_predicate("block_hash")?;
+        let block_time = envelope.try_optional_object_for_predicate("block_time")?;
         let block_position = envelope.try_optional_object_for_predicate("block_position")?;
+        let fee = envelope.try_optional_object_for_predicate("fee")?;
+        let transparent_inputs =
+            envelope_indexed_objects_for_predicate(&envelope, "transparent_input")
+                .map_err(|e| bc_envelope::Error::General(format!("transparent_inputs: {}", e)))?;
+        let transparent_outputs =
+            envelope_indexed_objects_for_predicate(&envelope, "transparent_output")
+                .map_err(|e| bc_envelope::Error::General(format!("transparent_outputs: {}", e)))?;
+        let joinsplits = envelope_indexed_objects_for_predicate(&envelope, "joinsplit")
+            .map_err(|e| bc_envelope::Error::General(format!("joinsplits: {}", e)))?;
+        let sapling_spends = envelope_indexed_objects_for_predicate(&envelope, "sapling_spend")
+            .map_err(|e| bc_envelope::Error::General(format!("sapling_spends: {}", e)))?;
+        let orchard_actions =
+            envelope_indexed_objects_for_predicate(&envelope, "orchard_action")
+                .map_err(|e| bc_envelope::Error::General(format!("orchard_actions: {}", e)))?;
+        let mut output_annotations = envelope
+            .objects_for_predicate("output_annotation")
+            .into_iter()
+            .map(OutputAnnotation::try_from)
+            .collect::<bc_envelope::Result<Vec<_>>>()?;
+        output_annotations.sort_by_key(OutputAnnotation::key);
         let attachments = Attachments::try_from_envelope(&envelope)
             .map_err(|e| bc_envelope::Error::General(format!("attachments: {}", e)))?;
 
         Ok(Self {
             txid,
             raw,
+            version,
+            consensus_branch_id,
+            status,
             target_height,
+            expiry_height,
             mined_height,
+            block_hash,
+            block_time,
             block_position,
+            fee,
+            transparent_inputs,
+            transparent_outputs,
+            joinsplits,
+            sapling_spends,
+            orchard_actions,
+            output_annotations,
             attachments,
         })
     }
@@ -164,23 +618,400 @@ impl TryFrom<Envelope> for Transaction {
 
 #[cfg(test)]
 mod tests {
-    use bc_envelope::Attachments;
+    use std::collections::BTreeMap;
+
+    use bc_envelope::prelude::*;
 
     use super::Transaction;
-    use crate::{BlockHeight, Data, TxBlockPosition, TxId, test_envelope_roundtrip};
+    use crate::{
+        Amount, BlockHash, BlockHeight, BranchId, Data, Error, InputValueResolver, Memo, Network,
+        OutPoint, OutputAnnotation, Pool, Script, ScriptKind, SecondsSinceEpoch, SetIndexes,
+        TransactionStatus, TxBlockPosition, TxId, TxIn, TxOut, TxVersion, Zewif,
+        test_envelope_roundtrip,
+    };
 
     impl crate::RandomInstance for Transaction {
         fn random() -> Self {
             Self {
                 txid: TxId::random(),
                 raw: Data::opt_random(),
+                version: TxVersion::opt_random(),
+                consensus_branch_id: BranchId::opt_random(),
+                status: TransactionStatus::random(),
                 target_height: BlockHeight::opt_random(),
+                expiry_height: BlockHeight::opt_random(),
                 mined_height: BlockHeight::opt_random(),
+                block_hash: BlockHash::opt_random(),
+                block_time: SecondsSinceEpoch::opt_random(),
                 block_position: TxBlockPosition::opt_random(),
+                fee: Amount::opt_random(),
+                transparent_inputs: Vec::random().set_indexes(),
+                transparent_outputs: Vec::random().set_indexes(),
+                joinsplits: Vec::random().set_indexes(),
+                sapling_spends: Vec::random().set_indexes(),
+                orchard_actions: Vec::random().set_indexes(),
+                output_annotations: {
+                    let mut annotations = Vec::<OutputAnnotation>::random();
+                    annotations.sort_by_key(OutputAnnotation::key);
+                    annotations.dedup_by_key(|annotation| annotation.key());
+                    annotations
+                },
                 attachments: Attachments::random(),
             }
         }
     }
 
     test_envelope_roundtrip!(Transaction);
+
+    #[test]
+    fn test_block_metadata_roundtrip() {
+        let hash =
+            BlockHash::from_hex("0000000001a5ba1f00ff5bd1bdd6e0bd7d2b2cfa1bc32a0c2c8e6dc1ab2b1b56")
+                .unwrap();
+        let mut transaction = Transaction::new(TxId::from_bytes([1; 32]));
+        transaction.set_block_metadata(
+            Some(BlockHeight::from(1_000_000)),
+            Some(hash),
+            Some(SecondsSinceEpoch::from(1_600_000_000)),
+        );
+        let decoded = Transaction::try_from(Envelope::from(transaction.clone())).unwrap();
+        assert_eq!(decoded, transaction);
+        assert_eq!(decoded.mined_height(), Some(&BlockHeight::from(1_000_000)));
+        assert_eq!(decoded.block_hash(), Some(&hash));
+        assert_eq!(
+            decoded.block_time(),
+            Some(SecondsSinceEpoch::from(1_600_000_000))
+        );
+
+        // An unmined transaction records none of them, asserting only its
+        // type.
+        let unmined = Transaction::new(TxId::from_bytes([2; 32]));
+        let envelope = Envelope::from(unmined.clone());
+        assert_eq!(envelope.assertions().len(), 1);
+        let decoded = Transaction::try_from(envelope).unwrap();
+        assert_eq!(decoded, unmined);
+        assert_eq!(
+            (
+                decoded.mined_height(),
+                decoded.block_hash(),
+                decoded.block_time()
+            ),
+            (None, None, None)
+        );
+        assert_eq!(decoded.status(), TransactionStatus::Unknown);
+
+        // The hash falls back to the block position's, which is cleared when
+        // the transaction is remined in another block.
+        let mut transaction = Transaction::new(TxId::from_bytes([3; 32]));
+        transaction.set_block_position(Some(TxBlockPosition::new(hash, 7)));
+        assert_eq!(transaction.block_hash(), Some(&hash));
+        transaction.set_block_metadata(
+            Some(BlockHeight::from(1_000_001)),
+            Some(BlockHash::from_bytes([0; 32])),
+            None,
+        );
+        assert_eq!(transaction.block_position(), None);
+        transaction.set_block_metadata(None, None, None);
+        assert_eq!(transaction.block_hash(), None);
+    }
+
+    fn fixture(hex_lines: &str) -> Data {
+        Data::from_vec(hex::decode(hex_lines.split_whitespace().collect::<String>()).unwrap())
+    }
+
+    #[test]
+    fn test_verify_txid() {
+        let fixtures = [
+            (
+                include_str!("test_data/tx_v4_testnet.hex"),
+                "64f0bd7fe30ce23753358fe3a2dc835b8fba9c0274c4e2c54a6f73114cb55639",
+            ),
+            (
+                include_str!("test_data/tx_v5_zip244.hex"),
+                "d0854b7070bb168392e7cf3d3a558711b49c2c0ad8eca3a8a14b8333bd962c55",
+            ),
+        ];
+        for (raw, txid) in fixtures {
+            let mut transaction = Transaction::new(TxId::from_hex(txid).unwrap());
+            assert!(transaction.verify_txid().is_err());
+            transaction.set_raw(fixture(raw));
+            assert!(transaction.verify_txid().unwrap());
+            transaction.set_txid(TxId::from_bytes([0; 32]));
+            assert!(!transaction.verify_txid().unwrap());
+        }
+    }
+
+    /// The transparent components of a real testnet transaction are parsed,
+    /// and re-encode to the bytes they were read from.
+    #[test]
+    fn test_populate_transparent() {
+        let raw = fixture(include_str!("test_data/tx_v4_testnet.hex"));
+        let mut transaction = Transaction::new(
+            TxId::from_hex("64f0bd7fe30ce23753358fe3a2dc835b8fba9c0274c4e2c54a6f73114cb55639")
+                .unwrap(),
+        );
+        assert!(matches!(
+            transaction.populate_transparent(),
+            Err(Error::MissingRawTransaction { .. })
+        ));
+        transaction.set_raw(raw.clone());
+        transaction.populate_transparent().unwrap();
+
+        let [input] = transaction.transparent_inputs().as_slice() else {
+            panic!("expected one input")
+        };
+        let funding =
+            TxId::from_hex("6761f1bc78fa24958d3e9fe9826cca23f49334aebae59ad7a6931edf9629648f")
+                .unwrap();
+        assert_eq!(*input.prevout(), OutPoint::new(funding, 0));
+        assert!(input.has_script_sig() && !input.is_coinbase());
+        assert_eq!(input.sequence(), u32::MAX);
+        let [output] = transaction.transparent_outputs().as_slice() else {
+            panic!("expected one output")
+        };
+        assert_eq!(output.value(), Amount::from_u64(8_999_990_000).unwrap());
+        assert_eq!(output.kind(), ScriptKind::P2pkh);
+        assert_eq!(
+            output.address(Network::Test).as_deref(),
+            Some("tmQXfuJqk1ypsuwioJRXM5GjcSUDycU2ZfM")
+        );
+        let params = crate::NetworkParameters::for_network(Network::Test);
+        assert_eq!(output.address_with(&params), output.address(Network::Test));
+        let mut params = crate::NetworkParameters::for_network(Network::Regtest);
+        params.set_p2pkh_prefix(Network::Main.p2pkh_prefix());
+        assert_eq!(output.address_with(&params), output.address(Network::Main));
+
+        let mut encoded = Vec::new();
+        input.write(&mut encoded).unwrap();
+        output.write(&mut encoded).unwrap();
+        // The version, version group ID, and input count precede the input,
+        // and the output count precedes the output.
+        let input_len = encoded.len() - (8 + 1 + output.script_pubkey().len());
+        assert_eq!(&encoded[..input_len], &raw.as_slice()[9..9 + input_len]);
+        assert_eq!(
+            &encoded[input_len..],
+            &raw.as_slice()[10 + input_len..10 + encoded.len()]
+        );
+        assert_eq!(TxIn::read(&encoded[..input_len]).unwrap(), *input);
+        assert_eq!(TxOut::read(&encoded[input_len..]).unwrap(), *output);
+
+        let decoded = Transaction::try_from(Envelope::from(transaction.clone())).unwrap();
+        assert_eq!(decoded, transaction);
+
+        // The recorded output resolves the value spent from it.
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif
+            .add_transaction(transaction.txid(), transaction.clone())
+            .unwrap();
+        assert_eq!(
+            zewif.input_value(&OutPoint::new(transaction.txid(), 0)),
+            Some(output.value())
+        );
+    }
+
+    #[test]
+    fn test_output_annotations() {
+        let mut transaction = Transaction::new(TxId::from_bytes([4; 32]));
+        for (pool, index, text) in [
+            (Pool::Orchard, 0, "première"),
+            (Pool::Sapling, 2, "второй"),
+            (Pool::Orchard, 0, "最後"),
+        ] {
+            let mut annotation = OutputAnnotation::new(pool, index);
+            annotation.set_memo(Memo::from_text(text));
+            transaction.set_output_annotation(annotation);
+        }
+        let keys: Vec<_> = transaction
+            .output_annotations()
+            .iter()
+            .map(OutputAnnotation::key)
+            .collect();
+        assert_eq!(keys, [(Pool::Sapling, 2), (Pool::Orchard, 0)]);
+        let latest = transaction
+            .output_annotation(Pool::Orchard, 0)
+            .and_then(OutputAnnotation::memo);
+        assert_eq!(latest.and_then(Memo::text).as_deref(), Some("最後"));
+        assert_eq!(transaction.output_annotation(Pool::Sapling, 0), None);
+        assert_eq!(
+            Transaction::try_from(Envelope::from(transaction.clone())).unwrap(),
+            transaction
+        );
+    }
+
+    #[test]
+    fn test_parse_header_fields() {
+        let mut v4 = Transaction::new(TxId::from_bytes([5; 32]));
+        assert!(matches!(
+            v4.parse_header_fields(),
+            Err(Error::MissingRawTransaction { .. })
+        ));
+        v4.set_consensus_branch_id(Some(BranchId::Sapling));
+        v4.set_raw(fixture(include_str!("test_data/tx_v4_testnet.hex")));
+        v4.parse_header_fields().unwrap();
+        assert_eq!(
+            v4.version(),
+            Some(TxVersion::new(4, Some(TxVersion::SAPLING_VERSION_GROUP_ID)))
+        );
+        assert_eq!(v4.expiry_height(), Some(BlockHeight::from(280_023)));
+        // Version 4 transactions do not encode their branch.
+        assert_eq!(v4.consensus_branch_id(), Some(BranchId::Sapling));
+
+        let mut v5 = Transaction::new(TxId::from_bytes([6; 32]));
+        v5.set_raw(fixture(include_str!("test_data/tx_v5_zip244.hex")));
+        v5.parse_header_fields().unwrap();
+        assert_eq!(
+            v5.version(),
+            Some(TxVersion::new(5, Some(TxVersion::ZIP225_VERSION_GROUP_ID)))
+        );
+        assert_eq!(v5.consensus_branch_id(), Some(BranchId::Nu5));
+        assert_eq!(v5.expiry_height(), Some(BlockHeight::from(36_466_477)));
+
+        for transaction in [v4, v5] {
+            assert_eq!(
+                Transaction::try_from(Envelope::from(transaction.clone())).unwrap(),
+                transaction
+            );
+        }
+    }
+
+    /// Returns a version 1 transaction spending `inputs` and paying
+    /// `outputs`, with empty scripts.
+    fn v1_transaction(inputs: &[OutPoint], outputs: &[i64]) -> Transaction {
+        let mut raw = 1u32.to_le_bytes().to_vec();
+        raw.push(inputs.len() as u8);
+        for input in inputs {
+            raw.extend_from_slice(input.txid().as_bytes());
+            raw.extend_from_slice(&input.index().to_le_bytes());
+            raw.push(0);
+            raw.extend_from_slice(&u32::MAX.to_le_bytes());
+        }
+        raw.push(outputs.len() as u8);
+        for output in outputs {
+            raw.extend_from_slice(&output.to_le_bytes());
+            raw.push(0);
+        }
+        raw.extend_from_slice(&0u32.to_le_bytes());
+        let mut transaction = Transaction::new(TxId::from_raw_transaction(&raw).unwrap());
+        transaction.set_raw(Data::from_vec(raw));
+        transaction
+    }
+
+    #[test]
+    fn test_compute_fee() {
+        let funding = v1_transaction(&[], &[7_000, 5_000]);
+        let external = OutPoint::new(TxId::from_bytes([0xee; 32]), 3);
+        let spend = v1_transaction(
+            &[OutPoint::new(funding.txid(), 1), external],
+            &[4_000, 1_500],
+        );
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif
+            .add_transaction(funding.txid(), funding.clone())
+            .unwrap();
+
+        // The funding output is resolved from the Zewif, and the other input
+        // is supplied externally.
+        let values = BTreeMap::from([(external, Amount::from_u64(1_000).unwrap())]);
+        let fee = spend.compute_fee(&(&zewif, &values)).unwrap();
+        assert_eq!(fee, Amount::from_u64(500).unwrap());
+    }
+
+    #[test]
+    fn test_compute_fee_missing_input() {
+        let funding = v1_transaction(&[], &[7_000]);
+        let spend = v1_transaction(&[OutPoint::new(funding.txid(), 0)], &[6_000]);
+        let zewif = Zewif::new(BlockHeight::from(2_000_000));
+        let error = spend.compute_fee(&zewif).unwrap_err();
+        assert!(matches!(
+            &error,
+            Error::UnresolvedInput { outpoint } if *outpoint == OutPoint::new(funding.txid(), 0)
+        ));
+
+        // A spend of more than its inputs yields no valid fee.
+        let values = BTreeMap::from([(
+            OutPoint::new(funding.txid(), 0),
+            Amount::from_u64(5_000).unwrap(),
+        )]);
+        assert!(matches!(
+            spend.compute_fee(&values),
+            Err(Error::AmountUnderflow(_))
+        ));
+        assert!(matches!(
+            Transaction::new(spend.txid()).compute_fee(&values),
+            Err(Error::MissingRawTransaction { .. })
+        ));
+    }
+
+    #[test]
+    fn test_compute_fee_from_recorded_components() {
+        let funding = v1_transaction(&[], &[7_000]);
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif
+            .add_transaction(funding.txid(), funding.clone())
+            .unwrap();
+
+        let mut spend = Transaction::new(TxId::from_bytes([1; 32]));
+        spend.add_transparent_input(TxIn::new(
+            OutPoint::new(funding.txid(), 0),
+            Script::from(Data::new()),
+            u32::MAX,
+        ));
+        spend.add_transparent_output(TxOut::new(
+            Amount::from_u64(6_000).unwrap(),
+            Script::from(Data::new()),
+        ));
+        assert_eq!(
+            spend.compute_fee(&zewif).unwrap(),
+            Amount::from_u64(1_000).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_compute_fee_coinbase() {
+        let coinbase_input = OutPoint::new(TxId::from_bytes([0; 32]), u32::MAX);
+        let raw = v1_transaction(&[coinbase_input], &[625_000_000]);
+        let zewif = Zewif::new(BlockHeight::from(2_000_000));
+        assert_eq!(raw.compute_fee(&zewif).unwrap(), Amount::zero());
+
+        let mut recorded = Transaction::new(raw.txid());
+        recorded.add_transparent_input(TxIn::new(
+            coinbase_input,
+            Script::from(Data::new()),
+            u32::MAX,
+        ));
+        recorded.add_transparent_output(TxOut::new(
+            Amount::from_u64(625_000_000).unwrap(),
+            Script::from(Data::new()),
+        ));
+        assert!(recorded.is_coinbase());
+        assert_eq!(recorded.compute_fee(&zewif).unwrap(), Amount::zero());
+    }
+
+    /// Raw transactions are leaves, so large ones are compressed by
+    /// `Compression::LargeLeavesOver`.
+    #[test]
+    fn test_raw_compressed_when_large() {
+        use crate::{Compression, EncodeOptions, Zewif, ZewifEnvelope};
+
+        let txid =
+            TxId::from_hex("d0854b7070bb168392e7cf3d3a558711b49c2c0ad8eca3a8a14b8333bd962c55")
+                .unwrap();
+        let mut transaction = Transaction::new(txid);
+        transaction.set_raw(fixture(include_str!("test_data/tx_v5_zip244.hex")));
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_transaction(txid, transaction).unwrap();
+
+        let mut options = EncodeOptions::default();
+        options.set_compression(Compression::LargeLeavesOver(1024));
+        let envelope = zewif.to_envelope_with(&options).unwrap();
+        assert!(
+            ZewifEnvelope::new(envelope.clone())
+                .unwrap()
+                .has_compressed_elements()
+        );
+
+        let decoded = Zewif::try_from(envelope).unwrap();
+        assert_eq!(decoded, zewif);
+        assert!(decoded.transaction(&txid).unwrap().verify_txid().unwrap());
+    }
 }
@@ -0,0 +1,647 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashSet},
+};
+
+use crate::{
+    Account, Address, Amount, BlockHash, BlockHeight, Indexed, Memo, Network, NetworkParameters,
+    OutputAnnotation, Pool, SecondsSinceEpoch, Transaction, TransactionKind, TransactionStatus,
+    TxId, Zewif, ZewifWallet,
+};
+
+/// The change in value of one account caused by a transaction; see
+/// [`TransactionSummary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountValueChange {
+    wallet: usize,
+    account: usize,
+    received: Option<Amount>,
+    spent: Option<Amount>,
+    is_partial: bool,
+    kind: Option<TransactionKind>,
+}
+
+impl AccountValueChange {
+    /// Returns the index of the account's wallet in the Zewif.
+    pub fn wallet(&self) -> usize {
+        self.wallet
+    }
+
+    /// Returns the index of the account in its wallet.
+    pub fn account(&self) -> usize {
+        self.account
+    }
+
+    /// Returns the total value paid to the account over all pools, as given
+    /// by [`NetValueChange::received`](crate::NetValueChange::received), or
+    /// `None` if it overflows.
+    pub fn received(&self) -> Option<Amount> {
+        self.received
+    }
+
+    /// Returns the total value spent from the account over all pools, as
+    /// given by [`NetValueChange::spent`](crate::NetValueChange::spent), or
+    /// `None` if it overflows.
+    pub fn spent(&self) -> Option<Amount> {
+        self.spent
+    }
+
+    /// Returns whether some transparent inputs of the transaction could not
+    /// be resolved, so that the account may have spent more than
+    /// [`AccountValueChange::spent`] accounts for; see
+    /// [`NetValueChange::is_partial`](crate::NetValueChange::is_partial).
+    pub fn is_partial(&self) -> bool {
+        self.is_partial
+    }
+
+    /// Returns the net change in the account's value, or `None` if it
+    /// overflows.
+    pub fn value(&self) -> Option<Amount> {
+        self.received? - self.spent?
+    }
+
+    /// Returns the category of the transaction as seen by the account, as
+    /// given by [`Transaction::classify`], or `None` if the transaction is
+    /// not stored, does not count toward balances, or moved none of the
+    /// account's funds.
+    pub fn kind(&self) -> Option<TransactionKind> {
+        self.kind
+    }
+}
+
+/// One entry of a transaction history, as returned by
+/// [`Zewif::transaction_history`] and [`Account::transaction_history`].
+///
+/// Value changes are computed as by [`Transaction::net_value_change`]: a
+/// received note adds to the value of its account in the transaction that
+/// created it, and subtracts from it in the transaction recorded as spending
+/// it, and the transaction's transparent outputs and resolved inputs count
+/// for the accounts whose addresses they pay. A transaction missing from the
+/// Zewif's store moves only the value of the accounts' notes.
+///
+/// Each account's change also carries the category of the transaction as
+/// the account sees it; see [`AccountValueChange::kind`].
+///
+/// Transactions whose [`status`](Self::status) does not count toward
+/// balances, such as those abandoned or expired, are still listed, but move
+/// no value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransactionSummary {
+    txid: TxId,
+    status: TransactionStatus,
+    mined_height: Option<BlockHeight>,
+    block_hash: Option<BlockHash>,
+    block_time: Option<SecondsSinceEpoch>,
+    block_index: Option<u32>,
+    fee: Option<Amount>,
+    value_changes: Vec<AccountValueChange>,
+    output_annotations: Vec<OutputAnnotation>,
+}
+
+impl TransactionSummary {
+    pub fn txid(&self) -> TxId {
+        self.txid
+    }
+
+    /// Returns the recorded status of the transaction, or
+    /// [`TransactionStatus::Unknown`] if it is not held by the Zewif.
+    pub fn status(&self) -> TransactionStatus {
+        self.status
+    }
+
+    /// Returns the height at which the transaction was mined, or `None` if it
+    /// is unmined or not held by the Zewif.
+    pub fn mined_height(&self) -> Option<BlockHeight> {
+        self.mined_height
+    }
+
+    /// Returns the hash of the block containing the transaction, if
+    /// recorded.
+    pub fn block_hash(&self) -> Option<BlockHash> {
+        self.block_hash
+    }
+
+    /// Returns the time of the block containing the transaction, if
+    /// recorded.
+    pub fn block_time(&self) -> Option<SecondsSinceEpoch> {
+        self.block_time
+    }
+
+    /// Returns the position of the transaction within its block, if
+    /// recorded.
+    pub fn block_index(&self) -> Option<u32> {
+        self.block_index
+    }
+
+    /// Returns the fee recorded for the transaction, if any; see
+    /// [`Transaction::compute_fee`](crate::Transaction::compute_fee).
+    pub fn fee(&self) -> Option<Amount> {
+        self.fee
+    }
+
+    /// Returns the wallet's annotations of the transaction's outputs, ordered
+    /// by pool and index; see [`Transaction::output_annotations`].
+    pub fn output_annotations(&self) -> &[OutputAnnotation] {
+        &self.output_annotations
+    }
+
+    /// Returns the text of each of the transaction's decrypted memos that
+    /// holds text, with the pool and index of its output, for display; see
+    /// [`Memo::text`].
+    pub fn memos(&self) -> Vec<(Pool, u32, String)> {
+        self.output_annotations
+            .iter()
+            .filter_map(|annotation| {
+                Some((
+                    annotation.pool(),
+                    annotation.index(),
+                    annotation.memo().and_then(Memo::text)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns the change in value of each account involved in the
+    /// transaction, in wallet and account order.
+    pub fn value_changes(&self) -> &[AccountValueChange] {
+        &self.value_changes
+    }
+
+    /// Returns the net change in value over all the accounts, or `None` if it
+    /// overflows.
+    pub fn net_value(&self) -> Option<Amount> {
+        Amount::sum(
+            self.value_changes
+                .iter()
+                .map(AccountValueChange::value)
+                .collect::<Option<Vec<_>>>()?,
+        )
+    }
+
+    /// Returns whether the value change of any account is partial; see
+    /// [`AccountValueChange::is_partial`].
+    pub fn is_partial(&self) -> bool {
+        self.value_changes
+            .iter()
+            .any(AccountValueChange::is_partial)
+    }
+
+    /// Returns the category of the transaction as seen by all the accounts
+    /// together, or `None` if it moved none of their funds.
+    ///
+    /// If the accounts' [`kinds`](AccountValueChange::kind) agree, that is
+    /// the kind. Otherwise a coinbase transaction is
+    /// [`TransactionKind::Coinbase`], a transaction that accounts see only as
+    /// sending, receiving, or moving their own funds moved funds between
+    /// them, and is a [`TransactionKind::SelfTransfer`], and any other is
+    /// [`TransactionKind::Mixed`].
+    pub fn kind(&self) -> Option<TransactionKind> {
+        let kinds: Vec<_> = self
+            .value_changes
+            .iter()
+            .filter_map(AccountValueChange::kind)
+            .collect();
+        let first = *kinds.first()?;
+        let within = |kind: &TransactionKind| {
+            matches!(
+                kind,
+                TransactionKind::Sent | TransactionKind::Received | TransactionKind::SelfTransfer
+            )
+        };
+        Some(if kinds.iter().all(|kind| *kind == first) {
+            first
+        } else if kinds.contains(&TransactionKind::Coinbase) {
+            TransactionKind::Coinbase
+        } else if kinds.iter().all(within) {
+            TransactionKind::SelfTransfer
+        } else {
+            TransactionKind::Mixed
+        })
+    }
+
+    /// Orders summaries by mined height, with unmined transactions last, then
+    /// by block time, then by position within the block, then by transaction
+    /// ID.
+    fn history_order(&self, other: &Self) -> Ordering {
+        let height = |summary: &Self| (summary.mined_height.is_none(), summary.mined_height);
+        let time = |summary: &Self| (summary.block_time.is_none(), summary.block_time);
+        let index = |summary: &Self| (summary.block_index.is_none(), summary.block_index);
+        height(self)
+            .cmp(&height(other))
+            .then(time(self).cmp(&time(other)))
+            .then(index(self).cmp(&index(other)))
+            .then(self.txid.cmp(&other.txid))
+    }
+}
+
+/// An account whose value changes a history reports, with the transactions
+/// involving it and the addresses its transparent outputs are matched
+/// against, gathered once for the whole history.
+struct HistoryAccount<'a> {
+    wallet: usize,
+    account: &'a Account,
+    holder: Option<&'a ZewifWallet>,
+    txids: HashSet<TxId>,
+    addresses: HashSet<String>,
+    params: NetworkParameters,
+}
+
+impl<'a> HistoryAccount<'a> {
+    /// Transparent outputs are matched against the account's addresses only
+    /// if the Zewif holds its wallet, whose network parameters encode them.
+    fn new(zewif: &'a Zewif, wallet: usize, account: &'a Account) -> Self {
+        let holder = zewif.wallets().get(wallet);
+        let txids = account
+            .relevant_transactions()
+            .copied()
+            .chain(account.note_transactions())
+            .collect();
+        let addresses = match holder {
+            Some(_) => account.addresses().iter().map(Address::as_string).collect(),
+            None => HashSet::new(),
+        };
+        // Without addresses to match, the parameters are not consulted.
+        let params = holder.map_or_else(
+            || NetworkParameters::for_network(Network::Main),
+            ZewifWallet::effective_network_parameters,
+        );
+        Self {
+            wallet,
+            account,
+            holder,
+            txids,
+            addresses,
+            params,
+        }
+    }
+}
+
+/// Returns the change in the account's value caused by `txid`, which is
+/// zero unless the transaction counts toward balances.
+fn value_change(zewif: &Zewif, history: &HistoryAccount, txid: TxId) -> AccountValueChange {
+    let zero = Some(Amount::zero());
+    let mut value_change = AccountValueChange {
+        wallet: history.wallet,
+        account: history.account.index(),
+        received: zero,
+        spent: zero,
+        is_partial: false,
+        kind: None,
+    };
+    if !zewif.counts_toward_balance(txid) {
+        return value_change;
+    }
+    let stored = zewif.transaction(&txid);
+    let unstored = Transaction::new(txid);
+    let transaction = stored.unwrap_or(&unstored);
+    match transaction.value_change_of(history.account, &history.addresses, &history.params, zewif) {
+        Ok(change) => {
+            value_change.received = change.received().total();
+            value_change.spent = change.spent().total();
+            value_change.is_partial = change.is_partial();
+            value_change.kind = stored
+                .and(history.holder)
+                .and_then(|_| transaction.kind_of(&change, zewif));
+        }
+        Err(_) => (value_change.received, value_change.spent) = (None, None),
+    }
+    value_change
+}
+
+impl Zewif {
+    /// Returns the history of the transactions involving any account: those
+    /// recorded as relevant to it, and those that created or spent its notes.
+    ///
+    /// The history is ordered by mined height, with unmined transactions
+    /// last, and transactions mined in the same block by their recorded
+    /// position in it. Transactions whose height is unknown but whose block
+    /// time is recorded precede the rest, in order of time. Transactions
+    /// missing from the Zewif's store are listed as unmined.
+    pub fn transaction_history(&self) -> Vec<TransactionSummary> {
+        let accounts: Vec<_> = self
+            .wallets()
+            .iter()
+            .flat_map(|wallet| {
+                wallet
+                    .accounts()
+                    .iter()
+                    .map(move |account| (wallet.index(), account))
+            })
+            .collect();
+        self.history(&accounts)
+    }
+
+    fn history(&self, accounts: &[(usize, &Account)]) -> Vec<TransactionSummary> {
+        let accounts: Vec<_> = accounts
+            .iter()
+            .map(|&(wallet, account)| HistoryAccount::new(self, wallet, account))
+            .collect();
+        let txids: BTreeSet<TxId> = accounts
+            .iter()
+            .flat_map(|account| account.txids.iter().copied())
+            .collect();
+        let mut history: Vec<_> = txids
+            .into_iter()
+            .map(|txid| {
+                let transaction = self.transaction(&txid);
+                let value_changes = accounts
+                    .iter()
+                    .filter(|account| account.txids.contains(&txid))
+                    .map(|account| value_change(self, account, txid))
+                    .collect();
+                TransactionSummary {
+                    txid,
+                    status: transaction.map_or(TransactionStatus::Unknown, Transaction::status),
+                    mined_height: transaction
+                        .and_then(|transaction| transaction.mined_height().copied()),
+                    block_hash: transaction
+                        .and_then(|transaction| transaction.block_hash().copied()),
+                    block_time: transaction.and_then(Transaction::block_time),
+                    block_index: transaction
+                        .and_then(|transaction| transaction.block_position())
+                        .map(|p| p.index()),
+                    fee: transaction.and_then(Transaction::fee),
+                    value_changes,
+                    output_annotations: transaction.map_or_else(Vec::new, |transaction| {
+                        transaction.output_annotations().clone()
+                    }),
+                }
+            })
+            .collect();
+        history.sort_by(TransactionSummary::history_order);
+        history
+    }
+}
+
+impl Account {
+    /// Returns the history of the transactions involving the account, ordered
+    /// as by [`Zewif::transaction_history`], with heights taken from
+    /// `zewif`'s transaction store.
+    ///
+    /// `wallet` is the index of the account's wallet, which identifies the
+    /// account in the summaries' value changes. Since only this account is
+    /// considered, a transfer to another account appears as
+    /// [`TransactionKind::Sent`] rather than
+    /// [`TransactionKind::SelfTransfer`].
+    pub fn transaction_history(&self, wallet: usize, zewif: &Zewif) -> Vec<TransactionSummary> {
+        zewif.history(&[(wallet, self)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{note, transaction, txid, zats};
+    use crate::{
+        Account, Address, Amount, BlockHash, BlockHeight, Data, Memo, Network, OutPoint,
+        OutputAnnotation, Pool, ProtocolAddress, Script, SecondsSinceEpoch, Transaction,
+        TransactionKind, TransactionStatus, TxBlockPosition, TxIn, TxOut, Zewif, ZewifWallet,
+        transparent,
+    };
+
+    /// A transaction mined at `height` and `index` within its block, if
+    /// given.
+    fn mined(byte: u8, height: Option<u32>, index: Option<u32>) -> Transaction {
+        let mut transaction = transaction(byte, vec![], vec![]);
+        if let Some(height) = height {
+            transaction.set_mined_height(BlockHeight::from(height));
+        }
+        transaction.set_block_position(
+            index.map(|index| TxBlockPosition::new(BlockHash::from_bytes([0; 32]), index)),
+        );
+        transaction
+    }
+
+    /// Account 0 receives 5 in transaction 1, and in transaction 2 spends
+    /// it, paying 2 to account 1 and 2 back to itself. Transaction 3 pays 1
+    /// to account 1; transaction 4 is relevant to account 1 alone and
+    /// involves no notes.
+    fn zewif() -> Zewif {
+        let mut wallet = ZewifWallet::new(Network::Main);
+        let mut first = Account::new();
+        first.add_sapling_received_note(note(txid(1), 5, Some(txid(2))));
+        first.add_sapling_received_note(note(txid(2), 2, None));
+        wallet.add_account(first);
+        let mut second = Account::new();
+        second.add_sapling_received_note(note(txid(2), 2, None));
+        second.add_sapling_received_note(note(txid(3), 1, None));
+        second.add_relevant_transaction(txid(4));
+        wallet.add_account(second);
+
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        let mut receive = mined(1, Some(1_000), Some(4));
+        receive.set_fee(Some(zats(1_000)));
+        let transactions = [
+            receive,
+            mined(2, Some(1_200), Some(1)),
+            mined(3, None, None),
+            mined(4, Some(1_200), Some(0)),
+        ];
+        for transaction in transactions {
+            zewif
+                .add_transaction(transaction.txid(), transaction)
+                .unwrap();
+        }
+        zewif
+    }
+
+    #[test]
+    fn test_history_order() {
+        let zewif = zewif();
+        let history = zewif.transaction_history();
+        // Transactions 4 and 2 share a block, in which 4 comes first; the
+        // unmined transaction 3 comes last.
+        let txids: Vec<_> = history.iter().map(|summary| summary.txid()).collect();
+        assert_eq!(txids, [txid(1), txid(4), txid(2), txid(3)]);
+        assert_eq!(history[1].mined_height(), Some(BlockHeight::from(1_200)));
+        assert_eq!(history[1].block_index(), Some(0));
+        assert_eq!(history[0].fee(), Some(zats(1_000)));
+        assert_eq!(history[1].fee(), None);
+        assert_eq!(history[3].mined_height(), None);
+
+        // Unmined transactions, and those missing from the store, are ordered
+        // by ID.
+        let mut zewif = zewif.clone();
+        zewif.wallets_mut()[0].accounts_mut()[0].add_relevant_transaction(txid(0));
+        let txids: Vec<_> = zewif
+            .transaction_history()
+            .iter()
+            .map(|summary| summary.txid())
+            .collect();
+        assert_eq!(txids, [txid(1), txid(4), txid(2), txid(0), txid(3)]);
+
+        // A block time orders a transaction of unknown height ahead of the
+        // unmined ones.
+        let mut transaction = zewif.transaction(&txid(3)).unwrap().clone();
+        let hash = BlockHash::from_bytes([3; 32]);
+        transaction.set_block_metadata(
+            None,
+            Some(hash),
+            Some(SecondsSinceEpoch::from(1_600_000_000)),
+        );
+        zewif.replace_transaction(txid(3), transaction);
+        let history = zewif.transaction_history();
+        let txids: Vec<_> = history.iter().map(|summary| summary.txid()).collect();
+        assert_eq!(txids, [txid(1), txid(4), txid(2), txid(3), txid(0)]);
+        assert_eq!(history[3].block_hash(), Some(hash));
+        assert_eq!(
+            history[3].block_time(),
+            Some(SecondsSinceEpoch::from(1_600_000_000))
+        );
+        assert_eq!(
+            history[0].block_hash(),
+            Some(BlockHash::from_bytes([0; 32]))
+        );
+    }
+
+    #[test]
+    fn test_history_excludes_unmined() {
+        let mut zewif = zewif();
+        let mut transaction = zewif.transaction(&txid(2)).unwrap().clone();
+        transaction.set_status(TransactionStatus::Abandoned);
+        zewif.replace_transaction(txid(2), transaction);
+
+        // The abandoned transfer is listed, flagged, but moves no value, so
+        // the note it would have spent is still held.
+        let history = zewif.transaction_history();
+        let abandoned = history
+            .iter()
+            .find(|summary| summary.txid() == txid(2))
+            .unwrap();
+        assert_eq!(abandoned.status(), TransactionStatus::Abandoned);
+        assert_eq!(abandoned.kind(), None);
+        assert_eq!(abandoned.net_value(), Some(Amount::zero()));
+        assert_eq!(history[0].status(), TransactionStatus::Unknown);
+        assert_eq!(zewif.total_balance().unwrap().sapling(), zats(6));
+    }
+
+    #[test]
+    fn test_history_memos() {
+        let mut zewif = zewif();
+        let mut transaction = zewif.transaction(&txid(2)).unwrap().clone();
+        let mut annotation = OutputAnnotation::new(Pool::Sapling, 1);
+        annotation.set_memo(Memo::from_text("Danke für das Essen 🍜"));
+        annotation.set_recipient(Some("zs1friend".to_string()));
+        transaction.set_output_annotation(annotation);
+        // A memo of arbitrary data is kept, but has no text to display.
+        let mut binary = OutputAnnotation::new(Pool::Sapling, 0);
+        binary.set_memo(Some(Memo::from_vec(vec![0xFF; 512])));
+        transaction.set_output_annotation(binary);
+        zewif.replace_transaction(txid(2), transaction);
+
+        let history = zewif.transaction_history();
+        let summary = history
+            .iter()
+            .find(|summary| summary.txid() == txid(2))
+            .unwrap();
+        assert_eq!(summary.output_annotations().len(), 2);
+        assert_eq!(
+            summary.output_annotations()[1].recipient(),
+            Some("zs1friend")
+        );
+        assert_eq!(
+            summary.memos(),
+            [(Pool::Sapling, 1, "Danke für das Essen 🍜".to_string())]
+        );
+        assert!(
+            history
+                .iter()
+                .filter(|summary| summary.txid() != txid(2))
+                .all(|summary| summary.memos().is_empty())
+        );
+    }
+
+    #[test]
+    fn test_history_values() {
+        let zewif = zewif();
+        let history = zewif.transaction_history();
+        let kinds: Vec<_> = history.iter().map(|summary| summary.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                Some(TransactionKind::Received),
+                None,
+                Some(TransactionKind::SelfTransfer),
+                Some(TransactionKind::Received)
+            ]
+        );
+
+        let internal = &history[2];
+        let values: Vec<_> = internal
+            .value_changes()
+            .iter()
+            .map(|change| (change.account(), change.value()))
+            .collect();
+        assert_eq!(values, [(0, Some(zats(-3))), (1, Some(zats(2)))]);
+        assert_eq!(internal.value_changes()[0].spent(), Some(zats(5)));
+        assert_eq!(internal.net_value(), Some(zats(-1)));
+        assert_eq!(history[1].value_changes()[0].value(), Some(Amount::zero()));
+        let kinds: Vec<_> = internal
+            .value_changes()
+            .iter()
+            .map(|change| change.kind())
+            .collect();
+        assert_eq!(
+            kinds,
+            [Some(TransactionKind::Sent), Some(TransactionKind::Received)]
+        );
+        assert_eq!(history[1].value_changes()[0].kind(), None);
+
+        // Seen from the first account alone, the transfer is a send.
+        let account = &zewif.wallets()[0].accounts()[0];
+        let history = account.transaction_history(0, &zewif);
+        assert_eq!(
+            history
+                .iter()
+                .map(|summary| summary.txid())
+                .collect::<Vec<_>>(),
+            [txid(1), txid(2)]
+        );
+        assert_eq!(history[1].kind(), Some(TransactionKind::Sent));
+        assert_eq!(history[1].value_changes().len(), 1);
+        assert_eq!(history[1].net_value(), Some(zats(-3)));
+    }
+
+    /// Transparent funds count toward the value changes of the history as
+    /// they do for `Transaction::net_value_change`.
+    #[test]
+    fn test_history_transparent_values() {
+        let script = [&[0x76, 0xa9, 0x14][..], &[1; 20], &[0x88, 0xac]].concat();
+        let output = TxOut::new(zats(3_000), Script::from(Data::from_vec(script)));
+        let mut zewif = zewif();
+        let address = output.address(Network::Main).unwrap();
+        let account = &mut zewif.wallets_mut()[0].accounts_mut()[1];
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new(address),
+        )));
+        account.add_relevant_transaction(txid(5));
+        let mut transaction = mined(5, Some(1_300), Some(0));
+        transaction.add_transparent_input(TxIn::new(
+            OutPoint::new(txid(9), 0),
+            Script::from(Data::new()),
+            u32::MAX,
+        ));
+        transaction.add_transparent_output(output);
+        zewif.add_transaction(txid(5), transaction).unwrap();
+
+        let history = zewif.transaction_history();
+        let summary = history
+            .iter()
+            .find(|summary| summary.txid() == txid(5))
+            .unwrap();
+        let change = &summary.value_changes()[0];
+        let account = &zewif.wallets()[0].accounts()[1];
+        let expected = zewif
+            .transaction(&txid(5))
+            .unwrap()
+            .net_value_change(account, &zewif)
+            .unwrap();
+        assert_eq!(change.value(), Some(expected.value()));
+        assert_eq!(change.received(), Some(zats(3_000)));
+        assert_eq!(change.kind(), Some(TransactionKind::Received));
+        assert!(change.is_partial() && summary.is_partial());
+        assert!(!history[0].is_partial());
+    }
+}
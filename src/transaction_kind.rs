@@ -0,0 +1,259 @@
+use std::fmt;
+
+use crate::{
+    Account, Amount, NetValueChange, Result, Transaction, TxOut, Zewif, zewif_impl::pool_total,
+};
+
+/// The category of a transaction as seen by one account, for display in a
+/// history; see [`Transaction::classify`].
+///
+/// The transparent pool is distinguished from the shielded pools taken
+/// together, so a transfer from Sapling to Orchard within an account is a
+/// [`TransactionKind::SelfTransfer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransactionKind {
+    /// The transaction paid the account without spending its funds.
+    Received,
+    /// The transaction spent the account's funds to pay others, perhaps
+    /// returning change in the pools it spent from.
+    Sent,
+    /// The transaction moved the account's funds within the transparent or
+    /// the shielded pools, paying no one else.
+    SelfTransfer,
+    /// The transaction moved the account's transparent funds into the
+    /// shielded pools, paying no one else.
+    Shielding,
+    /// The transaction moved the account's shielded funds into the
+    /// transparent pool, paying no one else.
+    Deshielding,
+    /// The transaction spent from, or returned funds to, both the transparent
+    /// and the shielded pools in a way none of the other kinds describes,
+    /// such as paying others while shielding the change.
+    Mixed,
+    /// The transaction is a coinbase transaction, creating new funds.
+    Coinbase,
+}
+
+impl fmt::Display for TransactionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TransactionKind::Received => "received",
+            TransactionKind::Sent => "sent",
+            TransactionKind::SelfTransfer => "self-transfer",
+            TransactionKind::Shielding => "shielding",
+            TransactionKind::Deshielding => "deshielding",
+            TransactionKind::Mixed => "mixed",
+            TransactionKind::Coinbase => "coinbase",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Transaction {
+    /// Classifies the transaction by how it moved `account`'s funds, or
+    /// returns `None` if it neither paid the account nor spent its funds.
+    ///
+    /// The funds moved are those found by
+    /// [`Transaction::net_value_change`]. A coinbase transaction, recognized
+    /// by its transparent input (see [`Transaction::is_coinbase`]), is
+    /// [`TransactionKind::Coinbase`]. Otherwise the account paid others if
+    /// some transparent output pays another address, or if it lost more than
+    /// the fee, which is the recorded one or, failing that, one computed with
+    /// `zewif` resolving the inputs. If the fee is unknown, any loss is taken
+    /// as a payment, so a transfer within the account cannot be told from a
+    /// send.
+    ///
+    /// # Errors
+    /// Returns an error as for [`Transaction::net_value_change`].
+    pub fn classify(&self, account: &Account, zewif: &Zewif) -> Result<Option<TransactionKind>> {
+        let change = self.net_value_change(account, zewif)?;
+        Ok(self.kind_of(&change, zewif))
+    }
+
+    pub(crate) fn kind_of(
+        &self,
+        change: &NetValueChange,
+        zewif: &Zewif,
+    ) -> Option<TransactionKind> {
+        let zero = Amount::zero();
+        let (received, spent) = (change.received(), change.spent());
+        let spent_transparent = spent.transparent() != zero;
+        let spent_shielded = [spent.sprout(), spent.sapling(), spent.orchard()] != [zero; 3];
+        let received_transparent = received.transparent() != zero;
+        let received_shielded =
+            [received.sprout(), received.sapling(), received.orchard()] != [zero; 3];
+        if !(spent_transparent || spent_shielded || received_transparent || received_shielded) {
+            return None;
+        }
+        if self.is_coinbase() {
+            return Some(TransactionKind::Coinbase);
+        }
+        if !(spent_transparent || spent_shielded) {
+            return Some(TransactionKind::Received);
+        }
+
+        let transparent_to_others = pool_total(self.transparent_outputs().iter().map(TxOut::value))
+            .is_ok_and(|total| total != received.transparent());
+        let fee = self.fee().or_else(|| self.compute_fee(zewif).ok());
+        let pays_others = transparent_to_others || -change.value() > fee.unwrap_or(Amount::zero());
+        let crosses_pools =
+            (received_transparent && !spent_transparent) || (received_shielded && !spent_shielded);
+        let kind = match (
+            spent_transparent,
+            spent_shielded,
+            received_transparent,
+            received_shielded,
+        ) {
+            _ if pays_others && crosses_pools => TransactionKind::Mixed,
+            _ if pays_others => TransactionKind::Sent,
+            (_, _, false, false) => TransactionKind::Sent,
+            (true, false, false, true) => TransactionKind::Shielding,
+            (false, true, true, false) => TransactionKind::Deshielding,
+            (true, false, true, false) | (false, true, false, true) => {
+                TransactionKind::SelfTransfer
+            }
+            _ => TransactionKind::Mixed,
+        };
+        Some(kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{note, p2pkh, spend_of, transaction, txid, zats};
+    use crate::{
+        Account, Address, BlockHeight, Network, ProtocolAddress, Transaction, TransactionKind,
+        TxId, Zewif, ZewifWallet, sapling, transparent,
+    };
+
+    fn with_fee(mut transaction: Transaction, fee: i64) -> Transaction {
+        transaction.set_fee(Some(zats(fee)));
+        transaction
+    }
+
+    /// Builds a Zewif whose one account owns the transparent address with
+    /// hash 1 and the given Sapling notes, holding the given transactions,
+    /// and returns the classification of each.
+    fn classify(
+        notes: Vec<sapling::SaplingReceivedNote>,
+        transactions: Vec<Transaction>,
+    ) -> Vec<Option<TransactionKind>> {
+        let mut account = Account::new();
+        let address = p2pkh(1, 0).address(Network::Test).unwrap();
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new(address),
+        )));
+        notes
+            .into_iter()
+            .for_each(|note| account.add_sapling_received_note(note));
+        let mut wallet = ZewifWallet::new(Network::Test);
+        wallet.add_account(account);
+        let mut zewif = Zewif::new(BlockHeight::from(1_000));
+        zewif.add_wallet(wallet);
+        for transaction in &transactions {
+            zewif
+                .add_transaction(transaction.txid(), transaction.clone())
+                .unwrap();
+        }
+        let account = &zewif.wallets()[0].accounts()[0];
+        transactions
+            .iter()
+            .map(|transaction| transaction.classify(account, &zewif).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_transparent_kinds() {
+        let coinbase = transaction(
+            1,
+            vec![spend_of(TxId::from_bytes([0; 32]), u32::MAX)],
+            vec![p2pkh(1, 5_000)],
+        );
+        let received = transaction(2, vec![spend_of(txid(9), 0)], vec![p2pkh(1, 3_000)]);
+        // Pays 1_000 to hash 2 with 1_900 change, then consolidates the
+        // change and the coinbase output.
+        let sent = with_fee(
+            transaction(
+                3,
+                vec![spend_of(txid(2), 0)],
+                vec![p2pkh(2, 1_000), p2pkh(1, 1_900)],
+            ),
+            100,
+        );
+        let consolidated = with_fee(
+            transaction(
+                4,
+                vec![spend_of(txid(3), 1), spend_of(txid(1), 0)],
+                vec![p2pkh(1, 6_800)],
+            ),
+            100,
+        );
+        let unrelated = transaction(5, vec![spend_of(txid(9), 1)], vec![p2pkh(2, 1_000)]);
+        assert_eq!(
+            classify(
+                vec![],
+                vec![coinbase, received, sent, consolidated, unrelated]
+            ),
+            [
+                Some(TransactionKind::Coinbase),
+                Some(TransactionKind::Received),
+                Some(TransactionKind::Sent),
+                Some(TransactionKind::SelfTransfer),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shielded_kinds() {
+        // Transaction 2 shields the output of transaction 1; transaction 3
+        // sends from the resulting note with shielded change; transaction 4
+        // moves the change to a fresh note; transaction 5 deshields that
+        // note; transaction 6 receives a note.
+        let notes = vec![
+            note(txid(2), 2_900, Some(txid(3))),
+            note(txid(3), 1_800, Some(txid(4))),
+            note(txid(4), 1_700, Some(txid(5))),
+            note(txid(6), 400, None),
+        ];
+        let transactions = vec![
+            transaction(1, vec![spend_of(txid(9), 0)], vec![p2pkh(1, 3_000)]),
+            with_fee(transaction(2, vec![spend_of(txid(1), 0)], vec![]), 100),
+            with_fee(transaction(3, vec![], vec![]), 100),
+            with_fee(transaction(4, vec![], vec![]), 100),
+            with_fee(transaction(5, vec![], vec![p2pkh(1, 1_600)]), 100),
+            transaction(6, vec![], vec![]),
+        ];
+        let kinds = classify(notes, transactions);
+        assert_eq!(
+            kinds[1..],
+            [
+                Some(TransactionKind::Shielding),
+                Some(TransactionKind::Sent),
+                Some(TransactionKind::SelfTransfer),
+                Some(TransactionKind::Deshielding),
+                Some(TransactionKind::Received)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mixed() {
+        // Spends a transparent output, paying 1_000 to hash 2 and shielding
+        // the change.
+        let notes = vec![note(txid(2), 1_900, None)];
+        let transactions = vec![
+            transaction(1, vec![spend_of(txid(9), 0)], vec![p2pkh(1, 3_000)]),
+            with_fee(
+                transaction(2, vec![spend_of(txid(1), 0)], vec![p2pkh(2, 1_000)]),
+                100,
+            ),
+        ];
+        assert_eq!(
+            classify(notes, transactions)[1],
+            Some(TransactionKind::Mixed)
+        );
+        assert_eq!(TransactionKind::SelfTransfer.to_string(), "self-transfer");
+    }
+}
@@ -0,0 +1,193 @@
+use bc_envelope::prelude::*;
+
+use crate::BlockHeight;
+
+/// Where a transaction stands with respect to the chain, as last seen by the
+/// source wallet.
+///
+/// Wallets keep transactions they created that were never mined: zcashd holds
+/// them in its wallet until they are mined, expire past their expiry height,
+/// or are abandoned with `abandontransaction` (`fAbandoned`), and a
+/// transaction conflicting with a mined one can never be mined. Only mined
+/// transactions move funds, so the balances computed by
+/// [`Zewif::total_balance`](crate::Zewif::total_balance) and the values
+/// reported by [`Zewif::transaction_history`](crate::Zewif::transaction_history)
+/// exclude the others; see [`TransactionStatus::counts_toward_balance`].
+///
+/// # Examples
+/// ```
+/// # use zewif::{BlockHeight, TransactionStatus};
+/// let status = TransactionStatus::from_zcashd(
+///     None,
+///     Some(BlockHeight::from(1_000)),
+///     false,
+///     false,
+///     BlockHeight::from(1_200),
+/// );
+/// assert_eq!(status, TransactionStatus::Expired);
+/// assert!(!status.counts_toward_balance());
+/// assert!(TransactionStatus::Unknown.counts_toward_balance());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransactionStatus {
+    /// Mined at the given height.
+    Mined { height: BlockHeight },
+
+    /// Broadcast or awaiting broadcast, and not yet mined.
+    Mempool,
+
+    /// Not mined by its expiry height, and so never minable.
+    Expired,
+
+    /// Abandoned by the user, or conflicting with a mined transaction.
+    Abandoned,
+
+    /// The source wallet did not record the transaction's status.
+    #[default]
+    Unknown,
+}
+
+impl TransactionStatus {
+    /// Returns the status of a transaction from zcashd's wallet state: the
+    /// height of the block containing it, if any, its expiry height, if any
+    /// (zcashd records zero for none), whether it is marked `fAbandoned`,
+    /// whether it conflicts with a mined transaction, and the height of the
+    /// wallet's chain tip.
+    pub fn from_zcashd(
+        mined_height: Option<BlockHeight>,
+        expiry_height: Option<BlockHeight>,
+        abandoned: bool,
+        conflicted: bool,
+        tip_height: BlockHeight,
+    ) -> Self {
+        match mined_height {
+            Some(height) => Self::Mined { height },
+            None if abandoned || conflicted => Self::Abandoned,
+            None if expiry_height
+                .is_some_and(|expiry| u32::from(expiry) != 0 && expiry <= tip_height) =>
+            {
+                Self::Expired
+            }
+            None => Self::Mempool,
+        }
+    }
+
+    pub fn is_mined(&self) -> bool {
+        matches!(self, Self::Mined { .. })
+    }
+
+    /// Returns the height at which the transaction was mined, if it was.
+    pub fn mined_height(&self) -> Option<BlockHeight> {
+        match self {
+            Self::Mined { height } => Some(*height),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the notes the transaction creates and spends count
+    /// toward balances: those of mined transactions do, and so, for lack of
+    /// evidence otherwise, do those of transactions of unknown status.
+    pub fn counts_toward_balance(&self) -> bool {
+        matches!(self, Self::Mined { .. } | Self::Unknown)
+    }
+}
+
+impl From<TransactionStatus> for Envelope {
+    fn from(value: TransactionStatus) -> Self {
+        match value {
+            TransactionStatus::Mined { height } => {
+                Envelope::new("Mined").add_assertion("height", height)
+            }
+            TransactionStatus::Mempool => Envelope::new("Mempool"),
+            TransactionStatus::Expired => Envelope::new("Expired"),
+            TransactionStatus::Abandoned => Envelope::new("Abandoned"),
+            TransactionStatus::Unknown => Envelope::new("Unknown"),
+        }
+        .add_type("TransactionStatus")
+    }
+}
+
+impl TryFrom<Envelope> for TransactionStatus {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("TransactionStatus")?;
+        match envelope.extract_subject::<String>()?.as_str() {
+            "Mined" => Ok(TransactionStatus::Mined {
+                height: envelope.extract_object_for_predicate("height")?,
+            }),
+            "Mempool" => Ok(TransactionStatus::Mempool),
+            "Expired" => Ok(TransactionStatus::Expired),
+            "Abandoned" => Ok(TransactionStatus::Abandoned),
+            "Unknown" => Ok(TransactionStatus::Unknown),
+            other => Err(bc_envelope::Error::General(format!(
+                "invalid transaction status: {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BlockHeight, test_envelope_roundtrip};
+
+    use super::TransactionStatus;
+
+    impl crate::RandomInstance for TransactionStatus {
+        fn random() -> Self {
+            match rand::random_range(0..5) {
+                0 => TransactionStatus::Mined {
+                    height: BlockHeight::random(),
+                },
+                1 => TransactionStatus::Mempool,
+                2 => TransactionStatus::Expired,
+                3 => TransactionStatus::Abandoned,
+                _ => TransactionStatus::Unknown,
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(TransactionStatus);
+
+    #[test]
+    fn test_from_zcashd() {
+        let height = |height: u32| BlockHeight::from(height);
+        let tip = height(1_200);
+        let mined = TransactionStatus::from_zcashd(
+            Some(height(1_100)),
+            Some(height(1_000)),
+            false,
+            false,
+            tip,
+        );
+        assert_eq!(
+            mined,
+            TransactionStatus::Mined {
+                height: height(1_100)
+            }
+        );
+        assert_eq!(
+            TransactionStatus::from_zcashd(None, None, true, false, tip),
+            TransactionStatus::Abandoned
+        );
+        assert_eq!(
+            TransactionStatus::from_zcashd(None, None, false, true, tip),
+            TransactionStatus::Abandoned
+        );
+        assert_eq!(
+            TransactionStatus::from_zcashd(None, Some(height(1_000)), false, false, tip),
+            TransactionStatus::Expired
+        );
+        // An expiry height of zero disables expiry.
+        assert_eq!(
+            TransactionStatus::from_zcashd(None, Some(height(0)), false, false, tip),
+            TransactionStatus::Mempool
+        );
+        assert_eq!(
+            TransactionStatus::from_zcashd(None, Some(height(1_300)), false, false, tip),
+            TransactionStatus::Mempool
+        );
+    }
+}
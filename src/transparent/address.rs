@@ -1,4 +1,4 @@
-use crate::DerivationInfo;
+use crate::{DerivationInfo, KeyOrigin, watch_only::optional_spending_object};
 
 use super::TransparentSpendAuthority;
 use bc_envelope::prelude::*;
@@ -49,6 +49,7 @@ use bc_envelope::prelude::*;
 /// assert_eq!(address.address(), "t1exampleaddress");
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Address {
     /// The transparent address string (starting with 't')
     /// This is used as a unique identifier within the wallet
@@ -61,6 +62,9 @@ pub struct Address {
     /// Optional HD wallet derivation information
     /// When present, this contains the path information for HD wallets
     derivation_info: Option<DerivationInfo>,
+
+    /// Whether the spending key was derived from a seed or imported, if known
+    key_origin: Option<KeyOrigin>,
 }
 
 impl Address {
@@ -84,6 +88,7 @@ impl Address {
             address: address.into(),
             spend_authority: None,
             derivation_info: None,
+            key_origin: None,
         }
     }
 
@@ -145,6 +150,16 @@ impl Address {
     pub fn set_derivation_info(&mut self, derivation_info: DerivationInfo) {
         self.derivation_info = Some(derivation_info);
     }
+
+    /// Returns whether the address's spending key was derived from a seed or
+    /// imported, if known.
+    pub fn key_origin(&self) -> Option<&KeyOrigin> {
+        self.key_origin.as_ref()
+    }
+
+    pub fn set_key_origin(&mut self, key_origin: Option<KeyOrigin>) {
+        self.key_origin = key_origin;
+    }
 }
 
 impl From<Address> for Envelope {
@@ -153,6 +168,7 @@ impl From<Address> for Envelope {
             .add_type("TransparentAddress")
             .add_optional_assertion("spend_authority", value.spend_authority)
             .add_optional_assertion("derivation_info", value.derivation_info)
+            .add_optional_assertion("key_origin", value.key_origin)
     }
 }
 
@@ -162,11 +178,16 @@ impl TryFrom<Envelope> for Address {
     fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
         envelope.check_type("TransparentAddress")?;
         let address = envelope.extract_subject()?;
-        let spend_authority =
-            envelope.try_optional_object_for_predicate("spend_authority")?;
+        let spend_authority = optional_spending_object(&envelope, "spend_authority")?;
         let derivation_info =
             envelope.try_optional_object_for_predicate("derivation_info")?;
-        Ok(Address { address, spend_authority, derivation_info })
+        let key_origin = envelope.try_optional_object_for_predicate("key_origin")?;
+        Ok(Address {
+            address,
+            spend_authority,
+            derivation_info,
+            key_origin,
+        })
     }
 }
 
@@ -177,6 +198,7 @@ impl crate::RandomInstance for Address {
             address: String::random(),
             spend_authority: TransparentSpendAuthority::opt_random(),
             derivation_info: DerivationInfo::opt_random(),
+            key_origin: KeyOrigin::opt_random(),
         }
     }
 }
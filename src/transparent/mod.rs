@@ -3,5 +3,7 @@
 use crate::mod_use;
 
 mod_use!(address);
+mod_use!(transparent_account_pub_key);
 mod_use!(transparent_spending_key);
 mod_use!(transparent_spend_authority);
+mod_use!(utxo);
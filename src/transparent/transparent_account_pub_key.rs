@@ -0,0 +1,21 @@
+use crate::{blob, blob_envelope};
+
+blob!(
+    TransparentAccountPubKey,
+    65,
+    r#"The extended public key of a transparent BIP 44 account, encoded as
+`chain_code || compressed_public_key`.
+
+This is the pruned encoding used for the transparent component of a ZIP 316
+unified full viewing key. The depth, parent fingerprint and child index of the
+full extended key are omitted; non-hardened child addresses can still be
+derived from it.
+
+# Examples
+```
+# use zewif::transparent::TransparentAccountPubKey;
+let key = TransparentAccountPubKey::new([0u8; 65]);
+```"#
+);
+
+blob_envelope!(TransparentAccountPubKey);
@@ -10,7 +10,8 @@ use crate::{blob, blob_envelope};
 blob!(
     TransparentSpendingKey,
     32,
-    "A Zcash transparent private key"
+    "A Zcash transparent private key",
+    secret
 );
 impl Copy for TransparentSpendingKey {}
 
@@ -0,0 +1,174 @@
+use bc_envelope::prelude::*;
+
+use crate::{Amount, BlockHeight, Indexed, Network, NetworkParameters, OutPoint, Script};
+
+/// The number of confirmations a coinbase output needs before it can be
+/// spent.
+pub const COINBASE_MATURITY: u32 = 100;
+
+/// An unspent transparent output held by an account.
+///
+/// # Zcash Concept Relation
+/// Transparent funds are held as unspent transaction outputs (UTXOs) paying
+/// to the wallet's addresses, as in Bitcoin. The outputs of coinbase
+/// transactions can only be spent once they have [`COINBASE_MATURITY`]
+/// confirmations.
+///
+/// # Data Preservation
+/// The account's UTXOs are a snapshot of its transparent funds, sparing the
+/// receiving wallet a rescan of the chain. They are derived from the stored
+/// transactions by [`Zewif::rebuild_utxo_set`](crate::Zewif::rebuild_utxo_set),
+/// and make up the transparent component of the account's balance.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Amount, BlockHeight, Data, OutPoint, Script, TxId, transparent::Utxo};
+/// let outpoint = OutPoint::new(TxId::from_bytes([1; 32]), 0);
+/// let value = Amount::from_u64(625_000_000).unwrap();
+/// let mut utxo = Utxo::new(outpoint, value, Script::from(Data::new()));
+/// utxo.set_mined_height(Some(BlockHeight::from(1_000)));
+/// utxo.set_coinbase(true);
+/// assert!(!utxo.is_mature(BlockHeight::from(1_098)));
+/// assert!(utxo.is_mature(BlockHeight::from(1_099)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Utxo {
+    /// The index of the UTXO within the account's set.
+    index: usize,
+    outpoint: OutPoint,
+    value: Amount,
+    script_pubkey: Script,
+    /// The height at which the output was created, if known.
+    mined_height: Option<BlockHeight>,
+    /// Whether the output was created by a coinbase transaction.
+    is_coinbase: bool,
+}
+
+impl Indexed for Utxo {
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
+
+impl Utxo {
+    pub fn new(outpoint: OutPoint, value: Amount, script_pubkey: Script) -> Self {
+        Self {
+            index: 0,
+            outpoint,
+            value,
+            script_pubkey,
+            mined_height: None,
+            is_coinbase: false,
+        }
+    }
+
+    pub fn outpoint(&self) -> &OutPoint {
+        &self.outpoint
+    }
+
+    pub fn value(&self) -> Amount {
+        self.value
+    }
+
+    pub fn script_pubkey(&self) -> &Script {
+        &self.script_pubkey
+    }
+
+    /// Returns the transparent address the output pays to on `network`, or
+    /// `None` if its script is not standard.
+    pub fn address(&self, network: Network) -> Option<String> {
+        self.script_pubkey.address(network)
+    }
+
+    /// Like [`Utxo::address`], encoding the address with the prefixes of
+    /// `params`; see [`Script::address_with`](crate::Script::address_with).
+    pub fn address_with(&self, params: &NetworkParameters) -> Option<String> {
+        self.script_pubkey.address_with(params)
+    }
+
+    pub fn mined_height(&self) -> Option<BlockHeight> {
+        self.mined_height
+    }
+
+    pub fn set_mined_height(&mut self, mined_height: Option<BlockHeight>) {
+        self.mined_height = mined_height;
+    }
+
+    pub fn is_coinbase(&self) -> bool {
+        self.is_coinbase
+    }
+
+    pub fn set_coinbase(&mut self, is_coinbase: bool) {
+        self.is_coinbase = is_coinbase;
+    }
+
+    /// Returns whether the output can be spent in the block after
+    /// `tip_height`: outputs other than coinbase outputs always can, and
+    /// coinbase outputs can once they have [`COINBASE_MATURITY`]
+    /// confirmations. A coinbase output of unknown height is not mature.
+    pub fn is_mature(&self, tip_height: BlockHeight) -> bool {
+        !self.is_coinbase
+            || self.mined_height.is_some_and(|height| {
+                u64::from(tip_height) + 1 >= u64::from(height) + u64::from(COINBASE_MATURITY)
+            })
+    }
+}
+
+#[rustfmt::skip]
+impl From<Utxo> for Envelope {
+    fn from(value: Utxo) -> Self {
+        Envelope::new(value.index)
+            .add_type("Utxo")
+            .add_assertion("outpoint", value.outpoint)
+            .add_assertion("value", value.value)
+            .add_assertion("script_pubkey", value.script_pubkey)
+            .add_optional_assertion("mined_height", value.mined_height)
+            .add_optional_assertion("is_coinbase", value.is_coinbase.then_some(true))
+    }
+}
+
+impl TryFrom<Envelope> for Utxo {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("Utxo")?;
+        Ok(Self {
+            index: envelope.extract_subject()?,
+            outpoint: envelope.try_object_for_predicate("outpoint")?,
+            value: envelope.try_object_for_predicate("value")?,
+            script_pubkey: envelope.extract_object_for_predicate("script_pubkey")?,
+            mined_height: envelope.extract_optional_object_for_predicate("mined_height")?,
+            // Only asserted for coinbase outputs.
+            is_coinbase: envelope
+                .extract_optional_object_for_predicate("is_coinbase")?
+                .unwrap_or(false),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Amount, BlockHeight, OutPoint, Script, test_envelope_roundtrip};
+
+    use super::Utxo;
+
+    impl crate::RandomInstance for Utxo {
+        fn random() -> Self {
+            Self {
+                index: 0,
+                outpoint: OutPoint::random(),
+                value: Amount::random(),
+                script_pubkey: Script::random(),
+                mined_height: BlockHeight::opt_random(),
+                is_coinbase: rand::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(Utxo);
+}
@@ -0,0 +1,107 @@
+use crate::{BlockHeight, Error, Result};
+
+/// The state of a note commitment tree as of a particular block height.
+///
+/// `TreeState` records the frontier of a note commitment tree: the rightmost
+/// path of the tree, which is all that is needed to append further note
+/// commitments and to compute the tree's root. A wallet that preserves the tree
+/// state at export time can resume maintaining witnesses from that height
+/// rather than rebuilding the tree from the activation of the pool.
+///
+/// # Zcash Concept Relation
+/// Each shielded pool has its own note commitment tree, and therefore its own
+/// tree state. The pool-specific types
+/// [`SaplingTreeState`](crate::sapling::SaplingTreeState) and
+/// [`OrchardTreeState`](crate::orchard::OrchardTreeState) fix the tree depth
+/// and node type for each pool.
+///
+/// # Type Parameters
+/// * `DEPTH` - The depth of the Merkle tree (32 for Sapling/Orchard)
+/// * `Node` - The hash type used for tree nodes (varies by protocol)
+///
+/// # Examples
+/// ```
+/// # use zewif::{BlockHeight, TreeState};
+/// let state = TreeState::<32, [u8; 32]>::from_parts(
+///     BlockHeight::from(2_000_000),
+///     67891, // number of leaves in the tree
+///     vec![[1u8; 32]; 3], // fake frontier nodes
+/// );
+/// assert!(state.validate().is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreeState<const DEPTH: usize, Node> {
+    height: BlockHeight,
+    tree_size: u32,
+    frontier: Vec<Node>,
+}
+
+impl<const DEPTH: usize, Node> TreeState<DEPTH, Node> {
+    /// Constructs a tree state from its constituent parts.
+    pub fn from_parts(height: BlockHeight, tree_size: u32, frontier: Vec<Node>) -> Self {
+        Self {
+            height,
+            tree_size,
+            frontier,
+        }
+    }
+
+    /// The height of the block as of which this tree state was computed.
+    pub fn height(&self) -> BlockHeight {
+        self.height
+    }
+
+    /// The number of note commitments in the tree as of `height`.
+    pub fn tree_size(&self) -> u32 {
+        self.tree_size
+    }
+
+    /// The frontier of the tree, ordered from leaf to root.
+    pub fn frontier(&self) -> &[Node] {
+        &self.frontier
+    }
+
+    /// Checks that the tree state is structurally consistent with a tree of
+    /// depth `DEPTH`.
+    pub fn validate(&self) -> Result<()> {
+        if self.frontier.len() > DEPTH {
+            return Err(Error::InvalidTreeState {
+                reason: format!(
+                    "frontier has {} nodes, more than the tree depth {}",
+                    self.frontier.len(),
+                    DEPTH
+                ),
+            });
+        }
+        if self.tree_size == 0 && !self.frontier.is_empty() {
+            return Err(Error::InvalidTreeState {
+                reason: "an empty tree has a non-empty frontier".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TreeState;
+    use crate::{BlockHeight, RandomInstance};
+
+    impl<const DEPTH: usize, Node: RandomInstance> RandomInstance for TreeState<DEPTH, Node> {
+        fn random() -> Self {
+            let mut rng = bc_rand::thread_rng();
+            let len = rand::Rng::random_range(&mut rng, 1..=DEPTH);
+            Self {
+                height: BlockHeight::random(),
+                tree_size: 1 + u32::random() / 2,
+                frontier: (0..len).map(|_| Node::random()).collect(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_tree_state_is_valid() {
+        assert!(TreeState::<32, u32>::random().validate().is_ok());
+    }
+}
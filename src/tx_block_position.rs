@@ -6,6 +6,7 @@ use crate::BlockHash;
 /// The unique identifier of a transaction on the blockchain in terms of the hash of the block that
 /// includes it and the index of the transaction within the block.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TxBlockPosition {
     /// The hash of the block containing the transaction.
     block_hash: BlockHash,
@@ -0,0 +1,157 @@
+use std::io::{self, Read, Write};
+
+use bc_envelope::prelude::*;
+
+use crate::{Indexed, OutPoint, Script, TxId};
+
+/// A transparent input of a transaction, spending an output of an earlier
+/// transaction.
+///
+/// # Zcash Concept Relation
+/// Transparent inputs work as in Bitcoin: each names the output it spends by
+/// its [`OutPoint`], and carries a `scriptSig` satisfying the conditions of
+/// that output's `scriptPubKey`. The value spent is not recorded in the input;
+/// it is that of the output referenced, which is needed to compute the
+/// transaction's fee; see [`Transaction::compute_fee`](crate::Transaction::compute_fee).
+///
+/// # Data Preservation
+/// Preserving the inputs lets a receiving wallet tell which of its
+/// transparent outputs have been spent without refetching the transaction.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use zewif::{Data, OutPoint, Script, TxId, TxIn};
+/// let prevout = OutPoint::new(TxId::from_bytes([1; 32]), 0);
+/// let input = TxIn::new(prevout, Script::from(Data::from_vec(vec![0x51])), u32::MAX);
+/// let mut encoded = Vec::new();
+/// input.write(&mut encoded).unwrap();
+/// assert_eq!(encoded.len(), 32 + 4 + 2 + 4);
+/// assert_eq!(TxIn::read(Cursor::new(encoded)).unwrap(), input);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxIn {
+    /// The index of the input within the transaction.
+    index: usize,
+    prevout: OutPoint,
+    script_sig: Script,
+    sequence: u32,
+}
+
+impl Indexed for TxIn {
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
+
+impl TxIn {
+    pub fn new(prevout: OutPoint, script_sig: Script, sequence: u32) -> Self {
+        Self {
+            index: 0,
+            prevout,
+            script_sig,
+            sequence,
+        }
+    }
+
+    /// Returns the output spent by the input.
+    pub fn prevout(&self) -> &OutPoint {
+        &self.prevout
+    }
+
+    pub fn script_sig(&self) -> &Script {
+        &self.script_sig
+    }
+
+    /// Returns whether the input carries a `scriptSig`, which it lacks if the
+    /// transaction is unsigned.
+    pub fn has_script_sig(&self) -> bool {
+        !self.script_sig.is_empty()
+    }
+
+    pub fn sequence(&self) -> u32 {
+        self.sequence
+    }
+
+    /// Returns whether the input is that of a coinbase transaction, which
+    /// spends no earlier output.
+    pub fn is_coinbase(&self) -> bool {
+        self.prevout.is_null()
+    }
+
+    /// Reads an input in its consensus encoding.
+    ///
+    /// # Errors
+    /// Returns an IO error if the reader does not contain a complete input.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut txid = [0u8; 32];
+        reader.read_exact(&mut txid)?;
+        let mut index = [0u8; 4];
+        reader.read_exact(&mut index)?;
+        let script_sig = Script::read(&mut reader)?;
+        let mut sequence = [0u8; 4];
+        reader.read_exact(&mut sequence)?;
+        let prevout = OutPoint::new(TxId::from_bytes(txid), u32::from_le_bytes(index));
+        Ok(Self::new(prevout, script_sig, u32::from_le_bytes(sequence)))
+    }
+
+    /// Writes the input in its consensus encoding.
+    ///
+    /// # Errors
+    /// Returns an IO error if writing fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.prevout.txid().as_bytes())?;
+        writer.write_all(&self.prevout.index().to_le_bytes())?;
+        self.script_sig.write(&mut writer)?;
+        writer.write_all(&self.sequence.to_le_bytes())
+    }
+}
+
+impl From<TxIn> for Envelope {
+    fn from(value: TxIn) -> Self {
+        Envelope::new(value.index)
+            .add_type("TxIn")
+            .add_assertion("prevout", value.prevout)
+            .add_assertion("script_sig", value.script_sig)
+            .add_assertion("sequence", value.sequence)
+    }
+}
+
+impl TryFrom<Envelope> for TxIn {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("TxIn")?;
+        Ok(Self {
+            index: envelope.extract_subject()?,
+            prevout: envelope.try_object_for_predicate("prevout")?,
+            script_sig: envelope.extract_object_for_predicate("script_sig")?,
+            sequence: envelope.extract_object_for_predicate("sequence")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{OutPoint, Script, test_envelope_roundtrip};
+
+    use super::TxIn;
+
+    impl crate::RandomInstance for TxIn {
+        fn random() -> Self {
+            Self {
+                index: 0,
+                prevout: OutPoint::random(),
+                script_sig: Script::random(),
+                sequence: u32::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(TxIn);
+}
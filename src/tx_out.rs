@@ -0,0 +1,147 @@
+use std::io::{self, Read, Write};
+
+use bc_envelope::prelude::*;
+
+use crate::{Amount, Indexed, Network, NetworkParameters, Script, ScriptKind};
+
+/// A transparent output of a transaction, encumbering a value with a
+/// `scriptPubKey`.
+///
+/// # Zcash Concept Relation
+/// Transparent outputs work as in Bitcoin. Most pay to a transparent address,
+/// with a P2PKH or P2SH script from which the address can be recovered; see
+/// [`TxOut::address`].
+///
+/// # Data Preservation
+/// Preserving the outputs lets a receiving wallet rebuild its set of unspent
+/// transparent outputs, and resolve the values spent by later transactions,
+/// without refetching the transaction.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Amount, Data, Network, Script, ScriptKind, TxOut};
+/// let mut script = vec![0x76, 0xa9, 0x14];
+/// script.extend_from_slice(&[0u8; 20]);
+/// script.extend_from_slice(&[0x88, 0xac]);
+/// let value = Amount::from_u64(10_000).unwrap();
+/// let output = TxOut::new(value, Script::from(Data::from_vec(script)));
+/// assert_eq!(output.kind(), ScriptKind::P2pkh);
+/// assert!(output.address(Network::Main).unwrap().starts_with("t1"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxOut {
+    /// The index of the output within the transaction.
+    index: usize,
+    value: Amount,
+    script_pubkey: Script,
+}
+
+impl Indexed for TxOut {
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
+
+impl TxOut {
+    pub fn new(value: Amount, script_pubkey: Script) -> Self {
+        Self {
+            index: 0,
+            value,
+            script_pubkey,
+        }
+    }
+
+    pub fn value(&self) -> Amount {
+        self.value
+    }
+
+    pub fn script_pubkey(&self) -> &Script {
+        &self.script_pubkey
+    }
+
+    /// Classifies the output's `scriptPubKey`.
+    pub fn kind(&self) -> ScriptKind {
+        self.script_pubkey.kind()
+    }
+
+    /// Returns the transparent address the output pays to on `network`, in
+    /// the string encoding by which [`Zewif::find_address`](crate::Zewif::find_address)
+    /// looks it up, or `None` if the output's script is not standard.
+    pub fn address(&self, network: Network) -> Option<String> {
+        self.script_pubkey.address(network)
+    }
+
+    /// Like [`TxOut::address`], encoding the address with the prefixes of
+    /// `params`; see [`Script::address_with`].
+    pub fn address_with(&self, params: &NetworkParameters) -> Option<String> {
+        self.script_pubkey.address_with(params)
+    }
+
+    /// Reads an output in its consensus encoding.
+    ///
+    /// # Errors
+    /// Returns an IO error if the reader does not contain a complete output,
+    /// or if its value is out of range.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut value = [0u8; 8];
+        reader.read_exact(&mut value)?;
+        let value = Amount::from_nonnegative_i64_le_bytes(value)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        Ok(Self::new(value, Script::read(&mut reader)?))
+    }
+
+    /// Writes the output in its consensus encoding.
+    ///
+    /// # Errors
+    /// Returns an IO error if writing fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.value.to_i64_le_bytes())?;
+        self.script_pubkey.write(&mut writer)
+    }
+}
+
+impl From<TxOut> for Envelope {
+    fn from(value: TxOut) -> Self {
+        Envelope::new(value.index)
+            .add_type("TxOut")
+            .add_assertion("value", value.value)
+            .add_assertion("script_pubkey", value.script_pubkey)
+    }
+}
+
+impl TryFrom<Envelope> for TxOut {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("TxOut")?;
+        Ok(Self {
+            index: envelope.extract_subject()?,
+            value: envelope.try_object_for_predicate("value")?,
+            script_pubkey: envelope.extract_object_for_predicate("script_pubkey")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Amount, Script, test_envelope_roundtrip};
+
+    use super::TxOut;
+
+    impl crate::RandomInstance for TxOut {
+        fn random() -> Self {
+            Self {
+                index: 0,
+                value: Amount::random(),
+                script_pubkey: Script::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(TxOut);
+}
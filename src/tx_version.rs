@@ -0,0 +1,154 @@
+use std::fmt;
+
+use bc_envelope::prelude::*;
+
+use crate::{Error, Result};
+
+/// The version of a transaction's encoding, with its version group.
+///
+/// # Zcash Concept Relation
+/// Transactions before Overwinter carry a bare version number, 1 or 2.
+/// Overwinter introduced the `fOverwintered` flag, the top bit of the
+/// header, and a version group ID that pins the encoding: version 3 with the
+/// Overwinter group, version 4 with the Sapling group, and version 5 with the
+/// ZIP 225 group. Replaying or re-validating a transaction requires both.
+///
+/// # Examples
+/// ```
+/// # use zewif::TxVersion;
+/// let version =
+///     TxVersion::from_header(0x8000_0004, Some(TxVersion::SAPLING_VERSION_GROUP_ID)).unwrap();
+/// assert_eq!(version.number(), 4);
+/// assert!(version.is_overwintered());
+/// assert_eq!(version.header(), 0x8000_0004);
+/// assert!(TxVersion::from_header(0x8000_0004, None).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxVersion {
+    number: u32,
+    /// The version group ID, present exactly when the transaction is
+    /// overwintered.
+    version_group_id: Option<u32>,
+}
+
+impl TxVersion {
+    pub const OVERWINTER_VERSION_GROUP_ID: u32 = 0x03C4_8270;
+    pub const SAPLING_VERSION_GROUP_ID: u32 = 0x892F_2085;
+    pub const ZIP225_VERSION_GROUP_ID: u32 = 0x26A7_270A;
+
+    /// Returns a version that is overwintered if and only if it has a
+    /// version group ID.
+    pub fn new(number: u32, version_group_id: Option<u32>) -> Self {
+        Self {
+            number: number & 0x7FFF_FFFF,
+            version_group_id,
+        }
+    }
+
+    /// Returns the version given by the header field of an encoded
+    /// transaction, and its version group ID.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidRawTransaction`] if the version group ID is
+    /// present but the header is not overwintered, or the reverse.
+    pub fn from_header(header: u32, version_group_id: Option<u32>) -> Result<Self> {
+        let overwintered = header >> 31 == 1;
+        if overwintered != version_group_id.is_some() {
+            return Err(Error::InvalidRawTransaction {
+                reason: format!(
+                    "header {:#010x} does not match version group ID {:?}",
+                    header, version_group_id
+                ),
+            });
+        }
+        Ok(Self::new(header, version_group_id))
+    }
+
+    /// Returns the version number, without the `fOverwintered` flag.
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    pub fn is_overwintered(&self) -> bool {
+        self.version_group_id.is_some()
+    }
+
+    pub fn version_group_id(&self) -> Option<u32> {
+        self.version_group_id
+    }
+
+    /// Returns the header field of the transaction's encoding: the version
+    /// number, with the `fOverwintered` flag as its top bit.
+    pub fn header(&self) -> u32 {
+        if self.is_overwintered() {
+            self.number | 0x8000_0000
+        } else {
+            self.number
+        }
+    }
+}
+
+impl fmt::Display for TxVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.version_group_id {
+            Some(group) => write!(f, "v{} ({:#010x})", self.number, group),
+            None => write!(f, "v{}", self.number),
+        }
+    }
+}
+
+impl From<TxVersion> for CBOR {
+    fn from(value: TxVersion) -> Self {
+        let mut map = Map::new();
+        map.insert("number", value.number);
+        if let Some(group) = value.version_group_id {
+            map.insert("version_group_id", group);
+        }
+        map.into()
+    }
+}
+
+impl TryFrom<CBOR> for TxVersion {
+    type Error = dcbor::Error;
+
+    fn try_from(value: CBOR) -> dcbor::Result<Self> {
+        if let CBORCase::Map(map) = value.into_case() {
+            let number: u32 = map.extract("number")?;
+            let version_group_id: Option<u32> = map.get("version_group_id");
+            Ok(TxVersion::new(number, version_group_id))
+        } else {
+            Err("Expected a CBOR map".into())
+        }
+    }
+}
+
+impl From<TxVersion> for Envelope {
+    fn from(value: TxVersion) -> Self {
+        Envelope::new(CBOR::from(value))
+    }
+}
+
+impl TryFrom<Envelope> for TxVersion {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.extract_subject()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_cbor_roundtrip, test_envelope_roundtrip};
+
+    use super::TxVersion;
+
+    impl crate::RandomInstance for TxVersion {
+        fn random() -> Self {
+            TxVersion::new(rand::random_range(1..=5), u32::opt_random())
+        }
+    }
+
+    test_cbor_roundtrip!(TxVersion);
+    test_envelope_roundtrip!(TxVersion);
+}
@@ -3,6 +3,7 @@ use bc_envelope::prelude::*;
 use std::{
     fmt,
     io::{self, Read, Write},
+    str::FromStr,
 };
 
 /// A transaction identifier (TxId) represented as a 32-byte hash.
@@ -18,8 +19,20 @@ use std::{
 /// - In block data structures to identify included transactions
 /// - In client APIs and explorers to look up transaction details
 ///
-/// Transaction IDs are displayed in reverse byte order by convention (to match
-/// Bitcoin's historical display format), while stored internally in little-endian order.
+/// # Byte Order
+/// A `TxId` holds the bytes of the transaction hash in the order the hash
+/// function produces them, which is also the order in which they appear in
+/// serialized transactions and in this crate's CBOR and envelope encodings; see
+/// [`TxId::as_bytes`]. By a convention inherited from Bitcoin, transaction IDs
+/// are *displayed* with those bytes reversed: this is the form shown by block
+/// explorers and returned by zcashd's RPC methods. [`Display`](fmt::Display),
+/// [`Debug`](fmt::Debug), [`FromStr`], [`TxId::from_hex`], and the JSON
+/// encoding all use the reversed form, so a transaction ID printed by this
+/// crate can be compared directly with one shown by an explorer.
+///
+/// Hex-encoding the bytes of [`TxId::as_bytes`] directly gives the internal
+/// order, which matches no explorer; CBOR diagnostic notation, such as that of
+/// [`debug::diagnostic`](crate::debug::diagnostic), shows this order too.
 ///
 /// # Data Preservation
 /// The `TxId` type preserves the exact 32-byte transaction identifier as found in wallet
@@ -36,6 +49,11 @@ use std::{
 /// // Display the TxId in the conventional reversed format used by explorers
 /// // Note: this would display as a string of 64 hex characters (zeros in this example)
 /// println!("Transaction ID: {}", txid);
+///
+/// // Parse a transaction ID as shown by an explorer
+/// let hex = "c4eaa58879081de3c24a7b117ed2b28300e7ec4c4c1dff1d3f1268b7857a4cf2";
+/// let txid: TxId = hex.parse().unwrap();
+/// assert_eq!(txid.as_bytes()[0], 0xf2);
 /// ```
 #[derive(Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
 pub struct TxId([u8; 32]);
@@ -56,6 +74,16 @@ impl fmt::Display for TxId {
     }
 }
 
+impl FromStr for TxId {
+    type Err = Error;
+
+    /// Parses a transaction ID in the byte-reversed form shown by block
+    /// explorers; see [`TxId::from_hex`].
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_hex(s)
+    }
+}
+
 impl AsRef<[u8; 32]> for TxId {
     fn as_ref(&self) -> &[u8; 32] {
         &self.0
@@ -85,7 +113,18 @@ impl TxId {
         TxId(bytes)
     }
 
-    /// Parses a `TxId` from a canonically-encoded (byte-reversed) hexadecimal string.
+    /// Returns the bytes of the transaction ID in internal order, the reverse
+    /// of the order in which they are displayed.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Parses a `TxId` from a canonically-encoded (byte-reversed) hexadecimal string,
+    /// as shown by block explorers.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidHexString`] if `hex` is not hexadecimal, and
+    /// [`Error::HexLengthMismatch`] if it does not encode 32 bytes.
     ///
     /// # Examples
     /// ```
@@ -106,6 +145,29 @@ impl TxId {
         })?))
     }
 
+    /// Computes the ID of a consensus-serialized transaction: the double
+    /// SHA-256 of the transaction for versions before 5, and the ZIP 244
+    /// digest for version 5.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidRawTransaction`] if a version 5 transaction is
+    /// malformed, and [`Error::UnsupportedTransactionVersion`] if the
+    /// transaction is of a later version.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::TxId;
+    /// // A version 1 transaction with no inputs or outputs.
+    /// let raw = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    /// assert!(TxId::from_raw_transaction(&raw).is_ok());
+    ///
+    /// // A version 5 transaction cut short after its header.
+    /// assert!(TxId::from_raw_transaction(&[5, 0, 0, 0x80]).is_err());
+    /// ```
+    pub fn from_raw_transaction(raw: &[u8]) -> Result<Self> {
+        crate::raw_transaction::txid(raw)
+    }
+
     /// Reads a `TxId` from any source implementing the `Read` trait.
     ///
     /// This method is useful when reading transaction IDs directly from files
@@ -212,7 +274,7 @@ impl TryFrom<Envelope> for TxId {
 
 #[cfg(test)]
 mod tests {
-    use crate::{test_cbor_roundtrip, test_envelope_roundtrip};
+    use crate::{Error, test_cbor_roundtrip, test_envelope_roundtrip};
 
     use super::TxId;
 
@@ -225,4 +287,46 @@ mod tests {
 
     test_cbor_roundtrip!(TxId);
     test_envelope_roundtrip!(TxId);
+
+    /// The coinbase transaction of the Zcash mainnet genesis block, as shown
+    /// by block explorers.
+    const GENESIS_COINBASE: &str =
+        "c4eaa58879081de3c24a7b117ed2b28300e7ec4c4c1dff1d3f1268b7857a4cf2";
+
+    #[test]
+    fn test_explorer_order() {
+        let txid: TxId = GENESIS_COINBASE.parse().unwrap();
+        let mut internal = hex::decode(GENESIS_COINBASE).unwrap();
+        internal.reverse();
+        assert_eq!(txid.as_bytes().as_slice(), internal);
+        assert_eq!(txid.to_string(), GENESIS_COINBASE);
+        assert_eq!(format!("{:?}", txid), format!("TxId({})", GENESIS_COINBASE));
+        assert_eq!(TxId::from_hex(GENESIS_COINBASE).unwrap(), txid);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            GENESIS_COINBASE[2..].parse::<TxId>(),
+            Err(Error::HexLengthMismatch {
+                expected: 32,
+                actual: 31
+            })
+        ));
+        assert!(matches!(
+            format!("{}00", GENESIS_COINBASE).parse::<TxId>(),
+            Err(Error::HexLengthMismatch { .. })
+        ));
+        assert!(matches!(
+            GENESIS_COINBASE[1..].parse::<TxId>(),
+            Err(Error::InvalidHexString(_))
+        ));
+        assert!(matches!(
+            "".parse::<TxId>(),
+            Err(Error::HexLengthMismatch {
+                expected: 32,
+                actual: 0
+            })
+        ));
+    }
 }
@@ -0,0 +1,190 @@
+use bc_envelope::prelude::*;
+
+use crate::Result;
+
+/// Stores and retrieves vendor-specific values in the attachments of a
+/// [`Zewif`](crate::Zewif), [`ZewifWallet`](crate::ZewifWallet),
+/// [`Account`](crate::Account), [`Address`](crate::Address), or
+/// [`Transaction`](crate::Transaction), identified by their vendor and the
+/// format they conform to rather than by digest.
+///
+/// Vendors should be identified by reverse-DNS strings, such as
+/// `org.zcashfoundation`, and formats by strings of the vendor's choosing,
+/// such as `zcashd-v5-metadata`, so that attachments written by different
+/// importers never collide.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Account, TypedAttachments};
+/// let mut account = Account::new();
+/// account.add_typed_attachment("com.example.wallet", Some("color-v1"), "blue".to_string());
+/// let color: Option<String> =
+///     account.typed_attachment("com.example.wallet", Some("color-v1")).unwrap();
+/// assert_eq!(color.as_deref(), Some("blue"));
+/// ```
+pub trait TypedAttachments: Attachable {
+    /// Returns the payload of the attachment from `vendor` conforming to
+    /// `conforms_to`, or to no format if it is `None`.
+    ///
+    /// If several such attachments were added with
+    /// [`Attachable::add_attachment`], the one with the lowest digest is
+    /// returned.
+    fn attachment_payload(&self, vendor: &str, conforms_to: Option<&str>) -> Option<Envelope> {
+        matching_attachments(self.attachments(), vendor, conforms_to)
+            .into_iter()
+            .next()
+            .and_then(|attachment| attachment.attachment_payload().ok())
+    }
+
+    /// Attaches `value` as the payload from `vendor` conforming to
+    /// `conforms_to`, replacing any attachments with the same vendor and
+    /// format.
+    fn add_typed_attachment(
+        &mut self,
+        vendor: &str,
+        conforms_to: Option<&str>,
+        value: impl EnvelopeEncodable,
+    ) {
+        for attachment in matching_attachments(self.attachments(), vendor, conforms_to) {
+            self.attachments_mut().remove(attachment.digest());
+        }
+        self.attachments_mut().add(value, vendor, conforms_to);
+    }
+
+    /// Decodes the payload of the attachment from `vendor` conforming to
+    /// `conforms_to`, returning `None` if there is no such attachment.
+    ///
+    /// # Errors
+    /// Returns an error if the payload cannot be decoded as a `T`.
+    fn typed_attachment<T>(&self, vendor: &str, conforms_to: Option<&str>) -> Result<Option<T>>
+    where
+        T: TryFrom<Envelope>,
+        crate::Error: From<T::Error>,
+    {
+        self.attachment_payload(vendor, conforms_to)
+            .map(T::try_from)
+            .transpose()
+            .map_err(crate::Error::from)
+    }
+}
+
+impl<A: Attachable> TypedAttachments for A {}
+
+/// Returns the vendors of `attachments`.
+pub(crate) fn attachment_vendors(attachments: &Attachments) -> impl Iterator<Item = String> {
+    attachments
+        .add_to_envelope(Envelope::unit())
+        .assertions()
+        .into_iter()
+        .filter_map(|attachment| attachment.attachment_vendor().ok())
+}
+
+/// Returns the attachment assertions from `vendor` conforming to
+/// `conforms_to`, ordered by digest.
+fn matching_attachments(
+    attachments: &Attachments,
+    vendor: &str,
+    conforms_to: Option<&str>,
+) -> Vec<Envelope> {
+    let mut matching: Vec<_> = attachments
+        .add_to_envelope(Envelope::unit())
+        .assertions()
+        .into_iter()
+        .filter(|attachment| {
+            attachment.attachment_vendor().is_ok_and(|v| v == vendor)
+                && attachment
+                    .attachment_conforms_to()
+                    .is_ok_and(|c| c.as_deref() == conforms_to)
+        })
+        .collect();
+    matching.sort_by_key(|attachment| attachment.digest());
+    matching
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{Address, Error, ProtocolAddress, TypedAttachments, transparent};
+
+    const VENDOR: &str = "org.zcashfoundation";
+    const CONFORMS_TO: &str = "zcashd-v5-metadata";
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct KeyMetadata {
+        create_time: u64,
+        hd_keypath: String,
+    }
+
+    impl From<KeyMetadata> for Envelope {
+        fn from(value: KeyMetadata) -> Self {
+            Envelope::new(value.create_time)
+                .add_type("KeyMetadata")
+                .add_assertion("hd_keypath", value.hd_keypath)
+        }
+    }
+
+    impl TryFrom<Envelope> for KeyMetadata {
+        type Error = bc_envelope::Error;
+
+        fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+            envelope.check_type("KeyMetadata")?;
+            Ok(Self {
+                create_time: envelope.extract_subject()?,
+                hd_keypath: envelope.extract_object_for_predicate("hd_keypath")?,
+            })
+        }
+    }
+
+    fn address() -> Address {
+        Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+            "t1typed",
+        )))
+    }
+
+    #[test]
+    fn test_typed_attachment() {
+        let metadata = KeyMetadata {
+            create_time: 1_700_000_000,
+            hd_keypath: "m/0'/0'/7'".to_string(),
+        };
+        let mut address = address();
+        address.add_attachment("unrelated", VENDOR, Some("other-format"));
+        address.add_typed_attachment(VENDOR, Some(CONFORMS_TO), metadata.clone());
+
+        let address = Address::try_from(Envelope::from(address)).unwrap();
+        let decoded: Option<KeyMetadata> =
+            address.typed_attachment(VENDOR, Some(CONFORMS_TO)).unwrap();
+        assert_eq!(decoded, Some(metadata));
+
+        let missing: Option<KeyMetadata> = address.typed_attachment(VENDOR, None).unwrap();
+        assert_eq!(missing, None);
+        assert!(
+            address
+                .attachment_payload("com.example", Some(CONFORMS_TO))
+                .is_none()
+        );
+
+        let wrong_type = address.typed_attachment::<KeyMetadata>(VENDOR, Some("other-format"));
+        assert!(matches!(wrong_type, Err(Error::EnvelopeError(_))));
+    }
+
+    #[test]
+    fn test_typed_attachment_replaces() {
+        let mut address = address();
+        address.add_typed_attachment(VENDOR, Some(CONFORMS_TO), "first".to_string());
+        address.add_typed_attachment(VENDOR, Some(CONFORMS_TO), "second".to_string());
+        let payload = address
+            .attachment_payload(VENDOR, Some(CONFORMS_TO))
+            .unwrap();
+        assert_eq!(payload.extract_subject::<String>().unwrap(), "second");
+        assert_eq!(
+            address
+                .attachments()
+                .add_to_envelope(Envelope::unit())
+                .assertions()
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bc_envelope::prelude::*;
+
+use crate::{DiversifierIndex, Network, Result, UnifiedFullViewingKey};
+
+/// A kind of receiver that a unified address can contain, as defined in
+/// ZIP 316.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReceiverType {
+    P2pkh,
+    P2sh,
+    Sapling,
+    Orchard,
+}
+
+impl ReceiverType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::P2pkh => "p2pkh",
+            Self::P2sh => "p2sh",
+            Self::Sapling => "sapling",
+            Self::Orchard => "orchard",
+        }
+    }
+}
+
+impl From<ReceiverType> for CBOR {
+    fn from(value: ReceiverType) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl TryFrom<CBOR> for ReceiverType {
+    type Error = dcbor::Error;
+
+    fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
+        match String::try_from(cbor)?.as_str() {
+            "p2pkh" => Ok(Self::P2pkh),
+            "p2sh" => Ok(Self::P2sh),
+            "sapling" => Ok(Self::Sapling),
+            "orchard" => Ok(Self::Orchard),
+            other => Err(dcbor::Error::msg(format!(
+                "invalid receiver type: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// A unified address generated by a unified account: the receiver types
+/// selected when it was generated, and the encodings of the address and its
+/// receivers where the source wallet recorded them.
+///
+/// zcashd records only the diversifier index and the receiver types of each
+/// address it generates, deriving the address itself when needed, so the
+/// encodings are optional.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnifiedAddressRecord {
+    address: Option<String>,
+    receiver_types: BTreeSet<ReceiverType>,
+    receivers: BTreeMap<ReceiverType, String>,
+}
+
+impl UnifiedAddressRecord {
+    pub fn new(receiver_types: impl IntoIterator<Item = ReceiverType>) -> Self {
+        Self {
+            receiver_types: receiver_types.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// The encoded unified address, if recorded.
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    pub fn set_address(&mut self, address: Option<String>) {
+        self.address = address;
+    }
+
+    pub fn receiver_types(&self) -> &BTreeSet<ReceiverType> {
+        &self.receiver_types
+    }
+
+    /// The encodings of the address's receivers as standalone addresses, such
+    /// as the `t1...` address of its P2PKH receiver, keyed by receiver type.
+    pub fn receivers(&self) -> &BTreeMap<ReceiverType, String> {
+        &self.receivers
+    }
+
+    /// Records the standalone encoding of one of the address's receivers,
+    /// adding its type to the receiver types if needed.
+    pub fn set_receiver(&mut self, receiver_type: ReceiverType, address: impl Into<String>) {
+        self.receiver_types.insert(receiver_type);
+        self.receivers.insert(receiver_type, address.into());
+    }
+}
+
+/// Metadata of a ZIP 316 unified account beyond its key material: its unified
+/// full viewing key, the unified addresses it has generated, and the receiver
+/// types selected by default for new addresses.
+///
+/// # Zcash Concept Relation
+/// zcashd's unified accounts (`z_getnewaccount`) store the account's UFVK and,
+/// for each address generated with `z_getaddressforaccount`, its diversifier
+/// index and receiver types. Addresses are keyed by diversifier index, so
+/// they are listed in the order they were generated.
+///
+/// # Examples
+/// ```
+/// # use zewif::{DiversifierIndex, ReceiverType, UnifiedAccountMetadata, UnifiedAddressRecord};
+/// let mut metadata = UnifiedAccountMetadata::new();
+/// let mut record = UnifiedAddressRecord::new([ReceiverType::Orchard]);
+/// record.set_address(Some("u1example".to_string()));
+/// record.set_receiver(ReceiverType::P2pkh, "t1receiver");
+/// metadata.add_address(DiversifierIndex::from(3u64), record);
+///
+/// let links = metadata.receiver_links();
+/// assert_eq!(links["t1receiver"], (DiversifierIndex::from(3u64), Some("u1example")));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnifiedAccountMetadata {
+    ufvk: Option<String>,
+    addresses: BTreeMap<DiversifierIndex, UnifiedAddressRecord>,
+    default_receiver_types: BTreeSet<ReceiverType>,
+}
+
+impl UnifiedAccountMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The account's encoded unified full viewing key, if known.
+    pub fn ufvk(&self) -> Option<&str> {
+        self.ufvk.as_deref()
+    }
+
+    pub fn set_ufvk(&mut self, ufvk: Option<String>) {
+        self.ufvk = ufvk;
+    }
+
+    /// Decodes the account's unified full viewing key into its components.
+    ///
+    /// # Errors
+    /// Returns an error if the key does not decode for `network`; see
+    /// [`UnifiedFullViewingKey::parse`].
+    pub fn parsed_ufvk(&self, network: Network) -> Result<Option<UnifiedFullViewingKey>> {
+        self.ufvk
+            .as_deref()
+            .map(|ufvk| UnifiedFullViewingKey::parse(ufvk, network))
+            .transpose()
+    }
+
+    pub fn addresses(&self) -> &BTreeMap<DiversifierIndex, UnifiedAddressRecord> {
+        &self.addresses
+    }
+
+    /// Adds the address generated at `index`, replacing any recorded there.
+    pub fn add_address(&mut self, index: DiversifierIndex, record: UnifiedAddressRecord) {
+        self.addresses.insert(index, record);
+    }
+
+    pub fn default_receiver_types(&self) -> &BTreeSet<ReceiverType> {
+        &self.default_receiver_types
+    }
+
+    pub fn set_default_receiver_types(
+        &mut self,
+        receiver_types: impl IntoIterator<Item = ReceiverType>,
+    ) {
+        self.default_receiver_types = receiver_types.into_iter().collect();
+    }
+
+    /// Returns, for each recorded receiver encoding, the diversifier index of
+    /// the unified address containing it, and that address if recorded.
+    ///
+    /// This lets a standalone address such as a `t1...` address, seen in a
+    /// transaction or among an account's addresses, be attributed to the
+    /// unified address it belongs to.
+    pub fn receiver_links(&self) -> BTreeMap<&str, (DiversifierIndex, Option<&str>)> {
+        self.addresses
+            .iter()
+            .flat_map(|(index, record)| {
+                record
+                    .receivers
+                    .values()
+                    .map(move |receiver| (receiver.as_str(), (*index, record.address())))
+            })
+            .collect()
+    }
+}
+
+impl From<UnifiedAddressRecord> for Envelope {
+    fn from(value: UnifiedAddressRecord) -> Self {
+        let e = Envelope::new(value.receiver_types.into_iter().collect::<Vec<_>>())
+            .add_type("UnifiedAddressRecord")
+            .add_optional_assertion("address", value.address);
+        value
+            .receivers
+            .into_iter()
+            .fold(e, |e, (receiver_type, address)| {
+                e.add_assertion(
+                    "receiver",
+                    Envelope::new(CBOR::from(receiver_type)).add_assertion("address", address),
+                )
+            })
+    }
+}
+
+impl TryFrom<Envelope> for UnifiedAddressRecord {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("UnifiedAddressRecord")?;
+        let receiver_types = envelope
+            .extract_subject::<Vec<ReceiverType>>()?
+            .into_iter()
+            .collect();
+        let address = envelope.try_optional_object_for_predicate("address")?;
+        let receivers = envelope
+            .objects_for_predicate("receiver")
+            .into_iter()
+            .map(|receiver| {
+                Ok((
+                    receiver.extract_subject::<ReceiverType>()?,
+                    receiver.extract_object_for_predicate("address")?,
+                ))
+            })
+            .collect::<bc_envelope::Result<_>>()?;
+        Ok(Self {
+            address,
+            receiver_types,
+            receivers,
+        })
+    }
+}
+
+/// Each address is an assertion whose object is its record, carrying the
+/// diversifier index.
+impl From<UnifiedAccountMetadata> for Envelope {
+    fn from(value: UnifiedAccountMetadata) -> Self {
+        let e = Envelope::new("UnifiedAccountMetadata")
+            .add_type("UnifiedAccountMetadata")
+            .add_optional_assertion("ufvk", value.ufvk)
+            .add_assertion(
+                "default_receiver_types",
+                value.default_receiver_types.into_iter().collect::<Vec<_>>(),
+            );
+        value.addresses.into_iter().fold(e, |e, (index, record)| {
+            e.add_assertion(
+                "address",
+                Envelope::from(record).add_assertion("diversifier_index", index),
+            )
+        })
+    }
+}
+
+impl TryFrom<Envelope> for UnifiedAccountMetadata {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("UnifiedAccountMetadata")?;
+        let ufvk = envelope.extract_optional_object_for_predicate("ufvk")?;
+        let default_receiver_types = envelope
+            .extract_object_for_predicate::<Vec<ReceiverType>>("default_receiver_types")?
+            .into_iter()
+            .collect();
+        let addresses = envelope
+            .objects_for_predicate("address")
+            .into_iter()
+            .map(|record| {
+                Ok((
+                    record.try_object_for_predicate("diversifier_index")?,
+                    UnifiedAddressRecord::try_from(record)?,
+                ))
+            })
+            .collect::<bc_envelope::Result<_>>()?;
+        Ok(Self {
+            ufvk,
+            addresses,
+            default_receiver_types,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use crate::{DiversifierIndex, RandomInstance, test_envelope_roundtrip};
+
+    use super::{ReceiverType, UnifiedAccountMetadata, UnifiedAddressRecord};
+
+    impl RandomInstance for ReceiverType {
+        fn random() -> Self {
+            match rand::random_range(0..4) {
+                0 => Self::P2pkh,
+                1 => Self::P2sh,
+                2 => Self::Sapling,
+                _ => Self::Orchard,
+            }
+        }
+    }
+
+    impl RandomInstance for UnifiedAddressRecord {
+        fn random() -> Self {
+            let receivers: BTreeMap<ReceiverType, String> = Vec::<ReceiverType>::random()
+                .into_iter()
+                .map(|receiver_type| (receiver_type, String::random()))
+                .collect();
+            let mut receiver_types: BTreeSet<ReceiverType> = Vec::random().into_iter().collect();
+            receiver_types.extend(receivers.keys());
+            Self {
+                address: String::opt_random(),
+                receiver_types,
+                receivers,
+            }
+        }
+    }
+
+    impl RandomInstance for UnifiedAccountMetadata {
+        fn random() -> Self {
+            Self {
+                ufvk: String::opt_random(),
+                addresses: Vec::<UnifiedAddressRecord>::random()
+                    .into_iter()
+                    .map(|record| (DiversifierIndex::random(), record))
+                    .collect(),
+                default_receiver_types: Vec::random().into_iter().collect(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(UnifiedAccountMetadata);
+
+    #[test]
+    fn test_receiver_links() {
+        let mut metadata = UnifiedAccountMetadata::new();
+        metadata.set_ufvk(Some("uview1example".to_string()));
+        metadata.set_default_receiver_types([ReceiverType::Sapling, ReceiverType::Orchard]);
+
+        let mut first = UnifiedAddressRecord::new([ReceiverType::Orchard]);
+        first.set_address(Some("u1first".to_string()));
+        first.set_receiver(ReceiverType::P2pkh, "t1first");
+        first.set_receiver(ReceiverType::Sapling, "zs1first");
+        let mut second = UnifiedAddressRecord::new([ReceiverType::Orchard]);
+        second.set_receiver(ReceiverType::Sapling, "zs1second");
+        metadata.add_address(DiversifierIndex::from(5u64), second);
+        metadata.add_address(DiversifierIndex::from(0u64), first);
+
+        assert_eq!(
+            metadata.addresses().keys().copied().collect::<Vec<_>>(),
+            [DiversifierIndex::from(0u64), DiversifierIndex::from(5u64)]
+        );
+        let links = metadata.receiver_links();
+        assert_eq!(links.len(), 3);
+        assert_eq!(
+            links["t1first"],
+            (DiversifierIndex::from(0u64), Some("u1first"))
+        );
+        assert_eq!(links["zs1second"], (DiversifierIndex::from(5u64), None));
+    }
+}
@@ -49,6 +49,7 @@ use bc_envelope::prelude::*;
 /// let diversifier_index = Blob::new(diversifier_data);
 /// ua.set_diversifier_index(diversifier_index);
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnifiedAddress {
     /// The full unified address string (starting with "u...")
     address: String,
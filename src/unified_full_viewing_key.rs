@@ -0,0 +1,350 @@
+use bech32::{Bech32m, Hrp, primitives::decode::CheckedHrpstring};
+
+use crate::{
+    Data, Error, Network, Result,
+    f4jumble::{f4jumble, f4jumble_inv},
+    orchard::OrchardFullViewingKey,
+    sapling::SaplingDiversifiableFullViewingKey,
+    transparent::TransparentAccountPubKey,
+};
+
+const TYPECODE_P2PKH: u32 = 0x00;
+const TYPECODE_P2SH: u32 = 0x01;
+const TYPECODE_SAPLING: u32 = 0x02;
+const TYPECODE_ORCHARD: u32 = 0x03;
+
+/// The length of the HRP padding appended to the items before jumbling.
+const PADDING_LEN: usize = 16;
+
+/// A ZIP 316 unified full viewing key, decoded into its per-pool components.
+///
+/// Accounts frequently carry their viewing capability only as an opaque
+/// `uview1...` string. `UnifiedFullViewingKey` decodes such a string so that the
+/// individual transparent, Sapling and Orchard components can populate the
+/// typed key slots of the interchange format.
+///
+/// # Zcash Concept Relation
+/// As specified in ZIP 316, a unified full viewing key is a Bech32m string
+/// whose payload is the F4Jumble permutation of a sequence of typed items,
+/// followed by the human-readable part as padding. Each item is encoded as a
+/// typecode, a length, and the component's raw encoding:
+///
+/// - **Transparent (`0x00`)**: The account's pruned extended public key (65 bytes)
+/// - **Sapling (`0x02`)**: The diversifiable full viewing key (128 bytes)
+/// - **Orchard (`0x03`)**: The full viewing key (96 bytes)
+///
+/// Items with typecodes unknown to this implementation are preserved so that
+/// they are not lost when the key is re-encoded.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Network, UnifiedFullViewingKey};
+/// # use zewif::Result;
+/// # fn example(encoded: &str) -> Result<()> {
+/// let ufvk = UnifiedFullViewingKey::parse(encoded, Network::Main)?;
+/// if let Some(orchard) = ufvk.orchard() {
+///     // Populate the account's Orchard key slot...
+/// }
+/// assert_eq!(ufvk.encode(), encoded);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnifiedFullViewingKey {
+    network: Network,
+    transparent: Option<TransparentAccountPubKey>,
+    sapling: Option<SaplingDiversifiableFullViewingKey>,
+    orchard: Option<OrchardFullViewingKey>,
+    unknown: Vec<(u32, Data)>,
+}
+
+impl UnifiedFullViewingKey {
+    /// Decodes a unified full viewing key string for the given network.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnifiedKeyNetworkMismatch`] if the string is a
+    /// well-formed Bech32m string whose human-readable part is not the one for
+    /// `network`, and [`Error::InvalidUnifiedKey`] if the payload is corrupt or
+    /// does not satisfy the ZIP 316 encoding rules.
+    pub fn parse(s: &str, network: Network) -> Result<Self> {
+        let checked =
+            CheckedHrpstring::new::<Bech32m>(s).map_err(|e| invalid_key(None, e.to_string()))?;
+        let hrp = checked.hrp();
+        if hrp.as_str() != network.unified_full_viewing_key_hrp() {
+            return Err(Error::UnifiedKeyNetworkMismatch {
+                expected: network.unified_full_viewing_key_hrp().to_string(),
+                actual: hrp.to_string(),
+            });
+        }
+
+        let mut payload: Vec<u8> = checked.byte_iter().collect();
+        f4jumble_inv(&mut payload).ok_or_else(|| {
+            invalid_key(None, format!("invalid payload length {}", payload.len()))
+        })?;
+        let items_len = payload.len() - PADDING_LEN;
+        if payload[items_len..] != Self::padding(network) {
+            return Err(invalid_key(None, "invalid padding"));
+        }
+
+        let mut ufvk = Self {
+            network,
+            transparent: None,
+            sapling: None,
+            orchard: None,
+            unknown: Vec::new(),
+        };
+        let mut items = &payload[..items_len];
+        let mut last_typecode = None;
+        while !items.is_empty() {
+            let typecode = read_compact_size(&mut items)?;
+            let len = read_compact_size(&mut items)? as usize;
+            if len > items.len() {
+                return Err(invalid_key(Some(typecode), "truncated item"));
+            }
+            let (data, rest) = items.split_at(len);
+            items = rest;
+
+            if last_typecode.is_some_and(|last| typecode <= last) {
+                return Err(invalid_key(
+                    Some(typecode),
+                    "items are not in ascending typecode order",
+                ));
+            }
+            last_typecode = Some(typecode);
+
+            let invalid_length = |_| invalid_key(Some(typecode), format!("invalid length {}", len));
+            match typecode {
+                TYPECODE_P2PKH => {
+                    ufvk.transparent =
+                        Some(TransparentAccountPubKey::from_slice(data).map_err(invalid_length)?)
+                }
+                TYPECODE_P2SH => {
+                    return Err(invalid_key(
+                        Some(typecode),
+                        "P2SH items are not permitted in a viewing key",
+                    ));
+                }
+                TYPECODE_SAPLING => {
+                    ufvk.sapling = Some(
+                        SaplingDiversifiableFullViewingKey::from_slice(data)
+                            .map_err(invalid_length)?,
+                    )
+                }
+                TYPECODE_ORCHARD => {
+                    ufvk.orchard =
+                        Some(OrchardFullViewingKey::from_slice(data).map_err(invalid_length)?)
+                }
+                _ => ufvk.unknown.push((typecode, Data::from_slice(data))),
+            }
+        }
+
+        if ufvk.sapling.is_none() && ufvk.orchard.is_none() && ufvk.unknown.is_empty() {
+            return Err(invalid_key(None, "no shielded components"));
+        }
+        Ok(ufvk)
+    }
+
+    /// Encodes the key as a unified full viewing key string.
+    pub fn encode(&self) -> String {
+        let mut items = Vec::new();
+        let mut write_item = |typecode: u32, data: &[u8]| {
+            write_compact_size(&mut items, typecode as u64);
+            write_compact_size(&mut items, data.len() as u64);
+            items.extend_from_slice(data);
+        };
+        if let Some(key) = &self.transparent {
+            write_item(TYPECODE_P2PKH, key.as_slice());
+        }
+        if let Some(key) = &self.sapling {
+            write_item(TYPECODE_SAPLING, key.as_slice());
+        }
+        if let Some(key) = &self.orchard {
+            write_item(TYPECODE_ORCHARD, key.as_slice());
+        }
+        for (typecode, data) in &self.unknown {
+            write_item(*typecode, data.as_slice());
+        }
+        items.extend_from_slice(&Self::padding(self.network));
+        f4jumble(&mut items).expect("a unified key always has a valid length");
+
+        let hrp = Hrp::parse_unchecked(self.network.unified_full_viewing_key_hrp());
+        bech32::encode::<Bech32m>(hrp, &items)
+            .expect("a unified key never exceeds the Bech32m length limit")
+    }
+
+    /// Returns the network for which the key was encoded.
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Returns the transparent component, if present.
+    pub fn transparent(&self) -> Option<&TransparentAccountPubKey> {
+        self.transparent.as_ref()
+    }
+
+    /// Returns the Sapling component, if present.
+    pub fn sapling(&self) -> Option<&SaplingDiversifiableFullViewingKey> {
+        self.sapling.as_ref()
+    }
+
+    /// Returns the Orchard component, if present.
+    pub fn orchard(&self) -> Option<&OrchardFullViewingKey> {
+        self.orchard.as_ref()
+    }
+
+    /// Returns the items whose typecodes are not known to this
+    /// implementation, as `(typecode, data)` pairs in encoding order.
+    pub fn unknown(&self) -> &[(u32, Data)] {
+        &self.unknown
+    }
+
+    fn padding(network: Network) -> [u8; PADDING_LEN] {
+        let mut padding = [0u8; PADDING_LEN];
+        let hrp = network.unified_full_viewing_key_hrp().as_bytes();
+        padding[..hrp.len()].copy_from_slice(hrp);
+        padding
+    }
+}
+
+fn invalid_key(typecode: Option<u32>, reason: impl Into<String>) -> Error {
+    Error::InvalidUnifiedKey {
+        typecode,
+        reason: reason.into(),
+    }
+}
+
+/// Reads a Bitcoin-style CompactSize integer, rejecting non-canonical
+/// encodings and values that do not fit in a `u32`.
+fn read_compact_size(input: &mut &[u8]) -> Result<u32> {
+    fn take<const N: usize>(input: &mut &[u8]) -> Result<[u8; N]> {
+        if input.len() < N {
+            return Err(invalid_key(None, "truncated CompactSize"));
+        }
+        let (bytes, rest) = input.split_at(N);
+        *input = rest;
+        Ok(bytes.try_into().expect("length checked"))
+    }
+
+    let [flag] = take::<1>(input)?;
+    let (value, min) = match flag {
+        0xfd => (u16::from_le_bytes(take(input)?) as u64, 0xfd),
+        0xfe => (u32::from_le_bytes(take(input)?) as u64, 0x10000),
+        0xff => (u64::from_le_bytes(take(input)?), 0x100000000),
+        n => (n as u64, 0),
+    };
+    if value < min {
+        return Err(invalid_key(None, "non-canonical CompactSize"));
+    }
+    u32::try_from(value).map_err(|_| invalid_key(None, "CompactSize out of range"))
+}
+
+fn write_compact_size(output: &mut Vec<u8>, value: u64) {
+    match value {
+        0..=0xfc => output.push(value as u8),
+        0xfd..=0xffff => {
+            output.push(0xfd);
+            output.extend_from_slice(&(value as u16).to_le_bytes());
+        }
+        0x10000..=0xffffffff => {
+            output.push(0xfe);
+            output.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+        _ => {
+            output.push(0xff);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnifiedFullViewingKey;
+    use crate::{Error, Network};
+
+    /// The example mainnet UFVK from the `zcash_address` crate documentation.
+    const MAINNET_UFVK: &str = "uview1cgrqnry478ckvpr0f580t6fsahp0a5mj2e9xl7hv2d2jd4ldzy449mwwk2l9yeuts85wjls6hjtghdsy5vhhvmjdw3jxl3cxhrg3vs296a3czazrycrr5cywjhwc5c3ztfyjdhmz0exvzzeyejamyp0cr9z8f9wj0953fzht0m4lenk94t70ruwgjxag2tvp63wn9ftzhtkh20gyre3w5s24f6wlgqxnjh40gd2lxe75sf3z8h5y2x0atpxcyf9t3em4h0evvsftluruqne6w4sm066sw0qe5y8qg423grple5fftxrqyy7xmqmatv7nzd7tcjadu8f7mqz4l83jsyxy4t8pkayytyk7nrp467ds85knekdkvnd7hqkfer8mnqd7pv";
+
+    /// A testnet UFVK with Sapling component `[0, 1, ..., 127]` and Orchard
+    /// component `[0xaa; 96]`, encoded by the `zcash_address` crate.
+    const TESTNET_UFVK: &str = "uviewtest1v7j4s6huup27zwavhx42wpa3terf80mz5euqtrezhj48anjhhqwa76tthuehkqql3puczvpyctdr7rv65t9z0kgl6qzx2274gyuvw0el6gf8pvt8c9hjc8d6jdsqy2h7z3e8guvgdpcjkxwuhsr4kzrpue9p7r5r6qr5prrl3hdvuy3u80hrskcpunhc9edpd3t59q98wr4mxucxyhgn699hfln4qd8aqg4zw85e76lre9v59lmc84xuw8lylk3k4mv2fztx59gzlh3n3cmwkhs8yy79yc28hk9kzx4px2jve78nhyavmemyfq46fr0pfz8f7ry6256k4y0pkq55nsses3lf27vcxzhdwud9mdg4p8l7a3hlesel0jsajmld76v026q5att2d";
+
+    #[test]
+    fn test_parse_mainnet_ufvk() {
+        let ufvk = UnifiedFullViewingKey::parse(MAINNET_UFVK, Network::Main).unwrap();
+        assert_eq!(
+            hex::encode(ufvk.transparent().unwrap().as_slice()),
+            "9ba0439c6a2d3d903883d4537c362288626da62c6299012e362d8fb6efebab4702ed638532c475f67400350fb1d6eda559cdc289a19b4319eb175140aa86893836"
+        );
+        assert!(ufvk.sapling().is_none());
+        assert_eq!(
+            hex::encode(ufvk.orchard().unwrap().as_slice()),
+            "7648764a4567b7165410bc313f922b72fa34153dcad112a3971620240ffbf30d7f19edb9f295cdf160be1863b41c96312daf7273ba01198f5066f28629b56f17e4ab726579eea0fb19ab5ae2b8889ce455c79c5959bfda796823ee805c794814"
+        );
+        assert_eq!(ufvk.unknown().len(), 1);
+        assert_eq!(ufvk.unknown()[0].0, 0xfffd);
+        assert_eq!(ufvk.encode(), MAINNET_UFVK);
+    }
+
+    #[test]
+    fn test_parse_testnet_ufvk() {
+        let ufvk = UnifiedFullViewingKey::parse(TESTNET_UFVK, Network::Test).unwrap();
+        let sapling: Vec<u8> = (0u8..128).collect();
+        assert_eq!(ufvk.sapling().unwrap().as_slice(), &sapling[..]);
+        assert_eq!(ufvk.orchard().unwrap().as_slice(), &[0xaa; 96][..]);
+        assert!(ufvk.transparent().is_none());
+        assert_eq!(ufvk.encode(), TESTNET_UFVK);
+    }
+
+    #[test]
+    fn test_wrong_network() {
+        let err = UnifiedFullViewingKey::parse(MAINNET_UFVK, Network::Test).unwrap_err();
+        assert!(matches!(err, Error::UnifiedKeyNetworkMismatch { .. }));
+    }
+
+    #[test]
+    fn test_corrupt_payload() {
+        // Changing a character invalidates the Bech32m checksum.
+        let mut corrupt = MAINNET_UFVK.to_string();
+        corrupt.replace_range(
+            20..21,
+            if &MAINNET_UFVK[20..21] == "q" {
+                "p"
+            } else {
+                "q"
+            },
+        );
+        let err = UnifiedFullViewingKey::parse(&corrupt, Network::Main).unwrap_err();
+        assert!(matches!(err, Error::InvalidUnifiedKey { .. }));
+    }
+
+    #[test]
+    fn test_compact_size() {
+        use super::{read_compact_size, write_compact_size};
+
+        for value in [0u32, 0xfc, 0xfd, 0xffff, 0x10000, u32::MAX] {
+            let mut encoded = Vec::new();
+            write_compact_size(&mut encoded, value as u64);
+            let mut input = encoded.as_slice();
+            assert_eq!(read_compact_size(&mut input).unwrap(), value);
+            assert!(input.is_empty());
+        }
+
+        // Values that fit a shorter encoding are rejected.
+        let non_canonical: [&[u8]; 3] = [
+            &[0xfd, 0xfc, 0x00],
+            &[0xfe, 0xff, 0xff, 0x00, 0x00],
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0],
+        ];
+        for encoded in non_canonical {
+            let mut input = encoded;
+            assert!(matches!(
+                read_compact_size(&mut input),
+                Err(Error::InvalidUnifiedKey { reason, .. }) if reason.contains("non-canonical")
+            ));
+        }
+
+        // Truncated encodings and values beyond `u32` are rejected.
+        assert!(read_compact_size(&mut [0xfd, 0x00].as_slice()).is_err());
+        assert!(read_compact_size(&mut [0xff, 0, 0, 0, 0, 1, 0, 0, 0].as_slice()).is_err());
+    }
+}
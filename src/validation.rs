@@ -0,0 +1,1591 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    Account, Error, Indexed, OrphanedRecord, SeedFingerprint, SeedStatus, TxId, Zewif, ZewifWallet,
+    envelope_decoder::segment,
+};
+
+/// Selects the checks run by [`Zewif::validate`].
+///
+/// The checks that need no cryptography are enabled by default. Verifying
+/// derivations and computing witness roots hash every key and witness in the
+/// wallet, and must be enabled explicitly; both require the `zip32` feature,
+/// and are reported as skipped without it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationOptions {
+    network: bool,
+    addresses: bool,
+    seeds: bool,
+    witnesses: bool,
+    transactions: bool,
+    derivations: bool,
+    witness_roots: bool,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            network: true,
+            addresses: true,
+            seeds: true,
+            witnesses: true,
+            transactions: true,
+            derivations: false,
+            witness_roots: false,
+        }
+    }
+}
+
+impl ValidationOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns options enabling every check, including the expensive ones.
+    pub fn thorough() -> Self {
+        Self {
+            derivations: true,
+            witness_roots: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn network(&self) -> bool {
+        self.network
+    }
+
+    /// Selects whether network parameters, addresses, and derivation paths
+    /// are checked against each wallet's network.
+    pub fn set_network(&mut self, network: bool) {
+        self.network = network;
+    }
+
+    pub fn addresses(&self) -> bool {
+        self.addresses
+    }
+
+    /// Selects whether addresses held by more than one account are reported,
+    /// see [`Zewif::find_duplicate_addresses`], and likewise account
+    /// identifiers, which [`Zewif::get_by_id`] requires to be unique.
+    pub fn set_addresses(&mut self, addresses: bool) {
+        self.addresses = addresses;
+    }
+
+    pub fn seeds(&self) -> bool {
+        self.seeds
+    }
+
+    /// Selects whether seeds are checked for well-formedness and against
+    /// their recorded fingerprints, and the seed fingerprints named by
+    /// accounts and key origins for a matching seed.
+    pub fn set_seeds(&mut self, seeds: bool) {
+        self.seeds = seeds;
+    }
+
+    pub fn witnesses(&self) -> bool {
+        self.witnesses
+    }
+
+    /// Selects whether tree states and witnesses are checked for structure,
+    /// and witness anchors against the wallet's anchor registries.
+    pub fn set_witnesses(&mut self, witnesses: bool) {
+        self.witnesses = witnesses;
+    }
+
+    pub fn transactions(&self) -> bool {
+        self.transactions
+    }
+
+    /// Selects whether the transactions named by accounts are checked for
+    /// presence, heights against the export height, and transaction IDs
+    /// against raw transactions.
+    pub fn set_transactions(&mut self, transactions: bool) {
+        self.transactions = transactions;
+    }
+
+    pub fn derivations(&self) -> bool {
+        self.derivations
+    }
+
+    /// Selects whether Orchard spending keys are re-derived from the wallet's
+    /// seeds and compared with their recorded derivations.
+    pub fn set_derivations(&mut self, derivations: bool) {
+        self.derivations = derivations;
+    }
+
+    pub fn witness_roots(&self) -> bool {
+        self.witness_roots
+    }
+
+    /// Selects whether the root of each Sapling witness is computed and
+    /// compared with its anchor. Orchard witness roots are not computed, and
+    /// an account with Orchard witnesses gets a
+    /// [`FindingCode::CheckSkipped`] finding instead.
+    pub fn set_witness_roots(&mut self, witness_roots: bool) {
+        self.witness_roots = witness_roots;
+    }
+}
+
+/// The severity of a [`Finding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Worth knowing, but not a problem, such as a seed that is encrypted.
+    Info,
+    /// Likely to surprise the user of the restored wallet, such as a missing
+    /// transaction, but not preventing the import.
+    Warning,
+    /// Inconsistent data that would produce a broken wallet.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// The machine-readable kind of a [`Finding`].
+///
+/// The strings returned by [`FindingCode::as_str`] are part of the crate's
+/// public API and will not change; new codes may be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum FindingCode {
+    /// A wallet's custom network parameters are for another network.
+    InvalidNetworkParameters,
+    /// The Zewif's wallets are on different networks.
+    MixedNetworks,
+    /// An address or derivation is encoded for another network than its
+    /// wallet's.
+    NetworkMismatch,
+    /// A seed is malformed, such as a mnemonic with a bad checksum.
+    InvalidSeed,
+    /// A seed is encrypted, held by a hardware device, or redacted.
+    SeedUnavailable,
+    /// An account or key origin names a seed the wallet does not hold.
+    UnknownSeedFingerprint,
+    /// A seed's recorded fingerprint is not that of its seed material.
+    SeedFingerprintMismatch,
+    /// A wallet's commitment tree state is malformed.
+    InvalidTreeState,
+    /// A witness is malformed.
+    InvalidWitness,
+    /// A witness refers to an anchor missing from the wallet's registry.
+    UnknownAnchor,
+    /// A witness's Merkle path does not lead to its anchor.
+    WitnessRootMismatch,
+    /// An account's spending key does not match its recorded derivation.
+    DerivationMismatch,
+    /// Two accounts derived from the same seed claim the same ZIP 32 account
+    /// ID.
+    DuplicateAccountIndex,
+    /// An account identifier is held by more than one account.
+    DuplicateAccountId,
+    /// An address is held by more than one account.
+    DuplicateAddress,
+    /// A wallet holds a spending key that no account claims.
+    OrphanedSpendingKey,
+    /// A wallet's key pool names an address the wallet does not hold.
+    UnknownKeyPoolKey,
+    /// An account names a transaction the Zewif does not hold.
+    MissingTransaction,
+    /// A transaction's raw bytes cannot be parsed.
+    InvalidRawTransaction,
+    /// A transaction's ID does not match the one computed from its raw
+    /// bytes.
+    TxidMismatch,
+    /// A transaction's block hash differs from that of its block position.
+    BlockHashMismatch,
+    /// A height lies beyond the Zewif's export height.
+    HeightAfterExport,
+    /// A transaction was mined before the birthday height of an account
+    /// involved in it.
+    TransactionBeforeBirthday,
+    /// A requested check could not be run.
+    CheckSkipped,
+}
+
+impl FindingCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FindingCode::InvalidNetworkParameters => "invalid-network-parameters",
+            FindingCode::MixedNetworks => "mixed-networks",
+            FindingCode::NetworkMismatch => "network-mismatch",
+            FindingCode::InvalidSeed => "invalid-seed",
+            FindingCode::SeedUnavailable => "seed-unavailable",
+            FindingCode::UnknownSeedFingerprint => "unknown-seed-fingerprint",
+            FindingCode::SeedFingerprintMismatch => "seed-fingerprint-mismatch",
+            FindingCode::InvalidTreeState => "invalid-tree-state",
+            FindingCode::InvalidWitness => "invalid-witness",
+            FindingCode::UnknownAnchor => "unknown-anchor",
+            FindingCode::WitnessRootMismatch => "witness-root-mismatch",
+            FindingCode::DerivationMismatch => "derivation-mismatch",
+            FindingCode::DuplicateAccountIndex => "duplicate-account-index",
+            FindingCode::DuplicateAccountId => "duplicate-account-id",
+            FindingCode::DuplicateAddress => "duplicate-address",
+            FindingCode::OrphanedSpendingKey => "orphaned-spending-key",
+            FindingCode::UnknownKeyPoolKey => "unknown-key-pool-key",
+            FindingCode::MissingTransaction => "missing-transaction",
+            FindingCode::InvalidRawTransaction => "invalid-raw-transaction",
+            FindingCode::TxidMismatch => "txid-mismatch",
+            FindingCode::BlockHashMismatch => "block-hash-mismatch",
+            FindingCode::HeightAfterExport => "height-after-export",
+            FindingCode::TransactionBeforeBirthday => "transaction-before-birthday",
+            FindingCode::CheckSkipped => "check-skipped",
+        }
+    }
+}
+
+impl fmt::Display for FindingCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// One problem or observation reported by [`Zewif::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    severity: Severity,
+    code: FindingCode,
+    path: Vec<String>,
+    message: String,
+}
+
+impl Finding {
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn code(&self) -> FindingCode {
+        self.code
+    }
+
+    /// Returns the path of the offending element, such as
+    /// `["Zewif", "Wallet[0]", "Account[\"Savings\"]"]`.
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {}: {}",
+            self.code,
+            self.path.join(" > "),
+            self.message
+        )
+    }
+}
+
+/// The result of [`Zewif::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    /// Returns every finding, in the order the checks ran.
+    pub fn findings(&self) -> &[Finding] {
+        &self.findings
+    }
+
+    /// Returns the findings of the given severity.
+    pub fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Finding> {
+        self.findings
+            .iter()
+            .filter(move |finding| finding.severity == severity)
+    }
+
+    /// Returns whether the findings include the given code.
+    pub fn contains(&self, code: FindingCode) -> bool {
+        self.findings.iter().any(|finding| finding.code == code)
+    }
+
+    /// Returns whether no check found an error; warnings and information are
+    /// allowed.
+    pub fn is_ok(&self) -> bool {
+        !self.has_errors()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.with_severity(Severity::Error).next().is_some()
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        self.with_severity(Severity::Warning).next().is_some()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.findings.is_empty() {
+            return write!(f, "no findings");
+        }
+        let groups = [
+            (Severity::Error, "Errors"),
+            (Severity::Warning, "Warnings"),
+            (Severity::Info, "Info"),
+        ];
+        let mut first = true;
+        for (severity, heading) in groups {
+            let findings: Vec<_> = self.with_severity(severity).collect();
+            if findings.is_empty() {
+                continue;
+            }
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+            write!(f, "{} ({}):", heading, findings.len())?;
+            for finding in findings {
+                write!(f, "\n  {}", finding)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Zewif {
+    /// Runs the checks selected by `options` over every wallet, account,
+    /// seed, witness, and transaction, and reports every finding rather than
+    /// stopping at the first.
+    ///
+    /// Importers should call this before handing the data to the receiving
+    /// wallet, so that malformed seeds or witnesses, and addresses or
+    /// derivations for the wrong network, are reported up front rather than
+    /// when the restored wallet fails.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{BlockHeight, ValidationOptions, Zewif};
+    /// let zewif = Zewif::new(BlockHeight::from(2_000_000u32));
+    /// let report = zewif.validate(ValidationOptions::new());
+    /// assert!(report.is_ok());
+    /// ```
+    pub fn validate(&self, options: ValidationOptions) -> ValidationReport {
+        let mut validator = Validator {
+            options,
+            findings: Vec::new(),
+        };
+        validator.zewif(self);
+        ValidationReport {
+            findings: validator.findings,
+        }
+    }
+}
+
+struct Validator {
+    options: ValidationOptions,
+    findings: Vec<Finding>,
+}
+
+fn child(path: &[String], segment: String) -> Vec<String> {
+    let mut path = path.to_vec();
+    path.push(segment);
+    path
+}
+
+fn account_segment(account: &Account) -> String {
+    segment("Account", format!("{:?}", account.name()))
+}
+
+impl Validator {
+    fn report(
+        &mut self,
+        severity: Severity,
+        code: FindingCode,
+        path: Vec<String>,
+        message: impl Into<String>,
+    ) {
+        self.findings.push(Finding {
+            severity,
+            code,
+            path,
+            message: message.into(),
+        });
+    }
+
+    fn zewif(&mut self, zewif: &Zewif) {
+        let root = vec!["Zewif".to_string()];
+        if self.options.network {
+            let mut networks: Vec<_> = zewif
+                .wallets()
+                .iter()
+                .map(|wallet| String::from(wallet.network()))
+                .collect();
+            networks.sort();
+            networks.dedup();
+            if networks.len() > 1 {
+                let message = format!("wallets are on the {} networks", networks.join(" and "));
+                self.report(
+                    Severity::Warning,
+                    FindingCode::MixedNetworks,
+                    root.clone(),
+                    message,
+                );
+            }
+        }
+        if !cfg!(feature = "zip32") {
+            for (enabled, check) in [
+                (self.options.derivations, "derivations"),
+                (self.options.witness_roots, "witness roots"),
+            ] {
+                if enabled {
+                    let message = format!("checking {} requires the zip32 feature", check);
+                    self.report(
+                        Severity::Info,
+                        FindingCode::CheckSkipped,
+                        root.clone(),
+                        message,
+                    );
+                }
+            }
+        }
+
+        for wallet in zewif.wallets() {
+            self.wallet(
+                zewif,
+                wallet,
+                &child(&root, segment("Wallet", wallet.index())),
+            );
+        }
+
+        if self.options.addresses {
+            // Reported at the second account holding the identifier.
+            let mut holders = HashMap::new();
+            for wallet in zewif.wallets() {
+                let wallet_path = child(&root, segment("Wallet", wallet.index()));
+                for id in wallet.duplicate_account_ids() {
+                    let mut accounts = wallet
+                        .accounts()
+                        .iter()
+                        .filter(|account| account.id() == *id);
+                    let (first, second) = (accounts.next().unwrap(), accounts.next().unwrap());
+                    let message = format!(
+                        "identifier {} is also held by {}",
+                        id.hex(),
+                        account_segment(first)
+                    );
+                    let path = child(&wallet_path, account_segment(second));
+                    self.report(
+                        Severity::Error,
+                        FindingCode::DuplicateAccountId,
+                        path,
+                        message,
+                    );
+                }
+                for account in wallet.accounts() {
+                    match holders.get(&account.id()) {
+                        Some(&other) if other != wallet.index() => {
+                            let message = format!(
+                                "identifier {} is also held by an account of wallet {}",
+                                account.id().hex(),
+                                other
+                            );
+                            let path = child(&wallet_path, account_segment(account));
+                            self.report(
+                                Severity::Error,
+                                FindingCode::DuplicateAccountId,
+                                path,
+                                message,
+                            );
+                        }
+                        Some(_) => {}
+                        None => {
+                            holders.insert(account.id(), wallet.index());
+                        }
+                    }
+                }
+            }
+
+            // Reported at the first account holding the address.
+            for duplicate in zewif.find_duplicate_addresses() {
+                let (wallet, account) = duplicate.accounts()[0];
+                let path = child(
+                    &child(&root, segment("Wallet", wallet)),
+                    account_segment(&zewif.wallets()[wallet].accounts()[account]),
+                );
+                self.report(
+                    Severity::Warning,
+                    FindingCode::DuplicateAddress,
+                    path,
+                    duplicate.to_string(),
+                );
+            }
+        }
+
+        if self.options.transactions {
+            let mut transactions: Vec<_> = zewif.transactions().values().collect();
+            transactions.sort_by_key(|transaction| transaction.txid());
+            for transaction in transactions {
+                let path = child(&root, segment("Transaction", transaction.txid()));
+                if let Some(height) = transaction
+                    .mined_height()
+                    .filter(|height| **height > zewif.export_height())
+                {
+                    let message = format!(
+                        "mined at height {}, after the export height {}",
+                        height,
+                        zewif.export_height()
+                    );
+                    self.report(
+                        Severity::Warning,
+                        FindingCode::HeightAfterExport,
+                        path.clone(),
+                        message,
+                    );
+                }
+                if let (Some(hash), Some(position)) =
+                    (transaction.block_hash(), transaction.block_position())
+                    && hash != position.block_hash()
+                {
+                    let message = format!(
+                        "block hash {} differs from that of the block position, {}",
+                        hash,
+                        position.block_hash()
+                    );
+                    self.report(
+                        Severity::Error,
+                        FindingCode::BlockHashMismatch,
+                        path.clone(),
+                        message,
+                    );
+                }
+                let Some(raw) = transaction.raw() else {
+                    continue;
+                };
+                match TxId::from_raw_transaction(raw.as_slice()) {
+                    Ok(txid) if txid != transaction.txid() => {
+                        let message = format!("raw transaction has ID {}", txid);
+                        self.report(Severity::Error, FindingCode::TxidMismatch, path, message);
+                    }
+                    Ok(_) => {}
+                    Err(error) => {
+                        self.report(
+                            Severity::Warning,
+                            FindingCode::InvalidRawTransaction,
+                            path,
+                            error.to_string(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn wallet(&mut self, zewif: &Zewif, wallet: &ZewifWallet, path: &[String]) {
+        if self.options.network {
+            if let Some(params) = wallet
+                .network_parameters()
+                .filter(|params| params.network() != wallet.network())
+            {
+                let message = format!(
+                    "network parameters are for the {} network, but the wallet is on the {} network",
+                    String::from(params.network()),
+                    String::from(wallet.network())
+                );
+                self.report(
+                    Severity::Error,
+                    FindingCode::InvalidNetworkParameters,
+                    path.to_vec(),
+                    message,
+                );
+            }
+            for mismatch in wallet.validate_network() {
+                let account = &wallet.accounts()[mismatch.account()];
+                let error = Error::NetworkMismatch {
+                    element: mismatch.element().to_string(),
+                    expected: wallet.network().into(),
+                    detected: mismatch.detected().into(),
+                };
+                let path = child(path, account_segment(account));
+                self.report(
+                    Severity::Error,
+                    FindingCode::NetworkMismatch,
+                    path,
+                    error.to_string(),
+                );
+            }
+        }
+
+        if self.options.seeds {
+            for seed in wallet.seeds() {
+                let seed_path = child(path, segment("WalletSeed", seed.index()));
+                // The fingerprint of an unavailable seed is the one recorded
+                // with its material, if any.
+                if let (Some(recorded), Ok(computed)) =
+                    (seed.fingerprint(), seed.seed_material().fingerprint())
+                    && *recorded != computed
+                {
+                    let message = format!(
+                        "recorded fingerprint {} differs from {}, the fingerprint of the seed material",
+                        recorded.to_hex(),
+                        computed.to_hex()
+                    );
+                    self.report(
+                        Severity::Error,
+                        FindingCode::SeedFingerprintMismatch,
+                        seed_path.clone(),
+                        message,
+                    );
+                }
+                let unavailable = match seed.seed_material().validate() {
+                    Err(error) => {
+                        self.report(
+                            Severity::Error,
+                            FindingCode::InvalidSeed,
+                            seed_path,
+                            error.to_string(),
+                        );
+                        continue;
+                    }
+                    Ok(SeedStatus::Available) => continue,
+                    Ok(SeedStatus::Locked) => "seed is encrypted",
+                    Ok(SeedStatus::External) => "seed is held by a hardware device",
+                    Ok(SeedStatus::Redacted) => "seed was redacted from a watch-only export",
+                };
+                self.report(
+                    Severity::Info,
+                    FindingCode::SeedUnavailable,
+                    seed_path,
+                    unavailable,
+                );
+            }
+
+            // An account naming no seed is derived from the wallet's only seed,
+            // if it has just one.
+            let mut claimed: Vec<(Option<&SeedFingerprint>, u32, &Account)> = Vec::new();
+            for account in wallet.accounts() {
+                let seed = account.seed_fingerprint();
+                let Some(id) = account
+                    .zip32_account_id()
+                    .filter(|_| seed.is_some() || wallet.seeds().len() <= 1)
+                else {
+                    continue;
+                };
+                match claimed
+                    .iter()
+                    .find(|(other_seed, other_id, _)| *other_seed == seed && *other_id == id)
+                {
+                    Some((_, _, other)) => {
+                        let message = format!(
+                            "ZIP 32 account ID {} is also claimed by {}",
+                            id,
+                            account_segment(other)
+                        );
+                        let account_path = child(path, account_segment(account));
+                        self.report(
+                            Severity::Error,
+                            FindingCode::DuplicateAccountIndex,
+                            account_path,
+                            message,
+                        );
+                    }
+                    None => claimed.push((seed, id, account)),
+                }
+            }
+
+            let orphaned_keys = wallet.orphaned_data().spending_keys().count();
+            if orphaned_keys > 0 {
+                let message = format!(
+                    "{} spending keys are not attributed to any account",
+                    orphaned_keys
+                );
+                let path = child(path, "OrphanedData".to_string());
+                self.report(
+                    Severity::Warning,
+                    FindingCode::OrphanedSpendingKey,
+                    path,
+                    message,
+                );
+            }
+
+            let held: HashSet<String> =
+                wallet
+                    .accounts()
+                    .iter()
+                    .flat_map(|account| account.addresses())
+                    .chain(wallet.orphaned_data().records().iter().filter_map(
+                        |record| match record {
+                            OrphanedRecord::Address(address) => Some(address),
+                            _ => None,
+                        },
+                    ))
+                    .map(|address| address.as_string())
+                    .collect();
+            for entry in wallet.key_pool().entries() {
+                if let Some(address) = entry.address().filter(|address| !held.contains(*address)) {
+                    let message =
+                        format!("address {} is not among the wallet's key material", address);
+                    let path = child(
+                        &child(path, "KeyPool".to_string()),
+                        segment("KeyPoolEntry", entry.pool_index()),
+                    );
+                    self.report(
+                        Severity::Warning,
+                        FindingCode::UnknownKeyPoolKey,
+                        path,
+                        message,
+                    );
+                }
+            }
+        }
+
+        if self.options.witnesses {
+            let tree_states = [
+                (
+                    "Sapling",
+                    wallet.sapling_tree_state().map(|state| state.validate()),
+                ),
+                (
+                    "Orchard",
+                    wallet.orchard_tree_state().map(|state| state.validate()),
+                ),
+            ];
+            for (pool, result) in tree_states {
+                if let Some(Err(error)) = result {
+                    let message = format!("{} tree state: {}", pool, error);
+                    self.report(
+                        Severity::Error,
+                        FindingCode::InvalidTreeState,
+                        path.to_vec(),
+                        message,
+                    );
+                }
+            }
+        }
+
+        if self.options.transactions
+            && let Some(birthday) = wallet
+                .birthday_height()
+                .filter(|height| *height > zewif.export_height())
+        {
+            let message = format!(
+                "birthday height {} is after the export height {}",
+                birthday,
+                zewif.export_height()
+            );
+            self.report(
+                Severity::Warning,
+                FindingCode::HeightAfterExport,
+                path.to_vec(),
+                message,
+            );
+        }
+
+        for account in wallet.accounts() {
+            self.account(
+                zewif,
+                wallet,
+                account,
+                &child(path, account_segment(account)),
+            );
+        }
+    }
+
+    fn account(&mut self, zewif: &Zewif, wallet: &ZewifWallet, account: &Account, path: &[String]) {
+        if self.options.seeds {
+            for fingerprint in account.referenced_seed_fingerprints() {
+                if !wallet.may_hold_seed(account, fingerprint) {
+                    let message = Error::UnknownSeedFingerprint {
+                        fingerprint: *fingerprint,
+                    }
+                    .to_string();
+                    self.report(
+                        Severity::Error,
+                        FindingCode::UnknownSeedFingerprint,
+                        path.to_vec(),
+                        message,
+                    );
+                }
+            }
+        }
+
+        #[cfg(feature = "zip32")]
+        if self.options.derivations {
+            let result = wallet.seed_for_account(account).and_then(|seed| {
+                seed.map_or(Ok(()), |seed| account.verify_orchard_derivation(&seed))
+            });
+            match result {
+                Ok(()) | Err(Error::UnknownSeedFingerprint { .. }) => {}
+                Err(error) => self.report(
+                    Severity::Error,
+                    FindingCode::DerivationMismatch,
+                    path.to_vec(),
+                    error.to_string(),
+                ),
+            }
+        }
+
+        if self.options.witnesses {
+            self.witnesses(wallet, account, path);
+        }
+
+        if self.options.transactions {
+            if let Some(birthday) = account
+                .birthday_height()
+                .filter(|height| *height > zewif.export_height())
+            {
+                let message = format!(
+                    "birthday height {} is after the export height {}",
+                    birthday,
+                    zewif.export_height()
+                );
+                self.report(
+                    Severity::Warning,
+                    FindingCode::HeightAfterExport,
+                    path.to_vec(),
+                    message,
+                );
+            }
+            let missing = account
+                .relevant_transactions()
+                .filter(|txid| zewif.get_transaction(**txid).is_none());
+            for txid in missing {
+                let message = format!("relevant transaction {} is not in the Zewif", txid);
+                self.report(
+                    Severity::Warning,
+                    FindingCode::MissingTransaction,
+                    path.to_vec(),
+                    message,
+                );
+            }
+
+            // An account without a birthday of its own is bounded by its
+            // wallet's.
+            if let Some(birthday) = account.birthday_height().or(wallet.birthday_height()) {
+                let mut early: Vec<_> = account
+                    .relevant_transactions()
+                    .filter_map(|txid| {
+                        Some((
+                            zewif.get_transaction(*txid)?.mined_height().copied()?,
+                            *txid,
+                        ))
+                    })
+                    .filter(|(height, _)| *height < birthday)
+                    .collect();
+                early.sort();
+                for (height, txid) in early {
+                    let message = format!(
+                        "transaction {} was mined at height {}, before the birthday height {}",
+                        txid, height, birthday
+                    );
+                    self.report(
+                        Severity::Warning,
+                        FindingCode::TransactionBeforeBirthday,
+                        path.to_vec(),
+                        message,
+                    );
+                }
+            }
+        }
+    }
+
+    fn witnesses(&mut self, wallet: &ZewifWallet, account: &Account, path: &[String]) {
+        for note in account.sprout_received_notes() {
+            if let Some(Err(error)) = note.witness().map(|witness| witness.validate()) {
+                let path = child(path, segment("SproutReceivedNote", note.index()));
+                self.report(
+                    Severity::Error,
+                    FindingCode::InvalidWitness,
+                    path,
+                    error.to_string(),
+                );
+            }
+        }
+        for note in account.sapling_received_notes() {
+            let Some(witness) = note.witness() else {
+                continue;
+            };
+            let path = child(path, segment("SaplingReceivedNote", note.index()));
+            if let Err(error) = witness.validate() {
+                self.report(
+                    Severity::Error,
+                    FindingCode::InvalidWitness,
+                    path,
+                    error.to_string(),
+                );
+                continue;
+            }
+            if wallet
+                .sapling_anchors()
+                .is_some_and(|anchors| !anchors.registry().contains(witness.witness().anchor()))
+            {
+                let message = "anchor is not a known Sapling anchor";
+                self.report(
+                    Severity::Error,
+                    FindingCode::UnknownAnchor,
+                    path.clone(),
+                    message,
+                );
+            }
+            #[cfg(feature = "zip32")]
+            if self.options.witness_roots
+                && let Err(error) = witness.verify_root()
+            {
+                self.report(
+                    Severity::Error,
+                    FindingCode::WitnessRootMismatch,
+                    path,
+                    error.to_string(),
+                );
+            }
+        }
+        for note in account.orchard_received_notes() {
+            let Some(witness) = note.witness() else {
+                continue;
+            };
+            let path = child(path, segment("OrchardReceivedNote", note.index()));
+            if let Err(error) = witness.validate() {
+                self.report(
+                    Severity::Error,
+                    FindingCode::InvalidWitness,
+                    path,
+                    error.to_string(),
+                );
+                continue;
+            }
+            if wallet
+                .orchard_anchors()
+                .is_some_and(|anchors| !anchors.registry().contains(witness.witness().anchor()))
+            {
+                let message = "anchor is not a known Orchard anchor";
+                self.report(Severity::Error, FindingCode::UnknownAnchor, path, message);
+            }
+        }
+        let has_orchard_witnesses = account
+            .orchard_received_notes()
+            .iter()
+            .any(|note| note.witness().is_some());
+        if cfg!(feature = "zip32") && self.options.witness_roots && has_orchard_witnesses {
+            let message = "checking Orchard witness roots is not supported";
+            self.report(
+                Severity::Info,
+                FindingCode::CheckSkipped,
+                path.to_vec(),
+                message,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, AccountDerivation, Address, Bip39Mnemonic, Blob, BlockHash, BlockHeight, Data,
+        DerivationPath, FindingCode, HardwareSeedReference, IncrementalWitness, KeyPoolEntry,
+        Network, NetworkParameters, OrphanedRecord, ProtocolAddress, RandomInstance,
+        SeedFingerprint, SeedMaterial, Severity, Transaction, TxBlockPosition, TxId,
+        ValidationOptions, WalletSeed, Zewif, ZewifWallet,
+        sapling::{SaplingReceivedNote, SaplingWitness},
+        transparent::{self, TransparentSpendingKey},
+    };
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn zewif(wallet: ZewifWallet) -> Zewif {
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        zewif
+    }
+
+    fn wallet_with_account(account: Account) -> ZewifWallet {
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_seed(
+            WalletSeed::new(SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(
+                MNEMONIC, None,
+            )))
+            .unwrap(),
+        );
+        wallet.add_account(account);
+        wallet
+    }
+
+    fn savings() -> Account {
+        let mut account = Account::new();
+        account.set_name("Savings");
+        account
+    }
+
+    #[test]
+    fn test_valid_zewif() {
+        let report = zewif(wallet_with_account(savings())).validate(ValidationOptions::new());
+        assert!(report.is_ok());
+        assert!(report.findings().is_empty());
+        assert_eq!(report.to_string(), "no findings");
+    }
+
+    #[test]
+    fn test_invalid_seed() {
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_seed(
+            WalletSeed::new(SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(
+                "not a mnemonic",
+                None,
+            )))
+            .unwrap(),
+        );
+        let report = zewif(wallet).validate(ValidationOptions::new());
+        assert!(report.has_errors());
+        let finding = &report.findings()[0];
+        assert_eq!(finding.code(), FindingCode::InvalidSeed);
+        assert_eq!(finding.severity(), Severity::Error);
+        assert_eq!(finding.path(), ["Zewif", "Wallet[0]", "WalletSeed[0]"]);
+
+        let mut options = ValidationOptions::new();
+        options.set_seeds(false);
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_seed(
+            WalletSeed::new(SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(
+                "not a mnemonic",
+                None,
+            )))
+            .unwrap(),
+        );
+        assert!(zewif(wallet).validate(options).is_ok());
+    }
+
+    #[test]
+    fn test_seed_fingerprint_mismatch() {
+        let seed = WalletSeed::new(SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(
+            MNEMONIC, None,
+        )))
+        .unwrap();
+        let envelope = Envelope::from(seed.clone());
+        let fingerprint = envelope.assertion_with_predicate("fingerprint").unwrap();
+        let envelope = envelope
+            .remove_assertion(fingerprint)
+            .add_assertion("fingerprint", SeedFingerprint::new([7; 32]));
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_seed(WalletSeed::try_from(envelope).unwrap());
+
+        let report = zewif(wallet).validate(ValidationOptions::new());
+        let finding = &report.findings()[0];
+        assert_eq!(finding.code(), FindingCode::SeedFingerprintMismatch);
+        assert_eq!(finding.severity(), Severity::Error);
+        assert_eq!(finding.path(), ["Zewif", "Wallet[0]", "WalletSeed[0]"]);
+        assert!(
+            finding
+                .message()
+                .contains(&seed.fingerprint().unwrap().to_hex())
+        );
+    }
+
+    #[test]
+    fn test_network_findings() {
+        let mut account = savings();
+        let address = transparent::Address::new("tmBsTi2xWTjUdEXnuTceL7fecEQKeWaPDJd");
+        account.add_address(crate::Address::new(ProtocolAddress::Transparent(address)));
+        let mut wallet = wallet_with_account(account);
+        wallet.set_network_parameters(Some(NetworkParameters::for_network(Network::Test)));
+        let mut zewif = zewif(wallet);
+        zewif.add_wallet(ZewifWallet::new(Network::Test));
+
+        let report = zewif.validate(ValidationOptions::new());
+        assert!(report.contains(FindingCode::MixedNetworks));
+        assert!(report.contains(FindingCode::InvalidNetworkParameters));
+        let mismatch = report
+            .findings()
+            .iter()
+            .find(|f| f.code() == FindingCode::NetworkMismatch)
+            .unwrap();
+        assert_eq!(
+            mismatch.path(),
+            ["Zewif", "Wallet[0]", "Account[\"Savings\"]"]
+        );
+        assert!(
+            mismatch
+                .message()
+                .contains("tmBsTi2xWTjUdEXnuTceL7fecEQKeWaPDJd")
+        );
+    }
+
+    #[test]
+    fn test_seed_and_transaction_findings() {
+        let mut account = savings();
+        account.set_seed_fingerprint(Some(SeedFingerprint::new([7; 32])));
+        account.set_birthday_height(Some(BlockHeight::from(2_100_000)));
+        let txid = TxId::from_bytes([1; 32]);
+        account.add_relevant_transaction(txid);
+        let mut wallet = wallet_with_account(account);
+        let reference =
+            HardwareSeedReference::new("Ledger Nano S", Blob::new([0x0a, 0x1b, 0x2c, 0x3d]));
+        wallet.add_seed(WalletSeed::new(SeedMaterial::HardwareReference(reference)).unwrap());
+
+        let report = zewif(wallet.clone()).validate(ValidationOptions::new());
+        let codes: Vec<_> = report
+            .findings()
+            .iter()
+            .map(|finding| (finding.severity(), finding.code()))
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                (Severity::Info, FindingCode::SeedUnavailable),
+                (Severity::Warning, FindingCode::HeightAfterExport),
+                (Severity::Warning, FindingCode::MissingTransaction),
+            ]
+        );
+        assert!(report.is_ok());
+        assert!(report.has_warnings());
+        assert!(report.findings()[2].message().contains(&txid.to_string()));
+
+        let mut wallet = wallet_with_account(Account::new());
+        wallet.accounts_mut()[0].set_seed_fingerprint(Some(SeedFingerprint::new([7; 32])));
+        let report = zewif(wallet).validate(ValidationOptions::new());
+        assert!(report.contains(FindingCode::UnknownSeedFingerprint));
+    }
+
+    #[test]
+    fn test_raw_transaction_findings() {
+        let raw = hex::decode(
+            include_str!("test_data/tx_v4_testnet.hex")
+                .split_whitespace()
+                .collect::<String>(),
+        )
+        .unwrap();
+        let txid =
+            TxId::from_hex("64f0bd7fe30ce23753358fe3a2dc835b8fba9c0274c4e2c54a6f73114cb55639")
+                .unwrap();
+        let mut zewif = zewif(wallet_with_account(savings()));
+        for (txid, raw) in [
+            (txid, raw.clone()),
+            (TxId::from_bytes([1; 32]), raw),
+            (TxId::from_bytes([2; 32]), vec![5, 0, 0, 0x80]),
+        ] {
+            let mut transaction = Transaction::new(txid);
+            transaction.set_raw(Data::from_vec(raw));
+            zewif.add_transaction(txid, transaction).unwrap();
+        }
+        let mut transaction = Transaction::new(TxId::from_bytes([3; 32]));
+        transaction.set_block_position(Some(TxBlockPosition::new(
+            BlockHash::from_bytes([1; 32]),
+            0,
+        )));
+        transaction.set_block_hash(Some(BlockHash::from_bytes([2; 32])));
+        zewif
+            .add_transaction(transaction.txid(), transaction)
+            .unwrap();
+
+        let report = zewif.validate(ValidationOptions::new());
+        let codes: Vec<_> = report
+            .findings()
+            .iter()
+            .map(|finding| (finding.severity(), finding.code()))
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                (Severity::Error, FindingCode::TxidMismatch),
+                (Severity::Warning, FindingCode::InvalidRawTransaction),
+                (Severity::Error, FindingCode::BlockHashMismatch),
+            ]
+        );
+        assert!(report.findings()[0].message().contains(&txid.to_string()));
+    }
+
+    #[test]
+    fn test_witness_findings() {
+        let mut note = SaplingReceivedNote::random();
+        let witness = SaplingWitness::random();
+        let inner = witness.witness();
+        let malformed = IncrementalWitness::from_parts(
+            *inner.note_commitment(),
+            inner.note_position(),
+            inner.merkle_path()[1..].to_vec(),
+            *inner.anchor(),
+            inner.anchor_tree_size(),
+            vec![],
+        );
+        note.set_witness(Some(SaplingWitness::new(malformed)));
+        let mut account = savings();
+        account.add_sapling_received_note(note);
+
+        let report = zewif(wallet_with_account(account)).validate(ValidationOptions::new());
+        let finding = report
+            .findings()
+            .iter()
+            .find(|f| f.code() == FindingCode::InvalidWitness)
+            .unwrap();
+        assert_eq!(
+            finding.path(),
+            [
+                "Zewif",
+                "Wallet[0]",
+                "Account[\"Savings\"]",
+                "SaplingReceivedNote[0]"
+            ]
+        );
+
+        let rendered = report.to_string();
+        assert!(
+            rendered.starts_with("Errors (1):\n  [invalid-witness] Zewif > Wallet[0]"),
+            "{}",
+            rendered
+        );
+
+        let mut options = ValidationOptions::new();
+        options.set_witnesses(false);
+        assert!(
+            !zewif(wallet_with_account(Account::new()))
+                .validate(options)
+                .contains(FindingCode::InvalidWitness)
+        );
+    }
+
+    #[test]
+    fn test_transaction_before_birthday() {
+        let txid = TxId::from_bytes([1; 32]);
+        let mut tx = Transaction::new(txid);
+        tx.set_mined_height(BlockHeight::from(1_000_000));
+        let mut account = savings();
+        account.add_relevant_transaction(txid);
+        let mut wallet = wallet_with_account(account);
+        wallet.set_birthday_height(Some(BlockHeight::from(1_200_000)));
+        let mut zewif = zewif(wallet);
+        zewif.add_transaction(txid, tx).unwrap();
+
+        let report = zewif.validate(ValidationOptions::new());
+        let finding = report
+            .findings()
+            .iter()
+            .find(|f| f.code() == FindingCode::TransactionBeforeBirthday)
+            .unwrap();
+        assert_eq!(finding.severity(), Severity::Warning);
+        assert_eq!(
+            finding.path(),
+            ["Zewif", "Wallet[0]", "Account[\"Savings\"]"]
+        );
+        assert_eq!(
+            finding.message(),
+            format!(
+                "transaction {} was mined at height 1000000, before the birthday height 1200000",
+                txid
+            )
+        );
+
+        // The account's own birthday takes precedence over its wallet's.
+        zewif.wallets_mut()[0].accounts_mut()[0]
+            .set_birthday_height(Some(BlockHeight::from(900_000)));
+        assert!(zewif.validate(ValidationOptions::new()).is_ok());
+        assert!(
+            zewif
+                .validate(ValidationOptions::new())
+                .findings()
+                .is_empty()
+        );
+
+        zewif.wallets_mut()[0].set_birthday_height(Some(BlockHeight::from(2_100_000)));
+        assert!(
+            zewif
+                .validate(ValidationOptions::new())
+                .contains(FindingCode::HeightAfterExport)
+        );
+    }
+
+    #[test]
+    fn test_unknown_derivation_seed() {
+        let mut account = savings();
+        account.set_derivation(AccountDerivation::FromSeed {
+            fingerprint: SeedFingerprint::new([7; 32]),
+            path: DerivationPath::hardened(&[32, 133, 0]).unwrap(),
+        });
+        let report = zewif(wallet_with_account(account)).validate(ValidationOptions::new());
+        let finding = report
+            .findings()
+            .iter()
+            .find(|f| f.code() == FindingCode::UnknownSeedFingerprint)
+            .unwrap();
+        assert_eq!(
+            finding.path(),
+            ["Zewif", "Wallet[0]", "Account[\"Savings\"]"]
+        );
+
+        let mut account = savings();
+        account.set_derivation(AccountDerivation::ImportedKeysOnly);
+        assert!(
+            zewif(wallet_with_account(account))
+                .validate(ValidationOptions::new())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_duplicate_account_index() {
+        let mut wallet = wallet_with_account(savings());
+        wallet.accounts_mut()[0].set_zip32_account_id(0);
+        let mut spending = Account::new();
+        spending.set_name("Spending");
+        spending.set_zip32_account_id(0);
+        wallet.add_account(spending);
+
+        let report = zewif(wallet.clone()).validate(ValidationOptions::new());
+        let finding = report
+            .findings()
+            .iter()
+            .find(|f| f.code() == FindingCode::DuplicateAccountIndex)
+            .unwrap();
+        assert_eq!(finding.severity(), Severity::Error);
+        assert_eq!(
+            finding.path(),
+            ["Zewif", "Wallet[0]", "Account[\"Spending\"]"]
+        );
+        assert_eq!(
+            finding.message(),
+            "ZIP 32 account ID 0 is also claimed by Account[\"Savings\"]"
+        );
+
+        // Accounts naming no seed cannot be compared once the wallet holds
+        // several.
+        wallet.add_seed(WalletSeed::new(SeedMaterial::Redacted).unwrap());
+        assert!(
+            !zewif(wallet)
+                .validate(ValidationOptions::new())
+                .contains(FindingCode::DuplicateAccountIndex)
+        );
+
+        // The same index under different seeds is not a conflict.
+        let mut wallet = wallet_with_account(savings());
+        wallet.accounts_mut()[0].set_zip32_account_id(0);
+        let mut other = Account::new();
+        other.set_zip32_account_id(0);
+        other.set_seed_fingerprint(Some(SeedFingerprint::new([7; 32])));
+        wallet.add_account(other);
+        assert!(
+            !zewif(wallet)
+                .validate(ValidationOptions::new())
+                .contains(FindingCode::DuplicateAccountIndex)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_account_id() {
+        let mut wallet = wallet_with_account(savings());
+        wallet.add_account(Account::new());
+        let mut zewif = zewif(wallet.clone());
+        assert!(
+            !zewif
+                .validate(ValidationOptions::new())
+                .contains(FindingCode::DuplicateAccountId)
+        );
+
+        // A clone keeps the identifier of the account it copies.
+        let mut copy = wallet.accounts()[0].clone();
+        copy.set_name("Copy");
+        zewif.wallets_mut()[0].add_account(copy.clone());
+        let mut other = ZewifWallet::new(Network::Main);
+        copy.set_name("Elsewhere");
+        other.add_account(copy);
+        zewif.add_wallet(other);
+        let report = zewif.validate(ValidationOptions::new());
+        let findings: Vec<_> = report
+            .findings()
+            .iter()
+            .filter(|f| f.code() == FindingCode::DuplicateAccountId)
+            .map(|f| (f.severity(), f.path().join(" > ")))
+            .collect();
+        assert_eq!(
+            findings,
+            [
+                (
+                    Severity::Error,
+                    "Zewif > Wallet[0] > Account[\"Copy\"]".to_string()
+                ),
+                (
+                    Severity::Error,
+                    "Zewif > Wallet[1] > Account[\"Elsewhere\"]".to_string()
+                )
+            ]
+        );
+        let id = zewif.wallets()[0].accounts()[0].id().hex();
+        assert!(
+            report.findings().iter().any(|f| f.message()
+                == format!("identifier {} is also held by Account[\"Savings\"]", id))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_address() {
+        let shared = || {
+            Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+                "t1shared",
+            )))
+        };
+        let mut wallet = wallet_with_account(savings());
+        wallet.accounts_mut()[0].add_address(shared());
+        let mut spending = Account::new();
+        spending.set_name("Spending");
+        wallet.add_account(spending.clone());
+        assert!(
+            !zewif(wallet.clone())
+                .validate(ValidationOptions::new())
+                .contains(FindingCode::DuplicateAddress)
+        );
+
+        spending.add_address(shared());
+        wallet.add_account(spending);
+        let report = zewif(wallet.clone()).validate(ValidationOptions::new());
+        let finding = report
+            .findings()
+            .iter()
+            .find(|f| f.code() == FindingCode::DuplicateAddress)
+            .unwrap();
+        assert_eq!(finding.severity(), Severity::Warning);
+        assert_eq!(
+            finding.path(),
+            ["Zewif", "Wallet[0]", "Account[\"Savings\"]"]
+        );
+        assert_eq!(
+            finding.message(),
+            "address t1shared is held by account 0 of wallet 0, account 2 of wallet 0"
+        );
+
+        let mut options = ValidationOptions::new();
+        options.set_addresses(false);
+        assert!(
+            !zewif(wallet)
+                .validate(options)
+                .contains(FindingCode::DuplicateAddress)
+        );
+    }
+
+    #[test]
+    fn test_orphaned_spending_key() {
+        let mut wallet = wallet_with_account(savings());
+        wallet.add_orphaned_record(OrphanedRecord::Address(Address::new(
+            ProtocolAddress::Transparent(transparent::Address::new("t1orphan")),
+        )));
+        assert!(
+            zewif(wallet.clone())
+                .validate(ValidationOptions::new())
+                .is_ok()
+        );
+
+        wallet.add_orphaned_record(OrphanedRecord::TransparentSpendingKey(
+            TransparentSpendingKey::new([9; 32]),
+        ));
+        let report = zewif(wallet).validate(ValidationOptions::new());
+        let finding = report
+            .findings()
+            .iter()
+            .find(|f| f.code() == FindingCode::OrphanedSpendingKey)
+            .unwrap();
+        assert_eq!(finding.severity(), Severity::Warning);
+        assert_eq!(finding.path(), ["Zewif", "Wallet[0]", "OrphanedData"]);
+        assert_eq!(
+            finding.message(),
+            "1 spending keys are not attributed to any account"
+        );
+    }
+
+    #[test]
+    fn test_unknown_key_pool_key() {
+        let mut wallet = wallet_with_account(savings());
+        wallet.accounts_mut()[0].add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new("t1held"),
+        )));
+        let mut held = KeyPoolEntry::new(0, false);
+        held.set_address(Some("t1held".to_string()));
+        wallet.key_pool_mut().add(held);
+        // Entries recorded only by public key cannot be checked.
+        wallet.key_pool_mut().add(KeyPoolEntry::new(1, true));
+        assert!(
+            !zewif(wallet.clone())
+                .validate(ValidationOptions::new())
+                .contains(FindingCode::UnknownKeyPoolKey)
+        );
+
+        let mut unknown = KeyPoolEntry::new(2, false);
+        unknown.set_address(Some("t1unknown".to_string()));
+        wallet.key_pool_mut().add(unknown);
+        let report = zewif(wallet).validate(ValidationOptions::new());
+        let finding = report
+            .findings()
+            .iter()
+            .find(|f| f.code() == FindingCode::UnknownKeyPoolKey)
+            .unwrap();
+        assert_eq!(finding.severity(), Severity::Warning);
+        assert_eq!(
+            finding.path(),
+            ["Zewif", "Wallet[0]", "KeyPool", "KeyPoolEntry[2]"]
+        );
+        assert_eq!(
+            finding.message(),
+            "address t1unknown is not among the wallet's key material"
+        );
+    }
+
+    #[cfg(not(feature = "zip32"))]
+    #[test]
+    fn test_expensive_checks_skipped() {
+        let report = zewif(wallet_with_account(savings())).validate(ValidationOptions::thorough());
+        assert!(report.is_ok());
+        assert_eq!(report.with_severity(Severity::Info).count(), 2);
+        assert!(report.contains(FindingCode::CheckSkipped));
+    }
+
+    #[cfg(feature = "zip32")]
+    #[test]
+    fn test_witness_root_mismatch() {
+        let mut note = SaplingReceivedNote::random();
+        note.set_witness(Some(SaplingWitness::random()));
+        let mut account = savings();
+        account.add_sapling_received_note(note);
+        let zewif = zewif(wallet_with_account(account));
+
+        assert!(
+            !zewif
+                .validate(ValidationOptions::new())
+                .contains(FindingCode::WitnessRootMismatch)
+        );
+        let report = zewif.validate(ValidationOptions::thorough());
+        assert!(report.contains(FindingCode::WitnessRootMismatch));
+        assert!(!report.contains(FindingCode::CheckSkipped));
+    }
+
+    #[cfg(feature = "zip32")]
+    #[test]
+    fn test_orchard_witness_roots_skipped() {
+        let mut note = crate::orchard::OrchardReceivedNote::random();
+        note.set_witness(Some(crate::orchard::OrchardWitness::random()));
+        let mut account = savings();
+        account.add_orchard_received_note(note);
+        let zewif = zewif(wallet_with_account(account));
+
+        assert!(
+            !zewif
+                .validate(ValidationOptions::new())
+                .contains(FindingCode::CheckSkipped)
+        );
+        let report = zewif.validate(ValidationOptions::thorough());
+        let finding = report
+            .findings()
+            .iter()
+            .find(|f| f.code() == FindingCode::CheckSkipped)
+            .unwrap();
+        assert_eq!(finding.severity(), Severity::Info);
+        assert_eq!(
+            finding.path(),
+            ["Zewif", "Wallet[0]", "Account[\"Savings\"]"]
+        );
+        assert_eq!(
+            finding.message(),
+            "checking Orchard witness roots is not supported"
+        );
+    }
+}
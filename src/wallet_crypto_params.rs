@@ -0,0 +1,135 @@
+use bc_envelope::prelude::*;
+
+use crate::Data;
+
+/// zcashd's key derivation method using OpenSSL's `EVP_BytesToKey` with
+/// SHA-512.
+pub const ZCASHD_DERIVATION_METHOD_EVP_SHA512: u32 = 0;
+
+/// The parameters with which a wallet's secrets were encrypted under the
+/// user's passphrase.
+///
+/// zcashd encrypts secrets with a random master key, which is itself stored
+/// encrypted under a key derived from the passphrase (the `mkey` record). To
+/// decrypt a secret, the passphrase is stretched with the recorded derivation
+/// method, salt, and number of rounds, the result is used to decrypt the
+/// master key, and the master key decrypts the secret.
+///
+/// # Data Preservation
+/// These parameters must be preserved exactly, along with the encrypted
+/// secrets, for a later decryption to be possible.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Data, WalletCryptoParams, ZCASHD_DERIVATION_METHOD_EVP_SHA512};
+/// let params = WalletCryptoParams::new(
+///     Data::from_vec(vec![0u8; 48]),
+///     Data::from_vec(vec![1u8; 8]),
+///     ZCASHD_DERIVATION_METHOD_EVP_SHA512,
+///     25_000,
+/// );
+/// assert_eq!(params.rounds(), 25_000);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct WalletCryptoParams {
+    /// The master key, encrypted under the passphrase-derived key.
+    encrypted_master_key: Data,
+    salt: Data,
+    method: u32,
+    rounds: u32,
+    /// Parameters for derivation methods other than
+    /// [`ZCASHD_DERIVATION_METHOD_EVP_SHA512`], which has none.
+    other_parameters: Data,
+}
+
+impl WalletCryptoParams {
+    pub fn new(encrypted_master_key: Data, salt: Data, method: u32, rounds: u32) -> Self {
+        Self {
+            encrypted_master_key,
+            salt,
+            method,
+            rounds,
+            other_parameters: Data::new(),
+        }
+    }
+
+    pub fn encrypted_master_key(&self) -> &Data {
+        &self.encrypted_master_key
+    }
+
+    pub fn salt(&self) -> &Data {
+        &self.salt
+    }
+
+    /// Returns the key derivation method; see
+    /// [`ZCASHD_DERIVATION_METHOD_EVP_SHA512`].
+    pub fn method(&self) -> u32 {
+        self.method
+    }
+
+    /// Returns the number of rounds of the key derivation.
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    pub fn other_parameters(&self) -> &Data {
+        &self.other_parameters
+    }
+
+    pub fn set_other_parameters(&mut self, other_parameters: Data) {
+        self.other_parameters = other_parameters;
+    }
+}
+
+impl From<WalletCryptoParams> for Envelope {
+    fn from(value: WalletCryptoParams) -> Self {
+        Envelope::new(value.encrypted_master_key)
+            .add_type("WalletCryptoParams")
+            .add_assertion("salt", value.salt)
+            .add_assertion("method", value.method)
+            .add_assertion("rounds", value.rounds)
+            .add_assertion("other_parameters", value.other_parameters)
+    }
+}
+
+impl TryFrom<Envelope> for WalletCryptoParams {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("WalletCryptoParams")?;
+        let encrypted_master_key = envelope.extract_subject()?;
+        let salt = envelope.extract_object_for_predicate("salt")?;
+        let method = envelope.extract_object_for_predicate("method")?;
+        let rounds = envelope.extract_object_for_predicate("rounds")?;
+        let other_parameters = envelope.extract_object_for_predicate("other_parameters")?;
+        Ok(Self {
+            encrypted_master_key,
+            salt,
+            method,
+            rounds,
+            other_parameters,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Data, test_envelope_roundtrip};
+
+    use super::WalletCryptoParams;
+
+    impl crate::RandomInstance for WalletCryptoParams {
+        fn random() -> Self {
+            Self {
+                encrypted_master_key: Data::random(),
+                salt: Data::random(),
+                method: u32::random(),
+                rounds: u32::random(),
+                other_parameters: Data::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(WalletCryptoParams);
+}
@@ -0,0 +1,216 @@
+use std::fmt;
+
+use bc_envelope::prelude::*;
+use chrono::DateTime;
+
+use crate::SecondsSinceEpoch;
+
+/// Descriptive information about where a wallet came from: the software and
+/// file it was read from, and the tool that exported it.
+///
+/// Importers record this once per wallet so that a restore can be traced
+/// back to its source long after the export, such as when debugging a
+/// missing balance. It is plain metadata, unlike the [`ProvenanceInfo`] of a
+/// signed Zewif, and nothing in it is verified.
+///
+/// [`ProvenanceInfo`]: crate::ProvenanceInfo
+///
+/// # Examples
+/// ```
+/// # use zewif::{SecondsSinceEpoch, WalletProvenance};
+/// let mut provenance = WalletProvenance::new("zcashd");
+/// provenance.set_source_version(Some("5.9.1".to_string()));
+/// provenance.set_source_format(Some("wallet.dat".to_string()));
+/// provenance.set_exported_at(Some(SecondsSinceEpoch::from(1_730_592_000u64)));
+/// provenance.set_tool_name(Some("zewif-zcashd".to_string()));
+/// provenance.set_tool_version(Some("0.4".to_string()));
+/// assert_eq!(
+///     provenance.to_string(),
+///     "zcashd 5.9.1 wallet.dat, exported 2024-11-03 by zewif-zcashd 0.4"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct WalletProvenance {
+    source_software: String,
+    source_version: Option<String>,
+    source_format: Option<String>,
+    exported_at: Option<SecondsSinceEpoch>,
+    tool_name: Option<String>,
+    tool_version: Option<String>,
+    notes: Option<String>,
+}
+
+impl WalletProvenance {
+    /// Creates provenance naming the software, such as `zcashd` or `zecwallet`,
+    /// whose wallet was exported.
+    pub fn new(source_software: impl Into<String>) -> Self {
+        Self {
+            source_software: source_software.into(),
+            source_version: None,
+            source_format: None,
+            exported_at: None,
+            tool_name: None,
+            tool_version: None,
+            notes: None,
+        }
+    }
+
+    pub fn source_software(&self) -> &str {
+        &self.source_software
+    }
+
+    pub fn set_source_software(&mut self, source_software: impl Into<String>) {
+        self.source_software = source_software.into();
+    }
+
+    pub fn source_version(&self) -> Option<&str> {
+        self.source_version.as_deref()
+    }
+
+    pub fn set_source_version(&mut self, source_version: Option<String>) {
+        self.source_version = source_version;
+    }
+
+    /// Returns the format of the file the wallet was read from, such as
+    /// `wallet.dat`.
+    pub fn source_format(&self) -> Option<&str> {
+        self.source_format.as_deref()
+    }
+
+    pub fn set_source_format(&mut self, source_format: Option<String>) {
+        self.source_format = source_format;
+    }
+
+    /// Returns the time at which the wallet was exported.
+    pub fn exported_at(&self) -> Option<SecondsSinceEpoch> {
+        self.exported_at
+    }
+
+    pub fn set_exported_at(&mut self, exported_at: Option<SecondsSinceEpoch>) {
+        self.exported_at = exported_at;
+    }
+
+    /// Returns the name of the tool that exported the wallet.
+    pub fn tool_name(&self) -> Option<&str> {
+        self.tool_name.as_deref()
+    }
+
+    pub fn set_tool_name(&mut self, tool_name: Option<String>) {
+        self.tool_name = tool_name;
+    }
+
+    pub fn tool_version(&self) -> Option<&str> {
+        self.tool_version.as_deref()
+    }
+
+    pub fn set_tool_version(&mut self, tool_version: Option<String>) {
+        self.tool_version = tool_version;
+    }
+
+    /// Returns free-form notes left by the exporting tool or its user.
+    pub fn notes(&self) -> Option<&str> {
+        self.notes.as_deref()
+    }
+
+    pub fn set_notes(&mut self, notes: Option<String>) {
+        self.notes = notes;
+    }
+}
+
+/// Formats the provenance as a single line, such as `zcashd 5.9.1
+/// wallet.dat, exported 2024-11-03 by zewif-zcashd 0.4`. The notes are not
+/// included.
+impl fmt::Display for WalletProvenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source_software)?;
+        for part in [&self.source_version, &self.source_format]
+            .into_iter()
+            .flatten()
+        {
+            write!(f, " {}", part)?;
+        }
+        if self.exported_at.is_some() || self.tool_name.is_some() {
+            write!(f, ", exported")?;
+        }
+        if let Some(exported_at) = self.exported_at {
+            let date = i64::try_from(u64::from(exported_at))
+                .ok()
+                .and_then(|seconds| DateTime::from_timestamp(seconds, 0));
+            match date {
+                Some(date) => write!(f, " {}", date.format("%Y-%m-%d"))?,
+                None => write!(f, " at {}", exported_at)?,
+            }
+        }
+        if let Some(tool_name) = &self.tool_name {
+            write!(f, " by {}", tool_name)?;
+            if let Some(tool_version) = &self.tool_version {
+                write!(f, " {}", tool_version)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<WalletProvenance> for Envelope {
+    fn from(value: WalletProvenance) -> Self {
+        Envelope::new(value.source_software)
+            .add_type("WalletProvenance")
+            .add_optional_assertion("source_version", value.source_version)
+            .add_optional_assertion("source_format", value.source_format)
+            .add_optional_assertion("exported_at", value.exported_at)
+            .add_optional_assertion("tool_name", value.tool_name)
+            .add_optional_assertion("tool_version", value.tool_version)
+            .add_optional_assertion("notes", value.notes)
+    }
+}
+
+impl TryFrom<Envelope> for WalletProvenance {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("WalletProvenance")?;
+        Ok(Self {
+            source_software: envelope.extract_subject()?,
+            source_version: envelope.extract_optional_object_for_predicate("source_version")?,
+            source_format: envelope.extract_optional_object_for_predicate("source_format")?,
+            exported_at: envelope.extract_optional_object_for_predicate("exported_at")?,
+            tool_name: envelope.extract_optional_object_for_predicate("tool_name")?,
+            tool_version: envelope.extract_optional_object_for_predicate("tool_version")?,
+            notes: envelope.extract_optional_object_for_predicate("notes")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SecondsSinceEpoch, test_envelope_roundtrip};
+
+    use super::WalletProvenance;
+
+    impl crate::RandomInstance for WalletProvenance {
+        fn random() -> Self {
+            Self {
+                source_software: String::random(),
+                source_version: String::opt_random(),
+                source_format: String::opt_random(),
+                exported_at: SecondsSinceEpoch::opt_random(),
+                tool_name: String::opt_random(),
+                tool_version: String::opt_random(),
+                notes: String::opt_random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(WalletProvenance);
+
+    #[test]
+    fn test_display() {
+        assert_eq!(WalletProvenance::new("zecwallet").to_string(), "zecwallet");
+
+        let mut provenance = WalletProvenance::new("zcashd");
+        provenance.set_tool_name(Some("zewif-zcashd".to_string()));
+        provenance.set_notes(Some("restored from backup".to_string()));
+        assert_eq!(provenance.to_string(), "zcashd, exported by zewif-zcashd");
+    }
+}
@@ -0,0 +1,200 @@
+use bc_envelope::prelude::*;
+
+use crate::{Error, Indexed, Result, SeedFingerprint, SeedMaterial};
+
+/// One of the seeds held by a wallet, identified by its ZIP 32 fingerprint.
+///
+/// A wallet may accumulate several seeds over its lifetime, for example by
+/// importing another wallet's seed, or, for zcashd wallets created before
+/// version 4.7.0, by keeping its original seed alongside the mnemonic added on
+/// upgrade. Each account names the seed it is derived from by fingerprint;
+/// see [`Account::seed_fingerprint`](crate::Account::seed_fingerprint).
+///
+/// The fingerprint is unknown only for a seed that is encrypted or held by a
+/// hardware device and whose fingerprint was not recorded.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Bip39Mnemonic, SeedMaterial, WalletSeed};
+/// # fn example() -> zewif::Result<()> {
+/// let phrase =
+///     "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+/// let mnemonic = Bip39Mnemonic::new(phrase, None);
+/// let mut seed = WalletSeed::new(SeedMaterial::Bip39Mnemonic(mnemonic))?;
+/// seed.set_label(Some("Savings".to_string()));
+///
+/// assert!(seed.matches(seed.fingerprint().unwrap()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct WalletSeed {
+    /// The index of this seed within the wallet's seeds.
+    index: usize,
+
+    /// The ZIP 32 fingerprint of the seed returned by
+    /// [`SeedMaterial::to_seed`], if known.
+    fingerprint: Option<SeedFingerprint>,
+
+    seed_material: SeedMaterial,
+
+    /// A user-defined label, which need not be unique.
+    label: Option<String>,
+}
+
+impl Indexed for WalletSeed {
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
+
+impl WalletSeed {
+    /// Creates a wallet seed, computing the fingerprint of the seed material
+    /// where the seed is available.
+    ///
+    /// # Errors
+    /// Returns an error if the seed is available but malformed; see
+    /// [`SeedMaterial::fingerprint`].
+    pub fn new(seed_material: SeedMaterial) -> Result<Self> {
+        let fingerprint = match seed_material.fingerprint() {
+            Ok(fingerprint) => Some(fingerprint),
+            Err(Error::SeedUnavailable { .. }) => None,
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            index: 0,
+            fingerprint,
+            seed_material,
+            label: None,
+        })
+    }
+
+    /// Returns the fingerprint of the seed, if known.
+    pub fn fingerprint(&self) -> Option<&SeedFingerprint> {
+        self.fingerprint.as_ref()
+    }
+
+    pub fn seed_material(&self) -> &SeedMaterial {
+        &self.seed_material
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    /// Returns the seed bytes having the given fingerprint, if this is the
+    /// seed it identifies and the seed is not encrypted.
+    ///
+    /// Besides the seed's own fingerprint, the recorded fingerprint of the
+    /// original seed of an upgraded zcashd wallet also matches, in which case
+    /// the original seed is returned.
+    pub fn seed_for(&self, fingerprint: &SeedFingerprint) -> Option<Vec<u8>> {
+        if Some(fingerprint) == self.fingerprint.as_ref() {
+            return self.seed_material.to_seed().ok();
+        }
+        match &self.seed_material {
+            SeedMaterial::LegacyZcashd(seed)
+                if seed.original_fingerprint() == Some(fingerprint) =>
+            {
+                Some(seed.original_seed().to_vec())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether the given fingerprint identifies this seed; see
+    /// [`WalletSeed::seed_for`].
+    pub fn matches(&self, fingerprint: &SeedFingerprint) -> bool {
+        Some(fingerprint) == self.fingerprint.as_ref()
+            || matches!(
+                &self.seed_material,
+                SeedMaterial::LegacyZcashd(seed) if seed.original_fingerprint() == Some(fingerprint)
+            )
+    }
+}
+
+impl From<WalletSeed> for Envelope {
+    fn from(value: WalletSeed) -> Self {
+        Envelope::new(value.index)
+            .add_type("WalletSeed")
+            .add_optional_assertion("fingerprint", value.fingerprint)
+            .add_assertion("seed_material", value.seed_material)
+            .add_optional_assertion("label", value.label)
+    }
+}
+
+impl TryFrom<Envelope> for WalletSeed {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("WalletSeed")?;
+        let index = envelope.extract_subject()?;
+        let fingerprint = envelope.try_optional_object_for_predicate("fingerprint")?;
+        let seed_material = envelope.try_object_for_predicate("seed_material")?;
+        let label = envelope.extract_optional_object_for_predicate("label")?;
+        Ok(Self {
+            index,
+            fingerprint,
+            seed_material,
+            label,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Blob, SeedFingerprint, SeedMaterial, ZcashdLegacySeed, test_envelope_roundtrip};
+
+    use super::WalletSeed;
+
+    impl crate::RandomInstance for WalletSeed {
+        fn random() -> Self {
+            Self {
+                index: 0,
+                fingerprint: SeedFingerprint::opt_random(),
+                seed_material: SeedMaterial::random(),
+                label: String::opt_random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(WalletSeed);
+
+    #[test]
+    fn test_zcashd_original_seed_matches() {
+        use crate::Bip39Mnemonic;
+
+        let original = [7u8; 32];
+        let original_fingerprint = SeedFingerprint::from_seed(&original).unwrap();
+        let mnemonic = Bip39Mnemonic::new(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            None,
+        );
+        let mut legacy = ZcashdLegacySeed::new(Blob::new(original), mnemonic, false);
+        legacy.set_original_fingerprint(Some(original_fingerprint));
+        let seed = WalletSeed::new(SeedMaterial::LegacyZcashd(legacy)).unwrap();
+
+        let fingerprint = *seed.fingerprint().unwrap();
+        assert_ne!(fingerprint, original_fingerprint);
+        assert!(seed.matches(&fingerprint));
+        assert!(seed.matches(&original_fingerprint));
+        assert_eq!(
+            seed.seed_for(&original_fingerprint),
+            Some(original.to_vec())
+        );
+        assert_eq!(
+            seed.seed_for(&fingerprint),
+            seed.seed_material().to_seed().ok()
+        );
+        assert!(!seed.matches(&SeedFingerprint::new([0u8; 32])));
+    }
+}
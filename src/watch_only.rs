@@ -0,0 +1,191 @@
+//! Watch-only exports, from which seeds and spending keys are elided.
+
+use std::{cell::RefCell, collections::HashSet};
+
+use bc_envelope::prelude::*;
+
+use crate::{Result, Zewif};
+
+/// Predicates whose objects are secret key material: seeds, the keys and
+/// authorities that can spend funds, and the uninspected values of orphaned
+/// raw records, which may be either.
+///
+/// This is the one list of secrets. Their objects are elided from a
+/// watch-only export and from the diagnostics attached to decoding errors,
+/// elements holding them are never skipped by lenient decoding, and the JSON
+/// fields of the same names are dropped by redaction. Seeds keep their
+/// fingerprints and labels, so that the accounts derived from them can still
+/// be matched to them.
+pub(crate) const SECRET_PREDICATES: &[&str] = &[
+    "seed_material",
+    "spending_key",
+    "orchard_spending_key",
+    "spend_authority",
+    "raw_value",
+];
+
+impl Zewif {
+    /// Encodes the Zewif as an envelope from which every seed and spending
+    /// key is elided, leaving viewing keys, addresses, and transactions
+    /// readable.
+    ///
+    /// Elision leaves the digest of each secret in its place, so the
+    /// watch-only envelope has the same digest as the full one, and a holder
+    /// of the full envelope can prove that the two are consistent. Decoding
+    /// it yields a Zewif whose seeds are [`SeedMaterial::Redacted`] and
+    /// whose spending keys and spend authorities are `None`.
+    ///
+    /// [`SeedMaterial::Redacted`]: crate::SeedMaterial::Redacted
+    pub fn to_watch_only_envelope(&self) -> Result<Envelope> {
+        Ok(elide_objects_of(
+            &Envelope::from(self.clone()),
+            SECRET_PREDICATES,
+        ))
+    }
+}
+
+/// Elides the object of every assertion in `envelope` whose predicate is one
+/// of `predicates`.
+pub(crate) fn elide_objects_of(envelope: &Envelope, predicates: &[&str]) -> Envelope {
+    let targets = RefCell::new(HashSet::new());
+    envelope.walk(false, (), &|envelope, _, _, _| {
+        if let Some(assertion) = envelope.as_assertion()
+            && assertion
+                .as_predicate()
+                .and_then(|predicate| predicate.extract_subject::<String>().ok())
+                .is_some_and(|predicate| predicates.contains(&predicate.as_str()))
+        {
+            targets
+                .borrow_mut()
+                .insert(assertion.as_object().unwrap().digest());
+        }
+        ((), false)
+    });
+    envelope.elide_removing_set(&targets.into_inner())
+}
+
+/// Decodes the optional object of `predicate`, which is a seed or spending
+/// key that is `None` if it was elided from a watch-only export.
+pub(crate) fn optional_spending_object<T>(
+    envelope: &Envelope,
+    predicate: &str,
+) -> bc_envelope::Result<Option<T>>
+where
+    T: TryFrom<Envelope, Error = bc_envelope::Error>,
+{
+    match envelope.optional_object_for_predicate(predicate)? {
+        Some(object) if object.is_elided() => Ok(None),
+        object => object.map(T::try_from).transpose(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, Address, Bip39Mnemonic, Blob, BlockHeight, Data, Network, OrphanedRecord,
+        ProtocolAddress, SeedMaterial, SeedStatus, WalletSeed, Zewif, ZewifWallet,
+        orchard::OrchardSpendingKey,
+        sapling::{self, SaplingExtendedSpendingKey},
+        sprout,
+        transparent::{self, TransparentSpendAuthority, TransparentSpendingKey},
+    };
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn zewif_with_secrets() -> Zewif {
+        let mut account = Account::new();
+        account.set_name("Spending");
+        account.set_orchard_spending_key(Some(OrchardSpendingKey::new([0xa1; 32])));
+
+        let mut sapling = sapling::Address::new("zs1watchonly".to_string());
+        sapling.set_spending_key(SaplingExtendedSpendingKey::new([0xa2; 169]));
+        account.add_address(Address::new(ProtocolAddress::Sapling(Box::new(sapling))));
+
+        let mut sprout = sprout::Address::new(
+            "zcwatchonly".to_string(),
+            Blob::new([1; 32]),
+            Blob::new([2; 32]),
+        );
+        sprout.set_spending_key(sprout::SpendingKey::new(Blob::new([0x0a; 32])));
+        account.add_address(Address::new(ProtocolAddress::Sprout(Box::new(sprout))));
+
+        let mut transparent = transparent::Address::new("t1watchonly");
+        transparent.set_spend_authority(TransparentSpendAuthority::SpendingKey(
+            TransparentSpendingKey::new([0xa4; 32]),
+        ));
+        account.add_address(Address::new(ProtocolAddress::Transparent(transparent)));
+
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_seed(
+            WalletSeed::new(SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(
+                PHRASE, None,
+            )))
+            .unwrap(),
+        );
+        wallet.add_account(account);
+        wallet.add_orphaned_record(OrphanedRecord::Raw {
+            key: Data::from_slice(b"\x03key"),
+            value: Data::from_slice(&[0xa5; 32]),
+            description: "zcashd key record".to_string(),
+        });
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        zewif
+    }
+
+    #[test]
+    fn test_watch_only_envelope() {
+        let zewif = zewif_with_secrets();
+        let full = Envelope::from(zewif.clone());
+        let watch_only = zewif.to_watch_only_envelope().unwrap();
+        assert!(watch_only.is_equivalent_to(&full));
+
+        // No secret appears anywhere in the encoding.
+        let cbor = watch_only.to_cbor_data();
+        let contains = |secret: &[u8]| cbor.windows(secret.len()).any(|window| window == secret);
+        assert!(contains(b"zs1watchonly"));
+        assert!(!contains(b"abandon"));
+        for byte in [0xa1, 0xa2, 0x0a, 0xa4, 0xa5] {
+            assert!(!contains(&[byte; 32]));
+        }
+
+        let decoded = Zewif::try_from(watch_only).unwrap();
+        let wallet = &decoded.wallets()[0];
+        let seed = &wallet.seeds()[0];
+        assert_eq!(seed.seed_material(), &SeedMaterial::Redacted);
+        assert_eq!(
+            seed.seed_material().validate().unwrap(),
+            SeedStatus::Redacted
+        );
+        assert_eq!(
+            seed.fingerprint(),
+            zewif.wallets()[0].seeds()[0].fingerprint()
+        );
+
+        assert_eq!(wallet.orphaned_data().records(), [OrphanedRecord::Redacted]);
+
+        let account = &wallet.accounts()[0];
+        assert_eq!(account.name(), "Spending");
+        assert!(account.orchard_spending_key().is_none());
+        let addresses = account.addresses();
+        assert_eq!(addresses.len(), 3);
+        for address in addresses {
+            match address.address() {
+                ProtocolAddress::Sapling(address) => assert!(address.spending_key().is_none()),
+                ProtocolAddress::Sprout(address) => assert!(address.spending_key().is_none()),
+                ProtocolAddress::Transparent(address) => {
+                    assert!(address.spend_authority().is_none())
+                }
+                ProtocolAddress::Unified(_) => unreachable!(),
+            }
+        }
+
+        // A decoded watch-only Zewif can be encoded again, still without secrets.
+        assert_eq!(
+            Zewif::try_from(Envelope::from(decoded.clone())).unwrap(),
+            decoded
+        );
+    }
+}
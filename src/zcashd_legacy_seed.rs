@@ -0,0 +1,172 @@
+use bc_envelope::prelude::*;
+
+use crate::{Bip39Mnemonic, Blob32, NoQuotesDebugOption, SeedFingerprint};
+
+/// The two seeds of a zcashd wallet created before version 4.7.0.
+///
+/// Before 4.7.0, zcashd generated a random 32-byte HD seed with no mnemonic.
+/// When such a wallet is upgraded, zcashd keeps that original seed and also
+/// generates a BIP-39 mnemonic, whose seed is used from then on. Both secrets
+/// are needed to recover every key of the wallet.
+///
+/// # Zcash Concept Relation
+/// - **Original seed**: The seed of all keys created before the upgrade,
+///   including Sapling keys derived with ZIP 32 (`m/32'/coin_type'/i'`) by
+///   `z_getnewaddress`.
+/// - **Mnemonic**: ZIP 32 derivation of unified accounts created after the
+///   upgrade (`z_getnewaccount`) applies to the BIP-39 seed of this phrase,
+///   which is what [`SeedMaterial::to_seed`](crate::SeedMaterial::to_seed)
+///   returns for this variant.
+/// - **Adjusted entropy**: zcashd may increment the mnemonic's entropy until
+///   the phrase yields valid keys, in which case the phrase cannot be
+///   reproduced from the random entropy alone.
+///
+/// Accounts refer to the seed they are derived from by its fingerprint; see
+/// [`Account::seed_fingerprint`](crate::Account::seed_fingerprint).
+///
+/// # Examples
+/// ```
+/// # use zewif::{Bip39Mnemonic, Blob, ZcashdLegacySeed};
+/// let phrase =
+///     "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+/// let mnemonic = Bip39Mnemonic::new(phrase, None);
+/// let seed = ZcashdLegacySeed::new(Blob::new([7u8; 32]), mnemonic, false);
+///
+/// assert_eq!(
+///     format!("{:?}", seed),
+///     "ZcashdLegacySeed { original_seed: <32 bytes>, original_fingerprint: None, \
+///      mnemonic: Bip39Mnemonic(<12 words>), entropy_was_adjusted: false }"
+/// );
+/// ```
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZcashdLegacySeed {
+    original_seed: Blob32,
+    original_fingerprint: Option<SeedFingerprint>,
+    mnemonic: Bip39Mnemonic,
+    entropy_was_adjusted: bool,
+}
+
+/// Prints the redacted forms of both seeds.
+impl std::fmt::Debug for ZcashdLegacySeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ZcashdLegacySeed")
+            .field(
+                "original_seed",
+                &format_args!("<{} bytes>", self.original_seed.len()),
+            )
+            .field(
+                "original_fingerprint",
+                &NoQuotesDebugOption(&self.original_fingerprint.map(|f| f.short_hex())),
+            )
+            .field("mnemonic", &self.mnemonic)
+            .field("entropy_was_adjusted", &self.entropy_was_adjusted)
+            .finish()
+    }
+}
+
+impl ZcashdLegacySeed {
+    pub fn new(original_seed: Blob32, mnemonic: Bip39Mnemonic, entropy_was_adjusted: bool) -> Self {
+        Self {
+            original_seed,
+            original_fingerprint: None,
+            mnemonic,
+            entropy_was_adjusted,
+        }
+    }
+
+    /// Returns the random seed generated before the wallet was upgraded.
+    pub fn original_seed(&self) -> &Blob32 {
+        &self.original_seed
+    }
+
+    /// Returns the ZIP 32 fingerprint of the original seed, if known.
+    pub fn original_fingerprint(&self) -> Option<&SeedFingerprint> {
+        self.original_fingerprint.as_ref()
+    }
+
+    pub fn set_original_fingerprint(&mut self, fingerprint: Option<SeedFingerprint>) {
+        self.original_fingerprint = fingerprint;
+    }
+
+    /// Computes the ZIP 32 fingerprint of the original seed, which an
+    /// importer can record with
+    /// [`ZcashdLegacySeed::set_original_fingerprint`].
+    pub fn compute_original_fingerprint(&self) -> SeedFingerprint {
+        SeedFingerprint::from_seed(self.original_seed.as_slice())
+            .expect("a 32-byte seed has a fingerprint")
+    }
+
+    /// Returns the mnemonic generated when the wallet was upgraded.
+    pub fn mnemonic(&self) -> &Bip39Mnemonic {
+        &self.mnemonic
+    }
+
+    /// Returns whether zcashd adjusted the mnemonic's entropy to obtain a valid
+    /// phrase.
+    pub fn entropy_was_adjusted(&self) -> bool {
+        self.entropy_was_adjusted
+    }
+}
+
+impl From<ZcashdLegacySeed> for Envelope {
+    fn from(value: ZcashdLegacySeed) -> Self {
+        Envelope::new(value.original_seed)
+            .add_type("ZcashdLegacySeed")
+            .add_optional_assertion("original_fingerprint", value.original_fingerprint)
+            .add_assertion("mnemonic", value.mnemonic)
+            .add_assertion("entropy_was_adjusted", value.entropy_was_adjusted)
+    }
+}
+
+impl TryFrom<Envelope> for ZcashdLegacySeed {
+    type Error = bc_envelope::Error;
+
+    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
+        envelope.check_type("ZcashdLegacySeed")?;
+        let original_seed = envelope.extract_subject()?;
+        let original_fingerprint =
+            envelope.try_optional_object_for_predicate("original_fingerprint")?;
+        let mnemonic = envelope.try_object_for_predicate("mnemonic")?;
+        let entropy_was_adjusted = envelope.extract_object_for_predicate("entropy_was_adjusted")?;
+        Ok(Self {
+            original_seed,
+            original_fingerprint,
+            mnemonic,
+            entropy_was_adjusted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Bip39Mnemonic, Blob, SeedFingerprint, test_envelope_roundtrip};
+
+    use super::ZcashdLegacySeed;
+
+    impl crate::RandomInstance for ZcashdLegacySeed {
+        fn random() -> Self {
+            Self {
+                original_seed: Blob::random(),
+                original_fingerprint: SeedFingerprint::opt_random(),
+                mnemonic: Bip39Mnemonic::random(),
+                entropy_was_adjusted: rand::random(),
+            }
+        }
+    }
+
+    test_envelope_roundtrip!(ZcashdLegacySeed);
+
+    #[test]
+    fn test_compute_original_fingerprint() {
+        let mnemonic = Bip39Mnemonic::new(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            None,
+        );
+        let mut seed = ZcashdLegacySeed::new(Blob::new([7u8; 32]), mnemonic, false);
+        let fingerprint = seed.compute_original_fingerprint();
+        assert_eq!(fingerprint, SeedFingerprint::from_seed(&[7u8; 32]).unwrap());
+        seed.set_original_fingerprint(Some(fingerprint));
+        assert_eq!(seed.original_fingerprint(), Some(&fingerprint));
+    }
+}
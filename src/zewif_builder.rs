@@ -0,0 +1,374 @@
+use crate::{
+    Account, AccountDerivation, Address, AddressConflictPolicy, BlockHeight, Error, Network,
+    Result, SeedFingerprint, Severity, Transaction, TxId, ValidationOptions, WalletProvenance,
+    WalletSeed, Zewif, ZewifWallet,
+};
+
+/// Assembles a [`Zewif`] from its wallets, accounts, and transactions,
+/// checking the result as a whole when it is built.
+///
+/// Building a Zewif with `new()` and setters makes it easy to leave out a
+/// linkage, such as the seed an account claims to derive from. The builders
+/// collect everything first, and [`ZewifBuilder::build`] then reports every
+/// problem found at once: wallets without a network, conflicting copies of a
+/// transaction, addresses held by more than one account if so configured,
+/// and the errors found by [`Zewif::validate`] with its default checks.
+///
+/// # Examples
+/// ```
+/// # use zewif::{
+/// #     Address, Bip39Mnemonic, BlockHeight, Network, ProtocolAddress, SeedMaterial, WalletSeed,
+/// #     ZewifBuilder, transparent,
+/// # };
+/// let seed = WalletSeed::new(SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(
+///     "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+///     None,
+/// )))?;
+/// let fingerprint = *seed.fingerprint().unwrap();
+/// let address = Address::new(ProtocolAddress::Transparent(transparent::Address::new("t1abc")));
+/// let zewif = ZewifBuilder::new(BlockHeight::from(2_000_000u32))
+///     .wallet(|wallet| {
+///         wallet.network(Network::Main).seed(seed).account(|account| {
+///             account
+///                 .name("Savings")
+///                 .seed_fingerprint(fingerprint)
+///                 .address(address)
+///         })
+///     })
+///     .build()?;
+/// assert_eq!(zewif.wallets()[0].accounts()[0].name(), "Savings");
+/// # Ok::<(), zewif::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct ZewifBuilder {
+    export_height: BlockHeight,
+    wallets: Vec<WalletBuilder>,
+    transactions: Vec<Transaction>,
+    on_address_conflict: AddressConflictPolicy,
+}
+
+impl ZewifBuilder {
+    pub fn new(export_height: BlockHeight) -> Self {
+        Self {
+            export_height,
+            wallets: Vec::new(),
+            transactions: Vec::new(),
+            on_address_conflict: AddressConflictPolicy::default(),
+        }
+    }
+
+    /// Selects what [`ZewifBuilder::build`] does with an address held by more
+    /// than one account. Under [`AddressConflictPolicy::Reject`] the build
+    /// fails; otherwise the duplicate is left to be reported as a warning by
+    /// [`Zewif::validate`].
+    pub fn on_address_conflict(mut self, on_conflict: AddressConflictPolicy) -> Self {
+        self.on_address_conflict = on_conflict;
+        self
+    }
+
+    /// Adds a wallet configured by `build`.
+    pub fn wallet(mut self, build: impl FnOnce(WalletBuilder) -> WalletBuilder) -> Self {
+        self.wallets.push(build(WalletBuilder::new()));
+        self
+    }
+
+    /// Adds a transaction to the shared store. Adding identical copies has no
+    /// effect; differing copies are reported by [`ZewifBuilder::build`].
+    pub fn transaction(mut self, transaction: Transaction) -> Self {
+        self.transactions.push(transaction);
+        self
+    }
+
+    /// Builds the Zewif.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidZewif`] listing every problem found: each wallet
+    /// without a network, each transaction conflicting with another copy, each
+    /// duplicate address under [`AddressConflictPolicy::Reject`], and each
+    /// error-level finding of [`Zewif::validate`]. Warnings do not
+    /// prevent the build.
+    pub fn build(self) -> Result<Zewif> {
+        let mut problems = Vec::new();
+        let mut zewif = Zewif::new(self.export_height);
+        for (index, wallet) in self.wallets.into_iter().enumerate() {
+            match wallet.build() {
+                Ok(wallet) => zewif.add_wallet(wallet),
+                Err(error) => problems.push(format!("Wallet[{}]: {}", index, error)),
+            }
+        }
+        for transaction in self.transactions {
+            if let Err(error) = zewif.add_transaction(transaction.txid(), transaction) {
+                problems.push(error.to_string());
+            }
+        }
+        if self.on_address_conflict == AddressConflictPolicy::Reject {
+            let duplicates = zewif.find_duplicate_addresses().into_iter();
+            problems
+                .extend(duplicates.map(|duplicate| Error::DuplicateAddress(duplicate).to_string()));
+        }
+        let report = zewif.validate(ValidationOptions::new());
+        problems.extend(
+            report
+                .with_severity(Severity::Error)
+                .map(ToString::to_string),
+        );
+
+        if problems.is_empty() {
+            Ok(zewif)
+        } else {
+            Err(Error::InvalidZewif { problems })
+        }
+    }
+}
+
+/// Assembles a [`ZewifWallet`]; see [`ZewifBuilder`].
+#[derive(Debug, Clone, Default)]
+pub struct WalletBuilder {
+    network: Option<Network>,
+    birthday_height: Option<BlockHeight>,
+    provenance: Option<WalletProvenance>,
+    seeds: Vec<WalletSeed>,
+    accounts: Vec<Account>,
+}
+
+impl WalletBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn network(mut self, network: Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    pub fn birthday_height(mut self, birthday_height: BlockHeight) -> Self {
+        self.birthday_height = Some(birthday_height);
+        self
+    }
+
+    pub fn provenance(mut self, provenance: WalletProvenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    pub fn seed(mut self, seed: WalletSeed) -> Self {
+        self.seeds.push(seed);
+        self
+    }
+
+    /// Adds an account configured by `build`.
+    pub fn account(mut self, build: impl FnOnce(AccountBuilder) -> AccountBuilder) -> Self {
+        self.accounts.push(build(AccountBuilder::new()).build());
+        self
+    }
+
+    /// Builds the wallet. Its contents are only checked when it is built as
+    /// part of a [`ZewifBuilder`].
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidNetwork`] if no network was set.
+    pub fn build(self) -> Result<ZewifWallet> {
+        let network = self.network.ok_or_else(|| Error::InvalidNetwork {
+            network: None,
+            reason: "no network was set".to_string(),
+        })?;
+        let mut wallet = ZewifWallet::new(network);
+        wallet.set_birthday_height(self.birthday_height);
+        wallet.set_provenance(self.provenance);
+        self.seeds
+            .into_iter()
+            .for_each(|seed| wallet.add_seed(seed));
+        self.accounts
+            .into_iter()
+            .for_each(|account| wallet.add_account(account));
+        Ok(wallet)
+    }
+}
+
+/// Assembles an [`Account`]; see [`ZewifBuilder`].
+#[derive(Debug, Clone, Default)]
+pub struct AccountBuilder {
+    account: Account,
+}
+
+impl AccountBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.account.set_name(name);
+        self
+    }
+
+    pub fn birthday_height(mut self, birthday_height: BlockHeight) -> Self {
+        self.account.set_birthday_height(Some(birthday_height));
+        self
+    }
+
+    pub fn zip32_account_id(mut self, id: u32) -> Self {
+        self.account.set_zip32_account_id(id);
+        self
+    }
+
+    /// Names the seed the account derives from, which the wallet must hold.
+    pub fn seed_fingerprint(mut self, fingerprint: SeedFingerprint) -> Self {
+        self.account.set_seed_fingerprint(Some(fingerprint));
+        self
+    }
+
+    pub fn derivation(mut self, derivation: AccountDerivation) -> Self {
+        self.account.set_derivation(derivation);
+        self
+    }
+
+    pub fn address(mut self, address: Address) -> Self {
+        self.account.add_address(address);
+        self
+    }
+
+    pub fn relevant_transaction(mut self, txid: TxId) -> Self {
+        self.account.add_relevant_transaction(txid);
+        self
+    }
+
+    /// Applies `configure` to the account, for settings without a builder
+    /// method, such as received notes.
+    pub fn with(mut self, configure: impl FnOnce(&mut Account)) -> Self {
+        configure(&mut self.account);
+        self
+    }
+
+    pub fn build(self) -> Account {
+        self.account
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Address, AddressConflictPolicy, Bip39Mnemonic, BlockHeight, Error, Network,
+        ProtocolAddress, SeedFingerprint, SeedMaterial, Transaction, TxId, WalletSeed, Zewif,
+        sapling, transparent,
+    };
+
+    use super::ZewifBuilder;
+
+    fn transparent_address(string: &str) -> Address {
+        Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+            string,
+        )))
+    }
+
+    #[test]
+    fn test_build_and_roundtrip() {
+        let seed = WalletSeed::new(SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            None,
+        )))
+        .unwrap();
+        let fingerprint = *seed.fingerprint().unwrap();
+        let txid = TxId::from_bytes([1; 32]);
+
+        let zewif = ZewifBuilder::new(BlockHeight::from(2_000_000))
+            .wallet(|wallet| {
+                wallet
+                    .network(Network::Main)
+                    .birthday_height(BlockHeight::from(1_000_000))
+                    .seed(seed)
+                    .account(|account| {
+                        account
+                            .name("Savings")
+                            .seed_fingerprint(fingerprint)
+                            .zip32_account_id(0)
+                            .address(transparent_address("t1savings"))
+                            .relevant_transaction(txid)
+                    })
+                    .account(|account| {
+                        account
+                            .name("Spending")
+                            .seed_fingerprint(fingerprint)
+                            .zip32_account_id(1)
+                            .address(Address::new(ProtocolAddress::Sapling(Box::new(
+                                sapling::Address::new("zs1spending".to_string()),
+                            ))))
+                    })
+            })
+            .transaction(Transaction::new(txid))
+            .build()
+            .unwrap();
+
+        let wallet = &zewif.wallets()[0];
+        assert_eq!(wallet.accounts().len(), 2);
+        assert_eq!(wallet.accounts()[1].name(), "Spending");
+        assert!(zewif.transaction(&txid).is_some());
+
+        let decoded = Zewif::try_from(Envelope::from(zewif.clone())).unwrap();
+        assert_eq!(decoded, zewif);
+    }
+
+    #[test]
+    fn test_build_reports_every_problem() {
+        let txid = TxId::from_bytes([1; 32]);
+        let mut conflicting = Transaction::new(txid);
+        conflicting.set_mined_height(BlockHeight::from(1_000));
+
+        let result = ZewifBuilder::new(BlockHeight::from(2_000_000))
+            .wallet(|wallet| wallet.account(|account| account.name("Lost")))
+            .wallet(|wallet| {
+                wallet.network(Network::Main).account(|account| {
+                    account
+                        .name("Orphan")
+                        .seed_fingerprint(SeedFingerprint::new([7; 32]))
+                })
+            })
+            .transaction(Transaction::new(txid))
+            .transaction(conflicting)
+            .build();
+        let Err(Error::InvalidZewif { problems }) = result else {
+            panic!("expected an invalid Zewif");
+        };
+        assert_eq!(problems.len(), 3);
+        assert_eq!(
+            problems[0],
+            "Wallet[0]: Invalid network: no network was set"
+        );
+        assert!(problems[1].contains("conflicts with the copy already in the Zewif"));
+        assert!(problems[2].starts_with("[unknown-seed-fingerprint]"));
+    }
+
+    #[test]
+    fn test_build_rejects_duplicate_address() {
+        let builder = ZewifBuilder::new(BlockHeight::from(2_000_000)).wallet(|wallet| {
+            wallet
+                .network(Network::Main)
+                .account(|account| {
+                    account
+                        .name("Savings")
+                        .address(transparent_address("t1shared"))
+                })
+                .account(|account| {
+                    account
+                        .name("Spending")
+                        .address(transparent_address("t1shared"))
+                })
+        });
+        let zewif = builder.clone().build().unwrap();
+        assert_eq!(zewif.find_duplicate_addresses().len(), 1);
+
+        let result = builder
+            .on_address_conflict(AddressConflictPolicy::Reject)
+            .build();
+        let Err(Error::InvalidZewif { problems }) = result else {
+            panic!("expected an invalid Zewif");
+        };
+        assert_eq!(
+            problems,
+            [
+                "Duplicate address: address t1shared is held by account 0 of wallet 0, account 1 of wallet 0"
+            ]
+        );
+    }
+}
@@ -1,10 +1,13 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, cell::RefCell, collections::HashSet};
 
-use bc_components::{ARID, SymmetricKey};
+use bc_components::{ARID, Encrypter, PrivateKeys, PublicKeys, Salt, SymmetricKey};
 use bc_crypto::pbkdf2_hmac_sha256;
 use bc_envelope::prelude::*;
 
-use crate::error::{Error, Result};
+use crate::{
+    Compression, KdfParams,
+    error::{Error, Result},
+};
 
 #[derive(Debug, Clone)]
 pub struct ZewifEnvelope {
@@ -12,12 +15,36 @@ pub struct ZewifEnvelope {
     envelope: Envelope,
 }
 
+/// Returns the envelope wrapped by a signature, such as one added by
+/// [`Zewif::sign`](crate::Zewif::sign), or `envelope` itself if it is not
+/// signed. The signature is not verified.
+pub(crate) fn unsigned(envelope: &Envelope) -> Envelope {
+    if is_signed(envelope) {
+        envelope.try_unwrap().expect("signed envelopes are wrapped")
+    } else {
+        envelope.clone()
+    }
+}
+
+fn is_signed(envelope: &Envelope) -> bool {
+    envelope.subject().is_wrapped()
+        && !envelope
+            .assertions_with_predicate(known_values::SIGNED)
+            .is_empty()
+}
+
 impl ZewifEnvelope {
+    /// Accepts a Zewif envelope, or one signed by
+    /// [`Zewif::sign`](crate::Zewif::sign). A signed envelope is treated as
+    /// plain content: compressing or encrypting it compresses or encrypts it
+    /// along with its signature, which is restored by uncompressing or
+    /// decrypting it.
     pub fn new(envelope: Envelope) -> Result<Self> {
-        if !envelope.has_type("Zewif") {
+        let content = unsigned(&envelope);
+        if !content.has_type("Zewif") {
             return Err(Error::NotZewifEnvelope);
         }
-        let id = envelope.extract_subject().map_err(|e| Error::Context {
+        let id = content.extract_subject().map_err(|e| Error::Context {
             message: Cow::Borrowed("ID"),
             source: Box::new(e),
         })?;
@@ -26,6 +53,12 @@ impl ZewifEnvelope {
 
     pub fn id(&self) -> ARID { self.id }
 
+    /// Returns whether the envelope is signed, as opposed to holding signed
+    /// content that is compressed or encrypted.
+    pub fn is_signed(&self) -> bool {
+        is_signed(&self.envelope)
+    }
+
     pub fn digest(&self) -> Digest { self.envelope.digest() }
 
     pub fn envelope(&self) -> &Envelope { &self.envelope }
@@ -43,16 +76,33 @@ impl ZewifEnvelope {
             .is_some_and(|content| content.is_compressed())
     }
 
+    /// Returns whether the content is encrypted, either with a symmetric key
+    /// or to recipients.
     pub fn is_encrypted(&self) -> bool {
         self.obscured_content()
-            .is_some_and(|content| content.is_encrypted())
+            .is_some_and(|content| content.subject().is_encrypted())
     }
 
-    pub fn can_compress(&self) -> bool { !self.is_obscured() }
+    /// Returns whether some elements of the envelope, rather than the
+    /// envelope as a whole, are compressed; see
+    /// [`Compression::LargeLeavesOver`].
+    pub fn has_compressed_elements(&self) -> bool {
+        !self.is_obscured()
+            && !self
+                .envelope
+                .nodes_matching(None, &[ObscureType::Compressed])
+                .is_empty()
+    }
+
+    pub fn can_compress(&self) -> bool {
+        !self.is_obscured() && !self.has_compressed_elements()
+    }
 
     pub fn can_encrypt(&self) -> bool { !self.is_encrypted() }
 
-    pub fn can_uncompress(&self) -> bool { self.is_compressed() }
+    pub fn can_uncompress(&self) -> bool {
+        self.is_compressed() || self.has_compressed_elements()
+    }
 
     pub fn can_decrypt(&self) -> bool { self.is_encrypted() }
 
@@ -68,13 +118,51 @@ impl ZewifEnvelope {
         Ok(())
     }
 
+    /// Compresses the envelope as selected; [`Compression::None`] leaves it
+    /// unchanged.
+    pub fn compress_with(&mut self, compression: Compression) -> Result<()> {
+        match compression {
+            Compression::None => Ok(()),
+            Compression::Whole => self.compress(),
+            Compression::LargeLeavesOver(threshold) => self.compress_large_leaves(threshold),
+        }
+    }
+
+    /// Compresses each leaf whose CBOR encoding is longer than `threshold`
+    /// bytes, other than the Zewif's ID, leaving the rest of the envelope
+    /// readable.
+    pub fn compress_large_leaves(&mut self, threshold: usize) -> Result<()> {
+        if !self.can_compress() {
+            return Err(Error::AlreadyCompressedOrEncrypted);
+        }
+        let id = self.envelope.subject().digest();
+        let targets = RefCell::new(HashSet::new());
+        self.envelope.walk(false, (), &|envelope, _, _, _| {
+            if envelope.is_leaf()
+                && envelope.digest() != id
+                && envelope.to_cbor_data().len() > threshold
+            {
+                targets.borrow_mut().insert(envelope.digest());
+            }
+            ((), false)
+        });
+        self.envelope = self
+            .envelope
+            .elide_removing_set_with_action(&targets.into_inner(), &ObscureAction::Compress);
+        Ok(())
+    }
+
+    /// Reverses [`ZewifEnvelope::compress`] or
+    /// [`ZewifEnvelope::compress_large_leaves`].
     pub fn uncompress(&mut self) -> Result<()> {
-        if self.can_uncompress() {
+        if self.is_compressed() {
             self.envelope = self
                 .envelope
                 .object_for_predicate("content")?
                 .decompress()?
                 .try_unwrap()?;
+        } else if self.has_compressed_elements() {
+            self.envelope = self.envelope.walk_decompress(None);
         } else {
             return Err(Error::NotCompressed);
         }
@@ -110,12 +198,105 @@ impl ZewifEnvelope {
         }
         Ok(())
     }
+
+    /// Encrypts the envelope with a random content key, which is in turn
+    /// encrypted to each of `recipients`, so that any one of them can decrypt
+    /// it.
+    ///
+    /// # Errors
+    /// Returns [`Error::NoRecipients`] if `recipients` is empty, as no one
+    /// could decrypt the result.
+    pub fn encrypt_to_recipients(&mut self, recipients: &[PublicKeys]) -> Result<()> {
+        if !self.can_encrypt() {
+            return Err(Error::AlreadyEncrypted);
+        }
+        if recipients.is_empty() {
+            return Err(Error::NoRecipients);
+        }
+        let recipients: Vec<&dyn Encrypter> = recipients
+            .iter()
+            .map(|recipient| recipient as &dyn Encrypter)
+            .collect();
+        let content = self
+            .envelope
+            .wrap()
+            .encrypt_subject_to_recipients(&recipients)?;
+        self.envelope = Envelope::new(self.id)
+            .add_type("Zewif")
+            .add_assertion("content", content);
+        Ok(())
+    }
+
+    /// Decrypts an envelope encrypted with
+    /// [`ZewifEnvelope::encrypt_to_recipients`].
+    ///
+    /// # Errors
+    /// Returns [`Error::NotARecipient`] if the content key was not encrypted
+    /// to `private_keys`.
+    pub fn decrypt_as_recipient(&mut self, private_keys: &PrivateKeys) -> Result<()> {
+        if !self.can_decrypt() {
+            return Err(Error::NotEncrypted);
+        }
+        self.envelope = self
+            .envelope
+            .object_for_predicate("content")?
+            .decrypt_to_recipient(private_keys)
+            .map_err(|e| match e {
+                bc_envelope::Error::UnknownRecipient => Error::NotARecipient,
+                e => e.into(),
+            })?;
+        Ok(())
+    }
+
+    /// Encrypts the envelope with a key derived from `password` by Argon2id,
+    /// recording `params` and a random salt in the outer envelope.
+    pub fn encrypt_with_password(
+        &mut self,
+        password: impl AsRef<[u8]>,
+        params: KdfParams,
+    ) -> Result<()> {
+        if !self.can_encrypt() {
+            return Err(Error::AlreadyEncrypted);
+        }
+        let salt = Salt::new_with_len(16).expect("16 bytes is a valid salt length");
+        self.encrypt(&params.derive_key(password, &salt)?)?;
+        self.envelope = self
+            .envelope
+            .add_assertion("kdf", params)
+            .add_assertion("kdf_salt", salt);
+        Ok(())
+    }
+
+    /// Decrypts an envelope encrypted with
+    /// [`ZewifEnvelope::encrypt_with_password`].
+    ///
+    /// # Errors
+    /// Returns [`Error::WrongPassword`] if the content cannot be decrypted
+    /// with the key derived from `password`, which is also the case if the
+    /// ciphertext has been altered, and [`Error::InvalidKdfParams`] if the
+    /// recorded parameters exceed the limits of [`KdfParams`], before any key
+    /// is derived.
+    pub fn decrypt_with_password(&mut self, password: impl AsRef<[u8]>) -> Result<()> {
+        if !self.can_decrypt() {
+            return Err(Error::NotEncrypted);
+        }
+        let params: KdfParams = self
+            .envelope
+            .try_object_for_predicate("kdf")
+            .map_err(|_| Error::NotPasswordEncrypted)?;
+        let salt: Salt = self
+            .envelope
+            .extract_object_for_predicate("kdf_salt")
+            .map_err(|_| Error::NotPasswordEncrypted)?;
+        let key = params.derive_key(password, &salt)?;
+        self.decrypt(&key).map_err(|_| Error::WrongPassword)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{RandomInstance, Zewif};
+    use crate::{EncodeOptions, KdfParams, RandomInstance, Zewif};
 
     #[test]
     fn test_new_envelope() {
@@ -218,4 +399,268 @@ mod tests {
         // Check that the reconstructed Zewif instance matches the original
         assert_eq!(zewif, zewif2);
     }
+
+    fn compressed(zewif: &Zewif, compression: Compression) -> Envelope {
+        let mut options = EncodeOptions::new();
+        options.set_compression(compression);
+        zewif.to_envelope_with(&options).unwrap()
+    }
+
+    #[test]
+    fn test_compression_modes() {
+        let zewif = Zewif::random();
+        let modes = [
+            Compression::None,
+            Compression::Whole,
+            Compression::LargeLeavesOver(64),
+        ];
+        for compression in modes {
+            let envelope = compressed(&zewif, compression);
+            if compression != Compression::Whole {
+                assert_eq!(envelope.digest(), Envelope::from(zewif.clone()).digest());
+            }
+            assert_eq!(Zewif::try_from(envelope).unwrap(), zewif);
+        }
+
+        let envelope = |compression| ZewifEnvelope::new(compressed(&zewif, compression)).unwrap();
+        let leaves = envelope(Compression::LargeLeavesOver(64));
+        assert!(leaves.has_compressed_elements());
+        assert!(!leaves.is_compressed());
+        assert!(leaves.can_uncompress());
+        assert!(leaves.can_encrypt());
+
+        // Compressing an envelope that is already compressed in either way,
+        // or encrypted, fails.
+        let key = ZewifEnvelope::derive_encryption_key("password");
+        let mut encrypted = envelope(Compression::None);
+        encrypted.encrypt(&key).unwrap();
+        for mut ze in [envelope(Compression::Whole), leaves.clone(), encrypted] {
+            for compression in [Compression::Whole, Compression::LargeLeavesOver(64)] {
+                assert!(matches!(
+                    ze.compress_with(compression),
+                    Err(Error::AlreadyCompressedOrEncrypted)
+                ));
+            }
+            assert!(ze.compress_with(Compression::None).is_ok());
+        }
+
+        let mut uncompressed = envelope(Compression::None);
+        assert!(matches!(
+            uncompressed.uncompress(),
+            Err(Error::NotCompressed)
+        ));
+        let mut ze = leaves;
+        ze.uncompress().unwrap();
+        assert_eq!(ze.envelope(), &Envelope::from(zewif));
+    }
+
+    #[test]
+    fn test_leaf_compression_size() {
+        use crate::{BlockHeight, Data, Transaction, TxId};
+
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000u32));
+        for i in 0..4u8 {
+            let txid = TxId::from_bytes([i; 32]);
+            let mut transaction = Transaction::new(txid);
+            transaction.set_raw(Data::from_vec(vec![i; 4096]));
+            zewif.add_transaction(txid, transaction).unwrap();
+        }
+
+        let size = |envelope: &Envelope| envelope.to_cbor_data().len();
+        let plain = compressed(&zewif, Compression::None);
+        let whole = compressed(&zewif, Compression::Whole);
+        let leaves = compressed(&zewif, Compression::LargeLeavesOver(256));
+        assert!(size(&whole) < size(&plain) / 10);
+        assert!(size(&leaves) < size(&plain) / 10);
+
+        // Only the leaf-compressed envelope can be read without decompressing
+        // it.
+        let export_height = |envelope: &Envelope| {
+            envelope
+                .extract_object_for_predicate::<BlockHeight>("export_height")
+                .ok()
+        };
+        assert_eq!(export_height(&leaves), Some(zewif.export_height()));
+        assert_eq!(leaves.objects_for_predicate("transaction").len(), 4);
+        assert_eq!(export_height(&whole), None);
+        assert!(whole.objects_for_predicate("transaction").is_empty());
+    }
+
+    #[test]
+    fn test_password_encryption() {
+        // Cheap parameters keep the test fast.
+        let params = KdfParams::new(64, 1, 1);
+        let zewif = Zewif::random();
+        let envelope = zewif
+            .encrypt_with_password("correct horse", params)
+            .unwrap();
+
+        let ze = ZewifEnvelope::new(envelope.clone()).unwrap();
+        assert!(ze.is_encrypted());
+        assert_eq!(ze.id(), zewif.id());
+        assert_eq!(
+            Zewif::decrypt_with_password(&envelope, "correct horse").unwrap(),
+            zewif
+        );
+
+        assert!(matches!(
+            Zewif::decrypt_with_password(&envelope, "battery staple"),
+            Err(Error::WrongPassword)
+        ));
+
+        // Encrypting twice, or decrypting an unencrypted or key-encrypted
+        // Zewif, fails.
+        let mut encrypted = ze.clone();
+        assert!(matches!(
+            encrypted.encrypt_with_password("again", params),
+            Err(Error::AlreadyEncrypted)
+        ));
+        assert!(matches!(
+            Zewif::decrypt_with_password(&Envelope::from(zewif.clone()), "x"),
+            Err(Error::NotEncrypted)
+        ));
+        let mut key_encrypted = ZewifEnvelope::new(Envelope::from(zewif.clone())).unwrap();
+        key_encrypted
+            .encrypt(&ZewifEnvelope::derive_encryption_key("x"))
+            .unwrap();
+        assert!(matches!(
+            key_encrypted.decrypt_with_password("x"),
+            Err(Error::NotPasswordEncrypted)
+        ));
+
+        // Invalid parameters are rejected rather than panicking.
+        assert!(matches!(
+            zewif.encrypt_with_password("x", KdfParams::new(64, 0, 1)),
+            Err(Error::InvalidKdfParams { .. })
+        ));
+
+        // A file asking for more memory than the limit is rejected before
+        // any key is derived.
+        let kdf = envelope.assertion_with_predicate("kdf").unwrap();
+        let crafted = envelope
+            .remove_assertion(kdf)
+            .add_assertion("kdf", KdfParams::new(u32::MAX, u32::MAX, 1));
+        assert!(matches!(
+            Zewif::decrypt_with_password(&crafted, "correct horse"),
+            Err(Error::InvalidKdfParams { .. })
+        ));
+    }
+
+    /// A file whose parameters are just over one of the limits is rejected
+    /// by the limit check, not by Argon2, which would accept them.
+    #[test]
+    fn test_password_kdf_limits() {
+        let zewif = Zewif::random();
+        let envelope = zewif
+            .encrypt_with_password("password", KdfParams::new(64, 1, 1))
+            .unwrap();
+        let kdf = envelope.assertion_with_predicate("kdf").unwrap();
+        let over_limits = [
+            (
+                "memory_kib",
+                KdfParams::new(KdfParams::MAX_MEMORY_KIB + 1, 1, 1),
+            ),
+            (
+                "iterations",
+                KdfParams::new(64, KdfParams::MAX_ITERATIONS + 1, 1),
+            ),
+            (
+                "parallelism",
+                KdfParams::new(128, 1, KdfParams::MAX_PARALLELISM + 1),
+            ),
+        ];
+        for (name, params) in over_limits {
+            let crafted = envelope
+                .remove_assertion(kdf.clone())
+                .add_assertion("kdf", params);
+            let error = Zewif::decrypt_with_password(&crafted, "password").unwrap_err();
+            assert!(
+                matches!(
+                    &error,
+                    Error::InvalidKdfParams { parameter, reason }
+                        if *parameter == name && reason.contains("more than the limit")
+                ),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_password_encryption_tampered() {
+        let params = KdfParams::new(64, 1, 1);
+        let zewif = Zewif::random();
+        let envelope = zewif.encrypt_with_password("password", params).unwrap();
+
+        // Flip a byte in the middle of the ciphertext.
+        let content = envelope.object_for_predicate("content").unwrap().subject();
+        let EnvelopeCase::Encrypted(message) = content.case() else {
+            panic!("content is not encrypted");
+        };
+        let ciphertext = message.ciphertext().to_vec();
+        let mut data = envelope.to_cbor_data();
+        let offset = data
+            .windows(ciphertext.len())
+            .position(|window| window == ciphertext)
+            .unwrap()
+            + ciphertext.len() / 2;
+        data[offset] ^= 0x01;
+        let tampered = Envelope::try_from_cbor_data(data).unwrap();
+
+        assert!(matches!(
+            Zewif::decrypt_with_password(&tampered, "password"),
+            Err(Error::WrongPassword)
+        ));
+    }
+
+    #[test]
+    fn test_recipient_encryption() {
+        use bc_components::keypair;
+
+        use crate::ExportMetadata;
+
+        let (alice_private, alice_public) = keypair();
+        let (bob_private, bob_public) = keypair();
+        let (eve_private, _) = keypair();
+
+        let zewif = Zewif::random();
+        let envelope = zewif
+            .encrypt_to_recipients(&[alice_public, bob_public])
+            .unwrap();
+        assert!(ZewifEnvelope::new(envelope.clone()).unwrap().is_encrypted());
+
+        for private_keys in [&alice_private, &bob_private] {
+            assert_eq!(
+                Zewif::decrypt_as_recipient(&envelope, private_keys).unwrap(),
+                zewif
+            );
+        }
+        assert!(matches!(
+            Zewif::decrypt_as_recipient(&envelope, &eve_private),
+            Err(Error::NotARecipient)
+        ));
+        assert!(matches!(
+            zewif.encrypt_to_recipients(&[]),
+            Err(Error::NoRecipients)
+        ));
+
+        // The outer layer identifies the export without decryption.
+        let metadata = ExportMetadata::from_envelope(&envelope).unwrap();
+        assert_eq!(metadata.format_version(), ExportMetadata::FORMAT_VERSION);
+        assert_eq!(metadata.export_height(), zewif.export_height());
+        assert!(metadata.source_tool().unwrap().starts_with("zewif "));
+        assert!(u64::from(metadata.exported_at().unwrap()) > 0);
+        assert!(envelope.objects_for_predicate("wallet").is_empty());
+
+        // Neither a password nor an unencrypted Zewif decrypts as a
+        // recipient.
+        assert!(matches!(
+            Zewif::decrypt_with_password(&envelope, "password"),
+            Err(Error::NotPasswordEncrypted)
+        ));
+        assert!(matches!(
+            Zewif::decrypt_as_recipient(&Envelope::from(zewif.clone()), &alice_private),
+            Err(Error::NotEncrypted)
+        ));
+    }
 }
@@ -1,10 +1,16 @@
-use bc_components::ARID;
+use bc_components::{ARID, PrivateKeys, PublicKeys};
 use bc_envelope::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::{BlockHeight, Indexed, envelope_indexed_objects_for_predicate};
+use crate::{
+    Amount, Balance, BlockHeight, DecodeIssue, EncodeOptions, ExportMetadata, Indexed, KdfParams,
+    ProvenanceInfo, ZewifEnvelope, envelope_decoder::EnvelopeDecoder, envelope_upgrade::upgraded,
+    zewif_envelope::unsigned, zewif_lookup::AddressIndex,
+};
 
-use super::{Transaction, TxId, ZewifWallet};
+use super::{
+    Account, Address, OutPoint, Transaction, TxId, TxIn, TxOut, ZewifWallet, transparent::Utxo,
+};
 
 /// The top-level container for the Zcash Wallet Interchange Format (ZeWIF).
 ///
@@ -48,23 +54,36 @@ use super::{Transaction, TxId, ZewifWallet};
 /// // Add a transaction to the global history
 /// let txid = TxId::from_bytes([0u8; 32]); // In practice, a real transaction ID
 /// let tx = Transaction::new(txid);
-/// zewif.add_transaction(txid, tx);
+/// zewif.add_transaction(txid, tx).unwrap();
 ///
 /// // Access transactions
 /// let tx_count = zewif.transactions().len();
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Zewif {
+    #[cfg_attr(feature = "json", serde(with = "crate::json::arid"))]
     id: ARID,
     wallets: Vec<ZewifWallet>,
+    #[cfg_attr(feature = "json", serde(with = "crate::json::transactions"))]
     transactions: HashMap<TxId, Transaction>,
     export_height: BlockHeight,
+    #[cfg_attr(feature = "json", serde(with = "crate::json::attachments"))]
     attachments: Attachments,
+    #[cfg_attr(feature = "json", serde(skip))]
+    address_index: AddressIndex,
 }
 
 bc_envelope::impl_attachable!(Zewif);
 
 impl Zewif {
+    /// The version of the envelope layout written by this crate, recorded in
+    /// the `zewifVersion` assertion of the top-level envelope. Envelopes of
+    /// earlier versions are migrated when decoded; those of later versions, and
+    /// those that cannot be migrated, are rejected with
+    /// [`Error::UnsupportedVersion`](crate::Error::UnsupportedVersion).
+    pub const VERSION: u32 = 2;
+
     pub fn new(export_height: BlockHeight) -> Self {
         Self {
             id: ARID::new(),
@@ -72,6 +91,7 @@ impl Zewif {
             transactions: HashMap::new(),
             export_height,
             attachments: Attachments::new(),
+            address_index: AddressIndex::default(),
         }
     }
 
@@ -83,41 +103,465 @@ impl Zewif {
         &self.wallets
     }
 
+    /// Returns the wallets for modification in place. Wallets are added and
+    /// removed with [`Zewif::add_wallet`] and [`Zewif::remove_wallet`], which
+    /// keep their indexes in step with their positions.
+    pub fn wallets_mut(&mut self) -> &mut [ZewifWallet] {
+        self.address_index.invalidate();
+        &mut self.wallets
+    }
+
     pub fn wallets_len(&self) -> usize {
         self.wallets.len()
     }
 
     pub fn add_wallet(&mut self, mut wallet: ZewifWallet) {
         wallet.set_index(self.wallets_len());
+        self.address_index.invalidate();
         self.wallets.push(wallet);
     }
 
+    /// Removes the wallet at `index`, returning it with the IDs of the
+    /// transactions its accounts referred to that no remaining account refers
+    /// to. The wallets after it are re-indexed.
+    ///
+    /// The orphaned transactions are left in the store so that the caller can
+    /// decide what to do with them, such as removing them with
+    /// [`Zewif::remove_transaction`].
+    pub fn remove_wallet(&mut self, index: usize) -> Option<(ZewifWallet, Vec<TxId>)> {
+        if index >= self.wallets.len() {
+            return None;
+        }
+        self.address_index.invalidate();
+        let removed = self.wallets.remove(index);
+        for (index, wallet) in self.wallets.iter_mut().enumerate().skip(index) {
+            wallet.set_index(index);
+        }
+        let orphaned = self.orphaned_by(removed.accounts());
+        Some((removed, orphaned))
+    }
+
+    /// Removes the account at `account` in the wallet at `wallet`, returning
+    /// it with the IDs of the transactions it referred to that no remaining
+    /// account refers to. The accounts after it are re-indexed.
+    ///
+    /// As with [`Zewif::remove_wallet`], the orphaned transactions are left in
+    /// the store.
+    pub fn remove_account(
+        &mut self,
+        wallet: usize,
+        account: usize,
+    ) -> Option<(Account, Vec<TxId>)> {
+        let removed = self
+            .wallets_mut()
+            .get_mut(wallet)?
+            .remove_account(account)?;
+        let orphaned = self.orphaned_by(std::slice::from_ref(&removed));
+        Some((removed, orphaned))
+    }
+
+    /// Returns the IDs, in ascending order, of the transactions relevant to
+    /// any of `removed` that no account in the Zewif is relevant to.
+    fn orphaned_by(&self, removed: &[Account]) -> Vec<TxId> {
+        let mut orphaned: Vec<_> = removed
+            .iter()
+            .flat_map(|account| account.relevant_transactions().copied())
+            .filter(|txid| !self.is_referenced(*txid))
+            .collect();
+        orphaned.sort();
+        orphaned.dedup();
+        orphaned
+    }
+
+    fn is_referenced(&self, txid: TxId) -> bool {
+        self.wallets
+            .iter()
+            .flat_map(|wallet| wallet.accounts())
+            .any(|account| account.contains_transaction(txid))
+    }
+
+    /// Returns the IDs, in ascending order, of the stored transactions that no
+    /// account refers to.
+    pub fn orphaned_transactions(&self) -> Vec<TxId> {
+        let mut orphaned: Vec<_> = self
+            .transactions
+            .keys()
+            .copied()
+            .filter(|txid| !self.is_referenced(*txid))
+            .collect();
+        orphaned.sort();
+        orphaned
+    }
+
     pub fn transactions(&self) -> &HashMap<TxId, Transaction> {
         &self.transactions
     }
 
-    pub fn add_transaction(&mut self, txid: TxId, transaction: Transaction) {
-        self.transactions.insert(txid, transaction);
+    /// Adds a transaction to the Zewif's store, which holds each transaction
+    /// once however many accounts it is relevant to.
+    ///
+    /// Adding a transaction that is already stored has no effect.
+    ///
+    /// # Errors
+    /// Returns [`Error::ConflictingTransaction`](crate::Error::ConflictingTransaction)
+    /// if a different transaction is already stored under `txid`; use
+    /// [`Zewif::replace_transaction`] to overwrite it.
+    pub fn add_transaction(&mut self, txid: TxId, transaction: Transaction) -> crate::Result<()> {
+        match self.transactions.get(&txid) {
+            Some(existing) if *existing != transaction => {
+                Err(crate::Error::ConflictingTransaction { txid })
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.transactions.insert(txid, transaction);
+                Ok(())
+            }
+        }
+    }
+
+    /// Stores a transaction under `txid`, returning the transaction it
+    /// replaces, if any.
+    pub fn replace_transaction(
+        &mut self,
+        txid: TxId,
+        transaction: Transaction,
+    ) -> Option<Transaction> {
+        self.transactions.insert(txid, transaction)
+    }
+
+    /// Removes the transaction stored under `txid`, returning it if there was
+    /// one. Accounts that refer to the transaction are not changed.
+    pub fn remove_transaction(&mut self, txid: &TxId) -> Option<Transaction> {
+        self.transactions.remove(txid)
+    }
+
+    pub fn transaction(&self, txid: &TxId) -> Option<&Transaction> {
+        self.transactions.get(txid)
     }
 
     pub fn get_transaction(&self, txid: TxId) -> Option<&Transaction> {
-        self.transactions.get(&txid)
+        self.transaction(&txid)
+    }
+
+    /// Returns the stored transactions relevant to `account`, in ascending
+    /// order of their IDs. Relevant transactions missing from the store are
+    /// skipped.
+    pub fn account_transactions<'a>(
+        &'a self,
+        account: &'a Account,
+    ) -> impl Iterator<Item = &'a Transaction> {
+        account
+            .relevant_transactions()
+            .filter_map(|txid| self.transaction(txid))
     }
 
     pub fn set_transactions(&mut self, transactions: HashMap<TxId, Transaction>) {
         self.transactions = transactions;
     }
 
+    pub(crate) fn address_index(&self) -> &AddressIndex {
+        &self.address_index
+    }
+
     pub fn export_height(&self) -> BlockHeight {
         self.export_height
     }
+
+    pub fn set_export_height(&mut self, export_height: BlockHeight) {
+        self.export_height = export_height;
+    }
+
+    /// Returns the height from which a receiving wallet should scan the chain:
+    /// the earliest birthday height recorded by any wallet or account.
+    ///
+    /// If no birthday is recorded, falls back to the earliest mined height of
+    /// the stored transactions. Returns `None` if neither is known.
+    pub fn effective_birthday(&self) -> Option<BlockHeight> {
+        let wallet_birthdays = self
+            .wallets
+            .iter()
+            .filter_map(|wallet| wallet.birthday_height());
+        let account_birthdays = self
+            .wallets
+            .iter()
+            .flat_map(|wallet| wallet.accounts())
+            .filter_map(|account| account.birthday_height());
+        wallet_birthdays.chain(account_birthdays).min().or_else(|| {
+            self.transactions
+                .values()
+                .filter_map(|tx| tx.mined_height().copied())
+                .min()
+        })
+    }
+
+    /// Returns whether the notes created and spent by `txid` count toward
+    /// balances, as given by its [`TransactionStatus`](crate::TransactionStatus).
+    /// A transaction missing from the store counts, as its status is unknown.
+    pub fn counts_toward_balance(&self, txid: TxId) -> bool {
+        self.transaction(&txid)
+            .is_none_or(|transaction| transaction.status().counts_toward_balance())
+    }
+
+    /// Returns whether a note created by `created_in` is held: its creating
+    /// transaction counts toward balances, and no transaction that counts
+    /// spent it.
+    fn holds_note(&self, created_in: TxId, spent_in: Option<TxId>) -> bool {
+        self.counts_toward_balance(created_in)
+            && !spent_in.is_some_and(|txid| self.counts_toward_balance(txid))
+    }
+
+    /// Returns the balance in each pool over every account of every wallet,
+    /// computed as by [`Zewif::account_balance`].
+    ///
+    /// # Errors
+    /// Returns [`Error::AmountOverflow`](crate::Error::AmountOverflow), giving
+    /// the overflowing total, if the total of any pool exceeds the valid range
+    /// of amounts.
+    pub fn total_balance(&self) -> crate::Result<Balance> {
+        self.balance_of(self.wallets.iter().flat_map(|wallet| wallet.accounts()))
+    }
+
+    /// Returns the balance of `account` in each pool, computed from its
+    /// unspent received notes.
+    ///
+    /// Unlike [`Account::balance`], this consults the status of the stored
+    /// transactions: notes and UTXOs created by transactions that were never
+    /// mined are excluded, and spends by such transactions are disregarded;
+    /// see [`Zewif::counts_toward_balance`]. Coinbase UTXOs are excluded until
+    /// they are mature as of the export height.
+    ///
+    /// # Errors
+    /// Returns [`Error::AmountOverflow`](crate::Error::AmountOverflow) as for
+    /// [`Zewif::total_balance`].
+    pub fn account_balance(&self, account: &Account) -> crate::Result<Balance> {
+        self.balance_of(std::iter::once(account))
+    }
+
+    fn balance_of<'a>(
+        &self,
+        accounts: impl Iterator<Item = &'a Account> + Clone,
+    ) -> crate::Result<Balance> {
+        let sprout = accounts
+            .clone()
+            .flat_map(|account| account.sprout_received_notes())
+            .filter(|note| self.holds_note(note.txid(), note.spent_in()))
+            .map(|note| note.note().value());
+        let sapling = accounts
+            .clone()
+            .flat_map(|account| account.sapling_received_notes())
+            .filter(|note| self.holds_note(note.txid(), note.spent_in()))
+            .map(|note| note.note().value());
+        let orchard = accounts
+            .clone()
+            .flat_map(|account| account.orchard_received_notes())
+            .filter(|note| self.holds_note(note.txid(), note.spent_in()))
+            .map(|note| note.note().value());
+        let transparent = accounts
+            .flat_map(|account| account.utxos())
+            .filter(|utxo| {
+                self.counts_toward_balance(utxo.outpoint().txid())
+                    && utxo.is_mature(self.export_height)
+            })
+            .map(|utxo| utxo.value());
+        Ok(Balance::new(
+            pool_total(transparent)?,
+            pool_total(sprout)?,
+            pool_total(sapling)?,
+            pool_total(orchard)?,
+        ))
+    }
+
+    /// Links received notes in every account to the transactions that spent
+    /// them.
+    ///
+    /// The nullifiers revealed by the Sapling spends and Orchard actions of all
+    /// transactions are matched against the nullifiers of each account's
+    /// received notes, and matching notes have their `spent_in` transaction
+    /// recorded. Notes without a known nullifier are left untouched.
+    ///
+    /// Returns the number of notes newly marked as spent.
+    pub fn link_spent_notes(&mut self) -> usize {
+        let mut sprout_spends = HashMap::new();
+        let mut sapling_spends = HashMap::new();
+        let mut orchard_spends = HashMap::new();
+        for (txid, transaction) in &self.transactions {
+            for joinsplit in transaction.joinsplits() {
+                for nullifier in joinsplit.nullifiers() {
+                    sprout_spends.insert(*nullifier, *txid);
+                }
+            }
+            for spend in transaction.sapling_spends() {
+                sapling_spends.insert(*spend.nullifier(), *txid);
+            }
+            for action in transaction.orchard_actions() {
+                orchard_spends.insert(*action.nullifier(), *txid);
+            }
+        }
+
+        self.wallets
+            .iter_mut()
+            .flat_map(|wallet| wallet.accounts_mut().iter_mut())
+            .map(|account| {
+                account.link_spent_notes(&sprout_spends, &sapling_spends, &orchard_spends)
+            })
+            .sum()
+    }
+
+    /// Recomputes which transactions are relevant to each account, such as
+    /// after a [`merge`](Self::merge) has brought in notes from another
+    /// export.
+    ///
+    /// Spent notes are first linked to the transactions that spent them, as
+    /// by [`Zewif::link_spent_notes`]. Each account is then marked relevant to
+    /// the transactions that created or spent its received notes, and to
+    /// those whose recorded transparent outputs pay to one of its addresses,
+    /// or whose transparent inputs spend such an output; see
+    /// [`Transaction::populate_transparent`]. Relevance that cannot be
+    /// recomputed, such as that of transactions recorded without their
+    /// transparent components, is kept.
+    ///
+    /// Returns the number of transactions newly marked as relevant, summed
+    /// over all accounts.
+    pub fn rebuild_transaction_relevance(&mut self) -> usize {
+        self.link_spent_notes();
+        let transactions = &self.transactions;
+        let mut added = 0;
+        for wallet in self.wallets.iter_mut() {
+            let params = wallet.effective_network_parameters();
+            for account in wallet.accounts_mut().iter_mut() {
+                let addresses: HashSet<_> =
+                    account.addresses().iter().map(Address::as_string).collect();
+                let pays_account = |output: &TxOut| {
+                    output
+                        .address_with(&params)
+                        .is_some_and(|address| addresses.contains(&address))
+                };
+                let spends_account = |input: &TxIn| {
+                    let prevout = input.prevout();
+                    transactions
+                        .get(&prevout.txid())
+                        .and_then(|funding| {
+                            funding.transparent_output(usize::try_from(prevout.index()).ok()?)
+                        })
+                        .is_some_and(pays_account)
+                };
+                let mut txids: Vec<_> = account.note_transactions().collect();
+                txids.extend(transactions.iter().filter_map(|(txid, transaction)| {
+                    let relevant = transaction.transparent_outputs().iter().any(pays_account)
+                        || transaction.transparent_inputs().iter().any(spends_account);
+                    relevant.then_some(*txid)
+                }));
+                added += txids
+                    .into_iter()
+                    .filter(|txid| account.add_relevant_transaction(*txid))
+                    .count();
+            }
+        }
+        added
+    }
+
+    /// Recomputes the unspent transparent outputs of each account from the
+    /// transparent inputs and outputs of the stored transactions; see
+    /// [`Transaction::populate_transparent`].
+    ///
+    /// An account holds the outputs paying to one of its addresses, encoded
+    /// with its wallet's effective network parameters, that were created by a
+    /// transaction counting toward balances and not spent by another; see
+    /// [`Zewif::counts_toward_balance`]. Each account's set replaces the one
+    /// it held, ordered by outpoint.
+    ///
+    /// Returns the outputs that are spent by a stored transaction not
+    /// relevant to any account of the wallet holding them, paired with the
+    /// spending transaction, ordered by outpoint. Such an output is excluded
+    /// from the set, but suggests that the wallet's relevant transactions are
+    /// incomplete; see [`Zewif::rebuild_transaction_relevance`].
+    pub fn rebuild_utxo_set(&mut self) -> Vec<(OutPoint, TxId)> {
+        let transactions = &self.transactions;
+        let spends: HashMap<OutPoint, TxId> = transactions
+            .iter()
+            .filter(|(_, transaction)| transaction.status().counts_toward_balance())
+            .flat_map(|(txid, transaction)| {
+                transaction
+                    .transparent_inputs()
+                    .iter()
+                    .map(|input| (*input.prevout(), *txid))
+            })
+            .collect();
+        let mut external_spends = Vec::new();
+        for wallet in self.wallets.iter_mut() {
+            let params = wallet.effective_network_parameters();
+            let wallet_transactions: HashSet<TxId> = wallet
+                .accounts()
+                .iter()
+                .flat_map(|account| account.relevant_transactions().copied())
+                .collect();
+            for account in wallet.accounts_mut().iter_mut() {
+                let addresses: HashSet<_> =
+                    account.addresses().iter().map(Address::as_string).collect();
+                let mut utxos = Vec::new();
+                for (txid, transaction) in transactions
+                    .iter()
+                    .filter(|(_, tx)| tx.status().counts_toward_balance())
+                {
+                    for output in transaction.transparent_outputs() {
+                        if !output
+                            .address_with(&params)
+                            .is_some_and(|address| addresses.contains(&address))
+                        {
+                            continue;
+                        }
+                        let Ok(index) = u32::try_from(output.index()) else {
+                            continue;
+                        };
+                        let outpoint = OutPoint::new(*txid, index);
+                        match spends.get(&outpoint) {
+                            Some(spent_in) if !wallet_transactions.contains(spent_in) => {
+                                external_spends.push((outpoint, *spent_in));
+                            }
+                            Some(_) => {}
+                            None => {
+                                let mut utxo = Utxo::new(
+                                    outpoint,
+                                    output.value(),
+                                    output.script_pubkey().clone(),
+                                );
+                                utxo.set_mined_height(
+                                    transaction
+                                        .mined_height()
+                                        .copied()
+                                        .or(transaction.status().mined_height()),
+                                );
+                                utxo.set_coinbase(transaction.is_coinbase());
+                                utxos.push(utxo);
+                            }
+                        }
+                    }
+                }
+                utxos.sort_by_key(|utxo| *utxo.outpoint());
+                account.set_utxos(utxos);
+            }
+        }
+        external_spends.sort();
+        external_spends
+    }
 }
 
+/// The encoding is deterministic, so that an export can be verified by hashing
+/// it: envelope assertions are ordered by digest regardless of the order in
+/// which they are added, and sets and maps such as an account's relevant
+/// transactions are encoded in sorted order. Compression with
+/// [`Zewif::to_envelope_with`] is deterministic as well.
+///
+/// Encryption is intentionally not: [`Zewif::encrypt_with_password`],
+/// [`Zewif::encrypt_to_recipients`], and [`Zewif::shard`] use random keys,
+/// nonces, and salts, and record the time of the export. To produce a
+/// reproducible export, hash the unencrypted envelope before encrypting it.
 #[rustfmt::skip]
 impl From<Zewif> for Envelope {
     fn from(value: Zewif) -> Self {
         let mut e = Envelope::new(value.id)
-            .add_type("Zewif");
+            .add_type("Zewif")
+            .add_assertion("zewifVersion", Zewif::VERSION);
         e = value.wallets.iter().fold(e, |e, wallet| e.add_assertion("wallet", wallet.clone()));
         e = value.transactions.iter().fold(e, |e, (_, transaction)| e.add_assertion("transaction", transaction.clone()));
         e = e.add_assertion("export_height", value.export_height);
@@ -125,32 +569,271 @@ impl From<Zewif> for Envelope {
     }
 }
 
-#[rustfmt::skip]
+/// Fails with an [`Error::EnvelopeDecode`](crate::Error::EnvelopeDecode)
+/// naming the nested object and predicate that could not be decoded, such as
+/// `Zewif > Wallet[0] > Account["Savings"] > Address[t1abc...]: predicate "name"`.
+///
+/// Envelopes compressed by [`Zewif::to_envelope_with`] are decompressed first,
+/// and envelopes of earlier versions of the format are migrated to
+/// [`Zewif::VERSION`].
 impl TryFrom<Envelope> for Zewif {
-    type Error = bc_envelope::Error;
+    type Error = crate::Error;
+
+    fn try_from(envelope: Envelope) -> crate::Result<Self> {
+        Self::decode(&EnvelopeDecoder::new(
+            upgraded(uncompressed(envelope)?)?,
+            "Zewif",
+        ))
+    }
+}
+
+impl Zewif {
+    /// Encodes the Zewif as an envelope, compressed as selected by `options`,
+    /// and carrying a `contentDigest` assertion if they request one.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{BlockHeight, Compression, EncodeOptions, Zewif};
+    /// let zewif = Zewif::new(BlockHeight::from(2_000_000u32));
+    /// let mut options = EncodeOptions::new();
+    /// options.set_compression(Compression::Whole);
+    /// let envelope = zewif.to_envelope_with(&options).unwrap();
+    /// assert_eq!(Zewif::try_from(envelope).unwrap(), zewif);
+    /// ```
+    pub fn to_envelope_with(&self, options: &EncodeOptions) -> crate::Result<Envelope> {
+        let mut envelope = ZewifEnvelope::new(Envelope::from(self.clone()))?;
+        let digest = envelope.digest();
+        envelope.compress_with(options.compression())?;
+        let envelope = envelope.envelope().clone();
+        if options.content_digest() {
+            return Ok(envelope.add_assertion("contentDigest", digest));
+        }
+        Ok(envelope)
+    }
 
-    fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
-        envelope.check_type("Zewif")?;
-        let id = envelope.extract_subject()?;
+    /// Encodes the Zewif as an envelope encrypted with a key derived from
+    /// `password` by Argon2id. Only the Zewif's ID and its
+    /// [`ExportMetadata`] remain readable; the key derivation parameters are
+    /// stored in the envelope, so [`Zewif::decrypt_with_password`] needs only
+    /// the password.
+    pub fn encrypt_with_password(
+        &self,
+        password: &str,
+        params: KdfParams,
+    ) -> crate::Result<Envelope> {
+        self.encrypt_with_password_and_metadata(password, params, &ExportMetadata::for_export(self))
+    }
 
-        let wallets = envelope_indexed_objects_for_predicate(&envelope, "wallet")
-            .map_err(|e| bc_envelope::Error::General(format!("wallets: {}", e)))?;
+    /// Like [`Zewif::encrypt_with_password`], recording `metadata`, such as
+    /// one naming the exporting tool or carrying a note, in the readable
+    /// outer layer.
+    pub fn encrypt_with_password_and_metadata(
+        &self,
+        password: &str,
+        params: KdfParams,
+        metadata: &ExportMetadata,
+    ) -> crate::Result<Envelope> {
+        let mut envelope = ZewifEnvelope::new(Envelope::from(self.clone()))?;
+        envelope.encrypt_with_password(password, params)?;
+        Ok(metadata.add_to_envelope(envelope.envelope().clone()))
+    }
 
-        let transactions = envelope
-            .try_objects_for_predicate::<Transaction>("transaction")?
-            .into_iter().map(|tx| (tx.txid(), tx)).collect();
+    /// Encodes the Zewif as an envelope encrypted so that any of `recipients`
+    /// can decrypt it with [`Zewif::decrypt_as_recipient`].
+    ///
+    /// The outer layer of the envelope remains readable and carries the
+    /// Zewif's ID and its [`ExportMetadata`].
+    ///
+    /// # Errors
+    /// Returns [`Error::NoRecipients`](crate::Error::NoRecipients) if
+    /// `recipients` is empty.
+    pub fn encrypt_to_recipients(&self, recipients: &[PublicKeys]) -> crate::Result<Envelope> {
+        self.encrypt_to_recipients_with_metadata(recipients, &ExportMetadata::for_export(self))
+    }
 
-        let export_height = envelope.extract_object_for_predicate("export_height")?;
-        let attachments = Attachments::try_from_envelope(&envelope)
-            .map_err(|e| bc_envelope::Error::General(format!("attachments: {}", e)))?;
+    /// Like [`Zewif::encrypt_to_recipients`], recording `metadata` in the
+    /// readable outer layer.
+    pub fn encrypt_to_recipients_with_metadata(
+        &self,
+        recipients: &[PublicKeys],
+        metadata: &ExportMetadata,
+    ) -> crate::Result<Envelope> {
+        let mut envelope = ZewifEnvelope::new(Envelope::from(self.clone()))?;
+        envelope.encrypt_to_recipients(recipients)?;
+        Ok(metadata.add_to_envelope(envelope.envelope().clone()))
+    }
 
-        Ok(Self {
+    /// Decodes an envelope produced by [`Zewif::encrypt_to_recipients`].
+    ///
+    /// # Errors
+    /// Returns [`Error::NotARecipient`](crate::Error::NotARecipient) if
+    /// `private_keys` are not those of any of the recipients.
+    pub fn decrypt_as_recipient(
+        envelope: &Envelope,
+        private_keys: &PrivateKeys,
+    ) -> crate::Result<Self> {
+        let mut envelope = ZewifEnvelope::new(envelope.clone())?;
+        envelope.decrypt_as_recipient(private_keys)?;
+        Self::try_from(envelope.envelope().clone())
+    }
+
+    /// Decodes an envelope produced by [`Zewif::encrypt_with_password`].
+    ///
+    /// # Errors
+    /// Returns [`Error::WrongPassword`](crate::Error::WrongPassword) if the
+    /// password is wrong or the ciphertext has been altered,
+    /// [`Error::InvalidKdfParams`](crate::Error::InvalidKdfParams) if the
+    /// envelope asks for a costlier key derivation than [`KdfParams`] allows,
+    /// and [`Error::NotEncrypted`](crate::Error::NotEncrypted) if the envelope
+    /// is not encrypted.
+    pub fn decrypt_with_password(envelope: &Envelope, password: &str) -> crate::Result<Self> {
+        let mut envelope = ZewifEnvelope::new(envelope.clone())?;
+        envelope.decrypt_with_password(password)?;
+        Self::try_from(envelope.envelope().clone())
+    }
+
+    /// Signs the Zewif on behalf of the tool or operator that exported it,
+    /// recording `provenance` in the signature's metadata.
+    ///
+    /// The Zewif is encoded as selected by `options`, as by
+    /// [`Zewif::to_envelope_with`], and the envelope is wrapped, with a
+    /// `'signed'` assertion added to the wrapper. The signature covers the
+    /// digest of the wrapped envelope, so it remains verifiable if the signed
+    /// envelope is later compressed or encrypted with [`ZewifEnvelope`].
+    /// [`Zewif::try_from`](Envelope), [`Zewif::load`], and the decryption
+    /// methods accept signed envelopes, without verifying the signature; use
+    /// [`Zewif::verify_signature`] or [`Zewif::from_signed_envelope`] for
+    /// that.
+    pub fn sign(
+        &self,
+        signer: &PrivateKeys,
+        provenance: ProvenanceInfo,
+        options: &EncodeOptions,
+    ) -> crate::Result<Envelope> {
+        let envelope = self.to_envelope_with(options)?;
+        Ok(envelope
+            .wrap()
+            .add_signature_opt(signer, None, Some(provenance.signature_metadata())))
+    }
+
+    /// Verifies the signature of an envelope produced by [`Zewif::sign`] and
+    /// decodes the Zewif it signs, returning it with the provenance recorded
+    /// with the signature.
+    ///
+    /// # Errors
+    /// Returns an error as for [`Zewif::verify_signature`], or if the signed
+    /// envelope cannot be decoded.
+    pub fn from_signed_envelope(
+        envelope: &Envelope,
+        public_keys: &PublicKeys,
+    ) -> crate::Result<(Self, ProvenanceInfo)> {
+        let provenance = Self::verify_signature(envelope, public_keys)?;
+        Ok((Self::try_from(envelope.clone())?, provenance))
+    }
+
+    /// Verifies the signature of an envelope produced by [`Zewif::sign`] and
+    /// returns the provenance recorded with it.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidSignature`](crate::Error::InvalidSignature) if
+    /// the envelope is not signed by `public_keys`, or if its content or the
+    /// signature's metadata has been altered.
+    pub fn verify_signature(
+        envelope: &Envelope,
+        public_keys: &PublicKeys,
+    ) -> crate::Result<ProvenanceInfo> {
+        let metadata = envelope
+            .verify_signature_from_returning_metadata(public_keys)
+            .map_err(|e| match e {
+                bc_envelope::Error::UnverifiedSignature
+                | bc_envelope::Error::UnverifiedInnerSignature => crate::Error::InvalidSignature,
+                e => e.into(),
+            })?;
+        ProvenanceInfo::from_signature_metadata(&metadata)
+    }
+
+    /// Decodes an envelope, skipping wallets, accounts, addresses, and
+    /// transactions that cannot be decoded rather than failing.
+    ///
+    /// Each skipped element is described by a [`DecodeIssue`] in the returned
+    /// list. Elements holding seeds or spending keys are never skipped, so
+    /// that no key material is silently lost.
+    ///
+    /// # Errors
+    /// Returns an error if the envelope is not a Zewif, if any of its required
+    /// fields cannot be decoded, or if an element holding seeds or spending
+    /// keys cannot be decoded.
+    pub fn from_envelope_lenient(envelope: Envelope) -> crate::Result<(Self, Vec<DecodeIssue>)> {
+        let decoder = EnvelopeDecoder::lenient(upgraded(uncompressed(envelope)?)?, "Zewif");
+        let zewif = Self::decode(&decoder)?;
+        Ok((zewif, decoder.take_issues()))
+    }
+
+    /// Decodes an envelope like [`Zewif::try_from`](Envelope), but attaches
+    /// the object that failed to decode to an
+    /// [`Error::EnvelopeDecode`](crate::Error::EnvelopeDecode), in CBOR
+    /// diagnostic notation with its seeds and spending keys elided.
+    pub fn from_envelope_with_diagnostics(envelope: Envelope) -> crate::Result<Self> {
+        Self::decode(
+            &EnvelopeDecoder::new(upgraded(uncompressed(envelope)?)?, "Zewif").with_diagnostics(),
+        )
+    }
+
+    fn decode(decoder: &EnvelopeDecoder) -> crate::Result<Self> {
+        decoder.check_type("Zewif")?;
+        let id = decoder.subject()?;
+        let wallets = decoder.indexed_objects_with(
+            "wallet",
+            ZewifWallet::envelope_segment,
+            ZewifWallet::decode,
+        )?;
+        let transactions = decoder.objects_with(
+            "transaction",
+            Transaction::envelope_segment,
+            EnvelopeDecoder::decode::<Transaction>,
+        )?;
+        let export_height = decoder.extract("export_height")?;
+        let attachments = decoder.attachments()?;
+
+        let mut zewif = Self {
             id,
             wallets,
-            transactions,
+            transactions: HashMap::new(),
             export_height,
             attachments,
-        })
+            address_index: AddressIndex::default(),
+        };
+        // Conflicting copies of a transaction cannot both be kept.
+        for transaction in transactions {
+            zewif.add_transaction(transaction.txid(), transaction)?;
+        }
+        Ok(zewif)
+    }
+}
+
+/// Sums amounts without intermediate overflow, failing if the total is not a
+/// valid amount.
+pub(crate) fn pool_total(values: impl Iterator<Item = Amount>) -> crate::Result<Amount> {
+    let total: i128 = values.map(|value| i128::from(i64::from(value))).sum();
+    let total = i64::try_from(total)
+        .map_err(|_| crate::Error::AmountOverflow(u64::try_from(total).unwrap_or(u64::MAX)))?;
+    Amount::from_i64(total)
+}
+
+/// Reverses any compression applied by [`Zewif::to_envelope_with`], removing
+/// the signature of an envelope signed by [`Zewif::sign`] without verifying
+/// it, whether the signed envelope was compressed before or after signing.
+pub(crate) fn uncompressed(envelope: Envelope) -> crate::Result<Envelope> {
+    uncompressed_layer(unsigned(&uncompressed_layer(unsigned(&envelope))?))
+}
+
+fn uncompressed_layer(envelope: Envelope) -> crate::Result<Envelope> {
+    match ZewifEnvelope::new(envelope.clone()) {
+        Ok(mut zewif_envelope) if zewif_envelope.can_uncompress() => {
+            zewif_envelope.uncompress()?;
+            Ok(zewif_envelope.envelope().clone())
+        }
+        _ => Ok(envelope),
     }
 }
 
@@ -176,9 +859,752 @@ mod tests {
                     .collect(),
                 export_height: BlockHeight::random(),
                 attachments: Attachments::random(),
+                address_index: Default::default(),
             }
         }
     }
 
     test_envelope_roundtrip!(Zewif);
+
+    #[test]
+    fn test_effective_birthday() {
+        use crate::{Account, Network, TxId, ZewifWallet};
+
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        assert_eq!(zewif.effective_birthday(), None);
+
+        for (byte, height) in [(1, 1_200_000), (2, 1_100_000)] {
+            let mut tx = Transaction::new(TxId::from_bytes([byte; 32]));
+            tx.set_mined_height(BlockHeight::from(height));
+            zewif.add_transaction(tx.txid(), tx).unwrap();
+        }
+        zewif
+            .add_transaction(
+                TxId::from_bytes([3; 32]),
+                Transaction::new(TxId::from_bytes([3; 32])),
+            )
+            .unwrap();
+        assert_eq!(
+            zewif.effective_birthday(),
+            Some(BlockHeight::from(1_100_000))
+        );
+
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.set_birthday_height(Some(BlockHeight::from(1_500_000)));
+        let mut account = Account::new();
+        account.set_birthday_height(Some(BlockHeight::from(1_300_000)));
+        wallet.add_account(account);
+        zewif.add_wallet(wallet);
+        assert_eq!(
+            zewif.effective_birthday(),
+            Some(BlockHeight::from(1_300_000))
+        );
+    }
+
+    #[test]
+    fn test_total_balance() {
+        use crate::{
+            Account, Amount, Blob, Error, MAX_MONEY, Network, TxId, ZewifWallet, orchard, sapling,
+        };
+
+        let zats = |value: u64| Amount::from_u64(value).unwrap();
+        let txid = TxId::from_bytes([1; 32]);
+        let sapling_note = |value, spent_in| {
+            let note =
+                sapling::Note::from_parts(Blob::default(), zats(value), Blob::default()).unwrap();
+            let mut note = sapling::SaplingReceivedNote::new(txid, 0, note);
+            note.set_spent_in(spent_in);
+            note
+        };
+
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        for values in [
+            [(1_000, None), (500, Some(txid))],
+            [(2_500, None), (7, None)],
+        ] {
+            let mut account = Account::new();
+            for (value, spent_in) in values {
+                account.add_sapling_received_note(sapling_note(value, spent_in));
+            }
+            let note = orchard::Note::from_parts(
+                Blob::default(),
+                zats(40),
+                Blob::default(),
+                Blob::default(),
+            )
+            .unwrap();
+            account.add_orchard_received_note(orchard::OrchardReceivedNote::new(txid, 0, note));
+            let mut wallet = ZewifWallet::new(Network::Main);
+            wallet.add_account(account);
+            zewif.add_wallet(wallet);
+        }
+        let balance = zewif.total_balance().unwrap();
+        assert_eq!(balance.transparent(), Amount::zero());
+        assert_eq!(balance.sprout(), Amount::zero());
+        assert_eq!(balance.sapling(), zats(3_507));
+        assert_eq!(balance.orchard(), zats(80));
+        assert_eq!(balance.total(), Some(zats(3_587)));
+
+        // Notes that are each valid may together exceed the supply.
+        for wallet in zewif.wallets_mut() {
+            wallet.accounts_mut()[0].add_sapling_received_note(sapling_note(MAX_MONEY, None));
+        }
+        match zewif.total_balance() {
+            Err(Error::AmountOverflow(total)) => assert_eq!(total, 2 * MAX_MONEY + 3_507),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    /// A note created by transaction 1 counts only if its status does, and a
+    /// note spent by it remains held unless its status counts.
+    #[test]
+    fn test_balance_by_status() {
+        use crate::{
+            Account, Amount, Blob, Network, Transaction, TransactionStatus, TxId, ZewifWallet,
+            sapling,
+        };
+
+        let zats = |value: u64| Amount::from_u64(value).unwrap();
+        let txid = |byte| TxId::from_bytes([byte; 32]);
+        let sapling_note = |created_in, value, spent_in| {
+            let note =
+                sapling::Note::from_parts(Blob::default(), zats(value), Blob::default()).unwrap();
+            let mut note = sapling::SaplingReceivedNote::new(created_in, 0, note);
+            note.set_spent_in(spent_in);
+            note
+        };
+        let statuses = [
+            (
+                TransactionStatus::Mined {
+                    height: BlockHeight::from(1_500_000),
+                },
+                1_000,
+            ),
+            (TransactionStatus::Mempool, 300),
+            (TransactionStatus::Expired, 300),
+            (TransactionStatus::Abandoned, 300),
+            (TransactionStatus::Unknown, 1_000),
+        ];
+        for (status, expected) in statuses {
+            let mut account = Account::new();
+            account.add_sapling_received_note(sapling_note(txid(1), 1_000, None));
+            account.add_sapling_received_note(sapling_note(txid(0), 300, Some(txid(1))));
+            let mut wallet = ZewifWallet::new(Network::Main);
+            wallet.add_account(account);
+            let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+            zewif.add_wallet(wallet);
+            let mut transaction = Transaction::new(txid(1));
+            transaction.set_status(status);
+            zewif.add_transaction(txid(1), transaction).unwrap();
+
+            assert_eq!(
+                zewif.counts_toward_balance(txid(1)),
+                status.counts_toward_balance()
+            );
+            assert_eq!(
+                zewif.total_balance().unwrap().sapling(),
+                zats(expected),
+                "{:?}",
+                status
+            );
+            let account = &zewif.wallets()[0].accounts()[0];
+            assert_eq!(
+                zewif.account_balance(account).unwrap().sapling(),
+                zats(expected)
+            );
+            // The account alone cannot see the status.
+            assert_eq!(account.balance().unwrap().sapling(), zats(1_000));
+        }
+    }
+
+    #[test]
+    fn test_removal() {
+        use bc_envelope::prelude::*;
+
+        use crate::{
+            Account, Address, Indexed, Network, ProtocolAddress, TxId, ZewifWallet, transparent,
+        };
+
+        let txid = |byte| TxId::from_bytes([byte; 32]);
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        for (w, network) in [Network::Main, Network::Test].into_iter().enumerate() {
+            let mut wallet = ZewifWallet::new(network);
+            for a in 0..3 {
+                let mut account = Account::new();
+                for suffix in ["a", "b", "c"] {
+                    let string = format!("t1{}{}{}", w, a, suffix);
+                    account.add_address(Address::new(ProtocolAddress::Transparent(
+                        transparent::Address::new(string),
+                    )));
+                }
+                // Transaction 1 is relevant to every account.
+                for byte in [1, (w * 3 + a + 2) as u8] {
+                    account.add_relevant_transaction(txid(byte));
+                    zewif
+                        .add_transaction(txid(byte), Transaction::new(txid(byte)))
+                        .unwrap();
+                }
+                wallet.add_account(account);
+            }
+            zewif.add_wallet(wallet);
+        }
+        assert!(zewif.orphaned_transactions().is_empty());
+
+        let account = &mut zewif.wallets_mut()[1].accounts_mut()[2];
+        let removed = account.remove_address("t112a").unwrap();
+        assert_eq!(removed.index(), 0);
+        assert!(account.remove_address("t1zzz").is_none());
+        assert_eq!(
+            account
+                .addresses()
+                .iter()
+                .map(Indexed::index)
+                .collect::<Vec<_>>(),
+            [0, 1]
+        );
+
+        let (account, orphaned) = zewif.remove_account(0, 0).unwrap();
+        assert_eq!(account.index(), 0);
+        assert_eq!(orphaned, [txid(2)]);
+        assert_eq!(
+            zewif.wallets()[0]
+                .accounts()
+                .iter()
+                .map(Indexed::index)
+                .collect::<Vec<_>>(),
+            [0, 1]
+        );
+        assert!(zewif.remove_account(0, 2).is_none());
+
+        let (wallet, orphaned) = zewif.remove_wallet(0).unwrap();
+        assert_eq!(wallet.network(), Network::Main);
+        assert_eq!(orphaned, [txid(3), txid(4)]);
+        assert_eq!(zewif.wallets()[0].index(), 0);
+        assert!(zewif.remove_wallet(1).is_none());
+
+        // Orphaned transactions stay in the store until removed.
+        assert_eq!(zewif.orphaned_transactions(), [txid(2), txid(3), txid(4)]);
+        for txid in zewif.orphaned_transactions() {
+            assert!(zewif.remove_transaction(&txid).is_some());
+        }
+        assert!(zewif.orphaned_transactions().is_empty());
+
+        let decoded = Zewif::try_from(Envelope::from(zewif.clone())).unwrap();
+        assert_eq!(decoded, zewif);
+    }
+
+    #[test]
+    fn test_deterministic_encoding() {
+        use bc_envelope::prelude::*;
+
+        use crate::{Compression, EncodeOptions, RandomInstance};
+
+        for _ in 0..10 {
+            let zewif = Zewif::random();
+            let bytes = Envelope::from(zewif.clone()).to_cbor_data();
+            assert_eq!(Envelope::from(zewif.clone()).to_cbor_data(), bytes);
+
+            // The order in which transactions were added, and a decoding
+            // round trip, do not affect the encoding.
+            let mut reordered = zewif.clone();
+            let mut transactions: Vec<_> = zewif.transactions().clone().into_iter().collect();
+            transactions.reverse();
+            reordered.set_transactions(transactions.into_iter().collect());
+            assert_eq!(Envelope::from(reordered).to_cbor_data(), bytes);
+            let decoded = Zewif::try_from(Envelope::from(zewif.clone())).unwrap();
+            assert_eq!(Envelope::from(decoded).to_cbor_data(), bytes);
+
+            for compression in [Compression::Whole, Compression::LargeLeavesOver(64)] {
+                let mut options = EncodeOptions::new();
+                options.set_compression(compression);
+                let encode = || zewif.to_envelope_with(&options).unwrap().to_cbor_data();
+                assert_eq!(encode(), encode());
+            }
+        }
+    }
+
+    #[test]
+    fn test_link_spent_notes_across_pools() {
+        use crate::{
+            Account, Amount, Anchor, Blob, Network, TxId, ZewifWallet,
+            orchard::{self, OrchardActionDescription, OrchardNullifier, OrchardReceivedNote},
+            sapling::{self, SaplingNullifier, SaplingReceivedNote, SaplingSpendDescription},
+        };
+
+        // tx1 creates a Sapling note, tx2 spends it into an Orchard note, and
+        // tx3 spends that Orchard note. A second Orchard note is never spent.
+        let tx1 = TxId::from_bytes([1u8; 32]);
+        let tx2 = TxId::from_bytes([2u8; 32]);
+        let tx3 = TxId::from_bytes([3u8; 32]);
+        let sapling_nf = SaplingNullifier::new([10u8; 32]);
+        let orchard_nf = OrchardNullifier::new([20u8; 32]);
+        let unspent_nf = OrchardNullifier::new([30u8; 32]);
+        let value = Amount::from_u64(1000).unwrap();
+
+        let mut sapling_note = SaplingReceivedNote::new(
+            tx1,
+            0,
+            sapling::Note::from_parts(Blob::default(), value, Blob::default()).unwrap(),
+        );
+        sapling_note.set_nullifier(Some(sapling_nf));
+        let orchard_note = |nf| {
+            let mut note = OrchardReceivedNote::new(
+                tx2,
+                0,
+                orchard::Note::from_parts(Blob::default(), value, Blob::default(), Blob::default())
+                    .unwrap(),
+            );
+            note.set_nullifier(Some(nf));
+            note
+        };
+
+        let mut account = Account::new();
+        account.add_sapling_received_note(sapling_note);
+        account.add_orchard_received_note(orchard_note(orchard_nf));
+        account.add_orchard_received_note(orchard_note(unspent_nf));
+        let mut wallet = ZewifWallet::new(Network::Test);
+        wallet.add_account(account);
+
+        let mut zewif = Zewif::new(BlockHeight::from(100));
+        zewif.add_wallet(wallet);
+        zewif.add_transaction(tx1, Transaction::new(tx1)).unwrap();
+        let mut spend_tx = Transaction::new(tx2);
+        spend_tx.add_sapling_spend(SaplingSpendDescription::from_parts(
+            Blob::default(),
+            Anchor::new([0u8; 32]),
+            sapling_nf,
+            Blob::default(),
+        ));
+        zewif.add_transaction(tx2, spend_tx).unwrap();
+        let mut action_tx = Transaction::new(tx3);
+        action_tx.add_orchard_action(OrchardActionDescription::from_parts(
+            Blob::default(),
+            orchard_nf,
+            Blob::default(),
+            Blob::default(),
+            Blob::default(),
+            Blob::default(),
+            Blob::default(),
+        ));
+        zewif.add_transaction(tx3, action_tx).unwrap();
+
+        assert_eq!(zewif.link_spent_notes(), 2);
+        let account = &zewif.wallets()[0].accounts()[0];
+        assert_eq!(account.sapling_received_notes()[0].spent_in(), Some(tx2));
+        assert_eq!(account.orchard_received_notes()[0].spent_in(), Some(tx3));
+        assert_eq!(account.orchard_received_notes()[1].spent_in(), None);
+
+        // Linking again finds nothing new.
+        assert_eq!(zewif.link_spent_notes(), 0);
+    }
+
+    #[test]
+    fn test_shared_transaction_store() {
+        use bc_envelope::prelude::*;
+
+        use crate::{Account, Error, Network, TxId, ZewifWallet};
+
+        let [tx1, tx2, tx3] = [1u8, 2, 3].map(|byte| TxId::from_bytes([byte; 32]));
+        let mut wallet = ZewifWallet::new(Network::Main);
+        for txids in [[tx1, tx2], [tx2, tx3]] {
+            let mut account = Account::new();
+            txids
+                .into_iter()
+                .for_each(|txid| _ = account.add_relevant_transaction(txid));
+            wallet.add_account(account);
+        }
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        for txid in [tx1, tx2] {
+            zewif.add_transaction(txid, Transaction::new(txid)).unwrap();
+        }
+
+        // Adding an identical copy is a no-op; a different one is rejected.
+        zewif.add_transaction(tx2, Transaction::new(tx2)).unwrap();
+        let mut conflicting = Transaction::new(tx2);
+        conflicting.set_mined_height(BlockHeight::from(1_000_000));
+        let error = zewif.add_transaction(tx2, conflicting.clone()).unwrap_err();
+        assert!(matches!(error, Error::ConflictingTransaction { .. }));
+        assert_eq!(zewif.transaction(&tx2), Some(&Transaction::new(tx2)));
+        assert_eq!(
+            zewif.replace_transaction(tx2, conflicting.clone()),
+            Some(Transaction::new(tx2))
+        );
+
+        // The transaction both accounts share is stored and encoded once.
+        let accounts = zewif.wallets()[0].accounts();
+        let resolved: Vec<_> = zewif
+            .account_transactions(&accounts[1])
+            .map(|tx| tx.txid())
+            .collect();
+        assert_eq!(resolved, [tx2]);
+        let envelope = Envelope::from(zewif.clone());
+        assert_eq!(envelope.objects_for_predicate("transaction").len(), 2);
+        assert_eq!(Zewif::try_from(envelope.clone()).unwrap(), zewif);
+
+        // Decoding rejects conflicting copies of a transaction.
+        let envelope = envelope.add_assertion("transaction", Transaction::new(tx2));
+        assert!(matches!(
+            Zewif::try_from(envelope),
+            Err(Error::ConflictingTransaction { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rebuild_transaction_relevance() {
+        use crate::{
+            Account, Amount, Anchor, Blob, Network, TxId, ZewifWallet,
+            sapling::{self, SaplingNullifier, SaplingReceivedNote, SaplingSpendDescription},
+        };
+
+        // tx1 creates a note that tx2 spends; tx3 involves only the account's
+        // transparent addresses.
+        let [tx1, tx2, tx3] = [1u8, 2, 3].map(|byte| TxId::from_bytes([byte; 32]));
+        let nullifier = SaplingNullifier::new([10u8; 32]);
+        let mut note = SaplingReceivedNote::new(
+            tx1,
+            0,
+            sapling::Note::from_parts(
+                Blob::default(),
+                Amount::from_u64(1000).unwrap(),
+                Blob::default(),
+            )
+            .unwrap(),
+        );
+        note.set_nullifier(Some(nullifier));
+        let mut account = Account::new();
+        account.add_sapling_received_note(note);
+        account.add_relevant_transaction(tx3);
+        let mut wallet = ZewifWallet::new(Network::Test);
+        wallet.add_account(account);
+
+        let mut zewif = Zewif::new(BlockHeight::from(100));
+        zewif.add_wallet(wallet);
+        let mut spend_tx = Transaction::new(tx2);
+        spend_tx.add_sapling_spend(SaplingSpendDescription::from_parts(
+            Blob::default(),
+            Anchor::new([0u8; 32]),
+            nullifier,
+            Blob::default(),
+        ));
+        zewif.add_transaction(tx2, spend_tx).unwrap();
+
+        assert_eq!(zewif.rebuild_transaction_relevance(), 2);
+        let account = &zewif.wallets()[0].accounts()[0];
+        assert_eq!(
+            account.relevant_transactions().copied().collect::<Vec<_>>(),
+            [tx1, tx2, tx3]
+        );
+        assert_eq!(account.sapling_received_notes()[0].spent_in(), Some(tx2));
+        assert_eq!(zewif.rebuild_transaction_relevance(), 0);
+    }
+
+    #[test]
+    fn test_rebuild_transparent_relevance() {
+        use crate::{
+            Account, Address, Amount, Data, Network, OutPoint, ProtocolAddress, Script, TxId, TxIn,
+            TxOut, ZewifWallet, transparent,
+        };
+
+        // tx1 pays the account's transparent address, tx2 spends that output,
+        // and tx3 pays elsewhere.
+        let [tx1, tx2, tx3] = [1u8, 2, 3].map(|byte| TxId::from_bytes([byte; 32]));
+        let p2pkh = |hash: u8| {
+            let script = [&[0x76, 0xa9, 0x14][..], &[hash; 20], &[0x88, 0xac]].concat();
+            TxOut::new(
+                Amount::from_u64(1000).unwrap(),
+                Script::from(Data::from_vec(script)),
+            )
+        };
+        let address = p2pkh(1).address(Network::Test).unwrap();
+        let mut account = Account::new();
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new(address),
+        )));
+        let mut wallet = ZewifWallet::new(Network::Test);
+        wallet.add_account(account);
+        let mut zewif = Zewif::new(BlockHeight::from(100));
+        zewif.add_wallet(wallet);
+
+        let mut funding = Transaction::new(tx1);
+        funding.add_transparent_output(p2pkh(2));
+        funding.add_transparent_output(p2pkh(1));
+        let mut spend = Transaction::new(tx2);
+        spend.add_transparent_input(TxIn::new(
+            OutPoint::new(tx1, 1),
+            Script::from(Data::new()),
+            u32::MAX,
+        ));
+        spend.add_transparent_output(p2pkh(2));
+        let mut unrelated = Transaction::new(tx3);
+        unrelated.add_transparent_input(TxIn::new(
+            OutPoint::new(tx1, 0),
+            Script::from(Data::new()),
+            u32::MAX,
+        ));
+        for transaction in [funding, spend, unrelated] {
+            zewif
+                .add_transaction(transaction.txid(), transaction)
+                .unwrap();
+        }
+
+        assert_eq!(zewif.rebuild_transaction_relevance(), 2);
+        let account = &zewif.wallets()[0].accounts()[0];
+        assert_eq!(
+            account.relevant_transactions().copied().collect::<Vec<_>>(),
+            [tx1, tx2]
+        );
+        assert_eq!(zewif.rebuild_transaction_relevance(), 0);
+    }
+
+    #[test]
+    fn test_rebuild_utxo_set() {
+        use crate::{
+            Account, Address, Amount, Data, Network, OutPoint, ProtocolAddress, Script,
+            TransactionStatus, TxId, TxIn, TxOut, ZewifWallet, transparent,
+        };
+
+        let p2pkh = |hash: u8, value: u64| {
+            let script = [&[0x76, 0xa9, 0x14][..], &[hash; 20], &[0x88, 0xac]].concat();
+            TxOut::new(
+                Amount::from_u64(value).unwrap(),
+                Script::from(Data::from_vec(script)),
+            )
+        };
+        let spend_of = |txid: TxId, index: u32| {
+            TxIn::new(
+                OutPoint::new(txid, index),
+                Script::from(Data::new()),
+                u32::MAX,
+            )
+        };
+        let mined = |height: u32| TransactionStatus::Mined {
+            height: BlockHeight::from(height),
+        };
+        let [coinbase, tx1, tx2, tx3, tx4, tx5, tx6] =
+            [1u8, 2, 3, 4, 5, 6, 7].map(|byte| TxId::from_bytes([byte; 32]));
+
+        // The coinbase output is immature at the export height. tx2 spends
+        // the account's output of tx1 and returns change, which the mempool
+        // tx6 would spend. tx3 never confirmed, and tx5, which the wallet
+        // does not record, spends tx4's output.
+        let transactions = [
+            (
+                coinbase,
+                mined(95),
+                vec![spend_of(TxId::from_bytes([0; 32]), u32::MAX)],
+                vec![p2pkh(1, 625_000_000)],
+            ),
+            (
+                tx1,
+                mined(10),
+                vec![],
+                vec![p2pkh(1, 1_000), p2pkh(2, 2_000)],
+            ),
+            (
+                tx2,
+                mined(20),
+                vec![spend_of(tx1, 0)],
+                vec![p2pkh(2, 300), p2pkh(1, 600)],
+            ),
+            (tx3, TransactionStatus::Expired, vec![], vec![p2pkh(1, 300)]),
+            (tx4, mined(30), vec![], vec![p2pkh(1, 700)]),
+            (tx5, mined(40), vec![spend_of(tx4, 0)], vec![p2pkh(2, 600)]),
+            (
+                tx6,
+                TransactionStatus::Mempool,
+                vec![spend_of(tx2, 1)],
+                vec![p2pkh(2, 500)],
+            ),
+        ];
+        let mut account = Account::new();
+        let address = p2pkh(1, 0).address(Network::Test).unwrap();
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new(address),
+        )));
+        for txid in [coinbase, tx1, tx2, tx3, tx4, tx6] {
+            account.add_relevant_transaction(txid);
+        }
+        let mut wallet = ZewifWallet::new(Network::Test);
+        wallet.add_account(account);
+        let mut zewif = Zewif::new(BlockHeight::from(100));
+        zewif.add_wallet(wallet);
+        for (txid, status, inputs, outputs) in transactions {
+            let mut transaction = Transaction::new(txid);
+            transaction.set_status(status);
+            inputs
+                .into_iter()
+                .for_each(|input| transaction.add_transparent_input(input));
+            outputs
+                .into_iter()
+                .for_each(|output| transaction.add_transparent_output(output));
+            zewif.add_transaction(txid, transaction).unwrap();
+        }
+
+        assert_eq!(zewif.rebuild_utxo_set(), [(OutPoint::new(tx4, 0), tx5)]);
+        let account = zewif.wallets()[0].accounts()[0].clone();
+        let utxos = account.utxos();
+        let outpoints: Vec<_> = utxos.iter().map(|utxo| *utxo.outpoint()).collect();
+        assert_eq!(
+            outpoints,
+            [OutPoint::new(coinbase, 0), OutPoint::new(tx2, 1)]
+        );
+        assert!(utxos[0].is_coinbase() && !utxos[0].is_mature(zewif.export_height()));
+        assert_eq!(utxos[0].mined_height(), Some(BlockHeight::from(95)));
+        assert_eq!(utxos[1].value(), Amount::from_u64(600).unwrap());
+
+        // The immature coinbase output is held, but does not count until it
+        // matures.
+        assert_eq!(
+            account.balance().unwrap().transparent(),
+            Amount::from_u64(625_000_600).unwrap()
+        );
+        assert_eq!(
+            zewif.total_balance().unwrap().transparent(),
+            Amount::from_u64(600).unwrap()
+        );
+        zewif.set_export_height(BlockHeight::from(194));
+        assert_eq!(
+            zewif.total_balance().unwrap().transparent(),
+            Amount::from_u64(625_000_600).unwrap()
+        );
+
+        let decoded = Zewif::try_from(bc_envelope::Envelope::from(zewif.clone())).unwrap();
+        assert_eq!(decoded.wallets()[0].accounts()[0].utxos(), utxos);
+    }
+
+    #[test]
+    fn test_validate_checks_seed_material() {
+        use crate::{
+            Bip39Mnemonic, Network, SeedMaterial, ValidationOptions, WalletSeed, ZewifWallet,
+        };
+
+        let wallet_with = |phrase: &str| {
+            let mut wallet = ZewifWallet::new(Network::Main);
+            let seed_material = SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(phrase, None));
+            wallet.add_seed(WalletSeed::new(seed_material).unwrap());
+            wallet
+        };
+
+        let mut zewif = Zewif::new(BlockHeight::from(100));
+        zewif.add_wallet(wallet_with(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        ));
+        assert!(zewif.validate(ValidationOptions::new()).is_ok());
+
+        zewif.add_wallet(wallet_with("not a mnemonic"));
+        let report = zewif.validate(ValidationOptions::new());
+        assert!(report.has_errors());
+        assert_eq!(
+            report.findings()[0].path(),
+            ["Zewif", "Wallet[1]", "WalletSeed[0]"]
+        );
+    }
+
+    #[test]
+    fn test_decode_error_path() {
+        use bc_envelope::prelude::*;
+
+        use crate::{Account, Address, Error, Network, ProtocolAddress, ZewifWallet, transparent};
+
+        let mut address = Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+            "t1abc",
+        )));
+        address.set_name("change".to_string());
+        let address = Envelope::from(address);
+        let name = address.assertion_with_predicate("name").unwrap();
+        let address = address.remove_assertion(name).add_assertion("name", 42);
+
+        let mut account = Account::new();
+        account.set_name("Savings");
+        let account = Envelope::from(account).add_assertion("address", address);
+        let wallet =
+            Envelope::from(ZewifWallet::new(Network::Main)).add_assertion("account", account);
+        let envelope =
+            Envelope::from(Zewif::new(BlockHeight::from(100))).add_assertion("wallet", wallet);
+
+        let error = Zewif::try_from(envelope).unwrap_err();
+        let Error::EnvelopeDecode {
+            path, predicate, ..
+        } = &error
+        else {
+            panic!("unexpected error: {}", error);
+        };
+        assert_eq!(
+            path,
+            &[
+                "Zewif",
+                "Wallet[0]",
+                "Account[\"Savings\"]",
+                "Address[t1abc]"
+            ]
+        );
+        assert_eq!(predicate.as_deref(), Some("name"));
+        assert!(error.to_string().starts_with(
+            "Zewif > Wallet[0] > Account[\"Savings\"] > Address[t1abc]: predicate \"name\""
+        ));
+    }
+
+    #[test]
+    fn test_from_envelope_lenient() {
+        use bc_envelope::prelude::*;
+
+        use crate::{
+            Account, Address, Indexed, Network, ProtocolAddress, ZewifWallet,
+            sapling::{self, SaplingExtendedSpendingKey},
+            transparent,
+        };
+
+        let corrupt_name = |address: Address| {
+            let address = Envelope::from(address);
+            let name = address.assertion_with_predicate("name").unwrap();
+            address.remove_assertion(name).add_assertion("name", 42)
+        };
+        let transparent_address = |index: usize, addr: &str| {
+            let mut address = Address::new(ProtocolAddress::Transparent(
+                transparent::Address::new(addr),
+            ));
+            address.set_index(index);
+            address
+        };
+
+        let mut account = Account::new();
+        account.set_name("Savings");
+        account.add_address(transparent_address(0, "t1first"));
+        account.add_address(transparent_address(1, "t1second"));
+        let account = Envelope::from(account)
+            .add_assertion("address", corrupt_name(transparent_address(2, "t1third")));
+        let wallet =
+            Envelope::from(ZewifWallet::new(Network::Main)).add_assertion("account", account);
+        let envelope = Envelope::from(Zewif::new(BlockHeight::from(100)))
+            .add_assertion("wallet", wallet.clone());
+
+        assert!(Zewif::try_from(envelope.clone()).is_err());
+        let (zewif, issues) = Zewif::from_envelope_lenient(envelope).unwrap();
+        let addresses: Vec<_> = zewif.wallets()[0].accounts()[0]
+            .addresses()
+            .iter()
+            .map(|address| address.as_string())
+            .collect();
+        assert_eq!(addresses, ["t1first", "t1second"]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].path(),
+            ["Zewif", "Wallet[0]", "Account[\"Savings\"]"]
+        );
+        assert_eq!(issues[0].predicate(), "address");
+        assert_eq!(issues[0].position(), Some(2));
+        assert_eq!(issues[0].type_tag(), Some("Address"));
+
+        // An address holding a spending key is never skipped.
+        let mut keyed = sapling::Address::new("zs1keyed".to_string());
+        keyed.set_spending_key(SaplingExtendedSpendingKey::new([0u8; 169]));
+        let keyed = corrupt_name(Address::new(ProtocolAddress::Sapling(Box::new(keyed))));
+        let account = Envelope::from(Account::new()).add_assertion("address", keyed);
+        let wallet =
+            Envelope::from(ZewifWallet::new(Network::Main)).add_assertion("account", account);
+        let envelope =
+            Envelope::from(Zewif::new(BlockHeight::from(100))).add_assertion("wallet", wallet);
+        assert!(Zewif::from_envelope_lenient(envelope).is_err());
+    }
 }
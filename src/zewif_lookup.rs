@@ -0,0 +1,328 @@
+//! Lookups of addresses and accounts across all the wallets of a Zewif,
+//! backed by indexes built on first use.
+
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    sync::OnceLock,
+};
+
+use bc_components::ARID;
+
+use crate::{Account, Address, Zewif, ZewifWallet};
+
+/// The position of an address within a Zewif: the indexes of its wallet, its
+/// account within the wallet, and the address within the account.
+type AddressPosition = (usize, usize, usize);
+
+/// An index built on the first lookup and discarded whenever its owner hands
+/// out mutable access to the items it indexes, so that it never goes stale.
+///
+/// The index is not part of its owner's value: clones start without one, and
+/// it is ignored by comparisons.
+pub(crate) struct LazyIndex<T>(OnceLock<T>);
+
+impl<T> LazyIndex<T> {
+    pub(crate) fn get_or_build(&self, build: impl FnOnce() -> T) -> &T {
+        self.0.get_or_init(build)
+    }
+
+    pub(crate) fn invalidate(&mut self) {
+        self.0.take();
+    }
+}
+
+impl<T> Default for LazyIndex<T> {
+    fn default() -> Self {
+        Self(OnceLock::new())
+    }
+}
+
+impl<T> Clone for LazyIndex<T> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<T> PartialEq for LazyIndex<T> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<T> std::fmt::Debug for LazyIndex<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LazyIndex")
+    }
+}
+
+/// An index of a Zewif's addresses by their string encoding.
+pub(crate) type AddressIndex = LazyIndex<HashMap<String, AddressPosition>>;
+
+fn address_index(zewif: &Zewif) -> HashMap<String, AddressPosition> {
+    let mut index = HashMap::new();
+    for (wallet, account, position, address) in positions(zewif) {
+        // The first occurrence of an address shared by several accounts wins,
+        // as it would for a scan.
+        index
+            .entry(address.as_string())
+            .or_insert((wallet, account, position));
+    }
+    index
+}
+
+/// The positions of a wallet's accounts by identifier, and the identifiers
+/// held by more than one account.
+#[derive(Default)]
+pub(crate) struct AccountIds {
+    pub(crate) positions: HashMap<ARID, usize>,
+    pub(crate) duplicates: Vec<ARID>,
+}
+
+impl AccountIds {
+    pub(crate) fn new(accounts: &[Account]) -> Self {
+        let mut ids = Self::default();
+        for (position, account) in accounts.iter().enumerate() {
+            // As for addresses, the first account with an identifier wins.
+            match ids.positions.entry(account.id()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(position);
+                }
+                Entry::Occupied(_) if !ids.duplicates.contains(&account.id()) => {
+                    ids.duplicates.push(account.id())
+                }
+                Entry::Occupied(_) => {}
+            }
+        }
+        ids
+    }
+}
+
+/// An index of a wallet's accounts by identifier.
+pub(crate) type AccountIndex = LazyIndex<AccountIds>;
+
+fn positions(zewif: &Zewif) -> impl Iterator<Item = (usize, usize, usize, &Address)> {
+    zewif
+        .wallets()
+        .iter()
+        .enumerate()
+        .flat_map(|(wallet_index, wallet)| {
+            wallet
+                .accounts()
+                .iter()
+                .enumerate()
+                .flat_map(move |(account_index, account)| {
+                    account
+                        .addresses()
+                        .iter()
+                        .enumerate()
+                        .map(move |(position, address)| {
+                            (wallet_index, account_index, position, address)
+                        })
+                })
+        })
+}
+
+impl Zewif {
+    /// Finds an address by its string encoding in any account of any wallet,
+    /// returning it with the wallet and account that hold it.
+    ///
+    /// If several accounts hold the address, the first in wallet and account
+    /// order is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{
+    /// #     Account, Address, BlockHeight, Network, ProtocolAddress, Zewif, ZewifWallet,
+    /// #     transparent,
+    /// # };
+    /// let mut account = Account::new();
+    /// let transparent = transparent::Address::new("t1abc");
+    /// account.add_address(Address::new(ProtocolAddress::Transparent(transparent)));
+    /// let mut wallet = ZewifWallet::new(Network::Main);
+    /// wallet.add_account(account);
+    /// let mut zewif = Zewif::new(BlockHeight::from(2_000_000u32));
+    /// zewif.add_wallet(wallet);
+    ///
+    /// let (_, _, address) = zewif.find_address("t1abc").unwrap();
+    /// assert_eq!(address.as_string(), "t1abc");
+    /// assert!(zewif.find_address("t1xyz").is_none());
+    /// ```
+    pub fn find_address(&self, address: &str) -> Option<(&ZewifWallet, &Account, &Address)> {
+        let &(wallet, account, position) = self
+            .address_index()
+            .get_or_build(|| address_index(self))
+            .get(address)?;
+        let wallet = &self.wallets()[wallet];
+        let account = &wallet.accounts()[account];
+        Some((wallet, account, &account.addresses()[position]))
+    }
+
+    /// Returns the account at `account` in the wallet at `wallet`, if any.
+    ///
+    /// Positions change when accounts are reordered; references that must
+    /// survive reordering should use [`Zewif::find_account_by_id`].
+    pub fn find_account(&self, wallet: usize, account: usize) -> Option<&Account> {
+        self.wallets().get(wallet)?.accounts().get(account)
+    }
+
+    /// Returns the account with the given identifier and the wallet holding
+    /// it, if any; see [`ZewifWallet::get_by_id`].
+    pub fn find_account_by_id(&self, id: &ARID) -> Option<(&ZewifWallet, &Account)> {
+        self.wallets()
+            .iter()
+            .find_map(|wallet| Some((wallet, wallet.get_by_id(id)?)))
+    }
+
+    /// Returns the account with the given identifier in any wallet, if any.
+    ///
+    /// Each wallet keeps an index of its accounts by identifier, so a lookup
+    /// takes time proportional to the number of wallets rather than of
+    /// accounts. If several accounts share the identifier, the first in
+    /// wallet and account order is returned; [`Zewif::validate`] reports
+    /// such duplicates.
+    pub fn get_by_id(&self, id: &ARID) -> Option<&Account> {
+        self.wallets()
+            .iter()
+            .find_map(|wallet| wallet.get_by_id(id))
+    }
+
+    /// Returns the account with the given identifier in any wallet, for
+    /// modification; see [`Zewif::get_by_id`].
+    pub fn get_mut_by_id(&mut self, id: &ARID) -> Option<&mut Account> {
+        self.wallets_mut()
+            .iter_mut()
+            .find_map(|wallet| wallet.get_mut_by_id(id))
+    }
+
+    /// Returns every address in the Zewif with the indexes of the wallet and
+    /// account that hold it, in wallet and account order.
+    pub fn addresses(&self) -> impl Iterator<Item = (usize, usize, &Address)> {
+        positions(self).map(|(wallet, account, _, address)| (wallet, account, address))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::ARID;
+
+    use crate::{
+        Account, Address, BlockHeight, Indexed, Network, ProtocolAddress, Zewif, ZewifWallet,
+        transparent,
+    };
+
+    fn address(string: &str) -> Address {
+        Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+            string,
+        )))
+    }
+
+    fn zewif() -> Zewif {
+        let mut wallet = ZewifWallet::new(Network::Main);
+        for addresses in [["t1a", "t1b"], ["t1c", "t1d"]] {
+            let mut account = Account::new();
+            addresses
+                .into_iter()
+                .for_each(|string| account.add_address(address(string)));
+            wallet.add_account(account);
+        }
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        zewif
+    }
+
+    #[test]
+    fn test_lookups() {
+        let zewif = zewif();
+        let (wallet, account, found) = zewif.find_address("t1d").unwrap();
+        assert_eq!(wallet.network(), Network::Main);
+        assert_eq!(account, &zewif.wallets()[0].accounts()[1]);
+        assert_eq!(found.as_string(), "t1d");
+        assert!(zewif.find_address("t1z").is_none());
+
+        assert_eq!(
+            zewif.find_account(0, 1),
+            Some(&zewif.wallets()[0].accounts()[1])
+        );
+        assert_eq!(zewif.find_account(1, 0), None);
+
+        let addresses: Vec<_> = zewif
+            .addresses()
+            .map(|(w, a, address)| (w, a, address.as_string()))
+            .collect();
+        assert_eq!(
+            addresses,
+            [
+                (0, 0, "t1a".to_string()),
+                (0, 0, "t1b".to_string()),
+                (0, 1, "t1c".to_string()),
+                (0, 1, "t1d".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_index_refreshes_after_mutation() {
+        let mut zewif = zewif();
+        assert!(zewif.find_address("t1e").is_none());
+
+        zewif.wallets_mut()[0].accounts_mut()[0].add_address(address("t1e"));
+        let (_, account, _) = zewif.find_address("t1e").unwrap();
+        assert_eq!(account.addresses_len(), 3);
+
+        zewif.wallets_mut()[0].remove_account(0);
+        assert!(zewif.find_address("t1a").is_none());
+        assert_eq!(zewif.find_address("t1c").unwrap().2.as_string(), "t1c");
+
+        let mut wallet = ZewifWallet::new(Network::Test);
+        let mut account = Account::new();
+        account.add_address(address("tmNew"));
+        wallet.add_account(account);
+        zewif.add_wallet(wallet);
+        assert_eq!(
+            zewif.find_address("tmNew").unwrap().0.network(),
+            Network::Test
+        );
+
+        // A clone builds its own index.
+        let clone = zewif.clone();
+        assert_eq!(clone, zewif);
+        assert!(clone.find_address("tmNew").is_some());
+    }
+
+    #[test]
+    fn test_lookup_by_id() {
+        let mut zewif = zewif();
+        let mut wallet = ZewifWallet::new(Network::Test);
+        (0..3_000).for_each(|_| wallet.add_account(Account::new()));
+        zewif.add_wallet(wallet);
+        let ids: Vec<_> = zewif.wallets()[1]
+            .accounts()
+            .iter()
+            .map(Account::id)
+            .collect();
+        for (index, id) in ids.iter().enumerate() {
+            assert_eq!(zewif.get_by_id(id).map(Indexed::index), Some(index));
+        }
+        let first = zewif.wallets()[0].accounts()[1].id();
+        assert_eq!(
+            zewif.find_account_by_id(&first).unwrap().1.addresses_len(),
+            2
+        );
+        assert!(zewif.get_by_id(&ARID::new()).is_none());
+
+        zewif.get_mut_by_id(&ids[7]).unwrap().set_name("Seventh");
+        assert_eq!(zewif.wallets()[1].accounts()[7].name(), "Seventh");
+
+        // Removing an account shifts the positions of those after it.
+        let wallet = &mut zewif.wallets_mut()[1];
+        wallet.remove_account(0);
+        assert_eq!(wallet.get_by_id(&ids[7]).unwrap().name(), "Seventh");
+        assert!(wallet.get_by_id(&ids[0]).is_none());
+        assert!(wallet.duplicate_account_ids().is_empty());
+
+        let account = wallet.accounts()[0].clone();
+        wallet.add_account(account);
+        assert_eq!(wallet.duplicate_account_ids(), [ids[1]]);
+        assert_eq!(wallet.get_by_id(&ids[1]).unwrap().index(), 0);
+    }
+}
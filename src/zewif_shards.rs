@@ -0,0 +1,189 @@
+//! Splitting an encrypted Zewif into SSKR shares, and recovering it from a
+//! sufficient subset of them.
+
+use std::collections::{HashMap, HashSet};
+
+use bc_components::{SSKRShare, SSKRSpec, SymmetricKey, sskr_combine};
+use bc_envelope::prelude::*;
+
+use crate::{Error, ExportMetadata, Result, Zewif, ZewifEnvelope};
+
+impl Zewif {
+    /// Encrypts the Zewif with a random content key and splits that key into
+    /// SSKR shares as described by `spec`, returning one envelope per share,
+    /// group by group.
+    ///
+    /// Each share carries the complete encrypted Zewif, its ID, and its
+    /// [`ExportMetadata`], but no single share reveals the content key; any
+    /// subset of shares meeting the spec's thresholds recovers the Zewif with
+    /// [`Zewif::from_shards`].
+    pub fn shard(&self, spec: SSKRSpec) -> Result<Vec<Envelope>> {
+        let content_key = SymmetricKey::new();
+        let mut envelope = ZewifEnvelope::new(Envelope::from(self.clone()))?;
+        envelope.encrypt(&content_key)?;
+        let envelope =
+            ExportMetadata::for_export(self).add_to_envelope(envelope.envelope().clone());
+        Ok(envelope.sskr_split_flattened(&spec, &content_key)?)
+    }
+
+    /// Recovers a Zewif from shares produced by [`Zewif::shard`].
+    ///
+    /// # Errors
+    /// - [`Error::InsufficientShares`] if the shares do not meet the
+    ///   thresholds of the spec they were created with.
+    /// - [`Error::MixedShares`] if the shares come from more than one call to
+    ///   [`Zewif::shard`].
+    /// - [`Error::InvalidShare`] if a share is not a Zewif share, or its
+    ///   encrypted content is corrupted.
+    pub fn from_shards(shares: &[Envelope]) -> Result<Zewif> {
+        let first = shares.first().ok_or(Error::InsufficientShares)?;
+        let invalid = |index, reason: &dyn std::fmt::Display| Error::InvalidShare {
+            index,
+            reason: reason.to_string(),
+        };
+
+        let mut sskr_shares = Vec::with_capacity(shares.len());
+        let mut ids = HashSet::new();
+        for (index, share) in shares.iter().enumerate() {
+            let envelope = ZewifEnvelope::new(share.clone()).map_err(|e| invalid(index, &e))?;
+            let sskr_share: SSKRShare = share
+                .extract_object_for_predicate(known_values::SSKR_SHARE)
+                .map_err(|e| invalid(index, &e))?;
+            ids.insert((envelope.id(), sskr_share.identifier()));
+            sskr_shares.push(sskr_share);
+        }
+        if ids.len() > 1 {
+            return Err(Error::MixedShares);
+        }
+        if !meets_thresholds(&sskr_shares) {
+            return Err(Error::InsufficientShares);
+        }
+
+        let secret = sskr_combine(&sskr_shares).map_err(|e| Error::Context {
+            message: "Cannot combine SSKR shares".into(),
+            source: Box::new(e),
+        })?;
+        let content_key = SymmetricKey::from_data_ref(secret.data()).map_err(|e| invalid(0, &e))?;
+
+        // Every share carries the same encrypted content; one that differs
+        // has been corrupted.
+        let content = first.object_for_predicate("content")?;
+        for (index, share) in shares.iter().enumerate().skip(1) {
+            if !share
+                .object_for_predicate("content")
+                .is_ok_and(|other| other.is_identical_to(&content))
+            {
+                return Err(invalid(index, &"encrypted content differs from share 0"));
+            }
+        }
+        let mut envelope = ZewifEnvelope::new(first.clone())?;
+        envelope
+            .decrypt(&content_key)
+            .map_err(|_| invalid(0, &"encrypted content cannot be decrypted"))?;
+        Zewif::try_from(envelope.envelope().clone())
+    }
+}
+
+/// Returns whether the shares, which must come from one sharding operation,
+/// include enough members of enough groups to recover the secret.
+fn meets_thresholds(shares: &[SSKRShare]) -> bool {
+    let mut members: HashMap<usize, HashSet<usize>> = HashMap::new();
+    for share in shares {
+        members
+            .entry(share.group_index())
+            .or_default()
+            .insert(share.member_index());
+    }
+    let Some(first) = shares.first() else {
+        return false;
+    };
+    let groups_met = members
+        .iter()
+        .filter(|(group, group_members)| {
+            shares
+                .iter()
+                .find(|share| share.group_index() == **group)
+                .is_some_and(|share| group_members.len() >= share.member_threshold())
+        })
+        .count();
+    groups_met >= first.group_threshold()
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::{SSKRGroupSpec, SSKRSpec};
+    use bc_envelope::prelude::*;
+
+    use crate::{Error, ExportMetadata, RandomInstance, Zewif};
+
+    fn two_of_three() -> SSKRSpec {
+        SSKRSpec::new(1, vec![SSKRGroupSpec::new(2, 3).unwrap()]).unwrap()
+    }
+
+    #[test]
+    fn test_shard_two_of_three() {
+        let zewif = Zewif::random();
+        let shares = zewif.shard(two_of_three()).unwrap();
+        assert_eq!(shares.len(), 3);
+
+        for (a, b) in [(0, 1), (0, 2), (1, 2)] {
+            let pair = [shares[a].clone(), shares[b].clone()];
+            assert_eq!(Zewif::from_shards(&pair).unwrap(), zewif);
+        }
+        assert_eq!(Zewif::from_shards(&shares).unwrap(), zewif);
+
+        // Each share identifies the export without revealing the wallet.
+        let metadata = ExportMetadata::from_envelope(&shares[0]).unwrap();
+        assert_eq!(metadata.export_height(), zewif.export_height());
+        assert!(shares[0].objects_for_predicate("wallet").is_empty());
+    }
+
+    #[test]
+    fn test_shard_errors() {
+        let zewif = Zewif::random();
+        let shares = zewif.shard(two_of_three()).unwrap();
+
+        assert!(matches!(
+            Zewif::from_shards(&[]),
+            Err(Error::InsufficientShares)
+        ));
+        assert!(matches!(
+            Zewif::from_shards(&shares[..1]),
+            Err(Error::InsufficientShares)
+        ));
+        // A repeated share does not count twice.
+        let repeated = [shares[0].clone(), shares[0].clone()];
+        assert!(matches!(
+            Zewif::from_shards(&repeated),
+            Err(Error::InsufficientShares)
+        ));
+
+        // Shares of the same Zewif from another sharding cannot be mixed in.
+        let other = zewif.shard(two_of_three()).unwrap();
+        let mixed = [shares[0].clone(), other[1].clone()];
+        assert!(matches!(
+            Zewif::from_shards(&mixed),
+            Err(Error::MixedShares)
+        ));
+
+        // A share without its SSKR share, or with altered content, is invalid.
+        let share = shares[1]
+            .assertions_with_predicate(known_values::SSKR_SHARE)
+            .remove(0);
+        let stripped = [shares[0].clone(), shares[1].remove_assertion(share)];
+        assert!(matches!(
+            Zewif::from_shards(&stripped),
+            Err(Error::InvalidShare { index: 1, .. })
+        ));
+
+        let content = shares[2].assertion_with_predicate("content").unwrap();
+        let corrupted = shares[2]
+            .remove_assertion(content)
+            .add_assertion("content", Envelope::new("corrupted"));
+        let corrupted = [shares[0].clone(), corrupted];
+        assert!(matches!(
+            Zewif::from_shards(&corrupted),
+            Err(Error::InvalidShare { index: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,487 @@
+use std::fmt;
+
+use crate::{
+    Amount, BlockHeight, ProtocolAddress, SeedFingerprint, SeedMaterial, WalletProvenance, Zewif,
+    format_signed_zats_as_zec,
+};
+
+/// The number of addresses of each protocol; see [`ZewifSummary`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct AddressCounts {
+    transparent: usize,
+    sprout: usize,
+    sapling: usize,
+    unified: usize,
+}
+
+impl AddressCounts {
+    pub fn transparent(&self) -> usize {
+        self.transparent
+    }
+
+    pub fn sprout(&self) -> usize {
+        self.sprout
+    }
+
+    pub fn sapling(&self) -> usize {
+        self.sapling
+    }
+
+    pub fn unified(&self) -> usize {
+        self.unified
+    }
+
+    pub fn total(&self) -> usize {
+        self.transparent + self.sprout + self.sapling + self.unified
+    }
+}
+
+/// The received notes of one shielded pool; see [`ZewifSummary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoolSummary {
+    note_count: usize,
+    unspent_count: usize,
+    unspent_value: Option<Amount>,
+}
+
+impl Default for PoolSummary {
+    fn default() -> Self {
+        Self {
+            note_count: 0,
+            unspent_count: 0,
+            unspent_value: Some(Amount::zero()),
+        }
+    }
+}
+
+impl PoolSummary {
+    fn add(&mut self, value: Amount, spent: bool) {
+        self.note_count += 1;
+        if !spent {
+            self.unspent_count += 1;
+            self.unspent_value = self.unspent_value.and_then(|total| total + value);
+        }
+    }
+
+    pub fn note_count(&self) -> usize {
+        self.note_count
+    }
+
+    /// Returns the number of notes with no recorded spending transaction.
+    pub fn unspent_count(&self) -> usize {
+        self.unspent_count
+    }
+
+    /// Returns the total value of the unspent notes, or `None` if it
+    /// overflows the valid range of amounts.
+    pub fn unspent_value(&self) -> Option<Amount> {
+        self.unspent_value
+    }
+}
+
+impl fmt::Display for PoolSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} notes, {} unspent",
+            self.note_count, self.unspent_count
+        )?;
+        match self.unspent_value {
+            Some(value) => write!(f, " ({})", format_signed_zats_as_zec(value)),
+            None => write!(f, " (overflow)"),
+        }
+    }
+}
+
+/// Counts and totals describing the contents of a Zewif, for showing a user
+/// what was found in their wallet before migrating it; see
+/// [`Zewif::summary`].
+///
+/// As with [`Balance`](crate::Balance), a note counts as unspent until its
+/// spending transaction is recorded, so spends should first be linked with
+/// [`Zewif::link_spent_notes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZewifSummary {
+    wallet_count: usize,
+    account_count: usize,
+    addresses: AddressCounts,
+    transaction_count: usize,
+    height_range: Option<(BlockHeight, BlockHeight)>,
+    sprout: PoolSummary,
+    sapling: PoolSummary,
+    orchard: PoolSummary,
+    seed_count: usize,
+    seed_fingerprints: Vec<SeedFingerprint>,
+    has_encrypted_material: bool,
+    has_watch_only_keys: bool,
+    orphaned_record_count: usize,
+    sources: Vec<WalletProvenance>,
+}
+
+impl ZewifSummary {
+    pub fn wallet_count(&self) -> usize {
+        self.wallet_count
+    }
+
+    pub fn account_count(&self) -> usize {
+        self.account_count
+    }
+
+    pub fn addresses(&self) -> &AddressCounts {
+        &self.addresses
+    }
+
+    pub fn transaction_count(&self) -> usize {
+        self.transaction_count
+    }
+
+    /// Returns the lowest and highest heights at which the transactions were
+    /// mined, or `None` if no transaction records its mined height.
+    pub fn height_range(&self) -> Option<(BlockHeight, BlockHeight)> {
+        self.height_range
+    }
+
+    pub fn sprout(&self) -> &PoolSummary {
+        &self.sprout
+    }
+
+    pub fn sapling(&self) -> &PoolSummary {
+        &self.sapling
+    }
+
+    pub fn orchard(&self) -> &PoolSummary {
+        &self.orchard
+    }
+
+    pub fn seed_count(&self) -> usize {
+        self.seed_count
+    }
+
+    /// Returns the known fingerprints of the seeds, without duplicates.
+    pub fn seed_fingerprints(&self) -> &[SeedFingerprint] {
+        &self.seed_fingerprints
+    }
+
+    /// Returns whether any seed is encrypted, and so cannot be used until it
+    /// is decrypted with the source wallet's passphrase.
+    pub fn has_encrypted_material(&self) -> bool {
+        self.has_encrypted_material
+    }
+
+    /// Returns whether any address holds a viewing key without its spending
+    /// key, or any seed was redacted from a watch-only export.
+    pub fn has_watch_only_keys(&self) -> bool {
+        self.has_watch_only_keys
+    }
+
+    /// Returns the number of records the importers could not attribute to any
+    /// account; see [`OrphanedData`](crate::OrphanedData).
+    pub fn orphaned_record_count(&self) -> usize {
+        self.orphaned_record_count
+    }
+
+    /// Returns the provenance recorded by each wallet that has one, in wallet
+    /// order.
+    pub fn sources(&self) -> &[WalletProvenance] {
+        &self.sources
+    }
+}
+
+impl fmt::Display for ZewifSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let addresses = &self.addresses;
+        writeln!(
+            f,
+            "{} wallets, {} accounts, {} seeds, {} addresses ({} transparent, {} Sprout, {} Sapling, {} unified)",
+            self.wallet_count,
+            self.account_count,
+            self.seed_count,
+            addresses.total(),
+            addresses.transparent,
+            addresses.sprout,
+            addresses.sapling,
+            addresses.unified
+        )?;
+        write!(f, "{} transactions", self.transaction_count)?;
+        if let Some((lowest, highest)) = self.height_range {
+            write!(f, " mined at heights {}..={}", lowest, highest)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "Sprout: {}", self.sprout)?;
+        writeln!(f, "Sapling: {}", self.sapling)?;
+        write!(f, "Orchard: {}", self.orchard)?;
+        if self.has_encrypted_material {
+            write!(f, "\nholds encrypted seeds")?;
+        }
+        if self.has_watch_only_keys {
+            write!(f, "\nholds watch-only keys")?;
+        }
+        if self.orphaned_record_count > 0 {
+            write!(f, "\nholds {} orphaned records", self.orphaned_record_count)?;
+        }
+        for source in &self.sources {
+            write!(f, "\nsource: {}", source)?;
+        }
+        Ok(())
+    }
+}
+
+impl Zewif {
+    /// Summarizes the contents of the Zewif.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{BlockHeight, Zewif};
+    /// let summary = Zewif::new(BlockHeight::from(2_000_000u32)).summary();
+    /// assert_eq!(summary.wallet_count(), 0);
+    /// assert_eq!(summary.height_range(), None);
+    /// ```
+    pub fn summary(&self) -> ZewifSummary {
+        let mut summary = ZewifSummary {
+            wallet_count: self.wallets_len(),
+            account_count: 0,
+            addresses: AddressCounts::default(),
+            transaction_count: self.transactions().len(),
+            height_range: None,
+            sprout: PoolSummary::default(),
+            sapling: PoolSummary::default(),
+            orchard: PoolSummary::default(),
+            seed_count: 0,
+            seed_fingerprints: Vec::new(),
+            has_encrypted_material: false,
+            has_watch_only_keys: false,
+            orphaned_record_count: self
+                .wallets()
+                .iter()
+                .map(|wallet| wallet.orphaned_data().len())
+                .sum(),
+            sources: self
+                .wallets()
+                .iter()
+                .filter_map(|wallet| wallet.provenance().cloned())
+                .collect(),
+        };
+
+        let heights = self
+            .transactions()
+            .values()
+            .filter_map(|transaction| transaction.mined_height().copied());
+        summary.height_range = heights.fold(None, |range, height| match range {
+            None => Some((height, height)),
+            Some((lowest, highest)) => Some((lowest.min(height), highest.max(height))),
+        });
+
+        for wallet in self.wallets() {
+            for seed in wallet.seeds() {
+                summary.seed_count += 1;
+                if let Some(fingerprint) = seed
+                    .fingerprint()
+                    .filter(|f| !summary.seed_fingerprints.contains(f))
+                {
+                    summary.seed_fingerprints.push(*fingerprint);
+                }
+                match seed.seed_material() {
+                    SeedMaterial::Encrypted(_) => summary.has_encrypted_material = true,
+                    SeedMaterial::Redacted => summary.has_watch_only_keys = true,
+                    _ => {}
+                }
+            }
+
+            for account in wallet.accounts() {
+                summary.account_count += 1;
+                for address in account.addresses() {
+                    let (count, watch_only) = match address.address() {
+                        ProtocolAddress::Transparent(_) => {
+                            (&mut summary.addresses.transparent, false)
+                        }
+                        ProtocolAddress::Sprout(address) => (
+                            &mut summary.addresses.sprout,
+                            address.viewing_key().is_some() && address.spending_key().is_none(),
+                        ),
+                        ProtocolAddress::Sapling(address) => (
+                            &mut summary.addresses.sapling,
+                            (address.incoming_viewing_key().is_some()
+                                || address.full_viewing_key().is_some())
+                                && address.spending_key().is_none(),
+                        ),
+                        ProtocolAddress::Unified(_) => (&mut summary.addresses.unified, false),
+                    };
+                    *count += 1;
+                    summary.has_watch_only_keys |= watch_only;
+                }
+
+                for note in account.sprout_received_notes() {
+                    summary
+                        .sprout
+                        .add(note.note().value(), note.spent_in().is_some());
+                }
+                for note in account.sapling_received_notes() {
+                    summary
+                        .sapling
+                        .add(note.note().value(), note.spent_in().is_some());
+                }
+                for note in account.orchard_received_notes() {
+                    summary
+                        .orchard
+                        .add(note.note().value(), note.spent_in().is_some());
+                }
+            }
+        }
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Account, Address, Amount, Bip39Mnemonic, Blob, BlockHeight, Data, EncryptedSeed, Network,
+        OrphanedRecord, ProtocolAddress, SecondsSinceEpoch, SeedMaterial, Transaction, TxId,
+        UnifiedAddress, WalletCryptoParams, WalletProvenance, WalletSeed, Zewif, ZewifWallet,
+        orchard, sapling, transparent,
+    };
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn zats(value: i64) -> Amount {
+        Amount::from_i64(value).unwrap()
+    }
+
+    /// A wallet with transparent, Sapling, and unified addresses, Sapling and
+    /// Orchard notes, and a second, encrypted seed.
+    fn zewif() -> Zewif {
+        let txid = |byte| TxId::from_bytes([byte; 32]);
+        let mut account = Account::new();
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new("t1summary"),
+        )));
+        let mut watched = sapling::Address::new("zs1summary".to_string());
+        watched.set_incoming_viewing_key(sapling::SaplingIncomingViewingKey::new([3; 32]));
+        account.add_address(Address::new(ProtocolAddress::Sapling(Box::new(watched))));
+        account.add_address(Address::new(ProtocolAddress::Unified(Box::new(
+            UnifiedAddress::new("u1summary".to_string()),
+        ))));
+
+        let recipient = Blob::new([0; 43]);
+        for (output, value, spent_in) in [(0, 150_000_000, None), (1, 25_000_000, Some(txid(3)))] {
+            let note =
+                sapling::Note::from_parts(recipient.clone(), zats(value), Blob::new([0; 32]))
+                    .unwrap();
+            let mut note = sapling::SaplingReceivedNote::new(txid(1), output, note);
+            note.set_spent_in(spent_in);
+            account.add_sapling_received_note(note);
+        }
+        let note = orchard::Note::from_parts(
+            recipient,
+            zats(5_000),
+            Blob::new([0; 32]),
+            Blob::new([0; 32]),
+        )
+        .unwrap();
+        account.add_orchard_received_note(orchard::OrchardReceivedNote::new(txid(2), 0, note));
+
+        let mut provenance = WalletProvenance::new("zcashd");
+        provenance.set_source_version(Some("5.9.1".to_string()));
+        provenance.set_exported_at(Some(SecondsSinceEpoch::from(1_730_592_000)));
+        let mut wallet = ZewifWallet::new(Network::Main).with_provenance(provenance);
+        wallet.add_seed(
+            WalletSeed::new(SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(
+                MNEMONIC, None,
+            )))
+            .unwrap(),
+        );
+        let params = WalletCryptoParams::new(
+            Data::from_vec(vec![1; 48]),
+            Data::from_vec(vec![2; 8]),
+            0,
+            25_000,
+        );
+        let encrypted = EncryptedSeed::new(Data::from_vec(vec![4; 64]), params, None);
+        wallet.add_seed(WalletSeed::new(SeedMaterial::Encrypted(encrypted)).unwrap());
+        wallet.add_account(account);
+        wallet.add_account(Account::new());
+        wallet.add_orphaned_record(OrphanedRecord::Raw {
+            key: Data::from_vec(vec![5; 4]),
+            value: Data::from_vec(vec![6; 4]),
+            description: "unknown record".to_string(),
+        });
+
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        for (byte, height) in [(1, Some(1_000_100)), (2, Some(1_900_000)), (3, None)] {
+            let mut transaction = Transaction::new(txid(byte));
+            if let Some(height) = height {
+                transaction.set_mined_height(BlockHeight::from(height));
+            }
+            zewif.add_transaction(txid(byte), transaction).unwrap();
+        }
+        zewif
+    }
+
+    #[test]
+    fn test_summary() {
+        let zewif = zewif();
+        let summary = zewif.summary();
+        assert_eq!(summary.wallet_count(), 1);
+        assert_eq!(summary.account_count(), 2);
+        let addresses = summary.addresses();
+        assert_eq!(
+            (
+                addresses.transparent(),
+                addresses.sprout(),
+                addresses.sapling(),
+                addresses.unified()
+            ),
+            (1, 0, 1, 1)
+        );
+        assert_eq!(summary.transaction_count(), 3);
+        assert_eq!(
+            summary.height_range(),
+            Some((BlockHeight::from(1_000_100), BlockHeight::from(1_900_000)))
+        );
+
+        assert_eq!(summary.sprout().note_count(), 0);
+        assert_eq!(summary.sapling().note_count(), 2);
+        assert_eq!(summary.sapling().unspent_count(), 1);
+        assert_eq!(summary.sapling().unspent_value(), Some(zats(150_000_000)));
+        assert_eq!(summary.orchard().unspent_value(), Some(zats(5_000)));
+
+        assert_eq!(summary.seed_count(), 2);
+        let mnemonic_seed = zewif.wallets()[0].seeds()[0].fingerprint().copied();
+        assert_eq!(summary.seed_fingerprints(), mnemonic_seed.as_slice());
+        assert!(summary.has_encrypted_material());
+        assert!(summary.has_watch_only_keys());
+        assert_eq!(summary.orphaned_record_count(), 1);
+
+        assert_eq!(
+            summary.to_string(),
+            "1 wallets, 2 accounts, 2 seeds, 3 addresses (1 transparent, 0 Sprout, 1 Sapling, 1 unified)\n\
+             3 transactions mined at heights 1000100..=1900000\n\
+             Sprout: 0 notes, 0 unspent (ZEC 0.0)\n\
+             Sapling: 2 notes, 1 unspent (ZEC 1.5)\n\
+             Orchard: 1 notes, 1 unspent (ZEC 0.00005)\n\
+             holds encrypted seeds\n\
+             holds watch-only keys\n\
+             holds 1 orphaned records\n\
+             source: zcashd 5.9.1, exported 2024-11-03"
+        );
+        assert_eq!(
+            summary.sources(),
+            [zewif.wallets()[0].provenance().unwrap().clone()]
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_summary_json() {
+        let summary = zewif().summary();
+        let json = serde_json::to_string(&summary).unwrap();
+        assert_eq!(
+            serde_json::from_str::<crate::ZewifSummary>(&json).unwrap(),
+            summary
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Encoding a Zewif as a Uniform Resource (UR), as a single `ur:zewif`
+//! string or as a multi-part, fountain-coded sequence for animated QR codes.
+
+use bc_envelope::prelude::*;
+
+use crate::{Error, Result, Zewif};
+
+impl Zewif {
+    /// The type of the URs of a Zewif, as in `ur:zewif/...`.
+    pub const UR_TYPE: &'static str = "zewif";
+
+    /// Returns the Zewif as a single-part UR.
+    ///
+    /// The UR holds the untagged CBOR of the Zewif's envelope; see
+    /// [`Zewif::to_ur_parts`] for a sequence of parts small enough for QR
+    /// codes.
+    pub fn to_ur(&self) -> UR {
+        UR::new(Self::UR_TYPE, Envelope::from(self.clone()).untagged_cbor())
+            .expect("UR type is valid")
+    }
+
+    /// Decodes a Zewif from a UR returned by [`Zewif::to_ur`] or recovered by
+    /// a [`ZewifURDecoder`].
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidUR`] if the UR is not of type `zewif`, or an
+    /// envelope error if it does not hold a Zewif envelope.
+    pub fn from_ur(ur: &UR) -> Result<Self> {
+        ur.check_type(Self::UR_TYPE).map_err(|e| Error::InvalidUR {
+            reason: e.to_string(),
+        })?;
+        Zewif::try_from(Envelope::from_untagged_cbor(ur.cbor())?)
+    }
+
+    /// Returns the Zewif as a multi-part UR of fragments no longer than
+    /// `max_fragment_len` bytes, one `ur:zewif/<index>-<count>/...` string
+    /// per fragment. The parts can be received in any order by a
+    /// [`ZewifURDecoder`].
+    ///
+    /// # Panics
+    /// Panics if `max_fragment_len` is zero.
+    pub fn to_ur_parts(&self, max_fragment_len: usize) -> Vec<String> {
+        let ur = self.to_ur();
+        let mut encoder =
+            MultipartEncoder::new(&ur, max_fragment_len).expect("fragment length is not zero");
+        (0..encoder.parts_count())
+            .map(|_| encoder.next_part().expect("fragment is encodable"))
+            .collect()
+    }
+}
+
+/// Reassembles a Zewif from the parts of a multi-part UR returned by
+/// [`Zewif::to_ur_parts`], such as those scanned from an animated QR code.
+///
+/// Parts may be received in any order, and a part received more than once
+/// is ignored after the first time.
+#[derive(Default)]
+pub struct ZewifURDecoder {
+    decoder: MultipartDecoder,
+}
+
+impl ZewifURDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receives one part of the UR.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidUR`] if the part is malformed, or belongs to a
+    /// different UR than the parts already received.
+    pub fn receive(&mut self, part: &str) -> Result<()> {
+        self.decoder
+            .receive(&part.to_lowercase())
+            .map_err(|e| Error::InvalidUR {
+                reason: e.to_string(),
+            })
+    }
+
+    /// Returns whether enough parts have been received to decode the Zewif.
+    pub fn is_complete(&self) -> bool {
+        self.decoder.is_complete()
+    }
+
+    /// Returns the decoded Zewif, or `None` if more parts are needed.
+    ///
+    /// # Errors
+    /// Returns an error if the reassembled UR does not hold a Zewif; see
+    /// [`Zewif::from_ur`].
+    pub fn zewif(&self) -> Result<Option<Zewif>> {
+        let ur = self.decoder.message().map_err(|e| Error::InvalidUR {
+            reason: e.to_string(),
+        })?;
+        ur.as_ref().map(Zewif::from_ur).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+    use rand::seq::SliceRandom;
+
+    use crate::{BlockHeight, Data, Error, Transaction, TxId, Zewif, ZewifURDecoder};
+
+    fn large_zewif() -> Zewif {
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        for byte in 0..20u8 {
+            let txid = TxId::from_bytes([byte; 32]);
+            let mut transaction = Transaction::new(txid);
+            transaction.set_raw(Data::from_vec(vec![byte; 200]));
+            zewif.add_transaction(txid, transaction).unwrap();
+        }
+        zewif
+    }
+
+    #[test]
+    fn test_single_part_ur() {
+        let zewif = large_zewif();
+        let ur = zewif.to_ur();
+        assert!(ur.string().starts_with("ur:zewif/"));
+        let parsed = UR::from_ur_string(ur.qr_string()).unwrap();
+        assert_eq!(Zewif::from_ur(&parsed).unwrap(), zewif);
+
+        let other = UR::new("bytes", CBOR::from(vec![0u8; 4])).unwrap();
+        assert!(matches!(
+            Zewif::from_ur(&other),
+            Err(Error::InvalidUR { .. })
+        ));
+    }
+
+    #[test]
+    fn test_multi_part_ur() {
+        let zewif = large_zewif();
+        let mut parts = zewif.to_ur_parts(200);
+        assert!(parts.len() >= 10);
+        assert!(parts.iter().all(|part| part.starts_with("ur:zewif/")));
+
+        // Receive every part in a random order, some of them twice.
+        parts.extend(parts[..3].to_vec());
+        parts.shuffle(&mut rand::rng());
+        let mut decoder = ZewifURDecoder::new();
+        for part in &parts[..parts.len() - 1] {
+            decoder.receive(part).unwrap();
+        }
+        decoder
+            .receive(&parts[parts.len() - 1].to_uppercase())
+            .unwrap();
+        assert!(decoder.is_complete());
+        assert_eq!(decoder.zewif().unwrap(), Some(zewif));
+    }
+
+    #[test]
+    fn test_incomplete_ur() {
+        let parts = large_zewif().to_ur_parts(200);
+        let mut decoder = ZewifURDecoder::new();
+        decoder.receive(&parts[0]).unwrap();
+        assert!(!decoder.is_complete());
+        assert!(decoder.zewif().unwrap().is_none());
+
+        // Parts of another UR cannot be mixed in.
+        let other = Zewif::new(BlockHeight::from(1)).to_ur_parts(10);
+        assert!(matches!(
+            decoder.receive(&other[0]),
+            Err(Error::InvalidUR { .. })
+        ));
+    }
+}
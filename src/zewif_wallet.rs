@@ -1,14 +1,22 @@
-use super::Network;
-use super::{Account, SeedMaterial};
+use super::Account;
+use super::{Network, NetworkMismatch, NetworkParameters};
 use crate::{
-    Indexed, NoQuotesDebugOption, envelope_indexed_objects_for_predicate,
+    Balance, BlockHeight, DerivationPath, Error, Indexed, KeyOrigin, KeyPool, KeyPoolSummary,
+    OrphanedData, OrphanedRecord, ProtocolAddress, Result, SeedFingerprint, SeedMaterial,
+    WalletProvenance, WalletSeed,
+    envelope_decoder::{EnvelopeDecoder, segment},
+    orchard::{OrchardAnchorRegistry, OrchardTreeState},
+    sapling::{SaplingAnchorRegistry, SaplingTreeState},
+    zewif_lookup::{AccountIds, AccountIndex},
 };
+use bc_components::ARID;
 use bc_envelope::prelude::*;
+use std::collections::HashSet;
 
 /// A complete Zcash wallet with multiple accounts and cryptographic key material.
 ///
 /// `ZewifWallet` represents an entire wallet consisting of multiple accounts, all operating
-/// on the same Zcash network. It can optionally include seed material for generating keys.
+/// on the same Zcash network. It can include any number of seeds from which keys are derived.
 /// This structure is the primary container for user wallet data but is not the top level
 /// of the interchange format hierarchy (that's `Zewif`).
 ///
@@ -28,7 +36,8 @@ use bc_envelope::prelude::*;
 /// During wallet migration, the following wallet data must be preserved:
 ///
 /// - **Network**: The Zcash network context (mainnet, testnet, regtest)
-/// - **Seed Material**: When available, the cryptographic material used for key generation
+/// - **Seeds**: Every seed the wallet holds, each identified by its fingerprint, so
+///   that accounts can name the seed they are derived from
 /// - **Accounts**: All accounts contained within the wallet, with their full structure
 /// - **Vendor-Specific Information**: Custom metadata stored in attachments
 ///
@@ -46,15 +55,38 @@ use bc_envelope::prelude::*;
 /// assert_eq!(wallet.network(), Network::Main);
 ///
 /// // If seed material were available, you could add it:
-/// // wallet.set_seed_material(seed_material);
+/// // wallet.add_seed(WalletSeed::new(seed_material)?);
 /// ```
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZewifWallet {
     index: usize,
     network: Network,
-    seed_material: Option<SeedMaterial>,
+    // Consensus parameters for a network whose parameters differ from the
+    // defaults for `network`, such as a regtest deployment.
+    network_parameters: Option<NetworkParameters>,
+    // The earliest height at which the wallet could have received funds, if
+    // known. Accounts may record later birthdays of their own.
+    birthday_height: Option<BlockHeight>,
+    // Where the wallet was exported from, recorded by the importer.
+    provenance: Option<WalletProvenance>,
+    seeds: Vec<WalletSeed>,
     accounts: Vec<Account>,
+
+    // The state of each pool's note commitment tree as of export, and the
+    // anchors against which the wallet's witnesses may have been computed.
+    sapling_tree_state: Option<SaplingTreeState>,
+    orchard_tree_state: Option<OrchardTreeState>,
+    sapling_anchors: Option<SaplingAnchorRegistry>,
+    orchard_anchors: Option<OrchardAnchorRegistry>,
+    // Records the importer could not attribute to any account.
+    orphaned_data: OrphanedData,
+    // Keys generated in advance but not yet handed out.
+    key_pool: KeyPool,
+    #[cfg_attr(feature = "json", serde(with = "crate::json::attachments"))]
     attachments: Attachments,
+    #[cfg_attr(feature = "json", serde(skip))]
+    account_index: AccountIndex,
 }
 
 impl Indexed for ZewifWallet {
@@ -72,8 +104,17 @@ impl std::fmt::Debug for ZewifWallet {
         f.debug_struct("ZewifWallet")
             .field("index", &self.index)
             .field("network", &self.network)
-            .field("seed_material", &NoQuotesDebugOption(&self.seed_material))
+            .field("network_parameters", &self.network_parameters)
+            .field("birthday_height", &self.birthday_height)
+            .field("provenance", &self.provenance)
+            .field("seeds", &self.seeds)
             .field("accounts", &self.accounts)
+            .field("sapling_tree_state", &self.sapling_tree_state)
+            .field("orchard_tree_state", &self.orchard_tree_state)
+            .field("sapling_anchors", &self.sapling_anchors)
+            .field("orchard_anchors", &self.orchard_anchors)
+            .field("orphaned_data", &self.orphaned_data)
+            .field("key_pool", &self.key_pool)
             .field("attachments", &self.attachments)
             .finish()
     }
@@ -86,9 +127,19 @@ impl ZewifWallet {
         Self {
             index: 0,
             network,
-            seed_material: None,
+            network_parameters: None,
+            birthday_height: None,
+            provenance: None,
+            seeds: Vec::new(),
             accounts: Vec::new(),
+            sapling_tree_state: None,
+            orchard_tree_state: None,
+            sapling_anchors: None,
+            orchard_anchors: None,
+            orphaned_data: OrphanedData::new(),
+            key_pool: KeyPool::new(),
             attachments: Attachments::new(),
+            account_index: AccountIndex::default(),
         }
     }
 
@@ -96,22 +147,481 @@ impl ZewifWallet {
         self.network
     }
 
-    pub fn seed_material(&self) -> Option<&SeedMaterial> {
-        self.seed_material.as_ref()
+    /// Returns the wallet's custom network parameters, if it records any.
+    pub fn network_parameters(&self) -> Option<&NetworkParameters> {
+        self.network_parameters.as_ref()
+    }
+
+    /// Returns the parameters of the wallet's network: its custom parameters
+    /// if it records any, or else the defaults for its network.
+    pub fn effective_network_parameters(&self) -> NetworkParameters {
+        self.network_parameters
+            .clone()
+            .unwrap_or_else(|| NetworkParameters::for_network(self.network))
+    }
+
+    pub fn set_network_parameters(&mut self, network_parameters: Option<NetworkParameters>) {
+        self.network_parameters = network_parameters;
+    }
+
+    /// Returns the earliest height at which the wallet could have received
+    /// funds, if known.
+    pub fn birthday_height(&self) -> Option<BlockHeight> {
+        self.birthday_height
+    }
+
+    pub fn set_birthday_height(&mut self, birthday_height: Option<BlockHeight>) {
+        self.birthday_height = birthday_height;
+    }
+
+    /// Returns where the wallet was exported from, if recorded.
+    pub fn provenance(&self) -> Option<&WalletProvenance> {
+        self.provenance.as_ref()
+    }
+
+    pub fn set_provenance(&mut self, provenance: Option<WalletProvenance>) {
+        self.provenance = provenance;
+    }
+
+    /// Returns the wallet with its provenance recorded, for importers to call
+    /// when constructing it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Network, WalletProvenance, ZewifWallet};
+    /// let wallet =
+    ///     ZewifWallet::new(Network::Main).with_provenance(WalletProvenance::new("zcashd"));
+    /// assert_eq!(wallet.provenance().unwrap().source_software(), "zcashd");
+    /// ```
+    pub fn with_provenance(mut self, provenance: WalletProvenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    pub fn seeds(&self) -> &Vec<WalletSeed> {
+        &self.seeds
+    }
+
+    pub fn add_seed(&mut self, mut seed: WalletSeed) {
+        seed.set_index(self.seeds.len());
+        self.seeds.push(seed);
+    }
+
+    /// Returns the seed identified by the given fingerprint, if the wallet
+    /// holds it; see [`WalletSeed::matches`].
+    pub fn seed_by_fingerprint(&self, fingerprint: &SeedFingerprint) -> Option<&WalletSeed> {
+        self.seeds.iter().find(|seed| seed.matches(fingerprint))
     }
 
-    pub fn set_seed_material(&mut self, seed_material: SeedMaterial) {
-        self.seed_material = Some(seed_material);
+    /// Returns whether `fingerprint`, named by `account`, refers to a seed of
+    /// this wallet: either one identified by that fingerprint or, if the
+    /// account holds no spending keys, a seed held by a hardware device that
+    /// did not report its fingerprint.
+    pub(crate) fn may_hold_seed(&self, account: &Account, fingerprint: &SeedFingerprint) -> bool {
+        let unreported_hardware_seed = || {
+            self.seeds.iter().any(|seed| {
+                seed.fingerprint().is_none()
+                    && matches!(seed.seed_material(), SeedMaterial::HardwareReference(_))
+            })
+        };
+        self.seed_by_fingerprint(fingerprint).is_some()
+            || (!account.holds_spending_keys() && unreported_hardware_seed())
+    }
+
+    /// Returns the seed bytes from which the account is derived: the seed
+    /// named by its [`AccountDerivation::FromSeed`] derivation. Returns `None`
+    /// if the account does not derive from a seed, or its seed is encrypted or
+    /// held by a hardware device.
+    ///
+    /// [`AccountDerivation::FromSeed`]: crate::AccountDerivation::FromSeed
+    #[cfg(feature = "zip32")]
+    pub(crate) fn seed_for_account(&self, account: &Account) -> Result<Option<Vec<u8>>> {
+        let Some(fingerprint) = account.derivation().seed_fingerprint() else {
+            return Ok(None);
+        };
+        match self.seed_by_fingerprint(fingerprint) {
+            Some(seed) => Ok(seed.seed_for(fingerprint)),
+            None if self.may_hold_seed(account, fingerprint) => Ok(None),
+            None => Err(Error::UnknownSeedFingerprint {
+                fingerprint: *fingerprint,
+            }),
+        }
     }
 
     pub fn accounts(&self) -> &Vec<Account> {
         &self.accounts
     }
 
+    /// Returns the accounts for modification in place. Accounts are added,
+    /// removed, and reordered with [`ZewifWallet::add_account`],
+    /// [`ZewifWallet::remove_account`], and [`ZewifWallet::reorder_accounts`],
+    /// which keep their indexes in step with their positions.
+    pub fn accounts_mut(&mut self) -> &mut [Account] {
+        self.account_index.invalidate();
+        &mut self.accounts
+    }
+
     pub fn add_account(&mut self, mut account: Account) {
         account.set_index(self.accounts.len());
+        self.account_index.invalidate();
         self.accounts.push(account);
     }
+
+    /// Returns the account with the given identifier, if the wallet holds it.
+    ///
+    /// Lookups use an index of the accounts by identifier, built on the
+    /// first lookup and rebuilt after the accounts change. If several
+    /// accounts share the identifier, the first is returned;
+    /// [`Zewif::validate`](crate::Zewif::validate) reports such duplicates.
+    pub fn get_by_id(&self, id: &ARID) -> Option<&Account> {
+        let position = *self.account_ids().positions.get(id)?;
+        self.accounts.get(position)
+    }
+
+    /// Returns the account with the given identifier, for modification; see
+    /// [`ZewifWallet::get_by_id`].
+    ///
+    /// An account's identifier cannot be changed, so the index stays valid;
+    /// replacing the whole account through the returned reference is not
+    /// supported, and [`ZewifWallet::accounts_mut`] should be used instead.
+    pub fn get_mut_by_id(&mut self, id: &ARID) -> Option<&mut Account> {
+        let position = *self.account_ids().positions.get(id)?;
+        self.accounts.get_mut(position)
+    }
+
+    /// Returns each identifier held by more than one of the wallet's
+    /// accounts, in order of first occurrence.
+    pub(crate) fn duplicate_account_ids(&self) -> &[ARID] {
+        &self.account_ids().duplicates
+    }
+
+    fn account_ids(&self) -> &AccountIds {
+        self.account_index
+            .get_or_build(|| AccountIds::new(&self.accounts))
+    }
+
+    /// Renames the account with the given identifier.
+    ///
+    /// # Errors
+    /// Returns [`Error::NotInZewif`] if the wallet holds no such account.
+    pub fn rename_account(&mut self, id: &ARID, name: impl Into<String>) -> Result<()> {
+        let account = self.get_mut_by_id(id).ok_or_else(|| Error::NotInZewif {
+            kind: "Account",
+            id: id.hex(),
+        })?;
+        account.set_name(name);
+        Ok(())
+    }
+
+    /// Puts the accounts in the order of `order`, which must list the
+    /// identifier of every account exactly once, and re-indexes them to match.
+    ///
+    /// Only the accounts' indices change: their identifiers, and everything
+    /// that refers to an account through its identifier or its contents,
+    /// are unaffected.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidAccountOrder`] if `order` is not a permutation
+    /// of the accounts' identifiers, leaving the order unchanged.
+    pub fn reorder_accounts(&mut self, order: &[ARID]) -> Result<()> {
+        let ids: HashSet<_> = self.accounts.iter().map(Account::id).collect();
+        let requested: HashSet<_> = order.iter().copied().collect();
+        if order.len() != self.accounts.len() || requested != ids {
+            return Err(Error::InvalidAccountOrder {
+                accounts: self.accounts.len(),
+                identifiers: order.len(),
+            });
+        }
+        self.account_index.invalidate();
+        self.accounts
+            .sort_by_key(|account| order.iter().position(|id| *id == account.id()));
+        for (index, account) in self.accounts.iter_mut().enumerate() {
+            account.set_index(index);
+        }
+        Ok(())
+    }
+
+    /// Removes the account at `index`, returning it if there was one. The
+    /// accounts after it are re-indexed.
+    ///
+    /// The transactions relevant to the account remain in the Zewif's store;
+    /// use [`Zewif::remove_account`](crate::Zewif::remove_account) to learn
+    /// which of them no remaining account refers to.
+    pub fn remove_account(&mut self, index: usize) -> Option<Account> {
+        if index >= self.accounts.len() {
+            return None;
+        }
+        self.account_index.invalidate();
+        let removed = self.accounts.remove(index);
+        for (index, account) in self.accounts.iter_mut().enumerate().skip(index) {
+            account.set_index(index);
+        }
+        Some(removed)
+    }
+
+    pub fn sapling_tree_state(&self) -> Option<&SaplingTreeState> {
+        self.sapling_tree_state.as_ref()
+    }
+
+    pub fn set_sapling_tree_state(&mut self, tree_state: Option<SaplingTreeState>) {
+        self.sapling_tree_state = tree_state;
+    }
+
+    pub fn orchard_tree_state(&self) -> Option<&OrchardTreeState> {
+        self.orchard_tree_state.as_ref()
+    }
+
+    pub fn set_orchard_tree_state(&mut self, tree_state: Option<OrchardTreeState>) {
+        self.orchard_tree_state = tree_state;
+    }
+
+    pub fn sapling_anchors(&self) -> Option<&SaplingAnchorRegistry> {
+        self.sapling_anchors.as_ref()
+    }
+
+    pub fn set_sapling_anchors(&mut self, anchors: Option<SaplingAnchorRegistry>) {
+        self.sapling_anchors = anchors;
+    }
+
+    pub fn orchard_anchors(&self) -> Option<&OrchardAnchorRegistry> {
+        self.orchard_anchors.as_ref()
+    }
+
+    pub fn set_orchard_anchors(&mut self, anchors: Option<OrchardAnchorRegistry>) {
+        self.orchard_anchors = anchors;
+    }
+
+    /// Returns the records the importer could not attribute to any account.
+    pub fn orphaned_data(&self) -> &OrphanedData {
+        &self.orphaned_data
+    }
+
+    pub fn set_orphaned_data(&mut self, orphaned_data: OrphanedData) {
+        self.orphaned_data = orphaned_data;
+    }
+
+    pub fn add_orphaned_record(&mut self, record: OrphanedRecord) {
+        self.orphaned_data.add(record);
+    }
+
+    pub fn key_pool(&self) -> &KeyPool {
+        &self.key_pool
+    }
+
+    pub fn key_pool_mut(&mut self) -> &mut KeyPool {
+        &mut self.key_pool
+    }
+
+    pub fn set_key_pool(&mut self, key_pool: KeyPool) {
+        self.key_pool = key_pool;
+    }
+
+    /// Returns the number of unused keys and the highest pool index on each
+    /// chain of the wallet's key pool. A restoring wallet should look ahead
+    /// at least this far when scanning for funds.
+    pub fn keypool_summary(&self) -> KeyPoolSummary {
+        self.key_pool.summary()
+    }
+
+    /// Returns the wallet's balance in each pool, aggregated over all of its
+    /// accounts.
+    ///
+    /// Returns `None` if any total overflows the valid range of amounts.
+    pub fn balance(&self) -> Option<Balance> {
+        self.accounts
+            .iter()
+            .try_fold(Balance::default(), |total, account| {
+                total + account.balance()?
+            })
+    }
+
+    /// Checks that each account's Orchard spending key matches its recorded
+    /// derivation from the wallet's seed material.
+    ///
+    /// Only accounts derived [`FromSeed`](crate::AccountDerivation::FromSeed)
+    /// are checked, against the seed named by that derivation. BIP-39 seeds
+    /// are computed with the mnemonic's passphrase, if any. Accounts without
+    /// recorded derivation metadata are checked against the path given by
+    /// their coin type and ZIP 32 account ID. Accounts lacking either a
+    /// spending key or a derivation, and accounts whose seed is encrypted or
+    /// held by a hardware device, are not checked.
+    ///
+    /// # Errors
+    /// Returns an error identifying the first account whose key does not match,
+    /// or whose seed fingerprint names a seed the wallet does not hold.
+    #[cfg(feature = "zip32")]
+    pub fn verify_orchard_derivations(&self) -> Result<()> {
+        for account in &self.accounts {
+            let result = self.seed_for_account(account).and_then(|seed| {
+                seed.map_or(Ok(()), |seed| account.verify_orchard_derivation(&seed))
+            });
+            result.map_err(|e| Error::Context {
+                message: format!("account {}", account.index()).into(),
+                source: Box::new(e),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Checks the wallet's contents for consistency: any custom network
+    /// parameters must be for the wallet's network, no element may be encoded
+    /// for another network (see [`ZewifWallet::validate_network`]), every
+    /// seed must be well formed, encrypted, or held by a hardware device,
+    /// every account's seed fingerprint and the seed fingerprint of every
+    /// derived key's [`KeyOrigin`] must name one of the wallet's seeds, and
+    /// every witness must pass [`ZewifWallet::validate_witnesses`].
+    ///
+    /// Accounts need not hold spending keys: the accounts of a wallet whose
+    /// seed is held by a hardware device hold only viewing keys and
+    /// addresses. If the device did not report the seed's fingerprint, the
+    /// fingerprints named by such accounts cannot be checked, but an account
+    /// holding spending keys must still name a seed the wallet holds.
+    ///
+    /// # Errors
+    /// Returns the first problem found.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(params) = &self.network_parameters
+            && params.network() != self.network
+        {
+            return Err(Error::InvalidNetwork {
+                network: Some(String::from(params.network())),
+                reason: format!(
+                    "the network parameters are for it, but the wallet is on {}",
+                    String::from(self.network)
+                ),
+            });
+        }
+        if let Some(mismatch) = self.validate_network().into_iter().next() {
+            return Err(Error::Context {
+                message: format!("account {}", mismatch.account()).into(),
+                source: Box::new(Error::NetworkMismatch {
+                    element: mismatch.element().to_string(),
+                    expected: self.network.into(),
+                    detected: mismatch.detected().into(),
+                }),
+            });
+        }
+        for seed in &self.seeds {
+            seed.seed_material()
+                .validate()
+                .map_err(|e| Error::Context {
+                    message: format!("seed {}", seed.index()).into(),
+                    source: Box::new(e),
+                })?;
+        }
+        for account in &self.accounts {
+            for fingerprint in account.referenced_seed_fingerprints() {
+                if !self.may_hold_seed(account, fingerprint) {
+                    return Err(Error::Context {
+                        message: format!("account {}", account.index()).into(),
+                        source: Box::new(Error::UnknownSeedFingerprint {
+                            fingerprint: *fingerprint,
+                        }),
+                    });
+                }
+            }
+        }
+        self.validate_witnesses()
+    }
+
+    /// Returns every element of the wallet's accounts that is encoded for a
+    /// different network than the wallet's.
+    ///
+    /// Addresses are checked by their encoding (see
+    /// [`ProtocolAddress::network_with`]), and derivation paths and Orchard
+    /// derivations by their coin type, in either case recognizing the custom
+    /// prefixes and coin type of the wallet's
+    /// [`ZewifWallet::effective_network_parameters`]. Testnet and regtest share their
+    /// Base58Check prefixes and coin type, so transparent and Sprout addresses
+    /// and coin types can only distinguish them from mainnet. Elements whose
+    /// network cannot be determined are not reported. Keys are held in their
+    /// raw encodings, which do not identify a network.
+    pub fn validate_network(&self) -> Vec<NetworkMismatch> {
+        let params = self.effective_network_parameters();
+        let mut mismatches = Vec::new();
+        for account in &self.accounts {
+            let mut check = |element: String, detected: Option<Network>, shares_base58: bool| {
+                if let Some(detected) = detected {
+                    let agrees = detected == self.network
+                        || (shares_base58 && detected.shares_base58_encoding(self.network));
+                    if !agrees {
+                        mismatches.push(NetworkMismatch::new(account.index(), element, detected));
+                    }
+                }
+            };
+            let path_network = |path: &DerivationPath| {
+                path.coin_type()
+                    .and_then(|coin_type| params.network_of_coin_type(coin_type))
+            };
+
+            for address in account.addresses() {
+                let protocol_address = address.address();
+                let encoded = protocol_address.as_string();
+                let shares_base58 =
+                    protocol_address.is_transparent() || protocol_address.is_sprout();
+                check(
+                    format!("address {}", encoded),
+                    protocol_address.network_with(&params),
+                    shares_base58,
+                );
+
+                let (hd_derivation_path, key_origin) = match protocol_address {
+                    ProtocolAddress::Transparent(address) => (None, address.key_origin()),
+                    ProtocolAddress::Sapling(address) => {
+                        (address.hd_derivation_path(), address.key_origin())
+                    }
+                    ProtocolAddress::Unified(address) => (address.hd_derivation_path(), None),
+                    ProtocolAddress::Sprout(_) => (None, None),
+                };
+                if let Some(path) =
+                    hd_derivation_path.and_then(|path| path.parse::<DerivationPath>().ok())
+                {
+                    check(
+                        format!("derivation path {} of address {}", path, encoded),
+                        path_network(&path),
+                        true,
+                    );
+                }
+                if let Some(KeyOrigin::Derived { path, .. }) = key_origin {
+                    check(
+                        format!("key origin {} of address {}", path, encoded),
+                        path_network(path),
+                        true,
+                    );
+                }
+            }
+
+            if let Some(derivation) = account.orchard_derivation() {
+                let detected = params.network_of_coin_type(derivation.coin_type());
+                check(
+                    format!("Orchard derivation {}", derivation.path()),
+                    detected,
+                    true,
+                );
+            }
+            if let Some(KeyOrigin::Derived { path, .. }) = account.orchard_key_origin() {
+                check(
+                    format!("Orchard key origin {}", path),
+                    path_network(path),
+                    true,
+                );
+            }
+        }
+        mismatches
+    }
+
+    /// Checks the witnesses of every account in the wallet.
+    ///
+    /// Each witness must be structurally valid for its pool's tree and, where
+    /// the wallet records an anchor registry for that pool, must refer to a
+    /// known anchor.
+    pub fn validate_witnesses(&self) -> Result<()> {
+        for account in &self.accounts {
+            account
+                .validate_witnesses(self.sapling_anchors.as_ref(), self.orchard_anchors.as_ref())?;
+        }
+        Ok(())
+    }
 }
 
 #[rustfmt::skip]
@@ -120,8 +630,23 @@ impl From<ZewifWallet> for Envelope {
         let mut e = Envelope::new(value.index)
             .add_type("ZewifWallet")
             .add_assertion("network", value.network)
-            .add_optional_assertion("seed_material", value.seed_material);
+            .add_optional_assertion("network_parameters", value.network_parameters)
+            .add_optional_assertion("birthday_height", value.birthday_height)
+            .add_optional_assertion("provenance", value.provenance)
+            .add_optional_assertion("sapling_tree_state", value.sapling_tree_state)
+            .add_optional_assertion("orchard_tree_state", value.orchard_tree_state)
+            .add_optional_assertion("sapling_anchors", value.sapling_anchors)
+            .add_optional_assertion("orchard_anchors", value.orchard_anchors)
+            .add_optional_assertion(
+                "orphaned_data",
+                (!value.orphaned_data.is_empty()).then_some(value.orphaned_data),
+            )
+            .add_optional_assertion(
+                "key_pool",
+                (!value.key_pool.is_empty()).then_some(value.key_pool),
+            );
 
+        e = value.seeds.iter().fold(e, |e, seed| e.add_assertion("seed", seed.clone()));
         e = value.accounts.iter().fold(e, |e, account| e.add_assertion("account", account.clone()));
 
         value.attachments.add_to_envelope(e)
@@ -133,23 +658,69 @@ impl TryFrom<Envelope> for ZewifWallet {
     type Error = bc_envelope::Error;
 
     fn try_from(envelope: Envelope) -> bc_envelope::Result<Self> {
-        envelope.check_type("ZewifWallet")?;
-        let index = envelope.extract_subject()?;
-        let network = envelope.extract_object_for_predicate("network")?;
-        let seed_material = envelope.try_optional_object_for_predicate("seed_material")?;
+        let segment = Self::envelope_segment(&envelope);
+        Ok(Self::decode(&EnvelopeDecoder::new(envelope, segment))?)
+    }
+}
 
-        let accounts = envelope_indexed_objects_for_predicate(&envelope, "account")
-            .map_err(|e| bc_envelope::Error::General(format!("accounts: {}", e)))?;
+impl ZewifWallet {
+    /// Returns the path segment identifying an encoded wallet in decoding
+    /// errors, such as `Wallet[0]`.
+    pub(crate) fn envelope_segment(envelope: &Envelope) -> String {
+        segment(
+            "Wallet",
+            envelope
+                .extract_subject::<usize>()
+                .map_or("?".to_string(), |index| index.to_string()),
+        )
+    }
 
-        let attachments = Attachments::try_from_envelope(&envelope)
-            .map_err(|e| bc_envelope::Error::General(format!("attachments: {}", e)))?;
+    pub(crate) fn decode(decoder: &EnvelopeDecoder) -> Result<Self> {
+        decoder.check_type("ZewifWallet")?;
+        let index = decoder.subject()?;
+        let network = decoder.extract("network")?;
+        let network_parameters = decoder.optional_object("network_parameters")?;
+        let birthday_height = decoder.extract_optional("birthday_height")?;
+        let provenance = decoder.optional_object("provenance")?;
+        let sapling_tree_state = decoder.optional_object("sapling_tree_state")?;
+        let orchard_tree_state = decoder.optional_object("orchard_tree_state")?;
+        let sapling_anchors = decoder.optional_object("sapling_anchors")?;
+        let orchard_anchors = decoder.optional_object("orchard_anchors")?;
+        let orphaned_data = decoder
+            .optional_object("orphaned_data")?
+            .unwrap_or_default();
+        let key_pool = decoder.optional_object("key_pool")?.unwrap_or_default();
+
+        let mut seeds: Vec<WalletSeed> = decoder.indexed_objects("seed")?;
+        // Version 1 wallets held at most one seed, without a fingerprint.
+        // `envelope_upgrade` migrates them within a Zewif, but a wallet
+        // envelope decoded on its own may still have the old layout.
+        if let Some(seed_material) = decoder.optional_object::<SeedMaterial>("seed_material")? {
+            let mut seed = WalletSeed::new(seed_material)
+                .map_err(|e| decoder.error(Some("seed_material"), e))?;
+            seed.set_index(seeds.len());
+            seeds.push(seed);
+        }
+        let accounts =
+            decoder.indexed_objects_with("account", Account::envelope_segment, Account::decode)?;
+        let attachments = decoder.attachments()?;
 
         Ok(Self {
             index,
             network,
-            seed_material,
+            network_parameters,
+            birthday_height,
+            provenance,
+            seeds,
             accounts,
+            sapling_tree_state,
+            orchard_tree_state,
+            sapling_anchors,
+            orchard_anchors,
+            orphaned_data,
+            key_pool,
             attachments,
+            account_index: AccountIndex::default(),
         })
     }
 }
@@ -158,7 +729,12 @@ impl TryFrom<Envelope> for ZewifWallet {
 mod tests {
     use bc_envelope::Attachments;
 
-    use crate::{Network, SeedMaterial, test_envelope_roundtrip};
+    use crate::{
+        BlockHeight, KeyPool, Network, NetworkParameters, OrphanedData, WalletProvenance,
+        orchard::{OrchardAnchorRegistry, OrchardTreeState},
+        sapling::{SaplingAnchorRegistry, SaplingTreeState},
+        test_envelope_roundtrip,
+    };
 
     use super::ZewifWallet;
 
@@ -166,15 +742,523 @@ mod tests {
         fn random() -> Self {
             use crate::SetIndexes;
 
+            let network = Network::random();
+            let network_parameters =
+                rand::random::<bool>().then(|| NetworkParameters::for_network(network));
             Self {
                 index: 0,
-                network: Network::random(),
-                seed_material: SeedMaterial::opt_random(),
+                network,
+                network_parameters,
+                birthday_height: BlockHeight::opt_random(),
+                provenance: WalletProvenance::opt_random(),
+                seeds: Vec::random().set_indexes(),
                 accounts: Vec::random().set_indexes(),
+                sapling_tree_state: SaplingTreeState::opt_random(),
+                orchard_tree_state: OrchardTreeState::opt_random(),
+                sapling_anchors: SaplingAnchorRegistry::opt_random(),
+                orchard_anchors: OrchardAnchorRegistry::opt_random(),
+                orphaned_data: OrphanedData::random(),
+                key_pool: KeyPool::random(),
                 attachments: Attachments::random(),
+                account_index: Default::default(),
             }
         }
     }
 
     test_envelope_roundtrip!(ZewifWallet);
+
+    #[test]
+    fn test_multiple_seeds() {
+        use bc_envelope::prelude::*;
+
+        use crate::{
+            Account, Bip39Mnemonic, Indexed, LegacySeed, SeedFingerprint, SeedMaterial, WalletSeed,
+        };
+
+        let mnemonic = SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            None,
+        ));
+        let legacy =
+            SeedMaterial::LegacySeed(LegacySeed::new((0u8..32).collect::<Vec<_>>().into(), None));
+        let mut imported = WalletSeed::new(legacy.clone()).unwrap();
+        imported.set_label(Some("Imported".to_string()));
+
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_seed(WalletSeed::new(mnemonic.clone()).unwrap());
+        wallet.add_seed(imported);
+        let mut account = Account::new();
+        account.set_seed_fingerprint(wallet.seeds()[1].fingerprint().copied());
+        wallet.add_account(account);
+        assert!(wallet.validate().is_ok());
+
+        let fingerprint = legacy.fingerprint().unwrap();
+        let seed = wallet.seed_by_fingerprint(&fingerprint).unwrap();
+        assert_eq!(seed.index(), 1);
+        assert_eq!(seed.label(), Some("Imported"));
+        assert!(
+            wallet
+                .seed_by_fingerprint(&SeedFingerprint::new([0u8; 32]))
+                .is_none()
+        );
+
+        let envelope = Envelope::from(wallet.clone());
+        assert_eq!(ZewifWallet::try_from(envelope).unwrap(), wallet);
+
+        // An account naming a seed the wallet does not hold is rejected.
+        let mut account = Account::new();
+        account.set_seed_fingerprint(Some(SeedFingerprint::new([0u8; 32])));
+        wallet.add_account(account);
+        assert!(wallet.validate().is_err());
+
+        // The single-seed form of version 1 is still accepted.
+        let envelope = Envelope::from(ZewifWallet::new(Network::Main))
+            .add_assertion("seed_material", mnemonic.clone());
+        let wallet = ZewifWallet::try_from(envelope).unwrap();
+        assert_eq!(wallet.seeds().len(), 1);
+        assert_eq!(wallet.seeds()[0].seed_material(), &mnemonic);
+        assert_eq!(
+            wallet.seeds()[0].fingerprint(),
+            Some(&mnemonic.fingerprint().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_key_origins_name_wallet_seeds() {
+        use crate::{
+            Account, Address, Bip39Mnemonic, DerivationPath, KeyOrigin, ProtocolAddress,
+            SecondsSinceEpoch, SeedFingerprint, SeedMaterial, WalletSeed, transparent,
+        };
+
+        let mnemonic = Bip39Mnemonic::new(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            None,
+        );
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_seed(WalletSeed::new(SeedMaterial::Bip39Mnemonic(mnemonic)).unwrap());
+        let fingerprint = *wallet.seeds()[0].fingerprint().unwrap();
+
+        let mut imported = transparent::Address::new("t1imported");
+        imported.set_key_origin(Some(KeyOrigin::Imported {
+            time: SecondsSinceEpoch::from(1_540_779_337u64),
+        }));
+        let mut derived = transparent::Address::new("t1derived");
+        derived.set_key_origin(Some(KeyOrigin::Derived {
+            path: "m/44'/133'/0'/0/0".parse::<DerivationPath>().unwrap(),
+            seed_fingerprint: Some(fingerprint),
+        }));
+        let mut account = Account::new();
+        account.add_address(Address::new(ProtocolAddress::Transparent(imported)));
+        account.add_address(Address::new(ProtocolAddress::Transparent(derived)));
+        assert_eq!(
+            account
+                .key_origins()
+                .filter(|origin| origin.needs_backup())
+                .count(),
+            1
+        );
+        wallet.add_account(account);
+        wallet.validate().unwrap();
+
+        // A key derived from a seed the wallet does not hold.
+        wallet.accounts_mut()[0].set_orchard_key_origin(Some(KeyOrigin::Derived {
+            path: DerivationPath::hardened(&[32, 133, 0]).unwrap(),
+            seed_fingerprint: Some(SeedFingerprint::new([3u8; 32])),
+        }));
+        let error = wallet.validate().unwrap_err();
+        assert!(format!("{:?}", error).contains("UnknownSeedFingerprint"));
+    }
+
+    #[test]
+    fn test_hardware_seed_accounts() {
+        use crate::{
+            Account, Blob, HardwareSeedReference, SeedFingerprint, SeedMaterial, WalletSeed,
+        };
+
+        let seed_fingerprint = SeedFingerprint::new([9u8; 32]);
+        let mut wallet = ZewifWallet::new(Network::Main);
+        let reference =
+            HardwareSeedReference::new("Ledger Nano S", Blob::new([0x0a, 0x1b, 0x2c, 0x3d]));
+        wallet
+            .add_seed(WalletSeed::new(SeedMaterial::HardwareReference(reference.clone())).unwrap());
+        assert_eq!(wallet.seeds()[0].fingerprint(), None);
+
+        // An account holding no spending keys, naming the device's seed.
+        let mut account = Account::new();
+        account.set_seed_fingerprint(Some(seed_fingerprint));
+        wallet.add_account(account);
+        wallet.validate().unwrap();
+        #[cfg(feature = "zip32")]
+        wallet.verify_orchard_derivations().unwrap();
+
+        // Once the device reports the seed's fingerprint, it must match.
+        let mut reference = reference;
+        reference.set_seed_fingerprint(Some(SeedFingerprint::new([8u8; 32])));
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_seed(WalletSeed::new(SeedMaterial::HardwareReference(reference)).unwrap());
+        let mut account = Account::new();
+        account.set_seed_fingerprint(Some(seed_fingerprint));
+        wallet.add_account(account);
+        assert!(wallet.validate().is_err());
+
+        // An unreported hardware seed does not vouch for an account holding
+        // spending keys, and a seed of unknown fingerprint that is not on a
+        // device vouches for no account.
+        let mut wallet = ZewifWallet::new(Network::Main);
+        let reference =
+            HardwareSeedReference::new("Ledger Nano S", Blob::new([0x0a, 0x1b, 0x2c, 0x3d]));
+        wallet.add_seed(WalletSeed::new(SeedMaterial::HardwareReference(reference)).unwrap());
+        let mut account = Account::new();
+        account.set_seed_fingerprint(Some(seed_fingerprint));
+        account.set_orchard_spending_key(Some(crate::orchard::OrchardSpendingKey::new([1u8; 32])));
+        wallet.add_account(account);
+        assert!(wallet.validate().is_err());
+
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_seed(WalletSeed::new(SeedMaterial::Redacted).unwrap());
+        let mut account = Account::new();
+        account.set_seed_fingerprint(Some(seed_fingerprint));
+        wallet.add_account(account);
+        assert_eq!(wallet.seeds()[0].fingerprint(), None);
+        assert!(wallet.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_witnesses_against_orchard_anchors() {
+        use crate::{
+            Account, Amount, AnchorRegistry, Blob, BlockHeight, IncrementalWitness, TxId,
+            orchard::{self, MerkleHashOrchard, OrchardReceivedNote, OrchardWitness},
+        };
+
+        let anchor = MerkleHashOrchard::new([7u8; 32]);
+        let witness = OrchardWitness::new(IncrementalWitness::from_parts(
+            MerkleHashOrchard::new([1u8; 32]),
+            0,
+            vec![MerkleHashOrchard::new([2u8; 32]); 32],
+            anchor,
+            1,
+            vec![],
+        ));
+        let mut note = OrchardReceivedNote::new(
+            TxId::from_bytes([0u8; 32]),
+            0,
+            orchard::Note::from_parts(
+                Blob::default(),
+                Amount::from_u64(1000).unwrap(),
+                Blob::default(),
+                Blob::default(),
+            )
+            .unwrap(),
+        );
+        note.set_witness(Some(witness));
+        let mut account = Account::new();
+        account.add_orchard_received_note(note);
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+
+        // Without a registry only the structure of the witness is checked.
+        assert!(wallet.validate_witnesses().is_ok());
+
+        let mut anchors = OrchardAnchorRegistry::new(AnchorRegistry::new());
+        anchors.insert(MerkleHashOrchard::new([8u8; 32]), BlockHeight::from(100));
+        wallet.set_orchard_anchors(Some(anchors.clone()));
+        assert!(wallet.validate_witnesses().is_err());
+
+        anchors.insert(anchor, BlockHeight::from(101));
+        wallet.set_orchard_anchors(Some(anchors));
+        assert!(wallet.validate_witnesses().is_ok());
+    }
+
+    #[test]
+    fn test_rename_and_reorder_accounts() {
+        use bc_components::ARID;
+        use bc_envelope::prelude::*;
+
+        use crate::{
+            Account, Address, Amount, AnchorRegistry, Blob, BlockHeight, Error, IncrementalWitness,
+            Indexed, ProtocolAddress, Transaction, TxId, ValidationOptions, Zewif,
+            orchard::{self, MerkleHashOrchard, OrchardReceivedNote, OrchardWitness},
+            transparent,
+        };
+
+        let anchor = MerkleHashOrchard::new([7u8; 32]);
+        let account = |name: &str, byte: u8| {
+            let txid = TxId::from_bytes([byte; 32]);
+            let mut note = OrchardReceivedNote::new(
+                txid,
+                0,
+                orchard::Note::from_parts(
+                    Blob::default(),
+                    Amount::from_u64(1000).unwrap(),
+                    Blob::default(),
+                    Blob::default(),
+                )
+                .unwrap(),
+            );
+            note.set_witness(Some(OrchardWitness::new(IncrementalWitness::from_parts(
+                MerkleHashOrchard::new([1u8; 32]),
+                0,
+                vec![MerkleHashOrchard::new([2u8; 32]); 32],
+                anchor,
+                1,
+                vec![],
+            ))));
+            let mut account = Account::new();
+            account.set_name(name);
+            account.add_orchard_received_note(note);
+            account.add_relevant_transaction(txid);
+            account.add_address(Address::new(ProtocolAddress::Transparent(
+                transparent::Address::new(format!("t1{}", name)),
+            )));
+            account
+        };
+        let mut wallet = ZewifWallet::new(Network::Main);
+        for (name, byte) in [("a", 1), ("b", 2), ("c", 3)] {
+            wallet.add_account(account(name, byte));
+        }
+        let mut anchors = OrchardAnchorRegistry::new(AnchorRegistry::new());
+        anchors.insert(anchor, BlockHeight::from(100));
+        wallet.set_orchard_anchors(Some(anchors));
+        let ids: Vec<ARID> = wallet.accounts().iter().map(Account::id).collect();
+
+        wallet.rename_account(&ids[1], "Savings").unwrap();
+        assert!(matches!(
+            wallet.rename_account(&ARID::new(), "Lost"),
+            Err(Error::NotInZewif { .. })
+        ));
+        assert!(matches!(
+            wallet.reorder_accounts(&[ids[2], ids[0]]),
+            Err(Error::InvalidAccountOrder { .. })
+        ));
+        assert!(matches!(
+            wallet.reorder_accounts(&[ids[2], ids[0], ids[0]]),
+            Err(Error::InvalidAccountOrder { .. })
+        ));
+        wallet.reorder_accounts(&[ids[2], ids[0], ids[1]]).unwrap();
+
+        let order = |wallet: &ZewifWallet| {
+            wallet
+                .accounts()
+                .iter()
+                .map(|account| (account.index(), account.name().to_string()))
+                .collect::<Vec<_>>()
+        };
+        let expected =
+            [(0, "c"), (1, "a"), (2, "Savings")].map(|(index, name)| (index, name.to_string()));
+        assert_eq!(order(&wallet), expected);
+        assert_eq!(
+            wallet
+                .accounts()
+                .iter()
+                .map(Account::id)
+                .collect::<Vec<_>>(),
+            [ids[2], ids[0], ids[1]]
+        );
+
+        let mut zewif = Zewif::new(BlockHeight::from(2_000_000));
+        zewif.add_wallet(wallet);
+        for byte in 1..=3 {
+            zewif
+                .add_transaction(
+                    TxId::from_bytes([byte; 32]),
+                    Transaction::new(TxId::from_bytes([byte; 32])),
+                )
+                .unwrap();
+        }
+        let zewif = Zewif::try_from(Envelope::from(zewif)).unwrap();
+        assert_eq!(order(&zewif.wallets()[0]), expected);
+
+        for (id, byte) in ids.iter().zip(1..) {
+            let (_, account) = zewif.find_account_by_id(id).unwrap();
+            let txids: Vec<_> = zewif
+                .account_transactions(account)
+                .map(Transaction::txid)
+                .collect();
+            assert_eq!(txids, [TxId::from_bytes([byte; 32])]);
+            let (_, holder, _) = zewif
+                .find_address(&account.addresses()[0].as_string())
+                .unwrap();
+            assert_eq!(holder.id(), *id);
+        }
+        assert!(
+            zewif
+                .validate(ValidationOptions::new())
+                .findings()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_balance_across_pools() {
+        use crate::{
+            Account, Amount, Blob, TxId,
+            orchard::{self, OrchardReceivedNote},
+            sapling::{self, SaplingReceivedNote},
+            sprout::{self, SproutReceivedNote},
+        };
+
+        let zats = |value| Amount::from_u64(value).unwrap();
+        let txid = TxId::from_bytes([1u8; 32]);
+        let spent = Some(TxId::from_bytes([2u8; 32]));
+
+        let mut account = Account::new();
+        for (value, spent_in) in [(100, None), (200, spent)] {
+            let mut note = SproutReceivedNote::new(
+                txid,
+                0,
+                0,
+                sprout::Note::from_parts(
+                    zats(value),
+                    Blob::default(),
+                    Blob::default(),
+                    Blob::default(),
+                )
+                .unwrap(),
+            );
+            note.set_spent_in(spent_in);
+            account.add_sprout_received_note(note);
+        }
+        for (value, spent_in) in [(1_000, None), (2_000, None), (4_000, spent)] {
+            let mut note = SaplingReceivedNote::new(
+                txid,
+                0,
+                sapling::Note::from_parts(Blob::default(), zats(value), Blob::default()).unwrap(),
+            );
+            note.set_spent_in(spent_in);
+            account.add_sapling_received_note(note);
+        }
+        for (value, spent_in) in [(10_000, spent), (20_000, None)] {
+            let mut note = OrchardReceivedNote::new(
+                txid,
+                0,
+                orchard::Note::from_parts(
+                    Blob::default(),
+                    zats(value),
+                    Blob::default(),
+                    Blob::default(),
+                )
+                .unwrap(),
+            );
+            note.set_spent_in(spent_in);
+            account.add_orchard_received_note(note);
+        }
+
+        let balance = account.balance().unwrap();
+        assert_eq!(balance.transparent(), Amount::zero());
+        assert_eq!(balance.sprout(), zats(100));
+        assert_eq!(balance.sapling(), zats(3_000));
+        assert_eq!(balance.orchard(), zats(20_000));
+        assert_eq!(balance.total(), Some(zats(23_100)));
+
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account.clone());
+        wallet.add_account(Account::new());
+        wallet.add_account(account);
+        let balance = wallet.balance().unwrap();
+        assert_eq!(balance.sprout(), zats(200));
+        assert_eq!(balance.sapling(), zats(6_000));
+        assert_eq!(balance.orchard(), zats(40_000));
+    }
+
+    #[test]
+    fn test_network_parameters() {
+        use crate::{BlockHeight, NetworkUpgrade};
+
+        let mut wallet = ZewifWallet::new(Network::Regtest);
+        assert_eq!(
+            wallet.effective_network_parameters(),
+            NetworkParameters::for_network(Network::Regtest)
+        );
+
+        let h = |height: u32| Some(BlockHeight::from(height));
+        let params = NetworkParameters::custom_regtest([h(1), h(1), h(1), h(1), h(1), h(100)]);
+        wallet.set_network_parameters(Some(params.clone()));
+        assert!(wallet.validate().is_ok());
+        assert_eq!(
+            wallet
+                .effective_network_parameters()
+                .activation_height(NetworkUpgrade::Nu5),
+            h(100)
+        );
+
+        wallet.set_network_parameters(Some(NetworkParameters::for_network(Network::Main)));
+        assert!(wallet.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_network() {
+        use bech32::{Bech32, Hrp};
+
+        use crate::{
+            Account, Address, ProtocolAddress, base58check, orchard::OrchardDerivation, sapling,
+            transparent,
+        };
+
+        let testnet_transparent = base58check::encode(&Network::Test.p2pkh_prefix(), &[7u8; 20]);
+        let testnet_sapling =
+            bech32::encode::<Bech32>(Hrp::parse("ztestsapling").unwrap(), &[7u8; 43]).unwrap();
+        let mut account = Account::new();
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new(testnet_transparent.clone()),
+        )));
+        let mut address = sapling::Address::new(testnet_sapling.clone());
+        address.set_hd_derivation_path("m/32'/1'/0'".to_string());
+        account.add_address(Address::new(ProtocolAddress::Sapling(Box::new(address))));
+        account.set_orchard_derivation(Some(OrchardDerivation::new(133, 0, None)));
+
+        // A mainnet wallet rejects the testnet address and derivation path.
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account.clone());
+        let elements: Vec<_> = wallet
+            .validate_network()
+            .iter()
+            .map(|m| m.element().to_string())
+            .collect();
+        assert_eq!(
+            elements,
+            vec![
+                format!("address {}", testnet_transparent),
+                format!("address {}", testnet_sapling),
+                format!("derivation path m/32'/1'/0' of address {}", testnet_sapling),
+            ]
+        );
+        assert!(
+            wallet
+                .validate_network()
+                .iter()
+                .all(|m| m.account() == 0 && m.detected() == Network::Test)
+        );
+        assert!(wallet.validate().is_err());
+
+        // A regtest wallet accepts the transparent address and the coin type,
+        // which it shares with testnet, but not the Sapling address.
+        let mut wallet = ZewifWallet::new(Network::Regtest);
+        wallet.add_account(account.clone());
+        let elements: Vec<_> = wallet
+            .validate_network()
+            .iter()
+            .map(|m| m.element().to_string())
+            .collect();
+        assert_eq!(
+            elements,
+            vec![
+                format!("address {}", testnet_sapling),
+                "Orchard derivation m/32'/133'/0'".to_string()
+            ]
+        );
+
+        // Unless its custom network parameters use that Sapling prefix and
+        // coin type.
+        let mut params = NetworkParameters::for_network(Network::Regtest);
+        params.set_sapling_hrp("ztestsapling");
+        params.set_coin_type(133);
+        wallet.set_network_parameters(Some(params));
+        assert!(wallet.validate_network().is_empty());
+
+        let mut wallet = ZewifWallet::new(Network::Test);
+        account.set_orchard_derivation(Some(OrchardDerivation::new(1, 0, None)));
+        wallet.add_account(account);
+        assert!(wallet.validate_network().is_empty());
+        assert!(wallet.validate().is_ok());
+    }
 }